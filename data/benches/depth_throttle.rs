@@ -0,0 +1,122 @@
+//! Work a pane does for one minute of a busy depth stream, with and without the depth throttle.
+//!
+//! Each update carries a 400 level book per side and 15 trades. Let through updates are
+//! inserted into a heatmap's `HistoricalDepth` and their trades into a footprint series,
+//! the same as panes do with what `DepthCoalescer` hands them.
+//!
+//! Run with `cargo bench -p flowsurface-data --features benches --bench depth_throttle`.
+
+use criterion::{BatchSize, BenchmarkId, Criterion, criterion_group, criterion_main};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use exchange::depth::Depth;
+use exchange::util::{Price, PriceStep};
+use exchange::{Timeframe, Trade};
+use flowsurface_data::aggr::throttle::DepthCoalescer;
+use flowsurface_data::aggr::time::TimeSeries;
+use flowsurface_data::chart::Basis;
+use flowsurface_data::chart::heatmap::HistoricalDepth;
+use flowsurface_data::chart::kline::KlineDataPoint;
+
+/// Streams push every 100ms
+const PUSH_MS: u64 = 100;
+const UPDATES: usize = 600;
+const LEVELS: usize = 400;
+const TRADES_PER_UPDATE: usize = 15;
+const INTERVALS_MS: [u64; 3] = [0, 300, 1000];
+
+fn mid_at(i: usize) -> f32 {
+    100_000.0 + (i as f32 * 0.05).sin() * 40.0
+}
+
+fn depth(i: usize) -> Arc<Depth> {
+    let mid = mid_at(i).round();
+    let qty = |level: usize| 0.5 + ((i + level * 7) % 13) as f32 * 0.3;
+
+    Arc::new(Depth {
+        bids: (1..=LEVELS)
+            .map(|level| (Price::from_f32(mid - level as f32), qty(level)))
+            .collect(),
+        asks: (1..=LEVELS)
+            .map(|level| (Price::from_f32(mid + level as f32), qty(level)))
+            .collect(),
+    })
+}
+
+fn trades(i: usize) -> Vec<Trade> {
+    (0..TRADES_PER_UPDATE)
+        .map(|n| Trade {
+            time: i as u64 * PUSH_MS + n as u64,
+            is_sell: n % 3 == 0,
+            price: Price::from_f32(mid_at(i).round() + (n % 5) as f32 - 2.0),
+            qty: 0.1 + (n % 7) as f32 * 0.05,
+        })
+        .collect()
+}
+
+struct Pane {
+    heatmap: HistoricalDepth,
+    footprint: TimeSeries<KlineDataPoint>,
+}
+
+impl Pane {
+    fn new() -> Self {
+        let step = PriceStep::from_f32_lossy(1.0);
+        Self {
+            heatmap: HistoricalDepth::new(0.0, step, Basis::Time(Timeframe::MS100)),
+            footprint: TimeSeries::<KlineDataPoint>::new(Timeframe::M1, step, &[]),
+        }
+    }
+
+    fn handle(&mut self, time: u64, depth: &Depth, trades: &[Trade]) {
+        self.heatmap.insert_latest_depth(depth, time);
+        self.footprint.insert_trades_or_create_bucket(trades);
+    }
+}
+
+fn one_minute(c: &mut Criterion) {
+    let mut group = c.benchmark_group("depth_throttle/1min");
+    let updates = (0..UPDATES)
+        .map(|i| (i as u64 * PUSH_MS, depth(i), trades(i)))
+        .collect::<Vec<_>>();
+
+    for interval_ms in INTERVALS_MS {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{interval_ms}ms")),
+            &interval_ms,
+            |b, &interval_ms| {
+                let interval = Duration::from_millis(interval_ms);
+
+                b.iter_batched(
+                    Pane::new,
+                    |mut pane| {
+                        if interval.is_zero() {
+                            for (time, depth, trades) in &updates {
+                                pane.handle(*time, depth, trades);
+                            }
+                            return pane;
+                        }
+
+                        let start = Instant::now();
+                        let mut coalescer = DepthCoalescer::default();
+                        for (time, depth, trades) in &updates {
+                            let now = start + Duration::from_millis(*time);
+                            if let Some(update) =
+                                coalescer.push(&0, *time, Arc::clone(depth), trades, interval, now)
+                            {
+                                pane.handle(update.depth_update_t, &update.depth, &update.trades);
+                            }
+                        }
+                        pane
+                    },
+                    BatchSize::LargeInput,
+                );
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, one_minute);
+criterion_main!(benches);
@@ -0,0 +1,174 @@
+//! Throughput of the time based kline aggregation.
+//!
+//! Run with `cargo bench -p flowsurface-data --features benches`.
+
+use criterion::{BatchSize, BenchmarkId, Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+
+use exchange::util::{Price, PriceStep};
+use exchange::{Kline, Timeframe, Trade};
+use flowsurface_data::aggr::time::TimeSeries;
+use flowsurface_data::chart::kline::{ClusterKind, FootprintCellText, KlineDataPoint};
+
+const TICK_SIZES: [f32; 3] = [0.1, 1.0, 10.0];
+const TIMEFRAME: Timeframe = Timeframe::M1;
+
+/// Deterministic walk around 100k so every run aggregates the same prices
+fn price_at(i: usize) -> f32 {
+    let i = i as f32;
+    100_000.0 + (i * 0.013).sin() * 400.0 + (i * 0.17).cos() * 25.0
+}
+
+fn trades(count: usize, bars: usize) -> Vec<Trade> {
+    let bar_ms = TIMEFRAME.to_milliseconds();
+    let spacing = (bars as u64 * bar_ms) / count as u64;
+
+    (0..count)
+        .map(|i| Trade {
+            time: i as u64 * spacing,
+            is_sell: i % 3 == 0,
+            price: Price::from_f32(price_at(i)),
+            qty: 0.1 + (i % 7) as f32 * 0.05,
+        })
+        .collect()
+}
+
+fn klines(count: usize) -> Vec<Kline> {
+    let bar_ms = TIMEFRAME.to_milliseconds();
+
+    (0..count)
+        .map(|i| {
+            let (open, close) = (price_at(i * 20), price_at(i * 20 + 19));
+            Kline {
+                time: i as u64 * bar_ms,
+                open: Price::from_f32(open),
+                high: Price::from_f32(open.max(close) + 15.0),
+                low: Price::from_f32(open.min(close) - 15.0),
+                close: Price::from_f32(close),
+                volume: (10.0, 8.0),
+            }
+        })
+        .collect()
+}
+
+fn empty_series(tick_size: f32) -> TimeSeries<KlineDataPoint> {
+    TimeSeries::<KlineDataPoint>::new(TIMEFRAME, PriceStep::from_f32_lossy(tick_size), &[])
+}
+
+fn series_with_trades(tick_size: f32, bars: usize) -> TimeSeries<KlineDataPoint> {
+    let mut series = TimeSeries::<KlineDataPoint>::new(
+        TIMEFRAME,
+        PriceStep::from_f32_lossy(tick_size),
+        &klines(bars),
+    );
+    series.insert_trades_or_create_bucket(&trades(bars * 20, bars));
+    series
+}
+
+fn insert_trades(c: &mut Criterion) {
+    let mut group = c.benchmark_group("insert_trades_or_create_bucket/10k");
+    let buffer = trades(10_000, 100);
+
+    for tick_size in TICK_SIZES {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(tick_size),
+            &tick_size,
+            |b, &tick_size| {
+                b.iter_batched(
+                    || empty_series(tick_size),
+                    |mut series| {
+                        series.insert_trades_or_create_bucket(black_box(&buffer));
+                        series
+                    },
+                    BatchSize::SmallInput,
+                );
+            },
+        );
+    }
+    group.finish();
+}
+
+fn insert_klines(c: &mut Criterion) {
+    let mut group = c.benchmark_group("insert_klines/1000");
+    let buffer = klines(1000);
+
+    for tick_size in TICK_SIZES {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(tick_size),
+            &tick_size,
+            |b, &tick_size| {
+                b.iter_batched(
+                    || empty_series(tick_size),
+                    |mut series| {
+                        series.insert_klines(black_box(&buffer));
+                        series
+                    },
+                    BatchSize::SmallInput,
+                );
+            },
+        );
+    }
+    group.finish();
+}
+
+fn update_poc_status(c: &mut Criterion) {
+    let mut group = c.benchmark_group("update_poc_status/500");
+
+    for tick_size in TICK_SIZES {
+        let series = series_with_trades(tick_size, 500);
+        let (earliest, latest) = series.timerange();
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(tick_size),
+            &tick_size,
+            |b, _| {
+                b.iter_batched(
+                    || series.with_trades(&[]),
+                    |mut series| {
+                        series.update_poc_status(black_box(earliest), black_box(latest));
+                        series
+                    },
+                    BatchSize::LargeInput,
+                );
+            },
+        );
+    }
+    group.finish();
+}
+
+fn max_qty_ts_range(c: &mut Criterion) {
+    let mut group = c.benchmark_group("max_qty_ts_range/1000");
+
+    for tick_size in TICK_SIZES {
+        let series = series_with_trades(tick_size, 1000);
+        let (earliest, latest) = series.timerange();
+        let (highest, lowest) = series.price_scale(1000);
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(tick_size),
+            &tick_size,
+            |b, _| {
+                b.iter(|| {
+                    series.max_qty_ts_range(
+                        black_box(ClusterKind::BidAsk),
+                        FootprintCellText::Volume,
+                        earliest,
+                        latest,
+                        highest,
+                        lowest,
+                    )
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    insert_trades,
+    insert_klines,
+    update_poc_status,
+    max_qty_ts_range
+);
+criterion_main!(benches);
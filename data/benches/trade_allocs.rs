@@ -0,0 +1,118 @@
+//! Heap allocations of `insert_trades_or_create_bucket` for a 10k trade stream,
+//! against the previous implementation that collected updated buckets in a fresh `Vec`.
+//!
+//! Run with `cargo bench -p flowsurface-data --features benches --bench trade_allocs`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use exchange::util::{Price, PriceStep};
+use exchange::{Kline, Timeframe, Trade};
+use flowsurface_data::aggr::time::TimeSeries;
+use flowsurface_data::chart::kline::{KlineDataPoint, KlineTrades};
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+const TIMEFRAME: Timeframe = Timeframe::M1;
+const TICK_SIZE: f32 = 1.0;
+const TRADES: usize = 10_000;
+const BARS: usize = 100;
+
+fn trades() -> Vec<Trade> {
+    let spacing = (BARS as u64 * TIMEFRAME.to_milliseconds()) / TRADES as u64;
+
+    (0..TRADES)
+        .map(|i| {
+            let x = i as f32;
+            Trade {
+                time: i as u64 * spacing,
+                is_sell: i % 3 == 0,
+                price: Price::from_f32(100_000.0 + (x * 0.013).sin() * 400.0),
+                qty: 0.1 + (i % 7) as f32 * 0.05,
+            }
+        })
+        .collect()
+}
+
+/// The aggregation as it was, kept here to compare against
+fn insert_with_vec(series: &mut TimeSeries<KlineDataPoint>, buffer: &[Trade]) {
+    let aggr_time = series.interval.to_milliseconds();
+    let mut updated_times = Vec::new();
+
+    for trade in buffer {
+        let rounded_time = (trade.time / aggr_time) * aggr_time;
+        if !updated_times.contains(&rounded_time) {
+            updated_times.push(rounded_time);
+        }
+
+        let tick_size = series.tick_size;
+        series
+            .datapoints
+            .entry(rounded_time)
+            .or_insert_with(|| KlineDataPoint {
+                kline: Kline {
+                    time: rounded_time,
+                    open: trade.price,
+                    high: trade.price,
+                    low: trade.price,
+                    close: trade.price,
+                    volume: (0.0, 0.0),
+                },
+                footprint: KlineTrades::new(),
+            })
+            .add_trade(trade, tick_size);
+    }
+
+    for time in updated_times {
+        if let Some(dp) = series.datapoints.get_mut(&time) {
+            dp.calculate_poc();
+        }
+    }
+}
+
+/// Allocations made while feeding the trades in buffers of `chunk`
+fn count(chunk: usize, insert: fn(&mut TimeSeries<KlineDataPoint>, &[Trade])) -> usize {
+    let buffer = trades();
+    let mut series =
+        TimeSeries::<KlineDataPoint>::new(TIMEFRAME, PriceStep::from_f32_lossy(TICK_SIZE), &[]);
+
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    for trades in buffer.chunks(chunk) {
+        insert(&mut series, trades);
+    }
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+fn main() {
+    println!("allocations for {TRADES} trades over {BARS} bars");
+    println!("{:>14} {:>10} {:>10}", "buffer size", "before", "after");
+
+    for chunk in [TRADES, 100, 10] {
+        let before = count(chunk, insert_with_vec);
+        let after = count(chunk, |series, trades| {
+            series.insert_trades_or_create_bucket(trades);
+        });
+        println!("{chunk:>14} {before:>10} {after:>10}");
+    }
+}
@@ -1,8 +1,25 @@
+pub mod multi_res;
+pub mod range;
+pub mod throttle;
 pub mod ticks;
 pub mod time;
 
+use exchange::util::Price;
 use serde::{Deserialize, Serialize};
 
+/// Widens `(low, high)` by `padding_pct` of its span on each side, and by at least `min_padding`
+pub fn pad_price_range(
+    low: Price,
+    high: Price,
+    padding_pct: f32,
+    min_padding: Price,
+) -> (Price, Price) {
+    let span = (high - low).to_f32();
+    let padding = Price::from_f32(span * padding_pct.max(0.0) / 100.0).max(min_padding);
+
+    (high + padding, low - padding)
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TickCount(pub u16);
 
@@ -0,0 +1,326 @@
+use std::collections::BTreeMap;
+
+use crate::aggr::time::{DataPoint, TimeSeries};
+use crate::chart::kline::KlineDataPoint;
+
+use exchange::{Kline, Timeframe};
+
+/// Coarser resolutions kept next to the base series, those that evenly divide into it
+pub const RESOLUTIONS: [Timeframe; 4] =
+    [Timeframe::M1, Timeframe::M5, Timeframe::M15, Timeframe::H1];
+
+/// Bars the renderer is allowed to draw before switching to a coarser resolution
+pub const MAX_VISIBLE_BARS: usize = 800;
+
+/// A datapoint that can be reduced to a [`DownsampledBar`]
+pub trait Downsample: DataPoint {
+    /// Taker buy and sell quantity the datapoint's delta comes from
+    fn buy_sell_qty(&self) -> (f32, f32);
+}
+
+impl Downsample for KlineDataPoint {
+    fn buy_sell_qty(&self) -> (f32, f32) {
+        if !self.footprint.trades.is_empty() {
+            return self
+                .footprint
+                .trades
+                .values()
+                .fold((0.0, 0.0), |(buy, sell), group| {
+                    (buy + group.buy_qty, sell + group.sell_qty)
+                });
+        }
+
+        // volume the exchange didn't split counts toward neither side, like `delta_qty`
+        match self.kline.volume {
+            (buy, _) if buy < 0.0 => (0.0, 0.0),
+            volume => volume,
+        }
+    }
+}
+
+/// A bar of a coarser resolution, only what the zoomed out candles and their delta need
+#[derive(Debug, Clone, Copy)]
+pub struct DownsampledBar {
+    pub kline: Kline,
+    pub buy_qty: f32,
+    pub sell_qty: f32,
+}
+
+impl DownsampledBar {
+    fn new<D: Downsample>(datapoint: &D, kline: &Kline, time: u64) -> Self {
+        let (buy_qty, sell_qty) = datapoint.buy_sell_qty();
+        Self {
+            kline: Kline { time, ..*kline },
+            buy_qty,
+            sell_qty,
+        }
+    }
+
+    fn merge(&mut self, later: &Self) {
+        let kline = &mut self.kline;
+        kline.high = kline.high.max(later.kline.high);
+        kline.low = kline.low.min(later.kline.low);
+        kline.close = later.kline.close;
+        kline.volume.0 += later.kline.volume.0;
+        kline.volume.1 += later.kline.volume.1;
+
+        self.buy_qty += later.buy_qty;
+        self.sell_qty += later.sell_qty;
+    }
+
+    pub fn delta_qty(&self) -> f32 {
+        self.buy_qty - self.sell_qty
+    }
+
+    /// Whether any of its base bars had a buy/sell split to color by
+    pub fn has_delta(&self) -> bool {
+        self.buy_qty + self.sell_qty > 0.0
+    }
+}
+
+struct Level {
+    timeframe: Timeframe,
+    bars: BTreeMap<u64, DownsampledBar>,
+    /// The open bucket without its newest base bar, which may still be forming
+    settled: Option<DownsampledBar>,
+    /// Time of the newest base bar folded into the open bucket
+    newest: Option<u64>,
+}
+
+impl Level {
+    /// Folds base bars in time order into their bucket, the newest one again when it changed
+    fn fold(&mut self, time: u64, datapoint: &impl Downsample) {
+        let Some(kline) = datapoint.kline() else {
+            return;
+        };
+
+        let interval = self.timeframe.to_milliseconds();
+        let bucket_time = (time / interval) * interval;
+
+        if self.newest != Some(time) {
+            // the previous newest bar is final now, the open bucket is settled up to here
+            self.settled = self
+                .newest
+                .filter(|newest| (newest / interval) * interval == bucket_time)
+                .and_then(|_| self.bars.get(&bucket_time).copied());
+            self.newest = Some(time);
+        }
+
+        let bar = DownsampledBar::new(datapoint, kline, bucket_time);
+        let open = match self.settled {
+            Some(mut settled) => {
+                settled.merge(&bar);
+                settled
+            }
+            None => bar,
+        };
+
+        self.bars.insert(bucket_time, open);
+    }
+}
+
+/// Bars of a base series downsampled to each coarser timeframe of [`RESOLUTIONS`]
+///
+/// The base series stays with its owner, after each insert the owner passes it back
+/// with the earliest time that changed. Changes to the newest base bar or after it are
+/// folded into the open bucket, older ones rebuild the buckets from the one holding them.
+pub struct MultiResTimeSeries {
+    base: Timeframe,
+    levels: Vec<Level>,
+}
+
+impl MultiResTimeSeries {
+    pub fn new<D: Downsample>(base: &TimeSeries<D>) -> Self {
+        let base_ms = base.interval.to_milliseconds();
+
+        let levels = RESOLUTIONS
+            .iter()
+            .filter(|tf| {
+                let ms = tf.to_milliseconds();
+                base_ms > 0 && ms > base_ms && ms % base_ms == 0
+            })
+            .map(|tf| Level {
+                timeframe: *tf,
+                bars: BTreeMap::new(),
+                settled: None,
+                newest: None,
+            })
+            .collect();
+
+        let mut multi_res = Self {
+            base: base.interval,
+            levels,
+        };
+        multi_res.downsample(base, 0);
+        multi_res
+    }
+
+    /// Brings every level up to date with the base series changed from `from` onwards
+    pub fn downsample<D: Downsample>(&mut self, base: &TimeSeries<D>, from: u64) {
+        for level in &mut self.levels {
+            let start = match level.newest {
+                Some(newest) if from >= newest => newest,
+                _ => {
+                    let interval = level.timeframe.to_milliseconds();
+                    let start = (from / interval) * interval;
+
+                    level.bars.split_off(&start);
+                    level.settled = None;
+                    level.newest = None;
+                    start
+                }
+            };
+
+            for (time, datapoint) in base.datapoints.range(start..) {
+                level.fold(*time, datapoint);
+            }
+        }
+    }
+
+    /// Finest timeframe that shows `visible_bar_count` base bars in at most
+    /// [`MAX_VISIBLE_BARS`] bars, the coarsest one kept if none does
+    pub fn select_resolution(&self, visible_bar_count: usize) -> Timeframe {
+        let base_ms = self.base.to_milliseconds().max(1);
+        let bars_at = |timeframe: Timeframe| {
+            let ratio = (timeframe.to_milliseconds() / base_ms).max(1) as usize;
+            visible_bar_count.div_ceil(ratio)
+        };
+
+        if bars_at(self.base) <= MAX_VISIBLE_BARS {
+            return self.base;
+        }
+
+        self.levels
+            .iter()
+            .map(|level| level.timeframe)
+            .find(|timeframe| bars_at(*timeframe) <= MAX_VISIBLE_BARS)
+            .or_else(|| self.levels.last().map(|level| level.timeframe))
+            .unwrap_or(self.base)
+    }
+
+    /// Downsampled bars at `timeframe`, `None` for the base or an unkept timeframe
+    pub fn level(&self, timeframe: Timeframe) -> Option<&BTreeMap<u64, DownsampledBar>> {
+        self.levels
+            .iter()
+            .find(|level| level.timeframe == timeframe)
+            .map(|level| &level.bars)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exchange::util::{Price, PriceStep};
+    use exchange::Trade;
+
+    fn minute_bars(closes: &[f32]) -> TimeSeries<KlineDataPoint> {
+        let interval = Timeframe::M1.to_milliseconds();
+        let klines: Vec<Kline> = closes
+            .iter()
+            .enumerate()
+            .map(|(i, close)| Kline {
+                time: i as u64 * interval,
+                open: Price::from_f32(close - 1.0),
+                high: Price::from_f32(close + 2.0),
+                low: Price::from_f32(close - 2.0),
+                close: Price::from_f32(*close),
+                volume: (1.0, 2.0),
+            })
+            .collect();
+
+        TimeSeries::<KlineDataPoint>::new(Timeframe::M1, PriceStep::from_f32_lossy(1.0), &klines)
+    }
+
+    #[test]
+    fn merges_ohlcv_into_coarser_buckets() {
+        let base = minute_bars(&[10.0, 14.0, 8.0, 11.0, 12.0, 20.0]);
+        let multi_res = MultiResTimeSeries::new(&base);
+
+        let five = multi_res.level(Timeframe::M5).unwrap();
+        assert_eq!(five.len(), 2);
+
+        let first = five[&0].kline;
+        assert_eq!(first.open, Price::from_f32(9.0));
+        assert_eq!(first.high, Price::from_f32(16.0));
+        assert_eq!(first.low, Price::from_f32(6.0));
+        assert_eq!(first.close, Price::from_f32(12.0));
+        assert_eq!(first.volume, (5.0, 10.0));
+
+        let hour = multi_res.level(Timeframe::H1).unwrap();
+        assert_eq!(hour[&0].kline.close, Price::from_f32(20.0));
+        assert_eq!(hour[&0].kline.volume, (6.0, 12.0));
+        assert!(multi_res.level(Timeframe::M1).is_none());
+    }
+
+    #[test]
+    fn insert_rebuilds_affected_bucket_with_trade_totals() {
+        let mut base = minute_bars(&[10.0, 11.0]);
+        let mut multi_res = MultiResTimeSeries::new(&base);
+
+        let minute = Timeframe::M1.to_milliseconds();
+        let trade = |time: u64, price: f32, qty: f32, is_sell: bool| Trade {
+            time,
+            is_sell,
+            price: Price::from_f32(price),
+            qty,
+        };
+        let trades = [
+            trade(10, 10.0, 1.0, false),
+            trade(minute + 10, 10.0, 2.0, true),
+            trade(minute + 20, 11.0, 0.5, false),
+        ];
+        base.insert_trades_existing_buckets(&trades);
+        multi_res.downsample(&base, trades[0].time);
+
+        let bucket = &multi_res.level(Timeframe::M5).unwrap()[&0];
+        assert_eq!((bucket.buy_qty, bucket.sell_qty), (1.5, 2.0));
+        assert_eq!(bucket.delta_qty(), -0.5);
+    }
+
+    #[test]
+    fn forming_bar_is_folded_without_double_counting() {
+        let mut base = minute_bars(&[10.0, 11.0]);
+        let mut multi_res = MultiResTimeSeries::new(&base);
+
+        let minute = Timeframe::M1.to_milliseconds();
+        let mut update = |time: u64, close: f32, volume: (f32, f32)| {
+            let kline = Kline {
+                time,
+                open: Price::from_f32(11.0),
+                high: Price::from_f32(close.max(11.0)),
+                low: Price::from_f32(close.min(11.0)),
+                close: Price::from_f32(close),
+                volume,
+            };
+            base.insert_klines(&[kline]);
+            multi_res.downsample(&base, time);
+        };
+
+        update(minute, 15.0, (2.0, 1.0));
+        update(minute, 9.0, (3.0, 4.0));
+        update(2 * minute, 12.0, (1.0, 1.0));
+        // a new hour only opens a new bucket
+        update(60 * minute, 13.0, (1.0, 0.0));
+
+        let bucket = multi_res.level(Timeframe::M5).unwrap()[&0].kline;
+        assert_eq!(bucket.close, Price::from_f32(12.0));
+        assert_eq!(bucket.low, Price::from_f32(8.0));
+        assert_eq!(bucket.volume, (5.0, 7.0));
+
+        let hour = multi_res.level(Timeframe::H1).unwrap();
+        assert_eq!(hour[&0].kline.volume, (5.0, 7.0));
+        assert_eq!(hour[&(60 * minute)].kline.open, Price::from_f32(11.0));
+    }
+
+    #[test]
+    fn selects_finest_resolution_under_the_bar_limit() {
+        let multi_res = MultiResTimeSeries::new(&minute_bars(&[10.0]));
+
+        let select = |bars: usize| multi_res.select_resolution(bars);
+
+        assert_eq!(select(MAX_VISIBLE_BARS), Timeframe::M1);
+        assert_eq!(select(MAX_VISIBLE_BARS + 1), Timeframe::M5);
+        assert_eq!(select(MAX_VISIBLE_BARS * 10), Timeframe::M15);
+        assert_eq!(select(MAX_VISIBLE_BARS * 100), Timeframe::H1);
+    }
+}
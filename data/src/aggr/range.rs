@@ -0,0 +1,214 @@
+use crate::aggr;
+use exchange::util::{Price, PriceStep};
+use exchange::{Kline, Trade};
+
+/// A bar that closes once price moves a fixed distance away from its open
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RangeBar {
+    pub open: Price,
+    pub high: Price,
+    pub low: Price,
+    pub close: Price,
+    pub buy_volume: f32,
+    pub sell_volume: f32,
+    pub start_time: u64,
+    pub end_time: u64,
+}
+
+impl RangeBar {
+    pub fn new(trade: &Trade) -> Self {
+        let (buy_volume, sell_volume) = if trade.is_sell {
+            (0.0, trade.qty)
+        } else {
+            (trade.qty, 0.0)
+        };
+
+        Self {
+            open: trade.price,
+            high: trade.price,
+            low: trade.price,
+            close: trade.price,
+            buy_volume,
+            sell_volume,
+            start_time: trade.time,
+            end_time: trade.time,
+        }
+    }
+
+    pub fn update_with_trade(&mut self, trade: &Trade) {
+        self.high = self.high.max(trade.price);
+        self.low = self.low.min(trade.price);
+        self.close = trade.price;
+        self.end_time = trade.time;
+
+        if trade.is_sell {
+            self.sell_volume += trade.qty;
+        } else {
+            self.buy_volume += trade.qty;
+        }
+    }
+
+    /// The bar as a kline stamped with its start time, for drawing with the candle primitives
+    pub fn kline(&self) -> Kline {
+        Kline {
+            time: self.start_time,
+            open: self.open,
+            high: self.high,
+            low: self.low,
+            close: self.close,
+            volume: (self.buy_volume, self.sell_volume),
+        }
+    }
+}
+
+pub struct RangeBarSeries {
+    pub bars: Vec<RangeBar>,
+    pub range_ticks: u32,
+    pub tick_size: PriceStep,
+}
+
+impl RangeBarSeries {
+    pub fn new(range_ticks: u32, tick_size: PriceStep, raw_trades: &[Trade]) -> Self {
+        let mut series = Self {
+            bars: Vec::new(),
+            range_ticks: range_ticks.max(1),
+            tick_size,
+        };
+
+        series.insert_trades(raw_trades);
+        series
+    }
+
+    /// Distance from the open a trade has to exceed to start a new bar
+    pub fn range(&self) -> Price {
+        Price::from_units(0).add_steps(i64::from(self.range_ticks), self.tick_size)
+    }
+
+    /// Starts a new bar when `trade` is more than the range away from the current bar's open,
+    /// both prices rounded to the tick size so float noise can't tip a bar over
+    pub fn feed_trade(&mut self, trade: &Trade) {
+        let (range, step) = (self.range(), self.tick_size);
+        let price = trade.price.round_to_step(step);
+
+        match self.bars.last_mut() {
+            Some(bar) if (price - bar.open.round_to_step(step)).units.abs() <= range.units => {
+                bar.update_with_trade(trade);
+            }
+            _ => self.bars.push(RangeBar::new(trade)),
+        }
+    }
+
+    pub fn insert_trades(&mut self, buffer: &[Trade]) {
+        for trade in buffer {
+            self.feed_trade(trade);
+        }
+    }
+
+    /// Rebuilds every bar from `raw_trades` with the new range
+    pub fn change_range(&mut self, range_ticks: u32, raw_trades: &[Trade]) {
+        self.range_ticks = range_ticks.max(1);
+        self.bars.clear();
+        self.insert_trades(raw_trades);
+    }
+
+    pub fn latest_bar(&self) -> Option<&RangeBar> {
+        self.bars.last()
+    }
+
+    /// Bars from `earliest` to `latest`, counted backwards with 0 as the latest bar
+    pub fn bars_in_range(
+        &self,
+        earliest: usize,
+        latest: usize,
+    ) -> impl Iterator<Item = (usize, &RangeBar)> {
+        self.bars
+            .iter()
+            .rev()
+            .enumerate()
+            .filter(move |(index, _)| *index >= earliest && *index <= latest)
+    }
+
+    pub fn min_max_price_in_range_prices(
+        &self,
+        earliest: usize,
+        latest: usize,
+    ) -> Option<(Price, Price)> {
+        if earliest > latest {
+            return None;
+        }
+
+        self.bars_in_range(earliest, latest)
+            .fold(None, |acc, (_, bar)| match acc {
+                Some((low, high)) => Some((bar.low.min(low), bar.high.max(high))),
+                None => Some((bar.low, bar.high)),
+            })
+    }
+
+    /// Padded `(highest, lowest)` over the bar range, zero bounds if it holds no bars
+    pub fn price_scale_in_range(
+        &self,
+        earliest: usize,
+        latest: usize,
+        padding_pct: f32,
+        min_padding: Price,
+    ) -> (Price, Price) {
+        match self.min_max_price_in_range_prices(earliest, latest) {
+            Some((low, high)) => aggr::pad_price_range(low, high, padding_pct, min_padding),
+            None => (Price::from_f32(0.0), Price::from_f32(0.0)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(time: u64, price: f32, is_sell: bool) -> Trade {
+        Trade {
+            time,
+            is_sell,
+            price: Price::from_f32(price),
+            qty: 1.0,
+        }
+    }
+
+    #[test]
+    fn new_bar_once_move_exceeds_range() {
+        let mut series = RangeBarSeries::new(10, PriceStep::from_f32_lossy(0.5), &[]);
+
+        // 10 ticks of 0.5, a move of exactly 5.0 stays in the bar
+        series.feed_trade(&trade(1, 100.0, false));
+        series.feed_trade(&trade(2, 105.0, true));
+        series.feed_trade(&trade(3, 95.0, false));
+        assert_eq!(series.bars.len(), 1);
+
+        series.feed_trade(&trade(4, 105.5, false));
+        assert_eq!(series.bars.len(), 2);
+
+        let first = series.bars[0];
+        assert_eq!(first.open, Price::from_f32(100.0));
+        assert_eq!(first.high, Price::from_f32(105.0));
+        assert_eq!(first.low, Price::from_f32(95.0));
+        assert_eq!(first.close, Price::from_f32(95.0));
+        assert_eq!((first.buy_volume, first.sell_volume), (2.0, 1.0));
+        assert_eq!((first.start_time, first.end_time), (1, 3));
+
+        let latest = series.latest_bar().unwrap();
+        assert_eq!(latest.open, Price::from_f32(105.5));
+        assert_eq!(latest.start_time, 4);
+    }
+
+    #[test]
+    fn change_range_rebuilds_bars() {
+        let trades: Vec<Trade> = (0..20).map(|i| trade(i, 100.0 + i as f32, false)).collect();
+        let mut series = RangeBarSeries::new(2, PriceStep::from_f32_lossy(1.0), &trades);
+        assert_eq!(series.bars.len(), 7);
+
+        series.change_range(9, &trades);
+        assert_eq!(series.bars.len(), 2);
+        assert_eq!(
+            series.min_max_price_in_range_prices(0, 0),
+            Some((Price::from_f32(110.0), Price::from_f32(119.0)))
+        );
+    }
+}
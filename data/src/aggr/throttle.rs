@@ -0,0 +1,148 @@
+use exchange::Trade;
+use exchange::depth::Depth;
+
+use rustc_hash::FxHashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Depth update let through by the throttle: the latest book of the stream
+/// and every trade that arrived since the previous one
+#[derive(Debug, Clone)]
+pub struct CoalescedDepth {
+    pub depth_update_t: u64,
+    pub depth: Arc<Depth>,
+    pub trades: Vec<Trade>,
+}
+
+/// Coalesces the depth updates of each stream to at most one per interval.
+///
+/// Books are snapshots, so a held back update is simply replaced by the next one,
+/// while its trades are carried over so footprints and sound alerts see all of them.
+pub struct DepthCoalescer<K> {
+    last_flush: FxHashMap<K, Instant>,
+    pending: FxHashMap<K, CoalescedDepth>,
+}
+
+impl<K> Default for DepthCoalescer<K> {
+    fn default() -> Self {
+        Self {
+            last_flush: FxHashMap::default(),
+            pending: FxHashMap::default(),
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone> DepthCoalescer<K> {
+    /// Takes an update of `stream`, returning it merged with the held back ones
+    /// once `interval` has passed since the stream's last flush
+    pub fn push(
+        &mut self,
+        stream: &K,
+        depth_update_t: u64,
+        depth: Arc<Depth>,
+        trades: &[Trade],
+        interval: Duration,
+        now: Instant,
+    ) -> Option<CoalescedDepth> {
+        let pending = self
+            .pending
+            .entry(stream.clone())
+            .or_insert_with(|| CoalescedDepth {
+                depth_update_t,
+                depth: depth.clone(),
+                trades: vec![],
+            });
+        pending.depth_update_t = depth_update_t;
+        pending.depth = depth;
+        pending.trades.extend_from_slice(trades);
+
+        let is_due = self
+            .last_flush
+            .get(stream)
+            .is_none_or(|last| now.duration_since(*last) >= interval);
+
+        if is_due {
+            self.last_flush.insert(stream.clone(), now);
+            self.pending.remove(stream)
+        } else {
+            None
+        }
+    }
+
+    /// Held back updates whose interval has passed, so a stream that went quiet
+    /// still shows its last book and trades
+    pub fn flush_due(&mut self, interval: Duration, now: Instant) -> Vec<(K, CoalescedDepth)> {
+        let due = self
+            .pending
+            .keys()
+            .filter(|stream| {
+                self.last_flush
+                    .get(*stream)
+                    .is_none_or(|last| now.duration_since(*last) >= interval)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        due.into_iter()
+            .filter_map(|stream| {
+                self.last_flush.insert(stream.clone(), now);
+                self.pending
+                    .remove(&stream)
+                    .map(|pending| (stream, pending))
+            })
+            .collect()
+    }
+
+    /// Everything held back, for when the throttle gets turned off
+    pub fn flush_all(&mut self) -> Vec<(K, CoalescedDepth)> {
+        self.last_flush.clear();
+        self.pending.drain().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exchange::util::Price;
+
+    fn trade(time: u64) -> Trade {
+        Trade {
+            time,
+            is_sell: false,
+            price: Price::from_f32(100.0),
+            qty: 1.0,
+        }
+    }
+
+    #[test]
+    fn updates_within_interval_are_merged_into_the_next_flush() {
+        let mut coalescer = DepthCoalescer::default();
+        let interval = Duration::from_millis(250);
+        let start = Instant::now();
+        let depth = Arc::new(Depth::default());
+
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        let mut push =
+            |t: u64| coalescer.push(&"btc", t, depth.clone(), &[trade(t)], interval, at(t));
+
+        assert_eq!(push(0).map(|d| d.trades.len()), Some(1));
+        assert!(push(100).is_none());
+        assert!(push(200).is_none());
+
+        let flushed = push(300).unwrap();
+        assert_eq!(flushed.depth_update_t, 300);
+        assert_eq!(
+            flushed.trades.iter().map(|t| t.time).collect::<Vec<_>>(),
+            [100, 200, 300]
+        );
+
+        assert!(push(400).is_none());
+        assert!(coalescer.flush_due(interval, at(500)).is_empty());
+
+        let quiet = coalescer.flush_due(interval, at(550));
+        assert_eq!(quiet.len(), 1);
+        assert_eq!(quiet[0].1.depth_update_t, 400);
+        assert!(coalescer.flush_all().is_empty());
+    }
+}
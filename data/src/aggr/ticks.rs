@@ -1,7 +1,7 @@
 use crate::aggr;
-use crate::chart::kline::{ClusterKind, KlineTrades, NPoc};
+use crate::chart::kline::{ClusterKind, FootprintCellText, KlineTrades, NPoc};
 use exchange::util::{Price, PriceStep};
-use exchange::{Kline, Trade};
+use exchange::{Kline, Liquidation, Trade};
 
 use std::collections::BTreeMap;
 
@@ -55,17 +55,16 @@ impl TickAccumulation {
         self.footprint.add_trade_to_nearest_bin(trade, step);
     }
 
-    pub fn max_cluster_qty(&self, cluster_kind: ClusterKind, highest: Price, lowest: Price) -> f32 {
-        match cluster_kind {
-            ClusterKind::BidAsk => self.footprint.max_qty_by(highest, lowest, f32::max),
-            ClusterKind::DeltaProfile => self
-                .footprint
-                .max_qty_by(highest, lowest, |buy, sell| (buy - sell).abs()),
-            ClusterKind::VolumeProfile => {
-                self.footprint
-                    .max_qty_by(highest, lowest, |buy, sell| buy + sell)
-            }
-        }
+    pub fn max_cluster_qty(
+        &self,
+        cluster_kind: ClusterKind,
+        cell_text: FootprintCellText,
+        highest: Price,
+        lowest: Price,
+    ) -> f32 {
+        self.footprint.max_cell_value(highest, lowest, |group| {
+            cluster_kind.cell_value(cell_text, group)
+        })
     }
 
     pub fn is_full(&self, interval: aggr::TickCount) -> bool {
@@ -116,6 +115,19 @@ impl TickAggr {
         }
     }
 
+    /// Adds each liquidation to the bar that was open at its time
+    pub fn insert_liquidations(&mut self, liquidations: &[Liquidation]) {
+        for liquidation in liquidations {
+            let idx = self
+                .datapoints
+                .partition_point(|dp| dp.kline.time <= liquidation.time);
+
+            if let Some(dp) = idx.checked_sub(1).and_then(|i| self.datapoints.get_mut(i)) {
+                dp.footprint.add_liquidation(liquidation, self.tick_size);
+            }
+        }
+    }
+
     /// return latest data point and its index
     pub fn latest_dp(&self) -> Option<(&TickAccumulation, usize)> {
         self.datapoints
@@ -157,23 +169,30 @@ impl TickAggr {
             }
         }
 
-        self.update_poc_status();
+        self.update_poc_status(0, self.datapoints.len().saturating_sub(1));
     }
 
-    pub fn update_poc_status(&mut self) {
-        let updates = self
-            .datapoints
+    /// Updates the naked/filled status of POCs from `earliest` to `latest`, counted backwards
+    /// with 0 as the latest datapoint, scanning no further than `earliest` for fills
+    pub fn update_poc_status(&mut self, earliest: usize, latest: usize) {
+        let total_points = self.datapoints.len();
+        if earliest >= total_points || earliest > latest {
+            return;
+        }
+
+        let first_idx = (total_points - 1).saturating_sub(latest);
+        let last_idx = (total_points - 1).saturating_sub(earliest);
+
+        let updates = self.datapoints[first_idx..=last_idx]
             .iter()
             .enumerate()
-            .filter_map(|(idx, dp)| dp.poc_price().map(|price| (idx, price)))
+            .filter_map(|(offset, dp)| dp.poc_price().map(|price| (first_idx + offset, price)))
             .collect::<Vec<_>>();
 
-        let total_points = self.datapoints.len();
-
         for (current_idx, poc_price) in updates {
             let mut npoc = NPoc::default();
 
-            for next_idx in (current_idx + 1)..total_points {
+            for next_idx in (current_idx + 1)..=last_idx {
                 let next_dp = &self.datapoints[next_idx];
 
                 let next_dp_low = next_dp.kline.low.round_to_side_step(true, self.tick_size);
@@ -234,6 +253,20 @@ impl TickAggr {
         }
     }
 
+    /// Padded `(highest, lowest)` over the tick range, zero bounds if it holds no datapoints
+    pub fn price_scale_in_range(
+        &self,
+        earliest: usize,
+        latest: usize,
+        padding_pct: f32,
+        min_padding: Price,
+    ) -> (Price, Price) {
+        match self.min_max_price_in_range_prices(earliest, latest) {
+            Some((low, high)) => aggr::pad_price_range(low, high, padding_pct, min_padding),
+            None => (Price::from_f32(0.0), Price::from_f32(0.0)),
+        }
+    }
+
     pub fn min_max_price_in_range(&self, earliest: usize, latest: usize) -> Option<(f32, f32)> {
         self.min_max_price_in_range_prices(earliest, latest)
             .map(|(min_p, max_p)| (min_p.to_f32(), max_p.to_f32()))
@@ -242,6 +275,7 @@ impl TickAggr {
     pub fn max_qty_idx_range(
         &self,
         cluster_kind: ClusterKind,
+        cell_text: FootprintCellText,
         earliest: usize,
         latest: usize,
         highest: Price,
@@ -255,8 +289,12 @@ impl TickAggr {
             .enumerate()
             .filter(|(index, _)| *index <= latest && *index >= earliest)
             .for_each(|(_, dp)| {
-                max_cluster_qty =
-                    max_cluster_qty.max(dp.max_cluster_qty(cluster_kind, highest, lowest));
+                max_cluster_qty = max_cluster_qty.max(dp.max_cluster_qty(
+                    cluster_kind,
+                    cell_text,
+                    highest,
+                    lowest,
+                ));
             });
 
         max_cluster_qty
@@ -6,13 +6,20 @@
 // ============================================================================
 
 use std::collections::BTreeMap;  // BTreeMap 是有序映射，按键排序
+use std::ops::RangeInclusive;
 
-use crate::chart::Basis;
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::UserTimezone;
+use crate::aggr::TickCount;
 use crate::chart::heatmap::HeatmapDataPoint;
-use crate::chart::kline::{ClusterKind, KlineDataPoint, KlineTrades, NPoc};
+use crate::chart::kline::{
+    ClusterKind, Divergence, FootprintCellText, KlineDataPoint, KlineTrades, NPoc, SessionSeparator,
+};
+use crate::chart::{Basis, Measurement};
 
 use exchange::util::{Price, PriceStep};
-use exchange::{Kline, Timeframe, Trade};
+use exchange::{Kline, Liquidation, Timeframe, Trade};
 
 /// ============================================================================
 /// DataPoint trait - 数据点抽象接口
@@ -26,6 +33,7 @@ use exchange::{Kline, Timeframe, Trade};
 /// - &mut self 表示可变借用（可以修改 self）
 /// - &Trade 表示不可变借用（只读访问，不转移所有权）
 /// - Option<T> 表示可能不存在的值（类型安全的空值）
+///
 /// ============================================================================
 pub trait DataPoint {
     /// 添加交易数据到当前数据点
@@ -74,6 +82,7 @@ pub trait DataPoint {
 ///   - 查询复杂度: O(log n)
 ///   - 范围查询高效（连续内存访问）
 ///   - 适合时间序列数据
+///
 /// ============================================================================
 pub struct TimeSeries<D: DataPoint> {
     /// 数据点映射：时间戳 -> 数据点
@@ -85,6 +94,9 @@ pub struct TimeSeries<D: DataPoint> {
     
     /// 价格步长，用于价格分组和显示
     pub tick_size: PriceStep,
+
+    /// 插入成交时被更新的时间桶，跨调用复用以免每批成交都重新分配
+    updated_times: FxHashSet<u64>,
 }
 
 /// ============================================================================
@@ -122,6 +134,33 @@ impl<D: DataPoint> TimeSeries<D> {
         self.datapoints.keys().last().copied()
     }
 
+    /// 遍历时段边界：`timezone` 时钟下每个新的自然日/周中第一个数据点的时间戳
+    ///
+    /// 第一个数据点之前的时段未知，不算边界；
+    /// 周期不短于时段长度时（如日线上的日分隔）每根K线都会是边界，此时不产出任何边界
+    pub fn session_boundaries(
+        &self,
+        session: SessionSeparator,
+        timezone: UserTimezone,
+    ) -> impl Iterator<Item = u64> + '_ {
+        let enabled = session
+            .period_ms()
+            .is_some_and(|period| self.interval.to_milliseconds() < period);
+
+        let mut prev_session = None;
+
+        self.datapoints
+            .keys()
+            .filter(move |_| enabled)
+            .filter_map(move |&time| {
+                let current = session.session_of(time, timezone.offset_ms_at(time as i64));
+                let is_boundary = prev_session.is_some_and(|prev| Some(prev) != current);
+                prev_session = current;
+
+                is_boundary.then_some(time)
+            })
+    }
+
     /// 获取最新的 K线数据
     /// 
     /// # Rust 特性
@@ -216,6 +255,47 @@ impl<D: DataPoint> TimeSeries<D> {
         Some((min_price, max_price))
     }
 
+    /// 按时间范围计算价格刻度
+    ///
+    /// 与 `price_scale` 不同，这里以实际的时间区间（而非数据点数量）为准，
+    /// 因此在不同周期和单元格宽度下表现一致。
+    ///
+    /// # 参数
+    /// - earliest/latest: 时间范围（毫秒，闭区间）
+    /// - padding_pct: 上下各留出的余量，占价格区间的百分比
+    /// - min_padding: 最小余量（例如足迹图上下需额外容纳的价格格子）
+    ///
+    /// # 返回值
+    /// - (highest, lowest): 含余量的价格范围，范围内无数据时返回零值
+    pub fn price_scale_in_range(
+        &self,
+        earliest: u64,
+        latest: u64,
+        padding_pct: f32,
+        min_padding: Price,
+    ) -> (Price, Price) {
+        match self.min_max_price_in_range_prices(earliest, latest) {
+            Some((low, high)) => super::pad_price_range(low, high, padding_pct, min_padding),
+            None => (Price::from_f32(0.0), Price::from_f32(0.0)),
+        }
+    }
+
+    /// 测量两个点之间的价格/时间跨度（测量尺工具）
+    ///
+    /// 时间戳对齐到本序列的周期，价格对齐到价格步长，
+    /// K线数量等由 [`Measurement`] 根据周期计算
+    pub fn measure(&self, start: (u64, Price), end: (u64, Price)) -> Measurement {
+        let interval = self.interval.to_milliseconds().max(1);
+        let snap = |(time, price): (u64, Price)| {
+            (
+                (time + interval / 2) / interval * interval,
+                price.round_to_step(self.tick_size),
+            )
+        };
+
+        Measurement::new(Basis::Time(self.interval), snap(start), snap(end))
+    }
+
     pub fn min_max_price_in_range(&self, earliest: u64, latest: u64) -> Option<(f32, f32)> {
         self.min_max_price_in_range_prices(earliest, latest)
             .map(|(min_p, max_p)| (min_p.to_f32(), max_p.to_f32()))
@@ -264,6 +344,27 @@ impl<D: DataPoint> TimeSeries<D> {
 
         None
     }
+
+    /// 区间内缺失的K线，相邻的缺口合并为 (首个缺失时间, 最后缺失时间)
+    pub fn gap_ranges(&self, earliest: u64, latest: u64, interval: u64) -> Vec<(u64, u64)> {
+        let mut ranges: Vec<(u64, u64)> = vec![];
+        if interval == 0 {
+            return ranges;
+        }
+
+        let mut time = earliest;
+        while time < latest {
+            if !self.datapoints.contains_key(&time) {
+                match ranges.last_mut() {
+                    Some((_, end)) if *end + interval == time => *end = time,
+                    _ => ranges.push((time, time)),
+                }
+            }
+            time += interval;
+        }
+
+        ranges
+    }
 }
 
 /// ============================================================================
@@ -290,6 +391,7 @@ impl TimeSeries<KlineDataPoint> {
             datapoints: BTreeMap::new(),
             interval,
             tick_size,
+            updated_times: FxHashSet::default(),
         };
 
         timeseries.insert_klines(klines);
@@ -308,6 +410,7 @@ impl TimeSeries<KlineDataPoint> {
             datapoints: self.datapoints.clone(),  // 深度复制
             interval: self.interval,
             tick_size: self.tick_size,
+            updated_times: FxHashSet::default(),
         };
 
         new_series.insert_trades_or_create_bucket(trades);
@@ -338,7 +441,79 @@ impl TimeSeries<KlineDataPoint> {
         }
 
         // 更新 POC (Point of Control) 状态
-        self.update_poc_status();
+        let (earliest, latest) = self.timerange();
+        self.update_poc_status(earliest, latest);
+    }
+
+    /// 原地更新（或追加）单根K线，结果与 `insert_klines(&[kline])` 一致
+    ///
+    /// 只重算这根K线的 POC，之前的 POC 里只有可能被它回补、或原本由它回补的才重新判断，
+    /// 实时更新最新K线时不必扫描整个序列
+    pub fn update_kline(&mut self, kline: &Kline) {
+        let time = kline.time;
+        let tick_size = self.tick_size;
+
+        let entry = self
+            .datapoints
+            .entry(time)
+            .or_insert_with(|| KlineDataPoint {
+                kline: *kline,
+                footprint: KlineTrades::new(),
+            });
+        entry.kline = *kline;
+        entry.calculate_poc();
+
+        if let Some(poc_price) = entry.poc_price() {
+            let status = self.poc_status_after(time, poc_price);
+            if let Some(data_point) = self.datapoints.get_mut(&time) {
+                data_point.set_poc_status(status);
+            }
+        }
+
+        let (low, high) = npoc_range(kline, tick_size);
+        let mut rescans = vec![];
+
+        for (&poc_time, data_point) in self.datapoints.range_mut(..time) {
+            let Some(poc) = data_point.footprint.poc.as_mut() else {
+                continue;
+            };
+            let covered = low <= poc.price && high >= poc.price;
+
+            match poc.status {
+                // 更早的K线已经回补，与这根无关
+                NPoc::Filled { at } if at < time => {}
+                NPoc::Filled { at } if at == time && !covered => {
+                    rescans.push((poc_time, poc.price));
+                }
+                // 成交改变 POC 后状态被重置，需要完整判断
+                NPoc::None => rescans.push((poc_time, poc.price)),
+                _ if covered => poc.status = NPoc::Filled { at: time },
+                _ => {}
+            }
+        }
+
+        for (poc_time, poc_price) in rescans {
+            let status = self.poc_status_after(poc_time, poc_price);
+            if let Some(data_point) = self.datapoints.get_mut(&poc_time) {
+                data_point.set_poc_status(status);
+            }
+        }
+    }
+
+    /// `time` 之后第一根覆盖 `poc_price` 的K线，规则同 [`NpocSnapshot::compute`]
+    fn poc_status_after(&self, time: u64, poc_price: Price) -> NPoc {
+        let mut npoc = NPoc::default();
+
+        for (&next_time, data_point) in self.datapoints.range(time.saturating_add(1)..) {
+            let (low, high) = npoc_range(&data_point.kline, self.tick_size);
+            if low <= poc_price && high >= poc_price {
+                npoc.filled(next_time);
+                break;
+            } else {
+                npoc.unfilled();
+            }
+        }
+        npoc
     }
 
     /// 插入交易数据，自动创建或更新 K线桶
@@ -352,30 +527,25 @@ impl TimeSeries<KlineDataPoint> {
     /// 4. 重新计算 POC (成交量最大价格)
     /// 
     /// # 性能优化
-    /// - 使用 Vec 跟踪更新的时间戳，避免重复计算 POC
+    /// - 使用复用的 FxHashSet 跟踪更新的时间戳，避免重复计算 POC
     /// - 批量处理，只在最后统一更新 POC
-    /// 
-    /// # Rust 特性
-    /// - buffer.iter().for_each() 是函数式编程风格
-    /// - 闭包捕获外部变量（aggr_time, updated_times）
     pub fn insert_trades_or_create_bucket(&mut self, buffer: &[Trade]) {
         if buffer.is_empty() {
             return;  // 提前返回，避免不必要的计算
         }
         
         let aggr_time = self.interval.to_milliseconds();
-        let mut updated_times = Vec::new();  // 跟踪哪些时间桶被更新
+        // 取出复用的集合，循环中才能同时可变借用 datapoints
+        let mut updated_times = std::mem::take(&mut self.updated_times);
 
         // 遍历所有交易
-        buffer.iter().for_each(|trade| {
+        for trade in buffer {
             // 时间戳向下取整到间隔边界
             // 例如：14:32:45 with 5分钟间隔 -> 14:30:00
             let rounded_time = (trade.time / aggr_time) * aggr_time;
 
             // 记录更新的时间戳（用于后续 POC 计算）
-            if !updated_times.contains(&rounded_time) {
-                updated_times.push(rounded_time);
-            }
+            updated_times.insert(rounded_time);
 
             // 获取或创建数据点
             let entry = self
@@ -395,14 +565,64 @@ impl TimeSeries<KlineDataPoint> {
 
             // 添加交易数据到 Footprint
             entry.add_trade(trade, self.tick_size);
-        });
+        }
 
-        // 批量更新所有受影响的数据点的 POC
-        for time in updated_times {
+        // 批量更新所有受影响的数据点的 POC，drain 保留集合容量供下次使用
+        for time in updated_times.drain() {
             if let Some(data_point) = self.datapoints.get_mut(&time) {
                 data_point.calculate_poc();
             }
         }
+        self.updated_times = updated_times;
+    }
+
+    /// 由成交直接构建K线，开高低收与买卖量都来自成交本身
+    ///
+    /// 用于交易所不提供的秒级周期；返回受影响的K线（按时间升序）
+    pub fn insert_trades_as_klines(&mut self, buffer: &[Trade]) -> Vec<Kline> {
+        let Some(first_time) = buffer.iter().map(|trade| trade.time).min() else {
+            return vec![];
+        };
+        let aggr_time = self.interval.to_milliseconds();
+
+        for trade in buffer {
+            let rounded_time = (trade.time / aggr_time) * aggr_time;
+
+            let entry = self
+                .datapoints
+                .entry(rounded_time)
+                .or_insert_with(|| KlineDataPoint {
+                    kline: Kline {
+                        time: rounded_time,
+                        open: trade.price,
+                        high: trade.price,
+                        low: trade.price,
+                        close: trade.price,
+                        volume: (0.0, 0.0),
+                    },
+                    footprint: KlineTrades::new(),
+                });
+
+            let kline = &mut entry.kline;
+            kline.high = kline.high.max(trade.price);
+            kline.low = kline.low.min(trade.price);
+            kline.close = trade.price;
+            if trade.is_sell {
+                kline.volume.1 += trade.qty;
+            } else {
+                kline.volume.0 += trade.qty;
+            }
+
+            entry.add_trade(trade, self.tick_size);
+        }
+
+        self.datapoints
+            .range_mut((first_time / aggr_time) * aggr_time..)
+            .map(|(_, dp)| {
+                dp.calculate_poc();
+                dp.kline
+            })
+            .collect()
     }
 
     pub fn insert_trades_existing_buckets(&mut self, buffer: &[Trade]) {
@@ -439,34 +659,154 @@ impl TimeSeries<KlineDataPoint> {
         }
     }
 
-    pub fn update_poc_status(&mut self) {
-        let updates = self
-            .datapoints
+    /// 把强平记录累加到所属时间桶的 footprint 上
+    ///
+    /// 只写入已存在的K线，尚无K线的时间桶直接忽略
+    pub fn insert_liquidations(&mut self, liquidations: &[Liquidation]) {
+        let aggr_time = self.interval.to_milliseconds().max(1);
+
+        for liquidation in liquidations {
+            let rounded_time = (liquidation.time / aggr_time) * aggr_time;
+
+            if let Some(entry) = self.datapoints.get_mut(&rounded_time) {
+                entry.footprint.add_liquidation(liquidation, self.tick_size);
+            }
+        }
+    }
+
+    /// 每根K线的 POC 价格，按K线时间升序排列
+    ///
+    /// 连起来即为 POC 的迁移轨迹；尚未计算出 POC 的K线不包含在内，
+    /// 裸露/回补状态由 [`Self::update_poc_status`] 写回各数据点
+    pub fn poc_history(&self) -> BTreeMap<u64, Price> {
+        self.datapoints
             .iter()
             .filter_map(|(&time, dp)| dp.poc_price().map(|price| (time, price)))
-            .collect::<Vec<_>>();
+            .collect()
+    }
 
-        for (current_time, poc_price) in updates {
-            let mut npoc = NPoc::default();
+    /// 合并 `earliest..=latest` 内所有K线的足迹成交
+    ///
+    /// 返回 价格 -> (主动买量, 主动卖量)，即整个区间的成交量分布，
+    /// 不同于逐根K线的 cluster
+    pub fn volume_profile(&self, earliest: u64, latest: u64) -> FxHashMap<Price, (f32, f32)> {
+        let mut profile = FxHashMap::default();
+        if earliest > latest {
+            return profile;
+        }
 
-            for (&next_time, next_dp) in self.datapoints.range((current_time + 1)..) {
-                let next_dp_low = next_dp.kline.low.round_to_side_step(true, self.tick_size);
-                let next_dp_high = next_dp.kline.high.round_to_side_step(false, self.tick_size);
+        for (_, dp) in self.datapoints.range(earliest..=latest) {
+            for (price, group) in &dp.footprint.trades {
+                let entry = profile.entry(*price).or_insert((0.0, 0.0));
+                entry.0 += group.buy_qty;
+                entry.1 += group.sell_qty;
+            }
+        }
+        profile
+    }
 
-                if next_dp_low <= poc_price && next_dp_high >= poc_price {
-                    npoc.filled(next_time);
-                    break;
-                } else {
-                    npoc.unfilled();
-                }
+    /// 按K线自带的成交量估算 `earliest..=latest` 的成交量分布
+    ///
+    /// 每根K线的总量（买 + 卖）均匀分摊到最低价与最高价之间的各价格档位，
+    /// 用于只有K线、没有逐笔成交的蜡烛图
+    pub fn kline_volume_profile(&self, earliest: u64, latest: u64) -> BTreeMap<Price, f32> {
+        let mut profile = BTreeMap::new();
+        if earliest > latest {
+            return profile;
+        }
+        let step = self.tick_size;
+
+        for (_, dp) in self.datapoints.range(earliest..=latest) {
+            let (buy, sell) = dp.kline.volume;
+            // 交易所未提供买卖拆分时买量为-1，卖量即总量
+            let total = if buy < 0.0 { sell } else { buy + sell };
+
+            let low = dp.kline.low.round_to_step(step);
+            let Some(levels) =
+                Price::steps_between_inclusive(low, dp.kline.high.round_to_step(step), step)
+            else {
+                continue;
+            };
+            if total <= 0.0 {
+                continue;
+            }
+
+            let share = total / levels as f32;
+            for level in 0..levels {
+                *profile
+                    .entry(low.add_steps(level as i64, step))
+                    .or_insert(0.0) += share;
+            }
+        }
+        profile
+    }
+
+    /// 更新 `earliest..=latest` 内各K线 POC 的裸露/回补状态
+    ///
+    /// 只向后扫描到 `latest` 为止，窗口外的K线既不更新也不参与判断
+    pub fn update_poc_status(&mut self, earliest: u64, latest: u64) {
+        let statuses = self.npoc_snapshot(earliest, latest).compute();
+        self.apply_poc_status(&statuses);
+    }
+
+    /// 复制 `earliest..=latest` 内计算裸露 POC 所需的数据
+    ///
+    /// 快照不借用时间序列，K线多时可交给后台线程计算
+    pub fn npoc_snapshot(&self, earliest: u64, latest: u64) -> NpocSnapshot {
+        if earliest > latest {
+            return NpocSnapshot::default();
+        }
+
+        let mut snapshot = NpocSnapshot::default();
+        for (&time, dp) in self.datapoints.range(earliest..=latest) {
+            if let Some(poc) = dp.poc_price() {
+                snapshot.pocs.push((time, poc));
             }
+            let (low, high) = npoc_range(&dp.kline, self.tick_size);
+            snapshot.ranges.push((time, low, high));
+        }
+        snapshot
+    }
 
-            if let Some(data_point) = self.datapoints.get_mut(&current_time) {
+    /// 写回 [`NpocSnapshot::compute`] 的结果
+    ///
+    /// 只更新 POC 仍在快照时价格上的K线，计算期间变了的K线等下次计算
+    pub fn apply_poc_status(&mut self, statuses: &[(u64, Price, NPoc)]) {
+        for &(time, poc_price, npoc) in statuses {
+            if let Some(data_point) = self.datapoints.get_mut(&time)
+                && data_point.poc_price() == Some(poc_price)
+            {
                 data_point.set_poc_status(npoc);
             }
         }
     }
 
+    /// K线数超过 `max_bars` 一成以上时，丢弃最早的K线直到剩下 `max_bars` 根
+    ///
+    /// `keep_from` 及之后的K线（可见与预取范围）始终保留，返回裁剪后最早的时间，
+    /// 未裁剪时返回 `None`。余量让裁剪不会在每根新K线上都发生。
+    /// 保留下来的 `NPoc::Filled` 只指向更晚的K线，不会指向被丢弃的K线；
+    /// 成交缺口也只在保留的K线里查找，裁剪不会造成新的缺口
+    pub fn prune_oldest(&mut self, max_bars: usize, keep_from: u64) -> Option<u64> {
+        let max_bars = max_bars.max(1);
+        if self.datapoints.len() <= max_bars + max_bars / 10 {
+            return None;
+        }
+
+        let excess = self.datapoints.len() - max_bars;
+        let cutoff = self
+            .datapoints
+            .keys()
+            .nth(excess)
+            .map(|&time| time.min(keep_from))?;
+
+        // 可见范围从最早的K线就开始时没有可丢弃的
+        self.datapoints.range(..cutoff).next()?;
+
+        self.datapoints = self.datapoints.split_off(&cutoff);
+        Some(cutoff)
+    }
+
     pub fn suggest_trade_fetch_range(
         &self,
         visible_earliest: u64,
@@ -527,6 +867,7 @@ impl TimeSeries<KlineDataPoint> {
     pub fn max_qty_ts_range(
         &self,
         cluster_kind: ClusterKind,
+        cell_text: FootprintCellText,
         earliest: u64,
         latest: u64,
         highest: Price,
@@ -537,28 +878,151 @@ impl TimeSeries<KlineDataPoint> {
         self.datapoints
             .range(earliest..=latest)
             .for_each(|(_, dp)| {
-                max_cluster_qty =
-                    max_cluster_qty.max(dp.max_cluster_qty(cluster_kind, highest, lowest));
+                max_cluster_qty = max_cluster_qty.max(dp.max_cluster_qty(
+                    cluster_kind,
+                    cell_text,
+                    highest,
+                    lowest,
+                ));
             });
 
         max_cluster_qty
     }
+
+    /// 检测价格与累计 Delta 之间的背离
+    ///
+    /// 摆动高点：最高价高于左侧 `lookback` 根、且不低于右侧 `lookback` 根K线的最高价，
+    /// 摆动低点同理。相邻两个摆动高点中价格创新高而累计 Delta 走低为看跌背离，
+    /// 相邻两个摆动低点中价格创新低而累计 Delta 抬高为看涨背离。
+    ///
+    /// # 返回值
+    /// 按时间升序排列的 (第二个摆动点的时间戳, 背离方向)；
+    /// 最新的 `lookback` 根K线右侧数据不足，尚不能确认摆动点
+    pub fn delta_divergences(&self, lookback: usize) -> Vec<(u64, Divergence)> {
+        let lookback = lookback.max(1);
+
+        let mut cum_delta = 0.0;
+        let bars: Vec<(u64, Price, Price, f32)> = self
+            .datapoints
+            .iter()
+            .map(|(&time, dp)| {
+                cum_delta += dp.delta_qty();
+                (time, dp.kline.high, dp.kline.low, cum_delta)
+            })
+            .collect();
+
+        let mut divergences = Vec::new();
+        let mut prev_high: Option<(Price, f32)> = None;
+        let mut prev_low: Option<(Price, f32)> = None;
+
+        for i in lookback..bars.len().saturating_sub(lookback) {
+            let (time, high, low, delta) = bars[i];
+            let (left, right) = (&bars[i - lookback..i], &bars[i + 1..=i + lookback]);
+
+            let is_swing_high =
+                left.iter().all(|b| b.1 < high) && right.iter().all(|b| b.1 <= high);
+            let is_swing_low = left.iter().all(|b| b.2 > low) && right.iter().all(|b| b.2 >= low);
+
+            if is_swing_high {
+                if let Some((prev_price, prev_delta)) = prev_high
+                    && high > prev_price
+                    && delta < prev_delta
+                {
+                    divergences.push((time, Divergence::Bearish));
+                }
+                prev_high = Some((high, delta));
+            }
+
+            if is_swing_low {
+                if let Some((prev_price, prev_delta)) = prev_low
+                    && low < prev_price
+                    && delta > prev_delta
+                {
+                    divergences.push((time, Divergence::Bullish));
+                }
+                prev_low = Some((low, delta));
+            }
+        }
+
+        divergences
+    }
+
+    /// `earliest..=latest` 内 |Delta| 最大的K线时间，Delta 全为 0 时为 None
+    ///
+    /// 传入数据点及其 Delta 而非整个序列，缩小时可以直接用更粗周期的K线；并列时取较早的K线
+    pub fn max_delta_bar<T>(
+        datapoints: &BTreeMap<u64, T>,
+        earliest: u64,
+        latest: u64,
+        delta_qty: impl Fn(&T) -> f32,
+    ) -> Option<u64> {
+        if latest < earliest {
+            return None;
+        }
+
+        datapoints
+            .range(earliest..=latest)
+            .map(|(&time, dp)| (time, delta_qty(dp).abs()))
+            .fold(None, |max: Option<(u64, f32)>, (time, delta)| match max {
+                Some((_, max_delta)) if max_delta >= delta => max,
+                _ if delta > 0.0 => Some((time, delta)),
+                _ => max,
+            })
+            .map(|(time, _)| time)
+    }
 }
 
+/// 热力图的数据点：时间基准下键为时间戳，Tick 基准下键为列序号（从最旧的列起递增）
 impl TimeSeries<HeatmapDataPoint> {
     pub fn new(basis: Basis, tick_size: PriceStep) -> Self {
         let timeframe = match basis {
             Basis::Time(interval) => interval,
-            Basis::Tick(_) => unimplemented!(),
+            // 按笔数分列时列宽由 insert_trades_by_count 的笔数决定，interval 不参与聚合
+            Basis::Tick(_) => Timeframe::MS100,
         };
 
         Self {
             datapoints: BTreeMap::new(),
             interval: timeframe,
             tick_size,
+            updated_times: FxHashSet::default(),
         }
     }
 
+    /// 按成交笔数分列：当前列满 `interval` 笔后开启新列
+    ///
+    /// 返回本次写入过的列序号范围，调用方为这些列记录同一份订单簿快照
+    pub fn insert_trades_by_count(
+        &mut self,
+        buffer: &[Trade],
+        interval: TickCount,
+        time: u64,
+    ) -> RangeInclusive<u64> {
+        let first_column = match self.datapoints.last_key_value() {
+            Some((column, _)) => *column,
+            None => {
+                self.datapoints.insert(0, HeatmapDataPoint::new(time));
+                0
+            }
+        };
+        let mut column = first_column;
+
+        for trade in buffer {
+            if self.datapoints[&column].is_full(interval) {
+                column += 1;
+                self.datapoints
+                    .insert(column, HeatmapDataPoint::new(trade.time));
+            }
+
+            if let Some(dp) = self.datapoints.get_mut(&column) {
+                dp.add_trade(trade, self.tick_size);
+            }
+        }
+
+        first_column..=column
+    }
+
+    /// 范围内单笔最大成交量与单列最大总成交量；`earliest`/`latest` 与键同义，即时间戳或列序号
     pub fn max_trade_qty_and_aggr_volume(&self, earliest: u64, latest: u64) -> (f32, f32) {
         let mut max_trade_qty = 0.0f32;
         let mut max_aggr_volume = 0.0f32;
@@ -595,3 +1059,677 @@ impl From<&TimeSeries<KlineDataPoint>> for BTreeMap<u64, (f32, f32)> {
             .collect()
     }
 }
+
+/// 计算裸露 POC 所需的快照：窗口内各K线的 POC 与按步长取整后的最低/最高价
+#[derive(Debug, Clone, Default)]
+pub struct NpocSnapshot {
+    /// (时间, POC 价格)，按时间升序
+    pocs: Vec<(u64, Price)>,
+    /// (时间, 最低价, 最高价)，按时间升序
+    ranges: Vec<(u64, Price, Price)>,
+}
+
+impl NpocSnapshot {
+    /// 快照内的K线数
+    pub fn bar_count(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// 每个 POC 的裸露/回补状态：(K线时间, POC 价格, 状态)
+    ///
+    /// 从 POC 所在K线之后向后扫描到快照末尾，第一根覆盖该价格的K线即回补时间
+    pub fn compute(&self) -> Vec<(u64, Price, NPoc)> {
+        self.pocs
+            .iter()
+            .map(|&(time, poc_price)| {
+                let mut npoc = NPoc::default();
+                let later = self.ranges.partition_point(|&(t, _, _)| t <= time);
+
+                for &(next_time, low, high) in &self.ranges[later..] {
+                    if low <= poc_price && high >= poc_price {
+                        npoc.filled(next_time);
+                        break;
+                    } else {
+                        npoc.unfilled();
+                    }
+                }
+                (time, poc_price, npoc)
+            })
+            .collect()
+    }
+}
+
+/// K线按步长向外取整后的最低/最高价，POC 落在其间即被回补
+fn npoc_range(kline: &Kline, tick_size: PriceStep) -> (Price, Price) {
+    (
+        kline.low.round_to_side_step(true, tick_size),
+        kline.high.round_to_side_step(false, tick_size),
+    )
+}
+
+/// 将K线重新采样到 `timeframe` 的时间桶
+///
+/// 同一时间桶内的K线合并为一根：取首根开盘价、末根收盘价、最高/最低价与成交量之和。
+/// 输入需按时间升序，比目标周期更粗的K线会原样保留其所在的时间桶。
+pub fn resample(klines: &[Kline], timeframe: Timeframe) -> Vec<Kline> {
+    let interval = timeframe.to_milliseconds();
+    if interval == 0 {
+        return klines.to_vec();
+    }
+
+    let mut resampled: Vec<Kline> = Vec::with_capacity(klines.len());
+
+    for kline in klines {
+        let bucket = (kline.time / interval) * interval;
+
+        match resampled.last_mut() {
+            Some(last) if last.time == bucket => {
+                last.high = last.high.max(kline.high);
+                last.low = last.low.min(kline.low);
+                last.close = kline.close;
+                last.volume.0 += kline.volume.0;
+                last.volume.1 += kline.volume.1;
+            }
+            _ => resampled.push(Kline {
+                time: bucket,
+                ..*kline
+            }),
+        }
+    }
+
+    resampled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// K线序列：(最高价, 最低价, 主动买量, 主动卖量)，每根相隔一个周期
+    fn series(bars: &[(f32, f32, f32, f32)]) -> TimeSeries<KlineDataPoint> {
+        let interval = Timeframe::M5.to_milliseconds();
+        let klines: Vec<Kline> = bars
+            .iter()
+            .enumerate()
+            .map(|(i, &(high, low, buy, sell))| Kline {
+                time: i as u64 * interval,
+                open: Price::from_f32(low),
+                high: Price::from_f32(high),
+                low: Price::from_f32(low),
+                close: Price::from_f32(high),
+                volume: (buy, sell),
+            })
+            .collect();
+
+        TimeSeries::<KlineDataPoint>::new(Timeframe::M5, PriceStep::from_f32_lossy(1.0), &klines)
+    }
+
+    #[test]
+    fn max_delta_bar_picks_largest_absolute_delta() {
+        let interval = Timeframe::M5.to_milliseconds();
+        // 第2根卖压最大，第3根未拆分买卖不计
+        let ts = series(&[
+            (10.0, 9.0, 6.0, 2.0),
+            (10.0, 9.0, 1.0, 8.0),
+            (10.0, 9.0, 8.0, 1.0),
+            (10.0, 9.0, -1.0, 50.0),
+        ]);
+
+        let max_delta_bar = |earliest, latest| {
+            TimeSeries::max_delta_bar(&ts.datapoints, earliest, latest, KlineDataPoint::delta_qty)
+        };
+
+        // 并列时取较早的第1根
+        assert_eq!(max_delta_bar(0, 3 * interval), Some(interval));
+        assert_eq!(max_delta_bar(0, 0), Some(0));
+        assert_eq!(max_delta_bar(3 * interval, 3 * interval), None);
+        assert_eq!(max_delta_bar(interval, 0), None);
+    }
+
+    #[test]
+    fn kline_volume_spread_over_range() {
+        // 第1根 10~12 共6，每档2；第2根未拆分买卖，卖量4即总量，10~11 每档2
+        let ts = series(&[(12.0, 10.0, 3.0, 3.0), (11.0, 10.0, -1.0, 4.0)]);
+        let interval = Timeframe::M5.to_milliseconds();
+        let at = |price: f32| Price::from_f32(price);
+
+        let profile = ts.kline_volume_profile(0, interval);
+        assert_eq!(
+            profile.into_iter().collect::<Vec<_>>(),
+            vec![(at(10.0), 4.0), (at(11.0), 4.0), (at(12.0), 2.0)]
+        );
+
+        // 只统计窗口内的K线
+        let profile = ts.kline_volume_profile(interval, interval);
+        assert_eq!(
+            profile.into_iter().collect::<Vec<_>>(),
+            vec![(at(10.0), 2.0), (at(11.0), 2.0)]
+        );
+        assert!(ts.kline_volume_profile(interval, 0).is_empty());
+    }
+
+    #[test]
+    fn bearish_divergence_on_higher_high() {
+        // 摆动高点在第2根(15)与第6根(17)，累计Delta从30降到20
+        let ts = series(&[
+            (10.0, 9.0, 10.0, 0.0),
+            (11.0, 10.0, 10.0, 0.0),
+            (15.0, 14.0, 10.0, 0.0),
+            (11.0, 10.0, 0.0, 10.0),
+            (10.0, 9.5, 0.0, 10.0),
+            (12.0, 11.0, 5.0, 0.0),
+            (17.0, 16.0, 5.0, 0.0),
+            (12.0, 11.0, 0.0, 0.0),
+            (10.0, 9.6, 0.0, 0.0),
+        ]);
+
+        let interval = Timeframe::M5.to_milliseconds();
+        assert_eq!(
+            ts.delta_divergences(2),
+            vec![(6 * interval, Divergence::Bearish)]
+        );
+    }
+
+    #[test]
+    fn bullish_divergence_on_lower_low() {
+        // 摆动低点在第2根(5)与第6根(3)，累计Delta从-30回升到-20
+        let ts = series(&[
+            (11.0, 10.0, 0.0, 10.0),
+            (10.0, 9.0, 0.0, 10.0),
+            (6.0, 5.0, 0.0, 10.0),
+            (10.0, 9.0, 10.0, 0.0),
+            (10.5, 10.0, 10.0, 0.0),
+            (9.0, 8.0, 0.0, 5.0),
+            (4.0, 3.0, 0.0, 5.0),
+            (9.0, 8.0, 0.0, 0.0),
+            (10.4, 10.0, 0.0, 0.0),
+        ]);
+
+        let interval = Timeframe::M5.to_milliseconds();
+        assert_eq!(
+            ts.delta_divergences(2),
+            vec![(6 * interval, Divergence::Bullish)]
+        );
+    }
+
+    #[test]
+    fn no_divergence_when_delta_confirms() {
+        let ts = series(&[
+            (10.0, 9.0, 10.0, 0.0),
+            (11.0, 10.0, 10.0, 0.0),
+            (15.0, 14.0, 10.0, 0.0),
+            (11.0, 10.0, 0.0, 5.0),
+            (10.0, 9.5, 0.0, 5.0),
+            (12.0, 11.0, 10.0, 0.0),
+            (17.0, 16.0, 10.0, 0.0),
+            (12.0, 11.0, 0.0, 0.0),
+            (10.0, 9.6, 0.0, 0.0),
+        ]);
+
+        assert!(ts.delta_divergences(2).is_empty());
+    }
+
+    /// K线序列：(最高价, 最低价, POC价格)，每根K线在POC价格上成交一笔
+    fn poc_series(bars: &[(f32, f32, f32)]) -> TimeSeries<KlineDataPoint> {
+        let interval = Timeframe::M5.to_milliseconds();
+        let klines: Vec<Kline> = bars
+            .iter()
+            .enumerate()
+            .map(|(i, &(high, low, _))| Kline {
+                time: i as u64 * interval,
+                open: Price::from_f32(low),
+                high: Price::from_f32(high),
+                low: Price::from_f32(low),
+                close: Price::from_f32(high),
+                volume: (1.0, 0.0),
+            })
+            .collect();
+        let trades: Vec<Trade> = bars
+            .iter()
+            .enumerate()
+            .map(|(i, &(_, _, poc))| Trade {
+                time: i as u64 * interval,
+                is_sell: false,
+                price: Price::from_f32(poc),
+                qty: 1.0,
+            })
+            .collect();
+
+        let mut ts = TimeSeries::<KlineDataPoint>::new(
+            Timeframe::M5,
+            PriceStep::from_f32_lossy(1.0),
+            &klines,
+        );
+        ts.insert_trades_existing_buckets(&trades);
+
+        let (earliest, latest) = ts.timerange();
+        ts.update_poc_status(earliest, latest);
+        ts
+    }
+
+    fn poc_status(ts: &TimeSeries<KlineDataPoint>, time: u64) -> Option<NPoc> {
+        ts.datapoints[&time].footprint.poc.map(|poc| poc.status)
+    }
+
+    #[test]
+    fn poc_history_follows_bar_pocs() {
+        let ts = poc_series(&[(101.0, 99.0, 100.0), (104.0, 102.0, 103.0)]);
+
+        let interval = Timeframe::M5.to_milliseconds();
+        let history: Vec<(u64, f32)> = ts
+            .poc_history()
+            .into_iter()
+            .map(|(time, price)| (time, price.to_f32()))
+            .collect();
+        assert_eq!(history, vec![(0, 100.0), (interval, 103.0)]);
+    }
+
+    #[test]
+    fn naked_poc_stays_naked_until_covered() {
+        let interval = Timeframe::M5.to_milliseconds();
+        let bars = [
+            (101.0, 99.0, 100.0),
+            (104.0, 102.0, 103.0),
+            (105.0, 103.0, 104.0),
+            (104.0, 99.0, 101.0),
+        ];
+
+        // 第1、2根都没有回到100，第0根的POC保持裸露
+        let ts = poc_series(&bars[..3]);
+        assert_eq!(poc_status(&ts, 0), Some(NPoc::Naked));
+        assert_eq!(
+            poc_status(&ts, interval),
+            Some(NPoc::Filled { at: 2 * interval })
+        );
+
+        let ts = poc_series(&bars);
+        assert_eq!(poc_status(&ts, 0), Some(NPoc::Filled { at: 3 * interval }));
+    }
+
+    #[test]
+    fn npoc_snapshot_skips_bars_whose_poc_moved() {
+        let interval = Timeframe::M5.to_milliseconds();
+        let mut ts = poc_series(&[
+            (101.0, 99.0, 100.0),
+            (104.0, 102.0, 103.0),
+            (105.0, 103.0, 104.0),
+            (104.0, 99.0, 101.0),
+        ]);
+
+        let statuses = ts.npoc_snapshot(0, 2 * interval).compute();
+        assert_eq!(statuses[0], (0, Price::from_f32(100.0), NPoc::Naked));
+
+        // 计算期间第0根的POC移到了101，旧结果不写回这根K线
+        ts.insert_trades_existing_buckets(&[Trade {
+            time: 1,
+            is_sell: false,
+            price: Price::from_f32(101.0),
+            qty: 5.0,
+        }]);
+        ts.apply_poc_status(&statuses);
+
+        assert_eq!(poc_status(&ts, 0), Some(NPoc::None));
+        assert_eq!(
+            poc_status(&ts, interval),
+            Some(NPoc::Filled { at: 2 * interval })
+        );
+    }
+
+    #[test]
+    fn poc_status_only_scans_window() {
+        let interval = Timeframe::M5.to_milliseconds();
+        let mut ts = poc_series(&[
+            (101.0, 99.0, 100.0),
+            (104.0, 102.0, 103.0),
+            (105.0, 103.0, 104.0),
+            (104.0, 99.0, 101.0),
+        ]);
+
+        // 第3根回补了第0根的POC，但窗口止于第2根
+        ts.update_poc_status(0, 2 * interval);
+        assert_eq!(poc_status(&ts, 0), Some(NPoc::Naked));
+        assert_eq!(
+            poc_status(&ts, interval),
+            Some(NPoc::Filled { at: 2 * interval })
+        );
+
+        // 窗口外的第0根保持不变
+        ts.update_poc_status(interval, 3 * interval);
+        assert_eq!(poc_status(&ts, 0), Some(NPoc::Naked));
+    }
+
+    #[test]
+    fn kline_update_matches_full_recompute() {
+        let interval = Timeframe::M5.to_milliseconds();
+        let bars = [
+            (101.0, 99.0, 100.0),
+            (104.0, 102.0, 103.0),
+            (105.0, 103.0, 104.0),
+            (106.0, 104.0, 105.0),
+        ];
+        let kline = |time: u64, high: f32, low: f32| Kline {
+            time,
+            open: Price::from_f32(low),
+            high: Price::from_f32(high),
+            low: Price::from_f32(low),
+            close: Price::from_f32(high),
+            volume: (1.0, 0.0),
+        };
+
+        let updates = [
+            // 最新K线下探回补第0、1根的POC
+            kline(3 * interval, 106.0, 99.0),
+            // 又缩回，原本由它回补的POC重新变为裸露
+            kline(3 * interval, 106.0, 104.0),
+            // 新K线
+            kline(4 * interval, 103.0, 100.0),
+        ];
+
+        let mut incremental = poc_series(&bars);
+        let mut full = poc_series(&bars);
+
+        // 成交改变 POC 后状态被重置为 None
+        for ts in [&mut incremental, &mut full] {
+            ts.insert_trades_existing_buckets(&[Trade {
+                time: interval + 1,
+                is_sell: false,
+                price: Price::from_f32(102.0),
+                qty: 5.0,
+            }]);
+        }
+        assert_eq!(poc_status(&incremental, interval), Some(NPoc::None));
+
+        for update in &updates {
+            incremental.update_kline(update);
+            full.insert_klines(&[*update]);
+
+            for time in full.datapoints.keys() {
+                assert_eq!(
+                    poc_status(&incremental, *time),
+                    poc_status(&full, *time),
+                    "bar {time} after updating {}",
+                    update.time
+                );
+            }
+        }
+        assert_eq!(
+            poc_status(&full, 0),
+            Some(NPoc::Filled { at: 4 * interval })
+        );
+        assert_eq!(
+            poc_status(&full, interval),
+            Some(NPoc::Filled { at: 4 * interval })
+        );
+    }
+
+    #[test]
+    fn pruning_keeps_cap_and_visible_bars() {
+        let times = (0..120).map(|i| i * 60_000).collect::<Vec<_>>();
+        let mut ts = keyed_series(Timeframe::M1, &times);
+
+        // within the slack, nothing goes
+        assert_eq!(ts.prune_oldest(110, u64::MAX), None);
+
+        assert_eq!(ts.prune_oldest(100, u64::MAX), Some(20 * 60_000));
+        assert_eq!(ts.datapoints.len(), 100);
+
+        // bars from `keep_from` on stay even past the cap
+        let mut ts = keyed_series(Timeframe::M1, &times);
+        assert_eq!(ts.prune_oldest(50, 10 * 60_000), Some(10 * 60_000));
+        assert_eq!(ts.datapoints.len(), 110);
+        assert_eq!(ts.prune_oldest(50, 0), None);
+    }
+
+    #[test]
+    fn volume_profile_merges_bars_in_range() {
+        let interval = Timeframe::M5.to_milliseconds();
+        let ts = poc_series(&[
+            (101.0, 99.0, 100.0),
+            (104.0, 102.0, 103.0),
+            (101.0, 99.0, 100.0),
+            (105.0, 103.0, 104.0),
+        ]);
+
+        let level = |price: f32| Price::from_f32(price).round_to_step(ts.tick_size);
+
+        let profile = ts.volume_profile(0, 2 * interval);
+        assert_eq!(profile.len(), 2);
+        assert_eq!(profile[&level(100.0)], (2.0, 0.0));
+        assert_eq!(profile[&level(103.0)], (1.0, 0.0));
+
+        assert!(ts.volume_profile(2 * interval, interval).is_empty());
+    }
+
+    fn keyed_series(timeframe: Timeframe, times: &[u64]) -> TimeSeries<KlineDataPoint> {
+        let klines: Vec<Kline> = times
+            .iter()
+            .map(|&time| Kline {
+                time,
+                open: Price::from_f32(1.0),
+                high: Price::from_f32(1.0),
+                low: Price::from_f32(1.0),
+                close: Price::from_f32(1.0),
+                volume: (0.0, 0.0),
+            })
+            .collect();
+
+        TimeSeries::<KlineDataPoint>::new(timeframe, PriceStep::from_f32_lossy(1.0), &klines)
+    }
+
+    #[test]
+    fn daily_boundaries_on_first_bar_of_each_day() {
+        let (hour, day) = (60 * 60 * 1000, 24 * 60 * 60 * 1000);
+
+        // 第1天缺了 00:00 的K线，边界落在当天第一根
+        let mut times: Vec<u64> = (0..6).map(|i| i * 4 * hour).collect();
+        times.extend([day + 8 * hour, day + 12 * hour, 2 * day]);
+        let ts = keyed_series(Timeframe::H4, &times);
+
+        let boundaries: Vec<u64> = ts
+            .session_boundaries(SessionSeparator::Daily, UserTimezone::Utc)
+            .collect();
+        assert_eq!(boundaries, vec![day + 8 * hour, 2 * day]);
+
+        assert_eq!(
+            ts.session_boundaries(SessionSeparator::Off, UserTimezone::Utc)
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn weekly_boundaries_start_on_monday() {
+        let day = 24 * 60 * 60 * 1000;
+
+        // 1970-01-01 是周四，之后的周一为第4天和第11天
+        let times: Vec<u64> = (0..14).map(|i| i * day).collect();
+        let ts = keyed_series(Timeframe::D1, &times);
+
+        let boundaries: Vec<u64> = ts
+            .session_boundaries(SessionSeparator::Weekly, UserTimezone::Utc)
+            .collect();
+        assert_eq!(boundaries, vec![4 * day, 11 * day]);
+
+        // 日线上每根都是新的一天，不画日分隔
+        assert_eq!(
+            ts.session_boundaries(SessionSeparator::Daily, UserTimezone::Utc)
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn daily_boundaries_follow_fixed_offset() {
+        let hour = 60 * 60 * 1000;
+
+        // UTC+09:00 的午夜是 UTC 15:00
+        let times: Vec<u64> = (0..12).map(|i| i * 4 * hour).collect();
+        let ts = keyed_series(Timeframe::H4, &times);
+
+        let boundaries: Vec<u64> = ts
+            .session_boundaries(SessionSeparator::Daily, UserTimezone::Fixed(9 * 60))
+            .collect();
+        assert_eq!(boundaries, vec![16 * hour, 40 * hour]);
+    }
+
+    #[test]
+    fn gap_ranges_merge_adjacent_missing_klines() {
+        let hour = 60 * 60 * 1000;
+
+        // 缺了第2、3根和第6根
+        let times: Vec<u64> = [0, 1, 4, 5, 7].iter().map(|i| i * hour).collect();
+        let ts = keyed_series(Timeframe::H1, &times);
+
+        assert_eq!(
+            ts.gap_ranges(0, 8 * hour, hour),
+            vec![(2 * hour, 3 * hour), (6 * hour, 6 * hour)]
+        );
+        assert!(ts.gap_ranges(0, 2 * hour, hour).is_empty());
+    }
+
+    #[test]
+    fn tick_columns_close_after_tick_count() {
+        let mut ts = TimeSeries::<HeatmapDataPoint>::new(
+            Basis::Tick(TickCount(2)),
+            PriceStep::from_f32_lossy(1.0),
+        );
+
+        let columns = ts.insert_trades_by_count(
+            &[
+                trade_at(1_000, 100.0, 1.0, false),
+                trade_at(1_100, 101.0, 4.0, true),
+                trade_at(1_200, 102.0, 2.0, false),
+            ],
+            TickCount(2),
+            1_000,
+        );
+        assert_eq!(columns, 0..=1);
+
+        // 第二列尚未满，下一批先补满它再开新列
+        let columns = ts.insert_trades_by_count(
+            &[
+                trade_at(1_300, 102.0, 3.0, false),
+                trade_at(1_400, 103.0, 1.0, true),
+            ],
+            TickCount(2),
+            1_300,
+        );
+        assert_eq!(columns, 1..=2);
+
+        let keys: Vec<u64> = ts.datapoints.keys().copied().collect();
+        assert_eq!(keys, vec![0, 1, 2]);
+        assert_eq!(ts.datapoints[&1].start_time, 1_200);
+        assert!(ts.datapoints.values().all(|dp| dp.tick_count <= 2));
+    }
+
+    #[test]
+    fn max_qty_over_tick_columns() {
+        let mut ts = TimeSeries::<HeatmapDataPoint>::new(
+            Basis::Tick(TickCount(2)),
+            PriceStep::from_f32_lossy(1.0),
+        );
+
+        ts.insert_trades_by_count(
+            &[
+                trade_at(1_000, 100.0, 1.0, false),
+                trade_at(1_100, 101.0, 4.0, true),
+                trade_at(1_200, 102.0, 2.0, false),
+                trade_at(1_300, 104.0, 3.0, false),
+                trade_at(1_400, 103.0, 9.0, true),
+            ],
+            TickCount(2),
+            1_000,
+        );
+
+        // 单笔最大为第 0 列的 4，两列的总成交量都是 5
+        assert_eq!(ts.max_trade_qty_and_aggr_volume(0, 1), (4.0, 5.0));
+        assert_eq!(ts.max_trade_qty_and_aggr_volume(2, 2), (9.0, 9.0));
+    }
+
+    fn trade_at(time: u64, price: f32, qty: f32, is_sell: bool) -> Trade {
+        Trade {
+            time,
+            is_sell,
+            price: Price::from_f32(price),
+            qty,
+        }
+    }
+
+    #[test]
+    fn trades_round_into_second_buckets() {
+        let mut ts =
+            TimeSeries::<KlineDataPoint>::new(Timeframe::S5, PriceStep::from_f32_lossy(1.0), &[]);
+
+        // 4.999s 仍属于第一个5秒桶，5.000s 开始下一个
+        ts.insert_trades_or_create_bucket(&[
+            trade_at(1_000, 100.0, 1.0, false),
+            trade_at(4_999, 101.0, 1.0, false),
+            trade_at(5_000, 102.0, 1.0, true),
+            trade_at(14_999, 103.0, 1.0, true),
+        ]);
+
+        let keys: Vec<u64> = ts.datapoints.keys().copied().collect();
+        assert_eq!(keys, vec![0, 5_000, 10_000]);
+        assert_eq!(ts.datapoints[&0].footprint.trades.len(), 2);
+    }
+
+    #[test]
+    fn second_klines_built_from_trades() {
+        let mut ts = TimeSeries::<KlineDataPoint>::new(
+            Timeframe::MS1000,
+            PriceStep::from_f32_lossy(1.0),
+            &[],
+        );
+
+        let klines = ts.insert_trades_as_klines(&[
+            trade_at(2_100, 100.0, 1.0, false),
+            trade_at(2_400, 103.0, 2.0, false),
+            trade_at(2_700, 99.0, 0.5, true),
+            trade_at(2_900, 101.0, 1.0, true),
+            trade_at(3_050, 102.0, 1.0, false),
+        ]);
+
+        assert_eq!(klines.len(), 2);
+        let first = klines[0];
+        assert_eq!(first.time, 2_000);
+        assert_eq!(
+            (first.open, first.high, first.low, first.close),
+            (
+                Price::from_f32(100.0),
+                Price::from_f32(103.0),
+                Price::from_f32(99.0),
+                Price::from_f32(101.0)
+            )
+        );
+        assert_eq!(first.volume, (3.0, 1.5));
+        assert_eq!(klines[1].time, 3_000);
+
+        // 后续成交继续更新已有的K线
+        ts.insert_trades_as_klines(&[trade_at(3_500, 104.0, 1.0, false)]);
+        let latest = ts.latest_kline().unwrap();
+        assert_eq!(latest.high, Price::from_f32(104.0));
+        assert_eq!(latest.volume, (2.0, 0.0));
+    }
+
+    #[test]
+    fn liquidations_add_up_per_price_level() {
+        let mut ts = series(&[(12.0, 10.0, 3.0, 3.0)]);
+        let liquidation = |time: u64, price: f32, qty: f32| Liquidation {
+            time,
+            is_sell: true,
+            price: Price::from_f32(price),
+            qty,
+        };
+
+        // 第二笔四舍五入到同一档位；第三笔所在时间桶没有K线
+        ts.insert_liquidations(&[
+            liquidation(1_000, 11.0, 2.0),
+            liquidation(2_000, 11.2, 0.5),
+            liquidation(Timeframe::M5.to_milliseconds(), 11.0, 4.0),
+        ]);
+
+        let footprint = &ts.datapoints[&0].footprint;
+        assert_eq!(footprint.liquidations.len(), 1);
+        assert_eq!(footprint.liquidations[&Price::from_f32(11.0)], 2.5);
+        assert_eq!(ts.datapoints.len(), 1);
+    }
+}
@@ -3,6 +3,14 @@ use exchange::SerTicker;
 
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Folder in the data path scanned for user-supplied WAV files
+pub const SOUNDS_DIR: &str = "sounds";
+
+/// How many successive alerts a ramp down fades out over, the last one being silent
+pub const RAMP_DOWN_STEPS: usize = 5;
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize)]
 pub enum Threshold {
@@ -23,6 +31,45 @@ impl std::fmt::Display for Threshold {
 pub struct StreamCfg {
     pub enabled: bool,
     pub threshold: Threshold,
+    /// Notional value (price × qty) a single trade must exceed to play the large trade alert,
+    /// zero disables it
+    #[serde(default)]
+    pub large_trade_threshold: f32,
+    /// Minimum time between two trade alerts of the stream, in milliseconds
+    #[serde(default = "default_cooldown_ms")]
+    pub cooldown_ms: u64,
+    /// Play alerts within the cooldown too, each quieter than the one before
+    #[serde(default)]
+    pub ramp_down: bool,
+}
+
+fn default_cooldown_ms() -> u64 {
+    200
+}
+
+impl StreamCfg {
+    pub fn is_large_trade(&self, notional: f32) -> bool {
+        self.large_trade_threshold > 0.0 && notional > self.large_trade_threshold
+    }
+
+    /// Volume factor for an alert `elapsed` after the last played one, along with
+    /// its position in the ongoing burst. `None` if it falls within the cooldown and
+    /// either ramping down is off or the burst already faded out
+    pub fn cooldown_gain(&self, elapsed: Option<Duration>, burst: usize) -> Option<(f32, usize)> {
+        let within_cooldown =
+            elapsed.is_some_and(|elapsed| elapsed < Duration::from_millis(self.cooldown_ms));
+
+        if !within_cooldown {
+            return Some((1.0, 0));
+        }
+
+        let next = burst + 1;
+        if !self.ramp_down || next >= RAMP_DOWN_STEPS {
+            return None;
+        }
+
+        Some((1.0 - next as f32 / RAMP_DOWN_STEPS as f32, next))
+    }
 }
 
 impl Default for StreamCfg {
@@ -30,6 +77,9 @@ impl Default for StreamCfg {
         StreamCfg {
             enabled: true,
             threshold: Threshold::Count(10),
+            large_trade_threshold: 0.0,
+            cooldown_ms: default_cooldown_ms(),
+            ramp_down: false,
         }
     }
 }
@@ -41,4 +91,171 @@ pub struct AudioStream {
     pub streams: FxHashMap<SerTicker, StreamCfg>,
     #[serde(deserialize_with = "ok_or_default")]
     pub volume: Option<f32>,
+    #[serde(deserialize_with = "ok_or_default")]
+    pub alert_sounds: AlertSounds,
+}
+
+/// Alerts that play a sound, each with its own choice of sound
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertKind {
+    Trade,
+    LargeTrade,
+    PriceAlert,
+}
+
+impl AlertKind {
+    pub const ALL: [AlertKind; 3] = [
+        AlertKind::Trade,
+        AlertKind::LargeTrade,
+        AlertKind::PriceAlert,
+    ];
+}
+
+impl std::fmt::Display for AlertKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AlertKind::Trade => write!(f, "Trade alert"),
+            AlertKind::LargeTrade => write!(f, "Large trade"),
+            AlertKind::PriceAlert => write!(f, "Price alert"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub enum AlertSound {
+    /// The bundled sounds, distinct for buys and sells
+    #[default]
+    BuiltIn,
+    /// File name of a WAV in the sounds folder
+    Custom(String),
+}
+
+impl std::fmt::Display for AlertSound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AlertSound::BuiltIn => write!(f, "Built-in"),
+            AlertSound::Custom(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AlertSounds {
+    pub trade: AlertSound,
+    pub large_trade: AlertSound,
+    pub price_alert: AlertSound,
+}
+
+impl AlertSounds {
+    pub fn get(&self, kind: AlertKind) -> &AlertSound {
+        match kind {
+            AlertKind::Trade => &self.trade,
+            AlertKind::LargeTrade => &self.large_trade,
+            AlertKind::PriceAlert => &self.price_alert,
+        }
+    }
+
+    pub fn set(&mut self, kind: AlertKind, sound: AlertSound) {
+        match kind {
+            AlertKind::Trade => self.trade = sound,
+            AlertKind::LargeTrade => self.large_trade = sound,
+            AlertKind::PriceAlert => self.price_alert = sound,
+        }
+    }
+}
+
+pub fn custom_sound_path(name: &str) -> PathBuf {
+    crate::data_path(None).join(SOUNDS_DIR).join(name)
+}
+
+/// File names of the WAVs in the sounds folder, sorted
+pub fn list_custom_sounds() -> std::io::Result<Vec<String>> {
+    list_custom_sounds_in(&crate::data_path(None).join(SOUNDS_DIR))
+}
+
+fn list_custom_sounds_in(dir: &Path) -> std::io::Result<Vec<String>> {
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut sounds = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_wav = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("wav"));
+
+        if is_wav && let Some(name) = path.file_name().and_then(|name| name.to_str()) {
+            sounds.push(name.to_string());
+        }
+    }
+
+    sounds.sort();
+    Ok(sounds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_only_wav_files_sorted() {
+        let dir = std::env::temp_dir().join(format!("flowsurface-sounds-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert!(list_custom_sounds_in(&dir).unwrap().is_empty());
+
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["ping.wav", "Alarm.WAV", "notes.txt"] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+
+        assert_eq!(
+            list_custom_sounds_in(&dir).unwrap(),
+            vec!["Alarm.WAV".to_string(), "ping.wav".to_string()]
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn config_without_alert_sounds_uses_built_in() {
+        let cfg: AudioStream = serde_json::from_str(r#"{"streams":{},"volume":50.0}"#).unwrap();
+
+        assert_eq!(
+            cfg.alert_sounds.get(AlertKind::PriceAlert),
+            &AlertSound::BuiltIn
+        );
+    }
+
+    #[test]
+    fn cooldown_skips_or_ramps_down_bursts() {
+        let within = Some(Duration::from_millis(50));
+        let after = Some(Duration::from_millis(250));
+
+        let cfg: StreamCfg =
+            serde_json::from_str(r#"{"enabled":true,"threshold":{"Count":10}}"#).unwrap();
+        assert_eq!(cfg.cooldown_ms, 200);
+        assert!(!cfg.ramp_down);
+
+        assert_eq!(cfg.cooldown_gain(None, 0), Some((1.0, 0)));
+        assert_eq!(cfg.cooldown_gain(within, 0), None);
+        assert_eq!(cfg.cooldown_gain(after, 3), Some((1.0, 0)));
+
+        let ramp = StreamCfg {
+            ramp_down: true,
+            ..cfg
+        };
+        let mut burst = 0;
+        let mut gains = vec![];
+        while let Some((gain, next)) = ramp.cooldown_gain(within, burst) {
+            gains.push(gain);
+            burst = next;
+        }
+        assert_eq!(gains.len(), RAMP_DOWN_STEPS - 1);
+        for (gain, expected) in gains.iter().zip([0.8, 0.6, 0.4, 0.2]) {
+            assert!((gain - expected).abs() < f32::EPSILON);
+        }
+    }
 }
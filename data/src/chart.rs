@@ -1,9 +1,16 @@
+pub mod annotation;
 pub mod comparison;
+pub mod correlation;
 pub mod heatmap;
 pub mod indicator;
 pub mod kline;
+pub mod range;
+pub mod spread;
+pub mod statistics;
+pub mod stats_panel;
 
-use exchange::Timeframe;
+use exchange::util::{Price, PriceStep};
+use exchange::{TickMultiplier, Timeframe};
 use serde::{Deserialize, Serialize};
 
 use super::aggr::{
@@ -30,6 +37,44 @@ impl<D: DataPoint> PlotData<D> {
         }
     }
 
+    /// Padded `(highest, lowest)` of the visible range, see `TimeSeries::price_scale_in_range`
+    pub fn visible_price_scale(
+        &self,
+        start_interval: u64,
+        end_interval: u64,
+        padding_pct: f32,
+        min_padding: Price,
+    ) -> (Price, Price) {
+        match self {
+            PlotData::TimeBased(timeseries) => timeseries.price_scale_in_range(
+                start_interval,
+                end_interval,
+                padding_pct,
+                min_padding,
+            ),
+            PlotData::TickBased(tick_aggr) => tick_aggr.price_scale_in_range(
+                start_interval as usize,
+                end_interval as usize,
+                padding_pct,
+                min_padding,
+            ),
+        }
+    }
+
+    /// Every kline held, oldest first
+    pub fn klines(&self) -> Vec<exchange::Kline> {
+        match self {
+            PlotData::TimeBased(timeseries) => timeseries
+                .datapoints
+                .values()
+                .filter_map(|dp| dp.kline().copied())
+                .collect(),
+            PlotData::TickBased(tick_aggr) => {
+                tick_aggr.datapoints.iter().map(|dp| dp.kline).collect()
+            }
+        }
+    }
+
     pub fn visible_price_range(
         &self,
         start_interval: u64,
@@ -46,10 +91,76 @@ impl<D: DataPoint> PlotData<D> {
     }
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct ViewConfig {
     pub splits: Vec<f32>,
     pub autoscale: Option<Autoscale>,
+    /// How the viewport moves when a new bar opens, read from the former `follow_latest` flag
+    /// of older layouts too
+    #[serde(
+        default,
+        alias = "follow_latest",
+        deserialize_with = "deserialize_auto_scroll"
+    )]
+    pub auto_scroll: AutoScrollMode,
+    /// Keep cell width and height on resize, the number of visible bars changes instead
+    #[serde(default)]
+    pub fixed_cells: bool,
+    #[serde(default)]
+    pub price_axis: PriceAxisMode,
+    #[serde(default)]
+    pub price_rounding: PriceAxisRounding,
+}
+
+fn deserialize_auto_scroll<'de, D>(deserializer: D) -> Result<AutoScrollMode, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Stored {
+        Mode(AutoScrollMode),
+        FollowLatest(bool),
+    }
+
+    Ok(match Stored::deserialize(deserializer)? {
+        Stored::Mode(mode) => mode,
+        Stored::FollowLatest(true) => AutoScrollMode::KeepLastVisible,
+        Stored::FollowLatest(false) => AutoScrollMode::None,
+    })
+}
+
+/// What happens to the viewport when a new bar opens. Auto-scroll pauses while the latest bar
+/// is scrolled out of view and resumes once it's back.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+pub enum AutoScrollMode {
+    /// The viewport stays where it is
+    None,
+    /// The viewport moves along, keeping the bars where they were on screen
+    #[default]
+    KeepLastVisible,
+    /// The viewport jumps to leave this many empty bars right of the latest one
+    KeepMargin(usize),
+}
+
+impl AutoScrollMode {
+    pub const CHOICES: [AutoScrollMode; 5] = [
+        AutoScrollMode::None,
+        AutoScrollMode::KeepLastVisible,
+        AutoScrollMode::KeepMargin(5),
+        AutoScrollMode::KeepMargin(10),
+        AutoScrollMode::KeepMargin(20),
+    ];
+}
+
+impl std::fmt::Display for AutoScrollMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AutoScrollMode::None => write!(f, "No scroll"),
+            AutoScrollMode::KeepLastVisible => write!(f, "Follow"),
+            AutoScrollMode::KeepMargin(bars) => write!(f, "Follow +{bars}"),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq)]
@@ -59,6 +170,80 @@ pub enum Autoscale {
     FitToVisible,
 }
 
+/// What the price axis labels show
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq)]
+pub enum PriceAxisMode {
+    #[default]
+    Absolute,
+    /// Change from `anchor_price`, the close of the oldest bar visible when it was enabled
+    Percent { anchor_price: f32 },
+}
+
+impl PriceAxisMode {
+    /// Change of `price` from the anchor in percent, `None` in absolute mode
+    pub fn percent_change(&self, price: f32) -> Option<f32> {
+        match self {
+            PriceAxisMode::Absolute => None,
+            PriceAxisMode::Percent { anchor_price } if *anchor_price > f32::EPSILON => {
+                Some((price - anchor_price) / anchor_price * 100.0)
+            }
+            PriceAxisMode::Percent { .. } => None,
+        }
+    }
+}
+
+/// Which prices the price axis labels land on
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq)]
+pub enum PriceAxisRounding {
+    /// A round step near the visible range over the rows of labels that fit
+    #[default]
+    Auto,
+    /// Multiples of the step, every second or fifth one and so on when they'd overlap
+    Fixed(f32),
+}
+
+impl PriceAxisRounding {
+    /// Fixed steps worth offering, 1, 2 and 5 times the powers of ten from `tick_size` up to
+    /// a twentieth of `price`
+    pub fn choices(tick_size: f32, price: f32) -> Vec<PriceAxisRounding> {
+        let mut choices = vec![PriceAxisRounding::Auto];
+        if tick_size <= 0.0 || !price.is_finite() || price <= 0.0 {
+            return choices;
+        }
+
+        let largest = price / 20.0;
+        let mut exponent = tick_size.log10().floor() as i32;
+
+        while 10f32.powi(exponent) <= largest {
+            for multiple in [1.0, 2.0, 5.0] {
+                // in f64 so steps like 0.2 come out as the nearest f32 and print as such
+                let step = (multiple * 10f64.powi(exponent)) as f32;
+                if step >= tick_size * 0.999 && step <= largest {
+                    choices.push(PriceAxisRounding::Fixed(step));
+                }
+            }
+            exponent += 1;
+        }
+        choices
+    }
+}
+
+impl std::fmt::Display for PriceAxisRounding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PriceAxisRounding::Auto => write!(f, "Auto"),
+            PriceAxisRounding::Fixed(step) => write!(f, "Every {step}"),
+        }
+    }
+}
+
+/// Signed percent with two decimals, e.g. `+2.34%`
+pub fn format_percent(change: f32) -> String {
+    // avoids `-0.00%` for changes that round to zero
+    let change = if change.abs() < 0.005 { 0.0 } else { change };
+    format!("{change:+.2}%")
+}
+
 /// Defines how chart data is aggregated and displayed along the x-axis.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub enum Basis {
@@ -107,8 +292,315 @@ impl From<exchange::Timeframe> for Basis {
     }
 }
 
+/// Price rows an automatic footprint tick size aims for across the visible height
+pub const AUTO_TICK_TARGET_ROWS: f32 = 30.0;
+
+/// Bars a footprint chart shows when it is first laid out
+const AUTO_TICK_VISIBLE_BARS: f32 = 12.0;
+
+/// Klines as CSV with a header row, prices printed at the ticker's tick precision.
+/// `buy_volume` stays empty when the exchange doesn't split volume by side.
+pub fn klines_csv(klines: &[exchange::Kline], precision: exchange::util::MinTicksize) -> String {
+    let mut csv = String::from("time,open,high,low,close,volume,buy_volume\n");
+
+    for kline in klines {
+        let (buy, sell) = kline.volume;
+        let (volume, buy_volume) = if buy < 0.0 {
+            (sell, String::new())
+        } else {
+            (buy + sell, buy.to_string())
+        };
+
+        csv.push_str(&format!(
+            "{},{},{},{},{},{volume},{buy_volume}\n",
+            kline.time,
+            kline.open.to_string(precision),
+            kline.high.to_string(precision),
+            kline.low.to_string(precision),
+            kline.close.to_string(precision),
+        ));
+    }
+    csv
+}
+
+/// Typical price move per square root minute, relative to price
+const AUTO_TICK_VOLATILITY: f32 = 0.0008;
+
+const AUTO_TICK_MULTIPLIERS: [u16; 11] = [1, 2, 5, 10, 25, 50, 100, 200, 500, 1000, 2000];
+
+/// Suggests a footprint tick multiplier for `min_tick` so that roughly 20-40 price rows
+/// fit the price range the visible bars usually span around `base_price`.
+pub fn suggest_tick_multiplier(
+    base_price: Price,
+    min_tick: PriceStep,
+    basis: Basis,
+) -> TickMultiplier {
+    let (price, tick) = (base_price.to_f32(), min_tick.to_f32_lossy());
+
+    if price <= 0.0 || tick <= 0.0 {
+        return TickMultiplier(1);
+    }
+
+    let ideal = expected_price_range(price, basis) / (AUTO_TICK_TARGET_ROWS * tick);
+    let distance = |m: u16| (f32::from(m) / ideal).ln().abs();
+
+    AUTO_TICK_MULTIPLIERS
+        .iter()
+        .copied()
+        .min_by(|a, b| distance(*a).total_cmp(&distance(*b)))
+        .map_or(TickMultiplier(1), TickMultiplier)
+}
+
+/// The range grows with the square root of the visible duration,
+/// tick based bars are treated as if each lasted a minute
+fn expected_price_range(price: f32, basis: Basis) -> f32 {
+    let bar_minutes = match basis {
+        Basis::Time(timeframe) => timeframe.to_milliseconds() as f32 / 60_000.0,
+        Basis::Tick(_) => 1.0,
+    };
+
+    price * AUTO_TICK_VOLATILITY * (bar_minutes * AUTO_TICK_VISIBLE_BARS).sqrt()
+}
+
+/// A price/time span picked with the ruler tool.
+///
+/// The `u64` of each point is a timestamp for time based charts and a tick index otherwise.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measurement {
+    pub basis: Basis,
+    pub start: (u64, Price),
+    pub end: (u64, Price),
+}
+
+impl Measurement {
+    pub fn new(basis: Basis, start: (u64, Price), end: (u64, Price)) -> Self {
+        Self { basis, start, end }
+    }
+
+    /// Signed price change from start to end, as `(is_negative, magnitude)`
+    pub fn price_delta(&self) -> (bool, Price) {
+        let (from, to) = (self.start.1, self.end.1);
+
+        if to < from {
+            (true, from - to)
+        } else {
+            (false, to - from)
+        }
+    }
+
+    pub fn price_change_pct(&self) -> f32 {
+        let from = self.start.1.to_f32();
+
+        if from == 0.0 {
+            0.0
+        } else {
+            (self.end.1.to_f32() - from) / from * 100.0
+        }
+    }
+
+    /// Elapsed time between the two points, only known for time based charts
+    pub fn time_delta_ms(&self) -> Option<u64> {
+        match self.basis {
+            Basis::Time(_) => Some(self.start.0.abs_diff(self.end.0)),
+            Basis::Tick(_) => None,
+        }
+    }
+
+    /// Number of bars the span covers, a span within a single bar still counts as one
+    pub fn bars(&self) -> u64 {
+        let step = match self.basis {
+            Basis::Time(timeframe) => timeframe.to_milliseconds(),
+            Basis::Tick(count) => u64::from(count.0),
+        };
+
+        (self.start.0.abs_diff(self.end.0) / step.max(1)).max(1)
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub enum Study {
     Heatmap(Vec<heatmap::HeatmapStudy>),
     Footprint(Vec<kline::FootprintStudy>),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exchange::Kline;
+    use exchange::util::PriceStep;
+
+    fn kline(time: u64, close: f32) -> Kline {
+        let price = Price::from_f32(close);
+        Kline {
+            time,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume: (0.0, 0.0),
+        }
+    }
+
+    #[test]
+    fn measurement_against_timeseries() {
+        let interval = Timeframe::M5.to_milliseconds();
+        let step = PriceStep::from_f32_lossy(0.5);
+        let price = |v: f32| Price::from_f32(v).round_to_step(step);
+
+        let klines: Vec<Kline> = (0..10)
+            .map(|i| kline(i * interval, 100.0 + i as f32))
+            .collect();
+        let series = TimeSeries::<kline::KlineDataPoint>::new(Timeframe::M5, step, &klines);
+
+        let m = series.measure(
+            (interval + 1_000, Price::from_f32(101.2)),
+            (4 * interval - 1_000, Price::from_f32(103.9)),
+        );
+
+        assert_eq!(m.start, (interval, price(101.0)));
+        assert_eq!(m.end, (4 * interval, price(104.0)));
+        assert_eq!(m.bars(), 3);
+        assert_eq!(m.time_delta_ms(), Some(3 * interval));
+        assert_eq!(m.price_delta(), (false, price(104.0) - price(101.0)));
+        assert!((m.price_change_pct() - 2.970_297).abs() < 1e-3);
+    }
+
+    #[test]
+    fn measurement_downward_within_one_bar() {
+        let m = Measurement::new(
+            Basis::Time(Timeframe::M1),
+            (60_000, Price::from_f32(50.0)),
+            (60_000, Price::from_f32(40.0)),
+        );
+
+        assert_eq!(m.bars(), 1);
+        assert_eq!(
+            m.price_delta(),
+            (true, Price::from_f32(50.0) - Price::from_f32(40.0))
+        );
+        assert!((m.price_change_pct() + 20.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn auto_tick_fits_target_rows() {
+        for (price, tick, basis) in [
+            (60_000.0, 0.1, Basis::Time(Timeframe::M5)),
+            (3_000.0, 0.01, Basis::Time(Timeframe::H1)),
+            (0.5, 0.0001, Basis::Time(Timeframe::M15)),
+            (60_000.0, 0.1, Basis::Tick(aggr::TickCount(100))),
+        ] {
+            let tm = suggest_tick_multiplier(
+                Price::from_f32(price),
+                PriceStep::from_f32_lossy(tick),
+                basis,
+            );
+            let rows = expected_price_range(price, basis) / (f32::from(tm.0) * tick);
+
+            assert!((20.0..=40.0).contains(&rows), "{tm} gives {rows} rows");
+        }
+    }
+
+    #[test]
+    fn candles_kind_from_saved_state() {
+        let legacy: kline::KlineChartKind = serde_json::from_str("\"Candles\"").unwrap();
+        assert_eq!(legacy, kline::KlineChartKind::default());
+
+        let kind = kline::KlineChartKind::Candles {
+            show_volume_profile: true,
+        };
+        let json = serde_json::to_string(&kind).unwrap();
+        assert_eq!(json, r#"{"Candles":{"show_volume_profile":true}}"#);
+        assert_eq!(
+            serde_json::from_str::<kline::KlineChartKind>(&json).unwrap(),
+            kind
+        );
+    }
+
+    #[test]
+    fn klines_as_csv_rows() {
+        let mut with_sides = kline(60_000, 101.25);
+        with_sides.volume = (2.0, 1.5);
+        let mut total_only = kline(120_000, 101.5);
+        total_only.volume = (-1.0, 4.0);
+
+        let csv = klines_csv(
+            &[with_sides, total_only],
+            exchange::util::MinTicksize::new(-2),
+        );
+
+        assert_eq!(
+            csv,
+            "time,open,high,low,close,volume,buy_volume\n\
+             60000,101.25,101.25,101.25,101.25,3.5,2\n\
+             120000,101.50,101.50,101.50,101.50,4,\n"
+        );
+    }
+
+    #[test]
+    fn view_config_without_fixed_cells() {
+        let layout: ViewConfig =
+            serde_json::from_str(r#"{"splits":[0.8],"autoscale":"FitToVisible"}"#).unwrap();
+
+        assert!(!layout.fixed_cells);
+        assert_eq!(layout.auto_scroll, AutoScrollMode::KeepLastVisible);
+        assert_eq!(layout.price_axis, PriceAxisMode::Absolute);
+        assert_eq!(layout.price_rounding, PriceAxisRounding::Auto);
+    }
+
+    #[test]
+    fn auto_scroll_reads_the_former_follow_latest_flag() {
+        let layout: ViewConfig =
+            serde_json::from_str(r#"{"splits":[],"autoscale":null,"follow_latest":false}"#)
+                .unwrap();
+        assert_eq!(layout.auto_scroll, AutoScrollMode::None);
+
+        let stored = serde_json::to_string(&ViewConfig {
+            auto_scroll: AutoScrollMode::KeepMargin(10),
+            ..ViewConfig::default()
+        })
+        .unwrap();
+        let layout: ViewConfig = serde_json::from_str(&stored).unwrap();
+        assert_eq!(layout.auto_scroll, AutoScrollMode::KeepMargin(10));
+    }
+
+    #[test]
+    fn price_rounding_choices_span_tick_to_price() {
+        let choices = PriceAxisRounding::choices(0.1, 90_000.0);
+
+        assert_eq!(choices[0], PriceAxisRounding::Auto);
+        assert_eq!(choices[1], PriceAxisRounding::Fixed(0.1));
+        assert!(choices.contains(&PriceAxisRounding::Fixed(500.0)));
+        // a twentieth of the price is the largest step
+        assert_eq!(choices.last(), Some(&PriceAxisRounding::Fixed(2000.0)));
+
+        assert_eq!(PriceAxisRounding::Fixed(0.5).to_string(), "Every 0.5");
+        assert_eq!(
+            PriceAxisRounding::choices(0.0, 100.0),
+            [PriceAxisRounding::Auto]
+        );
+    }
+
+    #[test]
+    fn percent_axis_change_from_anchor() {
+        let mode = PriceAxisMode::Percent {
+            anchor_price: 200.0,
+        };
+
+        let label = |price: f32| mode.percent_change(price).map(format_percent);
+
+        assert_eq!(label(204.68).as_deref(), Some("+2.34%"));
+        assert_eq!(label(197.76).as_deref(), Some("-1.12%"));
+        assert_eq!(format_percent(-0.001), "+0.00%");
+        assert_eq!(PriceAxisMode::Absolute.percent_change(204.68), None);
+    }
+
+    #[test]
+    fn auto_tick_without_price() {
+        let tm = suggest_tick_multiplier(
+            Price::from_f32(0.0),
+            PriceStep::from_f32_lossy(0.1),
+            Basis::Time(Timeframe::M5),
+        );
+        assert_eq!(tm, TickMultiplier(1));
+    }
+}
@@ -0,0 +1,234 @@
+use exchange::util::Price;
+use exchange::{Kline, Ticker};
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+
+/// A horizontal level marked on a chart
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceLine {
+    pub price: Price,
+    #[serde(default)]
+    pub label: String,
+    #[serde(default)]
+    pub alert: Option<PriceAlert>,
+}
+
+impl PriceLine {
+    pub fn new(price: Price) -> Self {
+        Self {
+            price,
+            label: String::new(),
+            alert: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AlertDirection {
+    Above,
+    Below,
+}
+
+impl AlertDirection {
+    pub const ALL: [AlertDirection; 2] = [AlertDirection::Above, AlertDirection::Below];
+}
+
+impl std::fmt::Display for AlertDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AlertDirection::Above => write!(f, "Above"),
+            AlertDirection::Below => write!(f, "Below"),
+        }
+    }
+}
+
+/// Turns a price line into an alert that fires once when price crosses it
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PriceAlert {
+    pub direction: AlertDirection,
+    #[serde(default)]
+    pub triggered: bool,
+    #[serde(default)]
+    pub sound: bool,
+}
+
+impl PriceAlert {
+    pub fn new(direction: AlertDirection) -> Self {
+        Self {
+            direction,
+            triggered: false,
+            sound: false,
+        }
+    }
+
+    /// Whether price went from the armed side of `level` at `previous` to the other one at `close`
+    fn is_crossed_by(&self, level: Price, previous: Price, close: Price) -> bool {
+        match self.direction {
+            AlertDirection::Above => previous < level && close >= level,
+            AlertDirection::Below => previous > level && close <= level,
+        }
+    }
+}
+
+/// Price lines keyed by ticker, so every pane showing a ticker draws the same levels
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PriceLines(FxHashMap<Ticker, Vec<PriceLine>>);
+
+impl PriceLines {
+    pub fn get(&self, ticker: &Ticker) -> &[PriceLine] {
+        self.0.get(ticker).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn add(&mut self, ticker: Ticker, price: Price) {
+        self.0
+            .entry(ticker)
+            .or_default()
+            .push(PriceLine::new(price));
+    }
+
+    pub fn move_line(&mut self, ticker: &Ticker, index: usize, price: Price) {
+        if let Some(line) = self.line_mut(ticker, index) {
+            line.price = price;
+
+            if let Some(alert) = line.alert.as_mut() {
+                alert.triggered = false;
+            }
+        }
+    }
+
+    pub fn set_label(&mut self, ticker: &Ticker, index: usize, label: String) {
+        if let Some(line) = self.line_mut(ticker, index) {
+            line.label = label;
+        }
+    }
+
+    pub fn set_alert(&mut self, ticker: &Ticker, index: usize, alert: Option<PriceAlert>) {
+        if let Some(line) = self.line_mut(ticker, index) {
+            line.alert = alert;
+        }
+    }
+
+    /// Marks alerts crossed between `previous_close`, the close of the stream's last update,
+    /// and the kline's close as triggered and returns the lines that just fired
+    pub fn check_alerts(
+        &mut self,
+        ticker: &Ticker,
+        previous_close: Price,
+        kline: &Kline,
+    ) -> Vec<PriceLine> {
+        let Some(lines) = self.0.get_mut(ticker) else {
+            return vec![];
+        };
+
+        let mut fired = vec![];
+
+        for line in lines.iter_mut() {
+            if let Some(alert) = line.alert.as_mut()
+                && !alert.triggered
+                && alert.is_crossed_by(line.price, previous_close, kline.close)
+            {
+                alert.triggered = true;
+                fired.push(line.clone());
+            }
+        }
+
+        fired
+    }
+
+    pub fn remove(&mut self, ticker: &Ticker, index: usize) {
+        if let Some(lines) = self.0.get_mut(ticker)
+            && index < lines.len()
+        {
+            lines.remove(index);
+
+            if lines.is_empty() {
+                self.0.remove(ticker);
+            }
+        }
+    }
+
+    fn line_mut(&mut self, ticker: &Ticker, index: usize) -> Option<&mut PriceLine> {
+        self.0.get_mut(ticker)?.get_mut(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exchange::adapter::Exchange;
+
+    fn kline(high: f32, close: f32) -> Kline {
+        Kline {
+            time: 0,
+            open: Price::from_f32(close),
+            high: Price::from_f32(high),
+            low: Price::from_f32(close),
+            close: Price::from_f32(close),
+            volume: (0.0, 0.0),
+        }
+    }
+
+    fn check(lines: &mut PriceLines, ticker: &Ticker, previous: f32, close: f32) -> usize {
+        lines
+            .check_alerts(ticker, Price::from_f32(previous), &kline(close, close))
+            .len()
+    }
+
+    #[test]
+    fn alerts_fire_once_until_rearmed() {
+        let ticker = Ticker::new("BTCUSDT", Exchange::BinanceLinear);
+        let mut lines = PriceLines::default();
+
+        lines.add(ticker, Price::from_f32(110.0));
+        lines.add(ticker, Price::from_f32(90.0));
+        lines.set_alert(&ticker, 0, Some(PriceAlert::new(AlertDirection::Above)));
+        lines.set_alert(&ticker, 1, Some(PriceAlert::new(AlertDirection::Below)));
+
+        assert_eq!(check(&mut lines, &ticker, 95.0, 105.0), 0);
+
+        let fired = lines.check_alerts(&ticker, Price::from_f32(105.0), &kline(110.0, 110.0));
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].price, Price::from_f32(110.0));
+        assert_eq!(check(&mut lines, &ticker, 100.0, 120.0), 0);
+
+        lines.move_line(&ticker, 0, Price::from_f32(130.0));
+        assert!(!lines.get(&ticker)[0].alert.unwrap().triggered);
+
+        assert_eq!(check(&mut lines, &ticker, 120.0, 130.0), 1);
+        assert_eq!(check(&mut lines, &ticker, 130.0, 85.0), 1);
+    }
+
+    #[test]
+    fn alerts_armed_past_their_level_wait_for_a_cross() {
+        let ticker = Ticker::new("BTCUSDT", Exchange::BinanceLinear);
+        let mut lines = PriceLines::default();
+
+        lines.add(ticker, Price::from_f32(100.0));
+        lines.set_alert(&ticker, 0, Some(PriceAlert::new(AlertDirection::Above)));
+
+        // price is already above the level when the alert is armed
+        assert_eq!(check(&mut lines, &ticker, 125.0, 115.0), 0);
+        assert_eq!(check(&mut lines, &ticker, 115.0, 110.0), 0);
+
+        // back under it, then up through it
+        assert_eq!(check(&mut lines, &ticker, 110.0, 95.0), 0);
+        assert_eq!(check(&mut lines, &ticker, 95.0, 105.0), 1);
+    }
+
+    #[test]
+    fn stale_high_of_a_forming_kline_does_not_fire() {
+        let ticker = Ticker::new("BTCUSDT", Exchange::BinanceLinear);
+        let mut lines = PriceLines::default();
+
+        lines.add(ticker, Price::from_f32(100.0));
+        lines.set_alert(&ticker, 0, Some(PriceAlert::new(AlertDirection::Above)));
+
+        // the bar spiked to 110 and fell back to 95, then the same update comes in again
+        let forming = kline(110.0, 95.0);
+        for _ in 0..2 {
+            let fired = lines.check_alerts(&ticker, forming.close, &forming);
+            assert!(fired.is_empty());
+        }
+    }
+}
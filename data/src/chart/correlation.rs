@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::statistics::pearson_correlation;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Config {
+    /// How many of the latest closes every pair of tickers is correlated over
+    pub window_bars: usize,
+}
+
+impl Config {
+    pub const WINDOW_BARS_RANGE: std::ops::RangeInclusive<usize> = 10..=500;
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self { window_bars: 50 }
+    }
+}
+
+/// Correlation of every pair of close series over their latest `window_bars` common timestamps,
+/// `None` where a pair shares fewer than two closes or one of them doesn't move
+pub fn correlation_matrix(
+    closes: &[&BTreeMap<u64, f32>],
+    window_bars: usize,
+) -> Vec<Vec<Option<f32>>> {
+    closes
+        .iter()
+        .map(|a| {
+            closes
+                .iter()
+                .map(|b| {
+                    let (xs, ys) = latest_common_closes(a, b, window_bars);
+                    Some(pearson_correlation(&xs, &ys)).filter(|r| r.is_finite())
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn latest_common_closes(
+    a: &BTreeMap<u64, f32>,
+    b: &BTreeMap<u64, f32>,
+    window_bars: usize,
+) -> (Vec<f32>, Vec<f32>) {
+    let mut pairs: Vec<(f32, f32)> = a
+        .iter()
+        .rev()
+        .filter_map(|(time, &x)| b.get(time).map(|&y| (x, y)))
+        .take(window_bars)
+        .collect();
+    pairs.reverse();
+
+    pairs.into_iter().unzip()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn closes(points: &[(u64, f32)]) -> BTreeMap<u64, f32> {
+        points.iter().copied().collect()
+    }
+
+    #[test]
+    fn matrix_pairs_closes_by_timestamp() {
+        let a = closes(&[(1, 1.0), (2, 2.0), (3, 3.0), (4, 4.0)]);
+        // 2 is missing, the common timestamps 1, 3 and 4 move together
+        let b = closes(&[(1, 10.0), (3, 30.0), (4, 40.0), (5, 0.0)]);
+        let flat = closes(&[(1, 5.0), (2, 5.0), (3, 5.0)]);
+
+        let matrix = correlation_matrix(&[&a, &b, &flat], 50);
+
+        assert!((matrix[0][1].unwrap() - 1.0).abs() < 1e-6);
+        assert_eq!(matrix[0][1], matrix[1][0]);
+        assert_eq!(matrix[2][0], None);
+        assert_eq!(matrix[2][2], None);
+    }
+
+    #[test]
+    fn matrix_only_uses_latest_window() {
+        // moves together early on, then in opposite directions for the last 3 bars
+        let a = closes(&[(1, 1.0), (2, 2.0), (3, 3.0), (4, 4.0), (5, 5.0), (6, 6.0)]);
+        let b = closes(&[(1, 1.0), (2, 2.0), (3, 3.0), (4, 9.0), (5, 8.0), (6, 7.0)]);
+
+        let matrix = correlation_matrix(&[&a, &b], 3);
+        assert!((matrix[0][1].unwrap() + 1.0).abs() < 1e-6);
+    }
+}
@@ -1,5 +1,5 @@
 use super::Basis;
-use super::aggr::time::DataPoint;
+use super::aggr::{TickCount, time::DataPoint};
 use exchange::util::{Price, PriceStep};
 use exchange::{adapter::MarketKind, depth::Depth, volume_size_unit};
 
@@ -35,6 +35,25 @@ impl Default for Config {
 pub struct HeatmapDataPoint {
     pub grouped_trades: Box<[GroupedTrade]>,
     pub buy_sell: (f32, f32),
+    /// Trades added so far, a tick basis column is closed once it reaches the tick count
+    pub tick_count: usize,
+    /// Time the column was opened at
+    pub start_time: u64,
+}
+
+impl HeatmapDataPoint {
+    pub fn new(start_time: u64) -> Self {
+        Self {
+            grouped_trades: Box::new([]),
+            buy_sell: (0.0, 0.0),
+            tick_count: 0,
+            start_time,
+        }
+    }
+
+    pub fn is_full(&self, interval: TickCount) -> bool {
+        self.tick_count >= interval.0 as usize
+    }
 }
 
 impl DataPoint for HeatmapDataPoint {
@@ -65,11 +84,13 @@ impl DataPoint for HeatmapDataPoint {
         } else {
             self.buy_sell.0 += trade.qty;
         }
+        self.tick_count += 1;
     }
 
     fn clear_trades(&mut self) {
         self.grouped_trades = Box::new([]);
         self.buy_sell = (0.0, 0.0);
+        self.tick_count = 0;
     }
 
     fn last_trade_time(&self) -> Option<u64> {
@@ -138,22 +159,28 @@ impl OrderRun {
     }
 }
 
+/// Order runs per price level, timed in milliseconds or in column indexes on tick basis
 #[derive(Default, Debug, Clone, PartialEq)]
 pub struct HistoricalDepth {
     price_levels: BTreeMap<Price, Vec<OrderRun>>,
     aggr_time: u64,
+    grace_period: u64,
     tick_size: PriceStep,
     min_order_qty: f32,
 }
 
 impl HistoricalDepth {
     pub fn new(min_order_qty: f32, tick_size: PriceStep, basis: Basis) -> Self {
+        // every tick column gets a depth snapshot, so runs only continue into the next column
+        let (aggr_time, grace_period) = match basis {
+            Basis::Time(interval) => (interval.into(), GRACE_PERIOD_MS),
+            Basis::Tick(_) => (1, 0),
+        };
+
         Self {
             price_levels: BTreeMap::new(),
-            aggr_time: match basis {
-                Basis::Time(interval) => interval.into(),
-                Basis::Tick(_) => unimplemented!(),
-            },
+            aggr_time,
+            grace_period,
             tick_size,
             min_order_qty,
         }
@@ -194,7 +221,7 @@ impl HistoricalDepth {
 
         match price_level.last_mut() {
             Some(last_run) if last_run.is_bid == is_bid => {
-                if time > last_run.until_time + GRACE_PERIOD_MS {
+                if time > last_run.until_time + self.grace_period {
                     price_level.push(OrderRun::new(time, aggr_time, qty, is_bid));
                     return;
                 }
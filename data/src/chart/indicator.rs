@@ -1,9 +1,13 @@
+use std::collections::{BTreeMap, VecDeque};
 use std::fmt::{self, Debug, Display};
 
 use enum_map::Enum;
 use exchange::adapter::MarketKind;
+use exchange::depth::Depth;
 use serde::{Deserialize, Serialize};
 
+use super::kline::KlineDataPoint;
+
 pub trait Indicator: PartialEq + Display + 'static {
     fn for_market(market: MarketKind) -> &'static [Self]
     where
@@ -93,3 +97,530 @@ impl From<HeatmapIndicator> for UiIndicator {
         UiIndicator::Heatmap(h)
     }
 }
+
+/// VWAP of typical price `(high + low + close) / 3`, accumulated from the candle at `from`
+/// forward. Candles before the first traded one are skipped.
+pub fn anchored_vwap(datapoints: &BTreeMap<u64, KlineDataPoint>, from: u64) -> Vec<(u64, f32)> {
+    let mut cum_pv = 0.0_f64;
+    let mut cum_volume = 0.0_f64;
+
+    datapoints
+        .range(from..)
+        .filter_map(|(time, dp)| {
+            let kline = &dp.kline;
+            let volume = f64::from(kline.volume.0 + kline.volume.1);
+            let typical = (kline.high.to_f32() + kline.low.to_f32() + kline.close.to_f32()) / 3.0;
+
+            cum_pv += f64::from(typical) * volume;
+            cum_volume += volume;
+
+            (cum_volume > 0.0).then(|| (*time, (cum_pv / cum_volume) as f32))
+        })
+        .collect()
+}
+
+/// Exponential moving average seeded with the simple average of the first `period` values.
+///
+/// Like the other rolling studies below, the result skips the warm-up and lines up with the
+/// last values of the input.
+pub fn ema(values: &[f32], period: usize) -> Vec<f32> {
+    if period == 0 || values.len() < period {
+        return vec![];
+    }
+
+    let alpha = 2.0 / (period as f32 + 1.0);
+    let seed = values[..period].iter().sum::<f32>() / period as f32;
+
+    let mut out = Vec::with_capacity(values.len() - period + 1);
+    out.push(seed);
+
+    for value in &values[period..] {
+        let prev = out[out.len() - 1];
+        out.push(prev + alpha * (value - prev));
+    }
+    out
+}
+
+/// Average true range with Wilder's smoothing, the first bar's true range is its high-low span
+pub fn atr(highs: &[f32], lows: &[f32], closes: &[f32], period: usize) -> Vec<f32> {
+    let len = highs.len().min(lows.len()).min(closes.len());
+    if period == 0 || len < period {
+        return vec![];
+    }
+
+    let true_ranges = (0..len).map(|i| {
+        let span = highs[i] - lows[i];
+        match i.checked_sub(1).map(|prev| closes[prev]) {
+            Some(prev_close) => span
+                .max((highs[i] - prev_close).abs())
+                .max((lows[i] - prev_close).abs()),
+            None => span,
+        }
+    });
+
+    let mut out = Vec::with_capacity(len - period + 1);
+    let mut seed = 0.0;
+
+    for (i, tr) in true_ranges.enumerate() {
+        if i < period {
+            seed += tr / period as f32;
+            if i == period - 1 {
+                out.push(seed);
+            }
+        } else {
+            let prev = out[out.len() - 1];
+            out.push((prev * (period - 1) as f32 + tr) / period as f32);
+        }
+    }
+    out
+}
+
+/// `(upper, mid, lower)` bands, an EMA of closes widened by `mult` ATRs on each side
+pub fn keltner_channel(
+    highs: &[f32],
+    lows: &[f32],
+    closes: &[f32],
+    ema_period: usize,
+    atr_period: usize,
+    mult: f32,
+) -> Vec<(f32, f32, f32)> {
+    let mid = ema(closes, ema_period);
+    let range = atr(highs, lows, closes, atr_period);
+
+    let len = mid.len().min(range.len());
+
+    mid[mid.len() - len..]
+        .iter()
+        .zip(&range[range.len() - len..])
+        .map(|(mid, atr)| (mid + mult * atr, *mid, mid - mult * atr))
+        .collect()
+}
+
+/// `(band, is_uptrend)` of a Supertrend: the band trails `mult` ATRs below the high-low
+/// midpoint while in an uptrend and above it in a downtrend, flipping once a close crosses it
+pub fn supertrend(
+    highs: &[f32],
+    lows: &[f32],
+    closes: &[f32],
+    period: usize,
+    mult: f32,
+) -> Vec<(f32, bool)> {
+    let range = atr(highs, lows, closes, period);
+    let Some(offset) = period.checked_sub(1) else {
+        return vec![];
+    };
+
+    let mut out = Vec::with_capacity(range.len());
+    let (mut upper, mut lower) = (f32::MAX, f32::MIN);
+    let mut is_uptrend = true;
+
+    for (i, atr) in range.iter().enumerate() {
+        let bar = i + offset;
+        let mid = (highs[bar] + lows[bar]) / 2.0;
+        let (basic_upper, basic_lower) = (mid + mult * atr, mid - mult * atr);
+        let close = closes[bar];
+
+        if i == 0 {
+            (upper, lower) = (basic_upper, basic_lower);
+            is_uptrend = close >= mid;
+        } else {
+            let prev_close = closes[bar - 1];
+            // bands only tighten, unless the previous close already broke through
+            if basic_upper < upper || prev_close > upper {
+                upper = basic_upper;
+            }
+            if basic_lower > lower || prev_close < lower {
+                lower = basic_lower;
+            }
+
+            if is_uptrend && close < lower {
+                is_uptrend = false;
+            } else if !is_uptrend && close > upper {
+                is_uptrend = true;
+            }
+        }
+
+        out.push((if is_uptrend { lower } else { upper }, is_uptrend));
+    }
+    out
+}
+
+/// `(upper, mid, lower)` bands, a simple average of closes widened by `std_devs` deviations
+pub fn bollinger_bands(closes: &[f32], period: usize, std_devs: f32) -> Vec<(f32, f32, f32)> {
+    if period == 0 {
+        return vec![];
+    }
+
+    closes
+        .windows(period)
+        .map(|window| {
+            let mean = window.iter().sum::<f32>() / period as f32;
+            let variance = window.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / period as f32;
+            let width = std_devs * variance.sqrt();
+
+            (mean + width, mean, mean - width)
+        })
+        .collect()
+}
+
+/// `(%K, %D)` of a Stochastic Oscillator, from 0 to 100: where the close sits within the
+/// high-low range of the last `k` bars, smoothed over `smooth` bars, with %D its `d` bar average.
+/// A bar without any range reads 50.
+pub fn stochastic(
+    highs: &[f32],
+    lows: &[f32],
+    closes: &[f32],
+    k: usize,
+    d: usize,
+    smooth: usize,
+) -> Vec<(f32, f32)> {
+    let len = highs.len().min(lows.len()).min(closes.len());
+    if k == 0 || len < k {
+        return vec![];
+    }
+
+    let raw = (k - 1..len)
+        .map(|i| {
+            let start = i + 1 - k;
+            let high = highs[start..=i].iter().copied().fold(f32::MIN, f32::max);
+            let low = lows[start..=i].iter().copied().fold(f32::MAX, f32::min);
+
+            if high > low {
+                100.0 * (closes[i] - low) / (high - low)
+            } else {
+                50.0
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let percent_k = sma(&raw, smooth);
+    let percent_d = sma(&percent_k, d);
+
+    percent_k[percent_k.len() - percent_d.len()..]
+        .iter()
+        .zip(percent_d)
+        .map(|(k, d)| (*k, d))
+        .collect()
+}
+
+fn sma(values: &[f32], period: usize) -> Vec<f32> {
+    if period == 0 {
+        return vec![];
+    }
+
+    values
+        .windows(period)
+        .map(|window| window.iter().sum::<f32>() / period as f32)
+        .collect()
+}
+
+/// Whether the Bollinger Bands sit inside the Keltner Channel, aligned to the shorter input
+pub fn squeezes(keltner: &[(f32, f32, f32)], bollinger: &[(f32, f32, f32)]) -> Vec<bool> {
+    let len = keltner.len().min(bollinger.len());
+
+    keltner[keltner.len() - len..]
+        .iter()
+        .zip(&bollinger[bollinger.len() - len..])
+        .map(|(kc, bb)| bb.0 < kc.0 && bb.2 > kc.2)
+        .collect()
+}
+
+/// Tenkan-sen, Kijun-sen, both Senkou spans and the Chikou span of an Ichimoku Cloud
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ichimoku {
+    /// Indexed like the input bars, `None` during the warm-up
+    pub tenkan: Vec<Option<f32>>,
+    pub kijun: Vec<Option<f32>>,
+    /// Plotted `kijun` bars ahead, so these run that many bars past the last input bar
+    pub senkou_a: Vec<Option<f32>>,
+    pub senkou_b: Vec<Option<f32>>,
+    /// Close plotted `kijun` bars back, indexed like the input bars and shorter by `kijun`
+    pub chikou: Vec<f32>,
+}
+
+/// Ichimoku lines over `tenkan`, `kijun` and `senkou_b` bar periods, `kijun` is also the
+/// displacement of the spans
+pub fn ichimoku(
+    highs: &[f32],
+    lows: &[f32],
+    closes: &[f32],
+    tenkan: usize,
+    kijun: usize,
+    senkou_b: usize,
+) -> Ichimoku {
+    let len = highs.len().min(lows.len()).min(closes.len());
+    let (highs, lows) = (&highs[..len], &lows[..len]);
+
+    let tenkan_line = range_midpoints(highs, lows, tenkan);
+    let kijun_line = range_midpoints(highs, lows, kijun);
+
+    let ahead = |line: Vec<Option<f32>>| {
+        std::iter::repeat_n(None, kijun)
+            .chain(line)
+            .collect::<Vec<_>>()
+    };
+
+    let senkou_a = ahead(
+        tenkan_line
+            .iter()
+            .zip(&kijun_line)
+            .map(|(t, k)| Some((t.as_ref()? + k.as_ref()?) / 2.0))
+            .collect(),
+    );
+    let senkou_b = ahead(range_midpoints(highs, lows, senkou_b));
+
+    Ichimoku {
+        tenkan: tenkan_line,
+        kijun: kijun_line,
+        senkou_a,
+        senkou_b,
+        chikou: closes[kijun.min(len)..len].to_vec(),
+    }
+}
+
+/// Midpoint of the highest high and lowest low over the last `period` bars, at each bar
+fn range_midpoints(highs: &[f32], lows: &[f32], period: usize) -> Vec<Option<f32>> {
+    (0..highs.len())
+        .map(|i| {
+            let start = (i + 1).checked_sub(period).filter(|_| period > 0)?;
+            let high = highs[start..=i].iter().copied().fold(f32::MIN, f32::max);
+            let low = lows[start..=i].iter().copied().fold(f32::MAX, f32::min);
+            Some((high + low) / 2.0)
+        })
+        .collect()
+}
+
+/// `(bids - asks) / (bids + asks)` over the best `levels` of each side, `None` on an empty book
+pub fn depth_imbalance(depth: &Depth, levels: usize) -> Option<f32> {
+    let bids: f32 = depth.bids.values().rev().take(levels).sum();
+    let asks: f32 = depth.asks.values().take(levels).sum();
+
+    let total = bids + asks;
+    (total > 0.0).then(|| (bids - asks) / total)
+}
+
+/// Depth imbalance averaged over the latest `window_size` snapshots, the last average of each
+/// bar kept as its value
+#[derive(Debug, Clone)]
+pub struct DepthImbalance {
+    pub window_size: usize,
+    pub levels: usize,
+    window: VecDeque<f32>,
+    pub history: BTreeMap<u64, f32>,
+}
+
+impl DepthImbalance {
+    pub fn new(window_size: usize, levels: usize) -> Self {
+        Self {
+            window_size: window_size.max(1),
+            levels: levels.max(1),
+            window: VecDeque::new(),
+            history: BTreeMap::new(),
+        }
+    }
+
+    /// Rolls `depth` into the window and stores the average under the bar starting at `bar_time`
+    pub fn insert(&mut self, bar_time: u64, depth: &Depth) {
+        let Some(ratio) = depth_imbalance(depth, self.levels) else {
+            return;
+        };
+
+        if self.window.len() == self.window_size {
+            self.window.pop_front();
+        }
+        self.window.push_back(ratio);
+
+        let average = self.window.iter().sum::<f32>() / self.window.len() as f32;
+        self.history.insert(bar_time, average);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exchange::Kline;
+    use exchange::util::Price;
+
+    fn datapoint(time: u64, high: f32, low: f32, close: f32, volume: f32) -> KlineDataPoint {
+        KlineDataPoint {
+            kline: Kline {
+                time,
+                open: Price::from_f32(close),
+                high: Price::from_f32(high),
+                low: Price::from_f32(low),
+                close: Price::from_f32(close),
+                volume: (volume / 2.0, volume / 2.0),
+            },
+            footprint: Default::default(),
+        }
+    }
+
+    #[test]
+    fn anchored_vwap_starts_at_anchor() {
+        let datapoints: BTreeMap<u64, KlineDataPoint> = [
+            datapoint(1_000, 90.0, 90.0, 90.0, 5.0),
+            datapoint(2_000, 102.0, 98.0, 100.0, 1.0),
+            datapoint(3_000, 112.0, 108.0, 110.0, 3.0),
+        ]
+        .into_iter()
+        .map(|dp| (dp.kline.time, dp))
+        .collect();
+
+        let vwap = anchored_vwap(&datapoints, 2_000);
+
+        assert_eq!(vwap.len(), 2);
+        assert_eq!(vwap[0], (2_000, 100.0));
+        assert_eq!(vwap[1].0, 3_000);
+        assert!((vwap[1].1 - 107.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn atr_of_steady_range() {
+        let closes = [100.0, 101.0, 102.0, 103.0, 104.0, 105.0];
+        let highs = closes.map(|c| c + 1.0);
+        let lows = closes.map(|c| c - 1.0);
+
+        let atr = atr(&highs, &lows, &closes, 3);
+
+        assert_eq!(atr.len(), 4);
+        assert!(atr.iter().all(|v| (v - 2.0).abs() < 1e-4), "{atr:?}");
+    }
+
+    #[test]
+    fn keltner_around_flat_closes() {
+        let closes = [50.0; 10];
+        let highs = [51.0; 10];
+        let lows = [49.0; 10];
+
+        let bands = keltner_channel(&highs, &lows, &closes, 4, 6, 1.5);
+
+        assert_eq!(bands.len(), 5);
+        for (upper, mid, lower) in bands {
+            assert!((mid - 50.0).abs() < 1e-4);
+            assert!((upper - 53.0).abs() < 1e-4);
+            assert!((lower - 47.0).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn supertrend_flips_when_close_crosses_band() {
+        let closes = [100.0, 101.0, 102.0, 103.0, 104.0, 90.0, 89.0];
+        let highs = closes.map(|c| c + 1.0);
+        let lows = closes.map(|c| c - 1.0);
+
+        let trend = supertrend(&highs, &lows, &closes, 3, 1.0);
+
+        assert_eq!(trend.len(), 5);
+        assert!(trend[..3].iter().all(|(_, up)| *up), "{trend:?}");
+        // the band trails one ATR of 2 below the midpoint and never loosens
+        assert!((trend[0].0 - 100.0).abs() < 1e-4);
+        assert!((trend[2].0 - 102.0).abs() < 1e-4);
+        // the drop to 90 closes under the band, which moves above price
+        assert!(!trend[3].1);
+        assert!(trend[3].0 > closes[5]);
+        assert!(!trend[4].1);
+    }
+
+    #[test]
+    fn ichimoku_spans_project_ahead() {
+        let highs = [10.0, 12.0, 14.0, 16.0, 18.0];
+        let lows = highs.map(|h| h - 4.0);
+        let closes = highs.map(|h| h - 1.0);
+
+        let cloud = ichimoku(&highs, &lows, &closes, 2, 3, 4);
+
+        assert_eq!(
+            cloud.tenkan,
+            vec![None, Some(9.0), Some(11.0), Some(13.0), Some(15.0)]
+        );
+        assert_eq!(
+            cloud.kijun,
+            vec![None, None, Some(10.0), Some(12.0), Some(14.0)]
+        );
+
+        // three bars of displacement, so both spans end three bars past the last close
+        assert_eq!(cloud.senkou_a.len(), highs.len() + 3);
+        assert_eq!(cloud.senkou_a[5], Some(10.5));
+        assert_eq!(cloud.senkou_a[7], Some(14.5));
+        assert_eq!(cloud.senkou_b[..6], [None; 6]);
+        assert_eq!(cloud.senkou_b[6..], [Some(11.0), Some(13.0)]);
+
+        assert_eq!(cloud.chikou, vec![15.0, 17.0]);
+    }
+
+    #[test]
+    fn squeeze_once_bollinger_fits_inside() {
+        let keltner = [(110.0, 100.0, 90.0); 3];
+        let bollinger = [(120.0, 100.0, 80.0), (105.0, 100.0, 95.0)];
+
+        assert_eq!(squeezes(&keltner, &bollinger), vec![false, true]);
+        assert_eq!(
+            bollinger_bands(&[100.0; 5], 5, 2.0),
+            vec![(100.0, 100.0, 100.0)]
+        );
+    }
+
+    #[test]
+    fn stochastic_places_close_within_range() {
+        let highs = [10.0, 11.0, 12.0, 13.0, 14.0, 15.0];
+        let lows = highs.map(|high| high - 2.0);
+
+        // closing at every high keeps %K pinned to the top, warm-up bars left out
+        assert_eq!(
+            stochastic(&highs, &lows, &highs, 3, 2, 2),
+            vec![(100.0, 100.0); 2]
+        );
+
+        let (highs, lows) = ([10.0; 3], [0.0; 3]);
+        assert_eq!(
+            stochastic(&highs, &lows, &[0.0, 5.0, 10.0], 1, 3, 1),
+            vec![(100.0, 50.0)]
+        );
+        assert_eq!(
+            stochastic(&[5.0; 3], &[5.0; 3], &[5.0; 3], 2, 1, 1),
+            vec![(50.0, 50.0); 2]
+        );
+        assert!(stochastic(&highs, &lows, &[5.0; 3], 4, 1, 1).is_empty());
+    }
+
+    fn depth(bids: &[(f32, f32)], asks: &[(f32, f32)]) -> Depth {
+        let side = |levels: &[(f32, f32)]| {
+            levels
+                .iter()
+                .map(|&(price, qty)| (Price::from_f32(price), qty))
+                .collect()
+        };
+
+        Depth {
+            bids: side(bids),
+            asks: side(asks),
+        }
+    }
+
+    #[test]
+    fn depth_imbalance_sums_best_levels() {
+        let book = depth(
+            &[(99.0, 3.0), (98.0, 1.0), (97.0, 100.0)],
+            &[(100.0, 1.0), (101.0, 1.0), (102.0, 100.0)],
+        );
+
+        // 4 on the bids against 2 on the asks, the far levels are left out
+        assert!((depth_imbalance(&book, 2).unwrap() - 1.0 / 3.0).abs() < 1e-6);
+        assert_eq!(depth_imbalance(&Depth::default(), 5), None);
+    }
+
+    #[test]
+    fn rolling_depth_imbalance_keeps_last_average_per_bar() {
+        let mut rolling = DepthImbalance::new(2, 1);
+        let bid_heavy = depth(&[(99.0, 3.0)], &[(100.0, 1.0)]);
+        let ask_heavy = depth(&[(99.0, 1.0)], &[(100.0, 3.0)]);
+
+        rolling.insert(1_000, &bid_heavy);
+        rolling.insert(1_000, &bid_heavy);
+        rolling.insert(2_000, &ask_heavy);
+        rolling.insert(2_000, &ask_heavy);
+
+        assert_eq!(rolling.history.get(&1_000), Some(&0.5));
+        assert_eq!(rolling.history.get(&2_000), Some(&-0.5));
+    }
+}
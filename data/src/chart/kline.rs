@@ -1,11 +1,12 @@
 use exchange::{
-    Kline, Trade,
+    Kline, Liquidation, TickerInfo, Timeframe, Trade,
     util::{Price, PriceStep},
 };
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, VecDeque, hash_map::Entry};
 
-use crate::aggr::time::DataPoint;
+use crate::aggr::time::{DataPoint, resample};
 
 // K线数据点结构体
 // 存储K线数据及其对应的footprint（订单流）数据
@@ -18,21 +19,20 @@ pub struct KlineDataPoint {
 // KlineDataPoint的实现块
 // 为KlineDataPoint结构体实现方法
 impl KlineDataPoint {
-    // 计算在指定价格范围内最大的簇（cluster）数量
+    // 计算在指定价格范围内最大的簇（cluster）数值
     // - cluster_kind: 簇的类型（买卖盘、成交量分布、Delta分布）
+    // - cell_text: 单元格显示的指标，缩放与显示的数字使用同一指标
     // - highest/lowest: 价格范围
-    // 使用泛型函数f处理不同的计算逻辑
-    pub fn max_cluster_qty(&self, cluster_kind: ClusterKind, highest: Price, lowest: Price) -> f32 {
-        match cluster_kind {
-            ClusterKind::BidAsk => self.footprint.max_qty_by(highest, lowest, f32::max),
-            ClusterKind::DeltaProfile => self
-                .footprint
-                .max_qty_by(highest, lowest, |buy, sell| (buy - sell).abs()),
-            ClusterKind::VolumeProfile => {
-                self.footprint
-                    .max_qty_by(highest, lowest, |buy, sell| buy + sell)
-            }
-        }
+    pub fn max_cluster_qty(
+        &self,
+        cluster_kind: ClusterKind,
+        cell_text: FootprintCellText,
+        highest: Price,
+        lowest: Price,
+    ) -> f32 {
+        self.footprint.max_cell_value(highest, lowest, |group| {
+            cluster_kind.cell_value(cell_text, group)
+        })
     }
 
     // 将一笔交易添加到最近的bin（价格区间）
@@ -73,6 +73,18 @@ impl KlineDataPoint {
     pub fn first_trade_time(&self) -> Option<u64> {
         self.footprint.first_trade_t()
     }
+
+    // 该K线的净成交量（Delta = 主动买入 - 主动卖出）
+    // 有逐笔交易时按footprint计算，否则退回K线自带的买卖量
+    // 交易所未提供买卖拆分时买量为-1，此时视为0
+    pub fn delta_qty(&self) -> f32 {
+        if !self.footprint.trades.is_empty() {
+            return self.footprint.delta_qty();
+        }
+
+        let (buy, sell) = self.kline.volume;
+        if buy < 0.0 { 0.0 } else { buy - sell }
+    }
 }
 
 // 为KlineDataPoint实现DataPoint trait
@@ -169,6 +181,8 @@ impl GroupedTrades {
 pub struct KlineTrades {
     pub trades: FxHashMap<Price, GroupedTrades>,  // 映射：价格 -> 该价格的交易分组
     pub poc: Option<PointOfControl>,             // 控制点POC（可选，可能没有）
+    pub liquidations: FxHashMap<Price, f32>,     // 映射：价格 -> 该价格的强平总量
+    prices: BTreeSet<Price>,                     // trades键的有序索引，用于按价格区间查找
 }
 
 // KlineTrades的实现块
@@ -178,9 +192,26 @@ impl KlineTrades {
         Self {
             trades: FxHashMap::default(),  // 使用default()创建默认的空HashMap
             poc: None,
+            liquidations: FxHashMap::default(),
+            prices: BTreeSet::new(),
         }
     }
 
+    // 将强平量累加到最近的价格档位，不区分多空方向
+    pub fn add_liquidation(&mut self, liquidation: &Liquidation, step: PriceStep) {
+        let price = liquidation.price.round_to_step(step);
+        *self.liquidations.entry(price).or_default() += liquidation.qty;
+    }
+
+    // 指定价格范围内单个档位的最大强平量
+    pub fn max_liquidation_qty(&self, highest: Price, lowest: Price) -> f32 {
+        self.liquidations
+            .iter()
+            .filter(|(price, _)| **price >= lowest && **price <= highest)
+            .map(|(_, qty)| *qty)
+            .fold(0.0, f32::max)
+    }
+
     // 获取第一笔交易的时间
     // Option<u64> 是Rust的可选类型，如果有交易返回Some(time)，否则返回None
     pub fn first_trade_t(&self) -> Option<u64> {
@@ -198,13 +229,11 @@ impl KlineTrades {
     pub fn add_trade_to_side_bin(&mut self, trade: &Trade, step: PriceStep) {
         let price = trade.price.round_to_side_step(trade.is_sell, step);
 
-        // entry() 是HashMap的方法，获取指定key的条目
-        // and_modify() 如果key存在，则修改对应的值
-        // or_insert_with() 如果key不存在，则插入新值
-        self.trades
-            .entry(price)
-            .and_modify(|group| group.add_trade(trade))
-            .or_insert_with(|| GroupedTrades::new(trade));
+        self.upsert_group(
+            price,
+            |group| group.add_trade(trade),
+            || GroupedTrades::new(trade),
+        );
     }
 
     /// 使用最近步长倍数方式添加交易到bin（无视方向）
@@ -213,12 +242,44 @@ impl KlineTrades {
     pub fn add_trade_to_nearest_bin(&mut self, trade: &Trade, step: PriceStep) {
         let price = trade.price.round_to_step(step);
 
-        // 使用entry API优雅地处理"存在则修改，不存在则插入"逻辑
-        // 比先contains_key()再insert()更高效，只需一次哈希查找
-        self.trades
-            .entry(price)
-            .and_modify(|group| group.add_trade(trade))
-            .or_insert_with(|| GroupedTrades::new(trade));
+        self.upsert_group(
+            price,
+            |group| group.add_trade(trade),
+            || GroupedTrades::new(trade),
+        );
+    }
+
+    // 价格档位已存在则用modify修改，否则插入insert创建的分组并登记到有序索引
+    // entry() 是HashMap的方法，获取指定key的条目
+    // and_modify() 如果key存在，则修改对应的值
+    // or_insert_with() 如果key不存在，则插入新值
+    pub fn upsert_group(
+        &mut self,
+        price: Price,
+        modify: impl FnOnce(&mut GroupedTrades),
+        insert: impl FnOnce() -> GroupedTrades,
+    ) {
+        match self.trades.entry(price) {
+            Entry::Occupied(mut group) => modify(group.get_mut()),
+            Entry::Vacant(slot) => {
+                slot.insert(insert());
+                self.prices.insert(price); // 新档位，O(log n)
+            }
+        }
+    }
+
+    // 按价格从低到高遍历[lowest, highest]区间内的档位，通过有序索引跳过区间外的价格
+    pub fn trades_in_range(
+        &self,
+        highest: Price,
+        lowest: Price,
+    ) -> impl Iterator<Item = (Price, &GroupedTrades)> {
+        let range = (lowest <= highest).then_some(lowest..=highest);
+
+        range
+            .into_iter()
+            .flat_map(|range| self.prices.range(range))
+            .filter_map(|price| self.trades.get(price).map(|group| (*price, group)))
     }
 
     // 在指定价格范围内，使用自定义函数计算最大数量
@@ -230,17 +291,92 @@ impl KlineTrades {
         F: Fn(f32, f32) -> f32,
     {
         let mut max_qty: f32 = 0.0;
-        // 迭代trades HashMap
-        // (price, group) 是模式匹配，解构元组
-        for (price, group) in &self.trades {
-            // 只处理在价格范围内的交易
-            if *price >= lowest && *price <= highest {
-                max_qty = max_qty.max(f(group.buy_qty, group.sell_qty));
-            }
+        // 只遍历价格范围内的档位
+        // (_, group) 是模式匹配，解构元组
+        for (_, group) in self.trades_in_range(highest, lowest) {
+            max_qty = max_qty.max(f(group.buy_qty, group.sell_qty));
         }
         max_qty
     }
 
+    // 与max_qty_by相同，但f接收整个GroupedTrades，可以用到成交笔数
+    pub fn max_cell_value<F>(&self, highest: Price, lowest: Price, f: F) -> f32
+    where
+        F: Fn(&GroupedTrades) -> f32,
+    {
+        self.trades_in_range(highest, lowest)
+            .map(|(_, group)| f(group))
+            .fold(0.0, f32::max)
+    }
+
+    // 所有价格水平的净成交量之和
+    pub fn delta_qty(&self) -> f32 {
+        self.trades.values().map(GroupedTrades::delta_qty).sum()
+    }
+
+    // 查找至少min_stack个连续价格水平的对角失衡区间
+    // 买方失衡：该价位买量超过低一档卖量的(100+threshold)%，卖方失衡：该价位卖量超过高一档买量
+    // 相邻价位必须正好相差一个step，缺档或未失衡的价位都会中断区间
+    pub fn stacked_imbalances(
+        &self,
+        threshold: usize,
+        min_stack: usize,
+        step: PriceStep,
+    ) -> Vec<StackedImbalance> {
+        let ratio = (100 + threshold) as f32 / 100.0;
+
+        // 对角价位没有成交或任一侧数量为零时不算失衡
+        let is_imbalanced = |price: Price, is_buy: bool| -> bool {
+            let (qty, diagonal) = if is_buy {
+                (self.trades[&price].buy_qty, price.add_steps(-1, step))
+            } else {
+                (self.trades[&price].sell_qty, price.add_steps(1, step))
+            };
+
+            self.trades.get(&diagonal).is_some_and(|group| {
+                let diagonal_qty = if is_buy {
+                    group.sell_qty
+                } else {
+                    group.buy_qty
+                };
+                diagonal_qty > 0.0 && qty > diagonal_qty * ratio
+            })
+        };
+
+        let mut stacks = Vec::new();
+
+        for is_buy in [true, false] {
+            let mut run: Option<StackedImbalance> = None;
+
+            for &price in &self.prices {
+                if !is_imbalanced(price, is_buy) {
+                    stacks.extend(run.take().filter(|run| run.levels >= min_stack));
+                    continue;
+                }
+
+                match &mut run {
+                    Some(current) if current.high.add_steps(1, step) == price => {
+                        current.high = price;
+                        current.levels += 1;
+                    }
+                    _ => {
+                        stacks.extend(run.take().filter(|run| run.levels >= min_stack));
+                        run = Some(StackedImbalance {
+                            low: price,
+                            high: price,
+                            levels: 1,
+                            is_buy,
+                        });
+                    }
+                }
+            }
+
+            stacks.extend(run.filter(|run| run.levels >= min_stack));
+        }
+
+        stacks
+    }
+
     // 计算POC（控制点）- 成交量最大的价格
     pub fn calculate_poc(&mut self) {
         // 如果trades为空，直接返回（提前返回模式）
@@ -287,6 +423,8 @@ impl KlineTrades {
     pub fn clear(&mut self) {
         self.trades.clear();     // 清空HashMap
         self.poc = None;         // 重置POC为None
+        self.liquidations.clear();
+        self.prices.clear();
     }
 }
 
@@ -296,67 +434,145 @@ impl KlineTrades {
 // - Debug: 用于打印调试信息
 // - Clone: 可克隆
 // - PartialEq/Eq: 可比较相等性
-// - Default: 提供默认值
 // - Deserialize/Serialize: 序列化反序列化支持（serde）
-#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+// remote = "Self" 让派生宏生成同名的固有函数，下方手写的 trait 实现再调用它们，
+// 以便兼容旧版本保存的单元变体 "Candles"
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(remote = "Self")]
 pub enum KlineChartKind {
-    #[default]
-    Candles,  // 普通K线图
+    // 普通K线图
+    Candles {
+        // 在右侧固定显示可见区间的成交量分布
+        #[serde(default)]
+        show_volume_profile: bool,
+    },
     Footprint {  // Footprint图（订单流图）
         clusters: ClusterKind,  // 簇的类型
         #[serde(default)]  // 反序列化时使用默认值如果字段缺失
         scaling: ClusterScaling,  // 缩放模式
         studies: Vec<FootprintStudy>,  // 研究指标集合（Vec是Rust的动态数组）
+        #[serde(default)]  // 叠加在Footprint下方的深度热力图层，None表示普通Footprint
+        depth_layer: Option<DepthLayer>,
+        #[serde(default)]  // 单元格显示的数值：成交量、笔数、平均单笔量或Delta
+        cell_text: FootprintCellText,
     },
 }
 
+/// 热力图 + footprint 面板各图层的不透明度，百分比
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DepthLayer {
+    pub heatmap_opacity: u8,
+    pub footprint_opacity: u8,
+}
+
+impl DepthLayer {
+    pub const OPACITY_RANGE: std::ops::RangeInclusive<u8> = 0..=100;
+
+    pub fn heatmap_alpha(&self) -> f32 {
+        f32::from(self.heatmap_opacity.min(100)) / 100.0
+    }
+
+    pub fn footprint_alpha(&self) -> f32 {
+        f32::from(self.footprint_opacity.min(100)) / 100.0
+    }
+}
+
+impl Default for DepthLayer {
+    fn default() -> Self {
+        Self {
+            heatmap_opacity: 60,
+            footprint_opacity: 100,
+        }
+    }
+}
+
+impl Default for KlineChartKind {
+    fn default() -> Self {
+        KlineChartKind::Candles {
+            show_volume_profile: false,
+        }
+    }
+}
+
+impl Serialize for KlineChartKind {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        KlineChartKind::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for KlineChartKind {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value =
+            serde_json::Value::deserialize(deserializer).map_err(serde::de::Error::custom)?;
+
+        // 旧版本的 Candles 没有字段，保存为字符串 "Candles"
+        if value.as_str() == Some("Candles") {
+            return Ok(KlineChartKind::default());
+        }
+        KlineChartKind::deserialize(value).map_err(serde::de::Error::custom)
+    }
+}
+
 impl KlineChartKind {
+    pub fn depth_layer(&self) -> Option<DepthLayer> {
+        match self {
+            KlineChartKind::Footprint { depth_layer, .. } => *depth_layer,
+            KlineChartKind::Candles { .. } => None,
+        }
+    }
+
     pub fn min_scaling(&self) -> f32 {
         match self {
             KlineChartKind::Footprint { .. } => 0.4,
-            KlineChartKind::Candles => 0.6,
+            KlineChartKind::Candles { .. } => 0.6,
         }
     }
 
     pub fn max_scaling(&self) -> f32 {
         match self {
             KlineChartKind::Footprint { .. } => 2.0,
-            KlineChartKind::Candles => 2.5,
+            KlineChartKind::Candles { .. } => 2.5,
         }
     }
 
     pub fn max_cell_width(&self) -> f32 {
         match self {
             KlineChartKind::Footprint { .. } => 360.0,
-            KlineChartKind::Candles => 16.0,
+            KlineChartKind::Candles { .. } => 16.0,
         }
     }
 
     pub fn min_cell_width(&self) -> f32 {
         match self {
             KlineChartKind::Footprint { .. } => 80.0,
-            KlineChartKind::Candles => 1.0,
+            KlineChartKind::Candles { .. } => 1.0,
         }
     }
 
     pub fn max_cell_height(&self) -> f32 {
         match self {
             KlineChartKind::Footprint { .. } => 90.0,
-            KlineChartKind::Candles => 8.0,
+            KlineChartKind::Candles { .. } => 8.0,
         }
     }
 
     pub fn min_cell_height(&self) -> f32 {
         match self {
             KlineChartKind::Footprint { .. } => 1.0,
-            KlineChartKind::Candles => 0.001,
+            KlineChartKind::Candles { .. } => 0.001,
         }
     }
 
     pub fn default_cell_width(&self) -> f32 {
         match self {
             KlineChartKind::Footprint { .. } => 80.0,
-            KlineChartKind::Candles => 4.0,
+            KlineChartKind::Candles { .. } => 4.0,
         }
     }
 }
@@ -402,8 +618,527 @@ impl std::fmt::Display for ClusterKind {
     }
 }
 
-#[derive(Debug, Default, Copy, Clone, PartialEq, Deserialize, Serialize)]
-pub struct Config {}
+impl ClusterKind {
+    // 单个价格档位在该簇类型下的数值，用于计算簇的最大值（决定条形宽度）
+    // 买卖盘取两侧较大者，成交量分布取合计，Delta分布取两侧之差的绝对值
+    pub fn cell_value(self, cell_text: FootprintCellText, group: &GroupedTrades) -> f32 {
+        match self {
+            ClusterKind::BidAsk => {
+                let (buy, sell) = cell_text.sides(group);
+                buy.max(sell)
+            }
+            ClusterKind::VolumeProfile => cell_text.combined(group).abs(),
+            ClusterKind::DeltaProfile => {
+                let (buy, sell) = cell_text.sides(group);
+                (buy - sell).abs()
+            }
+        }
+    }
+}
+
+// Footprint单元格显示的指标
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub enum FootprintCellText {
+    #[default]
+    Volume, // 成交量
+    Count,   // 成交笔数
+    AvgSize, // 平均单笔成交量
+    Delta,   // 净成交量（买入-卖出）
+}
+
+impl FootprintCellText {
+    pub const ALL: [FootprintCellText; 4] = [
+        FootprintCellText::Volume,
+        FootprintCellText::Count,
+        FootprintCellText::AvgSize,
+        FootprintCellText::Delta,
+    ];
+
+    // 买方和卖方各自的数值
+    // Delta只有一侧有值：净买入显示在买方，净卖出显示在卖方
+    pub fn sides(self, group: &GroupedTrades) -> (f32, f32) {
+        let avg = |qty: f32, count: usize| if count > 0 { qty / count as f32 } else { 0.0 };
+
+        match self {
+            FootprintCellText::Volume => (group.buy_qty, group.sell_qty),
+            FootprintCellText::Count => (group.buy_count as f32, group.sell_count as f32),
+            FootprintCellText::AvgSize => (
+                avg(group.buy_qty, group.buy_count),
+                avg(group.sell_qty, group.sell_count),
+            ),
+            FootprintCellText::Delta => {
+                let delta = group.delta_qty();
+                (delta.max(0.0), (-delta).max(0.0))
+            }
+        }
+    }
+
+    // 买卖合并后的数值，平均单笔量按总量除以总笔数计算
+    pub fn combined(self, group: &GroupedTrades) -> f32 {
+        match self {
+            FootprintCellText::Volume => group.total_qty(),
+            FootprintCellText::Count => (group.buy_count + group.sell_count) as f32,
+            FootprintCellText::AvgSize => {
+                let count = group.buy_count + group.sell_count;
+                if count > 0 {
+                    group.total_qty() / count as f32
+                } else {
+                    0.0
+                }
+            }
+            FootprintCellText::Delta => group.delta_qty(),
+        }
+    }
+}
+
+impl std::fmt::Display for FootprintCellText {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FootprintCellText::Volume => write!(f, "Volume"),
+            FootprintCellText::Count => write!(f, "Trade count"),
+            FootprintCellText::AvgSize => write!(f, "Avg trade size"),
+            FootprintCellText::Delta => write!(f, "Delta"),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Config {
+    /// 以相对强度线画在主图上的副品种
+    pub compare: CompareOverlays,
+    /// 联动组切换面板品种时，把之前的品种保留为叠加线
+    /// 以便新品种与其对比
+    pub compare_previous_on_link: bool,
+    /// 可见价格范围上下的留白，占其跨度的百分比
+    pub scale_padding: f32,
+    /// 绘制面板的锚定 VWAP，并在图表右键菜单中提供锚定
+    pub anchored_vwap: bool,
+    /// 逐根连接 footprint 的 POC，按是否被回补着色
+    pub poc_history: bool,
+    /// 在新的 UTC 日或周开始处画竖线
+    pub session_separator: SessionSeparator,
+    /// 整个可见范围的价格成交量分布，固定在右边缘
+    pub visible_profile: bool,
+    /// 决定K线实体颜色的方式
+    pub candle_coloring: CandleColoring,
+    /// K线实体的填充方式
+    pub candle_style: CandleStyle,
+    /// K线实体宽度占单元格宽度的比例
+    pub candle_body_width: f32,
+    /// 影线粗细占K线实体宽度的比例
+    pub candle_wick_width: f32,
+    /// 成交量指标显示原始成交量还是占日均的比例
+    pub volume_display: VolumeDisplayMode,
+    /// 由谁绘制 footprint 单元格的柱子
+    pub cell_renderer: CellRenderer,
+    /// 新K线开盘时短暂高亮最新一根
+    pub flash_new_bar: bool,
+    /// K线图下方的全历史概览条，点击时滚动图表
+    pub minimap: bool,
+    /// 概览条在屏幕上的高度
+    pub minimap_height: f32,
+    /// 面板宽或高低于此值时，footprint 画成不带坐标轴标签的普通K线，
+    /// 为 0 时总是完整绘制
+    pub compact_below: f32,
+}
+
+impl Config {
+    pub const SCALE_PADDING_RANGE: std::ops::RangeInclusive<f32> = 0.0..=25.0;
+    pub const CANDLE_BODY_WIDTH_RANGE: std::ops::RangeInclusive<f32> = 0.2..=1.0;
+    pub const CANDLE_WICK_WIDTH_RANGE: std::ops::RangeInclusive<f32> = 0.05..=1.0;
+    pub const MINIMAP_HEIGHT_RANGE: std::ops::RangeInclusive<f32> = 20.0..=50.0;
+    pub const COMPACT_BELOW_RANGE: std::ops::RangeInclusive<f32> = 0.0..=600.0;
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            compare: CompareOverlays::default(),
+            compare_previous_on_link: false,
+            scale_padding: 5.0,
+            anchored_vwap: false,
+            poc_history: false,
+            session_separator: SessionSeparator::Off,
+            visible_profile: false,
+            candle_coloring: CandleColoring::PriceAction,
+            candle_style: CandleStyle::Standard,
+            candle_body_width: 0.8,
+            candle_wick_width: 0.25,
+            volume_display: VolumeDisplayMode::Absolute,
+            cell_renderer: CellRenderer::Canvas,
+            flash_new_bar: true,
+            minimap: false,
+            minimap_height: 32.0,
+            compact_below: 240.0,
+        }
+    }
+}
+
+/// 新打开的 footprint 目标显示的价格行数
+pub const SUGGESTED_TICK_ROWS: usize = 30;
+
+/// footprint 首次打开时纵向通常覆盖的价格比例
+const SUGGESTED_VISIBLE_RANGE: f32 = 0.01;
+
+/// `exchange_tick_size` 的最小倍数，使 footprint 在 `price` 附近通常显示的价格范围内
+/// 最多约有 `target_row_count` 行
+pub fn suggest_tick_size(price: f32, exchange_tick_size: f32, target_row_count: usize) -> f32 {
+    if price <= 0.0 || exchange_tick_size <= 0.0 || target_row_count == 0 {
+        return exchange_tick_size;
+    }
+
+    let ideal = price * SUGGESTED_VISIBLE_RANGE / target_row_count as f32;
+    // 微小偏移避免像 10.000001 这样的恰好整除被向上取整多出一个 tick
+    let multiple = (ideal / exchange_tick_size - 1e-3).ceil().max(1.0);
+
+    multiple * exchange_tick_size
+}
+
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// UTC 日历时段，周从周一开始
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum SessionSeparator {
+    #[default]
+    Off,
+    Daily,
+    Weekly,
+}
+
+impl SessionSeparator {
+    pub const ALL: [SessionSeparator; 3] = [
+        SessionSeparator::Off,
+        SessionSeparator::Daily,
+        SessionSeparator::Weekly,
+    ];
+
+    /// 一个时段的毫秒长度，关闭时为 `None`
+    pub fn period_ms(self) -> Option<u64> {
+        match self {
+            SessionSeparator::Off => None,
+            SessionSeparator::Daily => Some(DAY_MS),
+            SessionSeparator::Weekly => Some(7 * DAY_MS),
+        }
+    }
+
+    /// 在比 UTC 快 `utc_offset_ms` 的时钟上 `timestamp` 所在时段的序号，
+    /// 纪元起点是周四
+    pub fn session_of(self, timestamp: u64, utc_offset_ms: i64) -> Option<u64> {
+        let timestamp = (timestamp as i64).saturating_add(utc_offset_ms).max(0) as u64;
+
+        match self {
+            SessionSeparator::Off => None,
+            SessionSeparator::Daily => Some(timestamp / DAY_MS),
+            SessionSeparator::Weekly => Some((timestamp / DAY_MS + 3) / 7),
+        }
+    }
+}
+
+impl std::fmt::Display for SessionSeparator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SessionSeparator::Off => write!(f, "Off"),
+            SessionSeparator::Daily => write!(f, "Daily"),
+            SessionSeparator::Weekly => write!(f, "Weekly"),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum CandleColoring {
+    /// 收盘价不低于开盘价时为涨
+    #[default]
+    PriceAction,
+    /// 按K线 footprint delta 的正负着色，在可见范围内越大越饱和
+    Delta,
+}
+
+impl CandleColoring {
+    pub const ALL: [CandleColoring; 2] = [CandleColoring::PriceAction, CandleColoring::Delta];
+}
+
+impl std::fmt::Display for CandleColoring {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CandleColoring::PriceAction => write!(f, "Price action"),
+            CandleColoring::Delta => write!(f, "Delta"),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum CandleStyle {
+    /// 涨跌都用实心实体
+    #[default]
+    Standard,
+    /// 收盘高于开盘时为空心实体，否则为实心
+    HollowBullish,
+}
+
+impl CandleStyle {
+    pub const ALL: [CandleStyle; 2] = [CandleStyle::Standard, CandleStyle::HollowBullish];
+}
+
+impl std::fmt::Display for CandleStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CandleStyle::Standard => write!(f, "Standard"),
+            CandleStyle::HollowBullish => write!(f, "Hollow bullish"),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum VolumeDisplayMode {
+    /// 每根K线的成交量
+    #[default]
+    Absolute,
+    /// K线成交量占之前 24 小时平均K线成交量的百分比
+    PercentOfDailyAverage,
+}
+
+impl VolumeDisplayMode {
+    pub const ALL: [VolumeDisplayMode; 2] = [
+        VolumeDisplayMode::Absolute,
+        VolumeDisplayMode::PercentOfDailyAverage,
+    ];
+}
+
+impl std::fmt::Display for VolumeDisplayMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VolumeDisplayMode::Absolute => write!(f, "Absolute"),
+            VolumeDisplayMode::PercentOfDailyAverage => write!(f, "% of daily average"),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum CellRenderer {
+    /// 与其他内容一起在图表画布上细分绘制
+    #[default]
+    Canvas,
+    /// 由画布下方的 wgpu 着色器绘制的实例化四边形，用于大型 footprint
+    Gpu,
+}
+
+impl CellRenderer {
+    pub const ALL: [CellRenderer; 2] = [CellRenderer::Canvas, CellRenderer::Gpu];
+}
+
+impl std::fmt::Display for CellRenderer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CellRenderer::Canvas => write!(f, "Canvas"),
+            CellRenderer::Gpu => write!(f, "GPU"),
+        }
+    }
+}
+
+/// 截至并包含每根K线的 24 小时平均K线成交量。
+///
+/// `bars` 是按时间排序的 `(key, time, volume)`，键与成交量指标的数据一致，
+/// 这样基于 tick 的序列可以连同K线时间一起传入索引。
+pub fn rolling_daily_average(
+    bars: impl IntoIterator<Item = (u64, u64, f32)>,
+) -> BTreeMap<u64, f32> {
+    let mut window = VecDeque::new();
+    let mut sum = 0.0_f64;
+
+    bars.into_iter()
+        .map(|(key, time, volume)| {
+            window.push_back((time, volume));
+            sum += f64::from(volume);
+
+            while let Some(&(oldest, oldest_volume)) = window.front() {
+                if oldest + DAY_MS > time {
+                    break;
+                }
+                sum -= f64::from(oldest_volume);
+                window.pop_front();
+            }
+
+            (key, (sum / window.len() as f64) as f32)
+        })
+        .collect()
+}
+
+/// 指标上显示的归一化成交量，例如 `+342%`
+pub fn format_volume_percent(percent: f32) -> String {
+    format!("{percent:+.0}%")
+}
+
+/// 叠加在K线图上的品种及其线条的 RGBA 颜色
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct CompareTicker {
+    pub ticker_info: TickerInfo,
+    pub color: [u8; 4],
+}
+
+/// 叠加在K线图上的最多 [`CompareOverlays::MAX`] 个品种，按添加顺序排列
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct CompareOverlays {
+    slots: [Option<CompareTicker>; CompareOverlays::MAX],
+}
+
+impl CompareOverlays {
+    pub const MAX: usize = 3;
+
+    /// 分配给新增品种的颜色，取第一个未被使用的
+    pub const COLORS: [[u8; 4]; CompareOverlays::MAX] = [
+        [66, 165, 245, 255],
+        [255, 152, 0, 255],
+        [186, 104, 200, 255],
+    ];
+
+    pub fn iter(&self) -> impl Iterator<Item = &CompareTicker> {
+        self.slots.iter().flatten()
+    }
+
+    pub fn tickers(&self) -> impl Iterator<Item = TickerInfo> + '_ {
+        self.iter().map(|compare| compare.ticker_info)
+    }
+
+    pub fn contains(&self, ticker_info: &TickerInfo) -> bool {
+        self.tickers().any(|ti| ti == *ticker_info)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.iter().all(Option::is_none)
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.slots.iter().all(Option::is_some)
+    }
+
+    /// 用第一个空闲颜色添加 `ticker_info`，已存在或没有空位时返回 false
+    pub fn add(&mut self, ticker_info: TickerInfo) -> bool {
+        if self.contains(&ticker_info) {
+            return false;
+        }
+
+        let color = Self::COLORS
+            .into_iter()
+            .find(|color| self.iter().all(|compare| compare.color != *color))
+            .unwrap_or(Self::COLORS[0]);
+
+        match self.slots.iter_mut().find(|slot| slot.is_none()) {
+            Some(slot) => {
+                *slot = Some(CompareTicker { ticker_info, color });
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn remove(&mut self, ticker_info: &TickerInfo) {
+        self.retain(|ti| ti != ticker_info);
+    }
+
+    /// 保留 `keep` 返回 true 的品种，并填补留下的空位
+    pub fn retain(&mut self, mut keep: impl FnMut(&TickerInfo) -> bool) {
+        let kept: Vec<CompareTicker> = self
+            .iter()
+            .filter(|compare| keep(&compare.ticker_info))
+            .copied()
+            .collect();
+
+        self.slots = [None; Self::MAX];
+        for (slot, compare) in self.slots.iter_mut().zip(kept) {
+            *slot = Some(compare);
+        }
+    }
+}
+
+impl Serialize for CompareOverlays {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for CompareOverlays {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value =
+            serde_json::Value::deserialize(deserializer).map_err(serde::de::Error::custom)?;
+
+        let mut overlays = CompareOverlays::default();
+
+        // 旧版本只保存一个对比品种，为 null 或单个 TickerInfo
+        if value.is_null() {
+            return Ok(overlays);
+        }
+        if !value.is_array() {
+            let ticker_info = TickerInfo::deserialize(value).map_err(serde::de::Error::custom)?;
+            overlays.add(ticker_info);
+            return Ok(overlays);
+        }
+
+        let compares =
+            Vec::<CompareTicker>::deserialize(value).map_err(serde::de::Error::custom)?;
+        for (slot, compare) in overlays.slots.iter_mut().zip(compares) {
+            *slot = Some(compare);
+        }
+        Ok(overlays)
+    }
+}
+
+/// 副品种的收盘价，以主图的时间周期分桶为键
+#[derive(Debug, Clone)]
+pub struct CompareSeries {
+    pub ticker_info: TickerInfo,
+    pub closes: BTreeMap<u64, Price>,
+}
+
+impl CompareSeries {
+    pub fn new(ticker_info: TickerInfo) -> Self {
+        Self {
+            ticker_info,
+            closes: BTreeMap::new(),
+        }
+    }
+
+    /// 插入K线，若时间周期不同则先重新采样
+    pub fn insert_klines(&mut self, klines: &[Kline], timeframe: Timeframe) {
+        for kline in resample(klines, timeframe) {
+            self.closes.insert(kline.time, kline.close);
+        }
+    }
+
+    pub fn timerange(&self) -> Option<(u64, u64)> {
+        let earliest = self.closes.keys().next()?;
+        let latest = self.closes.keys().next_back()?;
+        Some((*earliest, *latest))
+    }
+
+    /// `earliest..=latest` 内的收盘价，按比例缩放使第一个等于 `base_close`
+    pub fn normalized(&self, base_close: f32, earliest: u64, latest: u64) -> Vec<(u64, f32)> {
+        if latest < earliest {
+            return vec![];
+        }
+
+        let mut visible = self.closes.range(earliest..=latest).peekable();
+
+        let Some(first_close) = visible.peek().map(|(_, close)| close.to_f32()) else {
+            return vec![];
+        };
+        if first_close <= 0.0 {
+            return vec![];
+        }
+
+        let factor = base_close / first_close;
+
+        visible
+            .map(|(time, close)| (*time, close.to_f32() * factor))
+            .collect()
+    }
+}
 
 #[derive(Default, Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
 pub enum ClusterScaling {
@@ -418,11 +1153,21 @@ pub enum ClusterScaling {
 }
 
 impl ClusterScaling {
-    pub const ALL: [ClusterScaling; 3] = [
-        ClusterScaling::VisibleRange,
-        ClusterScaling::Hybrid { weight: 0.2 },
-        ClusterScaling::Datapoint,
-    ];
+    pub const DEFAULT_HYBRID_WEIGHT: f32 = 0.2;
+    pub const HYBRID_WEIGHT_RANGE: std::ops::RangeInclusive<f32> = 0.0..=1.0;
+
+    pub const ALL: [ClusterScaling; 3] = Self::options(Self::DEFAULT_HYBRID_WEIGHT);
+
+    /// 与 `ALL` 相同，只是混合项带的是 `hybrid_weight` 而不是默认值
+    pub const fn options(hybrid_weight: f32) -> [ClusterScaling; 3] {
+        [
+            ClusterScaling::VisibleRange,
+            ClusterScaling::Hybrid {
+                weight: hybrid_weight.clamp(0.0, 1.0),
+            },
+            ClusterScaling::Datapoint,
+        ]
+    }
 }
 
 impl std::fmt::Display for ClusterScaling {
@@ -440,13 +1185,29 @@ impl std::cmp::Eq for ClusterScaling {}
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
 pub enum FootprintStudy {
     NPoC {
-        lookback: usize,
+        lookback: NpocLookback,
     },
     Imbalance {
         threshold: usize,
         color_scale: Option<usize>,
         ignore_zeros: bool,
     },
+    /// 标出价格与累计 delta 相背离的摆动点
+    Divergence {
+        /// 摆动高/低点两侧需要超过的K线数
+        lookback: usize,
+    },
+    /// 连续价格水平上的斜向失衡
+    StackedImbalance {
+        threshold: usize,
+        /// 算作堆叠的最少连续水平数
+        min_stack: usize,
+    },
+    /// footprint 下方的条带，每根K线的 delta 画成横条，净买入向右
+    /// 净卖出向左，按屏幕上最大的 delta 缩放
+    DeltaBar,
+    /// 在发生爆仓的单元格上画条纹，爆仓越大越宽
+    LiquidationOverlay,
 }
 
 impl FootprintStudy {
@@ -458,18 +1219,40 @@ impl FootprintStudy {
                     FootprintStudy::Imbalance { .. },
                     FootprintStudy::Imbalance { .. }
                 )
+                | (
+                    FootprintStudy::Divergence { .. },
+                    FootprintStudy::Divergence { .. }
+                )
+                | (
+                    FootprintStudy::StackedImbalance { .. },
+                    FootprintStudy::StackedImbalance { .. }
+                )
+                | (FootprintStudy::DeltaBar, FootprintStudy::DeltaBar)
+                | (
+                    FootprintStudy::LiquidationOverlay,
+                    FootprintStudy::LiquidationOverlay
+                )
         )
     }
 }
 
 impl FootprintStudy {
-    pub const ALL: [FootprintStudy; 2] = [
-        FootprintStudy::NPoC { lookback: 80 },
+    pub const ALL: [FootprintStudy; 6] = [
+        FootprintStudy::NPoC {
+            lookback: NpocLookback::Fixed(80),
+        },
         FootprintStudy::Imbalance {
             threshold: 200,
             color_scale: Some(400),
             ignore_zeros: true,
         },
+        FootprintStudy::Divergence { lookback: 5 },
+        FootprintStudy::StackedImbalance {
+            threshold: 200,
+            min_stack: 3,
+        },
+        FootprintStudy::DeltaBar,
+        FootprintStudy::LiquidationOverlay,
     ];
 }
 
@@ -478,6 +1261,172 @@ impl std::fmt::Display for FootprintStudy {
         match self {
             FootprintStudy::NPoC { .. } => write!(f, "Naked Point of Control"),
             FootprintStudy::Imbalance { .. } => write!(f, "Imbalance"),
+            FootprintStudy::Divergence { .. } => write!(f, "Delta Divergence"),
+            FootprintStudy::StackedImbalance { .. } => write!(f, "Stacked Imbalance"),
+            FootprintStudy::DeltaBar => write!(f, "Delta Bars"),
+            FootprintStudy::LiquidationOverlay => write!(f, "Liquidations"),
+        }
+    }
+}
+
+/// 追踪并绘制哪些K线的裸 POC
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum NpocLookback {
+    /// 最近的 `n` 根K线，无论是否在屏幕上
+    Fixed(usize),
+    /// 只看可见范围内的K线，不扫描屏幕外
+    VisibleRange,
+}
+
+impl<'de> Deserialize<'de> for NpocLookback {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        // `VisibleRange` 出现之前保存的布局只存了K线数量
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Count(usize),
+            Lookback(Lookback),
+        }
+
+        #[derive(Deserialize)]
+        enum Lookback {
+            Fixed(usize),
+            VisibleRange,
+        }
+
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Count(count) | Repr::Lookback(Lookback::Fixed(count)) => {
+                NpocLookback::Fixed(count)
+            }
+            Repr::Lookback(Lookback::VisibleRange) => NpocLookback::VisibleRange,
+        })
+    }
+}
+
+/// footprint 中同一方向失衡的连续价格水平
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StackedImbalance {
+    pub low: Price,
+    pub high: Price,
+    pub levels: usize,
+    /// 买方压过下一档的卖单，否则为卖方压过上一档的买单
+    pub is_buy: bool,
+}
+
+/// 价格/累计 delta 背离的方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Divergence {
+    /// 价格创更高的高点，累计 delta 的高点更低
+    Bearish,
+    /// 价格创更低的低点，累计 delta 的低点更高
+    Bullish,
+}
+
+/// 绘制在任意图表类型K线上的研究指标，按面板保存
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum KlineStudy {
+    /// 从 `anchor_time` 开盘的K线起向后累计的 VWAP
+    AnchoredVwap { anchor_time: u64, color: [u8; 4] },
+    /// 收盘价的 EMA，带宽为 `multiplier` 倍 ATR，可选标出布林带收缩
+    KeltnerChannel {
+        ema_period: usize,
+        atr_period: usize,
+        multiplier: f32,
+        #[serde(default)]
+        highlight_squeeze: bool,
+    },
+    /// 最优 `lookback_levels` 档的买卖深度失衡，在 `window_size` 个快照上取平均，
+    /// 并画在独立的面板中
+    DepthImbalance {
+        window_size: usize,
+        lookback_levels: usize,
+    },
+    /// 一目均衡表，先行带画在其来源K线之后 `kijun` 根的位置
+    Ichimoku {
+        tenkan: usize,
+        kijun: usize,
+        senkou_b: usize,
+    },
+    /// 距价格 `multiplier` 倍 `period` 根K线 ATR 的带，位于趋势所在一侧
+    Supertrend { period: usize, multiplier: f32 },
+    /// 给可见范围内绝对 delta 最大的K线加边框
+    MaxDeltaBar,
+    /// `k_period` 根K线的随机指标，%K 经 `smooth` 根K线平滑，%D 为其
+    /// `d_period` 根K线均值，画在独立的面板中
+    Stochastic {
+        k_period: usize,
+        d_period: usize,
+        smooth: usize,
+    },
+}
+
+impl KlineStudy {
+    /// 依次分配给新锚定 VWAP 的颜色
+    pub const ANCHORED_VWAP_COLORS: [[u8; 4]; 4] = [
+        [255, 193, 7, 255],
+        [0, 188, 212, 255],
+        [233, 30, 99, 255],
+        [139, 195, 74, 255],
+    ];
+
+    pub const KELTNER_CHANNEL: KlineStudy = KlineStudy::KeltnerChannel {
+        ema_period: 20,
+        atr_period: 10,
+        multiplier: 2.0,
+        highlight_squeeze: false,
+    };
+
+    pub const DEPTH_IMBALANCE: KlineStudy = KlineStudy::DepthImbalance {
+        window_size: 20,
+        lookback_levels: 10,
+    };
+
+    pub const ICHIMOKU: KlineStudy = KlineStudy::Ichimoku {
+        tenkan: 9,
+        kijun: 26,
+        senkou_b: 52,
+    };
+
+    pub const SUPERTREND: KlineStudy = KlineStudy::Supertrend {
+        period: 10,
+        multiplier: 3.0,
+    };
+
+    pub const STOCHASTIC: KlineStudy = KlineStudy::Stochastic {
+        k_period: 14,
+        d_period: 3,
+        smooth: 3,
+    };
+
+    /// 标出收缩时与通道比较的布林带
+    pub const SQUEEZE_STD_DEVS: f32 = 2.0;
+
+    pub fn is_same_type(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (
+                KlineStudy::AnchoredVwap { .. },
+                KlineStudy::AnchoredVwap { .. }
+            ) | (
+                KlineStudy::KeltnerChannel { .. },
+                KlineStudy::KeltnerChannel { .. }
+            ) | (
+                KlineStudy::DepthImbalance { .. },
+                KlineStudy::DepthImbalance { .. }
+            ) | (KlineStudy::Ichimoku { .. }, KlineStudy::Ichimoku { .. })
+                | (KlineStudy::Supertrend { .. }, KlineStudy::Supertrend { .. })
+                | (KlineStudy::MaxDeltaBar, KlineStudy::MaxDeltaBar)
+                | (KlineStudy::Stochastic { .. }, KlineStudy::Stochastic { .. })
+        )
+    }
+
+    pub fn anchored_vwap(anchor_time: u64, nth: usize) -> Self {
+        KlineStudy::AnchoredVwap {
+            anchor_time,
+            color: Self::ANCHORED_VWAP_COLORS[nth % Self::ANCHORED_VWAP_COLORS.len()],
         }
     }
 }
@@ -518,3 +1467,444 @@ impl NPoc {
         *self = NPoc::Naked;
     }
 }
+
+/// 成交量分布 POC 周围、容纳其一定比例成交量的价格区间
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ValueArea {
+    pub poc: Price,
+    pub low: Price,
+    pub high: Price,
+}
+
+impl ValueArea {
+    /// 价值区内成交量所占比例
+    pub const DEFAULT_SHARE: f32 = 0.7;
+
+    /// 从 POC 开始每次扩展一个有成交的价格水平，朝成交量更大的相邻一侧扩展，
+    /// 直到覆盖分布中 `share` 比例的成交量
+    pub fn from_profile(profile: &FxHashMap<Price, (f32, f32)>, share: f32) -> Option<Self> {
+        let mut levels: Vec<(Price, f32)> = profile
+            .iter()
+            .map(|(&price, &(buy_qty, sell_qty))| (price, buy_qty + sell_qty))
+            .filter(|(_, volume)| *volume > 0.0)
+            .collect();
+        levels.sort_unstable_by_key(|(price, _)| *price);
+
+        let poc = levels
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.1.total_cmp(&b.1))
+            .map(|(index, _)| index)?;
+
+        let total: f32 = levels.iter().map(|(_, volume)| volume).sum();
+        let target = total * share.clamp(0.0, 1.0);
+
+        let (mut low, mut high) = (poc, poc);
+        let mut covered = levels[poc].1;
+
+        while covered < target {
+            let below = low.checked_sub(1).map(|index| levels[index].1);
+            let above = levels.get(high + 1).map(|(_, volume)| *volume);
+
+            match (below, above) {
+                (Some(below), Some(above)) if above >= below => {
+                    high += 1;
+                    covered += above;
+                }
+                (Some(below), _) => {
+                    low -= 1;
+                    covered += below;
+                }
+                (None, Some(above)) => {
+                    high += 1;
+                    covered += above;
+                }
+                (None, None) => break,
+            }
+        }
+
+        Some(Self {
+            poc: levels[poc].0,
+            low: levels[low].0,
+            high: levels[high].0,
+        })
+    }
+}
+
+/// 面板最多保持加载的时间周期数，包括当前显示的
+pub const MAX_LOADED_TIMEFRAMES: usize = 3;
+
+/// K线面板切换离开的时间周期的序列，连同实时流一起保留，
+/// 切回时无需重新获取。当前显示的周期也计入
+/// [`MAX_LOADED_TIMEFRAMES`]，超出时丢弃最久未查看的那个。
+#[derive(Debug)]
+pub struct TimeframeCache<T> {
+    /// 最久未查看的在前
+    entries: Vec<(Timeframe, T)>,
+}
+
+impl<T> Default for TimeframeCache<T> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<T> TimeframeCache<T> {
+    /// 保存正在切换离开的时间周期的序列，返回为腾出空间
+    /// 而丢弃的时间周期
+    pub fn store(&mut self, timeframe: Timeframe, series: T) -> Vec<Timeframe> {
+        self.entries.retain(|(tf, _)| *tf != timeframe);
+        self.entries.push((timeframe, series));
+
+        let excess = self.entries.len().saturating_sub(MAX_LOADED_TIMEFRAMES - 1);
+        self.entries
+            .drain(..excess)
+            .map(|(timeframe, _)| timeframe)
+            .collect()
+    }
+
+    /// 取出正在切换到的时间周期的序列
+    pub fn take(&mut self, timeframe: Timeframe) -> Option<T> {
+        let index = self.entries.iter().position(|(tf, _)| *tf == timeframe)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    pub fn get_mut(&mut self, timeframe: Timeframe) -> Option<&mut T> {
+        self.entries
+            .iter_mut()
+            .find(|(tf, _)| *tf == timeframe)
+            .map(|(_, series)| series)
+    }
+
+    /// 已缓存的时间周期，最久未查看的在前
+    pub fn timeframes(&self) -> impl Iterator<Item = Timeframe> + '_ {
+        self.entries.iter().map(|(timeframe, _)| *timeframe)
+    }
+
+    /// 丢弃不在 `keep` 中的时间周期
+    pub fn retain(&mut self, keep: &[Timeframe]) {
+        self.entries.retain(|(tf, _)| keep.contains(tf));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STEP: f32 = 0.5;
+
+    fn price(value: f32) -> Price {
+        Price::from_f32(value).round_to_step(PriceStep::from_f32(STEP))
+    }
+
+    fn footprint(levels: &[(f32, f32, f32)]) -> KlineTrades {
+        let mut trades = KlineTrades::new();
+        for &(price, buy_qty, sell_qty) in levels {
+            for (qty, is_sell) in [(buy_qty, false), (sell_qty, true)] {
+                if qty > 0.0 {
+                    let trade = Trade {
+                        time: 0,
+                        is_sell,
+                        price: Price::from_f32(price),
+                        qty,
+                    };
+                    trades.add_trade_to_nearest_bin(&trade, PriceStep::from_f32(STEP));
+                }
+            }
+        }
+        trades
+    }
+
+    #[test]
+    fn value_area_grows_towards_heavier_side() {
+        let profile: FxHashMap<Price, (f32, f32)> = [
+            (98.0, 1.0),
+            (99.0, 2.0),
+            (100.0, 10.0),
+            (101.0, 4.0),
+            (102.0, 3.0),
+        ]
+        .into_iter()
+        .map(|(level, volume)| (Price::from_f32(level), (volume, 0.0)))
+        .collect();
+
+        // 需要 20 中的 14，101 压过 99 并入 POC 时即达到
+        let value_area = ValueArea::from_profile(&profile, 0.7).unwrap();
+        assert_eq!(value_area.poc, Price::from_f32(100.0));
+        assert_eq!(value_area.low, Price::from_f32(100.0));
+        assert_eq!(value_area.high, Price::from_f32(101.0));
+
+        let value_area = ValueArea::from_profile(&profile, 0.9).unwrap();
+        assert_eq!(value_area.low, Price::from_f32(99.0));
+        assert_eq!(value_area.high, Price::from_f32(102.0));
+
+        assert_eq!(ValueArea::from_profile(&FxHashMap::default(), 0.7), None);
+    }
+
+    #[test]
+    fn price_range_lookups_only_see_indexed_levels_inside() {
+        let mut trades = footprint(&[(99.0, 8.0, 0.0), (100.0, 2.0, 1.0), (101.5, 0.0, 5.0)]);
+
+        let inside = trades
+            .trades_in_range(price(101.0), price(100.0))
+            .map(|(price, _)| price.to_f32())
+            .collect::<Vec<_>>();
+        assert_eq!(inside, [100.0]);
+
+        assert_eq!(trades.max_qty_by(price(101.5), price(100.0), f32::max), 5.0);
+        assert_eq!(
+            trades.max_qty_by(price(101.0), price(99.0), |b, s| b + s),
+            8.0
+        );
+        // 颠倒的区间不包含任何东西
+        assert_eq!(trades.max_qty_by(price(99.0), price(101.5), f32::max), 0.0);
+
+        trades.clear();
+        assert_eq!(trades.trades_in_range(price(200.0), price(0.0)).count(), 0);
+    }
+
+    #[test]
+    fn stacked_imbalance_needs_min_stack_levels() {
+        // 100.5..=101.5 的买单是下一档卖单的 4 倍
+        let trades = footprint(&[
+            (100.0, 1.0, 1.0),
+            (100.5, 4.0, 1.0),
+            (101.0, 4.0, 1.0),
+            (101.5, 4.0, 1.0),
+            (102.0, 1.0, 1.0),
+        ]);
+        let step = PriceStep::from_f32(STEP);
+
+        let stacks = trades.stacked_imbalances(200, 3, step);
+        assert_eq!(
+            stacks,
+            vec![StackedImbalance {
+                low: price(100.5),
+                high: price(101.5),
+                levels: 3,
+                is_buy: true,
+            }]
+        );
+
+        assert!(trades.stacked_imbalances(200, 4, step).is_empty());
+    }
+
+    #[test]
+    fn stacked_imbalance_breaks_on_missing_level() {
+        // 每一档卖单都压过上一档买单，但 101.5 从未成交
+        let trades = footprint(&[
+            (100.0, 1.0, 4.0),
+            (100.5, 1.0, 4.0),
+            (101.0, 1.0, 4.0),
+            (102.0, 1.0, 4.0),
+            (102.5, 1.0, 4.0),
+            (103.0, 1.0, 1.0),
+        ]);
+        let step = PriceStep::from_f32(STEP);
+
+        let stacks = trades.stacked_imbalances(200, 2, step);
+        let ranges: Vec<(Price, Price, bool)> = stacks
+            .iter()
+            .map(|stack| (stack.low, stack.high, stack.is_buy))
+            .collect();
+        assert_eq!(
+            ranges,
+            vec![
+                (price(100.0), price(100.5), false),
+                (price(102.0), price(102.5), false)
+            ]
+        );
+
+        assert!(trades.stacked_imbalances(200, 4, step).is_empty());
+    }
+
+    #[test]
+    fn npoc_lookback_reads_bare_count() {
+        let study: FootprintStudy = serde_json::from_str(r#"{"NPoC":{"lookback":80}}"#).unwrap();
+        assert_eq!(
+            study,
+            FootprintStudy::NPoC {
+                lookback: NpocLookback::Fixed(80)
+            }
+        );
+
+        for lookback in [NpocLookback::Fixed(120), NpocLookback::VisibleRange] {
+            let json = serde_json::to_string(&lookback).unwrap();
+            assert_eq!(
+                serde_json::from_str::<NpocLookback>(&json).unwrap(),
+                lookback
+            );
+        }
+    }
+
+    #[test]
+    fn hybrid_option_carries_clamped_weight() {
+        assert_eq!(ClusterScaling::ALL, ClusterScaling::options(0.2));
+        assert_eq!(
+            ClusterScaling::options(1.5)[1],
+            ClusterScaling::Hybrid { weight: 1.0 }
+        );
+        assert_eq!(
+            ClusterScaling::options(0.35)[1].to_string(),
+            "Hybrid (weight: 0.35)"
+        );
+    }
+
+    #[test]
+    fn footprint_without_depth_layer_still_loads() {
+        let json = r#"{"Footprint":{"clusters":"BidAsk","studies":[]}}"#;
+        let kind: KlineChartKind = serde_json::from_str(json).unwrap();
+        assert_eq!(kind.depth_layer(), None);
+
+        let layered = KlineChartKind::Footprint {
+            clusters: ClusterKind::BidAsk,
+            scaling: ClusterScaling::default(),
+            studies: vec![],
+            depth_layer: Some(DepthLayer::default()),
+            cell_text: FootprintCellText::default(),
+        };
+        let json = serde_json::to_string(&layered).unwrap();
+        assert_eq!(
+            serde_json::from_str::<KlineChartKind>(&json).unwrap(),
+            layered
+        );
+    }
+
+    #[test]
+    fn cell_text_scales_by_the_displayed_metric() {
+        let group = GroupedTrades {
+            buy_qty: 6.0,
+            sell_qty: 2.0,
+            first_time: 0,
+            last_time: 0,
+            buy_count: 2,
+            sell_count: 4,
+        };
+
+        assert_eq!(FootprintCellText::Count.sides(&group), (2.0, 4.0));
+        assert_eq!(FootprintCellText::AvgSize.sides(&group), (3.0, 0.5));
+        assert_eq!(FootprintCellText::Delta.sides(&group), (4.0, 0.0));
+        assert_eq!(FootprintCellText::AvgSize.combined(&group), 8.0 / 6.0);
+
+        assert_eq!(
+            ClusterKind::BidAsk.cell_value(FootprintCellText::Count, &group),
+            4.0
+        );
+        assert_eq!(
+            ClusterKind::DeltaProfile.cell_value(FootprintCellText::Count, &group),
+            2.0
+        );
+        assert_eq!(
+            ClusterKind::VolumeProfile.cell_value(FootprintCellText::Volume, &group),
+            8.0
+        );
+
+        let json = r#"{"Footprint":{"clusters":"BidAsk","studies":[]}}"#;
+        let kind: KlineChartKind = serde_json::from_str(json).unwrap();
+        assert!(matches!(
+            kind,
+            KlineChartKind::Footprint {
+                cell_text: FootprintCellText::Volume,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn compare_overlays_fill_slots_and_load_legacy_single_ticker() {
+        let ticker = |symbol: &str| {
+            TickerInfo::new(
+                exchange::Ticker::new(symbol, exchange::adapter::Exchange::BinanceLinear),
+                0.1,
+                0.001,
+                None,
+            )
+        };
+
+        let mut overlays = CompareOverlays::default();
+        for symbol in ["ETHUSDT", "SOLUSDT", "XRPUSDT"] {
+            assert!(overlays.add(ticker(symbol)));
+        }
+        assert!(!overlays.add(ticker("DOGEUSDT")));
+        assert!(overlays.is_full());
+
+        overlays.remove(&ticker("ETHUSDT"));
+        assert!(overlays.add(ticker("DOGEUSDT")));
+        let colors: Vec<[u8; 4]> = overlays.iter().map(|c| c.color).collect();
+        assert_eq!(colors[2], CompareOverlays::COLORS[0]);
+
+        let json = serde_json::to_string(&overlays).unwrap();
+        assert_eq!(
+            serde_json::from_str::<CompareOverlays>(&json).unwrap(),
+            overlays
+        );
+
+        let legacy = serde_json::to_string(&Some(ticker("ETHUSDT"))).unwrap();
+        let loaded = serde_json::from_str::<CompareOverlays>(&legacy).unwrap();
+        assert_eq!(
+            loaded.tickers().collect::<Vec<_>>(),
+            vec![ticker("ETHUSDT")]
+        );
+        assert!(
+            serde_json::from_str::<CompareOverlays>("null")
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn suggested_tick_size_is_a_multiple_near_target_rows() {
+        // 60000 的 1% 分成 30 行是 20，已经是 0.1 的倍数
+        assert!((suggest_tick_size(60_000.0, 0.1, 30) - 20.0).abs() < 1e-3);
+        // 3000 的 1% 分成 30 行是 1，向上取整到 0.25 的网格
+        assert!((suggest_tick_size(3_000.0, 0.25, 30) - 1.0).abs() < 1e-3);
+        assert!((suggest_tick_size(3_100.0, 0.25, 30) - 1.25).abs() < 1e-3);
+        // 不会比交易所 tick 更细
+        assert!((suggest_tick_size(0.5, 0.01, 30) - 0.01).abs() < 1e-6);
+        assert!((suggest_tick_size(0.0, 0.01, 30) - 0.01).abs() < 1e-6);
+    }
+
+    #[test]
+    fn rolling_daily_average_drops_bars_older_than_a_day() {
+        let hour = 60 * 60 * 1000;
+        let bars = [
+            (0, 10.0),
+            (12 * hour, 30.0),
+            (24 * hour, 50.0),
+            (30 * hour, 10.0),
+        ];
+
+        let avg = rolling_daily_average(bars.iter().map(|&(t, v)| (t, t, v)));
+
+        assert_eq!(avg[&0], 10.0);
+        assert_eq!(avg[&(12 * hour)], 20.0);
+        // 第一根K线正好是一天前的，已离开窗口
+        assert_eq!(avg[&(24 * hour)], 40.0);
+        assert_eq!(avg[&(30 * hour)], 30.0);
+        assert_eq!(format_volume_percent(342.4), "+342%");
+    }
+
+    #[test]
+    fn timeframe_cache_drops_the_least_recently_viewed() {
+        let mut cache = TimeframeCache::default();
+
+        // 先显示 1m，然后 5m，再 15m
+        assert!(cache.store(Timeframe::M1, "1m").is_empty());
+        assert!(cache.store(Timeframe::M5, "5m").is_empty());
+
+        // 切回 1m，此时 5m 成为最久未查看的
+        assert_eq!(cache.take(Timeframe::M1), Some("1m"));
+        assert!(cache.store(Timeframe::M15, "15m").is_empty());
+
+        // 从 1m 切到第四个周期 1h，挤掉了 5m
+        assert_eq!(cache.store(Timeframe::M1, "1m"), [Timeframe::M5]);
+
+        assert_eq!(
+            cache.timeframes().collect::<Vec<_>>(),
+            [Timeframe::M15, Timeframe::M1]
+        );
+        assert!(cache.take(Timeframe::M5).is_none());
+    }
+}
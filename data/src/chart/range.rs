@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+/// Bar sizes offered in the range chart settings, in ticks
+pub const RANGE_TICKS_OPTIONS: [u32; 6] = [4, 10, 20, 50, 100, 200];
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Config {
+    /// How many ticks price has to move from a bar's open before a new bar starts
+    pub range_ticks: u32,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self { range_ticks: 10 }
+    }
+}
@@ -0,0 +1,206 @@
+use exchange::depth::Depth;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// How long spread samples are kept, in market time
+pub const HISTORY_MS: u64 = 15 * 60 * 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Config {
+    /// Samples the rolling average line is computed over
+    pub avg_periods: usize,
+    pub unit: SpreadUnit,
+}
+
+impl Config {
+    pub const AVG_PERIODS_RANGE: std::ops::RangeInclusive<usize> = 5..=200;
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            avg_periods: 20,
+            unit: SpreadUnit::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub enum SpreadUnit {
+    /// Multiples of the ticker's min tick size
+    #[default]
+    Ticks,
+    /// Basis points of the mid price, comparable across tickers
+    Bps,
+}
+
+impl SpreadUnit {
+    pub const ALL: [SpreadUnit; 2] = [SpreadUnit::Ticks, SpreadUnit::Bps];
+}
+
+impl std::fmt::Display for SpreadUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpreadUnit::Ticks => write!(f, "Ticks"),
+            SpreadUnit::Bps => write!(f, "Bps of mid"),
+        }
+    }
+}
+
+/// Best ask minus best bid of one depth update, with the mid price it was quoted around
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpreadSample {
+    pub spread: f32,
+    pub mid: f32,
+}
+
+impl SpreadSample {
+    pub fn value(&self, unit: SpreadUnit, tick_size: f32) -> f32 {
+        match unit {
+            SpreadUnit::Ticks => self.spread / tick_size.max(f32::EPSILON),
+            SpreadUnit::Bps => self.spread / self.mid.max(f32::EPSILON) * 10_000.0,
+        }
+    }
+}
+
+/// Current, mean and max spread of a run of samples
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpreadSummary {
+    pub current: f32,
+    pub average: f32,
+    pub max: f32,
+}
+
+/// Spread of every depth update over the last `HISTORY_MS`, keyed by update time
+#[derive(Debug, Default)]
+pub struct SpreadSeries {
+    samples: BTreeMap<u64, SpreadSample>,
+}
+
+impl SpreadSeries {
+    pub fn insert_depth(&mut self, time: u64, depth: &Depth) {
+        let (Some((ask, _)), Some((bid, _))) =
+            (depth.asks.first_key_value(), depth.bids.last_key_value())
+        else {
+            return;
+        };
+
+        if ask < bid {
+            return;
+        }
+
+        self.samples.insert(
+            time,
+            SpreadSample {
+                spread: (*ask - *bid).to_f32(),
+                mid: ((*ask + *bid) / 2).to_f32(),
+            },
+        );
+
+        let start = time.saturating_sub(HISTORY_MS);
+        while self
+            .samples
+            .first_key_value()
+            .is_some_and(|(oldest, _)| *oldest < start)
+        {
+            self.samples.pop_first();
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Samples in `unit`, oldest first
+    pub fn values(&self, unit: SpreadUnit, tick_size: f32) -> Vec<(u64, f32)> {
+        self.samples
+            .iter()
+            .map(|(time, sample)| (*time, sample.value(unit, tick_size)))
+            .collect()
+    }
+}
+
+/// Mean of the last `periods` values at each point, shorter at the start of the series
+pub fn rolling_average(values: &[(u64, f32)], periods: usize) -> Vec<(u64, f32)> {
+    let periods = periods.max(1);
+    let mut sum = 0.0;
+
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, (time, value))| {
+            sum += value;
+            if i >= periods {
+                sum -= values[i - periods].1;
+            }
+            (*time, sum / (i + 1).min(periods) as f32)
+        })
+        .collect()
+}
+
+pub fn summary(values: &[(u64, f32)]) -> Option<SpreadSummary> {
+    let (_, current) = values.last()?;
+    let total: f32 = values.iter().map(|(_, value)| value).sum();
+    let max = values
+        .iter()
+        .map(|(_, value)| *value)
+        .fold(f32::MIN, f32::max);
+
+    Some(SpreadSummary {
+        current: *current,
+        average: total / values.len() as f32,
+        max,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exchange::util::Price;
+
+    // whole cents, so prices are exact in atomic units
+    fn price(value: f32) -> Price {
+        Price::from_units((value * 100.0).round() as i64 * 1_000_000)
+    }
+
+    fn depth(bid: f32, ask: f32) -> Depth {
+        Depth {
+            bids: [(price(bid - 1.0), 1.0), (price(bid), 1.0)].into(),
+            asks: [(price(ask), 1.0), (price(ask + 1.0), 1.0)].into(),
+        }
+    }
+
+    #[test]
+    fn spread_in_ticks_and_bps_of_mid() {
+        let mut series = SpreadSeries::default();
+        series.insert_depth(1_000, &depth(99.5, 100.5));
+
+        assert_eq!(series.values(SpreadUnit::Ticks, 0.5), vec![(1_000, 2.0)]);
+        assert_eq!(series.values(SpreadUnit::Bps, 0.5), vec![(1_000, 100.0)]);
+    }
+
+    #[test]
+    fn old_samples_fall_out_of_history() {
+        let mut series = SpreadSeries::default();
+        series.insert_depth(0, &depth(99.0, 100.0));
+        series.insert_depth(HISTORY_MS, &depth(99.0, 101.0));
+        series.insert_depth(HISTORY_MS + 1, &depth(99.0, 102.0));
+
+        let values = series.values(SpreadUnit::Ticks, 1.0);
+        assert_eq!(values, vec![(HISTORY_MS, 2.0), (HISTORY_MS + 1, 3.0)]);
+    }
+
+    #[test]
+    fn rolling_average_and_summary() {
+        let values = [(0, 1.0), (1, 3.0), (2, 5.0), (3, 1.0)];
+
+        let avg = rolling_average(&values, 2);
+        assert_eq!(avg, vec![(0, 1.0), (1, 2.0), (2, 4.0), (3, 3.0)]);
+
+        let summary = summary(&values).unwrap();
+        assert_eq!(summary.current, 1.0);
+        assert_eq!(summary.average, 2.5);
+        assert_eq!(summary.max, 5.0);
+    }
+}
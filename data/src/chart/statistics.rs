@@ -0,0 +1,58 @@
+/// Pearson correlation coefficient of two equally long series, from -1 to +1
+///
+/// Only the common prefix is used if the lengths differ. `NaN` when fewer than two points
+/// are given or either series is constant.
+pub fn pearson_correlation(xs: &[f32], ys: &[f32]) -> f32 {
+    let len = xs.len().min(ys.len());
+    if len < 2 {
+        return f32::NAN;
+    }
+    let (xs, ys) = (&xs[..len], &ys[..len]);
+
+    let mean = |values: &[f32]| values.iter().map(|&v| f64::from(v)).sum::<f64>() / len as f64;
+    let (mean_x, mean_y) = (mean(xs), mean(ys));
+
+    let (mut cov, mut var_x, mut var_y) = (0.0f64, 0.0f64, 0.0f64);
+    for (&x, &y) in xs.iter().zip(ys) {
+        let (dx, dy) = (f64::from(x) - mean_x, f64::from(y) - mean_y);
+        cov += dx * dy;
+        var_x += dx * dx;
+        var_y += dy * dy;
+    }
+
+    let denominator = (var_x * var_y).sqrt();
+    if denominator == 0.0 {
+        return f32::NAN;
+    }
+    (cov / denominator).clamp(-1.0, 1.0) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn perfectly_correlated_and_inverse_series() {
+        let xs = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let doubled = [2.0, 4.0, 6.0, 8.0, 10.0];
+        let inverse = [10.0, 8.0, 6.0, 4.0, 2.0];
+
+        assert!((pearson_correlation(&xs, &doubled) - 1.0).abs() < 1e-6);
+        assert!((pearson_correlation(&xs, &inverse) + 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn correlation_of_known_series() {
+        let xs = [1.0, 2.0, 3.0, 4.0];
+        let ys = [2.0, 1.0, 4.0, 3.0];
+
+        // cov 3, both variances 5
+        assert!((pearson_correlation(&xs, &ys) - 0.6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn undefined_for_constant_or_short_series() {
+        assert!(pearson_correlation(&[1.0, 1.0, 1.0], &[1.0, 2.0, 3.0]).is_nan());
+        assert!(pearson_correlation(&[1.0], &[2.0]).is_nan());
+    }
+}
@@ -0,0 +1,306 @@
+use exchange::Trade;
+use exchange::depth::Depth;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Levels per side summed into the bid/ask ratio, counted from the best price
+const DEPTH_LEVELS: usize = 20;
+/// Trades at least this many times the window's average size count as large
+const LARGE_TRADE_FACTOR: f32 = 2.0;
+/// Samples kept for the sparklines, one per second of market time
+pub const HISTORY_LEN: usize = 120;
+
+const SAMPLE_INTERVAL_MS: u64 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Config {
+    /// Length of the rolling window the trade metrics are computed over
+    pub window_secs: u64,
+}
+
+impl Config {
+    pub const WINDOW_SECS_RANGE: std::ops::RangeInclusive<u64> = 10..=600;
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self { window_secs: 60 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    BidAskRatio,
+    DeltaPerSec,
+    AvgTradeSize,
+    TradesPerMin,
+    LargeSmallRatio,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 5] = [
+        Metric::BidAskRatio,
+        Metric::DeltaPerSec,
+        Metric::AvgTradeSize,
+        Metric::TradesPerMin,
+        Metric::LargeSmallRatio,
+    ];
+
+    pub fn value(&self, stats: &Stats) -> Option<f32> {
+        match self {
+            Metric::BidAskRatio => stats.bid_ask_ratio,
+            Metric::DeltaPerSec => stats.delta_per_sec,
+            Metric::AvgTradeSize => stats.avg_trade_size,
+            Metric::TradesPerMin => stats.trades_per_min,
+            Metric::LargeSmallRatio => stats.large_small_ratio,
+        }
+    }
+}
+
+impl std::fmt::Display for Metric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Metric::BidAskRatio => "Bid/Ask ratio",
+            Metric::DeltaPerSec => "Delta/sec",
+            Metric::AvgTradeSize => "Avg trade size",
+            Metric::TradesPerMin => "Trades/min",
+            Metric::LargeSmallRatio => "Large/small",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// Metrics of one moment, `None` where there is nothing to compute them from yet
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Stats {
+    pub bid_ask_ratio: Option<f32>,
+    pub delta_per_sec: Option<f32>,
+    pub avg_trade_size: Option<f32>,
+    pub trades_per_min: Option<f32>,
+    pub large_small_ratio: Option<f32>,
+}
+
+/// Trade and depth metrics over a rolling window of market time.
+///
+/// The window trails the latest trade or depth timestamp seen, so it doesn't depend on
+/// the local clock.
+pub struct RollingStats {
+    window_ms: u64,
+    trades: VecDeque<Trade>,
+    buy_qty: f32,
+    sell_qty: f32,
+    bid_ask_ratio: Option<f32>,
+    first_time: Option<u64>,
+    latest_time: u64,
+    last_sample: Option<u64>,
+    current: Stats,
+    history: VecDeque<Stats>,
+}
+
+impl RollingStats {
+    pub fn new(config: Config) -> Self {
+        Self {
+            window_ms: config.window_secs.max(1) * 1000,
+            trades: VecDeque::new(),
+            buy_qty: 0.0,
+            sell_qty: 0.0,
+            bid_ask_ratio: None,
+            first_time: None,
+            latest_time: 0,
+            last_sample: None,
+            current: Stats::default(),
+            history: VecDeque::with_capacity(HISTORY_LEN),
+        }
+    }
+
+    pub fn set_window(&mut self, window_secs: u64) {
+        self.window_ms = window_secs.max(1) * 1000;
+        self.evict();
+        self.current = self.compute();
+    }
+
+    pub fn insert_trades(&mut self, buffer: &[Trade]) {
+        for trade in buffer {
+            if trade.is_sell {
+                self.sell_qty += trade.qty;
+            } else {
+                self.buy_qty += trade.qty;
+            }
+            self.trades.push_back(*trade);
+            self.advance(trade.time);
+        }
+
+        self.evict();
+        self.maybe_sample();
+    }
+
+    pub fn insert_depth(&mut self, time: u64, depth: &Depth) {
+        let bid_qty: f32 = depth.bids.values().rev().take(DEPTH_LEVELS).sum();
+        let ask_qty: f32 = depth.asks.values().take(DEPTH_LEVELS).sum();
+
+        if ask_qty > 0.0 {
+            self.bid_ask_ratio = Some(bid_qty / ask_qty);
+        }
+        self.advance(time);
+
+        self.evict();
+        self.maybe_sample();
+    }
+
+    pub fn current(&self) -> &Stats {
+        &self.current
+    }
+
+    /// Values of `metric` over the kept samples, oldest first, gaps left out
+    pub fn history(&self, metric: Metric) -> Vec<f32> {
+        self.history
+            .iter()
+            .filter_map(|stats| metric.value(stats))
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.first_time.is_none()
+    }
+
+    fn advance(&mut self, time: u64) {
+        self.first_time.get_or_insert(time);
+        self.latest_time = self.latest_time.max(time);
+    }
+
+    fn evict(&mut self) {
+        let start = self.latest_time.saturating_sub(self.window_ms);
+
+        while let Some(trade) = self.trades.front() {
+            if trade.time >= start {
+                break;
+            }
+            if trade.is_sell {
+                self.sell_qty -= trade.qty;
+            } else {
+                self.buy_qty -= trade.qty;
+            }
+            self.trades.pop_front();
+        }
+
+        if self.trades.is_empty() {
+            self.buy_qty = 0.0;
+            self.sell_qty = 0.0;
+        }
+    }
+
+    fn maybe_sample(&mut self) {
+        let due = self
+            .last_sample
+            .is_none_or(|last| self.latest_time >= last + SAMPLE_INTERVAL_MS);
+        if !due {
+            return;
+        }
+
+        self.current = self.compute();
+        self.last_sample = Some(self.latest_time);
+
+        if self.history.len() == HISTORY_LEN {
+            self.history.pop_front();
+        }
+        self.history.push_back(self.current);
+    }
+
+    fn compute(&self) -> Stats {
+        // before the window fills, rates are over the time actually covered
+        let covered_ms = self
+            .first_time
+            .map_or(0, |first| self.latest_time.saturating_sub(first))
+            .min(self.window_ms);
+        let covered_secs = (covered_ms as f32 / 1000.0).max(1.0);
+
+        let count = self.trades.len();
+        let total_qty = self.buy_qty + self.sell_qty;
+
+        let (avg_trade_size, large_small_ratio) = if count > 0 {
+            let avg = total_qty / count as f32;
+            let large = self
+                .trades
+                .iter()
+                .filter(|trade| trade.qty >= avg * LARGE_TRADE_FACTOR)
+                .count();
+            let small = count - large;
+
+            let ratio = (small > 0).then(|| large as f32 / small as f32);
+            (Some(avg), ratio)
+        } else {
+            (None, None)
+        };
+
+        let started = self.first_time.is_some();
+
+        Stats {
+            bid_ask_ratio: self.bid_ask_ratio,
+            delta_per_sec: started.then(|| (self.buy_qty - self.sell_qty) / covered_secs),
+            avg_trade_size,
+            trades_per_min: started.then(|| count as f32 * 60.0 / covered_secs),
+            large_small_ratio,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exchange::util::Price;
+
+    fn trade(time: u64, qty: f32, is_sell: bool) -> Trade {
+        Trade {
+            time,
+            is_sell,
+            price: Price::from_f32(100.0),
+            qty,
+        }
+    }
+
+    #[test]
+    fn trade_metrics_over_rolling_window() {
+        let mut stats = RollingStats::new(Config { window_secs: 10 });
+
+        // the first 4 trades fall out of the window once time reaches 25s
+        let trades = [
+            trade(0, 1.0, false),
+            trade(2_000, 1.0, true),
+            trade(4_000, 1.0, false),
+            trade(8_000, 1.0, false),
+            trade(15_000, 1.0, false),
+            trade(20_000, 1.0, true),
+            trade(24_000, 7.0, false),
+            trade(25_000, 1.0, false),
+        ];
+        stats.insert_trades(&trades);
+
+        let current = stats.current();
+        assert_eq!(current.avg_trade_size, Some(2.5));
+        assert_eq!(current.delta_per_sec, Some(0.8));
+        assert_eq!(current.trades_per_min, Some(24.0));
+        assert_eq!(current.large_small_ratio, Some(1.0 / 3.0));
+    }
+
+    #[test]
+    fn bid_ask_ratio_from_top_levels() {
+        let mut stats = RollingStats::new(Config::default());
+
+        let side = |levels: &[(f32, f32)]| {
+            levels
+                .iter()
+                .map(|(price, qty)| (Price::from_f32(*price), *qty))
+                .collect()
+        };
+        let depth = Depth {
+            bids: side(&[(99.0, 3.0), (98.0, 3.0)]),
+            asks: side(&[(101.0, 2.0), (102.0, 2.0)]),
+        };
+        stats.insert_depth(1_000, &depth);
+
+        assert_eq!(stats.current().bid_ask_ratio, Some(1.5));
+        assert_eq!(stats.current().trades_per_min, Some(0.0));
+        assert_eq!(stats.history(Metric::BidAskRatio), vec![1.5]);
+    }
+}
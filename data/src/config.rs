@@ -28,3 +28,206 @@ impl From<ScaleFactor> for f32 {
         value.0
     }
 }
+
+pub const MIN_TOAST_DURATION_MS: u64 = 2_000;
+pub const MAX_TOAST_DURATION_MS: u64 = 30_000;
+pub const MAX_TOAST_COUNT: usize = 10;
+
+/// Where and for how long notifications are shown
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct ToastConfig {
+    pub position: ToastPosition,
+    pub duration_ms: u64,
+    /// Oldest notifications are dismissed once this many are shown
+    pub max_count: usize,
+}
+
+impl Default for ToastConfig {
+    fn default() -> Self {
+        Self {
+            position: ToastPosition::default(),
+            duration_ms: 8_000,
+            max_count: 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Default)]
+pub enum ToastPosition {
+    #[default]
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    TopCenter,
+}
+
+impl ToastPosition {
+    pub const ALL: [ToastPosition; 5] = [
+        ToastPosition::TopLeft,
+        ToastPosition::TopCenter,
+        ToastPosition::TopRight,
+        ToastPosition::BottomLeft,
+        ToastPosition::BottomRight,
+    ];
+
+    pub fn is_bottom(self) -> bool {
+        matches!(self, ToastPosition::BottomLeft | ToastPosition::BottomRight)
+    }
+
+    /// Top corner on the sidebar's side, where notifications showed before they could be moved
+    pub fn beside(sidebar: sidebar::Position) -> Self {
+        match sidebar {
+            sidebar::Position::Left => ToastPosition::TopLeft,
+            sidebar::Position::Right => ToastPosition::TopRight,
+        }
+    }
+}
+
+impl std::fmt::Display for ToastPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ToastPosition::TopLeft => write!(f, "Top left"),
+            ToastPosition::TopRight => write!(f, "Top right"),
+            ToastPosition::BottomLeft => write!(f, "Bottom left"),
+            ToastPosition::BottomRight => write!(f, "Bottom right"),
+            ToastPosition::TopCenter => write!(f, "Top center"),
+        }
+    }
+}
+
+/// How long downloaded market data archives are kept before cleanup removes them
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct DataRetention {
+    pub days: u32,
+}
+
+impl DataRetention {
+    pub const DAYS_RANGE: std::ops::RangeInclusive<u32> = 1..=60;
+}
+
+impl Default for DataRetention {
+    fn default() -> Self {
+        Self { days: 4 }
+    }
+}
+
+/// How often the layout state is saved in the background, besides on exit
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct Autosave {
+    pub interval_secs: u32,
+}
+
+impl Autosave {
+    pub const INTERVAL_RANGE: std::ops::RangeInclusive<u32> = 15..=600;
+
+    pub fn interval(&self) -> std::time::Duration {
+        let secs = self
+            .interval_secs
+            .clamp(*Self::INTERVAL_RANGE.start(), *Self::INTERVAL_RANGE.end());
+        std::time::Duration::from_secs(u64::from(secs))
+    }
+}
+
+impl Default for Autosave {
+    fn default() -> Self {
+        Self { interval_secs: 60 }
+    }
+}
+
+/// Coalesces the depth updates of a stream to at most one per interval, with the trades
+/// in between carried over. 0 handles each update as it arrives.
+///
+/// Streams push every 100ms. For a minute of a 400 level book with 15 trades per push,
+/// heatmap and footprint work drops from 18.9ms to 7.9ms at 300ms and to 2.3ms at 1000ms,
+/// trades still being handled one by one (`depth_throttle` bench of the data crate)
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct DepthThrottle {
+    pub interval_ms: u32,
+}
+
+impl DepthThrottle {
+    pub const INTERVAL_RANGE: std::ops::RangeInclusive<u32> = 0..=1000;
+
+    /// `None` when updates aren't throttled
+    pub fn interval(&self) -> Option<std::time::Duration> {
+        let ms = self.interval_ms.min(*Self::INTERVAL_RANGE.end());
+        (ms > 0).then(|| std::time::Duration::from_millis(u64::from(ms)))
+    }
+}
+
+/// Bars a time based chart keeps in memory. Past this, the oldest bars left of the visible
+/// and prefetched range are dropped, scrolling back to them fetches them again
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct SeriesRetention {
+    pub max_bars: u32,
+}
+
+impl SeriesRetention {
+    pub const MAX_BARS_RANGE: std::ops::RangeInclusive<u32> = 10_000..=500_000;
+}
+
+impl Default for SeriesRetention {
+    fn default() -> Self {
+        Self { max_bars: 100_000 }
+    }
+}
+
+static SERIES_RETENTION: std::sync::RwLock<SeriesRetention> =
+    std::sync::RwLock::new(SeriesRetention { max_bars: 100_000 });
+
+/// Sets the cap every chart prunes its series to, kept in sync with the user's setting
+pub fn set_series_retention(retention: SeriesRetention) {
+    if let Ok(mut current) = SERIES_RETENTION.write() {
+        *current = retention;
+    }
+}
+
+pub fn series_retention() -> SeriesRetention {
+    SERIES_RETENTION
+        .read()
+        .map(|current| *current)
+        .unwrap_or_default()
+}
+
+/// Whether switching a pane's ticker restores the zoom it last had on that ticker and
+/// chart kind, instead of starting from the defaults
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct ZoomMemory {
+    pub per_ticker: bool,
+}
+
+impl Default for ZoomMemory {
+    fn default() -> Self {
+        Self { per_ticker: true }
+    }
+}
+
+/// Step sizes for navigating the focused chart with the keyboard
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct KeyboardNav {
+    /// Arrow keys shift the view by this fraction of the visible range
+    pub pan_step: f32,
+    /// `+` / `-` grow or shrink the cell width by this fraction
+    pub zoom_step: f32,
+}
+
+impl KeyboardNav {
+    pub const STEP_RANGE: std::ops::RangeInclusive<f32> = 0.05..=0.5;
+}
+
+impl Default for KeyboardNav {
+    fn default() -> Self {
+        Self {
+            pan_step: 0.1,
+            zoom_step: 0.1,
+        }
+    }
+}
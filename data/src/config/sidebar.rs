@@ -1,7 +1,16 @@
+use exchange::Ticker;
 use serde::{Deserialize, Deserializer, Serialize};
 
 use crate::tickers_table;
 
+/// Width of the icon column plus its gap to the tickers list
+pub const ICON_COLUMN_WIDTH: f32 = 40.0;
+pub const MIN_WIDTH: f32 = 200.0;
+pub const MAX_WIDTH: f32 = 520.0;
+/// Dragging the sidebar narrower than this collapses it to just its icons
+pub const COLLAPSE_WIDTH: f32 = 120.0;
+const DEFAULT_WIDTH: f32 = 240.0;
+
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(default)]
 pub struct Sidebar {
@@ -10,6 +19,10 @@ pub struct Sidebar {
     pub active_menu: Option<Menu>,
     #[serde(default)]
     pub tickers_table: Option<tickers_table::Settings>,
+    #[serde(default)]
+    pub watchlist: Vec<WatchlistGroup>,
+    /// Width of the whole sidebar while its tickers list is open
+    pub width: f32,
 }
 
 impl Sidebar {
@@ -21,6 +34,21 @@ impl Sidebar {
         self.position = position;
     }
 
+    /// Applies the width the resize handle was dragged to, returns whether
+    /// the tickers list should stay open
+    pub fn drag_width(&mut self, width: f32) -> bool {
+        if width < COLLAPSE_WIDTH {
+            return false;
+        }
+
+        self.width = width.clamp(MIN_WIDTH, MAX_WIDTH);
+        true
+    }
+
+    pub fn list_width(&self) -> f32 {
+        self.width.clamp(MIN_WIDTH, MAX_WIDTH) - ICON_COLUMN_WIDTH
+    }
+
     pub fn is_menu_active(&self, menu: Menu) -> bool {
         self.active_menu == Some(menu)
     }
@@ -28,6 +56,58 @@ impl Sidebar {
     pub fn sync_tickers_table_settings(&mut self, settings: &tickers_table::Settings) {
         self.tickers_table = Some(settings.clone());
     }
+
+    pub fn create_group(&mut self, name: &str) {
+        let name = match name.trim() {
+            "" => format!("Group {}", self.watchlist.len() + 1),
+            trimmed => trimmed.to_string(),
+        };
+
+        self.watchlist.push(WatchlistGroup::new(name));
+    }
+
+    pub fn rename_group(&mut self, index: usize, name: &str) {
+        let name = name.trim();
+
+        if let Some(group) = self.watchlist.get_mut(index)
+            && !name.is_empty()
+        {
+            group.name = name.to_string();
+        }
+    }
+
+    pub fn delete_group(&mut self, index: usize) {
+        if index < self.watchlist.len() {
+            self.watchlist.remove(index);
+        }
+    }
+
+    /// Moves `ticker` into the group at `index`, a ticker belongs to at most one group
+    pub fn move_ticker_to_group(&mut self, ticker: Ticker, index: usize) {
+        if index >= self.watchlist.len() {
+            return;
+        }
+
+        self.remove_ticker_from_groups(&ticker);
+        self.watchlist[index].tickers.push(ticker);
+    }
+
+    pub fn remove_ticker_from_groups(&mut self, ticker: &Ticker) {
+        for group in &mut self.watchlist {
+            group.tickers.retain(|t| t != ticker);
+        }
+    }
+
+    /// Moves the group at `from` so that it ends up before the group previously at `to`
+    pub fn reorder_group(&mut self, from: usize, to: usize) {
+        if from == to || from >= self.watchlist.len() {
+            return;
+        }
+
+        let group = self.watchlist.remove(from);
+        let to = if from < to { to - 1 } else { to };
+        self.watchlist.insert(to.min(self.watchlist.len()), group);
+    }
 }
 
 impl Default for Sidebar {
@@ -36,6 +116,8 @@ impl Default for Sidebar {
             position: Position::Left,
             active_menu: None,
             tickers_table: None,
+            watchlist: vec![],
+            width: DEFAULT_WIDTH,
         }
     }
 }
@@ -47,6 +129,24 @@ where
     Sidebar::deserialize(deserializer).or(Ok(Sidebar::default()))
 }
 
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct WatchlistGroup {
+    pub name: String,
+    pub tickers: Vec<Ticker>,
+    #[serde(default)]
+    pub collapsed: bool,
+}
+
+impl WatchlistGroup {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            tickers: vec![],
+            collapsed: false,
+        }
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Copy, Deserialize, Serialize)]
 pub enum Position {
     #[default]
@@ -69,4 +169,27 @@ pub enum Menu {
     Settings,
     Audio,
     ThemeEditor,
+    Replay,
+    Connections,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dragging_narrow_collapses_and_keeps_last_width() {
+        let mut sidebar: Sidebar = serde_json::from_str(r#"{"position":"Right"}"#).unwrap();
+        assert_eq!(sidebar.list_width(), DEFAULT_WIDTH - ICON_COLUMN_WIDTH);
+
+        assert!(sidebar.drag_width(1000.0));
+        assert_eq!(sidebar.width, MAX_WIDTH);
+
+        assert!(sidebar.drag_width(150.0));
+        assert_eq!(sidebar.width, MIN_WIDTH);
+
+        assert!(sidebar.drag_width(300.0));
+        assert!(!sidebar.drag_width(COLLAPSE_WIDTH - 1.0));
+        assert_eq!(sidebar.width, 300.0);
+    }
 }
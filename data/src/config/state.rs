@@ -1,6 +1,9 @@
-use super::ScaleFactor;
 use super::sidebar::Sidebar;
 use super::timezone::UserTimezone;
+use super::{
+    Autosave, DataRetention, DepthThrottle, KeyboardNav, ScaleFactor, SeriesRetention, ToastConfig,
+    ZoomMemory,
+};
 use crate::i18n::Language;
 use crate::layout::WindowSpec;
 use crate::{AudioStream, Layout, Theme};
@@ -16,6 +19,8 @@ pub struct Layouts {
 #[derive(Default, Clone, Deserialize, Serialize)]
 #[serde(default)]
 pub struct State {
+    /// Format of the file, files written before it was versioned read as 0
+    pub version: u32,
     pub layout_manager: Layouts,
     pub selected_theme: Theme,
     pub custom_theme: Option<Theme>,
@@ -27,9 +32,21 @@ pub struct State {
     pub trade_fetch_enabled: bool,
     pub size_in_quote_ccy: exchange::SizeUnit,
     pub language: Language,
+    pub sync_crosshair: bool,
+    /// `None` in files saved before notifications were configurable
+    pub toast_cfg: Option<ToastConfig>,
+    pub keyboard_nav: KeyboardNav,
+    pub data_retention: DataRetention,
+    pub autosave: Autosave,
+    pub depth_throttle: DepthThrottle,
+    pub series_retention: SeriesRetention,
+    pub zoom_memory: ZoomMemory,
 }
 
 impl State {
+    /// Bumped when a change to the format can't be read by older versions
+    pub const VERSION: u32 = 1;
+
     pub fn from_parts(
         layout_manager: Layouts,
         selected_theme: Theme,
@@ -40,8 +57,17 @@ impl State {
         scale_factor: ScaleFactor,
         audio_cfg: AudioStream,
         volume_size_unit: exchange::SizeUnit,
+        sync_crosshair: bool,
+        toast_cfg: ToastConfig,
+        keyboard_nav: KeyboardNav,
+        data_retention: DataRetention,
+        autosave: Autosave,
+        depth_throttle: DepthThrottle,
+        series_retention: SeriesRetention,
+        zoom_memory: ZoomMemory,
     ) -> Self {
         State {
+            version: Self::VERSION,
             layout_manager,
             selected_theme: Theme(selected_theme.0),
             custom_theme: custom_theme.map(|t| Theme(t.0)),
@@ -53,6 +79,14 @@ impl State {
             trade_fetch_enabled: exchange::fetcher::is_trade_fetch_enabled(),
             size_in_quote_ccy: volume_size_unit,
             language: Language::English,
+            sync_crosshair,
+            toast_cfg: Some(toast_cfg),
+            keyboard_nav,
+            data_retention,
+            autosave,
+            depth_throttle,
+            series_retention,
+            zoom_memory,
         }
     }
 }
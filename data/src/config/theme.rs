@@ -11,6 +11,7 @@ use palette::{
     rgb::{Rgb, Rgba},
 };
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 
 #[derive(Debug, Clone)]
 pub struct Theme(pub iced_core::Theme);
@@ -160,6 +161,90 @@ impl<'de> Deserialize<'de> for Theme {
     }
 }
 
+const PALETTE_FIELDS: [&str; 6] = [
+    "background",
+    "text",
+    "primary",
+    "success",
+    "danger",
+    "warning",
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum ThemeImportError {
+    #[error("Failed to read theme file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Theme file is not valid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Theme file is missing `{0}`")]
+    MissingField(String),
+    #[error("Theme field `{field}` is out of range: {value} (expected 0.0 to 1.0)")]
+    OutOfRange { field: String, value: f64 },
+}
+
+/// Writes the palette of `theme` to `path` in the same shape the saved state uses.
+pub fn export_theme(theme: &iced_core::Theme, path: &Path) -> serde_json::Result<()> {
+    let ser_theme = SerTheme {
+        name: "custom".to_string(),
+        palette: Some(theme.palette()),
+    };
+
+    let file = std::fs::File::create(path).map_err(serde_json::Error::io)?;
+    serde_json::to_writer_pretty(file, &ser_theme)
+}
+
+/// Reads a theme written by [`export_theme`], checking every palette color before building it.
+pub fn import_theme(path: &Path) -> Result<iced_core::Theme, ThemeImportError> {
+    let contents = std::fs::read_to_string(path)?;
+    let value: serde_json::Value = serde_json::from_str(&contents)?;
+
+    let palette = value
+        .get("palette")
+        .ok_or_else(|| ThemeImportError::MissingField("palette".to_string()))?;
+
+    let mut colors = [Color::BLACK; 6];
+    for (color, field) in colors.iter_mut().zip(PALETTE_FIELDS) {
+        let entry = palette
+            .get(field)
+            .ok_or_else(|| ThemeImportError::MissingField(format!("palette.{field}")))?;
+
+        let channel = |name: &str, default: Option<f64>| {
+            let path = format!("palette.{field}.{name}");
+            let value = match entry.get(name).and_then(serde_json::Value::as_f64) {
+                Some(value) => value,
+                None => default.ok_or(ThemeImportError::MissingField(path.clone()))?,
+            };
+
+            if (0.0..=1.0).contains(&value) {
+                Ok(value as f32)
+            } else {
+                Err(ThemeImportError::OutOfRange { field: path, value })
+            }
+        };
+
+        *color = Color {
+            r: channel("r", None)?,
+            g: channel("g", None)?,
+            b: channel("b", None)?,
+            a: channel("a", Some(1.0))?,
+        };
+    }
+
+    let [background, text, primary, success, danger, warning] = colors;
+
+    Ok(iced_core::Theme::custom(
+        "Custom".to_string(),
+        Palette {
+            background,
+            text,
+            primary,
+            success,
+            danger,
+            warning,
+        },
+    ))
+}
+
 pub fn hex_to_color(hex: &str) -> Option<Color> {
     if hex.len() == 7 || hex.len() == 9 {
         let hash = &hex[0..1];
@@ -345,3 +430,61 @@ pub fn from_hsv_degrees(h_deg: f32, s: f32, v: f32) -> Color {
     let hue = RgbHue::from_degrees(h_deg);
     from_hsva(Hsva::new(hue, s, v, 1.0))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn import_json(name: &str, json: &str) -> Result<iced_core::Theme, ThemeImportError> {
+        let path = std::env::temp_dir().join(format!(
+            "flowsurface-theme-{name}-{}.json",
+            std::process::id()
+        ));
+        std::fs::write(&path, json).unwrap();
+
+        let result = import_theme(&path);
+        std::fs::remove_file(&path).unwrap();
+        result
+    }
+
+    fn palette_json(background: &str) -> String {
+        let color = r#"{"r": 0.5, "g": 0.5, "b": 0.5}"#;
+        format!(
+            r#"{{"palette": {{"background": {background}, "text": {color}, "primary": {color},
+                "success": {color}, "danger": {color}, "warning": {color}}}}}"#
+        )
+    }
+
+    #[test]
+    fn import_names_the_missing_field() {
+        let err = import_json("no-palette", r#"{"name": "custom"}"#).unwrap_err();
+        assert!(matches!(err, ThemeImportError::MissingField(field) if field == "palette"));
+
+        let err = import_json("no-blue", &palette_json(r#"{"r": 0.1, "g": 0.2}"#)).unwrap_err();
+        assert!(
+            matches!(err, ThemeImportError::MissingField(field) if field == "palette.background.b")
+        );
+    }
+
+    #[test]
+    fn import_rejects_channels_out_of_range() {
+        let err = import_json(
+            "out-of-range",
+            &palette_json(r#"{"r": 0.1, "g": 1.5, "b": 0.3}"#),
+        )
+        .unwrap_err();
+
+        assert!(matches!(
+            err,
+            ThemeImportError::OutOfRange { field, value }
+                if field == "palette.background.g" && value == 1.5
+        ));
+    }
+
+    #[test]
+    fn import_defaults_missing_alpha_to_opaque() {
+        let theme =
+            import_json("opaque", &palette_json(r#"{"r": 0.1, "g": 0.2, "b": 0.3}"#)).unwrap();
+        assert_eq!(theme.palette().background.a, 1.0);
+    }
+}
@@ -1,73 +1,135 @@
 use std::fmt;
+use std::sync::RwLock;
 
-use chrono::DateTime;
+use chrono::{Offset, TimeZone};
 use serde::{Deserialize, Serialize};
 
+use crate::TimestampFormat;
+use crate::i18n::Language;
+
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum UserTimezone {
     #[default]
     Utc,
     Local,
+    /// Constant offset from UTC in minutes, doesn't follow daylight saving
+    Fixed(i32),
+}
+
+/// Standard-time offsets of common exchange clocks, in minutes
+const EXCHANGE_OFFSETS: [(&str, i32); 7] = [
+    ("New York", -5 * 60),
+    ("Chicago", -6 * 60),
+    ("London", 0),
+    ("Frankfurt", 60),
+    ("Hong Kong", 8 * 60),
+    ("Tokyo", 9 * 60),
+    ("Sydney", 10 * 60),
+];
+
+static SESSION_TIMEZONE: RwLock<UserTimezone> = RwLock::new(UserTimezone::Utc);
+
+/// Sets the timezone session separators split days in, kept in sync with the user's timezone
+pub fn set_session_timezone(timezone: UserTimezone) {
+    if let Ok(mut current) = SESSION_TIMEZONE.write() {
+        *current = timezone;
+    }
+}
+
+pub fn session_timezone() -> UserTimezone {
+    SESSION_TIMEZONE
+        .read()
+        .map(|current| *current)
+        .unwrap_or_default()
 }
 
 impl UserTimezone {
-    /// Converts UTC timestamp to the appropriate timezone and formats it according to timeframe
-    pub fn format_timestamp(&self, timestamp: i64, timeframe: exchange::Timeframe) -> String {
-        if let Some(datetime) = DateTime::from_timestamp(timestamp, 0) {
-            match self {
-                UserTimezone::Local => {
-                    let time_with_zone = datetime.with_timezone(&chrono::Local);
-                    Self::format_by_timeframe(&time_with_zone, timeframe)
-                }
-                UserTimezone::Utc => {
-                    let time_with_zone = datetime.with_timezone(&chrono::Utc);
-                    Self::format_by_timeframe(&time_with_zone, timeframe)
-                }
+    pub const ALL: [UserTimezone; 9] = [
+        UserTimezone::Utc,
+        UserTimezone::Local,
+        UserTimezone::Fixed(EXCHANGE_OFFSETS[0].1),
+        UserTimezone::Fixed(EXCHANGE_OFFSETS[1].1),
+        UserTimezone::Fixed(EXCHANGE_OFFSETS[2].1),
+        UserTimezone::Fixed(EXCHANGE_OFFSETS[3].1),
+        UserTimezone::Fixed(EXCHANGE_OFFSETS[4].1),
+        UserTimezone::Fixed(EXCHANGE_OFFSETS[5].1),
+        UserTimezone::Fixed(EXCHANGE_OFFSETS[6].1),
+    ];
+
+    /// Offset from UTC at `timestamp_millis`, only `Local` can differ between timestamps
+    pub fn offset_at(&self, timestamp_millis: i64) -> chrono::FixedOffset {
+        let utc = chrono::FixedOffset::east_opt(0).unwrap();
+
+        match self {
+            UserTimezone::Utc => utc,
+            UserTimezone::Local => chrono::Local
+                .timestamp_millis_opt(timestamp_millis)
+                .single()
+                .map_or(utc, |datetime| datetime.offset().fix()),
+            UserTimezone::Fixed(minutes) => {
+                chrono::FixedOffset::east_opt(minutes * 60).unwrap_or(utc)
             }
-        } else {
-            String::new()
         }
     }
 
-    /// Formats a `DateTime` with appropriate format based on timeframe
-    fn format_by_timeframe<Tz: chrono::TimeZone>(
-        datetime: &DateTime<Tz>,
-        timeframe: exchange::Timeframe,
-    ) -> String
-    where
-        Tz::Offset: std::fmt::Display,
-    {
-        let interval = timeframe.to_milliseconds();
+    /// Offset from UTC at `timestamp_millis` in milliseconds
+    pub fn offset_ms_at(&self, timestamp_millis: i64) -> i64 {
+        i64::from(self.offset_at(timestamp_millis).local_minus_utc()) * 1000
+    }
+
+    /// Converts UTC timestamp to the appropriate timezone and formats it according to timeframe
+    pub fn format_timestamp(&self, timestamp: i64, timeframe: exchange::Timeframe) -> String {
+        let timestamp_millis = timestamp * 1000;
 
-        if interval < 10000 {
-            datetime.format("%M:%S").to_string()
-        } else if datetime.format("%H:%M").to_string() == "00:00" {
-            datetime.format("%-d").to_string()
+        let format = if timeframe.to_milliseconds() < 60_000 {
+            TimestampFormat::MinuteSecond
+        } else if self.is_midnight(timestamp_millis) {
+            TimestampFormat::Day
         } else {
-            datetime.format("%H:%M").to_string()
-        }
+            TimestampFormat::HourMinute
+        };
+
+        self.format_as(timestamp_millis, format)
+    }
+
+    /// Formats the start of a session, with the time only when it isn't local midnight
+    pub fn format_session_label(&self, timestamp_millis: i64) -> String {
+        let format = if self.is_midnight(timestamp_millis) {
+            TimestampFormat::WeekdayDay
+        } else {
+            TimestampFormat::WeekdayDayTime
+        };
+
+        self.format_as(timestamp_millis, format)
     }
 
     /// Formats a `DateTime` with detailed format for crosshair display
     pub fn format_crosshair_timestamp(&self, timestamp_millis: i64, interval: u64) -> String {
-        if let Some(datetime) = DateTime::from_timestamp_millis(timestamp_millis) {
-            if interval < 10000 {
-                return datetime.format("%M:%S.%3f").to_string();
-            }
-
-            match self {
-                UserTimezone::Local => datetime
-                    .with_timezone(&chrono::Local)
-                    .format("%a %b %-d %H:%M")
-                    .to_string(),
-                UserTimezone::Utc => datetime
-                    .with_timezone(&chrono::Utc)
-                    .format("%a %b %-d %H:%M")
-                    .to_string(),
-            }
+        let format = if interval < 10000 {
+            TimestampFormat::Millis
         } else {
-            String::new()
+            TimestampFormat::Crosshair
+        };
+
+        self.format_as(timestamp_millis, format)
+    }
+
+    /// Whether the timestamp falls on the first minute of a day in this timezone
+    fn is_midnight(&self, timestamp_millis: i64) -> bool {
+        (timestamp_millis + self.offset_ms_at(timestamp_millis)).rem_euclid(86_400_000) < 60_000
+    }
+
+    fn format_as(&self, timestamp_millis: i64, format: TimestampFormat) -> String {
+        if timestamp_millis < 0 {
+            return String::new();
         }
+
+        crate::format_timestamp_as(
+            timestamp_millis as u64,
+            *self,
+            Language::from_code(crate::i18n::current_language()),
+            format,
+        )
     }
 }
 
@@ -81,10 +143,42 @@ impl fmt::Display for UserTimezone {
                 let minutes = (local_offset % 3600) / 60;
                 write!(f, "Local (UTC {hours:+03}:{minutes:02})")
             }
+            UserTimezone::Fixed(minutes) => {
+                let offset = format_offset(*minutes);
+
+                match EXCHANGE_OFFSETS.iter().find(|(_, m)| m == minutes) {
+                    Some((name, _)) => write!(f, "{name} ({offset})"),
+                    None => write!(f, "{offset}"),
+                }
+            }
         }
     }
 }
 
+/// `UTC+09:00` style label, also the serialized form of `Fixed`
+fn format_offset(minutes: i32) -> String {
+    let sign = if minutes < 0 { '-' } else { '+' };
+    let abs = minutes.unsigned_abs();
+
+    format!("UTC{sign}{:02}:{:02}", abs / 60, abs % 60)
+}
+
+fn parse_offset(value: &str) -> Option<i32> {
+    let rest = value.strip_prefix("utc")?;
+    let (sign, rest) = match rest.chars().next()? {
+        '+' => (1, &rest[1..]),
+        '-' => (-1, &rest[1..]),
+        _ => return None,
+    };
+    let (hours, minutes) = rest.split_once(':').unwrap_or((rest, "0"));
+    let (hours, minutes) = (hours.parse::<i32>().ok()?, minutes.parse::<i32>().ok()?);
+
+    if hours > 14 || minutes >= 60 {
+        return None;
+    }
+    Some(sign * (hours * 60 + minutes))
+}
+
 impl<'de> Deserialize<'de> for UserTimezone {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -94,7 +188,9 @@ impl<'de> Deserialize<'de> for UserTimezone {
         match timezone_str.to_lowercase().as_str() {
             "utc" => Ok(UserTimezone::Utc),
             "local" => Ok(UserTimezone::Local),
-            _ => Err(serde::de::Error::custom("Invalid UserTimezone")),
+            other => parse_offset(other)
+                .map(UserTimezone::Fixed)
+                .ok_or_else(|| serde::de::Error::custom("Invalid UserTimezone")),
         }
     }
 }
@@ -107,6 +203,58 @@ impl Serialize for UserTimezone {
         match self {
             UserTimezone::Utc => serializer.serialize_str("UTC"),
             UserTimezone::Local => serializer.serialize_str("Local"),
+            UserTimezone::Fixed(minutes) => serializer.serialize_str(&format_offset(*minutes)),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JANUARY: i64 = 1_704_110_400_000; // 2024-01-01 12:00 UTC
+    const JULY: i64 = 1_719_835_200_000; // 2024-07-01 12:00 UTC
+
+    #[test]
+    fn fixed_offset_ignores_daylight_saving() {
+        let new_york = UserTimezone::Fixed(-300);
+
+        assert_eq!(new_york.offset_ms_at(JANUARY), -5 * 3_600_000);
+        assert_eq!(new_york.offset_ms_at(JULY), -5 * 3_600_000);
+        assert_eq!(
+            new_york.format_session_label(JULY),
+            "Mon 1 07:00".to_string()
+        );
+    }
+
+    #[test]
+    fn local_offset_follows_system_clock() {
+        for ts in [JANUARY, JULY] {
+            let expected = chrono::Local
+                .timestamp_millis_opt(ts)
+                .unwrap()
+                .offset()
+                .local_minus_utc();
+
+            assert_eq!(
+                UserTimezone::Local.offset_ms_at(ts),
+                i64::from(expected) * 1000
+            );
+        }
+    }
+
+    #[test]
+    fn timezone_serde_keeps_old_names() {
+        for (json, tz) in [
+            ("\"UTC\"", UserTimezone::Utc),
+            ("\"Local\"", UserTimezone::Local),
+            ("\"UTC+09:00\"", UserTimezone::Fixed(540)),
+            ("\"UTC-05:30\"", UserTimezone::Fixed(-330)),
+        ] {
+            assert_eq!(serde_json::from_str::<UserTimezone>(json).unwrap(), tz);
+            assert_eq!(serde_json::to_string(&tz).unwrap(), json);
+        }
+
+        assert!(serde_json::from_str::<UserTimezone>("\"UTC+25:00\"").is_err());
+    }
+}
@@ -1,15 +1,33 @@
 // i18n! 宏已在 main.rs (crate root) 中初始化
 pub use rust_i18n::t;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
 pub enum Language {
+    #[default]
     English,
     SimplifiedChinese,
+    Spanish,
 }
 
-impl Default for Language {
-    fn default() -> Self {
-        Language::English
+/// 界面水平排列方向，由当前语言决定
+///
+/// 约定：视图代码只描述“起始侧 / 结束侧”，不要直接写死左右顺序，
+/// 需要水平排列的区域通过 [`LayoutDirection::arrange`] 得到实际顺序。
+/// 这样以后加入阿拉伯语等从右到左的语言时，只需在 [`Language::direction`] 中返回 `Rtl`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LayoutDirection {
+    #[default]
+    Ltr,
+    Rtl,
+}
+
+impl LayoutDirection {
+    /// 将按“起始侧在前”给出的元素排列成屏幕上从左到右的顺序
+    pub fn arrange<T>(self, [start, end]: [T; 2]) -> [T; 2] {
+        match self {
+            LayoutDirection::Ltr => [start, end],
+            LayoutDirection::Rtl => [end, start],
+        }
     }
 }
 
@@ -20,10 +38,17 @@ impl std::fmt::Display for Language {
 }
 
 impl Language {
+    pub const ALL: [Language; 3] = [
+        Language::English,
+        Language::SimplifiedChinese,
+        Language::Spanish,
+    ];
+
     pub fn code(&self) -> &'static str {
         match self {
             Language::English => "en-US",
             Language::SimplifiedChinese => "zh-CN",
+            Language::Spanish => "es-ES",
         }
     }
     
@@ -31,12 +56,24 @@ impl Language {
         match self {
             Language::English => "English",
             Language::SimplifiedChinese => "简体中文",
+            Language::Spanish => "Español",
+        }
+    }
+
+    /// 目前支持的语言都是从左到右书写
+    pub fn direction(&self) -> LayoutDirection {
+        match self {
+            Language::English | Language::SimplifiedChinese | Language::Spanish => {
+                LayoutDirection::Ltr
+            }
         }
     }
+
     pub fn from_code(code: String) -> Language {
         match code.as_str() {
             "en-US" => Language::English,
             "zh-CN" => Language::SimplifiedChinese,
+            "es-ES" => Language::Spanish,
             _ => Language::English,
         }
     }
@@ -48,4 +85,30 @@ pub fn set_language(lang: Language) {
 
 pub fn current_language() -> String {
     rust_i18n::locale().to_string()
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spanish_locale_resolves() {
+        let lang = Language::from_code("es-ES".to_string());
+
+        assert_eq!(lang, Language::Spanish);
+        assert_eq!(lang.code(), "es-ES");
+        assert_eq!(lang.direction(), LayoutDirection::Ltr);
+    }
+
+    #[test]
+    fn rtl_mirrors_horizontal_order() {
+        assert_eq!(
+            LayoutDirection::Ltr.arrange(["start", "end"]),
+            ["start", "end"]
+        );
+        assert_eq!(
+            LayoutDirection::Rtl.arrange(["start", "end"]),
+            ["end", "start"]
+        );
+    }
+}
@@ -0,0 +1,260 @@
+use exchange::util::Price;
+use exchange::{Kline, Timeframe};
+use serde::Deserialize;
+
+use std::path::Path;
+
+/// Times below this are taken as unix seconds, above as milliseconds
+const SECONDS_CUTOFF: u64 = 100_000_000_000;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ImportError {
+    #[error("Couldn't read file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Missing `{0}` column")]
+    MissingColumn(&'static str),
+    #[error("Line {line}: {reason}")]
+    Parse { line: usize, reason: String },
+    #[error("No klines found")]
+    Empty,
+}
+
+/// Reads OHLCV rows as exported by TradingView: a header row naming the `time`
+/// (or `datetime`/`date`), `open`, `high`, `low`, `close` and `volume` columns,
+/// extra columns are ignored. Times are unix seconds, milliseconds or RFC 3339 dates
+/// and get floored to `timeframe`. A `buy_volume` column, as written by the CSV export,
+/// splits the volume by side.
+pub fn import_klines_csv(path: &Path, timeframe: Timeframe) -> Result<Vec<Kline>, ImportError> {
+    parse_klines_csv(&std::fs::read_to_string(path)?, timeframe)
+}
+
+/// Reads a JSON array of `{ "time", "open", "high", "low", "close", "volume" }` objects,
+/// `time` in unix seconds or milliseconds and `buy_volume` optional.
+pub fn import_klines_json(path: &Path) -> Result<Vec<Kline>, ImportError> {
+    parse_klines_json(&std::fs::read_to_string(path)?)
+}
+
+fn parse_klines_csv(contents: &str, timeframe: Timeframe) -> Result<Vec<Kline>, ImportError> {
+    let mut lines = contents.lines().enumerate();
+
+    let header = lines
+        .next()
+        .map(|(_, header)| {
+            header
+                .split(',')
+                .map(|name| name.trim().trim_matches('"').to_lowercase())
+                .collect::<Vec<_>>()
+        })
+        .ok_or(ImportError::Empty)?;
+
+    let column = |names: &[&str], label: &'static str| {
+        header
+            .iter()
+            .position(|name| names.contains(&name.as_str()))
+            .ok_or(ImportError::MissingColumn(label))
+    };
+
+    let time_col = column(&["time", "datetime", "date", "timestamp"], "time")?;
+    let open_col = column(&["open"], "open")?;
+    let high_col = column(&["high"], "high")?;
+    let low_col = column(&["low"], "low")?;
+    let close_col = column(&["close"], "close")?;
+    let volume_col = column(&["volume"], "volume")?;
+    let buy_volume_col = column(&["buy_volume"], "buy_volume").ok();
+
+    let interval = timeframe.to_milliseconds();
+    let mut klines = vec![];
+
+    for (i, line) in lines {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let line_no = i + 1;
+        let fields = line
+            .split(',')
+            .map(|field| field.trim().trim_matches('"'))
+            .collect::<Vec<_>>();
+
+        let field = |col: usize| {
+            fields.get(col).copied().ok_or_else(|| ImportError::Parse {
+                line: line_no,
+                reason: format!("expected {} columns, found {}", header.len(), fields.len()),
+            })
+        };
+        let number = |col: usize| {
+            let value = field(col)?;
+            value.parse::<f32>().map_err(|_| ImportError::Parse {
+                line: line_no,
+                reason: format!("`{value}` is not a number"),
+            })
+        };
+
+        let time = field(time_col)?;
+        let time = parse_time(time).ok_or_else(|| ImportError::Parse {
+            line: line_no,
+            reason: format!("`{time}` is not a time"),
+        })?;
+
+        // TradingView leaves the volume empty or NaN on symbols without one
+        let volume = number(volume_col)
+            .ok()
+            .filter(|v| v.is_finite())
+            .unwrap_or(0.0);
+        let buy_volume = match buy_volume_col {
+            Some(col) if !field(col)?.is_empty() => Some(number(col)?),
+            _ => None,
+        };
+
+        klines.push(kline(
+            time - time % interval.max(1),
+            [
+                number(open_col)?,
+                number(high_col)?,
+                number(low_col)?,
+                number(close_col)?,
+            ],
+            volume,
+            buy_volume,
+        ));
+    }
+
+    finish(klines)
+}
+
+#[derive(Deserialize)]
+struct JsonKline {
+    time: u64,
+    open: f32,
+    high: f32,
+    low: f32,
+    close: f32,
+    #[serde(default)]
+    volume: f32,
+    #[serde(default)]
+    buy_volume: Option<f32>,
+}
+
+fn parse_klines_json(contents: &str) -> Result<Vec<Kline>, ImportError> {
+    let rows: Vec<JsonKline> = serde_json::from_str(contents)?;
+
+    let klines = rows
+        .into_iter()
+        .map(|row| {
+            kline(
+                to_millis(row.time),
+                [row.open, row.high, row.low, row.close],
+                row.volume,
+                row.buy_volume,
+            )
+        })
+        .collect();
+
+    finish(klines)
+}
+
+fn kline(time: u64, [open, high, low, close]: [f32; 4], volume: f32, buy: Option<f32>) -> Kline {
+    Kline {
+        time,
+        open: Price::from_f32(open),
+        high: Price::from_f32(high),
+        low: Price::from_f32(low),
+        close: Price::from_f32(close),
+        // a negative buy side marks volume that isn't split by side
+        volume: buy.map_or((-1.0, volume), |buy| (buy, volume - buy)),
+    }
+}
+
+/// Sorts by time, a later row replacing an earlier one of the same time
+fn finish(mut klines: Vec<Kline>) -> Result<Vec<Kline>, ImportError> {
+    if klines.is_empty() {
+        return Err(ImportError::Empty);
+    }
+
+    klines.reverse();
+    klines.sort_by_key(|kline| kline.time);
+    klines.dedup_by_key(|kline| kline.time);
+
+    Ok(klines)
+}
+
+fn to_millis(time: u64) -> u64 {
+    if time < SECONDS_CUTOFF {
+        time * 1000
+    } else {
+        time
+    }
+}
+
+fn parse_time(value: &str) -> Option<u64> {
+    if let Ok(time) = value.parse::<u64>() {
+        return Some(to_millis(time));
+    }
+
+    let datetime = chrono::DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.to_utc())
+        .or_else(|_| {
+            chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").map(|dt| dt.and_utc())
+        })
+        .or_else(|_| {
+            chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map(|date| date.and_time(chrono::NaiveTime::MIN).and_utc())
+        })
+        .ok()?;
+
+    u64::try_from(datetime.timestamp_millis()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_reads_tradingview_export() {
+        let csv = "time,open,high,low,close,Volume,MA\n\
+                   1704067260,100.5,101,100,100.75,12.5,NaN\n\
+                   2024-01-01T00:00:00Z,100,100.5,99.5,100.5,10,NaN\n\
+                   1704067230,1,1,1,1,1,NaN\n";
+
+        let klines = parse_klines_csv(csv, Timeframe::M1).unwrap();
+
+        assert_eq!(klines.len(), 2);
+        // 1704067230 floors onto the first minute, the later row replaces the dated one
+        assert_eq!(klines[0].time, 1_704_067_200_000);
+        assert_eq!(klines[0].volume, (-1.0, 1.0));
+        assert_eq!(klines[1].time, 1_704_067_260_000);
+        assert_eq!(klines[1].close, Price::from_f32(100.75));
+    }
+
+    #[test]
+    fn csv_rejects_missing_columns_and_bad_rows() {
+        assert!(matches!(
+            parse_klines_csv("time,open,high,low,volume\n", Timeframe::M1),
+            Err(ImportError::MissingColumn("close"))
+        ));
+        assert!(matches!(
+            parse_klines_csv(
+                "time,open,high,low,close,volume\n1,a,1,1,1,1\n",
+                Timeframe::M1
+            ),
+            Err(ImportError::Parse { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn json_splits_volume_when_buy_side_is_given() {
+        let json = r#"[
+            {"time": 1704067200000, "open": 1, "high": 2, "low": 0.5, "close": 1.5,
+             "volume": 10, "buy_volume": 6},
+            {"time": 1704067140, "open": 1, "high": 1, "low": 1, "close": 1}
+        ]"#;
+
+        let klines = parse_klines_json(json).unwrap();
+
+        assert_eq!(klines[0].time, 1_704_067_140_000);
+        assert_eq!(klines[0].volume, (-1.0, 0.0));
+        assert_eq!(klines[1].volume, (6.0, 4.0));
+        assert!(matches!(parse_klines_json("[]"), Err(ImportError::Empty)));
+    }
+}
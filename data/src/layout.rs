@@ -2,13 +2,23 @@ pub use dashboard::Dashboard;
 pub use pane::Pane;
 use serde::{Deserialize, Serialize};
 
+use crate::{Theme, UserTimezone};
+pub use template::ChartTemplate;
+
 pub mod dashboard;
 pub mod pane;
+pub mod template;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Layout {
     pub name: String,
     pub dashboard: Dashboard,
+    /// Used instead of the global timezone while the layout is active
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<UserTimezone>,
+    /// Used instead of the global theme while the layout is active
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<Theme>,
 }
 
 impl Default for Layout {
@@ -16,6 +26,8 @@ impl Default for Layout {
         Self {
             name: "Default".to_string(),
             dashboard: Dashboard::default(),
+            timezone: None,
+            theme: None,
         }
     }
 }
@@ -1,12 +1,34 @@
 use serde::{Deserialize, Serialize};
 
 use super::{WindowSpec, pane::Pane};
+use crate::chart::annotation::PriceLines;
+use crate::chart::kline::ClusterScaling;
 use crate::util::ok_or_default;
 
-#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Dashboard {
     #[serde(deserialize_with = "ok_or_default", default)]
     pub pane: Pane,
     #[serde(deserialize_with = "ok_or_default", default)]
     pub popout: Vec<(Pane, WindowSpec)>,
+    #[serde(deserialize_with = "ok_or_default", default)]
+    pub price_lines: PriceLines,
+    /// Weight the hybrid cluster scaling starts from when picked on a footprint chart
+    #[serde(default = "default_hybrid_weight")]
+    pub hybrid_weight: f32,
+}
+
+fn default_hybrid_weight() -> f32 {
+    ClusterScaling::DEFAULT_HYBRID_WEIGHT
+}
+
+impl Default for Dashboard {
+    fn default() -> Self {
+        Self {
+            pane: Pane::default(),
+            popout: vec![],
+            price_lines: PriceLines::default(),
+            hybrid_weight: default_hybrid_weight(),
+        }
+    }
 }
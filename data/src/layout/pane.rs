@@ -2,7 +2,7 @@ use exchange::adapter::PersistStreamKind;
 use exchange::{TickMultiplier, TickerInfo, Timeframe};
 use serde::{Deserialize, Serialize};
 
-use crate::chart::{comparison, heatmap, kline};
+use crate::chart::{comparison, correlation, heatmap, kline, range, spread, stats_panel};
 use crate::panel::{ladder, timeandsales};
 use crate::util::ok_or_default;
 
@@ -10,7 +10,7 @@ use crate::chart::{
     Basis, ViewConfig,
     heatmap::HeatmapStudy,
     indicator::{HeatmapIndicator, KlineIndicator},
-    kline::KlineChartKind,
+    kline::{KlineChartKind, KlineStudy},
 };
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize)]
@@ -56,6 +56,15 @@ pub enum Pane {
         #[serde(deserialize_with = "ok_or_default", default)]
         link_group: Option<LinkGroup>,
     },
+    RangeChart {
+        layout: ViewConfig,
+        #[serde(deserialize_with = "ok_or_default", default)]
+        stream_type: Vec<PersistStreamKind>,
+        #[serde(deserialize_with = "ok_or_default")]
+        settings: Settings,
+        #[serde(deserialize_with = "ok_or_default", default)]
+        link_group: Option<LinkGroup>,
+    },
     ComparisonChart {
         stream_type: Vec<PersistStreamKind>,
         #[serde(deserialize_with = "ok_or_default")]
@@ -63,6 +72,13 @@ pub enum Pane {
         #[serde(deserialize_with = "ok_or_default", default)]
         link_group: Option<LinkGroup>,
     },
+    CorrelationMatrix {
+        stream_type: Vec<PersistStreamKind>,
+        #[serde(deserialize_with = "ok_or_default")]
+        settings: Settings,
+        #[serde(deserialize_with = "ok_or_default", default)]
+        link_group: Option<LinkGroup>,
+    },
     TimeAndSales {
         stream_type: Vec<PersistStreamKind>,
         settings: Settings,
@@ -75,6 +91,35 @@ pub enum Pane {
         #[serde(deserialize_with = "ok_or_default", default)]
         link_group: Option<LinkGroup>,
     },
+    Statistics {
+        stream_type: Vec<PersistStreamKind>,
+        #[serde(deserialize_with = "ok_or_default")]
+        settings: Settings,
+        #[serde(deserialize_with = "ok_or_default", default)]
+        link_group: Option<LinkGroup>,
+    },
+    SpreadChart {
+        stream_type: Vec<PersistStreamKind>,
+        #[serde(deserialize_with = "ok_or_default")]
+        settings: Settings,
+        #[serde(deserialize_with = "ok_or_default", default)]
+        link_group: Option<LinkGroup>,
+    },
+    /// Small candles of every ticker the layout's other charts already stream
+    MiniCharts {
+        #[serde(deserialize_with = "ok_or_default", default = "default_mini_columns")]
+        columns: usize,
+        #[serde(deserialize_with = "ok_or_default", default)]
+        link_group: Option<LinkGroup>,
+    },
+}
+
+/// Tiles per row of a new mini charts pane
+pub const DEFAULT_MINI_COLUMNS: usize = 4;
+pub const MINI_COLUMNS_RANGE: std::ops::RangeInclusive<usize> = 1..=8;
+
+fn default_mini_columns() -> usize {
+    DEFAULT_MINI_COLUMNS
 }
 
 impl Default for Pane {
@@ -83,14 +128,51 @@ impl Default for Pane {
     }
 }
 
+impl Pane {
+    /// What a leaf pane shows, splits count as `Starter`
+    pub fn content_kind(&self) -> ContentKind {
+        match self {
+            Pane::Split { .. } | Pane::Starter { .. } => ContentKind::Starter,
+            Pane::HeatmapChart { .. } => ContentKind::HeatmapChart,
+            Pane::KlineChart { kind, .. } => match kind {
+                KlineChartKind::Footprint {
+                    depth_layer: Some(_),
+                    ..
+                } => ContentKind::HeatmapFootprint,
+                KlineChartKind::Footprint { .. } => ContentKind::FootprintChart,
+                KlineChartKind::Candles { .. } => ContentKind::CandlestickChart,
+            },
+            Pane::RangeChart { .. } => ContentKind::RangeChart,
+            Pane::ComparisonChart { .. } => ContentKind::ComparisonChart,
+            Pane::CorrelationMatrix { .. } => ContentKind::CorrelationMatrix,
+            Pane::TimeAndSales { .. } => ContentKind::TimeAndSales,
+            Pane::Ladder { .. } => ContentKind::Ladder,
+            Pane::Statistics { .. } => ContentKind::Statistics,
+            Pane::SpreadChart { .. } => ContentKind::SpreadChart,
+            Pane::MiniCharts { .. } => ContentKind::MiniCharts,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 #[serde(default)]
 pub struct Settings {
     pub tick_multiply: Option<exchange::TickMultiplier>,
+    /// Footprint tick size follows `chart::suggest_tick_multiplier` instead of `tick_multiply`
+    pub auto_tick: bool,
     pub visual_config: Option<VisualConfig>,
     pub selected_basis: Option<Basis>,
+    pub kline_studies: Vec<KlineStudy>,
+    /// Reminder about the pane's setup, shown from its header
+    pub note: Option<String>,
+    /// Timeframes a candle pane switches between from its header, keeping the recently viewed
+    /// ones loaded. Off with fewer than two.
+    pub timeframe_tabs: Vec<exchange::Timeframe>,
 }
 
+/// Longest note a pane can hold, in characters
+pub const MAX_NOTE_LEN: usize = 280;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub enum LinkGroup {
     A,
@@ -143,6 +225,10 @@ pub enum VisualConfig {
     Kline(kline::Config),
     Ladder(ladder::Config),
     Comparison(comparison::Config),
+    Range(range::Config),
+    Correlation(correlation::Config),
+    Statistics(stats_panel::Config),
+    Spread(spread::Config),
 }
 
 impl VisualConfig {
@@ -180,6 +266,34 @@ impl VisualConfig {
             _ => None,
         }
     }
+
+    pub fn range(&self) -> Option<range::Config> {
+        match self {
+            Self::Range(cfg) => Some(*cfg),
+            _ => None,
+        }
+    }
+
+    pub fn correlation(&self) -> Option<correlation::Config> {
+        match self {
+            Self::Correlation(cfg) => Some(*cfg),
+            _ => None,
+        }
+    }
+
+    pub fn statistics(&self) -> Option<stats_panel::Config> {
+        match self {
+            Self::Statistics(cfg) => Some(*cfg),
+            _ => None,
+        }
+    }
+
+    pub fn spread(&self) -> Option<spread::Config> {
+        match self {
+            Self::Spread(cfg) => Some(*cfg),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -187,21 +301,33 @@ pub enum ContentKind {
     Starter,
     HeatmapChart,
     FootprintChart,
+    HeatmapFootprint,
     CandlestickChart,
+    RangeChart,
     ComparisonChart,
+    CorrelationMatrix,
     TimeAndSales,
     Ladder,
+    Statistics,
+    SpreadChart,
+    MiniCharts,
 }
 
 impl ContentKind {
-    pub const ALL: [ContentKind; 7] = [
+    pub const ALL: [ContentKind; 13] = [
         ContentKind::Starter,
         ContentKind::HeatmapChart,
         ContentKind::FootprintChart,
+        ContentKind::HeatmapFootprint,
         ContentKind::CandlestickChart,
+        ContentKind::RangeChart,
         ContentKind::ComparisonChart,
+        ContentKind::CorrelationMatrix,
         ContentKind::TimeAndSales,
         ContentKind::Ladder,
+        ContentKind::Statistics,
+        ContentKind::SpreadChart,
+        ContentKind::MiniCharts,
     ];
 }
 
@@ -211,10 +337,16 @@ impl std::fmt::Display for ContentKind {
             ContentKind::Starter => "Starter Pane",
             ContentKind::HeatmapChart => "Heatmap Chart",
             ContentKind::FootprintChart => "Footprint Chart",
+            ContentKind::HeatmapFootprint => "Heatmap + Footprint",
             ContentKind::CandlestickChart => "Candlestick Chart",
+            ContentKind::RangeChart => "Range Bar Chart",
             ContentKind::ComparisonChart => "Comparison Chart",
+            ContentKind::CorrelationMatrix => "Correlation Matrix",
             ContentKind::TimeAndSales => "Time&Sales",
             ContentKind::Ladder => "DOM/Ladder",
+            ContentKind::Statistics => "Statistics",
+            ContentKind::SpreadChart => "Spread Chart",
+            ContentKind::MiniCharts => "Mini Charts",
         };
         write!(f, "{s}")
     }
@@ -249,20 +381,28 @@ impl PaneSetup {
             ContentKind::HeatmapChart => {
                 let current = current_basis.and_then(|b| match b {
                     Basis::Time(tf) if exchange.supports_heatmap_timeframe(tf) => Some(b),
-                    _ => None,
+                    Basis::Tick(_) => Some(b),
+                    Basis::Time(_) => None,
                 });
                 Some(current.unwrap_or_else(|| Basis::default_heatmap_time(Some(base_ticker))))
             }
             ContentKind::Ladder => Some(
                 current_basis.unwrap_or_else(|| Basis::default_heatmap_time(Some(base_ticker))),
             ),
-            ContentKind::FootprintChart => {
+            ContentKind::FootprintChart | ContentKind::HeatmapFootprint => {
                 Some(current_basis.unwrap_or(Basis::Time(Timeframe::M5)))
             }
-            ContentKind::CandlestickChart | ContentKind::ComparisonChart => {
+            ContentKind::CandlestickChart
+            | ContentKind::ComparisonChart
+            | ContentKind::CorrelationMatrix => {
                 Some(current_basis.unwrap_or(Basis::Time(Timeframe::M15)))
             }
-            ContentKind::Starter | ContentKind::TimeAndSales => None,
+            ContentKind::Starter
+            | ContentKind::TimeAndSales
+            | ContentKind::Statistics
+            | ContentKind::SpreadChart
+            | ContentKind::MiniCharts
+            | ContentKind::RangeChart => None,
         };
 
         let tick_multiplier = match content_kind {
@@ -278,12 +418,17 @@ impl PaneSetup {
                 };
                 Some(tm)
             }
-            ContentKind::FootprintChart => {
+            ContentKind::FootprintChart | ContentKind::HeatmapFootprint => {
                 Some(current_tick_multiplier.unwrap_or(TickMultiplier(50)))
             }
             ContentKind::CandlestickChart
+            | ContentKind::RangeChart
             | ContentKind::ComparisonChart
+            | ContentKind::CorrelationMatrix
             | ContentKind::TimeAndSales
+            | ContentKind::Statistics
+            | ContentKind::SpreadChart
+            | ContentKind::MiniCharts
             | ContentKind::Starter => current_tick_multiplier,
         };
 
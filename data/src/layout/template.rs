@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+use crate::chart::kline::{KlineChartKind, KlineStudy};
+
+/// Kline pane setup without its ticker, so it can be applied to whatever the pane shows
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ChartTemplate {
+    pub name: String,
+    pub chart_kind: KlineChartKind,
+    #[serde(default)]
+    pub studies: Vec<KlineStudy>,
+    /// Footprint tick size, rounded to a multiple of the ticker's min tick when applied
+    #[serde(default)]
+    pub default_tick_size: Option<f32>,
+}
+
+impl ChartTemplate {
+    /// File the template is stored in, characters that aren't safe in a file name become `_`
+    pub fn file_name(&self) -> String {
+        let stem: String = self
+            .name
+            .trim()
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || matches!(c, '-' | '_' | ' ') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+
+        format!("{stem}.json")
+    }
+}
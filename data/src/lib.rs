@@ -3,9 +3,11 @@ pub mod audio;
 pub mod chart;
 pub mod config;
 pub mod i18n;
+pub mod import;
 pub mod layout;
 pub mod log;
 pub mod panel;
+pub mod replay;
 pub mod tickers_table;
 pub mod util;
 
@@ -13,7 +15,7 @@ rust_i18n::i18n!("../locales", fallback = "en-US");
 
 use std::fs::File;
 use std::io::{Read, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 pub use audio::AudioStream;
 pub use config::ScaleFactor;
 pub use config::sidebar::{self, Sidebar};
@@ -22,9 +24,11 @@ pub use config::theme::Theme;
 pub use config::timezone::UserTimezone;
 
 use ::log::{error, info, warn};
-pub use layout::{Dashboard, Layout, Pane};
+pub use layout::{ChartTemplate, Dashboard, Layout, Pane};
 
 pub const SAVED_STATE_PATH: &str = "saved-state.json";
+/// Folder inside the data folder holding one JSON file per chart template
+pub const TEMPLATES_DIR: &str = "templates";
 
 #[derive(thiserror::Error, Debug, Clone)]
 pub enum InternalError {
@@ -34,9 +38,140 @@ pub enum InternalError {
     Layout(String),
 }
 
+/// Patterns under `datetime` in the locale files, from a full timestamp down to the single
+/// fields axis labels show
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampFormat {
+    /// Month, day and time
+    Full,
+    /// [`TimestampFormat::Full`] led by the weekday
+    Crosshair,
+    /// Minutes, seconds and milliseconds
+    Millis,
+    MinuteSecond,
+    HourMinuteSecond,
+    HourMinute,
+    /// Day of the month
+    Day,
+    /// Weekday and day of the month
+    WeekdayDay,
+    /// Weekday, day of the month and time
+    WeekdayDayTime,
+    /// Day and short month name
+    DayMonth,
+    /// Short month name
+    Month,
+    YearMonth,
+    Year,
+}
+
+impl TimestampFormat {
+    fn key(self) -> &'static str {
+        match self {
+            TimestampFormat::Full => "datetime.format",
+            TimestampFormat::Crosshair => "datetime.crosshair",
+            TimestampFormat::Millis => "datetime.millis",
+            TimestampFormat::MinuteSecond => "datetime.minute_second",
+            TimestampFormat::HourMinuteSecond => "datetime.hour_minute_second",
+            TimestampFormat::HourMinute => "datetime.hour_minute",
+            TimestampFormat::Day => "datetime.day",
+            TimestampFormat::WeekdayDay => "datetime.weekday_day",
+            TimestampFormat::WeekdayDayTime => "datetime.weekday_day_time",
+            TimestampFormat::DayMonth => "datetime.day_month",
+            TimestampFormat::Month => "datetime.month",
+            TimestampFormat::YearMonth => "datetime.year_month",
+            TimestampFormat::Year => "datetime.year",
+        }
+    }
+}
+
+/// Formats a millisecond timestamp with the `datetime.format` pattern of `lang`'s locale
+pub fn format_timestamp(ts_ms: u64, tz: UserTimezone, lang: i18n::Language) -> String {
+    format_timestamp_as(ts_ms, tz, lang, TimestampFormat::Full)
+}
+
+/// Formats a millisecond timestamp with the pattern of `format` in `lang`'s locale
+pub fn format_timestamp_as(
+    ts_ms: u64,
+    tz: UserTimezone,
+    lang: i18n::Language,
+    format: TimestampFormat,
+) -> String {
+    let Some(datetime) = chrono::DateTime::from_timestamp_millis(ts_ms as i64) else {
+        return String::new();
+    };
+
+    let pattern = rust_i18n::t!(format.key(), locale = lang.code());
+    let format = chrono_format(&pattern);
+
+    match tz {
+        UserTimezone::Local => datetime
+            .with_timezone(&chrono::Local)
+            .format(&format)
+            .to_string(),
+        UserTimezone::Utc => datetime.format(&format).to_string(),
+        UserTimezone::Fixed(_) => datetime
+            .with_timezone(&tz.offset_at(ts_ms as i64))
+            .format(&format)
+            .to_string(),
+    }
+}
+
+/// Translates the locale pattern tokens (`YYYY`, `MMM`, `MM`, `DD`, `D`, `ddd`, `HH`, `mm`,
+/// `ss`, `SSS`) into chrono specifiers
+fn chrono_format(pattern: &str) -> String {
+    const TOKENS: [(&str, &str); 10] = [
+        ("YYYY", "%Y"),
+        ("MMM", "%b"),
+        ("MM", "%m"),
+        ("DD", "%d"),
+        ("D", "%-d"),
+        ("ddd", "%a"),
+        ("HH", "%H"),
+        ("mm", "%M"),
+        ("ss", "%S"),
+        ("SSS", "%3f"),
+    ];
+
+    let mut format = String::with_capacity(pattern.len() * 2);
+    let mut rest = pattern;
+
+    'outer: while let Some(c) = rest.chars().next() {
+        for (token, specifier) in TOKENS {
+            if let Some(stripped) = rest.strip_prefix(token) {
+                format.push_str(specifier);
+                rest = stripped;
+                continue 'outer;
+            }
+        }
+
+        if c == '%' {
+            format.push_str("%%");
+        } else {
+            format.push(c);
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+
+    format
+}
+
+/// Where the saved state was loaded from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateSource {
+    Primary,
+    /// The primary file failed to load, the backup of the previous save was used
+    Backup,
+}
+
 pub fn write_json_to_file(json: &str, file_name: &str) -> std::io::Result<()> {
-    let path = data_path(Some(file_name));
+    write_json_atomically(&data_path(Some(file_name)), json)
+}
 
+/// Writes `json` to a temp file and renames it over `path`, so a crash mid-write can't leave
+/// a truncated file behind. The replaced file is kept as a `.bak`, linked before the rename
+/// so `path` always exists
+fn write_json_atomically(path: &Path, json: &str) -> std::io::Result<()> {
     let parent = path.parent().ok_or_else(|| {
         std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid state file path")
     })?;
@@ -45,15 +180,67 @@ pub fn write_json_to_file(json: &str, file_name: &str) -> std::io::Result<()> {
         std::fs::create_dir_all(parent)?;
     }
 
-    let mut file = File::create(path)?;
-    file.write_all(json.as_bytes())?;
-    Ok(())
+    let tmp_path = with_suffix(path, ".tmp");
+    {
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(json.as_bytes())?;
+        file.sync_all()?;
+    }
+
+    if path.exists() {
+        let backup_path = with_suffix(path, ".bak");
+        if backup_path.exists() {
+            std::fs::remove_file(&backup_path)?;
+        }
+        if std::fs::hard_link(path, &backup_path).is_err() {
+            std::fs::copy(path, &backup_path)?;
+        }
+    }
+
+    std::fs::rename(&tmp_path, path)
 }
 
-pub fn read_from_file(file_name: &str) -> Result<State, Box<dyn std::error::Error>> {
-    let path = data_path(Some(file_name));
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+pub fn read_from_file(file_name: &str) -> Result<(State, StateSource), Box<dyn std::error::Error>> {
+    read_from_path(&data_path(Some(file_name)))
+}
+
+/// Loads the state at `path`, falling back to its `.bak` when the file itself is unusable
+fn read_from_path(path: &Path) -> Result<(State, StateSource), Box<dyn std::error::Error>> {
+    match read_state_at(path) {
+        Ok(state) => Ok((state, StateSource::Primary)),
+        Err(err) => {
+            let backup_path = with_suffix(path, ".bak");
+            if !backup_path.exists() {
+                return Err(err);
+            }
 
-    let file_open_result = File::open(&path);
+            match read_state_at(&backup_path) {
+                Ok(state) => {
+                    warn!(
+                        "Failed to load state file '{}' ({}), recovered from '{}'",
+                        path.display(),
+                        err,
+                        backup_path.display()
+                    );
+                    Ok((state, StateSource::Backup))
+                }
+                Err(backup_err) => {
+                    error!("Backup state file is unusable too: {}", backup_err);
+                    Err(err)
+                }
+            }
+        }
+    }
+}
+
+fn read_state_at(path: &Path) -> Result<State, Box<dyn std::error::Error>> {
+    let file_open_result = File::open(path);
     let mut file = match file_open_result {
         Ok(file) => file,
         Err(e) => return Err(Box::new(e)),
@@ -71,15 +258,16 @@ pub fn read_from_file(file_name: &str) -> Result<State, Box<dyn std::error::Erro
             drop(file); // Close the file before renaming
 
             // Create backup file with different name to prevent overwriting it
-            let backup_file_name = if let Some(pos) = file_name.rfind('.') {
-                format!("{}_old{}", &file_name[..pos], &file_name[pos..])
-            } else {
-                format!("{}_old", file_name)
+            let backup_path = match (path.file_stem(), path.extension()) {
+                (Some(stem), Some(ext)) => path.with_file_name(format!(
+                    "{}_old.{}",
+                    stem.to_string_lossy(),
+                    ext.to_string_lossy()
+                )),
+                _ => with_suffix(path, "_old"),
             };
 
-            let backup_path = data_path(Some(&backup_file_name));
-
-            if let Err(rename_err) = std::fs::rename(&path, &backup_path) {
+            if let Err(rename_err) = std::fs::rename(path, &backup_path) {
                 warn!(
                     "Failed to backup corrupted state file '{}' to '{}': {}",
                     path.display(),
@@ -98,6 +286,95 @@ pub fn read_from_file(file_name: &str) -> Result<State, Box<dyn std::error::Erro
     }
 }
 
+#[derive(thiserror::Error, Debug)]
+pub enum ProfileError {
+    #[error("Couldn't read file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Not a saved state file: {0}")]
+    Invalid(String),
+    #[error("Saved by a newer version (format {0}, this one reads up to {max})", max = State::VERSION)]
+    NewerVersion(u64),
+    #[error("The file holds no layouts")]
+    NoLayouts,
+}
+
+/// Reads a state file exported by another user, checking its format before its layouts
+/// and settings are merged into the running ones
+pub fn read_profile(path: &Path) -> Result<State, ProfileError> {
+    parse_profile(&std::fs::read_to_string(path)?)
+}
+
+fn parse_profile(json: &str) -> Result<State, ProfileError> {
+    let value = serde_json::from_str::<serde_json::Value>(json)
+        .map_err(|e| ProfileError::Invalid(e.to_string()))?;
+
+    // every field has a default, so check the shape first or any JSON object would pass
+    if !value
+        .get("layout_manager")
+        .is_some_and(serde_json::Value::is_object)
+    {
+        return Err(ProfileError::Invalid(
+            "no `layout_manager` object".to_string(),
+        ));
+    }
+
+    let version = value
+        .get("version")
+        .map_or(Some(0), serde_json::Value::as_u64)
+        .ok_or_else(|| ProfileError::Invalid("`version` is not a number".to_string()))?;
+    if version > u64::from(State::VERSION) {
+        return Err(ProfileError::NewerVersion(version));
+    }
+
+    let state =
+        serde_json::from_value::<State>(value).map_err(|e| ProfileError::Invalid(e.to_string()))?;
+    if state.layout_manager.layouts.is_empty() {
+        return Err(ProfileError::NoLayouts);
+    }
+
+    Ok(state)
+}
+
+/// Chart templates saved in the data folder, sorted by name
+pub fn list_templates() -> std::io::Result<Vec<ChartTemplate>> {
+    list_templates_in(&data_path(None).join(TEMPLATES_DIR))
+}
+
+/// Saves `template` to the data folder, replacing an existing one of the same name
+pub fn save_template(template: &ChartTemplate) -> std::io::Result<()> {
+    save_template_in(&data_path(None).join(TEMPLATES_DIR), template)
+}
+
+fn list_templates_in(dir: &Path) -> std::io::Result<Vec<ChartTemplate>> {
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut templates = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+
+        let contents = std::fs::read_to_string(&path)?;
+        match serde_json::from_str::<ChartTemplate>(&contents) {
+            Ok(template) => templates.push(template),
+            Err(e) => warn!("Skipping invalid chart template {}: {}", path.display(), e),
+        }
+    }
+
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(templates)
+}
+
+fn save_template_in(dir: &Path, template: &ChartTemplate) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+
+    let json = serde_json::to_string_pretty(template).map_err(std::io::Error::other)?;
+    std::fs::write(dir.join(template.file_name()), json)
+}
+
 pub fn open_data_folder() -> Result<(), InternalError> {
     let pathbuf = data_path(None);
 
@@ -132,22 +409,61 @@ pub fn data_path(path_name: Option<&str>) -> PathBuf {
     }
 }
 
-fn cleanup_directory(data_path: &PathBuf) -> usize {
+/// Files and bytes removed by a market data cleanup
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CleanupReport {
+    pub files: usize,
+    pub bytes: u64,
+}
+
+impl std::ops::Add for CleanupReport {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            files: self.files + other.files,
+            bytes: self.bytes + other.bytes,
+        }
+    }
+}
+
+/// Total size of every file under the data folder, in bytes
+pub fn data_folder_size() -> u64 {
+    dir_size(&data_path(None))
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => dir_size(&entry.path()),
+            Ok(_) => entry.metadata().map_or(0, |meta| meta.len()),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+fn cleanup_directory(data_path: &PathBuf, retention_days: u32) -> CleanupReport {
+    let mut report = CleanupReport::default();
+
     if !data_path.exists() {
         warn!("Data path {:?} does not exist, skipping cleanup", data_path);
-        return 0;
+        return report;
     }
 
     let re =
         regex::Regex::new(r".*-(\d{4}-\d{2}-\d{2})\.zip$").expect("Cleanup regex pattern is valid");
     let today = chrono::Local::now().date_naive();
-    let mut deleted_files = Vec::new();
 
     let entries = match std::fs::read_dir(data_path) {
         Ok(entries) => entries,
         Err(e) => {
             error!("Failed to read data directory {:?}: {}", data_path, e);
-            return 0;
+            return report;
         }
     };
 
@@ -170,11 +486,14 @@ fn cleanup_directory(data_path: &PathBuf) -> usize {
                 && let Ok(file_date) = chrono::NaiveDate::parse_from_str(&cap[1], "%Y-%m-%d")
             {
                 let days_old = today.signed_duration_since(file_date).num_days();
-                if days_old > 4 {
+                if days_old > i64::from(retention_days) {
+                    let size = file.metadata().map_or(0, |meta| meta.len());
+
                     if let Err(e) = std::fs::remove_file(&path) {
                         error!("Failed to remove old file {}: {}", filename, e);
                     } else {
-                        deleted_files.push(filename.to_string());
+                        report.files += 1;
+                        report.bytes += size;
                         info!("Removed old file: {}", filename);
                     }
                 }
@@ -182,10 +501,11 @@ fn cleanup_directory(data_path: &PathBuf) -> usize {
         }
     }
 
-    deleted_files.len()
+    report
 }
 
-pub fn cleanup_old_market_data() -> usize {
+/// Removes downloaded trade archives dated more than `retention_days` days ago
+pub fn cleanup_old_market_data(retention_days: u32) -> CleanupReport {
     let paths = ["um", "cm"].map(|market_type| {
         data_path(Some(&format!(
             "market_data/binance/data/futures/{}/daily/aggTrades",
@@ -193,8 +513,190 @@ pub fn cleanup_old_market_data() -> usize {
         )))
     });
 
-    let total_deleted: usize = paths.iter().map(cleanup_directory).sum();
+    let report = paths
+        .iter()
+        .map(|path| cleanup_directory(path, retention_days))
+        .fold(CleanupReport::default(), |acc, report| acc + report);
+
+    info!(
+        "File cleanup completed. Deleted {} files ({} bytes)",
+        report.files, report.bytes
+    );
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamp_uses_locale_format() {
+        // 2024-03-05 14:07:00 UTC
+        let ts_ms = 1_709_647_620_000;
+
+        assert_eq!(
+            format_timestamp(ts_ms, UserTimezone::Utc, i18n::Language::English),
+            "03/05 14:07"
+        );
+        assert_eq!(
+            format_timestamp(ts_ms, UserTimezone::Utc, i18n::Language::SimplifiedChinese),
+            "03-05 14:07"
+        );
+    }
+
+    #[test]
+    fn templates_saved_and_listed_by_name() {
+        let dir =
+            std::env::temp_dir().join(format!("flowsurface-templates-{}", std::process::id()));
+        let template = |name: &str| ChartTemplate {
+            name: name.to_string(),
+            chart_kind: chart::KlineChartKind::default(),
+            studies: vec![],
+            default_tick_size: Some(0.5),
+        };
 
-    info!("File cleanup completed. Deleted {} files", total_deleted);
-    total_deleted
+        assert!(list_templates_in(&dir).unwrap().is_empty());
+
+        save_template_in(&dir, &template("Scalping")).unwrap();
+        save_template_in(&dir, &template("BTC/USDT swing")).unwrap();
+        std::fs::write(dir.join("broken.json"), "{").unwrap();
+
+        let names: Vec<_> = list_templates_in(&dir)
+            .unwrap()
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        assert_eq!(names, ["BTC/USDT swing", "Scalping"]);
+        assert!(dir.join("BTC_USDT swing.json").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn cleanup_keeps_archives_within_retention() {
+        let dir = std::env::temp_dir().join(format!("flowsurface-cleanup-{}", std::process::id()));
+        let symbol_dir = dir.join("BTCUSDT");
+        std::fs::create_dir_all(&symbol_dir).unwrap();
+
+        let archive = |days_ago: i64| {
+            let date = chrono::Local::now().date_naive() - chrono::Duration::days(days_ago);
+            symbol_dir.join(format!("BTCUSDT-aggTrades-{}.zip", date.format("%Y-%m-%d")))
+        };
+        std::fs::write(archive(1), [0; 10]).unwrap();
+        std::fs::write(archive(5), [0; 20]).unwrap();
+        std::fs::write(archive(30), [0; 40]).unwrap();
+
+        assert_eq!(dir_size(&dir), 70);
+
+        let report = cleanup_directory(&dir, 4);
+        assert_eq!((report.files, report.bytes), (2, 60));
+        assert!(archive(1).exists());
+        assert_eq!(dir_size(&dir), 10);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn truncated_state_falls_back_to_backup() {
+        let dir = std::env::temp_dir().join(format!("flowsurface-state-{}", std::process::id()));
+        let path = dir.join(SAVED_STATE_PATH);
+
+        let state = |scale: f32| {
+            serde_json::to_string(&State {
+                scale_factor: ScaleFactor::from(scale),
+                ..State::default()
+            })
+            .unwrap()
+        };
+
+        write_json_atomically(&path, &state(1.2)).unwrap();
+        write_json_atomically(&path, &state(1.4)).unwrap();
+        assert!(!with_suffix(&path, ".tmp").exists());
+
+        let saved = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, &saved[..saved.len() / 2]).unwrap();
+
+        let (recovered, source) = read_from_path(&path).unwrap();
+        assert_eq!(source, StateSource::Backup);
+        assert_eq!(f32::from(recovered.scale_factor), 1.2);
+        assert!(dir.join("saved-state_old.json").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn profile_checks_shape_and_version() {
+        let profile = |version: u32| {
+            serde_json::to_string(&State {
+                version,
+                layout_manager: Layouts {
+                    layouts: vec![Layout::default()],
+                    active_layout: None,
+                },
+                ..State::default()
+            })
+            .unwrap()
+        };
+
+        assert_eq!(parse_profile(&profile(1)).unwrap().version, 1);
+        assert!(parse_profile(&profile(0)).is_ok());
+        assert!(matches!(
+            parse_profile(&profile(State::VERSION + 1)),
+            Err(ProfileError::NewerVersion(2))
+        ));
+        assert!(matches!(
+            parse_profile(r#"{"theme": "dark"}"#),
+            Err(ProfileError::Invalid(_))
+        ));
+        assert!(matches!(
+            parse_profile(&serde_json::to_string(&State::default()).unwrap()),
+            Err(ProfileError::NoLayouts)
+        ));
+    }
+
+    #[test]
+    fn layout_overrides_are_optional() {
+        let json = serde_json::to_string(&layout::Layout::default()).unwrap();
+        assert!(!json.contains("timezone"));
+
+        let layout: layout::Layout = serde_json::from_str(&json).unwrap();
+        assert!(layout.timezone.is_none() && layout.theme.is_none());
+
+        let with_timezone = layout::Layout {
+            timezone: Some(UserTimezone::Fixed(540)),
+            ..layout::Layout::default()
+        };
+        let json = serde_json::to_string(&with_timezone).unwrap();
+        let layout: layout::Layout = serde_json::from_str(&json).unwrap();
+        assert_eq!(layout.timezone, Some(UserTimezone::Fixed(540)));
+    }
+
+    #[test]
+    fn locale_pattern_tokens() {
+        assert_eq!(chrono_format("YYYY-MM-DD HH:mm:ss"), "%Y-%m-%d %H:%M:%S");
+        assert_eq!(chrono_format("DD.MM 100%"), "%d.%m 100%%");
+        assert_eq!(chrono_format("ddd D MMM mm:ss.SSS"), "%a %-d %b %M:%S.%3f");
+    }
+
+    #[test]
+    fn axis_and_crosshair_formats() {
+        // 2024-03-05 14:07:09.250 UTC, a Tuesday
+        let ts_ms = 1_709_647_629_250;
+        let en =
+            |format| format_timestamp_as(ts_ms, UserTimezone::Utc, i18n::Language::English, format);
+
+        assert_eq!(en(TimestampFormat::Crosshair), "Tue 03/05 14:07");
+        assert_eq!(en(TimestampFormat::Millis), "07:09.250");
+        assert_eq!(en(TimestampFormat::DayMonth), "Mar 05");
+        assert_eq!(en(TimestampFormat::WeekdayDayTime), "Tue 5 14:07");
+        assert_eq!(
+            format_timestamp_as(
+                ts_ms,
+                UserTimezone::Fixed(540),
+                i18n::Language::SimplifiedChinese,
+                TimestampFormat::Day
+            ),
+            "5日"
+        );
+    }
 }
@@ -1,2 +1,3 @@
+pub mod imbalance;
 pub mod ladder;
 pub mod timeandsales;
@@ -0,0 +1,144 @@
+use exchange::depth::Depth;
+use serde::{Deserialize, Serialize};
+
+/// Number of depth updates the EMA roughly averages over
+const SMOOTHING_PERIOD: f32 = 10.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct Config {
+    /// Max levels per side summed into the ratio, counted from the best price
+    pub levels: usize,
+    /// Half-width of the price band around mid, in percent of the mid price
+    pub band_pct: f32,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            levels: 20,
+            band_pct: 0.5,
+        }
+    }
+}
+
+/// Bid/ask size imbalance in [-1, 1] over the top levels within the band around mid,
+/// positive when bids outweigh asks
+pub fn depth_imbalance(depth: &Depth, cfg: Config) -> Option<f32> {
+    let best_bid = depth.bids.last_key_value()?.0.to_f32();
+    let best_ask = depth.asks.first_key_value()?.0.to_f32();
+
+    let mid = (best_bid + best_ask) / 2.0;
+    let band = mid * cfg.band_pct / 100.0;
+
+    let bid_qty: f32 = depth
+        .bids
+        .iter()
+        .rev()
+        .take(cfg.levels)
+        .take_while(|(price, _)| price.to_f32() >= mid - band)
+        .map(|(_, qty)| qty)
+        .sum();
+    let ask_qty: f32 = depth
+        .asks
+        .iter()
+        .take(cfg.levels)
+        .take_while(|(price, _)| price.to_f32() <= mid + band)
+        .map(|(_, qty)| qty)
+        .sum();
+
+    let total = bid_qty + ask_qty;
+    if total <= 0.0 {
+        return None;
+    }
+
+    Some(((bid_qty - ask_qty) / total).clamp(-1.0, 1.0))
+}
+
+/// Exponentially smoothed imbalance, so the gauge doesn't flicker on every update
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImbalanceGauge {
+    value: Option<f32>,
+}
+
+impl ImbalanceGauge {
+    pub fn update(&mut self, depth: &Depth, cfg: Config) {
+        let Some(ratio) = depth_imbalance(depth, cfg) else {
+            return;
+        };
+
+        let alpha = 2.0 / (SMOOTHING_PERIOD + 1.0);
+        self.value = Some(match self.value {
+            Some(prev) => prev + alpha * (ratio - prev),
+            None => ratio,
+        });
+    }
+
+    pub fn value(&self) -> Option<f32> {
+        self.value
+    }
+
+    pub fn reset(&mut self) {
+        self.value = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exchange::util::Price;
+
+    fn depth(bids: &[(f32, f32)], asks: &[(f32, f32)]) -> Depth {
+        let side = |levels: &[(f32, f32)]| {
+            levels
+                .iter()
+                .map(|(price, qty)| (Price::from_f32(*price), *qty))
+                .collect()
+        };
+
+        Depth {
+            bids: side(bids),
+            asks: side(asks),
+        }
+    }
+
+    #[test]
+    fn imbalance_within_band_and_levels() {
+        let depth = depth(
+            &[(99.0, 100.0), (99.5, 3.0), (99.9, 2.0)],
+            &[(100.1, 1.0), (100.5, 1.0), (101.0, 100.0)],
+        );
+
+        // the far levels at 99.0 and 101.0 fall outside a 0.6% band
+        let cfg = Config {
+            levels: 10,
+            band_pct: 0.6,
+        };
+        let ratio = depth_imbalance(&depth, cfg).unwrap();
+        assert!((ratio - 3.0 / 7.0).abs() < 1e-6);
+
+        // only the best level of each side
+        let cfg = Config {
+            levels: 1,
+            band_pct: 5.0,
+        };
+        let ratio = depth_imbalance(&depth, cfg).unwrap();
+        assert!((ratio - 1.0 / 3.0).abs() < 1e-6);
+
+        assert_eq!(depth_imbalance(&Depth::default(), cfg), None);
+    }
+
+    #[test]
+    fn gauge_smooths_towards_ratio() {
+        let cfg = Config::default();
+        let bid_heavy = depth(&[(99.9, 3.0)], &[(100.1, 1.0)]);
+        let ask_heavy = depth(&[(99.9, 1.0)], &[(100.1, 3.0)]);
+
+        let mut gauge = ImbalanceGauge::default();
+        gauge.update(&bid_heavy, cfg);
+        assert_eq!(gauge.value(), Some(0.5));
+
+        gauge.update(&ask_heavy, cfg);
+        let value = gauge.value().unwrap();
+        assert!(value < 0.5 && value > -0.5);
+    }
+}
@@ -1,3 +1,4 @@
+use super::imbalance;
 use crate::chart::kline::KlineTrades;
 use crate::util::ok_or_default;
 use exchange::{
@@ -13,6 +14,8 @@ use std::{
 
 const TRADE_RETENTION_MS: u64 = 8 * 60_000;
 const CHASE_MIN_VISIBLE_OPACITY: f32 = 0.15;
+/// How long a level keeps flashing after its size changed between snapshots
+const SIZE_CHANGE_FLASH_MS: u64 = 600;
 
 #[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
 pub struct Config {
@@ -20,6 +23,14 @@ pub struct Config {
     #[serde(deserialize_with = "ok_or_default", default)]
     pub show_chase_tracker: bool,
     pub trade_retention: Duration,
+    #[serde(deserialize_with = "ok_or_default", default)]
+    pub show_cumulative: bool,
+    #[serde(deserialize_with = "ok_or_default", default)]
+    pub highlight_changes: bool,
+    #[serde(deserialize_with = "ok_or_default", default)]
+    pub show_imbalance: bool,
+    #[serde(deserialize_with = "ok_or_default", default)]
+    pub imbalance: imbalance::Config,
 }
 
 impl Default for Config {
@@ -28,6 +39,10 @@ impl Default for Config {
             show_spread: false,
             show_chase_tracker: true,
             trade_retention: Duration::from_millis(TRADE_RETENTION_MS),
+            show_cumulative: false,
+            highlight_changes: false,
+            show_imbalance: false,
+            imbalance: imbalance::Config::default(),
         }
     }
 }
@@ -57,10 +72,18 @@ impl Side {
     }
 }
 
+/// Most recent size change of a grouped level
+#[derive(Debug, Clone, Copy)]
+pub struct LevelChange {
+    pub time: u64,
+    pub increased: bool,
+}
+
 #[derive(Default)]
 pub struct GroupedDepth {
     pub orders: BTreeMap<Price, f32>,
     pub chase: ChaseTracker,
+    changes: BTreeMap<Price, LevelChange>,
 }
 
 impl GroupedDepth {
@@ -68,15 +91,61 @@ impl GroupedDepth {
         Self {
             orders: BTreeMap::new(),
             chase: ChaseTracker::default(),
+            changes: BTreeMap::new(),
         }
     }
 
-    pub fn regroup_from_raw(&mut self, levels: &BTreeMap<Price, f32>, side: Side, step: PriceStep) {
-        self.orders.clear();
+    pub fn regroup_from_raw(
+        &mut self,
+        levels: &BTreeMap<Price, f32>,
+        side: Side,
+        step: PriceStep,
+        update_t: u64,
+    ) {
+        let previous = std::mem::take(&mut self.orders);
+
         for (price, qty) in levels.iter() {
             let grouped_price = price.round_to_side_step(side.is_bid(), step);
             *self.orders.entry(grouped_price).or_insert(0.0) += *qty;
         }
+
+        self.changes
+            .retain(|_, change| update_t.saturating_sub(change.time) < SIZE_CHANGE_FLASH_MS);
+
+        // nothing to compare against on the first snapshot or right after a regroup
+        if previous.is_empty() {
+            return;
+        }
+
+        for (price, qty) in &self.orders {
+            let prev_qty = previous.get(price).copied().unwrap_or(0.0);
+            if *qty != prev_qty {
+                self.changes.insert(
+                    *price,
+                    LevelChange {
+                        time: update_t,
+                        increased: *qty > prev_qty,
+                    },
+                );
+            }
+        }
+        for price in previous.keys() {
+            if !self.orders.contains_key(price) {
+                self.changes.insert(
+                    *price,
+                    LevelChange {
+                        time: update_t,
+                        increased: false,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Drops grouped levels so the next snapshot isn't diffed against a different tick size
+    pub fn clear(&mut self) {
+        self.orders.clear();
+        self.changes.clear();
     }
 
     pub fn best_price(&self, side: Side) -> Option<Price> {
@@ -85,6 +154,27 @@ impl GroupedDepth {
             Side::Ask => self.orders.first_key_value().map(|(p, _)| *p),
         }
     }
+
+    /// Total size from the best price up to and including `price`
+    pub fn cumulative_qty(&self, side: Side, price: Price) -> f32 {
+        match side {
+            Side::Bid => self.orders.range(price..).map(|(_, qty)| qty).sum(),
+            Side::Ask => self.orders.range(..=price).map(|(_, qty)| qty).sum(),
+        }
+    }
+
+    /// Whether the level grew and how strong its flash is at `now_ms`, fading out linearly
+    pub fn change_at(&self, price: Price, now_ms: u64) -> Option<(bool, f32)> {
+        let change = self.changes.get(&price)?;
+        let elapsed = now_ms.saturating_sub(change.time);
+
+        if elapsed >= SIZE_CHANGE_FLASH_MS {
+            return None;
+        }
+
+        let strength = 1.0 - (elapsed as f32 / SIZE_CHANGE_FLASH_MS as f32);
+        Some((change.increased, strength))
+    }
 }
 
 #[derive(Debug)]
@@ -0,0 +1,192 @@
+//! Klines of an exported file played back as if they were streamed, to work on layouts and
+//! studies without a live connection.
+//!
+//! Only klines are replayed, trades aren't exported anywhere to be read back.
+
+use exchange::{Kline, Timeframe};
+
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReplaySpeed {
+    Paused,
+    #[default]
+    Normal,
+    Double,
+}
+
+impl ReplaySpeed {
+    pub const ALL: [ReplaySpeed; 3] = [
+        ReplaySpeed::Paused,
+        ReplaySpeed::Normal,
+        ReplaySpeed::Double,
+    ];
+
+    fn multiplier(self) -> u64 {
+        match self {
+            ReplaySpeed::Paused => 0,
+            ReplaySpeed::Normal => 1,
+            ReplaySpeed::Double => 2,
+        }
+    }
+}
+
+impl std::fmt::Display for ReplaySpeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReplaySpeed::Paused => write!(f, "Paused"),
+            ReplaySpeed::Normal => write!(f, "1x"),
+            ReplaySpeed::Double => write!(f, "2x"),
+        }
+    }
+}
+
+/// Klines released by moving the clock
+#[derive(Debug)]
+pub enum Seek<'a> {
+    /// Klines closed since the last release
+    Ahead(&'a [Kline]),
+    /// The clock went back, every kline closed up to it to play again from an empty series
+    Rewound(&'a [Kline]),
+}
+
+/// Plays klines back on a clock of their own, each one released once the clock passes its
+/// close like a live stream would
+#[derive(Debug, Clone)]
+pub struct Replay {
+    klines: Vec<Kline>,
+    interval: u64,
+    /// Data time the playback reached
+    clock: u64,
+    /// Index of the first kline not released yet
+    next: usize,
+    pub speed: ReplaySpeed,
+}
+
+impl Replay {
+    /// `None` without any kline to play
+    pub fn new(mut klines: Vec<Kline>, timeframe: Timeframe) -> Option<Self> {
+        klines.sort_by_key(|kline| kline.time);
+        klines.dedup_by_key(|kline| kline.time);
+
+        let start = klines.first()?.time;
+
+        Some(Self {
+            klines,
+            interval: timeframe.to_milliseconds(),
+            clock: start,
+            next: 0,
+            speed: ReplaySpeed::default(),
+        })
+    }
+
+    /// Moves the clock `elapsed` ahead at the current speed, returning the klines it closed
+    pub fn advance(&mut self, elapsed: Duration) -> &[Kline] {
+        let step = elapsed.as_millis() as u64 * self.speed.multiplier();
+        let closed = self.set_clock(self.clock.saturating_add(step));
+        let from = std::mem::replace(&mut self.next, closed);
+
+        &self.klines[from..closed]
+    }
+
+    /// Sets the clock to `time`. Going ahead returns every kline closed on the way, going back
+    /// past a close returns all those still closed, the charts start over from them.
+    pub fn seek(&mut self, time: u64) -> Seek<'_> {
+        let closed = self.set_clock(time);
+        let from = std::mem::replace(&mut self.next, closed);
+
+        if closed < from {
+            Seek::Rewound(&self.klines[..closed])
+        } else {
+            Seek::Ahead(&self.klines[from..closed])
+        }
+    }
+
+    /// Clamps `time` into the span, returning how many klines are closed by then
+    fn set_clock(&mut self, time: u64) -> usize {
+        let (start, end) = self.span();
+        self.clock = time.clamp(start, end);
+
+        self.klines
+            .partition_point(|kline| kline.time + self.interval <= self.clock)
+    }
+
+    /// Open of the first kline to close of the last one
+    pub fn span(&self) -> (u64, u64) {
+        match (self.klines.first(), self.klines.last()) {
+            (Some(first), Some(last)) => (first.time, last.time + self.interval),
+            _ => (0, 0),
+        }
+    }
+
+    pub fn clock(&self) -> u64 {
+        self.clock
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next == self.klines.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exchange::util::Price;
+
+    fn kline(time: u64) -> Kline {
+        let price = Price::from_f32(100.0);
+        Kline {
+            time,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume: (1.0, 1.0),
+        }
+    }
+
+    fn times(klines: &[Kline]) -> Vec<u64> {
+        klines.iter().map(|kline| kline.time).collect()
+    }
+
+    #[test]
+    fn releases_klines_as_the_clock_passes_their_close() {
+        let klines = [120_000, 0, 60_000].map(kline).to_vec();
+        let mut replay = Replay::new(klines, Timeframe::M1).unwrap();
+        assert_eq!(replay.span(), (0, 180_000));
+
+        assert!(replay.advance(Duration::from_secs(59)).is_empty());
+        assert_eq!(times(replay.advance(Duration::from_secs(1))), [0]);
+
+        replay.speed = ReplaySpeed::Paused;
+        assert!(replay.advance(Duration::from_secs(600)).is_empty());
+
+        replay.speed = ReplaySpeed::Double;
+        assert_eq!(times(replay.advance(Duration::from_secs(30))), [60_000]);
+        assert!(!replay.is_finished());
+    }
+
+    #[test]
+    fn seeking_ahead_releases_skipped_klines_and_back_replays_from_the_start() {
+        let klines = [0, 60_000, 120_000].map(kline).to_vec();
+        let mut replay = Replay::new(klines, Timeframe::M1).unwrap();
+
+        let seek = |replay: &mut Replay, time| match replay.seek(time) {
+            Seek::Ahead(klines) => (false, times(klines)),
+            Seek::Rewound(klines) => (true, times(klines)),
+        };
+
+        assert_eq!(seek(&mut replay, 150_000), (false, vec![0, 60_000]));
+        // still past the first close, the second one gets released again later
+        assert_eq!(seek(&mut replay, 60_000), (true, vec![0]));
+        assert_eq!(seek(&mut replay, 90_000), (false, vec![]));
+        assert_eq!(seek(&mut replay, u64::MAX), (false, vec![60_000, 120_000]));
+        assert_eq!(replay.clock(), 180_000);
+        assert!(replay.is_finished());
+
+        assert_eq!(seek(&mut replay, 0), (true, vec![]));
+        assert_eq!(times(replay.advance(Duration::from_secs(60))), [0]);
+
+        assert!(Replay::new(vec![], Timeframe::M1).is_none());
+    }
+}
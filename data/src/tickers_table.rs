@@ -4,9 +4,15 @@ use exchange::{
 };
 use serde::{Deserialize, Serialize};
 
+/// Recently opened tickers kept for the quick-access chips
+pub const MAX_RECENT_TICKERS: usize = 8;
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct Settings {
     pub favorited_tickers: Vec<Ticker>,
+    /// Most recently opened first
+    #[serde(default)]
+    pub recent_tickers: Vec<Ticker>,
     pub show_favorites: bool,
     pub selected_sort_option: SortOptions,
     pub selected_exchanges: Vec<ExchangeInclusive>,
@@ -17,6 +23,7 @@ impl Default for Settings {
     fn default() -> Self {
         Self {
             favorited_tickers: vec![],
+            recent_tickers: vec![],
             show_favorites: false,
             selected_sort_option: SortOptions::VolumeDesc,
             selected_exchanges: ExchangeInclusive::ALL.to_vec(),
@@ -134,3 +141,84 @@ fn split_price_changes(
 
     (unchanged_part, changed_part, direction)
 }
+
+/// How well `query` matches `symbol`, lower is better and `None` when it doesn't match
+///
+/// Exact matches rank first, then prefixes, then substrings by position, then symbols
+/// containing the query's characters in order, by how spread out they are.
+/// Both are expected uppercase.
+pub fn fuzzy_score(query: &str, symbol: &str) -> Option<usize> {
+    if query.is_empty() || symbol == query {
+        return Some(0);
+    }
+    if symbol.starts_with(query) {
+        return Some(1 + symbol.len() - query.len());
+    }
+    if let Some(pos) = symbol.find(query) {
+        return Some(100 + pos);
+    }
+
+    let mut query_chars = query.chars().peekable();
+    let mut first_match = None;
+    let mut gaps = 0;
+    let mut last_match = 0;
+
+    for (i, c) in symbol.chars().enumerate() {
+        if query_chars.peek() == Some(&c) {
+            query_chars.next();
+            if first_match.is_none() {
+                first_match = Some(i);
+            } else {
+                gaps += i - last_match - 1;
+            }
+            last_match = i;
+        }
+    }
+
+    if query_chars.peek().is_some() {
+        return None;
+    }
+
+    first_match.map(|first| 1_000 + first + gaps)
+}
+
+/// Moves `ticker` to the front of the recents, dropping the oldest past [`MAX_RECENT_TICKERS`]
+pub fn push_recent(recents: &mut Vec<Ticker>, ticker: Ticker) {
+    recents.retain(|recent| *recent != ticker);
+    recents.insert(0, ticker);
+    recents.truncate(MAX_RECENT_TICKERS);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuzzy_score_ranks_closer_matches_first() {
+        let score = |symbol| fuzzy_score("BTC", symbol);
+
+        assert_eq!(score("BTC"), Some(0));
+        assert!(score("BTCUSDT") < score("WBTCUSDT"));
+        assert!(score("WBTCUSDT") < score("BATCUSDT"));
+        assert!(score("BATCUSDT") < score("BXXTXXCUSDT"));
+        assert_eq!(score("ETHUSDT"), None);
+        assert_eq!(fuzzy_score("", "ETHUSDT"), Some(0));
+    }
+
+    #[test]
+    fn recents_dedupe_and_stay_bounded() {
+        let ticker = |i: usize| Ticker::new(&format!("T{i}USDT"), Exchange::BinanceLinear);
+        let mut recents = vec![];
+
+        for i in 0..MAX_RECENT_TICKERS + 2 {
+            push_recent(&mut recents, ticker(i));
+        }
+        assert_eq!(recents.len(), MAX_RECENT_TICKERS);
+        assert_eq!(recents[0], ticker(MAX_RECENT_TICKERS + 1));
+
+        push_recent(&mut recents, ticker(5));
+        assert_eq!(recents.len(), MAX_RECENT_TICKERS);
+        assert_eq!(recents[0], ticker(5));
+        assert_eq!(recents.iter().filter(|t| **t == ticker(5)).count(), 1);
+    }
+}
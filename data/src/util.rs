@@ -43,6 +43,24 @@ pub fn abbr_large_numbers(value: f32) -> String {
     }
 }
 
+/// Byte count in the largest unit that keeps it at or above 1, e.g. `1.5 GB`
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{value:.1} {}", UNITS[unit])
+}
+
 pub fn count_decimals(value: f32) -> usize {
     let value_str = value.to_string();
     if let Some(pos) = value_str.find('.') {
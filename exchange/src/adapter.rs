@@ -1,7 +1,7 @@
 use super::{Ticker, Timeframe};
 use crate::{
-    Kline, OpenInterest, Price, PushFrequency, TickMultiplier, TickerInfo, TickerStats, Trade,
-    depth::Depth,
+    Kline, Liquidation, OpenInterest, Price, PushFrequency, TickMultiplier, TickerInfo,
+    TickerStats, Trade, depth::Depth, fetcher::FetchProgress,
 };
 
 use enum_map::{Enum, EnumMap};
@@ -325,6 +325,13 @@ impl UniqueStreams {
     pub fn combined(&self) -> &EnumMap<Exchange, Option<StreamSpecs>> {
         &self.specs
     }
+
+    pub fn iter(&self) -> impl Iterator<Item = &StreamKind> {
+        self.streams
+            .values()
+            .flatten()
+            .flat_map(|ticker_map| ticker_map.values().flatten())
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
@@ -605,6 +612,9 @@ pub enum Event {
     Disconnected(Exchange, String),
     DepthReceived(StreamKind, u64, Arc<Depth>, Box<[Trade]>),
     KlineReceived(StreamKind, Kline),
+    /// Liquidation on the ticker of a depth and trades stream, not every exchange provides them
+    LiquidationReceived(StreamKind, Liquidation),
+    FetchProgress(StreamKind, FetchProgress),
 }
 
 #[derive(Debug, Clone, Hash)]
@@ -1,7 +1,7 @@
 use super::{
     super::{
-        Exchange, Kline, MarketKind, OpenInterest, Price, PushFrequency, SizeUnit, StreamKind,
-        Ticker, TickerInfo, TickerStats, Timeframe, Trade,
+        Exchange, Kline, Liquidation, MarketKind, OpenInterest, Price, PushFrequency, SizeUnit,
+        StreamKind, Ticker, TickerInfo, TickerStats, Timeframe, Trade,
         adapter::StreamTicksize,
         connect::{State, connect_ws},
         de_string_to_f32,
@@ -36,6 +36,9 @@ static LINEAR_LIMITER: LazyLock<Mutex<BinanceLimiter>> =
 static INVERSE_LIMITER: LazyLock<Mutex<BinanceLimiter>> =
     LazyLock::new(|| Mutex::new(BinanceLimiter::new(PERP_LIMIT, REFILL_RATE)));
 
+/// Most klines a single klines request returns
+pub const KLINE_FETCH_LIMIT: u64 = 1000;
+
 const SPOT_LIMIT: usize = 6000;
 const PERP_LIMIT: usize = 2400;
 
@@ -163,6 +166,25 @@ struct SonicTrade {
     #[serde(rename = "m")]
     is_sell: bool,
 }
+
+#[derive(Deserialize, Debug)]
+struct SonicForceOrder {
+    #[serde(rename = "o")]
+    order: SonicLiquidation,
+}
+
+#[derive(Deserialize, Debug)]
+struct SonicLiquidation {
+    #[serde(rename = "T")]
+    time: u64,
+    #[serde(rename = "S")]
+    side: String,
+    #[serde(rename = "ap", deserialize_with = "de_string_to_f32")]
+    avg_price: f32,
+    #[serde(rename = "z", deserialize_with = "de_string_to_f32")]
+    filled_qty: f32,
+}
+
 enum SonicDepth {
     Spot(SpotDepth),
     Perp(PerpDepth),
@@ -202,12 +224,14 @@ enum StreamData {
     Trade(SonicTrade),
     Depth(SonicDepth),
     Kline(Ticker, SonicKline),
+    Liquidation(SonicLiquidation),
 }
 
 enum StreamWrapper {
     Trade,
     Depth,
     Kline,
+    Liquidation,
 }
 
 impl StreamWrapper {
@@ -219,6 +243,7 @@ impl StreamWrapper {
                 s if s.starts_with("de") => Some(StreamWrapper::Depth),
                 s if s.starts_with("ag") => Some(StreamWrapper::Trade),
                 s if s.starts_with("kl") => Some(StreamWrapper::Kline),
+                s if s.starts_with("fo") => Some(StreamWrapper::Liquidation),
                 _ => None,
             })
     }
@@ -268,6 +293,12 @@ fn feed_de(slice: &[u8], market: MarketKind) -> Result<StreamData, AdapterError>
                         kline_wrap.kline,
                     ));
                 }
+                Some(StreamWrapper::Liquidation) => {
+                    let force_order: SonicForceOrder = sonic_rs::from_str(&v.as_raw_faststr())
+                        .map_err(|e| AdapterError::ParseError(e.to_string()))?;
+
+                    return Ok(StreamData::Liquidation(force_order.order));
+                }
                 _ => {
                     log::error!("Unknown stream type");
                 }
@@ -356,7 +387,10 @@ pub fn connect_market_stream(
                     let stream_2 = format!("{}@depth@100ms", symbol_str.to_lowercase());
 
                     let domain = ws_domain_from_market_type(market);
-                    let streams = format!("{stream_1}/{stream_2}");
+                    let mut streams = format!("{stream_1}/{stream_2}");
+                    if ticker_info.is_perps() {
+                        streams.push_str(&format!("/{}@forceOrder", symbol_str.to_lowercase()));
+                    }
                     let url = format!("wss://{domain}/stream?streams={streams}");
 
                     if let Ok(websocket) = connect_ws(domain, &url).await {
@@ -431,6 +465,36 @@ pub fn connect_market_stream(
 
                                             trades_buffer.push(trade);
                                         }
+                                        StreamData::Liquidation(de_liq) => {
+                                            let price = Price::from_f32(de_liq.avg_price)
+                                                .round_to_min_tick(ticker_info.min_ticksize);
+                                            let qty = contract_size.map_or(
+                                                if size_in_quote_ccy {
+                                                    (de_liq.filled_qty * de_liq.avg_price).round()
+                                                } else {
+                                                    de_liq.filled_qty
+                                                },
+                                                |size| de_liq.filled_qty * size,
+                                            );
+
+                                            let liquidation = Liquidation {
+                                                time: de_liq.time,
+                                                is_sell: de_liq.side == "SELL",
+                                                price,
+                                                qty,
+                                            };
+
+                                            let _ = output
+                                                .send(Event::LiquidationReceived(
+                                                    StreamKind::DepthAndTrades {
+                                                        ticker_info,
+                                                        depth_aggr: StreamTicksize::Client,
+                                                        push_freq,
+                                                    },
+                                                    liquidation,
+                                                ))
+                                                .await;
+                                        }
                                         StreamData::Depth(depth_type) => {
                                             if already_fetching {
                                                 log::warn!("Already fetching...\n");
@@ -940,12 +1004,12 @@ pub async fn fetch_klines(
 
     let limit_param = if let Some((start, end)) = range {
         let interval_ms = timeframe.to_milliseconds();
-        let num_intervals = ((end - start) / interval_ms).min(1000);
+        let num_intervals = ((end - start) / interval_ms).min(KLINE_FETCH_LIMIT);
 
         if num_intervals < 3 {
             let new_start = start - (interval_ms * 5);
             let new_end = end + (interval_ms * 5);
-            let num_intervals = ((new_end - new_start) / interval_ms).min(1000);
+            let num_intervals = ((new_end - new_start) / interval_ms).min(KLINE_FETCH_LIMIT);
 
             url.push_str(&format!(
                 "&startTime={new_start}&endTime={new_end}&limit={num_intervals}"
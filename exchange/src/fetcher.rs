@@ -1,8 +1,9 @@
-use crate::adapter::StreamKind;
-use crate::{Kline, OpenInterest, Trade};
+use crate::adapter::{AdapterError, Exchange, StreamKind, binance};
+use crate::{Kline, OpenInterest, TickerInfo, Trade};
 
 use smallvec::SmallVec;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use uuid::Uuid;
 
@@ -16,6 +17,49 @@ pub fn is_trade_fetch_enabled() -> bool {
     TRADE_FETCH_ENABLED.load(Ordering::Relaxed)
 }
 
+/// Historical trades of an exchange, used to backfill footprints past what the live stream saw
+pub trait TradeFetcher {
+    fn supports_trade_fetch(&self) -> bool;
+
+    /// A batch of trades starting at `from`, callers keep asking from the last trade's time
+    /// until `to` is reached. An exchange serving whole daily archives may return trades past `to`.
+    ///
+    /// `data_path` is the market data directory, where downloaded archives can be cached.
+    fn fetch_trades(
+        &self,
+        ticker_info: TickerInfo,
+        from: u64,
+        to: u64,
+        data_path: PathBuf,
+    ) -> impl Future<Output = Result<Vec<Trade>, AdapterError>> + Send;
+}
+
+impl TradeFetcher for Exchange {
+    fn supports_trade_fetch(&self) -> bool {
+        matches!(
+            self,
+            Exchange::BinanceLinear | Exchange::BinanceInverse | Exchange::BinanceSpot
+        )
+    }
+
+    async fn fetch_trades(
+        &self,
+        ticker_info: TickerInfo,
+        from: u64,
+        _to: u64,
+        data_path: PathBuf,
+    ) -> Result<Vec<Trade>, AdapterError> {
+        match self {
+            Exchange::BinanceLinear | Exchange::BinanceInverse | Exchange::BinanceSpot => {
+                binance::fetch_trades(ticker_info, from, data_path.join("binance")).await
+            }
+            _ => Err(AdapterError::InvalidRequest(format!(
+                "Trade fetch is not supported on {self}"
+            ))),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum FetchedData {
     Trades {
@@ -106,6 +150,13 @@ impl RequestHandler {
             log::warn!("Request not found: {:?}", id);
         }
     }
+
+    /// Whether a pending request of the same kind already spans the whole range
+    pub fn is_pending_within(&self, fetch: &FetchRange) -> bool {
+        self.requests
+            .values()
+            .any(|request| request.status == RequestStatus::Pending && request.contains(fetch))
+    }
 }
 
 impl Default for RequestHandler {
@@ -144,6 +195,15 @@ impl FetchRequest {
             _ => false,
         }
     }
+
+    fn contains(&self, fetch: &FetchRange) -> bool {
+        match (&self.fetch_type, fetch) {
+            (FetchRange::Kline(s1, e1), FetchRange::Kline(s2, e2))
+            | (FetchRange::OpenInterest(s1, e1), FetchRange::OpenInterest(s2, e2))
+            | (FetchRange::Trades(s1, e1), FetchRange::Trades(s2, e2)) => s1 <= s2 && e2 <= e1,
+            _ => false,
+        }
+    }
 }
 
 pub struct FetchSpec {
@@ -187,6 +247,23 @@ impl Clone for FetchSpec {
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum InfoKind {
     FetchingKlines,
+    FetchingKlineHistory(FetchProgress),
     FetchingTrades(usize),
     FetchingOI,
 }
+
+/// Requests done out of the ones a batched fetch was split into
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FetchProgress {
+    pub fetched: usize,
+    pub total: usize,
+}
+
+impl FetchProgress {
+    pub fn ratio(&self) -> f32 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        (self.fetched as f32 / self.total as f32).min(1.0)
+    }
+}
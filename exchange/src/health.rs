@@ -0,0 +1,248 @@
+//! Health of the market data streams, worked out from the events they send.
+//!
+//! Connection errors are reported per exchange connection, not per stream, so every stream of
+//! the exchange gets them.
+
+use crate::adapter::{Event, Exchange, StreamKind};
+
+use rustc_hash::FxHashMap;
+use std::collections::VecDeque;
+
+/// How far back arrivals are counted for the message rate
+const RATE_WINDOW_MS: u64 = 5_000;
+/// Silence after which a stream counts as degraded
+const STALE_AFTER_MS: u64 = 30_000;
+/// How long an error keeps a reconnected stream degraded
+const ERROR_COOLDOWN_MS: u64 = 60_000;
+/// Errors kept per stream
+const MAX_ERRORS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HealthStatus {
+    Healthy,
+    /// Connected, but silent for a while or recently erroring
+    Degraded,
+    Disconnected,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct StreamHealth {
+    pub stream: StreamKind,
+    pub status: HealthStatus,
+    /// Unix ms of the last message, 0 if none arrived yet
+    pub last_update: u64,
+    /// Messages per second over the last few seconds
+    pub message_rate: f32,
+    /// Unix ms since the stream is connected, `None` while disconnected
+    pub connected_since: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+struct Tracked {
+    /// Unix ms the stream started to be monitored
+    since: u64,
+    arrivals: VecDeque<u64>,
+    last_update: u64,
+    connected_since: Option<u64>,
+    disconnected: bool,
+    /// Latest last, with the unix ms they were received at
+    errors: VecDeque<(u64, String)>,
+}
+
+impl Tracked {
+    fn new(now: u64) -> Self {
+        Self {
+            since: now,
+            ..Default::default()
+        }
+    }
+
+    fn health(&self, stream: StreamKind, now: u64) -> StreamHealth {
+        let recent = self
+            .arrivals
+            .iter()
+            .filter(|t| now.saturating_sub(**t) <= RATE_WINDOW_MS)
+            .count();
+
+        let is_stale = now.saturating_sub(self.last_update.max(self.since)) > STALE_AFTER_MS;
+        let recently_failed = self
+            .errors
+            .back()
+            .is_some_and(|(at, _)| now.saturating_sub(*at) <= ERROR_COOLDOWN_MS);
+
+        let status = if self.disconnected {
+            HealthStatus::Disconnected
+        } else if is_stale || recently_failed {
+            HealthStatus::Degraded
+        } else {
+            HealthStatus::Healthy
+        };
+
+        StreamHealth {
+            stream,
+            status,
+            last_update: self.last_update,
+            message_rate: recent as f32 / (RATE_WINDOW_MS as f32 / 1000.0),
+            connected_since: self.connected_since,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct HealthMonitor {
+    streams: FxHashMap<StreamKind, Tracked>,
+}
+
+impl HealthMonitor {
+    /// Starts monitoring the subscribed streams that aren't yet and drops the others
+    pub fn sync<'a>(&mut self, subscribed: impl IntoIterator<Item = &'a StreamKind>, now: u64) {
+        let subscribed = subscribed.into_iter().collect::<Vec<_>>();
+
+        self.streams
+            .retain(|stream, _| subscribed.contains(&stream));
+        for stream in subscribed {
+            self.streams
+                .entry(*stream)
+                .or_insert_with(|| Tracked::new(now));
+        }
+    }
+
+    pub fn record(&mut self, event: &Event, now: u64) {
+        match event {
+            Event::Connected(exchange) => {
+                for tracked in self.of_exchange(*exchange) {
+                    tracked.disconnected = false;
+                    tracked.connected_since = Some(now);
+                }
+            }
+            Event::Disconnected(exchange, reason) => {
+                for tracked in self.of_exchange(*exchange) {
+                    tracked.disconnected = true;
+                    tracked.connected_since = None;
+
+                    if tracked.errors.len() == MAX_ERRORS {
+                        tracked.errors.pop_front();
+                    }
+                    tracked.errors.push_back((now, reason.clone()));
+                }
+            }
+            Event::DepthReceived(stream, ..)
+            | Event::KlineReceived(stream, _)
+            | Event::LiquidationReceived(stream, _) => {
+                let tracked = self
+                    .streams
+                    .entry(*stream)
+                    .or_insert_with(|| Tracked::new(now));
+
+                tracked.arrivals.push_back(now);
+                while tracked
+                    .arrivals
+                    .front()
+                    .is_some_and(|t| now.saturating_sub(*t) > RATE_WINDOW_MS)
+                {
+                    tracked.arrivals.pop_front();
+                }
+
+                tracked.last_update = now;
+                tracked.disconnected = false;
+                tracked.connected_since.get_or_insert(now);
+            }
+            Event::FetchProgress(..) => {}
+        }
+    }
+
+    pub fn health(&self, now: u64) -> impl Iterator<Item = StreamHealth> + '_ {
+        self.streams
+            .iter()
+            .map(move |(stream, tracked)| tracked.health(*stream, now))
+    }
+
+    /// Least healthy status among the monitored streams
+    pub fn worst(&self, now: u64) -> Option<HealthStatus> {
+        self.health(now).map(|health| health.status).max()
+    }
+
+    /// Last few errors of the stream, latest last
+    pub fn errors(&self, stream: &StreamKind) -> impl DoubleEndedIterator<Item = &(u64, String)> {
+        self.streams
+            .get(stream)
+            .into_iter()
+            .flat_map(|tracked| tracked.errors.iter())
+    }
+
+    fn of_exchange(&mut self, exchange: Exchange) -> impl Iterator<Item = &mut Tracked> {
+        self.streams
+            .iter_mut()
+            .filter(move |(stream, _)| stream.ticker_info().exchange() == exchange)
+            .map(|(_, tracked)| tracked)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Kline, Ticker, TickerInfo, Timeframe, util::Price};
+
+    fn kline_stream(exchange: Exchange) -> StreamKind {
+        StreamKind::Kline {
+            ticker_info: TickerInfo::new(Ticker::new("BTCUSDT", exchange), 0.1, 0.001, None),
+            timeframe: Timeframe::M1,
+        }
+    }
+
+    fn kline_event(stream: StreamKind) -> Event {
+        let price = Price::from_f32(100.0);
+        Event::KlineReceived(
+            stream,
+            Kline {
+                time: 0,
+                open: price,
+                high: price,
+                low: price,
+                close: price,
+                volume: (1.0, 1.0),
+            },
+        )
+    }
+
+    #[test]
+    fn errors_degrade_the_streams_of_their_exchange() {
+        let binance = kline_stream(Exchange::BinanceLinear);
+        let bybit = kline_stream(Exchange::BybitLinear);
+
+        let mut monitor = HealthMonitor::default();
+        monitor.sync([&binance, &bybit], 0);
+        for now in [1_000, 1_500] {
+            monitor.record(&kline_event(binance), now);
+            monitor.record(&kline_event(bybit), now);
+        }
+        assert_eq!(monitor.worst(2_000), Some(HealthStatus::Healthy));
+
+        monitor.record(
+            &Event::Disconnected(Exchange::BinanceLinear, "Rate limited".to_string()),
+            2_000,
+        );
+        let status = |monitor: &HealthMonitor, stream: StreamKind, now| {
+            monitor
+                .health(now)
+                .find(|health| health.stream == stream)
+                .map(|health| health.status)
+        };
+        assert_eq!(
+            status(&monitor, binance, 2_000),
+            Some(HealthStatus::Disconnected)
+        );
+        assert_eq!(status(&monitor, bybit, 2_000), Some(HealthStatus::Healthy));
+        assert_eq!(monitor.errors(&binance).count(), 1);
+
+        monitor.record(&kline_event(binance), 3_000);
+        assert_eq!(
+            status(&monitor, binance, 3_000),
+            Some(HealthStatus::Degraded)
+        );
+        assert_eq!(
+            status(&monitor, bybit, 1_500 + STALE_AFTER_MS + 1),
+            Some(HealthStatus::Degraded)
+        );
+    }
+}
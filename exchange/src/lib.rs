@@ -19,12 +19,14 @@ pub mod adapter;   // 交易所适配器
 pub mod connect;   // WebSocket 连接
 pub mod depth;     // 订单簿数据
 pub mod fetcher;   // 历史数据获取
+pub mod health;    // 数据流健康状态
 mod limiter;       // 速率限制器（私有）
 pub mod util;      // 工具函数和类型
 
 use crate::util::{ContractSize, MinQtySize, MinTicksize, Price};
 pub use adapter::Event;
 use adapter::{Exchange, MarketKind, StreamKind};
+pub use health::{HealthStatus, StreamHealth};
 
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
@@ -50,6 +52,7 @@ use std::{fmt, hash::Hash};
 /// - #[default] 标记默认变体（Quote）
 /// - 实现了 Copy trait，可以按位复制
 /// - 实现了 Hash trait，可以作为 HashMap 的键
+///
 /// ============================================================================
 #[repr(u8)]
 #[derive(Default, Copy, Clone, Debug, Eq, PartialEq, Hash, Deserialize, Serialize)]
@@ -71,6 +74,7 @@ pub enum SizeUnit {
 /// - AtomicU8 提供无锁的原子操作
 /// - static 变量在整个程序生命周期内存在
 /// - Ordering::Relaxed 表示不需要严格的内存顺序保证（性能最优）
+///
 /// ============================================================================
 static SIZE_CALC_UNIT: AtomicU8 = AtomicU8::new(SizeUnit::Base as u8);
 
@@ -134,6 +138,9 @@ impl std::fmt::Display for Timeframe {
                 Timeframe::MS300 => "300ms",
                 Timeframe::MS500 => "500ms",
                 Timeframe::MS1000 => "1s",
+                Timeframe::S5 => "5s",
+                Timeframe::S15 => "15s",
+                Timeframe::S30 => "30s",
                 Timeframe::M1 => "1m",
                 Timeframe::M3 => "3m",
                 Timeframe::M5 => "5m",
@@ -156,6 +163,9 @@ pub enum Timeframe {
     MS300,
     MS500,
     MS1000,
+    S5,
+    S15,
+    S30,
     M1,
     M3,
     M5,
@@ -190,6 +200,18 @@ impl Timeframe {
         Timeframe::MS1000,
     ];
 
+    /// Kline timeframes that no exchange streams, the pane builds them from its own trades
+    pub const SECONDS: [Timeframe; 4] = [
+        Timeframe::MS1000,
+        Timeframe::S5,
+        Timeframe::S15,
+        Timeframe::S30,
+    ];
+
+    pub fn is_sub_minute(self) -> bool {
+        self.to_milliseconds() < 60_000
+    }
+
     /// # Panics
     ///
     /// Will panic if the `Timeframe` is not one of the defined variants
@@ -216,6 +238,9 @@ impl Timeframe {
             Timeframe::MS300 => 300,
             Timeframe::MS500 => 500,
             Timeframe::MS1000 => 1_000,
+            Timeframe::S5 => 5_000,
+            Timeframe::S15 => 15_000,
+            Timeframe::S30 => 30_000,
             _ => {
                 let minutes = self.to_minutes();
                 u64::from(minutes) * 60_000
@@ -375,6 +400,7 @@ impl fmt::Display for SerTicker {
 /// - 快速复制（Copy trait）
 /// - 高效哈希（固定大小）
 /// - 缓存友好（连续内存）
+///
 /// ============================================================================
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Ticker {
@@ -701,6 +727,15 @@ pub struct Trade {
     pub qty: f32,
 }
 
+/// Forced order closing out a position, `is_sell` when a long was liquidated
+#[derive(Debug, Clone, Copy)]
+pub struct Liquidation {
+    pub time: u64,
+    pub is_sell: bool,
+    pub price: Price,
+    pub qty: f32,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Kline {
     pub time: u64,
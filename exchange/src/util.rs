@@ -87,6 +87,18 @@ impl PriceStep {
     pub fn from_f32(step: f32) -> Self {
         Self::from_f32_lossy(step)
     }
+
+    /// Number of decimal places needed to print multiples of this step, e.g. 2 for 0.25
+    pub fn decimals(self) -> u32 {
+        let mut units = self.units;
+        let mut decimals = Price::PRICE_SCALE as u32;
+
+        while decimals > 0 && units != 0 && units % 10 == 0 {
+            units /= 10;
+            decimals -= 1;
+        }
+        decimals
+    }
 }
 
 /// Fixed atomic unit scale: 10^-PRICE_SCALE is the smallest stored fraction.
@@ -1,4 +1,5 @@
 use rodio::{Decoder, OutputStream, OutputStreamHandle, Source};
+use rustc_hash::FxHashMap;
 use std::time::{Duration, Instant};
 
 pub const BUY_SOUND_DATA: &[u8] = include_bytes!("../assets/sounds/hard-typewriter-click.wav");
@@ -11,14 +12,38 @@ pub const HARD_BUY_SOUND: &str = "dry-pop-up.wav";
 pub const SELL_SOUND: &str = "hard-typewriter-hit.wav";
 pub const HARD_SELL_SOUND: &str = "fall-on-foam-splash.wav";
 
+pub const LARGE_BUY_SOUND: &str = "large-buy-tone";
+pub const LARGE_SELL_SOUND: &str = "large-sell-tone";
+
 const OVERLAP_THRESHOLD: Duration = Duration::from_millis(10);
 
+const SOUND_COUNT: usize = 6;
+
+/// Large trade alerts are synthesized tones, higher pitched for buys
+const LARGE_BUY_TONE_HZ: f32 = 1320.0;
+const LARGE_SELL_TONE_HZ: f32 = 660.0;
+const TONE_SAMPLE_RATE: u32 = 44_100;
+const TONE_DURATION: Duration = Duration::from_millis(180);
+
+/// When a sound last started and how many plays of it overlapped since
+type LastPlayed = (Option<Instant>, usize);
+
+#[derive(Debug, thiserror::Error)]
+pub enum AudioError {
+    #[error("{0}")]
+    Playback(String),
+    #[error("Invalid sound '{0}': {1}")]
+    InvalidSound(String, String),
+}
+
 #[derive(Clone, Copy)]
 pub enum SoundType {
     Buy = 0,
     HardBuy = 1,
     Sell = 2,
     HardSell = 3,
+    LargeBuy = 4,
+    LargeSell = 5,
 }
 
 impl std::fmt::Display for SoundType {
@@ -31,6 +56,8 @@ impl std::fmt::Display for SoundType {
                 Self::HardBuy => HARD_BUY_SOUND,
                 Self::Sell => SELL_SOUND,
                 Self::HardSell => HARD_SELL_SOUND,
+                Self::LargeBuy => LARGE_BUY_SOUND,
+                Self::LargeSell => LARGE_SELL_SOUND,
             }
         )
     }
@@ -46,8 +73,10 @@ pub struct SoundCache {
     _stream: OutputStream,
     stream_handle: OutputStreamHandle,
     volume: Option<f32>,
-    sample_buffers: [Option<rodio::buffer::SamplesBuffer<i16>>; 4],
-    last_played: [(Option<Instant>, usize); 4],
+    sample_buffers: [Option<rodio::buffer::SamplesBuffer<i16>>; SOUND_COUNT],
+    last_played: [LastPlayed; SOUND_COUNT],
+    /// User-supplied sounds decoded on first use, keyed by file name
+    custom_buffers: FxHashMap<String, (rodio::buffer::SamplesBuffer<i16>, LastPlayed)>,
 }
 
 impl SoundCache {
@@ -63,8 +92,9 @@ impl SoundCache {
             _stream: stream,
             stream_handle,
             volume,
-            sample_buffers: [None, None, None, None],
-            last_played: [(None, 0), (None, 0), (None, 0), (None, 0)],
+            sample_buffers: Default::default(),
+            last_played: [(None, 0); SOUND_COUNT],
+            custom_buffers: FxHashMap::default(),
         })
     }
 
@@ -84,6 +114,7 @@ impl SoundCache {
                 SoundType::HardBuy => (HARD_BUY_SOUND, HARD_BUY_SOUND_DATA),
                 SoundType::Sell => (SELL_SOUND, SELL_SOUND_DATA),
                 SoundType::HardSell => (HARD_SELL_SOUND, HARD_SELL_SOUND_DATA),
+                SoundType::LargeBuy | SoundType::LargeSell => continue,
             };
 
             if let Err(e) = cache.load_sound_from_memory(*sound_type, data) {
@@ -91,6 +122,9 @@ impl SoundCache {
             }
         }
 
+        cache.sample_buffers[usize::from(SoundType::LargeBuy)] = Some(tone(LARGE_BUY_TONE_HZ));
+        cache.sample_buffers[usize::from(SoundType::LargeSell)] = Some(tone(LARGE_SELL_TONE_HZ));
+
         Ok(cache)
     }
 
@@ -123,10 +157,36 @@ impl SoundCache {
         Ok(())
     }
 
-    pub fn play(&mut self, sound_type: SoundType) -> Result<(), String> {
-        let Some(base_volume) = self.volume else {
+    /// Decodes a WAV from the sounds folder once, later calls reuse the cached samples
+    pub fn load_custom_sound(&mut self, name: &str) -> Result<(), AudioError> {
+        if self.custom_buffers.contains_key(name) {
             return Ok(());
-        };
+        }
+
+        let invalid = |reason: String| AudioError::InvalidSound(name.to_string(), reason);
+
+        let data = std::fs::read(data::audio::custom_sound_path(name))
+            .map_err(|err| invalid(err.to_string()))?;
+        let decoder = Decoder::new_wav(std::io::Cursor::new(data))
+            .map_err(|err| invalid(format!("not a valid WAV file, {err}")))?;
+
+        let (channels, sample_rate) = (decoder.channels(), decoder.sample_rate());
+        let samples = decoder.collect::<Vec<i16>>();
+        if samples.is_empty() {
+            return Err(invalid("file has no audio samples".to_string()));
+        }
+
+        let buffer = rodio::buffer::SamplesBuffer::new(channels, sample_rate, samples);
+        self.custom_buffers
+            .insert(name.to_string(), (buffer, (None, 0)));
+        Ok(())
+    }
+
+    /// Plays a bundled sound, `gain` scaling the volume between 0 and 1
+    pub fn play(&mut self, sound_type: SoundType, gain: f32) -> Result<(), String> {
+        if self.volume.is_none() {
+            return Ok(());
+        }
 
         let index = usize::from(sound_type);
 
@@ -134,39 +194,29 @@ impl SoundCache {
             return Err(format!("Sound '{sound_type}' not loaded",));
         };
 
-        let now = Instant::now();
-        let (last_time, count) = &mut self.last_played[index];
-
-        let overlap_count = if let Some(last) = last_time {
-            if now.duration_since(*last) < OVERLAP_THRESHOLD {
-                *count += 1;
-                *last = now;
-                *count
-            } else {
-                *last = now;
-                *count = 1;
-                1
-            }
-        } else {
-            *last_time = Some(now);
-            *count = 1;
-            1
-        };
+        play_buffer(
+            &self.stream_handle,
+            self.volume.map(|volume| volume * gain),
+            buffer,
+            &mut self.last_played[index],
+        )
+    }
 
-        let adjusted_volume = base_volume / (overlap_count as f32);
+    /// Plays a sound from the sounds folder, loading it first if it wasn't yet
+    pub fn play_custom(&mut self, name: &str, gain: f32) -> Result<(), String> {
+        if self.volume.is_none() {
+            return Ok(());
+        }
 
-        let sink = match rodio::Sink::try_new(&self.stream_handle) {
-            Ok(sink) => sink,
-            Err(err) => {
-                return Err(format!("Failed to create audio sink: {}", err));
-            }
-        };
+        self.load_custom_sound(name)
+            .map_err(|err| err.to_string())?;
 
-        sink.set_volume(adjusted_volume / 100.0);
-        sink.append(buffer.clone());
-        sink.detach();
+        let Some((buffer, last_played)) = self.custom_buffers.get_mut(name) else {
+            return Err(format!("Sound '{name}' not loaded"));
+        };
 
-        Ok(())
+        let volume = self.volume.map(|volume| volume * gain);
+        play_buffer(&self.stream_handle, volume, buffer, last_played)
     }
 
     pub fn set_volume(&mut self, level: f32) {
@@ -185,3 +235,65 @@ impl SoundCache {
         self.volume.is_none()
     }
 }
+
+/// Plays `buffer` on a detached sink, quieter when the same sound overlaps itself
+fn play_buffer(
+    stream_handle: &OutputStreamHandle,
+    volume: Option<f32>,
+    buffer: &rodio::buffer::SamplesBuffer<i16>,
+    last_played: &mut LastPlayed,
+) -> Result<(), String> {
+    let Some(base_volume) = volume else {
+        return Ok(());
+    };
+
+    let now = Instant::now();
+    let (last_time, count) = last_played;
+
+    let overlap_count = if let Some(last) = last_time {
+        if now.duration_since(*last) < OVERLAP_THRESHOLD {
+            *count += 1;
+            *last = now;
+            *count
+        } else {
+            *last = now;
+            *count = 1;
+            1
+        }
+    } else {
+        *last_time = Some(now);
+        *count = 1;
+        1
+    };
+
+    let adjusted_volume = base_volume / (overlap_count as f32);
+
+    let sink = match rodio::Sink::try_new(stream_handle) {
+        Ok(sink) => sink,
+        Err(err) => {
+            return Err(format!("Failed to create audio sink: {}", err));
+        }
+    };
+
+    sink.set_volume(adjusted_volume / 100.0);
+    sink.append(buffer.clone());
+    sink.detach();
+
+    Ok(())
+}
+
+/// A short sine tone with a linear fade-out, so it doesn't click at the end
+fn tone(frequency: f32) -> rodio::buffer::SamplesBuffer<i16> {
+    let sample_count = (TONE_DURATION.as_secs_f32() * TONE_SAMPLE_RATE as f32) as usize;
+
+    let samples = (0..sample_count)
+        .map(|i| {
+            let t = i as f32 / TONE_SAMPLE_RATE as f32;
+            let envelope = 1.0 - (i as f32 / sample_count as f32);
+            let value = (t * frequency * std::f32::consts::TAU).sin() * envelope * 0.5;
+            (value * f32::from(i16::MAX)) as i16
+        })
+        .collect::<Vec<i16>>();
+
+    rodio::buffer::SamplesBuffer::new(1, TONE_SAMPLE_RATE, samples)
+}
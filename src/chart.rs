@@ -10,19 +10,30 @@
 // - 缓存优化：使用 Canvas Cache 减少重绘
 // ============================================================================
 
+mod cells;           // 足迹单元格的 GPU 渲染（私有）
 pub mod comparison;  // 对比图模块
+pub mod correlation; // 相关性矩阵模块
 pub mod heatmap;     // 热力图模块
 pub mod indicator;   // 指标模块
 pub mod kline;       // K线图模块
+mod minimap;         // 全历史缩略条（私有）
+pub mod range;       // 区间K线图模块
 mod scale;           // 坐标轴模块（私有）
 
 use crate::style;
 use crate::widget::multi_split::{DRAG_SIZE, MultiSplit};
 use crate::widget::tooltip;
-use data::chart::{Autoscale, Basis, PlotData, ViewConfig, indicator::Indicator};
-use exchange::TickerInfo;
+pub use cells::wgpu_active; // 当前是否由 wgpu 绘制，设置面板据此隐藏 GPU 选项
+use data::chart::{
+    AutoScrollMode, Autoscale, Basis, Measurement, PlotData, PriceAxisMode, PriceAxisRounding,
+    ViewConfig,
+    annotation::{PriceAlert, PriceLine, PriceLines},
+    indicator::Indicator,
+};
+use exchange::adapter::StreamKind;
 use exchange::fetcher::{FetchRange, FetchRequests, FetchSpec, RequestHandler};
-use exchange::util::{Price, PriceStep};
+use exchange::util::{MinTicksize, Price, PriceStep};
+use exchange::{Kline, TickerInfo};
 use scale::linear::PriceInfoLabel;
 use scale::{AxisLabelsX, AxisLabelsY};
 use rust_i18n::t;
@@ -30,8 +41,10 @@ use iced::theme::palette::Extended;
 use iced::widget::canvas::{self, Cache, Canvas, Event, Frame, LineDash, Path, Stroke};
 use iced::{
     Alignment, Element, Length, Point, Rectangle, Size, Theme, Vector, keyboard, mouse, padding,
-    widget::{button, center, column, container, mouse_area, row, rule, text},
+    touch,
+    widget::{button, center, column, container, mouse_area, row, rule, stack, text, text_input},
 };
+use iced_core::mouse::click as mouse_click;
 
 /// 缩放敏感度常量（数值越大，缩放越慢）
 const ZOOM_SENSITIVITY: f32 = 30.0;
@@ -39,6 +52,15 @@ const ZOOM_SENSITIVITY: f32 = 30.0;
 /// 默认文本大小（像素）
 const TEXT_SIZE: f32 = 12.0;
 
+/// 测量尺拖拽阈值（像素），小于该距离的松开视为点击
+const RULER_DRAG_THRESHOLD: f32 = 4.0;
+
+/// 价格线命中距离（像素），光标在该范围内可拖拽或删除价格线
+const PRICE_LINE_HIT_DISTANCE: f32 = 4.0;
+
+/// 在空白处按住不动超过该时长即添加价格线，供触屏使用（替代双击）
+const PRICE_LINE_HOLD: std::time::Duration = std::time::Duration::from_millis(500);
+
 /// ============================================================================
 /// Interaction - 用户交互模式枚举
 /// 
@@ -48,6 +70,7 @@ const TEXT_SIZE: f32 = 12.0;
 /// - #[derive(Default)] 自动生成 Default trait 实现
 /// - #[default] 标记默认变体
 /// - Debug, Clone, Copy 使这个枚举轻量且易于调试
+///
 /// ============================================================================
 #[derive(Default, Debug, Clone, Copy)]
 pub enum Interaction {
@@ -77,6 +100,20 @@ pub enum Interaction {
     Ruler {
         start: Option<Point>,
     },
+
+    /// 单击后的短暂状态，用于识别双击（双击添加价格线）
+    Clicked(mouse_click::Click),
+
+    /// 在空白处按下、尚未移动
+    ///
+    /// 移动超过拖拽阈值后转为平移，按住超过 `PRICE_LINE_HOLD` 则添加价格线并开始拖拽它
+    PressStart(std::time::Instant, Point),
+
+    /// 拖拽价格线
+    ///
+    /// index: 被拖拽的价格线索引
+    /// start: 按下时的位置，未移动则视为单击
+    MovingPriceLine { index: usize, start: Point },
 }
 
 /// ============================================================================
@@ -101,8 +138,9 @@ pub enum AxisScaleClicked {
 /// Rust 特性：
 /// - #[derive(Debug, Clone, Copy)] 使消息可调试、可复制
 /// - Copy trait 表示可以按位复制（所有字段都是 Copy 类型）
+///
 /// ============================================================================
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum Message {
     /// 平移事件
     /// Vector 包含 x 和 y 方向的平移量
@@ -118,9 +156,70 @@ pub enum Message {
     /// 切换自动缩放模式
     /// 循环切换：None -> CenterLatest -> FitToVisible -> None
     AutoscaleToggled,
+
+    /// 设置新K线出现时的自动滚动方式
+    /// 关闭后新数据只延长序列，不移动视口
+    AutoScrollChanged(AutoScrollMode),
+
+    /// 切换"锁定单元格大小"
+    /// 开启后调整面板大小时单元格宽高不变，改为增减可见K线数量
+    FixedCellsToggled,
+
+    /// 切换价格轴的显示方式：绝对价格 <-> 相对锚点的涨跌百分比
+    /// 切到百分比时以最早可见K线的收盘价为锚点
+    PriceAxisModeToggled,
+
+    /// 设置价格轴标签的取整步长：自动或固定为某个价格间隔的整数倍
+    PriceRoundingChanged(PriceAxisRounding),
+
+    /// 将单元格宽度恢复为图表类型的默认值（右键菜单）
+    ResetCellWidth,
     
     /// 十字线移动事件（仅重绘十字线，不重绘主图）
-    CrosshairMoved,
+    ///
+    /// 携带光标所在的时间戳（毫秒），光标不在图表内或非时间基准时为 None
+    /// 同一链接组内的其他面板据此绘制同步的时间线
+    CrosshairMoved(Option<u64>),
+
+    /// 测量尺完成或清除事件
+    ///
+    /// 完成一次拖拽测量后携带测量结果，保存在面板的 ViewState 中直到被替换或清除
+    Measured(Option<Measurement>),
+
+    /// 双击图表空白处，在该价格添加水平价格线
+    AddPriceLine(Price),
+
+    /// 价格线被拖拽到新价格（松开鼠标时触发）
+    MovePriceLine(usize, Price),
+
+    /// 右键删除价格线
+    RemovePriceLine(usize),
+
+    /// 打开（Some）或关闭（None）价格线标签编辑框
+    EditPriceLineLabel(Option<usize>),
+
+    /// 价格线标签输入
+    PriceLineLabelChanged(usize, String),
+
+    /// 打开（Some）或关闭（None）右键菜单
+    ///
+    /// 携带菜单在画布上的位置、光标所在K线的键（时间戳或 tick 索引）和光标处价格
+    ContextMenu(Option<(Point, u64, Price)>),
+
+    /// 在该时间戳的K线处添加锚定 VWAP（由面板保存）
+    AnchorVwap(u64),
+
+    /// 将该价格按面板的价格步长格式化后复制到剪贴板（右键菜单或 Ctrl+C）
+    CopyPrice(Price),
+
+    /// 将该键对应K线的 OHLC 以制表符分隔复制到剪贴板
+    CopyOhlc(u64),
+
+    /// 设置（Some）或取消（None）价格线上的价格提醒
+    SetPriceAlert(usize, Option<PriceAlert>),
+
+    /// 在缩略条上点击或拖动，将该键（时间戳或 tick 索引）的K线移到视口中央
+    MinimapScrolled(u64),
     
     /// Y轴缩放事件
     /// 
@@ -174,6 +273,7 @@ pub enum Message {
 ///    - &'_ self 中的 '_ 是匿名生命周期，编译器自动推断
 ///    - &[Self::IndicatorKind] 切片引用的生命周期与函数调用绑定
 ///    - Vec<Element<'_, Message>> 返回值的生命周期与 self 绑定
+///
 /// ============================================================================
 pub trait Chart: PlotConstants + canvas::Program<Message> {
     /// 关联类型：指标种类
@@ -240,6 +340,11 @@ pub trait Chart: PlotConstants + canvas::Program<Message> {
     /// - None: 不适用（如对比图）
     fn interval_keys(&self) -> Option<Vec<u64>>;
 
+    /// 获取时段分隔（UTC 日/周）边界的时间戳
+    ///
+    /// 用于在 X 轴上标注新时段的起点，未启用时段分隔的图表返回空列表
+    fn session_boundaries(&self) -> Vec<u64>;
+
     /// 获取自动缩放的坐标
     /// 
     /// 返回自动缩放模式下的目标平移向量
@@ -252,10 +357,48 @@ pub trait Chart: PlotConstants + canvas::Program<Message> {
     /// - false: 不支持（如热力图）
     fn supports_fit_autoscaling(&self) -> bool;
 
+    /// 右键菜单是否提供"在此锚定 VWAP"
+    ///
+    /// 仅在K线图启用锚定 VWAP 研究且为时间基准时为 true
+    fn supports_anchored_vwap(&self) -> bool;
+
+    /// 获取该键（时间戳或 tick 索引）处的K线
+    ///
+    /// 用于右键菜单的"复制 OHLC"，没有K线的图表（如热力图）返回 None
+    fn kline_at(&self, key: u64) -> Option<Kline>;
+
+    /// 获取可见区域内最早的一根K线
+    ///
+    /// 用作百分比价格轴的锚点，没有K线的图表（如热力图）返回 None
+    fn oldest_visible_kline(&self) -> Option<Kline>;
+
+    /// 是否支持"跟随最新"开关
+    ///
+    /// 热力图有自己的暂停缓冲机制，不使用该开关
+    fn supports_follow_latest(&self) -> bool;
+
+    /// 叠在画布下方的 GPU 图层
+    ///
+    /// 足迹图选择 GPU 渲染单元格时返回着色器控件，其余返回 None
+    fn gpu_layer(&self) -> Option<Element<'_, Message>>;
+
+    /// 主图下方的全历史缩略条，自带固定高度
+    ///
+    /// 仅开启缩略条的蜡烛图返回，其余返回 None
+    fn minimap(&self) -> Option<Element<'_, Message>>;
+
+    /// 面板过小时的精简模式，隐藏坐标轴标签
+    ///
+    /// 足迹图在面板小于设置的尺寸时改画普通蜡烛图，其余返回 false
+    fn is_compact(&self) -> bool;
+
     /// 检查图表是否为空
     /// 
     /// 用于显示"等待数据"提示
     fn is_empty(&self) -> bool;
+
+    /// 图表持有的数据点数量，用于性能浮层
+    fn datapoint_count(&self) -> usize;
 }
 
 fn canvas_interaction<T: Chart>(
@@ -269,14 +412,93 @@ fn canvas_interaction<T: Chart>(
         return Some(canvas::Action::publish(Message::BoundsChanged(bounds)));
     }
 
+    // 单指触摸按左键处理，光标位置取手指位置
+    if let Event::Touch(touch_event) = event {
+        let (mouse_event, position) = match *touch_event {
+            touch::Event::FingerPressed { position, .. } => {
+                (mouse::Event::ButtonPressed(mouse::Button::Left), position)
+            }
+            touch::Event::FingerMoved { position, .. } => {
+                (mouse::Event::CursorMoved { position }, position)
+            }
+            touch::Event::FingerLifted { position, .. }
+            | touch::Event::FingerLost { position, .. } => {
+                (mouse::Event::ButtonReleased(mouse::Button::Left), position)
+            }
+        };
+
+        return canvas_interaction(
+            chart,
+            interaction,
+            &Event::Mouse(mouse_event),
+            bounds,
+            mouse::Cursor::Available(position),
+        );
+    }
+
     let shrunken_bounds = bounds.shrink(DRAG_SIZE * 4.0);
     let cursor_position = cursor.position_in(shrunken_bounds);
 
     if let Event::Mouse(mouse::Event::ButtonReleased(_)) = event {
         match interaction {
-            Interaction::Panning { .. } | Interaction::Zoomin { .. } => {
+            Interaction::PressStart(_, start) => {
+                *interaction = Interaction::Clicked(mouse_click::Click::new(
+                    *start,
+                    mouse::Button::Left,
+                    None,
+                ));
+            }
+            Interaction::Panning { start, .. } => {
+                // 未拖动的单击保留下来，用于识别双击
+                *interaction = match cursor_position {
+                    Some(end) if start.distance(end) <= RULER_DRAG_THRESHOLD => {
+                        Interaction::Clicked(mouse_click::Click::new(
+                            *start,
+                            mouse::Button::Left,
+                            None,
+                        ))
+                    }
+                    _ => Interaction::None,
+                };
+            }
+            Interaction::Zoomin { .. } => {
                 *interaction = Interaction::None;
             }
+            Interaction::MovingPriceLine { index, start } => {
+                let (index, start) = (*index, *start);
+                *interaction = Interaction::None;
+
+                match cursor_position {
+                    Some(end) if start.distance(end) <= RULER_DRAG_THRESHOLD => {
+                        *interaction = Interaction::Clicked(mouse_click::Click::new(
+                            start,
+                            mouse::Button::Left,
+                            None,
+                        ));
+                    }
+                    Some(end) => {
+                        let price = chart.state().price_at(end.y, bounds.size());
+
+                        return Some(canvas::Action::publish(Message::MovePriceLine(
+                            index, price,
+                        )));
+                    }
+                    None => {}
+                }
+            }
+            // 拖拽测量：松开鼠标即完成；原地点击则保持起点，等待第二次点击
+            Interaction::Ruler { start: Some(start) } => {
+                if let Some(end) = cursor_position
+                    && start.distance(end) > RULER_DRAG_THRESHOLD
+                {
+                    let measurement = chart.state().measure(*start, end, bounds.size());
+                    *interaction = Interaction::None;
+
+                    return Some(canvas::Action::publish(Message::Measured(Some(
+                        measurement,
+                    ))));
+                }
+            }
             _ => {}
         }
     }
@@ -295,29 +517,121 @@ fn canvas_interaction<T: Chart>(
                 mouse::Event::ButtonPressed(button) => {
                     let cursor_in_bounds = cursor_position?;
 
+                    let line_at_cursor = state.price_line_at(cursor_in_bounds.y, bounds.size());
+
+                    if let mouse::Button::Right = button
+                        && let Some(index) = line_at_cursor
+                    {
+                        return Some(
+                            canvas::Action::publish(Message::RemovePriceLine(index)).and_capture(),
+                        );
+                    }
+
+                    if let mouse::Button::Right = button
+                        && let Some(menu_position) = cursor.position_in(bounds)
+                    {
+                        let region = state.visible_region(bounds.size());
+                        let (key, _) =
+                            state.snap_x_to_index(cursor_in_bounds.x, bounds.size(), region);
+                        let price = state.price_at(cursor_in_bounds.y, bounds.size());
+
+                        return Some(
+                            canvas::Action::publish(Message::ContextMenu(Some((
+                                menu_position,
+                                key,
+                                price,
+                            ))))
+                            .and_capture(),
+                        );
+                    }
+
+                    if state.context_menu.is_some() {
+                        return Some(
+                            canvas::Action::publish(Message::ContextMenu(None)).and_capture(),
+                        );
+                    }
+
                     if let mouse::Button::Left = button {
                         match interaction {
                             Interaction::None
                             | Interaction::Panning { .. }
-                            | Interaction::Zoomin { .. } => {
-                                *interaction = Interaction::Panning {
-                                    translation: state.translation,
-                                    start: cursor_in_bounds,
+                            | Interaction::Zoomin { .. }
+                            | Interaction::Clicked(_)
+                            | Interaction::PressStart(..)
+                            | Interaction::MovingPriceLine { .. } => {
+                                let previous = match interaction {
+                                    Interaction::Clicked(click) => Some(*click),
+                                    _ => None,
                                 };
+                                let click = mouse_click::Click::new(
+                                    cursor_in_bounds,
+                                    mouse::Button::Left,
+                                    previous,
+                                );
+
+                                if click.kind() == mouse_click::Kind::Double {
+                                    *interaction = Interaction::None;
+
+                                    let msg = match line_at_cursor {
+                                        Some(index) => Message::EditPriceLineLabel(Some(index)),
+                                        None => Message::AddPriceLine(
+                                            state.price_at(cursor_in_bounds.y, bounds.size()),
+                                        ),
+                                    };
+                                    return Some(canvas::Action::publish(msg).and_capture());
+                                }
+
+                                if let Some(index) = line_at_cursor {
+                                    *interaction = Interaction::MovingPriceLine {
+                                        index,
+                                        start: cursor_in_bounds,
+                                    };
+                                } else {
+                                    let now = std::time::Instant::now();
+                                    *interaction = Interaction::PressStart(now, cursor_in_bounds);
+
+                                    return Some(
+                                        canvas::Action::request_redraw_at(now + PRICE_LINE_HOLD)
+                                            .and_capture(),
+                                    );
+                                }
                             }
-                            Interaction::Ruler { start } if start.is_none() => {
+                            Interaction::Ruler { start: None } => {
                                 *interaction = Interaction::Ruler {
                                     start: Some(cursor_in_bounds),
                                 };
                             }
-                            Interaction::Ruler { .. } => {
+                            Interaction::Ruler { start: Some(start) } => {
+                                let measurement =
+                                    state.measure(*start, cursor_in_bounds, bounds.size());
                                 *interaction = Interaction::None;
+
+                                return Some(
+                                    canvas::Action::publish(Message::Measured(Some(measurement)))
+                                        .and_capture(),
+                                );
                             }
                         }
                     }
                     Some(canvas::Action::request_redraw().and_capture())
                 }
                 mouse::Event::CursorMoved { .. } => match *interaction {
+                    Interaction::PressStart(_, start)
+                        if cursor_position.is_some_and(|cursor| {
+                            start.distance(cursor) > RULER_DRAG_THRESHOLD
+                        }) =>
+                    {
+                        let cursor_in_bounds = cursor_position?;
+                        *interaction = Interaction::Panning {
+                            translation: state.translation,
+                            start,
+                        };
+
+                        let msg = Message::Translated(
+                            state.translation + (cursor_in_bounds - start) * (1.0 / state.scaling),
+                        );
+                        Some(canvas::Action::publish(msg).and_capture())
+                    }
                     Interaction::Panning { translation, start } => {
                         let cursor_in_bounds = cursor_position?;
                         let msg = Message::Translated(
@@ -325,8 +639,18 @@ fn canvas_interaction<T: Chart>(
                         );
                         Some(canvas::Action::publish(msg).and_capture())
                     }
-                    Interaction::None | Interaction::Ruler { .. } => {
-                        Some(canvas::Action::publish(Message::CrosshairMoved))
+                    Interaction::None
+                    | Interaction::Ruler { .. }
+                    | Interaction::Clicked(_)
+                    | Interaction::PressStart(..)
+                    | Interaction::MovingPriceLine { .. } => {
+                        let cursor_time = cursor
+                            .position_in(bounds)
+                            .and_then(|position| state.cursor_time(position, bounds.size()));
+
+                        Some(canvas::Action::publish(Message::CrosshairMoved(
+                            cursor_time,
+                        )))
                     }
                     _ => None,
                 },
@@ -428,9 +752,13 @@ fn canvas_interaction<T: Chart>(
             }
         }
         Event::Keyboard(keyboard_event) => {
-            cursor_position?;
+            let cursor_in_bounds = cursor_position?;
             match keyboard_event {
-                iced::keyboard::Event::KeyPressed { key, .. } => match key.as_ref() {
+                iced::keyboard::Event::KeyPressed { key, modifiers, .. } => match key.as_ref() {
+                    keyboard::Key::Character("c") if modifiers.command() => {
+                        let price = chart.state().price_at(cursor_in_bounds.y, bounds.size());
+                        Some(canvas::Action::publish(Message::CopyPrice(price)).and_capture())
+                    }
                     keyboard::Key::Named(keyboard::key::Named::Shift) => {
                         *interaction = Interaction::Ruler { start: None };
                         Some(canvas::Action::request_redraw().and_capture())
@@ -444,6 +772,23 @@ fn canvas_interaction<T: Chart>(
                 _ => None,
             }
         }
+        // 按住未移动达到时长：在按下处添加价格线，新线追加在末尾，随即进入拖拽
+        Event::Window(iced::window::Event::RedrawRequested(now)) => match *interaction {
+            Interaction::PressStart(pressed_at, start)
+                if now.duration_since(pressed_at) >= PRICE_LINE_HOLD =>
+            {
+                let state = chart.state();
+                *interaction = Interaction::MovingPriceLine {
+                    index: state.price_lines.len(),
+                    start,
+                };
+
+                Some(canvas::Action::publish(Message::AddPriceLine(
+                    state.price_at(start.y, bounds.size()),
+                )))
+            }
+            _ => None,
+        },
         _ => None,
     }
 }
@@ -486,6 +831,17 @@ pub fn update<T: Chart>(chart: &mut T, message: &Message) {
                 state.translation = *translation;
                 state.layout.autoscale = None;
             }
+
+            state.update_following();
+        }
+        Message::MinimapScrolled(key) => {
+            let state = chart.mut_state();
+            state.translation.x = -state.interval_to_x(*key);
+
+            if state.layout.autoscale == Some(Autoscale::CenterLatest) {
+                state.layout.autoscale = None;
+            }
+            state.update_following();
         }
         Message::Scaled(scaling, translation) => {
             let state = chart.mut_state();
@@ -493,6 +849,7 @@ pub fn update<T: Chart>(chart: &mut T, message: &Message) {
             state.translation = *translation;
 
             state.layout.autoscale = None;
+            state.update_following();
         }
         Message::AutoscaleToggled => {
             let supports_fit_autoscaling = chart.supports_fit_autoscaling();
@@ -517,6 +874,58 @@ pub fn update<T: Chart>(chart: &mut T, message: &Message) {
                 state.scaling = 1.0;
             }
         }
+        Message::AutoScrollChanged(mode) => {
+            let autoscaled_x = chart.autoscaled_coords().x;
+            let state = chart.mut_state();
+
+            let was_off = state.layout.auto_scroll == AutoScrollMode::None;
+            state.layout.auto_scroll = *mode;
+
+            // 重新开启时跳回最新K线
+            if was_off && *mode != AutoScrollMode::None && !state.is_latest_visible() {
+                state.translation.x = autoscaled_x;
+            }
+            state.update_following();
+        }
+        Message::FixedCellsToggled => {
+            let state = chart.mut_state();
+            state.layout.fixed_cells = !state.layout.fixed_cells;
+
+            if state.layout.fixed_cells {
+                clamp_cell_size(chart);
+            }
+        }
+        Message::PriceAxisModeToggled => {
+            let anchor = chart
+                .oldest_visible_kline()
+                .map(|kline| kline.close.to_f32());
+            let state = chart.mut_state();
+
+            state.layout.price_axis = match (state.layout.price_axis, anchor) {
+                (PriceAxisMode::Absolute, Some(anchor_price)) => {
+                    PriceAxisMode::Percent { anchor_price }
+                }
+                _ => PriceAxisMode::Absolute,
+            };
+            state.cache.clear_all();
+        }
+        Message::PriceRoundingChanged(rounding) => {
+            let state = chart.mut_state();
+            state.layout.price_rounding = *rounding;
+            state.cache.clear_all();
+        }
+        Message::ResetCellWidth => {
+            let default_cell_width = T::default_cell_width(chart);
+            let state = chart.mut_state();
+            state.context_menu = None;
+
+            // 与键盘缩放相同，按比例缩放平移量以保持视图中心不变
+            if state.cell_width > f32::EPSILON {
+                state.translation.x *= default_cell_width / state.cell_width;
+            }
+            state.cell_width = default_cell_width;
+            state.update_following();
+        }
         Message::XScaling(delta, cursor_to_center_x, is_wheel_scroll) => {
             let min_cell_width = T::min_cell_width(chart);
             let max_cell_width = T::max_cell_width(chart);
@@ -599,6 +1008,8 @@ pub fn update<T: Chart>(chart: &mut T, message: &Message) {
                     state.layout.autoscale = None;
                 }
             }
+
+            state.update_following();
         }
         Message::YScaling(delta, cursor_to_center_y, is_wheel_scroll) => {
             let min_cell_height = T::min_cell_height(chart);
@@ -652,6 +1063,11 @@ pub fn update<T: Chart>(chart: &mut T, message: &Message) {
             if state.layout.autoscale != Some(Autoscale::CenterLatest) {
                 state.translation.x += center_delta_x;
             }
+
+            // 锁定时右边缘保持不动，可见K线数量随宽度变化，单元格大小只做范围限制
+            if state.layout.fixed_cells {
+                clamp_cell_size(chart);
+            }
         }
         Message::SplitDragged(split, size) => {
             let state = chart.mut_state();
@@ -660,11 +1076,203 @@ pub fn update<T: Chart>(chart: &mut T, message: &Message) {
                 *split = (size * 100.0).round() / 100.0;
             }
         }
-        Message::CrosshairMoved => return chart.invalidate_crosshair(),
+        Message::CrosshairMoved(_) => return chart.invalidate_crosshair(),
+        Message::Measured(measurement) => {
+            chart.mut_state().measurement = *measurement;
+            return chart.invalidate_crosshair();
+        }
+        // 价格线由 dashboard 按交易对统一保存，再通过 `sync_price_lines` 同步回来
+        Message::AddPriceLine(_)
+        | Message::MovePriceLine(..)
+        | Message::PriceLineLabelChanged(..)
+        | Message::SetPriceAlert(..) => return,
+        Message::RemovePriceLine(_) => {
+            chart.mut_state().editing_price_line = None;
+            return;
+        }
+        Message::EditPriceLineLabel(index) => {
+            chart.mut_state().editing_price_line = *index;
+            return;
+        }
+        Message::ContextMenu(menu) => {
+            chart.mut_state().context_menu = *menu;
+            return;
+        }
+        // 锚点由面板按 pane 保存，再通过 `KlineChart::set_kline_studies` 同步回来
+        // 复制由面板通过 `clipboard_text` 取得文本后交给剪贴板任务
+        Message::AnchorVwap(_) | Message::CopyPrice(_) | Message::CopyOhlc(_) => {
+            chart.mut_state().context_menu = None;
+            return;
+        }
     }
     chart.invalidate_all();
 }
 
+/// 将单元格宽高限制在图表类型的范围内
+fn clamp_cell_size<T: Chart>(chart: &mut T) {
+    let (min_width, max_width) = (T::min_cell_width(chart), T::max_cell_width(chart));
+    let (min_height, max_height) = (T::min_cell_height(chart), T::max_cell_height(chart));
+
+    let state = chart.mut_state();
+    state.cell_width = state.cell_width.clamp(min_width, max_width);
+    state.cell_height = state.cell_height.clamp(min_height, max_height);
+}
+
+/// 复制类消息对应的剪贴板文本，其他消息或该键处没有K线时返回 None
+pub fn clipboard_text<T: Chart>(chart: &T, message: &Message) -> Option<String> {
+    let state = chart.state();
+
+    match message {
+        Message::CopyPrice(price) => Some(state.format_price(*price)),
+        Message::CopyOhlc(key) => chart.kline_at(*key).map(|kline| {
+            [kline.open, kline.high, kline.low, kline.close]
+                .map(|price| state.format_price(price))
+                .join("\t")
+        }),
+        _ => None,
+    }
+}
+
+/// 面板切换交易对前记下的缩放状态，切回同一交易对和图表类型时恢复
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ZoomState {
+    pub cell_width: f32,
+    pub cell_height: f32,
+    /// 水平平移量，即视口离最新K线的距离
+    pub scroll_offset: f32,
+}
+
+pub fn zoom_state<T: Chart>(chart: &T) -> ZoomState {
+    let state = chart.state();
+
+    ZoomState {
+        cell_width: state.cell_width,
+        cell_height: state.cell_height,
+        scroll_offset: state.translation.x,
+    }
+}
+
+/// 恢复记下的缩放状态，单元格大小限制在图表类型允许的范围内
+pub fn restore_zoom<T: Chart>(chart: &mut T, zoom: ZoomState) {
+    let state = chart.mut_state();
+    state.cell_width = zoom.cell_width;
+    state.cell_height = zoom.cell_height;
+    state.translation.x = zoom.scroll_offset;
+    clamp_cell_size(chart);
+
+    chart.mut_state().update_following();
+    chart.invalidate_all();
+}
+
+/// 设置链接面板的十字线时间戳，仅在变化时重绘十字线层
+pub fn set_synced_crosshair<T: Chart>(chart: &mut T, timestamp: Option<u64>) {
+    if chart.state().synced_crosshair != timestamp {
+        chart.mut_state().synced_crosshair = timestamp;
+        chart.invalidate_crosshair();
+    }
+}
+
+/// 同步本图表交易对的价格线，仅在变化时重绘十字线层
+pub fn sync_price_lines<T: Chart>(chart: &mut T, store: &PriceLines) {
+    let lines = store.get(&chart.state().ticker_info.ticker);
+
+    if chart.state().price_lines != lines {
+        let state = chart.mut_state();
+        state.price_lines = lines.to_vec();
+
+        if state
+            .editing_price_line
+            .is_some_and(|index| index >= lines.len())
+        {
+            state.editing_price_line = None;
+        }
+
+        chart.invalidate_crosshair();
+    }
+}
+
+/// 键盘导航按键
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NavigationKey {
+    /// 向左平移（更早的数据）
+    Left,
+    /// 向右平移（更新的数据）
+    Right,
+    ZoomIn,
+    ZoomOut,
+}
+
+/// 用键盘平移或缩放图表
+///
+/// 平移量为可见宽度的 `pan_step` 倍；缩放以视图中心为锚点，
+/// 单元格宽度限制在图表类型的 `min_cell_width`/`max_cell_width` 之间
+pub fn navigate<T: Chart>(chart: &mut T, key: NavigationKey, steps: data::config::KeyboardNav) {
+    let min_cell_width = T::min_cell_width(chart);
+    let max_cell_width = T::max_cell_width(chart);
+
+    let state = chart.mut_state();
+
+    match key {
+        NavigationKey::Left | NavigationKey::Right => {
+            let visible_width = state.bounds.width / state.scaling;
+            let shift = visible_width * steps.pan_step;
+
+            // 视口左移即平移量增大
+            state.translation.x += match key {
+                NavigationKey::Left => shift,
+                _ => -shift,
+            };
+
+            if state.layout.autoscale == Some(Autoscale::CenterLatest) {
+                state.layout.autoscale = None;
+            }
+        }
+        NavigationKey::ZoomIn | NavigationKey::ZoomOut => {
+            let factor = match key {
+                NavigationKey::ZoomIn => 1.0 + steps.zoom_step,
+                _ => 1.0 / (1.0 + steps.zoom_step),
+            };
+
+            let old_width = state.cell_width;
+            let new_width = (old_width * factor).clamp(min_cell_width, max_cell_width);
+
+            if (new_width - old_width).abs() < f32::EPSILON {
+                return;
+            }
+
+            // 横坐标与单元格宽度成正比（最新数据点在 x = 0），按比例缩放平移量即可保持视图中心不变
+            state.translation.x *= new_width / old_width;
+            state.cell_width = new_width;
+        }
+    }
+
+    state.update_following();
+    chart.invalidate_all();
+}
+
+/// 清除测量尺结果，返回是否存在需要清除的测量
+pub fn clear_measurement<T: Chart>(chart: &mut T) -> bool {
+    if chart.state().measurement.is_none() {
+        return false;
+    }
+
+    chart.mut_state().measurement = None;
+    chart.invalidate_crosshair();
+    true
+}
+
+/// 新K线出现时的自动滚动方式，不支持跟随最新的图表为 `None`
+pub fn auto_scroll<T: Chart>(chart: &T) -> Option<AutoScrollMode> {
+    chart
+        .supports_follow_latest()
+        .then_some(chart.state().layout.auto_scroll)
+}
+
+/// 当前图表交易对的价格线（由 dashboard 同步）
+pub fn price_lines<T: Chart>(chart: &T) -> &[PriceLine] {
+    &chart.state().price_lines
+}
+
 pub fn view<'a, T: Chart>(
     chart: &'a T,
     indicators: &'a [T::IndicatorKind],
@@ -686,6 +1294,7 @@ pub fn view<'a, T: Chart>(
         timezone,
         chart_bounds: state.bounds,
         interval_keys: chart.interval_keys(),
+        session_boundaries: chart.session_boundaries(),
         autoscaling: state.layout.autoscale,
     })
     .width(Length::Fill)
@@ -709,15 +1318,37 @@ pub fn view<'a, T: Chart>(
         .on_press(Message::AutoscaleToggled)
         .style(move |theme: &Theme, status| style::button::transparent(theme, status, is_active));
 
-        row![
-            iced::widget::space::horizontal(),
-            tooltip(
+        let mut buttons = row![iced::widget::space::horizontal()];
+
+        if chart.supports_fit_autoscaling() {
+            let is_fixed = state.layout.fixed_cells;
+
+            let fixed_cells_button = button(
+                text("L")
+                    .size(10)
+                    .align_x(Alignment::Center)
+                    .align_y(Alignment::Center),
+            )
+            .height(Length::Fill)
+            .on_press(Message::FixedCellsToggled)
+            .style(move |theme: &Theme, status| {
+                style::button::transparent(theme, status, is_fixed)
+            });
+
+            buttons = buttons.push(tooltip(
+                fixed_cells_button,
+                Some("Lock cell size, resizing changes the number of visible bars"),
+                iced::widget::tooltip::Position::Top,
+            ));
+        }
+
+        buttons
+            .push(tooltip(
                 autoscale_button,
                 autoscale_btn_tooltip,
-                iced::widget::tooltip::Position::Top
-            ),
-        ]
-        .padding(2)
+                iced::widget::tooltip::Position::Top,
+            ))
+            .padding(2)
     };
 
     let y_labels_width = state.y_labels_width();
@@ -734,23 +1365,102 @@ pub fn view<'a, T: Chart>(
             cell_height: state.cell_height,
             basis: state.basis,
             chart_bounds: state.bounds,
+            price_axis: state.layout.price_axis,
+            price_rounding: state.layout.price_rounding,
         })
         .width(Length::Fill)
         .height(Length::Fill);
 
-        let main_chart: Element<_> = row![
-            container(Canvas::new(chart).width(Length::Fill).height(Length::Fill))
-                .width(Length::FillPortion(10))
-                .height(Length::FillPortion(120)),
-            rule::vertical(1).style(style::split_ruler),
-            container(
-                mouse_area(axis_labels_y)
-                    .on_double_click(Message::DoubleClick(AxisScaleClicked::Y))
-            )
-            .width(y_labels_width)
-            .height(Length::FillPortion(120))
-        ]
-        .into();
+        let mut canvas = stack![];
+        if let Some(layer) = chart.gpu_layer() {
+            canvas = canvas.push(layer);
+        }
+        canvas = canvas.push(Canvas::new(chart).width(Length::Fill).height(Length::Fill));
+
+        if let Some(readout) = state.measurement_readout(timezone) {
+            canvas = canvas.push(
+                container(
+                    container(text(readout).size(11).font(style::AZERET_MONO))
+                        .padding(4)
+                        .style(style::tooltip),
+                )
+                .padding(8),
+            );
+        }
+
+        if let Some(index) = state.editing_price_line
+            && let Some(line) = state.price_lines.get(index)
+        {
+            let y = state.price_to_canvas_y(line.price, state.bounds.size());
+
+            canvas = canvas.push(
+                container(
+                    text_input("Label", &line.label)
+                        .on_input(move |label| Message::PriceLineLabelChanged(index, label))
+                        .on_submit(Message::EditPriceLineLabel(None))
+                        .size(11)
+                        .width(160),
+                )
+                .padding(padding::left(4).top((y - 24.0).max(0.0))),
+            );
+        }
+
+        if let Some((position, key, price)) = state.context_menu {
+            let menu_button = |label, message| {
+                button(text(label).size(11))
+                    .width(Length::Fill)
+                    .on_press(message)
+                    .style(|theme: &Theme, status| {
+                        style::button::transparent(theme, status, false)
+                    })
+            };
+
+            let mut items = column![menu_button(
+                format!("Copy price {}", state.format_price(price)),
+                Message::CopyPrice(price),
+            )]
+            .width(Length::Shrink);
+
+            if chart.kline_at(key).is_some() {
+                items = items.push(menu_button("Copy OHLC".to_string(), Message::CopyOhlc(key)));
+            }
+            items = items.push(menu_button(
+                "Reset cell width".to_string(),
+                Message::ResetCellWidth,
+            ));
+            if chart.supports_anchored_vwap() {
+                items = items.push(menu_button(
+                    "Anchor VWAP here".to_string(),
+                    Message::AnchorVwap(key),
+                ));
+            }
+
+            let menu = container(items).padding(2).style(style::chart_modal);
+
+            canvas =
+                canvas.push(container(menu).padding(padding::left(position.x).top(position.y)));
+        }
+
+        let main_chart: Element<_> = if chart.is_compact() {
+            container(canvas)
+                .width(Length::Fill)
+                .height(Length::FillPortion(120))
+                .into()
+        } else {
+            row![
+                container(canvas)
+                    .width(Length::FillPortion(10))
+                    .height(Length::FillPortion(120)),
+                rule::vertical(1).style(style::split_ruler),
+                container(
+                    mouse_area(axis_labels_y)
+                        .on_double_click(Message::DoubleClick(AxisScaleClicked::Y))
+                )
+                .width(y_labels_width)
+                .height(Length::FillPortion(120))
+            ]
+            .into()
+        };
 
         let indicators = chart.view_indicators(indicators);
 
@@ -768,22 +1478,35 @@ pub fn view<'a, T: Chart>(
         }
     };
 
-    column![
-        content,
-        rule::horizontal(1).style(style::split_ruler),
-        row![
-            container(
-                mouse_area(axis_labels_x)
-                    .on_double_click(Message::DoubleClick(AxisScaleClicked::X))
-            )
-            .padding(padding::right(1))
-            .width(Length::FillPortion(10))
-            .height(Length::Fixed(26.0)),
-            buttons.width(y_labels_width).height(Length::Fixed(26.0))
-        ]
-    ]
-    .padding(padding::left(1).right(1).bottom(1))
-    .into()
+    let mut layout = column![content];
+
+    if !chart.is_compact() {
+        layout = layout
+            .push(rule::horizontal(1).style(style::split_ruler))
+            .push(row![
+                container(
+                    mouse_area(axis_labels_x)
+                        .on_double_click(Message::DoubleClick(AxisScaleClicked::X))
+                )
+                .padding(padding::right(1))
+                .width(Length::FillPortion(10))
+                .height(Length::Fixed(26.0)),
+                buttons.width(y_labels_width).height(Length::Fixed(26.0))
+            ]);
+    }
+
+    if let Some(minimap) = chart.minimap() {
+        layout = layout
+            .push(rule::horizontal(1).style(style::split_ruler))
+            .push(row![
+                container(minimap)
+                    .padding(padding::right(1))
+                    .width(Length::FillPortion(10)),
+                iced::widget::space::horizontal().width(y_labels_width),
+            ]);
+    }
+
+    layout.padding(padding::left(1).right(1).bottom(1)).into()
 }
 
 pub trait PlotConstants {
@@ -833,6 +1556,7 @@ impl Caches {
 /// - 所有图表类型共享相同的视图状态结构
 /// - 通过 ViewState 统一处理缩放、平移等交互
 /// - 使用缓存优化渲染性能
+///
 /// ============================================================================
 pub struct ViewState {
     /// 渲染缓存
@@ -944,6 +1668,31 @@ pub struct ViewState {
     /// - autoscale: 自动缩放模式
     /// - splits: 指标分屏比例
     layout: ViewConfig,
+
+    /// 链接面板的十字线时间戳（毫秒）
+    ///
+    /// 本面板无光标时，在该时间处绘制一条同步的竖线
+    synced_crosshair: Option<u64>,
+
+    /// 测量尺的测量结果
+    ///
+    /// 每个面板各自保存，按 Esc 清除
+    measurement: Option<Measurement>,
+
+    /// 本交易对的水平价格线（由 dashboard 同步）
+    price_lines: Vec<PriceLine>,
+
+    /// 正在编辑标签的价格线索引
+    editing_price_line: Option<usize>,
+
+    /// 视口当前是否跟随最新数据
+    ///
+    /// 由 `layout.auto_scroll` 开启；用户平移离开最新K线时自动暂停，
+    /// 平移回来后恢复
+    following: bool,
+
+    /// 打开中的右键菜单：画布上的位置、对应K线的键和光标处价格
+    context_menu: Option<(Point, u64, Price)>,
 }
 
 impl ViewState {
@@ -956,6 +1705,8 @@ impl ViewState {
         cell_width: f32,
         cell_height: f32,
     ) -> Self {
+        let following = layout.auto_scroll != AutoScrollMode::None;
+
         ViewState {
             cache: Caches::default(),
             bounds: Rectangle::default(),
@@ -971,6 +1722,12 @@ impl ViewState {
             decimals,
             ticker_info,
             layout,
+            synced_crosshair: None,
+            measurement: None,
+            price_lines: Vec::new(),
+            editing_price_line: None,
+            following,
+            context_menu: None,
         }
     }
 
@@ -991,6 +1748,33 @@ impl ViewState {
         }
     }
 
+    /// 最新的数据点总在 x = 0 处：时间基准以 latest_x 为原点，Tick 基准的索引 0 即最新
+    fn is_latest_visible(&self) -> bool {
+        self.is_interval_x_visible(0.0)
+    }
+
+    /// 用户平移或缩放后重新判断是否跟随
+    fn update_following(&mut self) {
+        self.following =
+            self.layout.auto_scroll != AutoScrollMode::None && self.is_latest_visible();
+    }
+
+    /// 新数据让原点右移了 `shift_x`，不跟随时反向平移视口，画面保持不动；
+    /// 跟随且要求留白时，把视口移到最新K线右侧留出 `bars` 根K线的位置
+    fn hold_viewport(&mut self, shift_x: f32) {
+        if self.layout.autoscale == Some(Autoscale::CenterLatest) {
+            return;
+        }
+
+        if !self.following {
+            self.translation.x += shift_x;
+        } else if let AutoScrollMode::KeepMargin(bars) = self.layout.auto_scroll {
+            // 最新K线居中于 x = 0，视口右边缘 = 半根K线 + 留白
+            let margin = self.cell_width * (bars as f32 + 0.5);
+            self.translation.x = self.bounds.width / self.scaling / 2.0 - margin;
+        }
+    }
+
     fn is_interval_x_visible(&self, interval_x: f32) -> bool {
         let region = self.visible_region(self.bounds.size());
 
@@ -1097,67 +1881,23 @@ impl ViewState {
         let tick_size = self.tick_size.to_f32_lossy();
 
         if let Interaction::Ruler { start: Some(start) } = interaction {
-            let p1 = *start;
             let p2 = cursor_position;
 
-            let snap_y = |y: f32| {
-                let ratio = y / bounds.height;
-                let price = highest + ratio * (lowest - highest);
+            let measurement = self.measure(*start, p2, bounds);
 
-                let rounded_price_p = if self.tick_size.units == 0 {
-                    Price::from_f32_lossy((price / tick_size).round() * tick_size)
-                } else {
-                    let p = Price::from_f32_lossy(price);
-                    let tick_units = self.tick_size.units;
-                    let tick_index = p.units.div_euclid(tick_units);
-                    Price::from_units(tick_index * tick_units)
-                };
-                let rounded_price = rounded_price_p.to_f32_lossy();
-                let snap_ratio = (rounded_price - highest) / (lowest - highest);
-                snap_ratio * bounds.height
-            };
+            let pct_text = format!("{:.2}%", measurement.price_change_pct());
 
-            let snap_x = |x: f32| {
-                let (_, snap_ratio) = self.snap_x_to_index(x, bounds, region);
-                snap_ratio * bounds.width
+            let interval_diff: String = match measurement.time_delta_ms() {
+                Some(diff_ms) => data::util::format_duration_ms(diff_ms),
+                None => format!("{} ticks", measurement.start.0.abs_diff(measurement.end.0)),
             };
 
-            let snapped_p1_x = snap_x(p1.x);
-            let snapped_p1_y = snap_y(p1.y);
-            let snapped_p2_x = snap_x(p2.x);
-            let snapped_p2_y = snap_y(p2.y);
-
-            let price1 = self.y_to_price(snapped_p1_y);
-            let price2 = self.y_to_price(snapped_p2_y);
-
-            let pct = if price1.to_f32_lossy() == 0.0 {
-                0.0
-            } else {
-                ((price2.to_f32_lossy() - price1.to_f32_lossy()) / price1.to_f32_lossy()) * 100.0
-            };
-            let pct_text = format!("{:.2}%", pct);
-
-            let interval_diff: String = match self.basis {
-                Basis::Time(_) => {
-                    let (timestamp1, _) = self.snap_x_to_index(p1.x, bounds, region);
-                    let (timestamp2, _) = self.snap_x_to_index(p2.x, bounds, region);
-
-                    let diff_ms: u64 = timestamp1.abs_diff(timestamp2);
-                    data::util::format_duration_ms(diff_ms)
-                }
-                Basis::Tick(_) => {
-                    let (tick1, _) = self.snap_x_to_index(p1.x, bounds, region);
-                    let (tick2, _) = self.snap_x_to_index(p2.x, bounds, region);
-
-                    let tick_diff = tick1.abs_diff(tick2);
-                    format!("{} ticks", tick_diff)
-                }
-            };
-
-            let rect_x = snapped_p1_x.min(snapped_p2_x);
-            let rect_y = snapped_p1_y.min(snapped_p2_y);
-            let rect_w = (snapped_p1_x - snapped_p2_x).abs();
-            let rect_h = (snapped_p1_y - snapped_p2_y).abs();
+            let Rectangle {
+                x: rect_x,
+                y: rect_y,
+                width: rect_w,
+                height: rect_h,
+            } = self.measurement_rect(&measurement, bounds);
 
             let palette = theme.extended_palette();
 
@@ -1193,25 +1933,7 @@ impl ViewState {
                 _ => text_corner,
             };
 
-            let datapoints_text = match self.basis {
-                Basis::Time(timeframe) => {
-                    let interval_ms = timeframe.to_milliseconds();
-                    let (timestamp1, _) = self.snap_x_to_index(p1.x, bounds, region);
-                    let (timestamp2, _) = self.snap_x_to_index(p2.x, bounds, region);
-
-                    let diff_ms = timestamp1.abs_diff(timestamp2);
-                    let datapoints = (diff_ms / interval_ms).max(1);
-                    format!("{} bars", datapoints)
-                }
-                Basis::Tick(aggregation) => {
-                    let (tick1, _) = self.snap_x_to_index(p1.x, bounds, region);
-                    let (tick2, _) = self.snap_x_to_index(p2.x, bounds, region);
-
-                    let tick_diff = tick1.abs_diff(tick2);
-                    let datapoints = (tick_diff / u64::from(aggregation.0)).max(1);
-                    format!("{} bars", datapoints)
-                }
-            };
+            let datapoints_text = format!("{} bars", measurement.bars());
 
             let label_text = format!("{}, {} | {}", datapoints_text, interval_diff, pct_text);
 
@@ -1321,6 +2043,254 @@ impl ViewState {
         }
     }
 
+    /// 将画布上的两个点转换为测量结果
+    ///
+    /// 横坐标对齐到周期（或 Tick 聚合），价格对齐到价格步长
+    fn measure(&self, p1: Point, p2: Point, bounds: Size) -> Measurement {
+        let region = self.visible_region(bounds);
+
+        let snap = |point: Point| {
+            let (x, _) = self.snap_x_to_index(point.x, bounds, region);
+            (x, self.price_at(point.y, bounds))
+        };
+
+        Measurement::new(self.basis, snap(p1), snap(p2))
+    }
+
+    /// 画布纵坐标对应的价格（对齐到价格步长）
+    fn price_at(&self, y: f32, bounds: Size) -> Price {
+        let region = self.visible_region(bounds);
+        let chart_y = region.y + (y / bounds.height) * region.height;
+
+        self.y_to_price(chart_y).round_to_step(self.tick_size)
+    }
+
+    /// 按价格步长的小数位数格式化价格，例如步长 0.5 显示一位小数
+    fn format_price(&self, price: Price) -> String {
+        let precision = MinTicksize::new(-(self.tick_size.decimals() as i8));
+        price.round_to_step(self.tick_size).to_string(precision)
+    }
+
+    /// 价格在画布上的纵坐标
+    fn price_to_canvas_y(&self, price: Price, bounds: Size) -> f32 {
+        let region = self.visible_region(bounds);
+        (self.price_to_y(price) - region.y) / region.height * bounds.height
+    }
+
+    /// 光标附近的价格线索引
+    fn price_line_at(&self, y: f32, bounds: Size) -> Option<usize> {
+        self.price_lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| {
+                (
+                    index,
+                    (self.price_to_canvas_y(line.price, bounds) - y).abs(),
+                )
+            })
+            .filter(|(_, distance)| *distance <= PRICE_LINE_HIT_DISTANCE)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(index, _)| index)
+    }
+
+    /// 绘制横跨整个画布的价格线及其标签，拖拽中的价格线跟随光标
+    fn draw_price_lines(
+        &self,
+        frame: &mut Frame,
+        theme: &Theme,
+        bounds: Size,
+        interaction: &Interaction,
+        cursor_position: Option<Point>,
+    ) {
+        let palette = theme.extended_palette();
+
+        for (index, line) in self.price_lines.iter().enumerate() {
+            // 提醒线使用警示色，已触发的提醒线变淡
+            let color = match line.alert {
+                Some(alert) if alert.triggered => palette.warning.weak.color,
+                Some(_) => palette.warning.base.color,
+                None => palette.secondary.strong.color,
+            };
+
+            let price = match (interaction, cursor_position) {
+                (Interaction::MovingPriceLine { index: moving, .. }, Some(cursor))
+                    if *moving == index =>
+                {
+                    self.price_at(cursor.y, bounds)
+                }
+                _ => line.price,
+            };
+
+            let y = self.price_to_canvas_y(price, bounds);
+            if !(0.0..=bounds.height).contains(&y) {
+                continue;
+            }
+
+            frame.stroke(
+                &Path::line(Point::new(0.0, y), Point::new(bounds.width, y)),
+                Stroke::with_color(
+                    Stroke {
+                        width: 1.0,
+                        ..Default::default()
+                    },
+                    color,
+                ),
+            );
+
+            let price_text = price.to_string(self.ticker_info.min_ticksize);
+            let content = if line.label.is_empty() {
+                price_text
+            } else {
+                format!("{} {}", line.label, price_text)
+            };
+
+            let text_width = (content.chars().count() as f32) * TEXT_SIZE * 0.6;
+            let text_height = TEXT_SIZE * 1.2;
+
+            frame.fill_rectangle(
+                Point::new(4.0, y - text_height - 2.0),
+                Size::new(text_width + 8.0, text_height),
+                palette.background.weakest.color.scale_alpha(0.9),
+            );
+            frame.fill_text(canvas::Text {
+                content,
+                position: Point::new(8.0, y - 2.0 - text_height / 2.0),
+                color,
+                size: iced::Pixels(11.0),
+                align_y: Alignment::Center.into(),
+                font: style::AZERET_MONO,
+                ..Default::default()
+            });
+        }
+    }
+
+    /// 测量结果在画布上对应的矩形（画布坐标）
+    fn measurement_rect(&self, measurement: &Measurement, bounds: Size) -> Rectangle {
+        let region = self.visible_region(bounds);
+
+        let to_canvas = |(x, price): (u64, Price)| {
+            // Tick 基准下测量值以 Tick 数量计，需换算回单元格索引
+            let index = match self.basis {
+                Basis::Time(_) => x,
+                Basis::Tick(aggregation) => x / u64::from(aggregation.0).max(1),
+            };
+
+            Point::new(
+                (self.interval_to_x(index) - region.x) / region.width * bounds.width,
+                (self.price_to_y(price) - region.y) / region.height * bounds.height,
+            )
+        };
+
+        let (a, b) = (to_canvas(measurement.start), to_canvas(measurement.end));
+
+        Rectangle {
+            x: a.x.min(b.x),
+            y: a.y.min(b.y),
+            width: (a.x - b.x).abs(),
+            height: (a.y - b.y).abs(),
+        }
+    }
+
+    /// 绘制已保存的测量框，读数标签由 `view` 叠加显示
+    fn draw_measurement(&self, frame: &mut Frame, theme: &Theme, bounds: Size) {
+        let Some(measurement) = &self.measurement else {
+            return;
+        };
+
+        let rect = self.measurement_rect(measurement, bounds);
+        let color = theme.extended_palette().primary.base.color;
+
+        frame.fill_rectangle(rect.position(), rect.size(), color.scale_alpha(0.08));
+        frame.stroke(
+            &Path::rectangle(rect.position(), rect.size()),
+            Stroke::with_color(
+                Stroke {
+                    width: 1.0,
+                    ..Default::default()
+                },
+                color.scale_alpha(0.4),
+            ),
+        );
+    }
+
+    /// 测量结果的读数文本：K线数量、时间差、价格差及百分比，以及起止时间
+    fn measurement_readout(&self, timezone: data::UserTimezone) -> Option<String> {
+        let measurement = self.measurement?;
+
+        let (is_negative, delta) = measurement.price_delta();
+        let mut readout = format!(
+            "{} bars | {}{} ({:+.2}%)",
+            measurement.bars(),
+            if is_negative { "-" } else { "+" },
+            delta.to_string(self.ticker_info.min_ticksize),
+            measurement.price_change_pct(),
+        );
+
+        if let (Some(diff_ms), Basis::Time(timeframe)) =
+            (measurement.time_delta_ms(), measurement.basis)
+        {
+            let interval = timeframe.to_milliseconds();
+            let (from, to) = if measurement.start.0 <= measurement.end.0 {
+                (measurement.start.0, measurement.end.0)
+            } else {
+                (measurement.end.0, measurement.start.0)
+            };
+
+            readout.push_str(&format!(
+                "\n{} | {} → {}",
+                data::util::format_duration_ms(diff_ms),
+                timezone.format_crosshair_timestamp(from as i64, interval),
+                timezone.format_crosshair_timestamp(to as i64, interval),
+            ));
+        }
+
+        Some(readout)
+    }
+
+    /// 光标所在位置对应的时间戳，仅适用于时间基准的图表
+    fn cursor_time(&self, cursor_position: Point, bounds: Size) -> Option<u64> {
+        match self.basis {
+            Basis::Time(_) => {
+                let region = self.visible_region(bounds);
+                let (timestamp, _) = self.snap_x_to_index(cursor_position.x, bounds, region);
+                Some(timestamp)
+            }
+            Basis::Tick(_) => None,
+        }
+    }
+
+    /// 在链接面板的光标时间处绘制竖线（对齐到本图表自己的周期）
+    fn draw_synced_crosshair(&self, frame: &mut Frame, theme: &Theme, bounds: Size) {
+        let (Some(timestamp), Basis::Time(timeframe)) = (self.synced_crosshair, self.basis) else {
+            return;
+        };
+
+        let interval = timeframe.to_milliseconds();
+        if interval == 0 {
+            return;
+        }
+
+        let region = self.visible_region(bounds);
+        if region.width <= 0.0 {
+            return;
+        }
+
+        let snapped = (timestamp / interval) * interval;
+        let ratio = (self.interval_to_x(snapped) - region.x) / region.width;
+
+        if !(0.0..=1.0).contains(&ratio) {
+            return;
+        }
+
+        frame.stroke(
+            &Path::line(
+                Point::new(ratio * bounds.width, 0.0),
+                Point::new(ratio * bounds.width, bounds.height),
+            ),
+            style::dashed_line(theme),
+        );
+    }
+
     fn draw_last_price_line(
         &self,
         frame: &mut canvas::Frame,
@@ -1358,6 +2328,10 @@ impl ViewState {
         ViewConfig {
             splits: layout.splits.clone(),
             autoscale: layout.autoscale,
+            auto_scroll: layout.auto_scroll,
+            fixed_cells: layout.fixed_cells,
+            price_axis: layout.price_axis,
+            price_rounding: layout.price_rounding,
         }
     }
 
@@ -1413,12 +2387,21 @@ impl ViewState {
 }
 
 fn request_fetch(handler: &mut RequestHandler, range: FetchRange) -> Option<Action> {
+    request_fetch_for_stream(handler, range, None)
+}
+
+/// Same as `request_fetch`, but targets a specific stream instead of the pane's own
+fn request_fetch_for_stream(
+    handler: &mut RequestHandler,
+    range: FetchRange,
+    stream: Option<StreamKind>,
+) -> Option<Action> {
     match handler.add_request(range) {
         Ok(Some(req_id)) => {
             let fetch_spec = FetchSpec {
                 req_id,
                 fetch: range,
-                stream: None,
+                stream,
             };
             let fetch = FetchRequests::from([fetch_spec]);
             Some(Action::RequestFetch(fetch))
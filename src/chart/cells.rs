@@ -0,0 +1,365 @@
+//! Footprint cell bars drawn by a wgpu shader instead of being tessellated on the canvas.
+//!
+//! The canvas still lays the cells out and draws their text, it only hands the bars over
+//! whenever its cache is redrawn. The shader reads them when the frame is prepared, which
+//! comes after every widget drew, so bars and text always belong to the same frame.
+
+use iced::widget::canvas;
+use iced::widget::shader::{self, Viewport};
+use iced::{Color, Point, Rectangle, Renderer, Size, Vector, mouse, wgpu};
+use rustc_hash::FxHashMap;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// Whether windows are drawn by wgpu. Cleared once a chart is drawn by the tiny-skia
+/// fallback, picked with `ICED_BACKEND=tiny-skia` or when wgpu fails to start, which can't
+/// run shaders.
+static WGPU_ACTIVE: AtomicBool = AtomicBool::new(true);
+
+/// Records the backend of `renderer` and tells whether it can draw [`FootprintCells`]
+pub fn detect_backend(renderer: &Renderer) -> bool {
+    let wgpu = matches!(renderer, Renderer::Primary(_));
+    WGPU_ACTIVE.store(wgpu, Ordering::Relaxed);
+    wgpu
+}
+
+/// Backend of the last chart drawn, optimistic until one is
+pub fn wgpu_active() -> bool {
+    WGPU_ACTIVE.load(Ordering::Relaxed)
+}
+
+/// Bar of a footprint cell, `width` long at `max_qty` and growing leftwards when negative.
+/// The filled share, `(buy_qty + sell_qty) / max_qty`, is worked out per fragment on the GPU.
+#[derive(Debug, Clone, Copy)]
+pub struct CellQuad {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub buy_qty: f32,
+    pub sell_qty: f32,
+    pub max_qty: f32,
+    pub color: Color,
+}
+
+impl CellQuad {
+    pub fn filled_width(&self) -> f32 {
+        if self.max_qty <= 0.0 {
+            return 0.0;
+        }
+        self.width * ((self.buy_qty + self.sell_qty) / self.max_qty)
+    }
+
+    /// `[x, y, width, height, buy_qty, sell_qty, max_qty, r, g, b, a]`, as the vertex shader
+    /// reads it
+    fn vertex(&self) -> [f32; 11] {
+        let [r, g, b, a] = self.color.into_linear();
+        [
+            self.x,
+            self.y,
+            self.width,
+            self.height,
+            self.buy_qty,
+            self.sell_qty,
+            self.max_qty,
+            r,
+            g,
+            b,
+            a,
+        ]
+    }
+}
+
+/// Where the bars of footprint cells go
+pub enum CellBars<'a> {
+    /// Filled on the canvas frame, as everything else
+    Canvas,
+    /// Set aside for the shader drawn under the canvas
+    Gpu(&'a mut Vec<CellQuad>),
+}
+
+impl CellBars<'_> {
+    pub fn fill(&mut self, frame: &mut canvas::Frame, quad: CellQuad) {
+        match self {
+            CellBars::Canvas => {
+                let width = quad.filled_width();
+                if width != 0.0 {
+                    frame.fill_rectangle(
+                        Point::new(quad.x, quad.y),
+                        Size::new(width, quad.height),
+                        quad.color,
+                    );
+                }
+            }
+            CellBars::Gpu(quads) => quads.push(quad),
+        }
+    }
+}
+
+/// Bars of the last canvas redraw, with the transform the canvas frame applied to them
+#[derive(Debug, Default)]
+pub struct CellLayer {
+    pub quads: Vec<CellQuad>,
+    pub center: Vector,
+    pub scaling: f32,
+    pub translation: Vector,
+}
+
+/// Filled by the canvas whenever its cache is redrawn and read by the shader every frame
+pub type SharedCells = Arc<RwLock<CellLayer>>;
+
+/// Shader widget drawing the bars of a [`SharedCells`], stacked under the chart canvas
+pub struct FootprintCells {
+    pub layer: SharedCells,
+}
+
+impl<Message> shader::Program<Message> for FootprintCells {
+    type State = ();
+    type Primitive = CellsPrimitive;
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        _cursor: mouse::Cursor,
+        _bounds: Rectangle,
+    ) -> Self::Primitive {
+        CellsPrimitive {
+            layer: Arc::clone(&self.layer),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CellsPrimitive {
+    layer: SharedCells,
+}
+
+impl CellsPrimitive {
+    /// Every chart has a buffer of its own in the pipeline, shared by all primitives
+    fn key(&self) -> usize {
+        Arc::as_ptr(&self.layer) as usize
+    }
+}
+
+impl shader::Primitive for CellsPrimitive {
+    type Pipeline = CellsPipeline;
+
+    fn prepare(
+        &self,
+        pipeline: &mut Self::Pipeline,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        bounds: &Rectangle,
+        _viewport: &Viewport,
+    ) {
+        let Ok(layer) = self.layer.read() else {
+            return;
+        };
+
+        let uniforms = [
+            bounds.width,
+            bounds.height,
+            layer.center.x,
+            layer.center.y,
+            layer.translation.x,
+            layer.translation.y,
+            layer.scaling,
+            0.0,
+        ];
+        let instances = layer
+            .quads
+            .iter()
+            .flat_map(CellQuad::vertex)
+            .flat_map(f32::to_ne_bytes)
+            .collect::<Vec<u8>>();
+
+        pipeline.prepare(
+            self.key(),
+            device,
+            queue,
+            &uniforms,
+            &instances,
+            layer.quads.len(),
+        );
+    }
+
+    fn draw(&self, pipeline: &Self::Pipeline, render_pass: &mut wgpu::RenderPass<'_>) -> bool {
+        pipeline.draw(self.key(), render_pass);
+        true
+    }
+}
+
+const UNIFORMS_SIZE: u64 = 8 * 4;
+const INSTANCE_SIZE: u64 = 11 * 4;
+
+pub struct CellsPipeline {
+    pipeline: wgpu::RenderPipeline,
+    uniforms_layout: wgpu::BindGroupLayout,
+    charts: FxHashMap<usize, ChartBuffers>,
+}
+
+struct ChartBuffers {
+    uniforms: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    instances: wgpu::Buffer,
+    count: u32,
+    /// Whether the chart was prepared since the last trim, the others are gone
+    prepared: bool,
+}
+
+impl CellsPipeline {
+    fn prepare(
+        &mut self,
+        key: usize,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        uniforms: &[f32; 8],
+        instances: &[u8],
+        count: usize,
+    ) {
+        let buffers = self.charts.entry(key).or_insert_with(|| {
+            let uniforms = device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("footprint cells uniforms"),
+                size: UNIFORMS_SIZE,
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("footprint cells bind group"),
+                layout: &self.uniforms_layout,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniforms.as_entire_binding(),
+                }],
+            });
+
+            ChartBuffers {
+                uniforms,
+                bind_group,
+                instances: instance_buffer(device, INSTANCE_SIZE),
+                count: 0,
+                prepared: false,
+            }
+        });
+
+        let needed = instances.len() as u64;
+        if needed > buffers.instances.size() {
+            buffers.instances = instance_buffer(device, needed.next_power_of_two());
+        }
+
+        let uniforms = uniforms
+            .iter()
+            .flat_map(|v| v.to_ne_bytes())
+            .collect::<Vec<u8>>();
+        queue.write_buffer(&buffers.uniforms, 0, &uniforms);
+        if !instances.is_empty() {
+            queue.write_buffer(&buffers.instances, 0, instances);
+        }
+
+        buffers.count = count as u32;
+        buffers.prepared = true;
+    }
+
+    fn draw(&self, key: usize, render_pass: &mut wgpu::RenderPass<'_>) {
+        let Some(buffers) = self.charts.get(&key).filter(|b| b.count > 0) else {
+            return;
+        };
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &buffers.bind_group, &[]);
+        render_pass.set_vertex_buffer(0, buffers.instances.slice(..));
+        render_pass.draw(0..6, 0..buffers.count);
+    }
+}
+
+fn instance_buffer(device: &wgpu::Device, size: u64) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("footprint cells instances"),
+        size,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
+impl shader::Pipeline for CellsPipeline {
+    fn new(device: &wgpu::Device, _queue: &wgpu::Queue, format: wgpu::TextureFormat) -> Self {
+        let uniforms_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("footprint cells uniforms layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: wgpu::BufferSize::new(UNIFORMS_SIZE),
+                },
+                count: None,
+            }],
+        });
+
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("footprint cells pipeline layout"),
+            bind_group_layouts: &[&uniforms_layout],
+            push_constant_ranges: &[],
+        });
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("footprint cells shader"),
+            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(include_str!(
+                "cells.wgsl"
+            ))),
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("footprint cells pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: INSTANCE_SIZE,
+                    step_mode: wgpu::VertexStepMode::Instance,
+                    attributes: &wgpu::vertex_attr_array!(
+                        // x, y, width, height
+                        0 => Float32x4,
+                        // buy, sell and max quantity
+                        1 => Float32x3,
+                        // linear color
+                        2 => Float32x4,
+                    ),
+                }],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            pipeline,
+            uniforms_layout,
+            charts: FxHashMap::default(),
+        }
+    }
+
+    fn trim(&mut self) {
+        self.charts
+            .retain(|_, buffers| std::mem::take(&mut buffers.prepared));
+    }
+}
@@ -0,0 +1,62 @@
+struct Uniforms {
+    // widget size, logical pixels
+    size: vec2<f32>,
+    // the canvas frame's transform: center + (point + translation) * scaling
+    center: vec2<f32>,
+    translation: vec2<f32>,
+    scaling: f32,
+    _padding: f32,
+}
+
+@group(0) @binding(0) var<uniform> uniforms: Uniforms;
+
+struct Cell {
+    // x, y, width, height in chart coordinates, a negative width grows leftwards
+    @location(0) rect: vec4<f32>,
+    // buy, sell and max quantity
+    @location(1) qty: vec3<f32>,
+    @location(2) color: vec4<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    // 0 at the bar's origin, 1 at full length
+    @location(1) along: f32,
+    @location(2) filled: f32,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32, cell: Cell) -> VertexOutput {
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(0.0, 0.0),
+        vec2<f32>(1.0, 0.0),
+        vec2<f32>(0.0, 1.0),
+        vec2<f32>(0.0, 1.0),
+        vec2<f32>(1.0, 0.0),
+        vec2<f32>(1.0, 1.0),
+    );
+    let corner = corners[index];
+
+    let point = cell.rect.xy + corner * cell.rect.zw;
+    let screen = uniforms.center + (point + uniforms.translation) * uniforms.scaling;
+    let ndc = vec2<f32>(
+        screen.x / uniforms.size.x * 2.0 - 1.0,
+        1.0 - screen.y / uniforms.size.y * 2.0,
+    );
+
+    var out: VertexOutput;
+    out.position = vec4<f32>(ndc, 0.0, 1.0);
+    out.color = vec4<f32>(cell.color.rgb * cell.color.a, cell.color.a);
+    out.along = corner.x;
+    out.filled = select(0.0, (cell.qty.x + cell.qty.y) / cell.qty.z, cell.qty.z > 0.0);
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    if in.along > in.filled {
+        discard;
+    }
+    return in.color;
+}
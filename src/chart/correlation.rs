@@ -0,0 +1,312 @@
+use crate::style;
+
+use data::chart::correlation::{Config, correlation_matrix};
+use exchange::adapter::StreamKind;
+use exchange::fetcher::{FetchRange, FetchSpec, RequestHandler};
+use exchange::{Kline, TickerInfo, Timeframe};
+
+use iced::widget::canvas::{self, Cache, Canvas, Geometry};
+use iced::{Color, Element, Length, Point, Rectangle, Renderer, Size, Theme, mouse};
+use rustc_hash::FxHashMap;
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+const LABEL_WIDTH: f32 = 96.0;
+const HEADER_HEIGHT: f32 = 24.0;
+const TEXT_SIZE: f32 = 11.0;
+
+const NEGATIVE: Color = Color::from_rgb(0.72, 0.11, 0.11);
+const POSITIVE: Color = Color::from_rgb(0.11, 0.55, 0.24);
+
+/// Grid of Pearson correlations between the closes of every pair of selected tickers
+pub struct CorrelationMatrix {
+    pub timeframe: Timeframe,
+    pub config: Config,
+    selected_tickers: Vec<TickerInfo>,
+    closes: FxHashMap<TickerInfo, BTreeMap<u64, f32>>,
+    request_handler: FxHashMap<TickerInfo, RequestHandler>,
+    matrix: Vec<Vec<Option<f32>>>,
+    cache: Cache,
+    last_tick: Instant,
+}
+
+impl CorrelationMatrix {
+    pub fn new(timeframe: Timeframe, tickers: &[TickerInfo], config: Option<Config>) -> Self {
+        let mut matrix = Self {
+            timeframe,
+            config: config.unwrap_or_default(),
+            selected_tickers: tickers.to_vec(),
+            closes: FxHashMap::default(),
+            request_handler: FxHashMap::default(),
+            matrix: vec![],
+            cache: Cache::default(),
+            last_tick: Instant::now(),
+        };
+        matrix.rebuild_handlers();
+        matrix.recompute();
+        matrix
+    }
+
+    pub fn view<M: 'static>(&self) -> Element<'_, M> {
+        if self.closes.values().all(BTreeMap::is_empty) {
+            return iced::widget::center(iced::widget::text("Waiting for data...").size(16)).into();
+        }
+
+        Canvas::new(self)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    pub fn insert_history(
+        &mut self,
+        req_id: uuid::Uuid,
+        ticker_info: TickerInfo,
+        klines: &[Kline],
+    ) {
+        if klines.is_empty() {
+            if let Some(handler) = self.request_handler.get_mut(&ticker_info) {
+                handler.mark_failed(req_id, "No data received".to_string());
+            }
+            return;
+        }
+
+        let dt = self.timeframe.to_milliseconds().max(1);
+        let closes = self.closes.entry(ticker_info).or_default();
+        for kline in klines {
+            closes.insert((kline.time / dt) * dt, kline.close.to_f32());
+        }
+        Self::trim(closes);
+
+        if let Some(handler) = self.request_handler.get_mut(&ticker_info) {
+            handler.mark_completed(req_id);
+        }
+        self.recompute();
+    }
+
+    pub fn update_latest_kline(&mut self, ticker_info: &TickerInfo, kline: &Kline) {
+        let dt = self.timeframe.to_milliseconds().max(1);
+        let closes = self.closes.entry(*ticker_info).or_default();
+        closes.insert((kline.time / dt) * dt, kline.close.to_f32());
+        Self::trim(closes);
+
+        self.recompute();
+    }
+
+    pub fn set_window_bars(&mut self, window_bars: usize) {
+        self.config.window_bars = window_bars;
+        self.recompute();
+    }
+
+    pub fn last_update(&self) -> Instant {
+        self.last_tick
+    }
+
+    pub fn selected_tickers(&self) -> &[TickerInfo] {
+        &self.selected_tickers
+    }
+
+    pub fn add_ticker(&mut self, ticker_info: &TickerInfo) -> Vec<StreamKind> {
+        if !self.selected_tickers.contains(ticker_info) {
+            self.selected_tickers.push(*ticker_info);
+        }
+
+        self.rebuild_handlers();
+        self.recompute();
+        self.streams_for_all()
+    }
+
+    pub fn remove_ticker(&mut self, ticker_info: &TickerInfo) -> Vec<StreamKind> {
+        self.selected_tickers.retain(|t| t != ticker_info);
+        self.closes.remove(ticker_info);
+
+        self.rebuild_handlers();
+        self.recompute();
+        self.streams_for_all()
+    }
+
+    pub fn set_timeframe(&mut self, timeframe: Timeframe) -> Option<super::Action> {
+        self.timeframe = timeframe;
+        self.closes.clear();
+
+        self.rebuild_handlers();
+        self.recompute();
+        self.fetch_missing()
+    }
+
+    pub fn invalidate(&mut self, now: Option<Instant>) -> Option<super::Action> {
+        if let Some(t) = now {
+            self.last_tick = t;
+        }
+        self.fetch_missing()
+    }
+
+    /// Seeds every ticker without closes with enough history for the widest window
+    fn fetch_missing(&mut self) -> Option<super::Action> {
+        let dt = self.timeframe.to_milliseconds().max(1);
+        let end = (chrono::Utc::now().timestamp_millis() as u64 / dt) * dt;
+        let span = (*Config::WINDOW_BARS_RANGE.end() as u64).saturating_mul(dt);
+        let range = FetchRange::Kline(end.saturating_sub(span), end);
+
+        let mut specs = vec![];
+        for ticker in &self.selected_tickers {
+            if self.closes.get(ticker).is_some_and(|c| !c.is_empty()) {
+                continue;
+            }
+
+            let handler = self.request_handler.entry(*ticker).or_default();
+            if let Ok(Some(req_id)) = handler.add_request(range) {
+                specs.push(FetchSpec {
+                    req_id,
+                    fetch: range,
+                    stream: Some(StreamKind::Kline {
+                        ticker_info: *ticker,
+                        timeframe: self.timeframe,
+                    }),
+                });
+            }
+        }
+
+        if specs.is_empty() {
+            None
+        } else {
+            let requests = exchange::fetcher::FetchRequests::from(specs);
+            Some(super::Action::RequestFetch(requests))
+        }
+    }
+
+    fn recompute(&mut self) {
+        let empty = BTreeMap::new();
+        let closes: Vec<&BTreeMap<u64, f32>> = self
+            .selected_tickers
+            .iter()
+            .map(|ticker| self.closes.get(ticker).unwrap_or(&empty))
+            .collect();
+
+        self.matrix = correlation_matrix(&closes, self.config.window_bars);
+        self.cache.clear();
+    }
+
+    /// Keeps only as many closes as the widest window can use
+    fn trim(closes: &mut BTreeMap<u64, f32>) {
+        while closes.len() > *Config::WINDOW_BARS_RANGE.end() {
+            closes.pop_first();
+        }
+    }
+
+    fn rebuild_handlers(&mut self) {
+        self.request_handler.clear();
+
+        for &t in &self.selected_tickers {
+            self.request_handler.insert(t, RequestHandler::new());
+        }
+    }
+
+    fn streams_for_all(&self) -> Vec<StreamKind> {
+        self.selected_tickers
+            .iter()
+            .map(|&ticker_info| StreamKind::Kline {
+                ticker_info,
+                timeframe: self.timeframe,
+            })
+            .collect()
+    }
+}
+
+impl<M> canvas::Program<M> for CorrelationMatrix {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let palette = theme.extended_palette();
+
+        let grid = self.cache.draw(renderer, bounds.size(), |frame| {
+            let count = self.selected_tickers.len();
+            if count == 0 {
+                return;
+            }
+
+            let cell = ((bounds.width - LABEL_WIDTH) / count as f32)
+                .min((bounds.height - HEADER_HEIGHT) / count as f32)
+                .max(1.0);
+
+            let label = |ticker: &TickerInfo| ticker.ticker.display_symbol_and_type().0;
+
+            for (i, ticker) in self.selected_tickers.iter().enumerate() {
+                let offset = i as f32 * cell + cell / 2.0;
+
+                frame.fill_text(canvas::Text {
+                    content: label(ticker),
+                    position: Point::new(LABEL_WIDTH + offset, HEADER_HEIGHT / 2.0),
+                    size: iced::Pixels(TEXT_SIZE),
+                    color: palette.background.base.text,
+                    font: style::AZERET_MONO,
+                    align_x: iced::Alignment::Center.into(),
+                    align_y: iced::Alignment::Center.into(),
+                    ..canvas::Text::default()
+                });
+                frame.fill_text(canvas::Text {
+                    content: label(ticker),
+                    position: Point::new(LABEL_WIDTH - 6.0, HEADER_HEIGHT + offset),
+                    size: iced::Pixels(TEXT_SIZE),
+                    color: palette.background.base.text,
+                    font: style::AZERET_MONO,
+                    align_x: iced::Alignment::End.into(),
+                    align_y: iced::Alignment::Center.into(),
+                    ..canvas::Text::default()
+                });
+            }
+
+            for (i, row) in self.matrix.iter().enumerate() {
+                for (j, coefficient) in row.iter().enumerate() {
+                    let origin = Point::new(
+                        LABEL_WIDTH + j as f32 * cell,
+                        HEADER_HEIGHT + i as f32 * cell,
+                    );
+
+                    let fill = coefficient.map_or(palette.background.weak.color, correlation_color);
+                    frame.fill_rectangle(origin, Size::new(cell - 1.0, cell - 1.0), fill);
+
+                    if let Some(r) = coefficient {
+                        let color = if r.abs() > 0.5 {
+                            Color::WHITE
+                        } else {
+                            Color::BLACK
+                        };
+
+                        frame.fill_text(canvas::Text {
+                            content: format!("{r:.2}"),
+                            position: Point::new(origin.x + cell / 2.0, origin.y + cell / 2.0),
+                            size: iced::Pixels(TEXT_SIZE),
+                            color,
+                            font: style::AZERET_MONO,
+                            align_x: iced::Alignment::Center.into(),
+                            align_y: iced::Alignment::Center.into(),
+                            ..canvas::Text::default()
+                        });
+                    }
+                }
+            }
+        });
+
+        vec![grid]
+    }
+}
+
+/// Deep red at -1, white at 0 and deep green at +1
+fn correlation_color(r: f32) -> Color {
+    let target = if r < 0.0 { NEGATIVE } else { POSITIVE };
+    let t = r.abs().min(1.0);
+
+    Color::from_rgb(
+        1.0 + (target.r - 1.0) * t,
+        1.0 + (target.g - 1.0) * t,
+        1.0 + (target.b - 1.0) * t,
+    )
+}
@@ -35,7 +35,7 @@ use data::{
     chart::Autoscale,
 };
 use exchange::{
-    TickerInfo, Trade,
+    Kline, TickerInfo, Trade,
     depth::Depth,
     util::{Price, PriceStep},
     volume_size_unit,
@@ -110,7 +110,7 @@ impl Chart for HeatmapChart {
         let chart = self.state();
         let region = chart.visible_region(chart.bounds.size());
 
-        if region.width == 0.0 {
+        if region.width == 0.0 || matches!(chart.basis, Basis::Tick(_)) {
             return None;
         }
 
@@ -121,7 +121,20 @@ impl Chart for HeatmapChart {
     }
 
     fn interval_keys(&self) -> Option<Vec<u64>> {
-        None
+        match self.chart.basis {
+            Basis::Time(_) => None,
+            Basis::Tick(_) => Some(
+                self.trades
+                    .datapoints
+                    .values()
+                    .map(|dp| dp.start_time)
+                    .collect(),
+            ),
+        }
+    }
+
+    fn session_boundaries(&self) -> Vec<u64> {
+        vec![]
     }
 
     fn autoscaled_coords(&self) -> Vector {
@@ -136,9 +149,41 @@ impl Chart for HeatmapChart {
         false
     }
 
+    fn supports_follow_latest(&self) -> bool {
+        false
+    }
+
+    fn gpu_layer(&self) -> Option<Element<'_, Message>> {
+        None
+    }
+
+    fn minimap(&self) -> Option<Element<'_, Message>> {
+        None
+    }
+
+    fn is_compact(&self) -> bool {
+        false
+    }
+
+    fn supports_anchored_vwap(&self) -> bool {
+        false
+    }
+
+    fn kline_at(&self, _key: u64) -> Option<Kline> {
+        None
+    }
+
+    fn oldest_visible_kline(&self) -> Option<Kline> {
+        None
+    }
+
     fn is_empty(&self) -> bool {
         self.trades.datapoints.is_empty()
     }
+
+    fn datapoint_count(&self) -> usize {
+        self.trades.datapoints.len()
+    }
 }
 
 impl PlotConstants for HeatmapChart {
@@ -218,6 +263,10 @@ impl HeatmapChart {
             ViewConfig {
                 splits: layout.splits,
                 autoscale: Some(Autoscale::CenterLatest),
+                auto_scroll: layout.auto_scroll,
+                fixed_cells: layout.fixed_cells,
+                price_axis: layout.price_axis,
+                price_rounding: layout.price_rounding,
             },
             DEFAULT_CELL_WIDTH,
             4.0,
@@ -294,37 +343,44 @@ impl HeatmapChart {
     fn process_datapoint(&mut self, trades_buffer: &[Trade], depth_update: u64, depth: &Depth) {
         let chart = &mut self.chart;
 
-        let aggregate_time: u64 = match chart.basis {
-            Basis::Time(interval) => interval.into(),
-            Basis::Tick(_) => todo!(),
-        };
+        match chart.basis {
+            Basis::Time(interval) => {
+                let aggregate_time: u64 = interval.into();
+                let rounded_depth_update = (depth_update / aggregate_time) * aggregate_time;
 
-        let rounded_depth_update = (depth_update / aggregate_time) * aggregate_time;
+                {
+                    let entry = self
+                        .trades
+                        .datapoints
+                        .entry(rounded_depth_update)
+                        .or_insert_with(|| HeatmapDataPoint::new(rounded_depth_update));
 
-        {
-            let entry = self
-                .trades
-                .datapoints
-                .entry(rounded_depth_update)
-                .or_insert_with(|| HeatmapDataPoint {
-                    grouped_trades: Box::new([]),
-                    buy_sell: (0.0, 0.0),
-                });
+                    for trade in trades_buffer {
+                        entry.add_trade(trade, chart.tick_size);
+                    }
+                }
+
+                self.heatmap
+                    .insert_latest_depth(depth, rounded_depth_update);
 
-            for trade in trades_buffer {
-                entry.add_trade(trade, chart.tick_size);
+                chart.latest_x = rounded_depth_update;
+            }
+            Basis::Tick(interval) => {
+                let columns =
+                    self.trades
+                        .insert_trades_by_count(trades_buffer, interval, depth_update);
+
+                // columns filled by the same buffer share its depth snapshot
+                for column in columns {
+                    self.heatmap.insert_latest_depth(depth, column);
+                }
             }
         }
 
-        self.heatmap
-            .insert_latest_depth(depth, rounded_depth_update);
-
         {
             let mid_price = depth.mid_price().unwrap_or(chart.base_price_y);
             chart.base_price_y = mid_price.round_to_step(chart.tick_size);
         }
-
-        chart.latest_x = rounded_depth_update;
     }
 
     pub fn visual_config(&self) -> Config {
@@ -338,8 +394,9 @@ impl HeatmapChart {
 
     pub fn set_basis(&mut self, basis: Basis) {
         self.chart.basis = basis;
+        self.chart.latest_x = 0;
 
-        self.trades.datapoints.clear();
+        self.trades = TimeSeries::<HeatmapDataPoint>::new(basis, self.chart.tick_size);
         self.heatmap = HistoricalDepth::new(
             self.chart.ticker_info.min_qty.into(),
             self.chart.tick_size,
@@ -405,7 +462,7 @@ impl HeatmapChart {
         chart_state.tick_size = step;
         chart_state.decimals = count_decimals(new_tick_size);
 
-        self.trades.datapoints.clear();
+        self.trades = TimeSeries::<HeatmapDataPoint>::new(basis, step);
         self.heatmap = HistoricalDepth::new(self.chart.ticker_info.min_qty.into(), step, basis);
     }
 
@@ -517,7 +574,8 @@ impl canvas::Program<Message> for HeatmapChart {
 
             let region = chart.visible_region(frame.size());
 
-            let (earliest, latest) = chart.interval_range(&region);
+            let latest_column = self.trades.latest_timestamp().unwrap_or(0);
+            let (earliest, latest) = visible_keys(chart, latest_column, &region);
             let (highest, lowest) = chart.price_range(&region);
 
             if latest < earliest {
@@ -556,8 +614,8 @@ impl canvas::Program<Message> for HeatmapChart {
                         continue;
                     }
 
-                    let start_x = chart.interval_to_x(run_start_time_clipped);
-                    let end_x = chart.interval_to_x(run_until_time_clipped).min(0.0);
+                    let start_x = key_to_x(chart, latest_column, run_start_time_clipped);
+                    let end_x = key_to_x(chart, latest_column, run_until_time_clipped).min(0.0);
 
                     let width = end_x - start_x;
 
@@ -587,9 +645,11 @@ impl canvas::Program<Message> for HeatmapChart {
                                 order_size > self.visual_config.order_size_filter
                             })
                             .for_each(|run| {
-                                let start_x = chart.interval_to_x(run.start_time.max(earliest));
+                                let start_x =
+                                    key_to_x(chart, latest_column, run.start_time.max(earliest));
                                 let end_x =
-                                    chart.interval_to_x(run.until_time.min(latest)).min(0.0);
+                                    key_to_x(chart, latest_column, run.until_time.min(latest))
+                                        .min(0.0);
 
                                 let width = end_x - start_x;
 
@@ -648,7 +708,7 @@ impl canvas::Program<Message> for HeatmapChart {
                 .datapoints
                 .range(earliest..=latest)
                 .for_each(|(time, dp)| {
-                    let x_position = chart.interval_to_x(*time);
+                    let x_position = key_to_x(chart, latest_column, *time);
 
                     dp.grouped_trades.iter().for_each(|trade| {
                         let y_position = chart.price_to_y(trade.price);
@@ -797,6 +857,15 @@ impl canvas::Program<Message> for HeatmapChart {
 
         if !self.is_empty() {
             let crosshair = chart.cache.crosshair.draw(renderer, bounds_size, |frame| {
+                chart.draw_measurement(frame, theme, bounds_size);
+                chart.draw_price_lines(
+                    frame,
+                    theme,
+                    bounds_size,
+                    interaction,
+                    cursor.position_in(bounds),
+                );
+
                 if let Some(cursor_position) = cursor.position_in(bounds) {
                     let (cursor_at_price, cursor_at_time) = chart.draw_crosshair(
                         frame,
@@ -918,6 +987,8 @@ impl canvas::Program<Message> for HeatmapChart {
                             }
                         }
                     }
+                } else {
+                    chart.draw_synced_crosshair(frame, theme, bounds_size);
                 }
             });
 
@@ -936,7 +1007,11 @@ impl canvas::Program<Message> for HeatmapChart {
         match interaction {
             Interaction::Panning { .. } => mouse::Interaction::Grabbing,
             Interaction::Zoomin { .. } => mouse::Interaction::ZoomIn,
-            Interaction::None | Interaction::Ruler { .. } => {
+            Interaction::MovingPriceLine { .. } => mouse::Interaction::ResizingVertically,
+            Interaction::None
+            | Interaction::Ruler { .. }
+            | Interaction::Clicked(_)
+            | Interaction::PressStart(..) => {
                 if cursor.is_over(bounds) {
                     return mouse::Interaction::Crosshair;
                 }
@@ -946,6 +1021,28 @@ impl canvas::Program<Message> for HeatmapChart {
     }
 }
 
+/// Datapoint keys within the region, on tick basis the keys are column indexes
+fn visible_keys(chart: &ViewState, latest_column: u64, region: &Rectangle) -> (u64, u64) {
+    match chart.basis {
+        Basis::Time(_) => chart.interval_range(region),
+        Basis::Tick(_) => {
+            // tick basis x counts columns back from the latest one
+            let (newest_back, oldest_back) = chart.interval_range(region);
+            (
+                latest_column.saturating_sub(oldest_back),
+                latest_column.saturating_sub(newest_back) + 1,
+            )
+        }
+    }
+}
+
+fn key_to_x(chart: &ViewState, latest_column: u64, key: u64) -> f32 {
+    match chart.basis {
+        Basis::Time(_) => chart.interval_to_x(key),
+        Basis::Tick(_) => (key as f32 - latest_column as f32) * chart.cell_width,
+    }
+}
+
 fn depth_color(palette: &Extended, is_bid: bool, alpha: f32) -> Color {
     if is_bid {
         palette.success.strong.color.scale_alpha(alpha)
@@ -964,24 +1061,34 @@ fn draw_volume_profile(
     area_width: f32,
 ) {
     let (highest, lowest) = chart.price_range(region);
+    let latest_column = timeseries.latest_timestamp().unwrap_or(0);
 
-    let time_range = match kind {
-        ProfileKind::VisibleRange => {
+    let time_range = match (kind, chart.basis) {
+        (ProfileKind::VisibleRange, Basis::Time(_)) => {
             let earliest = chart.x_to_interval(region.x);
             let latest = chart.x_to_interval(region.x + region.width);
             earliest..=latest
         }
-        ProfileKind::FixedWindow(datapoints) => {
-            let basis_interval: u64 = match chart.basis {
-                Basis::Time(interval) => interval.into(),
-                Basis::Tick(_) => return,
-            };
+        (ProfileKind::VisibleRange, Basis::Tick(_)) => {
+            let (earliest, latest) = visible_keys(chart, latest_column, region);
+            earliest..=latest
+        }
+        (ProfileKind::FixedWindow(datapoints), Basis::Time(interval)) => {
+            let basis_interval: u64 = interval.into();
 
             let latest = chart
                 .latest_x
                 .min(chart.x_to_interval(region.x + region.width));
             let earliest = latest.saturating_sub((*datapoints as u64) * basis_interval);
 
+            earliest..=latest
+        }
+        (ProfileKind::FixedWindow(datapoints), Basis::Tick(_)) => {
+            let (_, visible_latest) = visible_keys(chart, latest_column, region);
+
+            let latest = latest_column.min(visible_latest);
+            let earliest = latest.saturating_sub(*datapoints as u64);
+
             earliest..=latest
         }
     };
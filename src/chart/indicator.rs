@@ -28,6 +28,28 @@ pub fn indicator_row<'a, P, Y>(
     datapoints: &'a BTreeMap<u64, Y>,
     visible_range: RangeInclusive<u64>,
 ) -> Element<'a, Message>
+where
+    P: Plot<AnySeries<'a, Y>> + 'a,
+{
+    indicator_row_with(
+        main_chart,
+        cache,
+        plot,
+        datapoints,
+        visible_range,
+        abbr_large_numbers,
+    )
+}
+
+/// Same as [`indicator_row`], with the y-axis labels written by `format`
+pub fn indicator_row_with<'a, P, Y>(
+    main_chart: &'a ViewState,
+    cache: &'a Caches,
+    plot: P,
+    datapoints: &'a BTreeMap<u64, Y>,
+    visible_range: RangeInclusive<u64>,
+    format: fn(f32) -> String,
+) -> Element<'a, Message>
 where
     P: Plot<AnySeries<'a, Y>> + 'a,
 {
@@ -55,6 +77,7 @@ where
         max,
         min,
         chart_bounds: main_chart.bounds,
+        format,
     })
     .height(Length::Fill)
     .width(main_chart.y_labels_width());
@@ -72,6 +95,7 @@ pub struct IndicatorLabel<'a> {
     pub max: f32,
     pub min: f32,
     pub chart_bounds: Rectangle,
+    pub format: fn(f32) -> String,
 }
 
 impl canvas::Program<Message> for IndicatorLabel<'_> {
@@ -103,13 +127,13 @@ impl canvas::Program<Message> for IndicatorLabel<'_> {
         let tick_size = data::util::guesstimate_ticks(range);
 
         let labels = self.label_cache.draw(renderer, bounds.size(), |frame| {
-            let mut all_labels = linear::generate_labels(
+            let mut all_labels = linear::labels_with(
                 bounds,
                 self.min,
                 self.max,
                 TEXT_SIZE,
                 palette.background.base.text,
-                None,
+                self.format,
             );
 
             let common_bounds = Rectangle {
@@ -126,7 +150,7 @@ impl canvas::Program<Message> for IndicatorLabel<'_> {
                 );
 
                 let label = LabelContent {
-                    content: abbr_large_numbers(rounded_value),
+                    content: (self.format)(rounded_value),
                     background_color: Some(palette.secondary.base.color),
                     text_color: palette.secondary.base.text,
                     text_size: TEXT_SIZE,
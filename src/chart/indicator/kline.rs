@@ -2,11 +2,13 @@ use crate::chart::{Message, ViewState};
 
 use data::chart::PlotData;
 use data::chart::indicator::KlineIndicator;
-use data::chart::kline::KlineDataPoint;
+use data::chart::kline::{KlineDataPoint, VolumeDisplayMode};
 use exchange::fetcher::FetchRange;
 use exchange::{Kline, Timeframe, Trade};
 
+pub mod depth_imbalance;
 pub mod open_interest;
+pub mod stochastic;
 pub mod volume;
 
 pub trait KlineIndicatorImpl {
@@ -47,6 +49,10 @@ pub trait KlineIndicatorImpl {
     fn on_basis_change(&mut self, _source: &PlotData<KlineDataPoint>) {}
 
     fn on_open_interest(&mut self, _pairs: &[exchange::OpenInterest]) {}
+
+    /// The chart's volume display mode was set, only the volume indicator follows it
+    fn set_volume_display(&mut self, _mode: VolumeDisplayMode, _source: &PlotData<KlineDataPoint>) {
+    }
 }
 
 pub struct FetchCtx<'a> {
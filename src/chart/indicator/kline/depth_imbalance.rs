@@ -0,0 +1,88 @@
+use crate::chart::{
+    Caches, Message, ViewState,
+    indicator::{
+        indicator_row,
+        plot::{PlotTooltip, line::LinePlot},
+    },
+};
+
+use data::chart::indicator::DepthImbalance;
+use exchange::depth::Depth;
+
+use iced::widget::{center, text};
+use std::ops::RangeInclusive;
+
+/// Sub-panel of the depth imbalance kline study, fed with the depth snapshots of the pane
+pub struct DepthImbalancePanel {
+    cache: Caches,
+    rolling: DepthImbalance,
+}
+
+impl DepthImbalancePanel {
+    pub fn new(window_size: usize, levels: usize) -> Self {
+        Self {
+            cache: Caches::default(),
+            rolling: DepthImbalance::new(window_size, levels),
+        }
+    }
+
+    pub fn is_configured(&self, window_size: usize, levels: usize) -> bool {
+        self.rolling.window_size == window_size.max(1) && self.rolling.levels == levels.max(1)
+    }
+
+    /// `bar` is the kline time on time based charts, the datapoint index on tick based ones
+    pub fn insert(&mut self, bar: u64, depth: &Depth) {
+        self.rolling.insert(bar, depth);
+        self.cache.clear_all();
+    }
+
+    /// Drops the history once its keys no longer line up with the chart's bars
+    pub fn reset(&mut self) {
+        self.rolling = DepthImbalance::new(self.rolling.window_size, self.rolling.levels);
+        self.cache.clear_all();
+    }
+
+    pub fn clear_all_caches(&mut self) {
+        self.cache.clear_all();
+    }
+
+    pub fn clear_crosshair_caches(&mut self) {
+        self.cache.clear_crosshair();
+    }
+
+    pub fn element<'a>(
+        &'a self,
+        main_chart: &'a ViewState,
+        receives_depth: bool,
+        visible_range: RangeInclusive<u64>,
+    ) -> iced::Element<'a, Message> {
+        if !receives_depth {
+            return center(text(
+                "Depth imbalance needs a depth stream, use a footprint or tick based chart",
+            ))
+            .into();
+        }
+
+        if self.rolling.history.is_empty() {
+            return center(text("Waiting for depth...")).into();
+        }
+
+        let tooltip = |value: &f32, _next: Option<&f32>| {
+            PlotTooltip::new(format!("Depth Imbalance: {:+.3}", value))
+        };
+
+        let plot = LinePlot::new(|v: &f32| *v)
+            .stroke_width(1.0)
+            .fixed_extents(-1.0, 1.0)
+            .signed(true)
+            .with_tooltip(tooltip);
+
+        indicator_row(
+            main_chart,
+            &self.cache,
+            plot,
+            &self.rolling.history,
+            visible_range,
+        )
+    }
+}
@@ -0,0 +1,196 @@
+use crate::chart::{
+    Caches, Message, ViewState,
+    indicator::{
+        indicator_row,
+        plot::{Plot, PlotTooltip, Series, TooltipFn, YScale},
+    },
+};
+use crate::style::dashed_line;
+
+use data::chart::PlotData;
+use data::chart::indicator::stochastic;
+use data::chart::kline::KlineDataPoint;
+
+use iced::widget::canvas::{self, Path, Stroke};
+use iced::widget::{center, text};
+use iced::{Point, Theme};
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+
+const OVERBOUGHT: f32 = 80.0;
+const OVERSOLD: f32 = 20.0;
+
+/// Sub-panel of the stochastic kline study, recomputed from the chart's klines
+pub struct StochasticPanel {
+    cache: Caches,
+    params: (usize, usize, usize),
+    /// `(%K, %D)` under the kline time on time based charts, the datapoint index on tick based ones
+    values: BTreeMap<u64, (f32, f32)>,
+}
+
+impl StochasticPanel {
+    pub fn new(k_period: usize, d_period: usize, smooth: usize) -> Self {
+        Self {
+            cache: Caches::default(),
+            params: (k_period, d_period, smooth),
+            values: BTreeMap::new(),
+        }
+    }
+
+    pub fn is_configured(&self, k_period: usize, d_period: usize, smooth: usize) -> bool {
+        self.params == (k_period, d_period, smooth)
+    }
+
+    pub fn rebuild(&mut self, source: &PlotData<KlineDataPoint>) {
+        let klines = match source {
+            PlotData::TimeBased(timeseries) => timeseries
+                .datapoints
+                .iter()
+                .map(|(time, dp)| (*time, &dp.kline))
+                .collect::<Vec<_>>(),
+            PlotData::TickBased(tick_aggr) => tick_aggr
+                .datapoints
+                .iter()
+                .enumerate()
+                .map(|(index, dp)| (index as u64, &dp.kline))
+                .collect(),
+        };
+
+        let highs = klines
+            .iter()
+            .map(|(_, k)| k.high.to_f32())
+            .collect::<Vec<_>>();
+        let lows = klines
+            .iter()
+            .map(|(_, k)| k.low.to_f32())
+            .collect::<Vec<_>>();
+        let closes = klines
+            .iter()
+            .map(|(_, k)| k.close.to_f32())
+            .collect::<Vec<_>>();
+
+        let (k_period, d_period, smooth) = self.params;
+        let values = stochastic(&highs, &lows, &closes, k_period, d_period, smooth);
+
+        // the oscillator lines up with the last bars, the warm-up ones have no value
+        self.values = klines[klines.len() - values.len()..]
+            .iter()
+            .map(|(key, _)| *key)
+            .zip(values)
+            .collect();
+        self.cache.clear_all();
+    }
+
+    pub fn clear_crosshair_caches(&mut self) {
+        self.cache.clear_crosshair();
+    }
+
+    pub fn element<'a>(
+        &'a self,
+        main_chart: &'a ViewState,
+        visible_range: RangeInclusive<u64>,
+    ) -> iced::Element<'a, Message> {
+        if self.values.is_empty() {
+            return center(text("Waiting for enough bars...")).into();
+        }
+
+        let plot = StochasticPlot {
+            tooltip: Box::new(|(k, d): &(f32, f32), _next: Option<&(f32, f32)>| {
+                PlotTooltip::new(format!("%K: {k:.1}\n%D: {d:.1}"))
+            }),
+        };
+
+        indicator_row(main_chart, &self.cache, plot, &self.values, visible_range)
+    }
+}
+
+/// %K and %D lines on a fixed 0–100 axis, with the overbought and oversold levels dashed and a
+/// dot where the lines cross
+struct StochasticPlot {
+    tooltip: TooltipFn<(f32, f32)>,
+}
+
+impl<S> Plot<S> for StochasticPlot
+where
+    S: Series<Y = (f32, f32)>,
+{
+    fn y_extents(&self, datapoints: &S, range: RangeInclusive<u64>) -> Option<(f32, f32)> {
+        let mut any = false;
+        datapoints.for_each_in(range, |_, _| any = true);
+
+        any.then_some((0.0, 100.0))
+    }
+
+    fn draw(
+        &self,
+        frame: &mut canvas::Frame,
+        ctx: &ViewState,
+        theme: &Theme,
+        datapoints: &S,
+        range: RangeInclusive<u64>,
+        scale: &YScale,
+    ) {
+        let palette = theme.extended_palette();
+
+        let (left, right) = {
+            let (a, b) = (
+                ctx.interval_to_x(*range.start()),
+                ctx.interval_to_x(*range.end()),
+            );
+            (a.min(b) - ctx.cell_width, a.max(b) + ctx.cell_width)
+        };
+        for level in [OVERBOUGHT, OVERSOLD] {
+            let y = scale.to_y(level);
+            frame.stroke(
+                &Path::line(Point::new(left, y), Point::new(right, y)),
+                dashed_line(theme),
+            );
+        }
+
+        let stroke = |color| {
+            Stroke::with_color(
+                Stroke {
+                    width: 1.0,
+                    ..Stroke::default()
+                },
+                color,
+            )
+        };
+        let (k_color, d_color) = (palette.primary.base.color, palette.warning.base.color);
+        let radius = (ctx.cell_width * 0.25).clamp(1.5, 3.0);
+
+        let mut prev: Option<(f32, f32, f32)> = None;
+        datapoints.for_each_in(range, |x, &(k, d)| {
+            let sx = ctx.interval_to_x(x) - (ctx.cell_width / 2.0);
+
+            if let Some((px, pk, pd)) = prev {
+                let line = |from, to| {
+                    Path::line(
+                        Point::new(px, scale.to_y(from)),
+                        Point::new(sx, scale.to_y(to)),
+                    )
+                };
+                frame.stroke(&line(pd, d), stroke(d_color));
+                frame.stroke(&line(pk, k), stroke(k_color));
+
+                let (prev_spread, spread) = (pk - pd, k - d);
+                if (prev_spread < 0.0) != (spread < 0.0) {
+                    let t = prev_spread / (prev_spread - spread);
+                    let cross = Point::new(px + (sx - px) * t, scale.to_y(pk + (k - pk) * t));
+                    let color = if spread > prev_spread {
+                        palette.success.base.color
+                    } else {
+                        palette.danger.base.color
+                    };
+
+                    frame.fill(&Path::circle(cross, radius), color);
+                }
+            }
+            prev = Some((sx, k, d));
+        });
+    }
+
+    fn tooltip_fn(&self) -> Option<&TooltipFn<S::Y>> {
+        Some(&self.tooltip)
+    }
+}
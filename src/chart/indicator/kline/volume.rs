@@ -1,7 +1,7 @@
 use crate::chart::{
     Caches, Message, ViewState,
     indicator::{
-        indicator_row,
+        indicator_row, indicator_row_with,
         kline::KlineIndicatorImpl,
         plot::{
             PlotTooltip,
@@ -10,7 +10,10 @@ use crate::chart::{
     },
 };
 
-use data::chart::{PlotData, kline::KlineDataPoint};
+use data::chart::PlotData;
+use data::chart::kline::{
+    KlineDataPoint, VolumeDisplayMode, format_volume_percent, rolling_daily_average,
+};
 use data::util::format_with_commas;
 use exchange::{Kline, Trade};
 
@@ -20,6 +23,11 @@ use std::ops::RangeInclusive;
 pub struct VolumeIndicator {
     cache: Caches,
     data: BTreeMap<u64, (f32, f32)>,
+    mode: VolumeDisplayMode,
+    /// Average bar volume over the preceding 24 hours, by the same keys as `data`
+    daily_avg: BTreeMap<u64, f32>,
+    /// `data` in percent of `daily_avg`, filled only while that mode is shown
+    normalized: BTreeMap<u64, (f32, f32)>,
 }
 
 impl VolumeIndicator {
@@ -27,6 +35,50 @@ impl VolumeIndicator {
         Self {
             cache: Caches::default(),
             data: BTreeMap::new(),
+            mode: VolumeDisplayMode::Absolute,
+            daily_avg: BTreeMap::new(),
+            normalized: BTreeMap::new(),
+        }
+    }
+
+    /// Recomputes the daily averages and the normalized bars, `bar_time` giving
+    /// the open time of the bar at a key
+    fn normalize(&mut self, bar_time: impl Fn(u64) -> Option<u64>) {
+        if self.mode == VolumeDisplayMode::Absolute {
+            self.daily_avg.clear();
+            self.normalized.clear();
+            return;
+        }
+
+        self.daily_avg = rolling_daily_average(self.data.iter().filter_map(|(&key, &volume)| {
+            bar_time(key).map(|time| (key, time, total_volume(volume)))
+        }));
+
+        self.normalized = self
+            .data
+            .iter()
+            .filter_map(|(key, &(buy, sell))| {
+                let avg = *self.daily_avg.get(key)?;
+                let scale = if avg > 0.0 { 100.0 / avg } else { 0.0 };
+
+                Some(if buy == -1.0 {
+                    (*key, (-1.0, sell * scale))
+                } else {
+                    (*key, (buy * scale, sell * scale))
+                })
+            })
+            .collect();
+    }
+
+    fn normalize_from_source(&mut self, source: &PlotData<KlineDataPoint>) {
+        match source {
+            PlotData::TimeBased(_) => self.normalize(Some),
+            PlotData::TickBased(tickseries) => self.normalize(|idx| {
+                tickseries
+                    .datapoints
+                    .get(idx as usize)
+                    .map(|dp| dp.kline.time)
+            }),
         }
     }
 
@@ -35,8 +87,15 @@ impl VolumeIndicator {
         main_chart: &'a ViewState,
         visible_range: RangeInclusive<u64>,
     ) -> iced::Element<'a, Message> {
-        let tooltip = |&(buy, sell): &(f32, f32), _next: Option<&(f32, f32)>| {
-            if buy == -1.0 {
+        let percent = self.mode == VolumeDisplayMode::PercentOfDailyAverage;
+
+        let tooltip = move |&(buy, sell): &(f32, f32), _next: Option<&(f32, f32)>| {
+            if percent {
+                PlotTooltip::new(format!(
+                    "Volume: {} of daily avg",
+                    format_volume_percent(total_volume((buy, sell)))
+                ))
+            } else if buy == -1.0 {
                 PlotTooltip::new(format!("Volume: {}", format_with_commas(sell)))
             } else {
                 let buy_t = format!("Buy Volume: {}", format_with_commas(buy));
@@ -55,18 +114,31 @@ impl VolumeIndicator {
             }
         };
 
-        let value_fn = |&(buy, sell): &(f32, f32)| {
-            if buy == -1.0 { sell } else { buy + sell }
-        };
+        let value_fn = |&volume: &(f32, f32)| total_volume(volume);
 
         let plot = BarPlot::new(value_fn, bar_kind)
             .bar_width_factor(0.9)
             .with_tooltip(tooltip);
 
-        indicator_row(main_chart, &self.cache, plot, &self.data, visible_range)
+        if percent {
+            indicator_row_with(
+                main_chart,
+                &self.cache,
+                plot,
+                &self.normalized,
+                visible_range,
+                format_volume_percent,
+            )
+        } else {
+            indicator_row(main_chart, &self.cache, plot, &self.data, visible_range)
+        }
     }
 }
 
+fn total_volume((buy, sell): (f32, f32)) -> f32 {
+    if buy == -1.0 { sell } else { buy + sell }
+}
+
 impl KlineIndicatorImpl for VolumeIndicator {
     fn clear_all_caches(&mut self) {
         self.cache.clear_all();
@@ -93,6 +165,7 @@ impl KlineIndicatorImpl for VolumeIndicator {
                 self.data = tickseries.volume_data();
             }
         }
+        self.normalize_from_source(source);
         self.clear_all_caches();
     }
 
@@ -101,6 +174,8 @@ impl KlineIndicatorImpl for VolumeIndicator {
             self.data
                 .insert(kline.time, (kline.volume.0, kline.volume.1));
         }
+        // klines only arrive on time based charts, keyed by their time
+        self.normalize(Some);
         self.clear_all_caches();
     }
 
@@ -120,6 +195,7 @@ impl KlineIndicatorImpl for VolumeIndicator {
                 }
             }
         }
+        self.normalize_from_source(source);
         self.clear_all_caches();
     }
 
@@ -130,4 +206,12 @@ impl KlineIndicatorImpl for VolumeIndicator {
     fn on_basis_change(&mut self, source: &PlotData<KlineDataPoint>) {
         self.rebuild_from_source(source);
     }
+
+    fn set_volume_display(&mut self, mode: VolumeDisplayMode, source: &PlotData<KlineDataPoint>) {
+        if self.mode != mode {
+            self.mode = mode;
+            self.normalize_from_source(source);
+            self.clear_all_caches();
+        }
+    }
 }
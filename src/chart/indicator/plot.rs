@@ -202,8 +202,11 @@ where
         match event {
             canvas::Event::Mouse(mouse::Event::CursorMoved { .. }) => {
                 let msg = matches!(*interaction, Interaction::None)
-                    .then(|| cursor.is_over(bounds))
-                    .and_then(|over| over.then_some(Message::CrosshairMoved));
+                    .then(|| cursor.position_in(bounds))
+                    .flatten()
+                    .map(|position| {
+                        Message::CrosshairMoved(self.ctx.cursor_time(position, bounds.size()))
+                    });
                 let action = msg.map_or(canvas::Action::request_redraw(), canvas::Action::publish);
                 Some(match interaction {
                     Interaction::None => action,
@@ -353,7 +356,7 @@ where
     }
 }
 
-type TooltipFn<T> = Box<dyn Fn(&T, Option<&T>) -> PlotTooltip>;
+pub type TooltipFn<T> = Box<dyn Fn(&T, Option<&T>) -> PlotTooltip>;
 
 const TOOLTIP_MARGIN: f32 = 4.0; // px from edge of canvas
 const TOOLTIP_PADDING: f32 = 8.0; // px inside tooltip box
@@ -18,6 +18,10 @@ pub struct LinePlot<V, T> {
     pub stroke_width: f32,
     pub show_points: bool,
     pub point_radius_factor: f32,
+    // fixed (min, max) instead of fitting the visible values
+    pub extents: Option<(f32, f32)>,
+    // success color above zero, danger color below
+    pub signed: bool,
     _phantom: std::marker::PhantomData<T>,
 }
 
@@ -32,6 +36,8 @@ impl<V, T> LinePlot<V, T> {
             stroke_width: 1.0,
             show_points: true,
             point_radius_factor: 0.2,
+            extents: None,
+            signed: false,
             _phantom: std::marker::PhantomData,
         }
     }
@@ -59,6 +65,18 @@ impl<V, T> LinePlot<V, T> {
         self
     }
 
+    /// keep the y axis between `min` and `max` whatever the visible values are
+    pub fn fixed_extents(mut self, min: f32, max: f32) -> Self {
+        self.extents = Some((min, max));
+        self
+    }
+
+    /// color the line by the sign of its values, splitting segments where they cross zero
+    pub fn signed(mut self, on: bool) -> Self {
+        self.signed = on;
+        self
+    }
+
     pub fn with_tooltip<F>(mut self, tooltip: F) -> Self
     where
         F: Fn(&T, Option<&T>) -> PlotTooltip + 'static,
@@ -90,12 +108,14 @@ where
         if min_v == f32::MAX {
             None
         } else {
-            Some((min_v, max_v))
+            Some(self.extents.unwrap_or((min_v, max_v)))
         }
     }
 
     fn adjust_extents(&self, min: f32, max: f32) -> (f32, f32) {
-        if self.padding > 0.0 && max > min {
+        if self.extents.is_some() {
+            (min, max)
+        } else if self.padding > 0.0 && max > min {
             let range = max - min;
             let pad = range * self.padding;
             (min - pad, max + pad)
@@ -114,37 +134,57 @@ where
         scale: &YScale,
     ) {
         let palette = theme.extended_palette();
-        let color = palette.secondary.strong.color;
-
-        let stroke = Stroke::with_color(
-            Stroke {
-                width: self.stroke_width,
-                ..Stroke::default()
-            },
-            color,
-        );
+        let color_of = |v: f32| {
+            if !self.signed {
+                palette.secondary.strong.color
+            } else if v < 0.0 {
+                palette.danger.base.color
+            } else {
+                palette.success.base.color
+            }
+        };
+
+        let stroke = |color| {
+            Stroke::with_color(
+                Stroke {
+                    width: self.stroke_width,
+                    ..Stroke::default()
+                },
+                color,
+            )
+        };
 
         // Polyline
-        let mut prev: Option<(f32, f32)> = None;
+        let mut prev: Option<(f32, f32, f32)> = None;
         datapoints.for_each_in(range.clone(), |x, y| {
             let sx = ctx.interval_to_x(x) - (ctx.cell_width / 2.0);
             let vy = (self.value)(y);
             let sy = scale.to_y(vy);
-            if let Some((px, py)) = prev {
-                frame.stroke(
-                    &Path::line(iced::Point::new(px, py), iced::Point::new(sx, sy)),
-                    stroke,
-                );
+            if let Some((px, py, pv)) = prev {
+                let (from, to) = (iced::Point::new(px, py), iced::Point::new(sx, sy));
+
+                if self.signed && (pv < 0.0) != (vy < 0.0) {
+                    let t = pv / (pv - vy);
+                    let cross = iced::Point::new(px + (sx - px) * t, scale.to_y(0.0));
+
+                    frame.stroke(&Path::line(from, cross), stroke(color_of(pv)));
+                    frame.stroke(&Path::line(cross, to), stroke(color_of(vy)));
+                } else {
+                    frame.stroke(&Path::line(from, to), stroke(color_of(vy)));
+                }
             }
-            prev = Some((sx, sy));
+            prev = Some((sx, sy, vy));
         });
 
         if self.show_points {
             let radius = (ctx.cell_width * self.point_radius_factor).min(5.0);
             datapoints.for_each_in(range, |x, y| {
                 let sx = ctx.interval_to_x(x) - (ctx.cell_width / 2.0);
-                let sy = scale.to_y((self.value)(y));
-                frame.fill(&Path::circle(iced::Point::new(sx, sy), radius), color);
+                let vy = (self.value)(y);
+                frame.fill(
+                    &Path::circle(iced::Point::new(sx, scale.to_y(vy)), radius),
+                    color_of(vy),
+                );
             });
         }
     }
@@ -1,32 +1,51 @@
 use super::{
     Action, Basis, Chart, Interaction, Message, PlotConstants, PlotData, TEXT_SIZE, ViewState,
-    indicator, request_fetch, scale::linear::PriceInfoLabel,
+    cells::{CellBars, CellLayer, CellQuad, FootprintCells, SharedCells},
+    indicator,
+    minimap::Minimap,
+    request_fetch, request_fetch_for_stream,
+    scale::linear::PriceInfoLabel,
+};
+use crate::chart::indicator::kline::{
+    KlineIndicatorImpl, depth_imbalance::DepthImbalancePanel, stochastic::StochasticPanel,
 };
-use crate::chart::indicator::kline::KlineIndicatorImpl;
 use crate::{modal::pane::settings::study, style};
+use data::aggr::multi_res::{DownsampledBar, MultiResTimeSeries};
 use data::aggr::ticks::TickAggr;
-use data::aggr::time::TimeSeries;
+use data::aggr::time::{NpocSnapshot, TimeSeries};
 use data::chart::Autoscale;
+use data::chart::heatmap::HistoricalDepth;
 use data::chart::kline::ClusterScaling;
 use data::chart::{
-    KlineChartKind, ViewConfig,
+    KlineChartKind, PriceAxisMode, PriceAxisRounding, ViewConfig,
     indicator::{Indicator, KlineIndicator},
-    kline::{ClusterKind, FootprintStudy, KlineDataPoint, KlineTrades, NPoc, PointOfControl},
+    kline::{
+        CandleColoring, CandleStyle, CellRenderer, ClusterKind, CompareOverlays, CompareSeries,
+        DepthLayer, Divergence, FootprintCellText, FootprintStudy, KlineDataPoint, KlineStudy,
+        KlineTrades, NPoc, NpocLookback, PointOfControl, SessionSeparator, TimeframeCache,
+        ValueArea, VolumeDisplayMode,
+    },
 };
 use data::util::{abbr_large_numbers, count_decimals};
 use exchange::util::{Price, PriceStep};
 use exchange::{
-    Kline, OpenInterest as OIData, TickerInfo, Trade,
-    fetcher::{FetchRange, RequestHandler},
+    Kline, Liquidation, OpenInterest as OIData, TickerInfo, Timeframe, Trade,
+    adapter::StreamKind,
+    depth::Depth,
+    fetcher::{FetchRange, FetchRequests, FetchSpec, RequestHandler, TradeFetcher},
 };
 
 use iced::task::Handle;
 use iced::theme::palette::Extended;
 use iced::widget::canvas::{self, Event, Geometry, Path, Stroke};
-use iced::{Alignment, Element, Point, Rectangle, Renderer, Size, Theme, Vector, mouse};
+use iced::{Alignment, Color, Element, Point, Rectangle, Renderer, Size, Theme, Vector, mouse};
 
 use enum_map::EnumMap;
-use std::time::Instant;
+use std::cell::{Ref, RefCell};
+use std::collections::{BTreeMap, VecDeque};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 impl Chart for KlineChart {
     type IndicatorKind = KlineIndicator;
@@ -45,6 +64,12 @@ impl Chart for KlineChart {
             .values_mut()
             .filter_map(Option::as_mut)
             .for_each(|indi| indi.clear_crosshair_caches());
+        if let Some(panel) = self.depth_imbalance.as_mut() {
+            panel.clear_crosshair_caches();
+        }
+        if let Some(panel) = self.stochastic.as_mut() {
+            panel.clear_crosshair_caches();
+        }
     }
 
     fn invalidate_all(&mut self) {
@@ -70,6 +95,13 @@ impl Chart for KlineChart {
                 elements.push(indi.element(chart_state, earliest..=latest));
             }
         }
+
+        if let Some(panel) = self.depth_imbalance.as_ref() {
+            elements.push(panel.element(chart_state, self.receives_depth(), earliest..=latest));
+        }
+        if let Some(panel) = self.stochastic.as_ref() {
+            elements.push(panel.element(chart_state, earliest..=latest));
+        }
         elements
     }
 
@@ -99,7 +131,7 @@ impl Chart for KlineChart {
     }
 
     fn interval_keys(&self) -> Option<Vec<u64>> {
-        match &self.data_source {
+        match &self.series.borrow().data_source {
             PlotData::TimeBased(_) => None,
             PlotData::TickBased(tick_aggr) => Some(
                 tick_aggr
@@ -111,30 +143,116 @@ impl Chart for KlineChart {
         }
     }
 
+    fn session_boundaries(&self) -> Vec<u64> {
+        match &self.series.borrow().data_source {
+            PlotData::TimeBased(timeseries) => timeseries
+                .session_boundaries(
+                    self.session_separator,
+                    data::config::timezone::session_timezone(),
+                )
+                .collect(),
+            PlotData::TickBased(_) => vec![],
+        }
+    }
+
     fn autoscaled_coords(&self) -> Vector {
         let chart = self.state();
-        let x_translation = match &self.kind {
-            KlineChartKind::Footprint { .. } => {
-                0.5 * (chart.bounds.width / chart.scaling) - (chart.cell_width / chart.scaling)
-            }
-            KlineChartKind::Candles => {
-                0.5 * (chart.bounds.width / chart.scaling)
-                    - (8.0 * chart.cell_width / chart.scaling)
-            }
-        };
-        Vector::new(x_translation, chart.translation.y)
+        Vector::new(latest_bar_x(&self.kind, chart), chart.translation.y)
     }
 
     fn supports_fit_autoscaling(&self) -> bool {
         true
     }
 
+    fn supports_follow_latest(&self) -> bool {
+        true
+    }
+
+    fn gpu_layer(&self) -> Option<Element<'_, Message>> {
+        if !matches!(self.kind, KlineChartKind::Footprint { .. })
+            || self.compact
+            || !super::cells::wgpu_active()
+        {
+            return None;
+        }
+        let layer = Arc::clone(self.gpu_cells.as_ref()?);
+
+        Some(
+            iced::widget::shader(FootprintCells { layer })
+                .width(iced::Length::Fill)
+                .height(iced::Length::Fill)
+                .into(),
+        )
+    }
+
+    fn minimap(&self) -> Option<Element<'_, Message>> {
+        if !matches!(self.kind, KlineChartKind::Candles { .. }) {
+            return None;
+        }
+        let height = self.minimap?;
+
+        let chart = self.state();
+        let region = chart.visible_region(chart.bounds.size());
+
+        Some(
+            canvas::Canvas::new(Minimap {
+                data_source: Ref::map(self.series.borrow(), |series| &series.data_source),
+                cache: &self.minimap_cache,
+                visible: chart.interval_range(&region),
+            })
+            .width(iced::Length::Fill)
+            .height(iced::Length::Fixed(height))
+            .into(),
+        )
+    }
+
+    fn is_compact(&self) -> bool {
+        self.compact
+    }
+
+    fn supports_anchored_vwap(&self) -> bool {
+        self.anchored_vwap && self.chart.basis.is_time()
+    }
+
+    fn kline_at(&self, key: u64) -> Option<Kline> {
+        kline_at_interval(&self.series.borrow().data_source, key).copied()
+    }
+
+    fn oldest_visible_kline(&self) -> Option<Kline> {
+        let chart = self.state();
+        let region = chart.visible_region(chart.bounds.size());
+        let (earliest, latest) = chart.interval_range(&region);
+
+        match &self.series.borrow().data_source {
+            PlotData::TimeBased(timeseries) => timeseries
+                .datapoints
+                .range(earliest..=latest)
+                .next()
+                .map(|(_, dp)| dp.kline),
+            PlotData::TickBased(tick_aggr) => {
+                // Tick 基准下索引越大越早，此时 latest 为最左侧（最早）的索引
+                let len = tick_aggr.datapoints.len() as u64;
+                let oldest = latest.min(len.checked_sub(1)?);
+
+                (oldest >= earliest)
+                    .then(|| tick_aggr.datapoints[(len - 1 - oldest) as usize].kline)
+            }
+        }
+    }
+
     fn is_empty(&self) -> bool {
-        match &self.data_source {
+        match &self.series.borrow().data_source {
             PlotData::TimeBased(timeseries) => timeseries.datapoints.is_empty(),
             PlotData::TickBased(tick_aggr) => tick_aggr.datapoints.is_empty(),
         }
     }
+
+    fn datapoint_count(&self) -> usize {
+        match &self.series.borrow().data_source {
+            PlotData::TimeBased(timeseries) => timeseries.datapoints.len(),
+            PlotData::TickBased(tick_aggr) => tick_aggr.datapoints.len(),
+        }
+    }
 }
 
 impl PlotConstants for KlineChart {
@@ -167,16 +285,258 @@ impl PlotConstants for KlineChart {
     }
 }
 
-pub struct KlineChart {
-    chart: ViewState,
+/// What a chart draws its bars from, shared with the copies of its pane popped out into other
+/// windows. Only one of them writes the live events, the others catch up from `revision`
+/// on their next tick.
+struct Series {
     data_source: PlotData<KlineDataPoint>,
     raw_trades: Vec<Trade>,
+    /// Kept so footprints rebuilt on a tick size change get their liquidations back
+    raw_liquidations: Vec<Liquidation>,
+    /// Coarser copies of a time based series, drawn in its place once zoomed out far enough
+    resolutions: Option<Box<MultiResTimeSeries>>,
+    /// Chart writing the live events, see [`KlineChart::writes_live`]
+    writer: Option<uuid::Uuid>,
+    /// Bumped on every write
+    revision: u64,
+}
+
+impl Series {
+    fn new(
+        data_source: PlotData<KlineDataPoint>,
+        raw_trades: Vec<Trade>,
+        raw_liquidations: Vec<Liquidation>,
+    ) -> Rc<RefCell<Self>> {
+        let resolutions = match &data_source {
+            PlotData::TimeBased(timeseries) => Some(Box::new(MultiResTimeSeries::new(timeseries))),
+            PlotData::TickBased(_) => None,
+        };
+
+        Rc::new(RefCell::new(Self {
+            data_source,
+            raw_trades,
+            raw_liquidations,
+            resolutions,
+            writer: None,
+            revision: 0,
+        }))
+    }
+
+    /// Brings the coarser resolutions up to date with the base series changed from `from`
+    fn downsample_from(&mut self, from: u64) {
+        if let (Some(resolutions), PlotData::TimeBased(timeseries)) =
+            (self.resolutions.as_mut(), &self.data_source)
+        {
+            resolutions.downsample(timeseries, from);
+        }
+    }
+
+    /// Downsampled bars to draw instead of the base series when `earliest..=latest`
+    /// holds too many bars, with the base and downsampled interval in milliseconds
+    fn coarser_resolution(
+        &self,
+        earliest: u64,
+        latest: u64,
+    ) -> Option<(&BTreeMap<u64, DownsampledBar>, u64, u64)> {
+        let (PlotData::TimeBased(timeseries), Some(resolutions)) =
+            (&self.data_source, &self.resolutions)
+        else {
+            return None;
+        };
+
+        let base_ms = timeseries.interval.to_milliseconds().max(1);
+        let visible_bars = (latest.saturating_sub(earliest) / base_ms) as usize;
+        let timeframe = resolutions.select_resolution(visible_bars);
+
+        resolutions
+            .level(timeframe)
+            .map(|datapoints| (datapoints, base_ms, timeframe.to_milliseconds()))
+    }
+}
+
+pub struct KlineChart {
+    id: uuid::Uuid,
+    chart: ViewState,
+    series: Rc<RefCell<Series>>,
+    /// Revision of `series` this chart's indicators and viewport last caught up with
+    seen_revision: u64,
     indicators: EnumMap<KlineIndicator, Option<Box<dyn KlineIndicatorImpl>>>,
     fetching_trades: (bool, Option<Handle>),
     pub(crate) kind: KlineChartKind,
     request_handler: RequestHandler,
     study_configurator: study::Configurator<FootprintStudy>,
     last_tick: Instant,
+    compare: Option<Box<CompareOverlay>>,
+    scale_padding: f32,
+    anchored_vwap: bool,
+    poc_history: bool,
+    session_separator: SessionSeparator,
+    visible_profile: bool,
+    candle_coloring: CandleColoring,
+    candle_style: CandleStyle,
+    /// Body width as a share of the cell width and wick thickness as a share of the body width
+    candle_proportions: (f32, f32),
+    volume_display: VolumeDisplayMode,
+    flash_new_bar: bool,
+    /// When the latest bar opened, until its highlight faded out
+    new_bar_flash: Option<Instant>,
+    /// Height of the overview strip under candle charts, `None` while it's hidden
+    minimap: Option<f32>,
+    minimap_cache: canvas::Cache,
+    /// Pane size under which footprints are drawn as plain candles, 0 to never do so
+    compact_below: f32,
+    /// Whether the pane is currently small enough for that
+    compact: bool,
+    /// Series of the other timeframes of a pane with timeframe tabs
+    timeframe_cache: Option<Box<TimeframeCache<TimeSeries<KlineDataPoint>>>>,
+    kline_studies: Vec<KlineStudy>,
+    depth_imbalance: Option<Box<DepthImbalancePanel>>,
+    stochastic: Option<Box<StochasticPanel>>,
+    /// Order book runs drawn under the footprint of a heatmap + footprint pane
+    depth_history: Option<Box<DepthLayerHistory>>,
+    gap_repair: Option<Box<GapRepair>>,
+    npoc_job: NpocJob,
+    /// Footprint cell bars handed to the shader layer, `None` when the canvas fills them
+    gpu_cells: Option<SharedCells>,
+    /// Fed by a replay, its history comes from the replay alone so nothing gets fetched
+    replaying: bool,
+}
+
+/// Canvas width kept on the right for the candle volume profile
+const CANDLE_PROFILE_WIDTH: f32 = 60.0;
+/// Screen height of the delta bar strip along the bottom of footprint charts
+const DELTA_BAR_PANEL_HEIGHT: f32 = 36.0;
+
+/// Klines asked for per repair request, same as the initial fetch
+const GAP_REPAIR_CHUNK_BARS: u64 = 450;
+/// Repair requests allowed in flight at once
+const GAP_REPAIR_CONCURRENCY: usize = 3;
+
+/// Bars of order book history kept for the depth layer
+const DEPTH_LAYER_BARS: u64 = 500;
+
+/// Footprints of compact panes are drawn like this
+static COMPACT_KIND: KlineChartKind = KlineChartKind::Candles {
+    show_volume_profile: false,
+};
+/// Screen height of the time axis under the chart, with its separator
+const X_AXIS_HEIGHT: f32 = 27.0;
+
+/// How long the highlight of a newly opened bar takes to fade out
+const NEW_BAR_FLASH: Duration = Duration::from_millis(800);
+
+/// Visible bars from which naked POCs are worked out on a background thread
+const BACKGROUND_NPOC_MIN_BARS: usize = 2_000;
+
+/// Depth runs keyed like the chart's bars, kline time or datapoint index on tick basis
+struct DepthLayerHistory {
+    depth: HistoricalDepth,
+    latest_bar: u64,
+}
+
+impl DepthLayerHistory {
+    fn for_kind(kind: &KlineChartKind, chart: &ViewState) -> Option<Box<Self>> {
+        kind.depth_layer()?;
+
+        Some(Box::new(Self {
+            depth: HistoricalDepth::new(
+                chart.ticker_info.min_qty.into(),
+                chart.tick_size,
+                chart.basis,
+            ),
+            latest_bar: 0,
+        }))
+    }
+
+    fn insert(&mut self, bar: u64, depth: &Depth, oldest_bar: u64) {
+        self.depth.insert_latest_depth(depth, bar);
+
+        if bar > self.latest_bar {
+            self.latest_bar = bar;
+            self.depth.cleanup_old_price_levels(oldest_bar);
+        }
+    }
+}
+
+/// Naked POC computation handed off to a background thread, one at a time with only
+/// the latest snapshot kept while it runs
+#[derive(Default)]
+struct NpocJob {
+    pending: Option<Box<NpocSnapshot>>,
+    in_flight: bool,
+}
+
+/// Refetch of the gaps found in the loaded klines, issued a few requests at a time
+struct GapRepair {
+    /// Loaded range at the start, checked again once every request settled
+    span: (u64, u64),
+    queued: VecDeque<FetchRange>,
+    in_flight: Vec<uuid::Uuid>,
+    total: usize,
+}
+
+impl GapRepair {
+    fn done(&self) -> usize {
+        self.total - self.queued.len() - self.in_flight.len()
+    }
+}
+
+/// How the klines look after a gap repair settled
+pub enum GapRepairOutcome {
+    Repaired { requests: usize },
+    GapsRemain { missing: usize },
+}
+
+/// Lines of the compared tickers, one per entry of `overlays` and in the same order
+struct CompareOverlay {
+    overlays: CompareOverlays,
+    /// Kept next to the lines for the tickers list, which borrows them as a slice
+    tickers: Vec<TickerInfo>,
+    lines: Vec<CompareLine>,
+}
+
+struct CompareLine {
+    series: CompareSeries,
+    request_handler: RequestHandler,
+}
+
+impl CompareLine {
+    fn new(ticker_info: TickerInfo) -> Self {
+        Self {
+            series: CompareSeries::new(ticker_info),
+            request_handler: RequestHandler::new(),
+        }
+    }
+}
+
+impl CompareOverlay {
+    /// Reuses the lines of tickers that stay, so their fetched klines aren't lost
+    fn new(overlays: CompareOverlays, mut previous: Vec<CompareLine>) -> Self {
+        let lines = overlays
+            .tickers()
+            .map(|ticker_info| {
+                previous
+                    .iter()
+                    .position(|line| line.series.ticker_info == ticker_info)
+                    .map_or_else(
+                        || CompareLine::new(ticker_info),
+                        |i| previous.swap_remove(i),
+                    )
+            })
+            .collect();
+
+        Self {
+            overlays,
+            tickers: overlays.tickers().collect(),
+            lines,
+        }
+    }
+
+    fn line_mut(&mut self, ticker_info: &TickerInfo) -> Option<&mut CompareLine> {
+        self.lines
+            .iter_mut()
+            .find(|line| line.series.ticker_info == *ticker_info)
+    }
 }
 
 impl KlineChart {
@@ -199,12 +559,19 @@ impl KlineChart {
 
                 let base_price_y = timeseries.base_price();
                 let latest_x = timeseries.latest_timestamp().unwrap_or(0);
-                let (scale_high, scale_low) = timeseries.price_scale({
-                    match kind {
+
+                let scale_padding = data::chart::kline::Config::default().scale_padding;
+                let initial_span = interval.to_milliseconds()
+                    * match kind {
                         KlineChartKind::Footprint { .. } => 12,
-                        KlineChartKind::Candles => 60,
-                    }
-                });
+                        KlineChartKind::Candles { .. } => 60,
+                    };
+                let (scale_high, scale_low) = timeseries.price_scale_in_range(
+                    latest_x.saturating_sub(initial_span),
+                    latest_x,
+                    scale_padding,
+                    footprint_min_padding(kind, step),
+                );
 
                 let low_rounded = scale_low.round_to_side_step(true, step);
                 let high_rounded = scale_high.round_to_side_step(false, step);
@@ -216,11 +583,11 @@ impl KlineChart {
 
                 let cell_width = match kind {
                     KlineChartKind::Footprint { .. } => 80.0,
-                    KlineChartKind::Candles => 4.0,
+                    KlineChartKind::Candles { .. } => 4.0,
                 };
                 let cell_height = match kind {
                     KlineChartKind::Footprint { .. } => 800.0 / y_ticks,
-                    KlineChartKind::Candles => 200.0 / y_ticks,
+                    KlineChartKind::Candles { .. } => 200.0 / y_ticks,
                 };
 
                 let mut chart = ViewState::new(
@@ -231,6 +598,10 @@ impl KlineChart {
                     ViewConfig {
                         splits: layout.splits,
                         autoscale: Some(Autoscale::FitToVisible),
+                        auto_scroll: layout.auto_scroll,
+                        fixed_cells: layout.fixed_cells,
+                        price_axis: layout.price_axis,
+                        price_rounding: layout.price_rounding,
                     },
                     cell_width,
                     cell_height,
@@ -238,19 +609,10 @@ impl KlineChart {
                 chart.base_price_y = base_price_y;
                 chart.latest_x = latest_x;
 
-                let x_translation = match &kind {
-                    KlineChartKind::Footprint { .. } => {
-                        0.5 * (chart.bounds.width / chart.scaling)
-                            - (chart.cell_width / chart.scaling)
-                    }
-                    KlineChartKind::Candles => {
-                        0.5 * (chart.bounds.width / chart.scaling)
-                            - (8.0 * chart.cell_width / chart.scaling)
-                    }
-                };
-                chart.translation.x = x_translation;
+                chart.translation.x = latest_bar_x(kind, &chart);
 
                 let data_source = PlotData::TimeBased(timeseries);
+                let depth_history = DepthLayerHistory::for_kind(kind, &chart);
 
                 let mut indicators = EnumMap::default();
                 for &i in enabled_indicators {
@@ -260,15 +622,41 @@ impl KlineChart {
                 }
 
                 KlineChart {
+                    id: uuid::Uuid::new_v4(),
                     chart,
-                    data_source,
-                    raw_trades,
+                    series: Series::new(data_source, raw_trades, vec![]),
+                    seen_revision: 0,
                     indicators,
                     fetching_trades: (false, None),
                     request_handler: RequestHandler::new(),
                     kind: kind.clone(),
                     study_configurator: study::Configurator::new(),
                     last_tick: Instant::now(),
+                    compare: None,
+                    scale_padding,
+                    anchored_vwap: false,
+                    poc_history: false,
+                    session_separator: SessionSeparator::Off,
+                    visible_profile: false,
+                    candle_coloring: CandleColoring::PriceAction,
+                    candle_style: CandleStyle::Standard,
+                    candle_proportions: (0.8, 0.25),
+                    volume_display: VolumeDisplayMode::Absolute,
+                    flash_new_bar: false,
+                    new_bar_flash: None,
+                    minimap: None,
+                    minimap_cache: canvas::Cache::default(),
+                    compact_below: 0.0,
+                    compact: false,
+                    timeframe_cache: None,
+                    kline_studies: vec![],
+                    depth_imbalance: None,
+                    stochastic: None,
+                    depth_history,
+                    gap_repair: None,
+                    npoc_job: NpocJob::default(),
+                    gpu_cells: None,
+                    replaying: false,
                 }
             }
             Basis::Tick(interval) => {
@@ -276,12 +664,13 @@ impl KlineChart {
 
                 let cell_width = match kind {
                     KlineChartKind::Footprint { .. } => 80.0,
-                    KlineChartKind::Candles => 4.0,
+                    KlineChartKind::Candles { .. } => 4.0,
                 };
                 let cell_height = match kind {
                     KlineChartKind::Footprint { .. } => 90.0,
-                    KlineChartKind::Candles => 8.0,
+                    KlineChartKind::Candles { .. } => 8.0,
                 };
+                let scale_padding = data::chart::kline::Config::default().scale_padding;
 
                 let mut chart = ViewState::new(
                     basis,
@@ -291,24 +680,19 @@ impl KlineChart {
                     ViewConfig {
                         splits: layout.splits,
                         autoscale: Some(Autoscale::FitToVisible),
+                        auto_scroll: layout.auto_scroll,
+                        fixed_cells: layout.fixed_cells,
+                        price_axis: layout.price_axis,
+                        price_rounding: layout.price_rounding,
                     },
                     cell_width,
                     cell_height,
                 );
 
-                let x_translation = match &kind {
-                    KlineChartKind::Footprint { .. } => {
-                        0.5 * (chart.bounds.width / chart.scaling)
-                            - (chart.cell_width / chart.scaling)
-                    }
-                    KlineChartKind::Candles => {
-                        0.5 * (chart.bounds.width / chart.scaling)
-                            - (8.0 * chart.cell_width / chart.scaling)
-                    }
-                };
-                chart.translation.x = x_translation;
+                chart.translation.x = latest_bar_x(kind, &chart);
 
                 let data_source = PlotData::TickBased(TickAggr::new(interval, step, &raw_trades));
+                let depth_history = DepthLayerHistory::for_kind(kind, &chart);
 
                 let mut indicators = EnumMap::default();
                 for &i in enabled_indicators {
@@ -318,49 +702,261 @@ impl KlineChart {
                 }
 
                 KlineChart {
+                    id: uuid::Uuid::new_v4(),
                     chart,
-                    data_source,
-                    raw_trades,
+                    series: Series::new(data_source, raw_trades, vec![]),
+                    seen_revision: 0,
                     indicators,
                     fetching_trades: (false, None),
                     request_handler: RequestHandler::new(),
                     kind: kind.clone(),
                     study_configurator: study::Configurator::new(),
                     last_tick: Instant::now(),
+                    compare: None,
+                    scale_padding,
+                    anchored_vwap: false,
+                    poc_history: false,
+                    session_separator: SessionSeparator::Off,
+                    visible_profile: false,
+                    candle_coloring: CandleColoring::PriceAction,
+                    candle_style: CandleStyle::Standard,
+                    candle_proportions: (0.8, 0.25),
+                    volume_display: VolumeDisplayMode::Absolute,
+                    flash_new_bar: false,
+                    new_bar_flash: None,
+                    minimap: None,
+                    minimap_cache: canvas::Cache::default(),
+                    compact_below: 0.0,
+                    compact: false,
+                    timeframe_cache: None,
+                    kline_studies: vec![],
+                    depth_imbalance: None,
+                    stochastic: None,
+                    depth_history,
+                    gap_repair: None,
+                    npoc_job: NpocJob::default(),
+                    gpu_cells: None,
+                    replaying: false,
                 }
             }
         }
     }
 
-    pub fn update_latest_kline(&mut self, kline: &Kline) {
-        match self.data_source {
-            PlotData::TimeBased(ref mut timeseries) => {
-                timeseries.insert_klines(&[*kline]);
+    pub fn update_latest_kline(&mut self, ticker_info: &TickerInfo, kline: &Kline) {
+        if let Some(line) = self
+            .compare
+            .as_mut()
+            .and_then(|overlay| overlay.line_mut(ticker_info))
+        {
+            if let PlotData::TimeBased(timeseries) = &self.series.borrow().data_source {
+                line.series.insert_klines(&[*kline], timeseries.interval);
+            }
+            return;
+        }
+
+        if !self.writes_live() {
+            return;
+        }
 
-                self.indicators
-                    .values_mut()
-                    .filter_map(Option::as_mut)
-                    .for_each(|indi| indi.on_insert_klines(&[*kline]));
+        let mut series = self.series.borrow_mut();
+        let PlotData::TimeBased(timeseries) = &mut series.data_source else {
+            return;
+        };
 
-                let chart = self.mut_state();
+        let prev_latest = timeseries.latest_timestamp();
+        timeseries.update_kline(kline);
+        let opened_bar = prev_latest.is_some_and(|prev| timeseries.latest_timestamp() > Some(prev));
 
-                if (kline.time) > chart.latest_x {
-                    chart.latest_x = kline.time;
-                }
+        series.downsample_from(kline.time);
+        drop(series);
+        self.wrote_series();
+
+        // 新开了一根K线就闪一下，首根K线不算
+        if self.flash_new_bar && opened_bar {
+            self.new_bar_flash = Some(Instant::now());
+        }
+
+        self.indicators
+            .values_mut()
+            .filter_map(Option::as_mut)
+            .for_each(|indi| indi.on_insert_klines(&[*kline]));
+
+        let chart = self.mut_state();
+
+        if (kline.time) > chart.latest_x {
+            if chart.latest_x > 0 {
+                let shift_x = chart.interval_to_x(kline.time);
+                chart.hold_viewport(shift_x);
+            }
+            chart.latest_x = kline.time;
+        }
+
+        chart.last_price = Some(PriceInfoLabel::new(kline.close, kline.open));
+    }
+
+    /// Whether live events get written into the series by this chart, the first of those
+    /// sharing it to get one does until it's dropped or moves on to another series
+    fn writes_live(&self) -> bool {
+        let mut series = self.series.borrow_mut();
+        *series.writer.get_or_insert(self.id) == self.id
+    }
+
+    /// Counts a write into the series, which leaves this chart caught up if it was before
+    fn wrote_series(&mut self) {
+        let mut series = self.series.borrow_mut();
+        let caught_up = series.revision == self.seen_revision;
+
+        series.revision += 1;
+        if caught_up {
+            self.seen_revision = series.revision;
+        }
+    }
+
+    /// Follows the writes of the charts sharing the series since this one last looked
+    fn catch_up(&mut self) {
+        if self.series.borrow().revision != self.seen_revision {
+            self.follow_series();
+        }
+    }
+
+    /// Rebuilds the indicators from the series and moves the viewport along to its latest bar
+    fn follow_series(&mut self) {
+        let series = self.series.borrow();
+        self.seen_revision = series.revision;
+
+        for indi in self.indicators.values_mut().filter_map(Option::as_mut) {
+            indi.rebuild_from_source(&series.data_source);
+        }
+
+        let latest = match &series.data_source {
+            PlotData::TimeBased(timeseries) => timeseries
+                .datapoints
+                .last_key_value()
+                .map(|(time, dp)| (Some(*time), dp.kline)),
+            PlotData::TickBased(tick_aggr) => {
+                tick_aggr.datapoints.last().map(|dp| (None, dp.kline))
+            }
+        };
+        let Some((time, kline)) = latest else {
+            return;
+        };
 
-                chart.last_price = Some(PriceInfoLabel::new(kline.close, kline.open));
+        let chart = &mut self.chart;
+        if let Some(time) = time
+            && time > chart.latest_x
+        {
+            if chart.latest_x > 0 {
+                let shift_x = chart.interval_to_x(time);
+                chart.hold_viewport(shift_x);
             }
-            PlotData::TickBased(_) => {}
+            chart.latest_x = time;
+        }
+        chart.last_price = Some(PriceInfoLabel::new(kline.close, kline.open));
+    }
+
+    /// Leaves the series to the charts still sharing it, one of them writes the live events next
+    fn release_series(&self) {
+        let mut series = self.series.borrow_mut();
+        if series.writer == Some(self.id) {
+            series.writer = None;
         }
     }
 
+    /// Draws from the same series as `other`, keeping only its own view of it.
+    /// Only for charts of the same basis and tick size
+    pub fn share_series(&mut self, other: &KlineChart) {
+        if self.chart.basis != other.chart.basis || self.chart.tick_size != other.chart.tick_size {
+            return;
+        }
+
+        self.release_series();
+        self.series = Rc::clone(&other.series);
+        self.chart.latest_x = other.chart.latest_x;
+
+        self.follow_series();
+        self.invalidate(None);
+    }
+
     pub fn kind(&self) -> &KlineChartKind {
         &self.kind
     }
 
+    pub fn compare_overlays(&self) -> CompareOverlays {
+        self.compare
+            .as_ref()
+            .map(|overlay| overlay.overlays)
+            .unwrap_or_default()
+    }
+
+    pub fn compare_tickers(&self) -> &[TickerInfo] {
+        self.compare
+            .as_ref()
+            .map_or(&[], |overlay| overlay.tickers.as_slice())
+    }
+
+    pub fn set_compare_overlays(&mut self, overlays: CompareOverlays) {
+        if self.compare_overlays() == overlays {
+            return;
+        }
+
+        let previous = self
+            .compare
+            .take()
+            .map(|overlay| overlay.lines)
+            .unwrap_or_default();
+
+        if !overlays.is_empty() {
+            self.compare = Some(Box::new(CompareOverlay::new(overlays, previous)));
+        }
+        self.invalidate(None);
+    }
+
+    pub fn insert_compare_klines(
+        &mut self,
+        req_id: Option<uuid::Uuid>,
+        ticker_info: &TickerInfo,
+        klines_raw: &[Kline],
+    ) {
+        let interval = match &self.series.borrow().data_source {
+            PlotData::TimeBased(timeseries) => timeseries.interval,
+            PlotData::TickBased(_) => return,
+        };
+
+        let Some(line) = self
+            .compare
+            .as_mut()
+            .and_then(|overlay| overlay.line_mut(ticker_info))
+        else {
+            return;
+        };
+
+        line.series.insert_klines(klines_raw, interval);
+
+        if let Some(req_id) = req_id {
+            if klines_raw.is_empty() {
+                line.request_handler
+                    .mark_failed(req_id, "No data received".to_string());
+            } else {
+                line.request_handler.mark_completed(req_id);
+            }
+        }
+
+        self.invalidate(None);
+    }
+
     fn missing_data_task(&mut self) -> Option<Action> {
-        match &self.data_source {
+        if self.replaying {
+            return None;
+        }
+
+        let series = self.series.borrow();
+        match &series.data_source {
             PlotData::TimeBased(timeseries) => {
+                // second bars come from live trades only, there is nothing to fetch
+                if timeseries.interval.is_sub_minute() {
+                    return None;
+                }
+
                 let timeframe_ms = timeseries.interval.to_milliseconds();
 
                 if timeseries.datapoints.is_empty() {
@@ -386,9 +982,37 @@ impl KlineChart {
                     }
                 }
 
+                // priority 1.5, klines of the compared tickers
+                for line in self
+                    .compare
+                    .iter_mut()
+                    .flat_map(|overlay| overlay.lines.iter_mut())
+                {
+                    let stream = StreamKind::Kline {
+                        ticker_info: line.series.ticker_info,
+                        timeframe: timeseries.interval,
+                    };
+
+                    let range = match line.series.timerange() {
+                        None => Some(FetchRange::Kline(earliest, kline_latest)),
+                        Some((compare_earliest, _)) if visible_earliest < compare_earliest => {
+                            Some(FetchRange::Kline(earliest, compare_earliest))
+                        }
+                        Some(_) => None,
+                    };
+
+                    if let Some(range) = range
+                        && let Some(action) =
+                            request_fetch_for_stream(&mut line.request_handler, range, Some(stream))
+                    {
+                        return Some(action);
+                    }
+                }
+
                 // priority 2, trades fetch
                 if !self.fetching_trades.0
                     && exchange::fetcher::is_trade_fetch_enabled()
+                    && self.chart.ticker_info.exchange().supports_trade_fetch()
                     && let Some((fetch_from, fetch_to)) =
                         timeseries.suggest_trade_fetch_range(visible_earliest, visible_latest)
                 {
@@ -441,28 +1065,160 @@ impl KlineChart {
     pub fn reset_request_handler(&mut self) {
         self.request_handler = RequestHandler::new();
         self.fetching_trades = (false, None);
+        self.gap_repair = None;
     }
 
-    pub fn raw_trades(&self) -> Vec<Trade> {
-        self.raw_trades.clone()
-    }
+    /// Queues refetches for every gap between the earliest and latest loaded kline,
+    /// returns how many requests were queued
+    pub fn start_gap_repair(&mut self) -> usize {
+        let series = self.series.borrow();
+        let PlotData::TimeBased(timeseries) = &series.data_source else {
+            return 0;
+        };
+        if self.gap_repair.is_some() {
+            return 0;
+        }
 
-    pub fn set_handle(&mut self, handle: Handle) {
-        self.fetching_trades.1 = Some(handle);
+        let interval = timeseries.interval.to_milliseconds();
+        let (earliest, latest) = timeseries.timerange();
+
+        let chunk = GAP_REPAIR_CHUNK_BARS * interval;
+        let mut queued = VecDeque::new();
+
+        for (first, last) in timeseries.gap_ranges(earliest, latest, interval) {
+            let mut start = first;
+            while start <= last {
+                let end = (start + chunk - interval).min(last);
+                // one kline of padding on both sides, like the integrity fetch
+                queued.push_back(FetchRange::Kline(start - interval, end + interval));
+                start = end + interval;
+            }
+        }
+
+        let total = queued.len();
+        if total > 0 {
+            self.gap_repair = Some(Box::new(GapRepair {
+                span: (earliest, latest),
+                queued,
+                in_flight: vec![],
+                total,
+            }));
+        }
+        total
     }
 
-    pub fn tick_size(&self) -> f32 {
-        self.chart.tick_size.to_f32_lossy()
+    /// Requests settled and total requests of the running gap repair
+    pub fn gap_repair_progress(&self) -> Option<(usize, usize)> {
+        self.gap_repair
+            .as_ref()
+            .map(|repair| (repair.done(), repair.total))
     }
 
-    pub fn study_configurator(&self) -> &study::Configurator<FootprintStudy> {
-        &self.study_configurator
+    pub fn abort_gap_repair(&mut self) -> Option<(usize, usize)> {
+        let progress = self.gap_repair_progress();
+        self.gap_repair = None;
+        progress
     }
 
-    pub fn update_study_configurator(&mut self, message: study::Message<FootprintStudy>) {
-        let KlineChartKind::Footprint {
-            ref mut studies, ..
-        } = self.kind
+    /// Issues queued repair requests up to the concurrency limit.
+    ///
+    /// Ranges a pending request already covers are dropped instead of fetched twice.
+    pub fn gap_repair_task(&mut self) -> Option<Action> {
+        let repair = self.gap_repair.as_mut()?;
+        let mut specs = vec![];
+
+        while repair.in_flight.len() < GAP_REPAIR_CONCURRENCY
+            && let Some(range) = repair.queued.pop_front()
+        {
+            if self.request_handler.is_pending_within(&range) {
+                continue;
+            }
+
+            match self.request_handler.add_request(range) {
+                Ok(Some(req_id)) => {
+                    repair.in_flight.push(req_id);
+                    specs.push(FetchSpec {
+                        req_id,
+                        fetch: range,
+                        stream: None,
+                    });
+                }
+                Ok(None) => {}
+                Err(reason) => log::warn!("Skipping gap repair of {:?}: {}", range, reason),
+            }
+        }
+
+        if specs.is_empty() {
+            None
+        } else {
+            Some(Action::RequestFetch(FetchRequests::from_iter(specs)))
+        }
+    }
+
+    /// Ends the repair once nothing is queued or in flight and checks the span again
+    pub fn take_gap_repair_outcome(&mut self) -> Option<GapRepairOutcome> {
+        let repair = self.gap_repair.as_ref()?;
+        if !repair.queued.is_empty() || !repair.in_flight.is_empty() {
+            return None;
+        }
+        let repair = self.gap_repair.take()?;
+
+        let series = self.series.borrow();
+        let PlotData::TimeBased(timeseries) = &series.data_source else {
+            return None;
+        };
+        let (earliest, latest) = repair.span;
+
+        match timeseries.check_kline_integrity(
+            earliest,
+            latest,
+            timeseries.interval.to_milliseconds(),
+        ) {
+            Some(missing) => Some(GapRepairOutcome::GapsRemain {
+                missing: missing.len(),
+            }),
+            None => Some(GapRepairOutcome::Repaired {
+                requests: repair.total,
+            }),
+        }
+    }
+
+    pub fn raw_trades(&self) -> Vec<Trade> {
+        self.series.borrow().raw_trades.clone()
+    }
+
+    /// Every loaded bar as CSV, see `data::chart::klines_csv`
+    pub fn klines_csv(&self) -> String {
+        data::chart::klines_csv(
+            &self.series.borrow().data_source.klines(),
+            self.chart.ticker_info.min_ticksize,
+        )
+    }
+
+    pub fn set_handle(&mut self, handle: Handle) {
+        self.fetching_trades.1 = Some(handle);
+    }
+
+    pub fn tick_size(&self) -> f32 {
+        self.chart.tick_size.to_f32_lossy()
+    }
+
+    /// Close of the latest bar, `None` until data arrives
+    pub fn latest_price(&self) -> Option<Price> {
+        match &self.series.borrow().data_source {
+            PlotData::TimeBased(timeseries) => timeseries.latest_kline().map(|kline| kline.close),
+            PlotData::TickBased(tick_aggr) => tick_aggr.latest_dp().map(|(dp, _)| dp.kline.close),
+        }
+    }
+
+    pub fn study_configurator(&self) -> &study::Configurator<FootprintStudy> {
+        &self.study_configurator
+    }
+
+    pub fn update_study_configurator(&mut self, message: study::Message<FootprintStudy>) {
+        let KlineChartKind::Footprint {
+            ref mut studies, ..
+        } = self.kind
         else {
             return;
         };
@@ -493,6 +1249,44 @@ impl KlineChart {
         self.chart.layout()
     }
 
+    pub fn price_axis_mode(&self) -> PriceAxisMode {
+        self.chart.layout.price_axis
+    }
+
+    /// Latest naked POC snapshot to compute in the background, `None` while one is running
+    pub fn take_npoc_job(&mut self) -> Option<Box<NpocSnapshot>> {
+        if self.npoc_job.in_flight {
+            return None;
+        }
+
+        let snapshot = self.npoc_job.pending.take()?;
+        self.npoc_job.in_flight = true;
+        Some(snapshot)
+    }
+
+    /// Writes back the naked POCs computed off the UI thread
+    pub fn apply_npoc_statuses(&mut self, statuses: &[(u64, Price, NPoc)]) {
+        self.npoc_job.in_flight = false;
+
+        if let PlotData::TimeBased(timeseries) = &mut self.series.borrow_mut().data_source {
+            timeseries.apply_poc_status(statuses);
+            self.chart.cache.clear_all();
+        }
+    }
+
+    pub fn price_rounding(&self) -> PriceAxisRounding {
+        self.chart.layout.price_rounding
+    }
+
+    /// Axis rounding steps that suit the ticker's tick size and current price
+    pub fn price_rounding_choices(&self) -> Vec<PriceAxisRounding> {
+        let price = self
+            .latest_price()
+            .unwrap_or(self.chart.base_price_y)
+            .to_f32_lossy();
+        PriceAxisRounding::choices(self.tick_size(), price)
+    }
+
     pub fn set_cluster_kind(&mut self, new_kind: ClusterKind) {
         if let KlineChartKind::Footprint {
             ref mut clusters, ..
@@ -515,598 +1309,2811 @@ impl KlineChart {
         self.invalidate(None);
     }
 
-    pub fn basis(&self) -> Basis {
-        self.chart.basis
+    pub fn set_cell_text(&mut self, new_cell_text: FootprintCellText) {
+        if let KlineChartKind::Footprint {
+            ref mut cell_text, ..
+        } = self.kind
+        {
+            *cell_text = new_cell_text;
+        }
+
+        self.invalidate(None);
     }
 
-    pub fn change_tick_size(&mut self, new_tick_size: f32) {
-        let chart = self.mut_state();
+    pub fn set_show_volume_profile(&mut self, enabled: bool) {
+        if let KlineChartKind::Candles {
+            ref mut show_volume_profile,
+        } = self.kind
+        {
+            *show_volume_profile = enabled;
+        }
 
-        let step = PriceStep::from_f32(new_tick_size);
+        self.invalidate(None);
+    }
 
-        chart.cell_height *= new_tick_size / chart.tick_size.to_f32_lossy();
-        chart.tick_size = step;
+    /// Applies the pane's visual settings that live on the chart, for charts being rebuilt
+    /// or restored. Compared tickers aren't part of it, they need their streams set up too
+    pub fn apply_config(&mut self, cfg: &data::chart::kline::Config) {
+        self.set_scale_padding(cfg.scale_padding);
+        self.set_anchored_vwap(cfg.anchored_vwap);
+        self.set_poc_history(cfg.poc_history);
+        self.set_session_separator(cfg.session_separator);
+        self.set_visible_profile(cfg.visible_profile);
+        self.set_candle_coloring(cfg.candle_coloring);
+        self.set_candle_style(cfg.candle_style);
+        self.set_candle_proportions(cfg.candle_body_width, cfg.candle_wick_width);
+        self.set_volume_display(cfg.volume_display);
+        self.set_cell_renderer(cfg.cell_renderer);
+        self.set_flash_new_bar(cfg.flash_new_bar);
+        self.set_minimap_height(cfg.minimap.then_some(cfg.minimap_height));
+        self.set_compact_below(cfg.compact_below);
+    }
 
-        match self.data_source {
-            PlotData::TickBased(ref mut tick_aggr) => {
-                tick_aggr.change_tick_size(new_tick_size, &self.raw_trades);
-            }
-            PlotData::TimeBased(ref mut timeseries) => {
-                timeseries.change_tick_size(new_tick_size, &self.raw_trades);
-            }
+    pub fn set_scale_padding(&mut self, padding: f32) {
+        self.scale_padding = padding;
+        self.invalidate(None);
+    }
+
+    pub fn set_anchored_vwap(&mut self, enabled: bool) {
+        self.anchored_vwap = enabled;
+        self.invalidate(None);
+    }
+
+    pub fn poc_history(&self) -> bool {
+        self.poc_history
+    }
+
+    pub fn set_poc_history(&mut self, enabled: bool) {
+        self.poc_history = enabled;
+        self.invalidate(None);
+    }
+
+    pub fn set_session_separator(&mut self, session: SessionSeparator) {
+        self.session_separator = session;
+        self.invalidate(None);
+    }
+
+    pub fn set_visible_profile(&mut self, enabled: bool) {
+        self.visible_profile = enabled;
+        self.invalidate(None);
+    }
+
+    pub fn set_flash_new_bar(&mut self, enabled: bool) {
+        self.flash_new_bar = enabled;
+        if !enabled {
+            self.new_bar_flash = None;
         }
+    }
 
-        self.indicators
-            .values_mut()
-            .filter_map(Option::as_mut)
-            .for_each(|indi| indi.on_ticksize_change(&self.data_source));
+    /// Height of the overview strip, `None` while it's hidden
+    pub fn set_minimap_height(&mut self, height: Option<f32>) {
+        self.minimap = height;
+        self.minimap_cache.clear();
+    }
 
+    pub fn set_compact_below(&mut self, size: f32) {
+        self.compact_below = size;
         self.invalidate(None);
     }
 
-    pub fn set_basis(&mut self, new_basis: Basis) -> Option<Action> {
-        self.chart.last_price = None;
-        self.chart.basis = new_basis;
+    /// Footprints in a pane smaller than `compact_below` either way are drawn as plain candles.
+    /// The canvas takes over the room of the axis labels once they're hidden, so they're added
+    /// back while shown to judge by the same pane size both ways.
+    fn update_compact(&mut self) {
+        let chart = &self.chart;
+        if !matches!(self.kind, KlineChartKind::Footprint { .. }) || chart.bounds.width == 0.0 {
+            self.compact = false;
+            return;
+        }
 
-        match new_basis {
-            Basis::Time(interval) => {
-                let step = self.chart.tick_size;
-                let timeseries = TimeSeries::<KlineDataPoint>::new(interval, step, &[]);
-                self.data_source = PlotData::TimeBased(timeseries);
-            }
-            Basis::Tick(tick_count) => {
-                let step = self.chart.tick_size;
-                let tick_aggr = TickAggr::new(tick_count, step, &self.raw_trades);
-                self.data_source = PlotData::TickBased(tick_aggr);
+        let (mut width, mut height) = (chart.bounds.width, chart.bounds.height);
+        if !self.compact {
+            if let iced::Length::Fixed(labels_width) = chart.y_labels_width() {
+                width += labels_width;
             }
+            height += X_AXIS_HEIGHT;
         }
 
-        self.indicators
-            .values_mut()
-            .filter_map(Option::as_mut)
-            .for_each(|indi| indi.on_basis_change(&self.data_source));
-
-        self.reset_request_handler();
-        self.invalidate(Some(Instant::now()))
+        self.compact = width.min(height) < self.compact_below;
     }
 
-    pub fn studies(&self) -> Option<Vec<FootprintStudy>> {
-        match &self.kind {
-            KlineChartKind::Footprint { studies, .. } => Some(studies.clone()),
-            _ => None,
+    /// What gets drawn, footprints turn into plain candles in compact panes
+    fn drawn_kind(&self) -> &KlineChartKind {
+        if self.compact {
+            &COMPACT_KIND
+        } else {
+            &self.kind
         }
     }
 
-    pub fn set_studies(&mut self, new_studies: Vec<FootprintStudy>) {
-        if let KlineChartKind::Footprint {
-            ref mut studies, ..
-        } = self.kind
-        {
-            *studies = new_studies;
+    /// Redraws the fading highlight of a newly opened bar. Driven by the tick rather than by
+    /// incoming klines, so it fades at the same pace on quiet and busy tickers.
+    pub fn fade_new_bar_flash(&mut self, now: Instant) {
+        let Some(opened) = self.new_bar_flash else {
+            return;
+        };
+
+        if now.saturating_duration_since(opened) >= NEW_BAR_FLASH {
+            self.new_bar_flash = None;
         }
+        self.chart.cache.crosshair.clear();
+    }
 
+    pub fn set_candle_coloring(&mut self, coloring: CandleColoring) {
+        self.candle_coloring = coloring;
         self.invalidate(None);
     }
 
-    pub fn insert_trades_buffer(&mut self, trades_buffer: &[Trade]) {
-        self.raw_trades.extend_from_slice(trades_buffer);
-
-        match self.data_source {
-            PlotData::TickBased(ref mut tick_aggr) => {
-                let old_dp_len = tick_aggr.datapoints.len();
-                tick_aggr.insert_trades(trades_buffer);
-
-                if let Some(last_dp) = tick_aggr.datapoints.last() {
-                    self.chart.last_price =
-                        Some(PriceInfoLabel::new(last_dp.kline.close, last_dp.kline.open));
-                } else {
-                    self.chart.last_price = None;
-                }
+    pub fn set_candle_style(&mut self, style: CandleStyle) {
+        self.candle_style = style;
+        self.invalidate(None);
+    }
 
-                self.indicators
-                    .values_mut()
-                    .filter_map(Option::as_mut)
-                    .for_each(|indi| {
-                        indi.on_insert_trades(trades_buffer, old_dp_len, &self.data_source)
-                    });
+    pub fn cell_renderer(&self) -> CellRenderer {
+        if self.gpu_cells.is_some() {
+            CellRenderer::Gpu
+        } else {
+            CellRenderer::Canvas
+        }
+    }
 
-                self.invalidate(None);
-            }
-            PlotData::TimeBased(ref mut timeseries) => {
-                timeseries.insert_trades_existing_buckets(trades_buffer);
-            }
+    pub fn set_cell_renderer(&mut self, renderer: CellRenderer) {
+        if renderer == self.cell_renderer() {
+            return;
         }
+        self.gpu_cells = match renderer {
+            CellRenderer::Canvas => None,
+            CellRenderer::Gpu => Some(SharedCells::default()),
+        };
+        self.invalidate(None);
     }
 
-    pub fn insert_raw_trades(&mut self, raw_trades: Vec<Trade>, is_batches_done: bool) {
-        match self.data_source {
-            PlotData::TickBased(ref mut tick_aggr) => {
-                tick_aggr.insert_trades(&raw_trades);
-            }
-            PlotData::TimeBased(ref mut timeseries) => {
-                timeseries.insert_trades_existing_buckets(&raw_trades);
-            }
+    pub fn set_volume_display(&mut self, mode: VolumeDisplayMode) {
+        self.volume_display = mode;
+        let series = self.series.borrow();
+        for indi in self.indicators.values_mut().filter_map(Option::as_mut) {
+            indi.set_volume_display(mode, &series.data_source);
         }
+    }
 
-        self.raw_trades.extend(raw_trades);
+    pub fn set_candle_proportions(&mut self, body_width: f32, wick_width: f32) {
+        self.candle_proportions = (body_width, wick_width);
+        self.invalidate(None);
+    }
 
-        if is_batches_done {
-            self.fetching_trades = (false, None);
+    pub fn set_kline_studies(&mut self, studies: &[KlineStudy]) {
+        if self.kline_studies != studies {
+            self.kline_studies = studies.to_vec();
+            self.sync_study_panels();
+            self.invalidate(None);
         }
     }
 
-    pub fn insert_hist_klines(&mut self, req_id: uuid::Uuid, klines_raw: &[Kline]) {
-        match self.data_source {
-            PlotData::TimeBased(ref mut timeseries) => {
-                timeseries.insert_klines(klines_raw);
-                timeseries.insert_trades_existing_buckets(&self.raw_trades);
+    /// Creates, reconfigures or drops the panels of the studies plotted below the chart
+    fn sync_study_panels(&mut self) {
+        let prev_panel_count = self.panel_count();
 
-                self.indicators
-                    .values_mut()
-                    .filter_map(Option::as_mut)
-                    .for_each(|indi| indi.on_insert_klines(klines_raw));
+        let params = self.kline_studies.iter().find_map(|study| match *study {
+            KlineStudy::DepthImbalance {
+                window_size,
+                lookback_levels,
+            } => Some((window_size, lookback_levels)),
+            _ => None,
+        });
 
-                if klines_raw.is_empty() {
-                    self.request_handler
-                        .mark_failed(req_id, "No data received".to_string());
-                } else {
-                    self.request_handler.mark_completed(req_id);
+        match params {
+            Some((window_size, levels)) => {
+                if !self
+                    .depth_imbalance
+                    .as_ref()
+                    .is_some_and(|panel| panel.is_configured(window_size, levels))
+                {
+                    self.depth_imbalance =
+                        Some(Box::new(DepthImbalancePanel::new(window_size, levels)));
                 }
-                self.invalidate(None);
             }
-            PlotData::TickBased(_) => {}
+            None => self.depth_imbalance = None,
         }
-    }
 
-    pub fn insert_open_interest(&mut self, req_id: Option<uuid::Uuid>, oi_data: &[OIData]) {
-        if let Some(req_id) = req_id {
-            if oi_data.is_empty() {
-                self.request_handler
-                    .mark_failed(req_id, "No data received".to_string());
-            } else {
-                self.request_handler.mark_completed(req_id);
+        let params = self.kline_studies.iter().find_map(|study| match *study {
+            KlineStudy::Stochastic {
+                k_period,
+                d_period,
+                smooth,
+            } => Some((k_period, d_period, smooth)),
+            _ => None,
+        });
+
+        match params {
+            Some((k_period, d_period, smooth)) => {
+                if !self
+                    .stochastic
+                    .as_ref()
+                    .is_some_and(|panel| panel.is_configured(k_period, d_period, smooth))
+                {
+                    let mut panel = StochasticPanel::new(k_period, d_period, smooth);
+                    panel.rebuild(&self.series.borrow().data_source);
+                    self.stochastic = Some(Box::new(panel));
+                }
             }
+            None => self.stochastic = None,
         }
 
-        if let Some(indi) = self.indicators[KlineIndicator::OpenInterest].as_mut() {
-            indi.on_open_interest(oi_data);
-        }
+        self.resize_panels(prev_panel_count);
     }
 
-    fn calc_qty_scales(
-        &self,
-        earliest: u64,
-        latest: u64,
-        highest: Price,
-        lowest: Price,
-        step: PriceStep,
-        cluster_kind: ClusterKind,
-    ) -> f32 {
-        let rounded_highest = highest.round_to_side_step(false, step).add_steps(1, step);
-
-        let rounded_lowest = lowest.round_to_side_step(true, step).add_steps(-1, step);
+    /// Time based candles only subscribe to klines, every other kline chart gets depth
+    fn receives_depth(&self) -> bool {
+        matches!(self.kind, KlineChartKind::Footprint { .. })
+            || matches!(self.series.borrow().data_source, PlotData::TickBased(_))
+    }
 
-        match &self.data_source {
-            PlotData::TimeBased(timeseries) => timeseries.max_qty_ts_range(
-                cluster_kind,
-                earliest,
-                latest,
-                rounded_highest,
-                rounded_lowest,
-            ),
-            PlotData::TickBased(tick_aggr) => {
-                let earliest = earliest as usize;
-                let latest = latest as usize;
+    /// Feeds the depth imbalance study and the depth layer, trades arrive separately
+    /// through `insert_trades_buffer`
+    pub fn insert_depth(&mut self, depth_update_t: u64, depth: &Depth) {
+        if self.depth_imbalance.is_none() && self.depth_history.is_none() {
+            return;
+        }
 
-                tick_aggr.max_qty_idx_range(
-                    cluster_kind,
-                    earliest,
-                    latest,
-                    rounded_highest,
-                    rounded_lowest,
+        let (bar, kept_span) = match &self.series.borrow().data_source {
+            PlotData::TimeBased(timeseries) => {
+                let interval = timeseries.interval.to_milliseconds();
+                (
+                    (depth_update_t / interval) * interval,
+                    DEPTH_LAYER_BARS * interval,
                 )
             }
+            PlotData::TickBased(tick_aggr) => match tick_aggr.datapoints.len() {
+                0 => return,
+                len => (len as u64 - 1, DEPTH_LAYER_BARS),
+            },
+        };
+
+        if let Some(panel) = self.depth_imbalance.as_mut() {
+            panel.insert(bar, depth);
+        }
+        if let Some(history) = self.depth_history.as_mut() {
+            history.insert(bar, depth, bar.saturating_sub(kept_span));
         }
     }
 
-    pub fn last_update(&self) -> Instant {
-        self.last_tick
-    }
+    pub fn set_depth_layer(&mut self, layer: DepthLayer) {
+        if let KlineChartKind::Footprint {
+            ref mut depth_layer,
+            ..
+        } = self.kind
+        {
+            *depth_layer = Some(layer);
+        }
 
-    pub fn invalidate(&mut self, now: Option<Instant>) -> Option<Action> {
-        let chart = &mut self.chart;
+        if self.depth_history.is_none() {
+            self.depth_history = DepthLayerHistory::for_kind(&self.kind, &self.chart);
+        }
 
-        if let Some(autoscale) = chart.layout.autoscale {
-            match autoscale {
-                super::Autoscale::CenterLatest => {
-                    let x_translation = match &self.kind {
-                        KlineChartKind::Footprint { .. } => {
-                            0.5 * (chart.bounds.width / chart.scaling)
-                                - (chart.cell_width / chart.scaling)
-                        }
-                        KlineChartKind::Candles => {
-                            0.5 * (chart.bounds.width / chart.scaling)
-                                - (8.0 * chart.cell_width / chart.scaling)
-                        }
-                    };
-                    chart.translation.x = x_translation;
+        self.invalidate(None);
+    }
 
-                    let calculate_target_y = |kline: exchange::Kline| -> f32 {
-                        let y_low = chart.price_to_y(kline.low);
-                        let y_high = chart.price_to_y(kline.high);
-                        let y_close = chart.price_to_y(kline.close);
+    pub fn basis(&self) -> Basis {
+        self.chart.basis
+    }
 
-                        let mut target_y_translation = -(y_low + y_high) / 2.0;
+    pub fn ticker_info(&self) -> TickerInfo {
+        self.chart.ticker_info
+    }
 
-                        if chart.bounds.height > f32::EPSILON && chart.scaling > f32::EPSILON {
-                            let visible_half_height = (chart.bounds.height / chart.scaling) / 2.0;
+    /// The last `count` candles, oldest first
+    pub fn recent_klines(&self, count: usize) -> Vec<Kline> {
+        let mut klines: Vec<Kline> = match &self.series.borrow().data_source {
+            PlotData::TimeBased(timeseries) => timeseries
+                .datapoints
+                .values()
+                .rev()
+                .take(count)
+                .map(|dp| dp.kline)
+                .collect(),
+            PlotData::TickBased(tick_aggr) => tick_aggr
+                .datapoints
+                .iter()
+                .rev()
+                .take(count)
+                .map(|dp| dp.kline)
+                .collect(),
+        };
+        klines.reverse();
+        klines
+    }
 
-                            let view_center_y_centered = -target_y_translation;
+    pub fn change_tick_size(&mut self, new_tick_size: f32) {
+        let chart = self.mut_state();
 
-                            let visible_y_top = view_center_y_centered - visible_half_height;
-                            let visible_y_bottom = view_center_y_centered + visible_half_height;
+        let step = PriceStep::from_f32(new_tick_size);
 
-                            let padding = chart.cell_height;
+        chart.cell_height *= new_tick_size / chart.tick_size.to_f32_lossy();
+        chart.tick_size = step;
 
-                            if y_close < visible_y_top {
-                                target_y_translation = -(y_close - padding + visible_half_height);
-                            } else if y_close > visible_y_bottom {
-                                target_y_translation = -(y_close + padding - visible_half_height);
-                            }
-                        }
-                        target_y_translation
-                    };
+        self.unshare_series();
+        {
+            let mut series = self.series.borrow_mut();
+            let series = &mut *series;
 
-                    chart.translation.y = self.data_source.latest_y_midpoint(calculate_target_y);
+            match &mut series.data_source {
+                PlotData::TickBased(tick_aggr) => {
+                    tick_aggr.change_tick_size(new_tick_size, &series.raw_trades);
+                    tick_aggr.insert_liquidations(&series.raw_liquidations);
                 }
-                super::Autoscale::FitToVisible => {
-                    let visible_region = chart.visible_region(chart.bounds.size());
-                    let (start_interval, end_interval) = chart.interval_range(&visible_region);
-
-                    if let Some((lowest, highest)) = self
-                        .data_source
-                        .visible_price_range(start_interval, end_interval)
-                    {
-                        let padding = (highest - lowest) * 0.05;
-                        let price_span = (highest - lowest) + (2.0 * padding);
-
-                        if price_span > 0.0 && chart.bounds.height > f32::EPSILON {
-                            let padded_highest = highest + padding;
-                            let chart_height = chart.bounds.height;
-                            let tick_size = chart.tick_size.to_f32_lossy();
-
-                            if tick_size > 0.0 {
-                                chart.cell_height = (chart_height * tick_size) / price_span;
-                                chart.base_price_y = Price::from_f32(padded_highest);
-                                chart.translation.y = -chart_height / 2.0;
-                            }
-                        }
-                    }
+                PlotData::TimeBased(timeseries) => {
+                    timeseries.change_tick_size(new_tick_size, &series.raw_trades);
+                    timeseries.insert_liquidations(&series.raw_liquidations);
                 }
             }
+            series.downsample_from(0);
         }
 
-        chart.cache.clear_all();
-        for indi in self.indicators.values_mut().filter_map(Option::as_mut) {
-            indi.clear_all_caches();
+        if self.depth_history.is_some() {
+            self.depth_history = DepthLayerHistory::for_kind(&self.kind, &self.chart);
         }
 
-        if let Some(t) = now {
-            self.last_tick = t;
-            self.missing_data_task()
-        } else {
-            None
+        let series = self.series.borrow();
+        self.indicators
+            .values_mut()
+            .filter_map(Option::as_mut)
+            .for_each(|indi| indi.on_ticksize_change(&series.data_source));
+        drop(series);
+
+        self.invalidate(None);
+    }
+
+    /// Copies the shared series for this chart alone, before changing it in a way
+    /// the other windows showing it don't follow
+    fn unshare_series(&mut self) {
+        if Rc::strong_count(&self.series) == 1 {
+            return;
         }
+
+        let series = self.series.borrow();
+        let data_source = match &series.data_source {
+            PlotData::TimeBased(timeseries) => PlotData::TimeBased(timeseries.with_trades(&[])),
+            PlotData::TickBased(tick_aggr) => {
+                let mut copy =
+                    TickAggr::new(tick_aggr.interval, tick_aggr.tick_size, &series.raw_trades);
+                copy.insert_liquidations(&series.raw_liquidations);
+                PlotData::TickBased(copy)
+            }
+        };
+        drop(series);
+
+        self.detach_series(data_source);
     }
 
-    pub fn toggle_indicator(&mut self, indicator: KlineIndicator) {
-        let prev_indi_count = self.indicators.values().filter(|v| v.is_some()).count();
+    /// Moves on to a series of its own drawn from `data_source`, which keeps the raw trades
+    /// and liquidations of the one left
+    fn detach_series(&mut self, data_source: PlotData<KlineDataPoint>) {
+        self.release_series();
+
+        let (raw_trades, raw_liquidations) = match Rc::get_mut(&mut self.series) {
+            Some(series) => {
+                let series = series.get_mut();
+                (
+                    std::mem::take(&mut series.raw_trades),
+                    std::mem::take(&mut series.raw_liquidations),
+                )
+            }
+            None => {
+                let series = self.series.borrow();
+                (series.raw_trades.clone(), series.raw_liquidations.clone())
+            }
+        };
 
-        if self.indicators[indicator].is_some() {
-            self.indicators[indicator] = None;
-        } else {
-            let mut box_indi = indicator::kline::make_empty(indicator);
-            box_indi.rebuild_from_source(&self.data_source);
-            self.indicators[indicator] = Some(box_indi);
+        self.series = Series::new(data_source, raw_trades, raw_liquidations);
+        self.seen_revision = 0;
+    }
+
+    pub fn set_basis(&mut self, new_basis: Basis) -> Option<Action> {
+        let step = self.chart.tick_size;
+        let data_source = match new_basis {
+            Basis::Time(interval) => {
+                PlotData::TimeBased(TimeSeries::<KlineDataPoint>::new(interval, step, &[]))
+            }
+            Basis::Tick(tick_count) => {
+                let series = self.series.borrow();
+                let mut tick_aggr = TickAggr::new(tick_count, step, &series.raw_trades);
+                tick_aggr.insert_liquidations(&series.raw_liquidations);
+                PlotData::TickBased(tick_aggr)
+            }
+        };
+
+        self.replace_series(new_basis, data_source)
+    }
+
+    /// Shows `timeframe` from its cached series when there's one, fetching it otherwise.
+    /// The series switched away from gets cached while the pane has timeframe tabs.
+    pub fn switch_timeframe(&mut self, timeframe: Timeframe) -> Option<Action> {
+        let (Basis::Time(shown), Some(mut cache)) = (self.chart.basis, self.timeframe_cache.take())
+        else {
+            return self.set_basis(Basis::Time(timeframe));
+        };
+        if shown == timeframe {
+            self.timeframe_cache = Some(cache);
+            return None;
         }
 
-        if let Some(main_split) = self.chart.layout.splits.first() {
-            let current_indi_count = self.indicators.values().filter(|v| v.is_some()).count();
-            self.chart.layout.splits = data::util::calc_panel_splits(
-                *main_split,
-                current_indi_count,
-                Some(prev_indi_count),
-            );
+        // taken out before storing the shown one, which could otherwise evict it
+        let cached = cache.take(timeframe);
+        let empty = PlotData::TimeBased(TimeSeries::<KlineDataPoint>::new(
+            timeframe,
+            self.chart.tick_size,
+            &[],
+        ));
+        let previous = match Rc::get_mut(&mut self.series) {
+            Some(series) => std::mem::replace(&mut series.get_mut().data_source, empty),
+            // the windows sharing it keep showing the series
+            None => match &self.series.borrow().data_source {
+                PlotData::TimeBased(timeseries) => PlotData::TimeBased(timeseries.with_trades(&[])),
+                PlotData::TickBased(_) => empty,
+            },
+        };
+        if let PlotData::TimeBased(previous) = previous {
+            cache.store(shown, previous);
         }
+        self.timeframe_cache = Some(cache);
+
+        let Some(timeseries) = cached else {
+            return self.set_basis(Basis::Time(timeframe));
+        };
+
+        let last_price = timeseries
+            .datapoints
+            .last_key_value()
+            .map(|(_, dp)| PriceInfoLabel::new(dp.kline.close, dp.kline.open));
+        self.chart.latest_x = timeseries.latest_timestamp().unwrap_or(0);
+
+        let action = self.replace_series(Basis::Time(timeframe), PlotData::TimeBased(timeseries));
+        self.chart.last_price = last_price;
+        action
     }
-}
 
-impl canvas::Program<Message> for KlineChart {
-    type State = Interaction;
+    /// Keeps the series of the timeframes switched away from while at least two tabs are set,
+    /// dropping those whose tab got removed
+    pub fn set_timeframe_tabs(&mut self, tabs: &[Timeframe]) {
+        if tabs.len() < 2 || !matches!(self.kind, KlineChartKind::Candles { .. }) {
+            self.timeframe_cache = None;
+            return;
+        }
 
-    fn update(
-        &self,
-        interaction: &mut Interaction,
-        event: &Event,
-        bounds: Rectangle,
-        cursor: mouse::Cursor,
-    ) -> Option<canvas::Action<Message>> {
-        super::canvas_interaction(self, interaction, event, bounds, cursor)
+        self.timeframe_cache.get_or_insert_default().retain(tabs);
     }
 
-    fn draw(
-        &self,
-        interaction: &Interaction,
-        renderer: &Renderer,
-        theme: &Theme,
-        bounds: Rectangle,
-        cursor: mouse::Cursor,
-    ) -> Vec<Geometry> {
-        let chart = self.state();
+    /// Timeframes whose series are cached besides the shown one, each kept up by its stream
+    pub fn cached_timeframes(&self) -> Vec<Timeframe> {
+        self.timeframe_cache
+            .as_ref()
+            .map(|cache| cache.timeframes().collect())
+            .unwrap_or_default()
+    }
 
-        if chart.bounds.width == 0.0 {
-            return vec![];
+    /// Feeds a kline of a cached timeframe, `false` if it isn't cached
+    pub fn update_cached_kline(&mut self, timeframe: Timeframe, kline: &Kline) -> bool {
+        match self
+            .timeframe_cache
+            .as_mut()
+            .and_then(|cache| cache.get_mut(timeframe))
+        {
+            Some(timeseries) => {
+                timeseries.update_kline(kline);
+                true
+            }
+            None => false,
         }
+    }
 
-        let bounds_size = bounds.size();
-        let palette = theme.extended_palette();
+    fn replace_series(
+        &mut self,
+        basis: Basis,
+        data_source: PlotData<KlineDataPoint>,
+    ) -> Option<Action> {
+        self.chart.last_price = None;
+        self.chart.basis = basis;
 
-        let klines = chart.cache.main.draw(renderer, bounds_size, |frame| {
-            let center = Vector::new(bounds.width / 2.0, bounds.height / 2.0);
+        if let Some(panel) = self.depth_imbalance.as_mut() {
+            panel.reset();
+        }
+        if self.depth_history.is_some() {
+            self.depth_history = DepthLayerHistory::for_kind(&self.kind, &self.chart);
+        }
 
-            frame.translate(center);
-            frame.scale(chart.scaling);
-            frame.translate(chart.translation);
+        self.detach_series(data_source);
 
-            let region = chart.visible_region(frame.size());
-            let (earliest, latest) = chart.interval_range(&region);
+        let series = self.series.borrow();
+        self.indicators
+            .values_mut()
+            .filter_map(Option::as_mut)
+            .for_each(|indi| indi.on_basis_change(&series.data_source));
+        drop(series);
 
-            let price_to_y = |price| chart.price_to_y(price);
-            let interval_to_x = |interval| chart.interval_to_x(interval);
+        if let Some(overlay) = self.compare.as_mut() {
+            **overlay = CompareOverlay::new(overlay.overlays, vec![]);
+        }
 
-            match &self.kind {
-                KlineChartKind::Footprint {
-                    clusters,
-                    scaling,
-                    studies,
-                } => {
-                    let (highest, lowest) = chart.price_range(&region);
+        self.reset_request_handler();
+        self.invalidate(Some(Instant::now()))
+    }
 
-                    let max_cluster_qty = self.calc_qty_scales(
-                        earliest,
-                        latest,
-                        highest,
-                        lowest,
-                        chart.tick_size,
-                        *clusters,
-                    );
+    pub fn studies(&self) -> Option<Vec<FootprintStudy>> {
+        match &self.kind {
+            KlineChartKind::Footprint { studies, .. } => Some(studies.clone()),
+            _ => None,
+        }
+    }
 
-                    let cell_height_unscaled = chart.cell_height * chart.scaling;
-                    let cell_width_unscaled = chart.cell_width * chart.scaling;
+    pub fn set_studies(&mut self, new_studies: Vec<FootprintStudy>) {
+        if let KlineChartKind::Footprint {
+            ref mut studies, ..
+        } = self.kind
+        {
+            *studies = new_studies;
+        }
 
-                    let text_size = {
-                        let text_size_from_height = cell_height_unscaled.round().min(16.0) - 3.0;
-                        let text_size_from_width =
-                            (cell_width_unscaled * 0.1).round().min(16.0) - 3.0;
+        self.invalidate(None);
+    }
 
-                        text_size_from_height.min(text_size_from_width).max(1.0)
-                    };
+    pub fn insert_trades_buffer(&mut self, trades_buffer: &[Trade]) {
+        if !self.writes_live() {
+            return;
+        }
 
-                    let candle_width = 0.1 * chart.cell_width;
-                    let content_spacing = ContentGaps::from_view(candle_width, chart.scaling);
+        let mut guard = self.series.borrow_mut();
+        let series = &mut *guard;
+        series.raw_trades.extend_from_slice(trades_buffer);
 
-                    let imbalance = studies.iter().find_map(|study| {
-                        if let FootprintStudy::Imbalance {
-                            threshold,
-                            color_scale,
-                            ignore_zeros,
-                        } = study
-                        {
-                            Some((*threshold, *color_scale, *ignore_zeros))
-                        } else {
-                            None
-                        }
+        match &mut series.data_source {
+            PlotData::TickBased(tick_aggr) => {
+                let old_dp_len = tick_aggr.datapoints.len();
+                tick_aggr.insert_trades(trades_buffer);
+
+                let new_dps = tick_aggr.datapoints.len().saturating_sub(old_dp_len);
+                if new_dps > 0 {
+                    let shift_x = new_dps as f32 * self.chart.cell_width;
+                    self.chart.hold_viewport(shift_x);
+                }
+
+                if let Some(last_dp) = tick_aggr.datapoints.last() {
+                    self.chart.last_price =
+                        Some(PriceInfoLabel::new(last_dp.kline.close, last_dp.kline.open));
+                } else {
+                    self.chart.last_price = None;
+                }
+
+                self.indicators
+                    .values_mut()
+                    .filter_map(Option::as_mut)
+                    .for_each(|indi| {
+                        indi.on_insert_trades(trades_buffer, old_dp_len, &series.data_source)
                     });
+            }
+            PlotData::TimeBased(timeseries) => {
+                let from = trades_buffer
+                    .iter()
+                    .map(|trade| trade.time)
+                    .min()
+                    .unwrap_or(0);
+
+                if !timeseries.interval.is_sub_minute() {
+                    timeseries.insert_trades_existing_buckets(trades_buffer);
+                    series.downsample_from(from);
+                    drop(guard);
+                    self.wrote_series();
+                    return;
+                }
 
-                    let show_text = {
-                        let min_w = match clusters {
-                            ClusterKind::VolumeProfile | ClusterKind::DeltaProfile => 80.0,
-                            ClusterKind::BidAsk => 120.0,
-                        };
-                        should_show_text(cell_height_unscaled, cell_width_unscaled, min_w)
-                    };
+                // no exchange streams klines this short, the trades build them instead
+                let klines = timeseries.insert_trades_as_klines(trades_buffer);
+                series.downsample_from(from);
 
-                    draw_all_npocs(
-                        &self.data_source,
-                        frame,
-                        price_to_y,
-                        interval_to_x,
-                        candle_width,
-                        chart.cell_width,
-                        chart.cell_height,
-                        palette,
-                        studies,
-                        earliest,
-                        latest,
-                        *clusters,
-                        content_spacing,
-                        imbalance.is_some(),
-                    );
+                self.indicators
+                    .values_mut()
+                    .filter_map(Option::as_mut)
+                    .for_each(|indi| indi.on_insert_klines(&klines));
 
-                    render_data_source(
-                        &self.data_source,
-                        frame,
-                        earliest,
-                        latest,
-                        interval_to_x,
-                        |frame, x_position, kline, trades| {
-                            let cluster_scaling =
-                                effective_cluster_qty(*scaling, max_cluster_qty, trades, *clusters);
+                if let Some(kline) = klines.last() {
+                    let chart = &mut self.chart;
 
-                            draw_clusters(
-                                frame,
-                                price_to_y,
-                                x_position,
-                                chart.cell_width,
-                                chart.cell_height,
-                                candle_width,
-                                cluster_scaling,
-                                palette,
-                                text_size,
-                                self.tick_size(),
-                                show_text,
-                                imbalance,
-                                kline,
-                                trades,
-                                *clusters,
-                                content_spacing,
-                            );
-                        },
-                    );
+                    if kline.time > chart.latest_x {
+                        if chart.latest_x > 0 {
+                            let shift_x = chart.interval_to_x(kline.time);
+                            chart.hold_viewport(shift_x);
+                        }
+                        chart.latest_x = kline.time;
+                    }
+
+                    chart.last_price = Some(PriceInfoLabel::new(kline.close, kline.open));
                 }
-                KlineChartKind::Candles => {
-                    let candle_width = chart.cell_width * 0.8;
+            }
+        }
 
-                    render_data_source(
-                        &self.data_source,
-                        frame,
-                        earliest,
-                        latest,
-                        interval_to_x,
-                        |frame, x_position, kline, _| {
-                            draw_candle_dp(
-                                frame,
-                                price_to_y,
-                                candle_width,
-                                palette,
-                                x_position,
-                                kline,
-                            );
-                        },
+        drop(guard);
+        self.wrote_series();
+        self.invalidate(None);
+    }
+
+    pub fn insert_liquidation(&mut self, liquidation: &Liquidation) {
+        if !self.writes_live() {
+            return;
+        }
+
+        {
+            let mut series = self.series.borrow_mut();
+            match &mut series.data_source {
+                PlotData::TickBased(tick_aggr) => {
+                    tick_aggr.insert_liquidations(std::slice::from_ref(liquidation));
+                }
+                PlotData::TimeBased(timeseries) => {
+                    timeseries.insert_liquidations(std::slice::from_ref(liquidation));
+                }
+            }
+
+            series.downsample_from(liquidation.time);
+            series.raw_liquidations.push(*liquidation);
+        }
+
+        self.wrote_series();
+        self.invalidate(None);
+    }
+
+    pub fn insert_raw_trades(&mut self, raw_trades: Vec<Trade>, is_batches_done: bool) {
+        {
+            let mut series = self.series.borrow_mut();
+            match &mut series.data_source {
+                PlotData::TickBased(tick_aggr) => {
+                    tick_aggr.insert_trades(&raw_trades);
+                }
+                PlotData::TimeBased(timeseries) => {
+                    timeseries.insert_trades_existing_buckets(&raw_trades);
+                }
+            }
+
+            if let Some(from) = raw_trades.iter().map(|trade| trade.time).min() {
+                series.downsample_from(from);
+            }
+            series.raw_trades.extend(raw_trades);
+        }
+        self.wrote_series();
+
+        if is_batches_done {
+            self.fetching_trades = (false, None);
+        }
+    }
+
+    pub fn insert_hist_klines(&mut self, req_id: uuid::Uuid, klines_raw: &[Kline]) {
+        // answers a fetch sent before the replay started, live history would mix into it
+        if self.replaying {
+            return;
+        }
+
+        {
+            let mut guard = self.series.borrow_mut();
+            let series = &mut *guard;
+            let PlotData::TimeBased(timeseries) = &mut series.data_source else {
+                return;
+            };
+
+            timeseries.insert_klines(klines_raw);
+            timeseries.insert_trades_existing_buckets(&series.raw_trades);
+            series.downsample_from(0);
+        }
+        self.wrote_series();
+
+        self.indicators
+            .values_mut()
+            .filter_map(Option::as_mut)
+            .for_each(|indi| indi.on_insert_klines(klines_raw));
+
+        if klines_raw.is_empty() {
+            self.request_handler
+                .mark_failed(req_id, "No data received".to_string());
+        } else {
+            self.request_handler.mark_completed(req_id);
+        }
+        if let Some(repair) = self.gap_repair.as_mut() {
+            repair.in_flight.retain(|id| *id != req_id);
+        }
+        self.invalidate(None);
+    }
+
+    /// Inserts klines read from a file, prices rounded to the ticker's tick size.
+    /// Returns `false` on tick based charts, which have no time buckets to fill.
+    pub fn insert_imported_klines(&mut self, klines: &[Kline]) -> bool {
+        let min_tick = self.chart.ticker_info.min_ticksize;
+        let klines = klines
+            .iter()
+            .map(|kline| Kline {
+                open: kline.open.round_to_min_tick(min_tick),
+                high: kline.high.round_to_min_tick(min_tick),
+                low: kline.low.round_to_min_tick(min_tick),
+                close: kline.close.round_to_min_tick(min_tick),
+                ..*kline
+            })
+            .collect::<Vec<_>>();
+
+        {
+            let mut guard = self.series.borrow_mut();
+            let series = &mut *guard;
+            let PlotData::TimeBased(timeseries) = &mut series.data_source else {
+                return false;
+            };
+
+            timeseries.insert_klines(&klines);
+            timeseries.insert_trades_existing_buckets(&series.raw_trades);
+            series.downsample_from(0);
+        }
+        self.wrote_series();
+
+        self.indicators
+            .values_mut()
+            .filter_map(Option::as_mut)
+            .for_each(|indi| indi.on_insert_klines(&klines));
+
+        self.invalidate(None);
+        true
+    }
+
+    /// Starts the series over from `klines` for a replay, fetching nothing meanwhile
+    pub fn reset_for_replay(&mut self, klines: &[Kline]) {
+        self.replaying = true;
+        self.clear_series();
+
+        if self.insert_imported_klines(klines)
+            && let Some(kline) = klines.last()
+        {
+            self.chart.latest_x = kline.time;
+            self.chart.last_price = Some(PriceInfoLabel::new(kline.close, kline.open));
+        }
+    }
+
+    /// Drops what a replay played in, the next tick fetches the live history again
+    pub fn end_replay(&mut self) {
+        if !self.replaying {
+            return;
+        }
+
+        self.clear_series();
+        self.replaying = false;
+    }
+
+    fn clear_series(&mut self) {
+        let Basis::Time(timeframe) = self.chart.basis else {
+            return;
+        };
+
+        self.chart.latest_x = 0;
+
+        let empty = TimeSeries::<KlineDataPoint>::new(timeframe, self.chart.tick_size, &[]);
+        // while replaying the invalidation doesn't ask for anything to fetch
+        self.replace_series(Basis::Time(timeframe), PlotData::TimeBased(empty));
+
+        // the series is this chart's own from here on, the others keep their trades
+        let mut series = self.series.borrow_mut();
+        series.raw_trades.clear();
+        series.raw_liquidations.clear();
+    }
+
+    pub fn insert_open_interest(&mut self, req_id: Option<uuid::Uuid>, oi_data: &[OIData]) {
+        if let Some(req_id) = req_id {
+            if oi_data.is_empty() {
+                self.request_handler
+                    .mark_failed(req_id, "No data received".to_string());
+            } else {
+                self.request_handler.mark_completed(req_id);
+            }
+        }
+
+        if let Some(indi) = self.indicators[KlineIndicator::OpenInterest].as_mut() {
+            indi.on_open_interest(oi_data);
+        }
+    }
+
+    fn calc_qty_scales(
+        &self,
+        earliest: u64,
+        latest: u64,
+        highest: Price,
+        lowest: Price,
+        step: PriceStep,
+        cluster_kind: ClusterKind,
+        cell_text: FootprintCellText,
+    ) -> f32 {
+        let rounded_highest = highest.round_to_side_step(false, step).add_steps(1, step);
+
+        let rounded_lowest = lowest.round_to_side_step(true, step).add_steps(-1, step);
+
+        match &self.series.borrow().data_source {
+            PlotData::TimeBased(timeseries) => timeseries.max_qty_ts_range(
+                cluster_kind,
+                cell_text,
+                earliest,
+                latest,
+                rounded_highest,
+                rounded_lowest,
+            ),
+            PlotData::TickBased(tick_aggr) => {
+                let earliest = earliest as usize;
+                let latest = latest as usize;
+
+                tick_aggr.max_qty_idx_range(
+                    cluster_kind,
+                    cell_text,
+                    earliest,
+                    latest,
+                    rounded_highest,
+                    rounded_lowest,
+                )
+            }
+        }
+    }
+
+    pub fn last_update(&self) -> Instant {
+        self.last_tick
+    }
+
+    pub fn invalidate(&mut self, now: Option<Instant>) -> Option<Action> {
+        if now.is_some() {
+            self.catch_up();
+            self.prune_to_retention();
+        }
+
+        self.update_compact();
+        let chart = &mut self.chart;
+
+        if let Some(autoscale) = chart.layout.autoscale {
+            match autoscale {
+                super::Autoscale::CenterLatest => {
+                    chart.translation.x = latest_bar_x(&self.kind, chart);
+
+                    let calculate_target_y = |kline: exchange::Kline| -> f32 {
+                        let y_low = chart.price_to_y(kline.low);
+                        let y_high = chart.price_to_y(kline.high);
+                        let y_close = chart.price_to_y(kline.close);
+
+                        let mut target_y_translation = -(y_low + y_high) / 2.0;
+
+                        if chart.bounds.height > f32::EPSILON && chart.scaling > f32::EPSILON {
+                            let visible_half_height = (chart.bounds.height / chart.scaling) / 2.0;
+
+                            let view_center_y_centered = -target_y_translation;
+
+                            let visible_y_top = view_center_y_centered - visible_half_height;
+                            let visible_y_bottom = view_center_y_centered + visible_half_height;
+
+                            let padding = chart.cell_height;
+
+                            if y_close < visible_y_top {
+                                target_y_translation = -(y_close - padding + visible_half_height);
+                            } else if y_close > visible_y_bottom {
+                                target_y_translation = -(y_close + padding - visible_half_height);
+                            }
+                        }
+                        target_y_translation
+                    };
+
+                    chart.translation.y = self
+                        .series
+                        .borrow()
+                        .data_source
+                        .latest_y_midpoint(calculate_target_y);
+                }
+                super::Autoscale::FitToVisible => {
+                    let visible_region = chart.visible_region(chart.bounds.size());
+                    let (start_interval, end_interval) = chart.interval_range(&visible_region);
+
+                    let (highest, lowest) = self.series.borrow().data_source.visible_price_scale(
+                        start_interval,
+                        end_interval,
+                        self.scale_padding,
+                        footprint_min_padding(&self.kind, chart.tick_size),
                     );
+                    let price_span = (highest - lowest).to_f32();
+
+                    if price_span > 0.0 && chart.bounds.height > f32::EPSILON {
+                        let chart_height = chart.bounds.height;
+                        let tick_size = chart.tick_size.to_f32_lossy();
+
+                        if tick_size > 0.0 && chart.layout.fixed_cells {
+                            // Cell height stays, the visible price range is centered instead
+                            let span_height = price_span / tick_size * chart.cell_height;
+                            chart.base_price_y = highest;
+                            chart.translation.y = -span_height / 2.0;
+                        } else if tick_size > 0.0 {
+                            chart.cell_height = (chart_height * tick_size) / price_span;
+                            chart.base_price_y = highest;
+                            chart.translation.y = -chart_height / 2.0;
+                        }
+                    }
+                }
+            }
+        }
+
+        if let KlineChartKind::Footprint { studies, .. } = &self.kind
+            && studies.contains(&FootprintStudy::NPoC {
+                lookback: NpocLookback::VisibleRange,
+            })
+        {
+            let visible_region = chart.visible_region(chart.bounds.size());
+            let (earliest, latest) = chart.interval_range(&visible_region);
+
+            match &mut self.series.borrow_mut().data_source {
+                PlotData::TimeBased(timeseries) => {
+                    let snapshot = timeseries.npoc_snapshot(earliest, latest);
+
+                    if snapshot.bar_count() >= BACKGROUND_NPOC_MIN_BARS {
+                        self.npoc_job.pending = Some(Box::new(snapshot));
+                    } else {
+                        timeseries.apply_poc_status(&snapshot.compute());
+                    }
+                }
+                PlotData::TickBased(tick_aggr) => {
+                    tick_aggr.update_poc_status(earliest as usize, latest as usize);
                 }
             }
+        }
+
+        chart.cache.clear_all();
+        self.minimap_cache.clear();
+        for indi in self.indicators.values_mut().filter_map(Option::as_mut) {
+            indi.clear_all_caches();
+        }
+        if let Some(panel) = self.depth_imbalance.as_mut() {
+            panel.clear_all_caches();
+        }
+        // recomputed over the whole series, like the studies drawn over the candles
+        if let Some(panel) = self.stochastic.as_mut() {
+            panel.rebuild(&self.series.borrow().data_source);
+        }
+
+        if let Some(t) = now {
+            self.last_tick = t;
+            self.gap_repair_task().or_else(|| self.missing_data_task())
+        } else {
+            None
+        }
+    }
+
+    /// Drops the oldest bars once the series grows past the retention cap, never the visible
+    /// ones or those before them that the kline fetch would prefetch
+    fn prune_to_retention(&mut self) {
+        // the other windows showing the series could still have the oldest bars in view
+        if Rc::strong_count(&self.series) > 1
+            || !matches!(self.series.borrow().data_source, PlotData::TimeBased(_))
+        {
+            return;
+        }
+        let Some((earliest, latest)) = self.visible_timerange() else {
+            return;
+        };
+        let keep_from = earliest.saturating_sub(latest.saturating_sub(earliest));
+        let max_bars = data::config::series_retention().max_bars as usize;
+
+        let mut guard = self.series.borrow_mut();
+        let series = &mut *guard;
+        let PlotData::TimeBased(timeseries) = &mut series.data_source else {
+            return;
+        };
+        let Some(cutoff) = timeseries.prune_oldest(max_bars, keep_from) else {
+            return;
+        };
+
+        // trades of dropped bars would only come back as footprints on a tick size change
+        series.raw_trades.retain(|trade| trade.time >= cutoff);
+        series.raw_liquidations.retain(|liq| liq.time >= cutoff);
+
+        if series.resolutions.is_some() {
+            series.resolutions = Some(Box::new(MultiResTimeSeries::new(timeseries)));
+        }
+        for indi in self.indicators.values_mut().filter_map(Option::as_mut) {
+            indi.rebuild_from_source(&series.data_source);
+        }
+    }
+
+    pub fn toggle_indicator(&mut self, indicator: KlineIndicator) {
+        let prev_panel_count = self.panel_count();
+
+        if self.indicators[indicator].is_some() {
+            self.indicators[indicator] = None;
+        } else {
+            let mut box_indi = indicator::kline::make_empty(indicator);
+            let series = self.series.borrow();
+            box_indi.set_volume_display(self.volume_display, &series.data_source);
+            box_indi.rebuild_from_source(&series.data_source);
+            self.indicators[indicator] = Some(box_indi);
+        }
+
+        self.resize_panels(prev_panel_count);
+    }
+
+    /// Indicator panels below the main chart, those of the studies included
+    fn panel_count(&self) -> usize {
+        self.indicators.values().filter(|v| v.is_some()).count()
+            + usize::from(self.depth_imbalance.is_some())
+            + usize::from(self.stochastic.is_some())
+    }
+
+    fn resize_panels(&mut self, prev_panel_count: usize) {
+        let panel_count = self.panel_count();
+
+        if panel_count != prev_panel_count
+            && let Some(main_split) = self.chart.layout.splits.first()
+        {
+            self.chart.layout.splits =
+                data::util::calc_panel_splits(*main_split, panel_count, Some(prev_panel_count));
+        }
+    }
+}
+
+impl Drop for KlineChart {
+    fn drop(&mut self) {
+        self.release_series();
+    }
+}
+
+impl canvas::Program<Message> for KlineChart {
+    type State = Interaction;
+
+    fn update(
+        &self,
+        interaction: &mut Interaction,
+        event: &Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Option<canvas::Action<Message>> {
+        super::canvas_interaction(self, interaction, event, bounds, cursor)
+    }
+
+    fn draw(
+        &self,
+        interaction: &Interaction,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let chart = self.state();
+
+        if chart.bounds.width == 0.0 {
+            return vec![];
+        }
+        let series = self.series.borrow();
+
+        let bounds_size = bounds.size();
+        let palette = theme.extended_palette();
+        // tiny-skia can't draw the shader, bars fall back to the canvas there
+        let gpu_bars = super::cells::detect_backend(renderer) && self.gpu_cells.is_some();
+
+        let klines = chart.cache.main.draw(renderer, bounds_size, |frame| {
+            let center = Vector::new(bounds.width / 2.0, bounds.height / 2.0);
+
+            frame.translate(center);
+            frame.scale(chart.scaling);
+            frame.translate(chart.translation);
+
+            let region = chart.visible_region(frame.size());
+            let (earliest, latest) = chart.interval_range(&region);
+
+            let price_to_y = |price| chart.price_to_y(price);
+            let interval_to_x = |interval| chart.interval_to_x(interval);
+
+            if let PlotData::TimeBased(timeseries) = &series.data_source {
+                draw_session_separators(
+                    frame,
+                    timeseries,
+                    self.session_separator,
+                    earliest,
+                    latest,
+                    interval_to_x,
+                    chart.cell_width,
+                    region,
+                    palette,
+                );
+            }
+
+            match self.drawn_kind() {
+                KlineChartKind::Footprint {
+                    clusters,
+                    scaling,
+                    studies,
+                    depth_layer,
+                    cell_text,
+                } => {
+                    let (highest, lowest) = chart.price_range(&region);
+
+                    // 深度热力图层画在Footprint下方，Footprint按设置的不透明度叠加在上面
+                    let faded_palette;
+                    let palette = match depth_layer {
+                        Some(layer) => {
+                            if let Some(history) = self.depth_history.as_deref() {
+                                draw_depth_layer(
+                                    frame,
+                                    chart,
+                                    &history.depth,
+                                    &series.data_source,
+                                    (earliest, latest),
+                                    (highest, lowest),
+                                    palette,
+                                    layer.heatmap_alpha(),
+                                );
+                            }
+
+                            faded_palette = fade_palette(palette, layer.footprint_alpha());
+                            &faded_palette
+                        }
+                        None => palette,
+                    };
+
+                    let max_cluster_qty = self.calc_qty_scales(
+                        earliest,
+                        latest,
+                        highest,
+                        lowest,
+                        chart.tick_size,
+                        *clusters,
+                        *cell_text,
+                    );
+
+                    let cell_height_unscaled = chart.cell_height * chart.scaling;
+                    let cell_width_unscaled = chart.cell_width * chart.scaling;
+
+                    let text_size = {
+                        let text_size_from_height = cell_height_unscaled.round().min(16.0) - 3.0;
+                        let text_size_from_width =
+                            (cell_width_unscaled * 0.1).round().min(16.0) - 3.0;
+
+                        text_size_from_height.min(text_size_from_width).max(1.0)
+                    };
+
+                    let candle_width = 0.1 * chart.cell_width;
+                    let content_spacing = ContentGaps::from_view(candle_width, chart.scaling);
+
+                    let imbalance = studies.iter().find_map(|study| {
+                        if let FootprintStudy::Imbalance {
+                            threshold,
+                            color_scale,
+                            ignore_zeros,
+                        } = study
+                        {
+                            Some((*threshold, *color_scale, *ignore_zeros))
+                        } else {
+                            None
+                        }
+                    });
+
+                    let max_liquidation = if studies.contains(&FootprintStudy::LiquidationOverlay) {
+                        max_liquidation_in_range(
+                            &series.data_source,
+                            earliest,
+                            latest,
+                            highest,
+                            lowest,
+                        )
+                    } else {
+                        0.0
+                    };
+
+                    let stacked_imbalance = studies.iter().find_map(|study| {
+                        if let FootprintStudy::StackedImbalance {
+                            threshold,
+                            min_stack,
+                        } = study
+                        {
+                            Some((*threshold, *min_stack))
+                        } else {
+                            None
+                        }
+                    });
+
+                    let show_text = {
+                        let min_w = match clusters {
+                            ClusterKind::VolumeProfile | ClusterKind::DeltaProfile => 80.0,
+                            ClusterKind::BidAsk => 120.0,
+                        };
+                        should_show_text(cell_height_unscaled, cell_width_unscaled, min_w)
+                    };
+
+                    draw_all_npocs(
+                        &series.data_source,
+                        frame,
+                        price_to_y,
+                        interval_to_x,
+                        candle_width,
+                        chart.cell_width,
+                        chart.cell_height,
+                        palette,
+                        studies,
+                        earliest,
+                        latest,
+                        *clusters,
+                        content_spacing,
+                        imbalance.is_some(),
+                    );
+
+                    let mut gpu_quads = Vec::new();
+                    let mut bars = if gpu_bars {
+                        CellBars::Gpu(&mut gpu_quads)
+                    } else {
+                        CellBars::Canvas
+                    };
+
+                    render_data_source(
+                        &series.data_source,
+                        frame,
+                        earliest,
+                        latest,
+                        interval_to_x,
+                        |frame, x_position, kline, trades| {
+                            let cluster_scaling = effective_cluster_qty(
+                                *scaling,
+                                max_cluster_qty,
+                                trades,
+                                *clusters,
+                                *cell_text,
+                            );
+
+                            draw_clusters(
+                                frame,
+                                price_to_y,
+                                x_position,
+                                chart.cell_width,
+                                chart.cell_height,
+                                candle_width,
+                                cluster_scaling,
+                                palette,
+                                (text_size, show_text),
+                                self.tick_size(),
+                                &mut bars,
+                                imbalance,
+                                kline,
+                                trades,
+                                (*clusters, *cell_text),
+                                content_spacing,
+                            );
+
+                            if max_liquidation > 0.0 {
+                                draw_liquidations(
+                                    frame,
+                                    price_to_y,
+                                    x_position,
+                                    chart.cell_width,
+                                    chart.cell_height,
+                                    trades,
+                                    max_liquidation,
+                                    palette,
+                                );
+                            }
+
+                            if let Some((threshold, min_stack)) = stacked_imbalance {
+                                draw_stacked_imbalances(
+                                    frame,
+                                    price_to_y,
+                                    x_position,
+                                    chart.cell_width,
+                                    chart.cell_height,
+                                    self.tick_size(),
+                                    trades,
+                                    threshold,
+                                    min_stack,
+                                    palette,
+                                );
+                            }
+                        },
+                    );
+
+                    if gpu_bars
+                        && let Some(cells) = &self.gpu_cells
+                        && let Ok(mut layer) = cells.write()
+                    {
+                        *layer = CellLayer {
+                            quads: gpu_quads,
+                            center,
+                            scaling: chart.scaling,
+                            translation: chart.translation,
+                        };
+                    }
+
+                    let divergence_lookback = studies.iter().find_map(|study| {
+                        if let FootprintStudy::Divergence { lookback } = study {
+                            Some(*lookback)
+                        } else {
+                            None
+                        }
+                    });
+
+                    if let (Some(lookback), PlotData::TimeBased(timeseries)) =
+                        (divergence_lookback, &series.data_source)
+                    {
+                        draw_divergences(
+                            frame,
+                            timeseries,
+                            lookback,
+                            earliest,
+                            latest,
+                            price_to_y,
+                            interval_to_x,
+                            chart.cell_height,
+                            8.0 / chart.scaling,
+                            palette,
+                        );
+                    }
+
+                    if studies.contains(&FootprintStudy::DeltaBar) {
+                        draw_delta_bars(
+                            frame,
+                            &series.data_source,
+                            earliest,
+                            latest,
+                            region,
+                            interval_to_x,
+                            chart.cell_width,
+                            chart.scaling,
+                            palette,
+                        );
+                    }
+
+                    if let (true, PlotData::TimeBased(timeseries)) =
+                        (self.poc_history, &series.data_source)
+                    {
+                        draw_poc_history(
+                            frame,
+                            timeseries,
+                            earliest,
+                            latest,
+                            price_to_y,
+                            interval_to_x,
+                            chart.cell_width,
+                            palette,
+                        );
+                    }
+
+                    if let (true, PlotData::TimeBased(timeseries)) =
+                        (self.visible_profile, &series.data_source)
+                    {
+                        draw_visible_profile(
+                            frame,
+                            timeseries,
+                            earliest,
+                            latest,
+                            region,
+                            price_to_y,
+                            chart.tick_size,
+                            palette,
+                        );
+                    }
+                }
+                KlineChartKind::Candles { .. } => {
+                    let (body_width, wick_width) = self.candle_proportions;
+                    let candle_width = chart.cell_width * body_width;
+
+                    let draw_candle = |frame: &mut canvas::Frame,
+                                       x_position: f32,
+                                       kline: &Kline,
+                                       delta: Option<f32>,
+                                       candle_width: f32,
+                                       max_abs_delta: f32| {
+                        let body_color = delta
+                            .filter(|_| max_abs_delta > 0.0)
+                            .map(|delta| delta_body_color(palette, delta, max_abs_delta));
+
+                        draw_candle_dp(
+                            frame,
+                            price_to_y,
+                            candle_width,
+                            palette,
+                            x_position,
+                            kline,
+                            body_color,
+                            self.candle_style,
+                            wick_width,
+                        );
+                    };
+
+                    if let Some((downsampled, base_ms, interval)) =
+                        series.coarser_resolution(earliest, latest)
+                    {
+                        // 缩小到K线过密时改画更粗周期的K线，每根覆盖 `bars` 根原周期K线
+                        let bars = interval / base_ms;
+                        let start = (earliest / interval) * interval;
+                        let visible = || downsampled.range(start..=latest.max(start));
+
+                        let max_abs_delta = match self.candle_coloring {
+                            CandleColoring::Delta => visible()
+                                .map(|(_, bar)| bar.delta_qty().abs())
+                                .fold(0.0, f32::max),
+                            CandleColoring::PriceAction => 0.0,
+                        };
+
+                        for (time, bar) in visible() {
+                            // 放在所覆盖的首末两根原周期K线中间
+                            let x_position = interval_to_x(time + (bars - 1) * base_ms / 2);
+
+                            draw_candle(
+                                frame,
+                                x_position,
+                                &bar.kline,
+                                bar.has_delta().then(|| bar.delta_qty()),
+                                candle_width * bars as f32,
+                                max_abs_delta,
+                            );
+                        }
+                    } else {
+                        let max_abs_delta = match self.candle_coloring {
+                            CandleColoring::Delta => {
+                                max_abs_delta_in_range(&series.data_source, earliest, latest)
+                            }
+                            CandleColoring::PriceAction => 0.0,
+                        };
+
+                        render_data_source(
+                            &series.data_source,
+                            frame,
+                            earliest,
+                            latest,
+                            interval_to_x,
+                            |frame, x_position, kline, footprint| {
+                                draw_candle(
+                                    frame,
+                                    x_position,
+                                    kline,
+                                    (!footprint.trades.is_empty()).then(|| footprint.delta_qty()),
+                                    candle_width,
+                                    max_abs_delta,
+                                );
+                            },
+                        );
+                    }
+                }
+            }
+
+            if let (Some(overlay), PlotData::TimeBased(timeseries)) =
+                (&self.compare, &series.data_source)
+            {
+                for (compare, line) in overlay.overlays.iter().zip(&overlay.lines) {
+                    draw_compare_line(
+                        frame,
+                        timeseries,
+                        &line.series,
+                        earliest,
+                        latest,
+                        price_to_y,
+                        interval_to_x,
+                        compare.color,
+                    );
+                }
+            }
+
+            for study in &self.kline_studies {
+                match *study {
+                    KlineStudy::AnchoredVwap { anchor_time, color } => {
+                        if let (true, PlotData::TimeBased(timeseries)) =
+                            (self.anchored_vwap, &series.data_source)
+                        {
+                            draw_anchored_vwap(
+                                frame,
+                                timeseries,
+                                anchor_time,
+                                color,
+                                earliest,
+                                latest,
+                                price_to_y,
+                                interval_to_x,
+                            );
+                        }
+                    }
+                    KlineStudy::KeltnerChannel {
+                        ema_period,
+                        atr_period,
+                        multiplier,
+                        highlight_squeeze,
+                    } => {
+                        draw_keltner_channel(
+                            frame,
+                            &series.data_source,
+                            (ema_period, atr_period, multiplier),
+                            highlight_squeeze,
+                            earliest,
+                            latest,
+                            price_to_y,
+                            interval_to_x,
+                            chart.cell_width,
+                            palette,
+                        );
+                    }
+                    KlineStudy::Ichimoku {
+                        tenkan,
+                        kijun,
+                        senkou_b,
+                    } => {
+                        draw_ichimoku(
+                            frame,
+                            &series.data_source,
+                            (tenkan, kijun, senkou_b),
+                            earliest,
+                            latest,
+                            price_to_y,
+                            interval_to_x,
+                            chart.cell_width,
+                            palette,
+                        );
+                    }
+                    KlineStudy::Supertrend { period, multiplier } => {
+                        draw_supertrend(
+                            frame,
+                            &series.data_source,
+                            (period, multiplier),
+                            earliest,
+                            latest,
+                            price_to_y,
+                            interval_to_x,
+                            chart.cell_width,
+                            palette,
+                        );
+                    }
+                    KlineStudy::MaxDeltaBar => {
+                        if let PlotData::TimeBased(timeseries) = &series.data_source {
+                            // 画到K线实际所用的周期上，缩小后边框包住的是整根粗周期K线
+                            match series.coarser_resolution(earliest, latest) {
+                                Some((downsampled, base_ms, interval)) => {
+                                    let bars = interval / base_ms;
+
+                                    draw_max_delta_bar(
+                                        frame,
+                                        downsampled,
+                                        |bar| (&bar.kline, bar.delta_qty()),
+                                        earliest,
+                                        latest,
+                                        price_to_y,
+                                        |time| interval_to_x(time + (bars - 1) * base_ms / 2),
+                                        chart.cell_width * bars as f32,
+                                        1.0 / chart.scaling,
+                                        palette,
+                                    );
+                                }
+                                None => draw_max_delta_bar(
+                                    frame,
+                                    &timeseries.datapoints,
+                                    |dp| (&dp.kline, dp.delta_qty()),
+                                    earliest,
+                                    latest,
+                                    price_to_y,
+                                    interval_to_x,
+                                    chart.cell_width,
+                                    1.0 / chart.scaling,
+                                    palette,
+                                ),
+                            }
+                        }
+                    }
+                    // drawn in their own panels
+                    KlineStudy::DepthImbalance { .. } | KlineStudy::Stochastic { .. } => {}
+                }
+            }
+
+            if let (
+                KlineChartKind::Candles {
+                    show_volume_profile: true,
+                },
+                PlotData::TimeBased(timeseries),
+            ) = (&self.kind, &series.data_source)
+            {
+                draw_candle_volume_profile(
+                    frame,
+                    timeseries,
+                    earliest,
+                    latest,
+                    region,
+                    price_to_y,
+                    chart.scaling,
+                    palette,
+                );
+            }
+
+            chart.draw_last_price_line(frame, palette, region);
+        });
+
+        let crosshair = chart.cache.crosshair.draw(renderer, bounds_size, |frame| {
+            if let (Some(opened), PlotData::TimeBased(timeseries)) =
+                (self.new_bar_flash, &series.data_source)
+                && let Some(latest) = timeseries.latest_timestamp()
+            {
+                let fade = 1.0 - opened.elapsed().as_secs_f32() / NEW_BAR_FLASH.as_secs_f32();
+                draw_new_bar_flash(frame, chart, latest, fade.max(0.0), palette);
+            }
+
+            chart.draw_measurement(frame, theme, bounds_size);
+            chart.draw_price_lines(
+                frame,
+                theme,
+                bounds_size,
+                interaction,
+                cursor.position_in(bounds),
+            );
+
+            if let Some(cursor_position) = cursor.position_in(bounds) {
+                let (_, rounded_aggregation) =
+                    chart.draw_crosshair(frame, theme, bounds_size, cursor_position, interaction);
+
+                draw_crosshair_tooltip(
+                    &series.data_source,
+                    &chart.ticker_info,
+                    frame,
+                    palette,
+                    rounded_aggregation,
+                );
+
+                if let Some(overlay) = &self.compare {
+                    let (earliest, _) = chart.interval_range(&chart.visible_region(bounds_size));
+                    draw_compare_tooltip(overlay, frame, palette, earliest, rounded_aggregation);
+                }
+            } else {
+                chart.draw_synced_crosshair(frame, theme, bounds_size);
+            }
+        });
+
+        vec![klines, crosshair]
+    }
+
+    fn mouse_interaction(
+        &self,
+        interaction: &Interaction,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        match interaction {
+            Interaction::Panning { .. } => mouse::Interaction::Grabbing,
+            Interaction::Zoomin { .. } => mouse::Interaction::ZoomIn,
+            Interaction::MovingPriceLine { .. } => mouse::Interaction::ResizingVertically,
+            Interaction::None
+            | Interaction::Ruler { .. }
+            | Interaction::Clicked(_)
+            | Interaction::PressStart(..) => {
+                if cursor.is_over(bounds) {
+                    mouse::Interaction::Crosshair
+                } else {
+                    mouse::Interaction::default()
+                }
+            }
+        }
+    }
+}
+
+fn draw_footprint_kline(
+    frame: &mut canvas::Frame,
+    price_to_y: impl Fn(Price) -> f32,
+    x_position: f32,
+    candle_width: f32,
+    kline: &Kline,
+    palette: &Extended,
+) {
+    let y_open = price_to_y(kline.open);
+    let y_high = price_to_y(kline.high);
+    let y_low = price_to_y(kline.low);
+    let y_close = price_to_y(kline.close);
+
+    let body_color = if kline.close >= kline.open {
+        palette.success.weak.color
+    } else {
+        palette.danger.weak.color
+    };
+    frame.fill_rectangle(
+        Point::new(x_position - (candle_width / 8.0), y_open.min(y_close)),
+        Size::new(candle_width / 4.0, (y_open - y_close).abs()),
+        body_color,
+    );
+
+    let wick_color = if kline.close >= kline.open {
+        palette.success.weak.color
+    } else {
+        palette.danger.weak.color
+    };
+    let marker_line = Stroke::with_color(
+        Stroke {
+            width: 1.0,
+            ..Default::default()
+        },
+        wick_color.scale_alpha(0.6),
+    );
+    frame.stroke(
+        &Path::line(
+            Point::new(x_position, y_high),
+            Point::new(x_position, y_low),
+        ),
+        marker_line,
+    );
+}
+
+pub(super) fn draw_candle_dp(
+    frame: &mut canvas::Frame,
+    price_to_y: impl Fn(Price) -> f32,
+    candle_width: f32,
+    palette: &Extended,
+    x_position: f32,
+    kline: &Kline,
+    body_color: Option<Color>,
+    style: CandleStyle,
+    wick_width: f32,
+) {
+    let y_open = price_to_y(kline.open);
+    let y_high = price_to_y(kline.high);
+    let y_low = price_to_y(kline.low);
+    let y_close = price_to_y(kline.close);
+
+    let body_color = body_color.unwrap_or(if kline.close >= kline.open {
+        palette.success.base.color
+    } else {
+        palette.danger.base.color
+    });
+    let wick_color = if kline.close >= kline.open {
+        palette.success.base.color
+    } else {
+        palette.danger.base.color
+    };
+
+    let body_top = y_open.min(y_close);
+    let body_height = (y_open - y_close).abs();
+    let wick_thickness = candle_width * wick_width;
+    let wick_x = x_position - (wick_thickness / 2.0);
+
+    if style == CandleStyle::HollowBullish && kline.close > kline.open {
+        frame.stroke(
+            &Path::rectangle(
+                Point::new(x_position - (candle_width / 2.0), body_top),
+                Size::new(candle_width, body_height),
+            ),
+            Stroke::with_color(
+                Stroke {
+                    width: 1.0,
+                    ..Default::default()
+                },
+                body_color,
+            ),
+        );
+
+        // wick stops at the body so the outline stays hollow
+        frame.fill_rectangle(
+            Point::new(wick_x, y_high),
+            Size::new(wick_thickness, (body_top - y_high).max(0.0)),
+            wick_color,
+        );
+        frame.fill_rectangle(
+            Point::new(wick_x, body_top + body_height),
+            Size::new(wick_thickness, (y_low - body_top - body_height).max(0.0)),
+            wick_color,
+        );
+        return;
+    }
+
+    frame.fill_rectangle(
+        Point::new(x_position - (candle_width / 2.0), body_top),
+        Size::new(candle_width, body_height),
+        body_color,
+    );
+    frame.fill_rectangle(
+        Point::new(wick_x, y_high),
+        Size::new(wick_thickness, (y_high - y_low).abs()),
+        wick_color,
+    );
+}
+
+/// Body color of a delta colored candle, fainter the smaller its delta is next to `max_abs_delta`
+fn delta_body_color(palette: &Extended, delta: f32, max_abs_delta: f32) -> Color {
+    let strength = (delta.abs() / max_abs_delta).clamp(0.0, 1.0);
+
+    let color = if delta >= 0.0 {
+        palette.success.base.color
+    } else {
+        palette.danger.base.color
+    };
+    color.scale_alpha(0.25 + 0.75 * strength)
+}
+
+/// Largest absolute footprint delta among the bars from `earliest` to `latest`
+fn max_abs_delta_in_range(
+    data_source: &PlotData<KlineDataPoint>,
+    earliest: u64,
+    latest: u64,
+) -> f32 {
+    match data_source {
+        PlotData::TickBased(tick_aggr) => tick_aggr
+            .datapoints
+            .iter()
+            .rev()
+            .enumerate()
+            .filter(|(index, _)| *index as u64 >= earliest && *index as u64 <= latest)
+            .map(|(_, dp)| dp.footprint.delta_qty().abs())
+            .fold(0.0, f32::max),
+        PlotData::TimeBased(timeseries) => {
+            if latest < earliest {
+                return 0.0;
+            }
+
+            timeseries
+                .datapoints
+                .range(earliest..=latest)
+                .map(|(_, dp)| dp.footprint.delta_qty().abs())
+                .fold(0.0, f32::max)
+        }
+    }
+}
+
+/// Largest liquidation at a single price level within the visible bars and prices
+fn max_liquidation_in_range(
+    data_source: &PlotData<KlineDataPoint>,
+    earliest: u64,
+    latest: u64,
+    highest: Price,
+    lowest: Price,
+) -> f32 {
+    match data_source {
+        PlotData::TickBased(tick_aggr) => tick_aggr
+            .datapoints
+            .iter()
+            .rev()
+            .enumerate()
+            .filter(|(index, _)| *index as u64 >= earliest && *index as u64 <= latest)
+            .map(|(_, dp)| dp.footprint.max_liquidation_qty(highest, lowest))
+            .fold(0.0, f32::max),
+        PlotData::TimeBased(timeseries) => {
+            if latest < earliest {
+                return 0.0;
+            }
+
+            timeseries
+                .datapoints
+                .range(earliest..=latest)
+                .map(|(_, dp)| dp.footprint.max_liquidation_qty(highest, lowest))
+                .fold(0.0, f32::max)
+        }
+    }
+}
+
+/// Footprint clusters extend a full price step around each kline, so the scale needs that much room
+/// Horizontal translation that puts the latest bar near the right edge, left of the candle
+/// volume profile when it is shown
+fn latest_bar_x(kind: &KlineChartKind, chart: &ViewState) -> f32 {
+    match kind {
+        KlineChartKind::Footprint { .. } => {
+            0.5 * (chart.bounds.width / chart.scaling) - (chart.cell_width / chart.scaling)
+        }
+        KlineChartKind::Candles {
+            show_volume_profile,
+        } => {
+            let reserved = if *show_volume_profile {
+                CANDLE_PROFILE_WIDTH
+            } else {
+                0.0
+            };
+            0.5 * (chart.bounds.width / chart.scaling)
+                - ((8.0 * chart.cell_width + reserved) / chart.scaling)
+        }
+    }
+}
+
+fn footprint_min_padding(kind: &KlineChartKind, step: PriceStep) -> Price {
+    match kind {
+        KlineChartKind::Footprint { .. } => Price::from_f32(step.to_f32_lossy()),
+        KlineChartKind::Candles { .. } => Price::from_f32(0.0),
+    }
+}
+
+fn draw_anchored_vwap(
+    frame: &mut canvas::Frame,
+    timeseries: &TimeSeries<KlineDataPoint>,
+    anchor_time: u64,
+    color: [u8; 4],
+    earliest: u64,
+    latest: u64,
+    price_to_y: impl Fn(Price) -> f32,
+    interval_to_x: impl Fn(u64) -> f32,
+) {
+    if latest < earliest.max(anchor_time) {
+        return;
+    }
+
+    let points = data::chart::indicator::anchored_vwap(&timeseries.datapoints, anchor_time);
+
+    let path = Path::new(|builder| {
+        let mut started = false;
+
+        for (time, value) in points
+            .iter()
+            .skip_while(|(time, _)| *time < earliest)
+            .take_while(|(time, _)| *time <= latest)
+        {
+            let point = Point::new(interval_to_x(*time), price_to_y(Price::from_f32(*value)));
+            if started {
+                builder.line_to(point);
+            } else {
+                builder.move_to(point);
+                started = true;
+            }
+        }
+    });
+
+    let [r, g, b, a] = color;
+
+    frame.stroke(
+        &path,
+        Stroke::with_color(
+            Stroke {
+                width: 1.5,
+                ..Default::default()
+            },
+            iced::Color::from_rgba8(r, g, b, f32::from(a) / 255.0),
+        ),
+    );
+}
+
+/// Klines oldest first, keyed by timestamp or by reversed tick index like the rest of the chart
+fn keyed_klines(data_source: &PlotData<KlineDataPoint>) -> Vec<(u64, &Kline)> {
+    match data_source {
+        PlotData::TimeBased(timeseries) => timeseries
+            .datapoints
+            .iter()
+            .map(|(time, dp)| (*time, &dp.kline))
+            .collect(),
+        PlotData::TickBased(tick_aggr) => {
+            let last = tick_aggr.datapoints.len().saturating_sub(1);
+            tick_aggr
+                .datapoints
+                .iter()
+                .enumerate()
+                .map(|(i, dp)| ((last - i) as u64, &dp.kline))
+                .collect()
+        }
+    }
+}
+
+/// Ichimoku lines and the cloud between the Senkou spans, which run `kijun` bars past the
+/// latest candle
+fn draw_ichimoku(
+    frame: &mut canvas::Frame,
+    data_source: &PlotData<KlineDataPoint>,
+    (tenkan, kijun, senkou_b): (usize, usize, usize),
+    earliest: u64,
+    latest: u64,
+    price_to_y: impl Fn(Price) -> f32,
+    interval_to_x: impl Fn(u64) -> f32,
+    cell_width: f32,
+    palette: &Extended,
+) {
+    let klines = keyed_klines(data_source);
+    let Some(&(last_key, _)) = klines.last() else {
+        return;
+    };
+
+    let highs: Vec<f32> = klines.iter().map(|(_, k)| k.high.to_f32()).collect();
+    let lows: Vec<f32> = klines.iter().map(|(_, k)| k.low.to_f32()).collect();
+    let closes: Vec<f32> = klines.iter().map(|(_, k)| k.close.to_f32()).collect();
+
+    let cloud = data::chart::indicator::ichimoku(&highs, &lows, &closes, tenkan, kijun, senkou_b);
+
+    // bars past the latest candle continue one cell width apart
+    let last_index = klines.len() - 1;
+    let last_x = interval_to_x(last_key);
+    let x_at = |i: usize| match klines.get(i) {
+        Some((key, _)) => interval_to_x(*key),
+        None => last_x + (i - last_index) as f32 * cell_width,
+    };
+    let is_visible = |i: usize| {
+        klines
+            .get(i)
+            .is_none_or(|(key, _)| (earliest..=latest).contains(key))
+    };
+    let y_at = |value: f32| price_to_y(Price::from_f32(value));
+
+    let spans = || {
+        cloud
+            .senkou_a
+            .iter()
+            .zip(&cloud.senkou_b)
+            .enumerate()
+            .filter_map(|(i, (a, b))| Some((i, (*a)?, (*b)?)))
+    };
+
+    for ((i, a, b), (next_i, next_a, next_b)) in spans().zip(spans().skip(1)) {
+        if next_i != i + 1 || !(is_visible(i) || is_visible(next_i)) {
+            continue;
+        }
+
+        let color = if a + next_a >= b + next_b {
+            palette.success.base.color
+        } else {
+            palette.danger.base.color
+        };
+
+        let (x, next_x) = (x_at(i), x_at(next_i));
+        let segment = Path::new(|builder| {
+            builder.move_to(Point::new(x, y_at(a)));
+            builder.line_to(Point::new(next_x, y_at(next_a)));
+            builder.line_to(Point::new(next_x, y_at(next_b)));
+            builder.line_to(Point::new(x, y_at(b)));
+            builder.close();
+        });
+        frame.fill(&segment, color.scale_alpha(0.15));
+    }
+
+    let line = |values: &[Option<f32>]| {
+        Path::new(|builder| {
+            let mut is_drawing = false;
+            for (i, value) in values.iter().enumerate() {
+                match value {
+                    Some(value) if is_visible(i) => {
+                        let point = Point::new(x_at(i), y_at(*value));
+                        if is_drawing {
+                            builder.line_to(point);
+                        } else {
+                            builder.move_to(point);
+                            is_drawing = true;
+                        }
+                    }
+                    _ => is_drawing = false,
+                }
+            }
+        })
+    };
+
+    let stroke = |color: iced::Color, alpha: f32| {
+        Stroke::with_color(
+            Stroke {
+                width: 1.0,
+                ..Default::default()
+            },
+            color.scale_alpha(alpha),
+        )
+    };
+
+    let chikou: Vec<Option<f32>> = cloud.chikou.iter().copied().map(Some).collect();
+
+    for (values, color, alpha) in [
+        (&cloud.senkou_a, palette.success.base.color, 0.6),
+        (&cloud.senkou_b, palette.danger.base.color, 0.6),
+        (&cloud.tenkan, palette.primary.base.color, 1.0),
+        (&cloud.kijun, palette.warning.base.color, 1.0),
+        (&chikou, palette.secondary.strong.color, 0.7),
+    ] {
+        frame.stroke(&line(values), stroke(color, alpha));
+    }
+}
+
+/// Column of the bar opened at `time`, at `fade` of its full strength
+fn draw_new_bar_flash(
+    frame: &mut canvas::Frame,
+    chart: &ViewState,
+    time: u64,
+    fade: f32,
+    palette: &Extended,
+) {
+    frame.with_save(|frame| {
+        frame.translate(Vector::new(frame.width() / 2.0, frame.height() / 2.0));
+        frame.scale(chart.scaling);
+        frame.translate(chart.translation);
+
+        let region = chart.visible_region(frame.size());
+        let x = chart.interval_to_x(time);
+
+        frame.fill_rectangle(
+            Point::new(x - chart.cell_width / 2.0, region.y),
+            Size::new(chart.cell_width, region.height),
+            palette.primary.base.color.scale_alpha(0.3 * fade),
+        );
+    });
+}
+
+/// Border around the high-low range of the bar with the largest absolute delta in view
+fn draw_max_delta_bar<T>(
+    frame: &mut canvas::Frame,
+    datapoints: &BTreeMap<u64, T>,
+    kline_and_delta: impl Fn(&T) -> (&Kline, f32),
+    earliest: u64,
+    latest: u64,
+    price_to_y: impl Fn(Price) -> f32,
+    interval_to_x: impl Fn(u64) -> f32,
+    bar_width: f32,
+    line_width: f32,
+    palette: &Extended,
+) {
+    let Some((&time, dp)) =
+        TimeSeries::<KlineDataPoint>::max_delta_bar(datapoints, earliest, latest, |dp| {
+            kline_and_delta(dp).1
+        })
+        .and_then(|time| datapoints.get_key_value(&time))
+    else {
+        return;
+    };
+
+    let (kline, _) = kline_and_delta(dp);
+    let x = interval_to_x(time);
+    let (high_y, low_y) = (price_to_y(kline.high), price_to_y(kline.low));
+
+    frame.stroke(
+        &Path::rectangle(
+            Point::new(x - bar_width / 2.0, high_y),
+            Size::new(bar_width, low_y - high_y),
+        ),
+        Stroke::with_color(
+            Stroke {
+                width: line_width,
+                ..Default::default()
+            },
+            palette.background.base.text,
+        ),
+    );
+}
+
+/// Supertrend band colored by trend, shaded up to the closes, with a triangle on each bar
+/// where the trend flips
+fn draw_supertrend(
+    frame: &mut canvas::Frame,
+    data_source: &PlotData<KlineDataPoint>,
+    (period, multiplier): (usize, f32),
+    earliest: u64,
+    latest: u64,
+    price_to_y: impl Fn(Price) -> f32,
+    interval_to_x: impl Fn(u64) -> f32,
+    cell_width: f32,
+    palette: &Extended,
+) {
+    let klines = keyed_klines(data_source);
+
+    let highs: Vec<f32> = klines.iter().map(|(_, k)| k.high.to_f32()).collect();
+    let lows: Vec<f32> = klines.iter().map(|(_, k)| k.low.to_f32()).collect();
+    let closes: Vec<f32> = klines.iter().map(|(_, k)| k.close.to_f32()).collect();
+
+    let trend = data::chart::indicator::supertrend(&highs, &lows, &closes, period, multiplier);
+    let keys = &klines[klines.len() - trend.len()..];
+    let closes = &closes[closes.len() - trend.len()..];
+
+    let y_at = |value: f32| price_to_y(Price::from_f32(value));
+    let color_of = |is_uptrend: bool| {
+        if is_uptrend {
+            palette.success.base.color
+        } else {
+            palette.danger.base.color
+        }
+    };
+    let stroke = |color: iced::Color| {
+        Stroke::with_color(
+            Stroke {
+                width: 1.5,
+                ..Default::default()
+            },
+            color,
+        )
+    };
+    let marker_size = (cell_width * 0.6).clamp(4.0, 10.0);
+
+    for i in 1..trend.len() {
+        let ((key, _), (prev_key, _)) = (keys[i], keys[i - 1]);
+        if !(earliest..=latest).contains(&key) && !(earliest..=latest).contains(&prev_key) {
+            continue;
+        }
+
+        let ((band, is_uptrend), (prev_band, prev_is_uptrend)) = (trend[i], trend[i - 1]);
+        let (x, prev_x) = (interval_to_x(key), interval_to_x(prev_key));
+        let color = color_of(is_uptrend);
+
+        if is_uptrend == prev_is_uptrend {
+            let area = Path::new(|builder| {
+                builder.move_to(Point::new(prev_x, y_at(prev_band)));
+                builder.line_to(Point::new(x, y_at(band)));
+                builder.line_to(Point::new(x, y_at(closes[i])));
+                builder.line_to(Point::new(prev_x, y_at(closes[i - 1])));
+                builder.close();
+            });
+            frame.fill(&area, color.scale_alpha(0.12));
+
+            frame.stroke(
+                &Path::line(
+                    Point::new(prev_x, y_at(prev_band)),
+                    Point::new(x, y_at(band)),
+                ),
+                stroke(color),
+            );
+        } else {
+            // the band sits below price after flipping up, so the marker points up from under it
+            let tip_y = y_at(band);
+            let base_y = if is_uptrend {
+                tip_y + marker_size
+            } else {
+                tip_y - marker_size
+            };
+
+            let triangle = Path::new(|builder| {
+                builder.move_to(Point::new(x, tip_y));
+                builder.line_to(Point::new(x - marker_size / 2.0, base_y));
+                builder.line_to(Point::new(x + marker_size / 2.0, base_y));
+                builder.close();
+            });
+            frame.fill(&triangle, color);
+        }
+    }
+}
+
+/// Upper, mid and lower channel lines, with squeeze bars shaded between the bands
+fn draw_keltner_channel(
+    frame: &mut canvas::Frame,
+    data_source: &PlotData<KlineDataPoint>,
+    (ema_period, atr_period, multiplier): (usize, usize, f32),
+    highlight_squeeze: bool,
+    earliest: u64,
+    latest: u64,
+    price_to_y: impl Fn(Price) -> f32,
+    interval_to_x: impl Fn(u64) -> f32,
+    cell_width: f32,
+    palette: &Extended,
+) {
+    let klines = keyed_klines(data_source);
+
+    let highs: Vec<f32> = klines.iter().map(|(_, k)| k.high.to_f32()).collect();
+    let lows: Vec<f32> = klines.iter().map(|(_, k)| k.low.to_f32()).collect();
+    let closes: Vec<f32> = klines.iter().map(|(_, k)| k.close.to_f32()).collect();
+
+    let bands = data::chart::indicator::keltner_channel(
+        &highs,
+        &lows,
+        &closes,
+        ema_period,
+        atr_period,
+        multiplier,
+    );
+    let keys = &klines[klines.len() - bands.len()..];
+
+    let visible = || {
+        keys.iter()
+            .zip(&bands)
+            .filter(|((key, _), _)| (earliest..=latest).contains(key))
+    };
+
+    if highlight_squeeze {
+        // bollinger bands over the same period as the channel's EMA
+        let bollinger = data::chart::indicator::bollinger_bands(
+            &closes,
+            ema_period,
+            KlineStudy::SQUEEZE_STD_DEVS,
+        );
+        let squeezes = data::chart::indicator::squeezes(&bands, &bollinger);
+        let offset = bands.len() - squeezes.len();
+
+        for (((key, _), (upper, _, lower)), _) in keys[offset..]
+            .iter()
+            .zip(&bands[offset..])
+            .zip(&squeezes)
+            .filter(|(((key, _), _), squeeze)| **squeeze && (earliest..=latest).contains(key))
+        {
+            let y_upper = price_to_y(Price::from_f32(*upper));
+            let y_lower = price_to_y(Price::from_f32(*lower));
+
+            frame.fill_rectangle(
+                Point::new(interval_to_x(*key) - cell_width / 2.0, y_upper),
+                Size::new(cell_width, y_lower - y_upper),
+                palette.warning.weak.color.scale_alpha(0.15),
+            );
+        }
+    }
+
+    let line = |pick: fn(&(f32, f32, f32)) -> f32| {
+        Path::new(|builder| {
+            for (i, ((key, _), band)) in visible().enumerate() {
+                let point =
+                    Point::new(interval_to_x(*key), price_to_y(Price::from_f32(pick(band))));
+                if i == 0 {
+                    builder.move_to(point);
+                } else {
+                    builder.line_to(point);
+                }
+            }
+        })
+    };
+
+    let color = palette.primary.base.color;
+    let stroke = |alpha: f32| {
+        Stroke::with_color(
+            Stroke {
+                width: 1.0,
+                ..Default::default()
+            },
+            color.scale_alpha(alpha),
+        )
+    };
+
+    frame.stroke(&line(|band| band.0), stroke(0.9));
+    frame.stroke(&line(|band| band.1), stroke(0.5));
+    frame.stroke(&line(|band| band.2), stroke(0.9));
+}
+
+/// Faint vertical lines on the left edge of the first bar of each session, behind the candles
+fn draw_session_separators(
+    frame: &mut canvas::Frame,
+    timeseries: &TimeSeries<KlineDataPoint>,
+    session: SessionSeparator,
+    earliest: u64,
+    latest: u64,
+    interval_to_x: impl Fn(u64) -> f32,
+    cell_width: f32,
+    region: Rectangle,
+    palette: &Extended,
+) {
+    let color = palette.background.strong.color.scale_alpha(0.6);
+
+    for time in timeseries
+        .session_boundaries(session, data::config::timezone::session_timezone())
+        .skip_while(|time| *time < earliest)
+        .take_while(|time| *time <= latest)
+    {
+        let x = interval_to_x(time) - cell_width / 2.0;
+
+        frame.fill_rectangle(
+            Point::new(x, region.y),
+            Size::new(1.0, region.height),
+            color,
+        );
+    }
+}
+
+/// Greyscale volume by price of the visible candles in a fixed strip along the right edge,
+/// each row's length is its buy + sell volume and the POC is marked with a line
+fn draw_candle_volume_profile(
+    frame: &mut canvas::Frame,
+    timeseries: &TimeSeries<KlineDataPoint>,
+    earliest: u64,
+    latest: u64,
+    region: Rectangle,
+    price_to_y: impl Fn(Price) -> f32,
+    scaling: f32,
+    palette: &Extended,
+) {
+    let width = CANDLE_PROFILE_WIDTH / scaling;
+    let right = region.x + region.width;
+    let left = right - width;
+
+    frame.fill_rectangle(
+        Point::new(left, region.y),
+        Size::new(width, region.height),
+        palette.background.base.color,
+    );
+    frame.fill_rectangle(
+        Point::new(left, region.y),
+        Size::new(1.0 / scaling, region.height),
+        palette.background.strong.color,
+    );
+
+    let profile = timeseries.kline_volume_profile(earliest, latest);
+    let Some((&poc, &max_qty)) = profile
+        .iter()
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .filter(|(_, qty)| **qty > 0.0)
+    else {
+        return;
+    };
+
+    let step = timeseries.tick_size;
+    let row_height = (price_to_y(poc.add_steps(1, step)) - price_to_y(poc)).abs();
+    let bar_height = row_height * 0.9;
+    let max_len = width * 0.9;
+
+    for (&price, &qty) in &profile {
+        let len = (qty / max_qty) * max_len;
+
+        frame.fill_rectangle(
+            Point::new(right - len, price_to_y(price) - bar_height / 2.0),
+            Size::new(len, bar_height),
+            palette.background.strongest.color.scale_alpha(0.8),
+        );
+    }
+
+    let poc_y = price_to_y(poc);
+    frame.stroke(
+        &Path::line(Point::new(left, poc_y), Point::new(right, poc_y)),
+        Stroke::with_color(
+            Stroke {
+                width: 1.0,
+                ..Default::default()
+            },
+            palette.warning.base.color,
+        ),
+    );
+}
+
+/// Each bar's delta in a strip along the bottom of the view, drawn from the bar's center to the
+/// right for net buying and to the left for net selling
+fn draw_delta_bars(
+    frame: &mut canvas::Frame,
+    data_source: &PlotData<KlineDataPoint>,
+    earliest: u64,
+    latest: u64,
+    region: Rectangle,
+    interval_to_x: impl Fn(u64) -> f32,
+    cell_width: f32,
+    scaling: f32,
+    palette: &Extended,
+) {
+    let height = DELTA_BAR_PANEL_HEIGHT / scaling;
+    let top = region.y + region.height - height;
+
+    frame.fill_rectangle(
+        Point::new(region.x, top),
+        Size::new(region.width, height),
+        palette.background.base.color,
+    );
+    frame.fill_rectangle(
+        Point::new(region.x, top),
+        Size::new(region.width, 1.0 / scaling),
+        palette.background.strong.color,
+    );
+
+    let max_abs_delta = max_abs_delta_in_range(data_source, earliest, latest);
+    if max_abs_delta <= 0.0 {
+        return;
+    }
+
+    let max_len = cell_width * 0.45;
+    let bar_height = height * 0.4;
+    let bar_y = top + (height - bar_height) / 2.0;
+
+    render_data_source(
+        data_source,
+        frame,
+        earliest,
+        latest,
+        interval_to_x,
+        |frame, x_position, _kline, footprint| {
+            let delta = footprint.delta_qty();
+            if delta == 0.0 {
+                return;
+            }
+
+            let len = (delta.abs() / max_abs_delta) * max_len;
+            let (x, color) = if delta > 0.0 {
+                (x_position, palette.success.base.color)
+            } else {
+                (x_position - len, palette.danger.base.color)
+            };
+
+            frame.fill_rectangle(Point::new(x, bar_y), Size::new(len, bar_height), color);
+        },
+    );
+}
+
+/// Step line through each bar's POC, naked POCs highlighted and the developing one in primary
+/// Volume by price over the visible bars as one histogram along the right edge, buys nearest
+/// the edge, with the value area shaded and its POC outlined
+fn draw_visible_profile(
+    frame: &mut canvas::Frame,
+    timeseries: &TimeSeries<KlineDataPoint>,
+    earliest: u64,
+    latest: u64,
+    region: Rectangle,
+    price_to_y: impl Fn(Price) -> f32,
+    step: PriceStep,
+    palette: &Extended,
+) {
+    let profile = timeseries.volume_profile(earliest, latest);
+    let Some(value_area) = ValueArea::from_profile(&profile, ValueArea::DEFAULT_SHARE) else {
+        return;
+    };
+
+    let max_qty = profile
+        .values()
+        .map(|(buy_qty, sell_qty)| buy_qty + sell_qty)
+        .fold(0.0f32, f32::max);
+    if max_qty <= 0.0 {
+        return;
+    }
+
+    let area_width = region.width * 0.2;
+    let right = region.x + region.width;
+    let row_height =
+        (price_to_y(value_area.poc.add_steps(1, step)) - price_to_y(value_area.poc)).abs();
+
+    let va_top = price_to_y(value_area.high) - row_height / 2.0;
+    let va_bottom = price_to_y(value_area.low) + row_height / 2.0;
+    frame.fill_rectangle(
+        Point::new(right - area_width, va_top),
+        Size::new(area_width, va_bottom - va_top),
+        palette.secondary.weak.color.scale_alpha(0.15),
+    );
+
+    let bar_height = row_height * 0.9;
+
+    for (&price, &(buy_qty, sell_qty)) in &profile {
+        let total_qty = buy_qty + sell_qty;
+        if total_qty <= 0.0 {
+            continue;
+        }
+
+        let alpha = if price >= value_area.low && price <= value_area.high {
+            0.7
+        } else {
+            0.35
+        };
+
+        let top = price_to_y(price) - bar_height / 2.0;
+        let total_len = (total_qty / max_qty) * area_width;
+        let buy_len = total_len * (buy_qty / total_qty);
+
+        frame.fill_rectangle(
+            Point::new(right - total_len, top),
+            Size::new(total_len - buy_len, bar_height),
+            palette.danger.base.color.scale_alpha(alpha),
+        );
+        frame.fill_rectangle(
+            Point::new(right - buy_len, top),
+            Size::new(buy_len, bar_height),
+            palette.success.base.color.scale_alpha(alpha),
+        );
+    }
+
+    let poc_top = price_to_y(value_area.poc) - row_height / 2.0;
+    frame.stroke(
+        &Path::rectangle(
+            Point::new(right - area_width, poc_top),
+            Size::new(area_width, row_height),
+        ),
+        Stroke::with_color(
+            Stroke {
+                width: 1.0,
+                ..Default::default()
+            },
+            palette.warning.base.color,
+        ),
+    );
+}
+
+fn draw_poc_history(
+    frame: &mut canvas::Frame,
+    timeseries: &TimeSeries<KlineDataPoint>,
+    earliest: u64,
+    latest: u64,
+    price_to_y: impl Fn(Price) -> f32,
+    interval_to_x: impl Fn(u64) -> f32,
+    cell_width: f32,
+    palette: &Extended,
+) {
+    if latest < earliest {
+        return;
+    }
+
+    let stroke = |color: Color| {
+        Stroke::with_color(
+            Stroke {
+                width: 1.5,
+                ..Default::default()
+            },
+            color,
+        )
+    };
+
+    // the bar before the visible range only anchors the first step
+    let mut prev_y: Option<f32> = None;
+
+    for (time, price) in timeseries.poc_history().range(..=latest) {
+        let y = price_to_y(*price);
+
+        if *time >= earliest {
+            let status = timeseries
+                .datapoints
+                .get(time)
+                .and_then(|dp| dp.footprint.poc)
+                .map(|poc| poc.status);
+
+            let color = match status {
+                Some(NPoc::Naked) => palette.warning.base.color,
+                Some(NPoc::Filled { .. }) => palette.background.strong.color,
+                Some(NPoc::None) | None => palette.primary.base.color,
+            };
+
+            let x = interval_to_x(*time);
+            let (left, right) = (x - cell_width / 2.0, x + cell_width / 2.0);
+
+            let step = Path::new(|builder| {
+                builder.move_to(Point::new(left, prev_y.unwrap_or(y)));
+                builder.line_to(Point::new(left, y));
+                builder.line_to(Point::new(right, y));
+            });
+            frame.stroke(&step, stroke(color));
+        }
+
+        prev_y = Some(y);
+    }
+}
+
+/// Triangles above bearish and below bullish divergence bars, pointing in the expected direction
+fn draw_divergences(
+    frame: &mut canvas::Frame,
+    timeseries: &TimeSeries<KlineDataPoint>,
+    lookback: usize,
+    earliest: u64,
+    latest: u64,
+    price_to_y: impl Fn(Price) -> f32,
+    interval_to_x: impl Fn(u64) -> f32,
+    cell_height: f32,
+    marker_size: f32,
+    palette: &Extended,
+) {
+    if latest < earliest {
+        return;
+    }
 
-            chart.draw_last_price_line(frame, palette, region);
-        });
+    for (time, divergence) in timeseries.delta_divergences(lookback) {
+        if time < earliest || time > latest {
+            continue;
+        }
+        let Some(dp) = timeseries.datapoints.get(&time) else {
+            continue;
+        };
 
-        let crosshair = chart.cache.crosshair.draw(renderer, bounds_size, |frame| {
-            if let Some(cursor_position) = cursor.position_in(bounds) {
-                let (_, rounded_aggregation) =
-                    chart.draw_crosshair(frame, theme, bounds_size, cursor_position, interaction);
+        let x = interval_to_x(time);
+        let gap = cell_height / 2.0 + marker_size / 2.0;
 
-                draw_crosshair_tooltip(
-                    &self.data_source,
-                    &chart.ticker_info,
-                    frame,
-                    palette,
-                    rounded_aggregation,
-                );
+        let (tip_y, base_y, color) = match divergence {
+            Divergence::Bearish => {
+                let tip = price_to_y(dp.kline.high) - gap;
+                (tip, tip - marker_size, palette.danger.base.color)
             }
-        });
+            Divergence::Bullish => {
+                let tip = price_to_y(dp.kline.low) + gap;
+                (tip, tip + marker_size, palette.success.base.color)
+            }
+        };
 
-        vec![klines, crosshair]
-    }
+        let triangle = Path::new(|builder| {
+            builder.move_to(Point::new(x, tip_y));
+            builder.line_to(Point::new(x - marker_size / 2.0, base_y));
+            builder.line_to(Point::new(x + marker_size / 2.0, base_y));
+            builder.close();
+        });
 
-    fn mouse_interaction(
-        &self,
-        interaction: &Interaction,
-        bounds: Rectangle,
-        cursor: mouse::Cursor,
-    ) -> mouse::Interaction {
-        match interaction {
-            Interaction::Panning { .. } => mouse::Interaction::Grabbing,
-            Interaction::Zoomin { .. } => mouse::Interaction::ZoomIn,
-            Interaction::None | Interaction::Ruler { .. } => {
-                if cursor.is_over(bounds) {
-                    mouse::Interaction::Crosshair
-                } else {
-                    mouse::Interaction::default()
-                }
-            }
-        }
+        frame.fill(&triangle, color);
     }
 }
 
-fn draw_footprint_kline(
+fn draw_compare_line(
     frame: &mut canvas::Frame,
+    timeseries: &TimeSeries<KlineDataPoint>,
+    series: &CompareSeries,
+    earliest: u64,
+    latest: u64,
     price_to_y: impl Fn(Price) -> f32,
-    x_position: f32,
-    candle_width: f32,
-    kline: &Kline,
-    palette: &Extended,
+    interval_to_x: impl Fn(u64) -> f32,
+    color: [u8; 4],
 ) {
-    let y_open = price_to_y(kline.open);
-    let y_high = price_to_y(kline.high);
-    let y_low = price_to_y(kline.low);
-    let y_close = price_to_y(kline.close);
+    if latest < earliest {
+        return;
+    }
 
-    let body_color = if kline.close >= kline.open {
-        palette.success.weak.color
-    } else {
-        palette.danger.weak.color
+    let Some((_, first_dp)) = timeseries.datapoints.range(earliest..=latest).next() else {
+        return;
     };
-    frame.fill_rectangle(
-        Point::new(x_position - (candle_width / 8.0), y_open.min(y_close)),
-        Size::new(candle_width / 4.0, (y_open - y_close).abs()),
-        body_color,
-    );
 
-    let wick_color = if kline.close >= kline.open {
-        palette.success.weak.color
-    } else {
-        palette.danger.weak.color
-    };
-    let marker_line = Stroke::with_color(
-        Stroke {
-            width: 1.0,
-            ..Default::default()
-        },
-        wick_color.scale_alpha(0.6),
-    );
+    let points = series.normalized(first_dp.kline.close.to_f32(), earliest, latest);
+    if points.len() < 2 {
+        return;
+    }
+
+    let path = Path::new(|builder| {
+        for (i, (time, value)) in points.iter().enumerate() {
+            let point = Point::new(interval_to_x(*time), price_to_y(Price::from_f32(*value)));
+            if i == 0 {
+                builder.move_to(point);
+            } else {
+                builder.line_to(point);
+            }
+        }
+    });
+
+    let [r, g, b, a] = color;
+
     frame.stroke(
-        &Path::line(
-            Point::new(x_position, y_high),
-            Point::new(x_position, y_low),
+        &path,
+        Stroke::with_color(
+            Stroke {
+                width: 1.5,
+                ..Default::default()
+            },
+            iced::Color::from_rgba8(r, g, b, f32::from(a) / 255.0),
         ),
-        marker_line,
     );
 }
 
-fn draw_candle_dp(
+/// Order book runs of the visible range as heatmap cells, one bar wide per snapshot bar
+fn draw_depth_layer(
     frame: &mut canvas::Frame,
-    price_to_y: impl Fn(Price) -> f32,
-    candle_width: f32,
+    chart: &ViewState,
+    depth: &HistoricalDepth,
+    data_source: &PlotData<KlineDataPoint>,
+    (earliest, latest): (u64, u64),
+    (highest, lowest): (Price, Price),
     palette: &Extended,
-    x_position: f32,
-    kline: &Kline,
+    opacity: f32,
 ) {
-    let y_open = price_to_y(kline.open);
-    let y_high = price_to_y(kline.high);
-    let y_low = price_to_y(kline.low);
-    let y_close = price_to_y(kline.close);
-
-    let body_color = if kline.close >= kline.open {
-        palette.success.base.color
-    } else {
-        palette.danger.base.color
+    // Tick basis keys are datapoint indexes from the oldest bar, the chart counts from the latest
+    let last_index = match data_source {
+        PlotData::TickBased(tick_aggr) => Some(tick_aggr.datapoints.len().saturating_sub(1) as u64),
+        PlotData::TimeBased(_) => None,
     };
-    frame.fill_rectangle(
-        Point::new(x_position - (candle_width / 2.0), y_open.min(y_close)),
-        Size::new(candle_width, (y_open - y_close).abs()),
-        body_color,
-    );
-
-    let wick_color = if kline.close >= kline.open {
-        palette.success.base.color
-    } else {
-        palette.danger.base.color
+    let (from, to) = match last_index {
+        Some(last) => (last.saturating_sub(latest), last.saturating_sub(earliest)),
+        None => (earliest, latest),
     };
-    frame.fill_rectangle(
-        Point::new(x_position - (candle_width / 8.0), y_high),
-        Size::new(candle_width / 4.0, (y_high - y_low).abs()),
-        wick_color,
-    );
+    let key_to_x = |key: u64| match last_index {
+        Some(last) => (key as f32 - last as f32) * chart.cell_width,
+        None => chart.interval_to_x(key),
+    };
+
+    let market_type = chart.ticker_info.market_type();
+    let max_depth_qty = depth.max_depth_qty_in_range(from, to, highest, lowest, market_type, 0.0);
+    if max_depth_qty <= 0.0 {
+        return;
+    }
+
+    let half_cell = chart.cell_width / 2.0;
+
+    depth
+        .iter_time_filtered(from, to, highest, lowest)
+        .for_each(|(price, runs)| {
+            let y = chart.price_to_y(*price);
+
+            runs.iter()
+                .filter_map(|run| run.with_range(from, to))
+                .for_each(|run| {
+                    let left = key_to_x(run.start_time) - half_cell;
+                    let right = key_to_x(run.until_time) - half_cell;
+                    let alpha = (run.qty() / max_depth_qty).min(1.0) * opacity;
+
+                    let color = if run.is_bid {
+                        palette.success.strong.color
+                    } else {
+                        palette.danger.strong.color
+                    };
+
+                    frame.fill_rectangle(
+                        Point::new(left, y - (chart.cell_height / 2.0)),
+                        Size::new(right - left, chart.cell_height),
+                        color.scale_alpha(alpha),
+                    );
+                });
+        });
+}
+
+/// Copy of `palette` with every color scaled by `alpha`, for drawing a layer see-through
+fn fade_palette(palette: &Extended, alpha: f32) -> Extended {
+    let mut faded = *palette;
+
+    let background = &mut faded.background;
+    for pair in [
+        &mut background.base,
+        &mut background.weakest,
+        &mut background.weaker,
+        &mut background.weak,
+        &mut background.neutral,
+        &mut background.strong,
+        &mut background.stronger,
+        &mut background.strongest,
+        &mut faded.primary.base,
+        &mut faded.primary.weak,
+        &mut faded.primary.strong,
+        &mut faded.secondary.base,
+        &mut faded.secondary.weak,
+        &mut faded.secondary.strong,
+        &mut faded.success.base,
+        &mut faded.success.weak,
+        &mut faded.success.strong,
+        &mut faded.warning.base,
+        &mut faded.warning.weak,
+        &mut faded.warning.strong,
+        &mut faded.danger.base,
+        &mut faded.danger.weak,
+        &mut faded.danger.strong,
+    ] {
+        pair.color = pair.color.scale_alpha(alpha);
+        pair.text = pair.text.scale_alpha(alpha);
+    }
+
+    faded
 }
 
 fn render_data_source<F>(
@@ -1115,9 +4122,9 @@ fn render_data_source<F>(
     earliest: u64,
     latest: u64,
     interval_to_x: impl Fn(u64) -> f32,
-    draw_fn: F,
+    mut draw_fn: F,
 ) where
-    F: Fn(&mut canvas::Frame, f32, &Kline, &KlineTrades),
+    F: FnMut(&mut canvas::Frame, f32, &Kline, &KlineTrades),
 {
     match data_source {
         PlotData::TickBased(tick_aggr) => {
@@ -1278,6 +4285,13 @@ fn draw_all_npocs(
         );
     };
 
+    let (count, visible_only) = match lookback {
+        NpocLookback::Fixed(count) => (count, false),
+        NpocLookback::VisibleRange => (usize::MAX, true),
+    };
+    let in_lookback =
+        |interval: u64| !visible_only || (visible_earliest..=visible_latest).contains(&interval);
+
     match data_source {
         PlotData::TickBased(tick_aggr) => {
             tick_aggr
@@ -1285,7 +4299,8 @@ fn draw_all_npocs(
                 .iter()
                 .rev()
                 .enumerate()
-                .take(lookback)
+                .take(count)
+                .filter(|(index, _)| in_lookback(*index as u64))
                 .filter_map(|(index, dp)| dp.footprint.poc.as_ref().map(|poc| (index as u64, poc)))
                 .for_each(|(interval, poc)| draw_the_line(interval, poc));
         }
@@ -1294,7 +4309,8 @@ fn draw_all_npocs(
                 .datapoints
                 .iter()
                 .rev()
-                .take(lookback)
+                .take(count)
+                .filter(|(timestamp, _)| in_lookback(**timestamp))
                 .filter_map(|(timestamp, dp)| {
                     dp.footprint.poc.as_ref().map(|poc| (*timestamp, poc))
                 })
@@ -1308,24 +4324,13 @@ fn effective_cluster_qty(
     visible_max: f32,
     footprint: &KlineTrades,
     cluster_kind: ClusterKind,
+    cell_text: FootprintCellText,
 ) -> f32 {
-    let individual_max = match cluster_kind {
-        ClusterKind::BidAsk => footprint
-            .trades
-            .values()
-            .map(|group| group.buy_qty.max(group.sell_qty))
-            .fold(0.0_f32, f32::max),
-        ClusterKind::DeltaProfile => footprint
-            .trades
-            .values()
-            .map(|group| (group.buy_qty - group.sell_qty).abs())
-            .fold(0.0_f32, f32::max),
-        ClusterKind::VolumeProfile => footprint
-            .trades
-            .values()
-            .map(|group| group.buy_qty + group.sell_qty)
-            .fold(0.0_f32, f32::max),
-    };
+    let individual_max = footprint
+        .trades
+        .values()
+        .map(|group| cluster_kind.cell_value(cell_text, group))
+        .fold(0.0_f32, f32::max);
 
     let safe = |v: f32| if v <= f32::EPSILON { 1.0 } else { v };
 
@@ -1339,6 +4344,14 @@ fn effective_cluster_qty(
     }
 }
 
+/// Trade counts are whole numbers, everything else is a quantity
+fn cell_text_label(cell_text: FootprintCellText, value: f32) -> String {
+    match cell_text {
+        FootprintCellText::Count if value.abs() < 1_000.0 => format!("{value:.0}"),
+        _ => abbr_large_numbers(value),
+    }
+}
+
 fn draw_clusters(
     frame: &mut canvas::Frame,
     price_to_y: impl Fn(Price) -> f32,
@@ -1348,13 +4361,13 @@ fn draw_clusters(
     candle_width: f32,
     max_cluster_qty: f32,
     palette: &Extended,
-    text_size: f32,
+    (text_size, show_text): (f32, bool),
     tick_size: f32,
-    show_text: bool,
+    bars: &mut CellBars,
     imbalance: Option<(usize, Option<usize>, bool)>,
     kline: &Kline,
     footprint: &KlineTrades,
-    cluster_kind: ClusterKind,
+    (cluster_kind, cell_text): (ClusterKind, FootprintCellText),
     spacing: ContentGaps,
 ) {
     let text_color = palette.background.weakest.text;
@@ -1382,25 +4395,50 @@ fn draw_clusters(
 
                 match cluster_kind {
                     ClusterKind::VolumeProfile => {
-                        super::draw_volume_bar(
-                            frame,
-                            area.bars_left,
-                            y,
-                            group.buy_qty,
-                            group.sell_qty,
-                            max_cluster_qty,
-                            area.bars_width,
-                            cell_height,
-                            palette.success.base.color,
-                            palette.danger.base.color,
-                            bar_alpha,
-                            true,
-                        );
+                        let combined = cell_text.combined(group);
+                        let (buy, sell) = cell_text.sides(group);
+
+                        // bar length follows the combined value, split by each side's share
+                        let split = combined.abs() / (buy + sell).max(f32::EPSILON);
+                        let (buy, sell) = (buy * split, sell * split);
+
+                        if max_cluster_qty > 0.0 {
+                            let bar = CellQuad {
+                                x: area.bars_left,
+                                y: y - (cell_height / 2.0),
+                                width: area.bars_width,
+                                height: cell_height,
+                                buy_qty: 0.0,
+                                sell_qty: 0.0,
+                                max_qty: max_cluster_qty,
+                                color: Color::TRANSPARENT,
+                            };
+                            let sell_bar = CellQuad {
+                                sell_qty: sell,
+                                color: palette.danger.base.color.scale_alpha(bar_alpha),
+                                ..bar
+                            };
+
+                            if sell > 0.0 {
+                                bars.fill(frame, sell_bar);
+                            }
+                            if buy > 0.0 {
+                                bars.fill(
+                                    frame,
+                                    CellQuad {
+                                        x: bar.x + sell_bar.filled_width(),
+                                        buy_qty: buy,
+                                        color: palette.success.base.color.scale_alpha(bar_alpha),
+                                        ..bar
+                                    },
+                                );
+                            }
+                        }
 
                         if show_text {
                             draw_cluster_text(
                                 frame,
-                                &abbr_large_numbers(group.total_qty()),
+                                &cell_text_label(cell_text, combined),
                                 Point::new(area.bars_left, y),
                                 text_size,
                                 text_color,
@@ -1410,11 +4448,12 @@ fn draw_clusters(
                         }
                     }
                     ClusterKind::DeltaProfile => {
-                        let delta = group.delta_qty();
+                        let (buy, sell) = cell_text.sides(group);
+                        let delta = buy - sell;
                         if show_text {
                             draw_cluster_text(
                                 frame,
-                                &abbr_large_numbers(delta),
+                                &cell_text_label(cell_text, delta),
                                 Point::new(area.bars_left, y),
                                 text_size,
                                 text_color,
@@ -1423,17 +4462,24 @@ fn draw_clusters(
                             );
                         }
 
-                        let bar_width = (delta.abs() / max_cluster_qty) * area.bars_width;
-                        if bar_width > 0.0 {
+                        if delta != 0.0 && max_cluster_qty > 0.0 {
                             let color = if delta >= 0.0 {
                                 palette.success.base.color.scale_alpha(bar_alpha)
                             } else {
                                 palette.danger.base.color.scale_alpha(bar_alpha)
                             };
-                            frame.fill_rectangle(
-                                Point::new(area.bars_left, y - (cell_height / 2.0)),
-                                Size::new(bar_width, cell_height),
-                                color,
+                            bars.fill(
+                                frame,
+                                CellQuad {
+                                    x: area.bars_left,
+                                    y: y - (cell_height / 2.0),
+                                    width: area.bars_width,
+                                    height: cell_height,
+                                    buy_qty: delta.abs(),
+                                    sell_qty: 0.0,
+                                    max_qty: max_cluster_qty,
+                                    color,
+                                },
                             );
                         }
                     }
@@ -1505,12 +4551,13 @@ fn draw_clusters(
 
             for (price, group) in &footprint.trades {
                 let y = price_to_y(*price);
+                let (buy, sell) = cell_text.sides(group);
 
-                if group.buy_qty > 0.0 && right_area_width > 0.0 {
+                if buy > 0.0 && right_area_width > 0.0 {
                     if show_text {
                         draw_cluster_text(
                             frame,
-                            &abbr_large_numbers(group.buy_qty),
+                            &cell_text_label(cell_text, buy),
                             Point::new(area.bid_area_left, y),
                             text_size,
                             text_color,
@@ -1519,20 +4566,27 @@ fn draw_clusters(
                         );
                     }
 
-                    let bar_width = (group.buy_qty / max_cluster_qty) * right_area_width;
-                    if bar_width > 0.0 {
-                        frame.fill_rectangle(
-                            Point::new(area.bid_area_left, y - (cell_height / 2.0)),
-                            Size::new(bar_width, cell_height),
-                            palette.success.base.color.scale_alpha(bar_alpha),
+                    if max_cluster_qty > 0.0 {
+                        bars.fill(
+                            frame,
+                            CellQuad {
+                                x: area.bid_area_left,
+                                y: y - (cell_height / 2.0),
+                                width: right_area_width,
+                                height: cell_height,
+                                buy_qty: buy,
+                                sell_qty: 0.0,
+                                max_qty: max_cluster_qty,
+                                color: palette.success.base.color.scale_alpha(bar_alpha),
+                            },
                         );
                     }
                 }
-                if group.sell_qty > 0.0 && left_area_width > 0.0 {
+                if sell > 0.0 && left_area_width > 0.0 {
                     if show_text {
                         draw_cluster_text(
                             frame,
-                            &abbr_large_numbers(group.sell_qty),
+                            &cell_text_label(cell_text, sell),
                             Point::new(area.ask_area_right, y),
                             text_size,
                             text_color,
@@ -1541,12 +4595,19 @@ fn draw_clusters(
                         );
                     }
 
-                    let bar_width = (group.sell_qty / max_cluster_qty) * left_area_width;
-                    if bar_width > 0.0 {
-                        frame.fill_rectangle(
-                            Point::new(area.ask_area_right, y - (cell_height / 2.0)),
-                            Size::new(-bar_width, cell_height),
-                            palette.danger.base.color.scale_alpha(bar_alpha),
+                    if max_cluster_qty > 0.0 {
+                        bars.fill(
+                            frame,
+                            CellQuad {
+                                x: area.ask_area_right,
+                                y: y - (cell_height / 2.0),
+                                width: -left_area_width,
+                                height: cell_height,
+                                buy_qty: 0.0,
+                                sell_qty: sell,
+                                max_qty: max_cluster_qty,
+                                color: palette.danger.base.color.scale_alpha(bar_alpha),
+                            },
                         );
                     }
                 }
@@ -1665,45 +4726,123 @@ fn draw_imbalance_markers(
 
         let rect_height = cell_height / 2.0;
 
-        let alpha_from_ratio = |ratio: f32| -> f32 {
+        let neutral = palette.background.strong.color;
+        let color_from_ratio = |ratio: f32, saturated: Color| -> Color {
             if let Some(scale) = color_scale {
-                let divisor = (scale as f32 / 10.0) - 1.0;
-                (0.2 + 0.8 * ((ratio - 1.0) / divisor).min(1.0)).min(1.0)
+                let t = (ratio * 100.0 / scale.max(1) as f32).clamp(0.0, 1.0);
+                mix_colors(neutral, saturated, t)
             } else {
-                1.0
+                saturated
             }
         };
 
         if diagonal_buy_qty >= sell_qty {
             let required_qty = sell_qty * (100 + threshold) as f32 / 100.0;
             if diagonal_buy_qty > required_qty {
-                let ratio = diagonal_buy_qty / required_qty;
-                let alpha = alpha_from_ratio(ratio);
+                let ratio = diagonal_buy_qty / sell_qty;
+                let color = color_from_ratio(ratio, palette.success.base.color);
 
                 let y = price_to_y(higher_price);
                 frame.fill_rectangle(
                     Point::new(buyside_x, y - (rect_height / 2.0)),
                     Size::new(rect_width, rect_height),
-                    palette.success.weak.color.scale_alpha(alpha),
+                    color,
                 );
             }
         } else {
             let required_qty = diagonal_buy_qty * (100 + threshold) as f32 / 100.0;
             if sell_qty > required_qty {
-                let ratio = sell_qty / required_qty;
-                let alpha = alpha_from_ratio(ratio);
+                let ratio = sell_qty / diagonal_buy_qty;
+                let color = color_from_ratio(ratio, palette.danger.base.color);
 
                 let y = price_to_y(price);
                 frame.fill_rectangle(
                     Point::new(sellside_x, y - (rect_height / 2.0)),
                     Size::new(rect_width, rect_height),
-                    palette.danger.weak.color.scale_alpha(alpha),
+                    color,
                 );
             }
         }
     }
 }
 
+/// Shades each stacked imbalance across the whole cell, with a solid edge on the winning side
+/// so runs stand apart from the single-level markers
+fn draw_stacked_imbalances(
+    frame: &mut canvas::Frame,
+    price_to_y: impl Fn(Price) -> f32,
+    x_position: f32,
+    cell_width: f32,
+    cell_height: f32,
+    tick_size: f32,
+    footprint: &KlineTrades,
+    threshold: usize,
+    min_stack: usize,
+    palette: &Extended,
+) {
+    let step = PriceStep::from_f32(tick_size);
+    let edge_width = (cell_width * 0.04).max(1.0);
+
+    for stack in footprint.stacked_imbalances(threshold, min_stack, step) {
+        let top = price_to_y(stack.high) - (cell_height / 2.0);
+        let bottom = price_to_y(stack.low) + (cell_height / 2.0);
+
+        let cell_left = x_position - (cell_width / 2.0);
+        let (color, edge_x) = if stack.is_buy {
+            let cell_right = x_position + (cell_width / 2.0);
+            (palette.success.base.color, cell_right - edge_width)
+        } else {
+            (palette.danger.base.color, cell_left)
+        };
+
+        frame.fill_rectangle(
+            Point::new(cell_left, top),
+            Size::new(cell_width, bottom - top),
+            color.scale_alpha(0.15),
+        );
+        frame.fill_rectangle(
+            Point::new(edge_x, top),
+            Size::new(edge_width, bottom - top),
+            color,
+        );
+    }
+}
+
+/// Stripe across each cell with liquidations, its width scaled against the largest on screen
+fn draw_liquidations(
+    frame: &mut canvas::Frame,
+    price_to_y: impl Fn(Price) -> f32,
+    x_position: f32,
+    cell_width: f32,
+    cell_height: f32,
+    footprint: &KlineTrades,
+    max_liquidation: f32,
+    palette: &Extended,
+) {
+    let cell_left = x_position - (cell_width / 2.0);
+    let stripe_height = (cell_height * 0.4).max(1.0);
+
+    for (price, qty) in &footprint.liquidations {
+        let width = cell_width * (qty / max_liquidation).clamp(0.05, 1.0);
+
+        frame.fill_rectangle(
+            Point::new(cell_left, price_to_y(*price) - (stripe_height / 2.0)),
+            Size::new(width, stripe_height),
+            palette.warning.base.color.scale_alpha(0.5),
+        );
+    }
+}
+
+/// Linear interpolation between two colors, `t` in `0.0..=1.0`
+fn mix_colors(from: Color, to: Color, t: f32) -> Color {
+    Color {
+        r: from.r + (to.r - from.r) * t,
+        g: from.g + (to.g - from.g) * t,
+        b: from.b + (to.b - from.b) * t,
+        a: from.a + (to.a - from.a) * t,
+    }
+}
+
 impl ContentGaps {
     fn from_view(candle_width: f32, scaling: f32) -> Self {
         let px = |p: f32| p / scaling;
@@ -1754,7 +4893,63 @@ fn draw_crosshair_tooltip(
     palette: &Extended,
     at_interval: u64,
 ) {
-    let kline_opt = match data {
+    if let Some(kline) = kline_at_interval(data, at_interval) {
+        draw_kline_tooltip(kline, ticker_info, frame, palette);
+    }
+}
+
+/// One row per compared ticker under the OHLC readout: close at the hovered bar and
+/// its change since the first visible bar
+fn draw_compare_tooltip(
+    overlay: &CompareOverlay,
+    frame: &mut canvas::Frame,
+    palette: &Extended,
+    earliest: u64,
+    at_interval: u64,
+) {
+    for (i, (compare, line)) in overlay.overlays.iter().zip(&overlay.lines).enumerate() {
+        let closes = &line.series.closes;
+
+        let Some(close) = closes
+            .get(&at_interval)
+            .or_else(|| closes.range(..=at_interval).next_back().map(|(_, c)| c))
+        else {
+            continue;
+        };
+
+        let ticker_info = &line.series.ticker_info;
+        let symbol = ticker_info.ticker.display_symbol_and_type().0;
+        let mut content = format!("{symbol} {}", close.to_string(ticker_info.min_ticksize));
+
+        if let Some((_, first)) = closes.range(earliest..).next()
+            && first.to_f32() > 0.0
+        {
+            let change_pct = (close.to_f32() / first.to_f32() - 1.0) * 100.0;
+            content.push_str(&format!(" {change_pct:+.2}%"));
+        }
+
+        let position = Point::new(8.0, 8.0 + 18.0 * (i + 1) as f32);
+        let [r, g, b, a] = compare.color;
+
+        frame.fill_rectangle(
+            position,
+            Size::new(content.len() as f32 * (TEXT_SIZE * 0.8), 16.0),
+            palette.background.weakest.color.scale_alpha(0.9),
+        );
+        frame.fill_text(canvas::Text {
+            content,
+            position,
+            size: iced::Pixels(12.0),
+            color: iced::Color::from_rgba8(r, g, b, f32::from(a) / 255.0),
+            font: style::AZERET_MONO,
+            ..canvas::Text::default()
+        });
+    }
+}
+
+/// Kline under the crosshair, the latest one when hovering past the end of a time series
+fn kline_at_interval(data: &PlotData<KlineDataPoint>, at_interval: u64) -> Option<&Kline> {
+    match data {
         PlotData::TimeBased(timeseries) => timeseries
             .datapoints
             .iter()
@@ -1780,64 +4975,70 @@ fn draw_crosshair_tooltip(
                 None
             }
         }
-    };
-
-    if let Some(kline) = kline_opt {
-        let change_pct = ((kline.close - kline.open).to_f32() / kline.open.to_f32()) * 100.0;
-        let change_color = if change_pct >= 0.0 {
-            palette.success.base.color
-        } else {
-            palette.danger.base.color
-        };
-
-        let base_color = palette.background.base.text;
-        let precision = ticker_info.min_ticksize;
-
-        let segments = [
-            ("O", base_color, false),
-            (&kline.open.to_string(precision), change_color, true),
-            ("H", base_color, false),
-            (&kline.high.to_string(precision), change_color, true),
-            ("L", base_color, false),
-            (&kline.low.to_string(precision), change_color, true),
-            ("C", base_color, false),
-            (&kline.close.to_string(precision), change_color, true),
-            (&format!("{change_pct:+.2}%"), change_color, true),
-        ];
-
-        let total_width: f32 = segments
-            .iter()
-            .map(|(s, _, _)| s.len() as f32 * (TEXT_SIZE * 0.8))
-            .sum();
+    }
+}
 
-        let position = Point::new(8.0, 8.0);
+/// OHLC readout of `kline` in the top left corner of the chart
+pub(super) fn draw_kline_tooltip(
+    kline: &Kline,
+    ticker_info: &TickerInfo,
+    frame: &mut canvas::Frame,
+    palette: &Extended,
+) {
+    let change_pct = ((kline.close - kline.open).to_f32() / kline.open.to_f32()) * 100.0;
+    let change_color = if change_pct >= 0.0 {
+        palette.success.base.color
+    } else {
+        palette.danger.base.color
+    };
 
-        let tooltip_rect = Rectangle {
-            x: position.x,
-            y: position.y,
-            width: total_width,
-            height: 16.0,
-        };
+    let base_color = palette.background.base.text;
+    let precision = ticker_info.min_ticksize;
+
+    let segments = [
+        ("O", base_color, false),
+        (&kline.open.to_string(precision), change_color, true),
+        ("H", base_color, false),
+        (&kline.high.to_string(precision), change_color, true),
+        ("L", base_color, false),
+        (&kline.low.to_string(precision), change_color, true),
+        ("C", base_color, false),
+        (&kline.close.to_string(precision), change_color, true),
+        (&format!("{change_pct:+.2}%"), change_color, true),
+    ];
+
+    let total_width: f32 = segments
+        .iter()
+        .map(|(s, _, _)| s.len() as f32 * (TEXT_SIZE * 0.8))
+        .sum();
+
+    let position = Point::new(8.0, 8.0);
+
+    let tooltip_rect = Rectangle {
+        x: position.x,
+        y: position.y,
+        width: total_width,
+        height: 16.0,
+    };
 
-        frame.fill_rectangle(
-            tooltip_rect.position(),
-            tooltip_rect.size(),
-            palette.background.weakest.color.scale_alpha(0.9),
-        );
+    frame.fill_rectangle(
+        tooltip_rect.position(),
+        tooltip_rect.size(),
+        palette.background.weakest.color.scale_alpha(0.9),
+    );
 
-        let mut x = position.x;
-        for (text, seg_color, is_value) in segments {
-            frame.fill_text(canvas::Text {
-                content: text.to_string(),
-                position: Point::new(x, position.y),
-                size: iced::Pixels(12.0),
-                color: seg_color,
-                font: style::AZERET_MONO,
-                ..canvas::Text::default()
-            });
-            x += text.len() as f32 * 8.0;
-            x += if is_value { 6.0 } else { 2.0 };
-        }
+    let mut x = position.x;
+    for (text, seg_color, is_value) in segments {
+        frame.fill_text(canvas::Text {
+            content: text.to_string(),
+            position: Point::new(x, position.y),
+            size: iced::Pixels(12.0),
+            color: seg_color,
+            font: style::AZERET_MONO,
+            ..canvas::Text::default()
+        });
+        x += text.len() as f32 * 8.0;
+        x += if is_value { 6.0 } else { 2.0 };
     }
 }
 
@@ -0,0 +1,219 @@
+//! Overview strip of a kline chart's whole history, drawn under the x axis.
+//!
+//! Bars are squeezed into columns of about two pixels, each one colored by the open of its
+//! first bar against the close of its last. Clicking or dragging on the strip brings the bar
+//! under the cursor to the middle of the chart.
+
+use super::{Message, PlotData};
+use data::chart::kline::KlineDataPoint;
+use exchange::Kline;
+
+use iced::widget::canvas::{self, Cache, Event, Geometry};
+use iced::{Point, Rectangle, Renderer, Size, Theme, mouse};
+use std::cell::Ref;
+
+/// Width a column aims for, several bars share one once the history is long enough
+const COLUMN_WIDTH: f32 = 2.0;
+
+pub struct Minimap<'a> {
+    pub data_source: Ref<'a, PlotData<KlineDataPoint>>,
+    /// Bar columns, redrawn along with the chart
+    pub cache: &'a Cache,
+    /// Keys of the visible bars, like the chart's `interval_range`
+    pub visible: (u64, u64),
+}
+
+impl Minimap<'_> {
+    fn len(&self) -> usize {
+        match &*self.data_source {
+            PlotData::TimeBased(timeseries) => timeseries.datapoints.len(),
+            PlotData::TickBased(tick_aggr) => tick_aggr.datapoints.len(),
+        }
+    }
+
+    /// Klines oldest first
+    fn klines(&self) -> Box<dyn Iterator<Item = &Kline> + '_> {
+        match &*self.data_source {
+            PlotData::TimeBased(timeseries) => {
+                Box::new(timeseries.datapoints.values().map(|dp| &dp.kline))
+            }
+            PlotData::TickBased(tick_aggr) => {
+                Box::new(tick_aggr.datapoints.iter().map(|dp| &dp.kline))
+            }
+        }
+    }
+
+    /// Chart key of the `index`th bar from the oldest
+    fn key_at(&self, index: usize) -> Option<u64> {
+        match &*self.data_source {
+            PlotData::TimeBased(timeseries) => timeseries.datapoints.keys().nth(index).copied(),
+            PlotData::TickBased(tick_aggr) => {
+                let len = tick_aggr.datapoints.len();
+                (index < len).then(|| (len - 1 - index) as u64)
+            }
+        }
+    }
+
+    /// Position from the oldest of the bar at `key` or of the first one after it
+    fn index_of(&self, key: u64) -> usize {
+        match &*self.data_source {
+            PlotData::TimeBased(timeseries) => timeseries.datapoints.range(..key).count(),
+            PlotData::TickBased(tick_aggr) => {
+                let len = tick_aggr.datapoints.len() as u64;
+                len.saturating_sub(1).saturating_sub(key) as usize
+            }
+        }
+    }
+
+    fn scroll_to(&self, x: f32, width: f32) -> Option<canvas::Action<Message>> {
+        let len = self.len();
+        if len == 0 || width <= 0.0 {
+            return None;
+        }
+
+        let index = ((x / width).clamp(0.0, 1.0) * len as f32) as usize;
+        let key = self.key_at(index.min(len - 1))?;
+
+        Some(canvas::Action::publish(Message::MinimapScrolled(key)).and_capture())
+    }
+}
+
+impl canvas::Program<Message> for Minimap<'_> {
+    /// Whether the strip is being dragged
+    type State = bool;
+
+    fn update(
+        &self,
+        dragging: &mut bool,
+        event: &Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Option<canvas::Action<Message>> {
+        let Event::Mouse(mouse_event) = event else {
+            return None;
+        };
+
+        match mouse_event {
+            mouse::Event::ButtonPressed(mouse::Button::Left) => {
+                let position = cursor.position_in(bounds)?;
+                *dragging = true;
+                self.scroll_to(position.x, bounds.width)
+            }
+            mouse::Event::CursorMoved { .. } if *dragging => {
+                let position = cursor.position()?;
+                self.scroll_to(position.x - bounds.x, bounds.width)
+            }
+            mouse::Event::ButtonReleased(mouse::Button::Left) => {
+                *dragging = false;
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn draw(
+        &self,
+        _dragging: &bool,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let len = self.len();
+        if len == 0 {
+            return vec![];
+        }
+
+        let palette = theme.extended_palette();
+
+        let bars = self.cache.draw(renderer, bounds.size(), |frame| {
+            let highest = self
+                .klines()
+                .map(|k| k.high.to_f32())
+                .fold(f32::MIN, f32::max);
+            let lowest = self
+                .klines()
+                .map(|k| k.low.to_f32())
+                .fold(f32::MAX, f32::min);
+            let span = (highest - lowest).max(f32::EPSILON);
+            let height = frame.height();
+            let to_y = |price: f32| (highest - price) / span * (height - 2.0) + 1.0;
+
+            let per_column = ((len as f32 * COLUMN_WIDTH / frame.width()).ceil() as usize).max(1);
+            let column_width = frame.width() / len.div_ceil(per_column) as f32;
+
+            let mut klines = self.klines().peekable();
+            let mut x = 0.0;
+            while klines.peek().is_some() {
+                let column = klines.by_ref().take(per_column).collect::<Vec<_>>();
+                let (Some(first), Some(last)) = (column.first(), column.last()) else {
+                    break;
+                };
+
+                let high = column
+                    .iter()
+                    .map(|k| k.high.to_f32())
+                    .fold(f32::MIN, f32::max);
+                let low = column
+                    .iter()
+                    .map(|k| k.low.to_f32())
+                    .fold(f32::MAX, f32::min);
+                let color = if last.close >= first.open {
+                    palette.success.base.color
+                } else {
+                    palette.danger.base.color
+                };
+
+                let top = to_y(high);
+                frame.fill_rectangle(
+                    Point::new(x, top),
+                    Size::new((column_width - 0.5).max(0.5), (to_y(low) - top).max(1.0)),
+                    color,
+                );
+                x += column_width;
+            }
+        });
+
+        let mut window = canvas::Frame::new(renderer, bounds.size());
+        let (start, end) = {
+            let (a, b) = (self.index_of(self.visible.0), self.index_of(self.visible.1));
+            (a.min(b), a.max(b) + 1)
+        };
+        let to_x = |index: usize| index.min(len) as f32 / len as f32 * bounds.width;
+        let (left, right) = (to_x(start), to_x(end).max(to_x(start) + 2.0));
+
+        let rect = Rectangle::new(
+            Point::new(left, 0.0),
+            Size::new(right - left, bounds.height),
+        );
+        window.fill_rectangle(
+            rect.position(),
+            rect.size(),
+            palette.background.strong.color.scale_alpha(0.3),
+        );
+        window.stroke_rectangle(
+            rect.position(),
+            rect.size(),
+            canvas::Stroke::default()
+                .with_color(palette.primary.base.color)
+                .with_width(1.0),
+        );
+
+        vec![bars, window.into_geometry()]
+    }
+
+    fn mouse_interaction(
+        &self,
+        dragging: &bool,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        if *dragging {
+            mouse::Interaction::Grabbing
+        } else if cursor.is_over(bounds) {
+            mouse::Interaction::Pointer
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+}
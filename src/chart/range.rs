@@ -0,0 +1,406 @@
+use super::kline::{draw_candle_dp, draw_kline_tooltip};
+use super::{
+    Action, Basis, Chart, Interaction, Message, PlotConstants, ViewState,
+    scale::linear::PriceInfoLabel,
+};
+use data::aggr::{TickCount, range::RangeBarSeries};
+use data::chart::{
+    Autoscale, KlineChartKind, ViewConfig, indicator::KlineIndicator, kline::CandleStyle, range,
+};
+use data::util::count_decimals;
+use exchange::util::{Price, PriceStep};
+use exchange::{Kline, TickerInfo, Trade};
+
+use iced::widget::canvas::{self, Event, Geometry};
+use iced::{Element, Rectangle, Renderer, Theme, Vector, mouse};
+
+use std::time::Instant;
+
+/// Range bars are drawn with the candlestick sizing
+const CANDLES: KlineChartKind = KlineChartKind::Candles {
+    show_volume_profile: false,
+};
+
+impl Chart for RangeChart {
+    type IndicatorKind = KlineIndicator;
+
+    fn state(&self) -> &ViewState {
+        &self.chart
+    }
+
+    fn mut_state(&mut self) -> &mut ViewState {
+        &mut self.chart
+    }
+
+    fn invalidate_crosshair(&mut self) {
+        self.chart.cache.clear_crosshair();
+    }
+
+    fn invalidate_all(&mut self) {
+        self.invalidate(None);
+    }
+
+    fn view_indicators(&'_ self, _enabled: &[Self::IndicatorKind]) -> Vec<Element<'_, Message>> {
+        vec![]
+    }
+
+    fn visible_timerange(&self) -> Option<(u64, u64)> {
+        None
+    }
+
+    fn interval_keys(&self) -> Option<Vec<u64>> {
+        Some(self.series.bars.iter().map(|bar| bar.start_time).collect())
+    }
+
+    fn session_boundaries(&self) -> Vec<u64> {
+        vec![]
+    }
+
+    fn autoscaled_coords(&self) -> Vector {
+        let chart = self.state();
+        Vector::new(latest_bar_x(chart), chart.translation.y)
+    }
+
+    fn supports_fit_autoscaling(&self) -> bool {
+        true
+    }
+
+    fn supports_follow_latest(&self) -> bool {
+        true
+    }
+
+    fn gpu_layer(&self) -> Option<Element<'_, Message>> {
+        None
+    }
+
+    fn minimap(&self) -> Option<Element<'_, Message>> {
+        None
+    }
+
+    fn is_compact(&self) -> bool {
+        false
+    }
+
+    fn supports_anchored_vwap(&self) -> bool {
+        false
+    }
+
+    fn kline_at(&self, key: u64) -> Option<Kline> {
+        self.series
+            .bars
+            .iter()
+            .rev()
+            .nth(key as usize)
+            .map(|bar| bar.kline())
+    }
+
+    fn oldest_visible_kline(&self) -> Option<Kline> {
+        let chart = self.state();
+        let region = chart.visible_region(chart.bounds.size());
+        let (newest, oldest) = chart.interval_range(&region);
+
+        let last_index = self.series.bars.len().checked_sub(1)? as u64;
+        let oldest = oldest.min(last_index);
+
+        if oldest < newest {
+            return None;
+        }
+        self.kline_at(oldest)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.series.bars.is_empty()
+    }
+
+    fn datapoint_count(&self) -> usize {
+        self.series.bars.len()
+    }
+}
+
+impl PlotConstants for RangeChart {
+    fn min_scaling(&self) -> f32 {
+        CANDLES.min_scaling()
+    }
+
+    fn max_scaling(&self) -> f32 {
+        CANDLES.max_scaling()
+    }
+
+    fn max_cell_width(&self) -> f32 {
+        CANDLES.max_cell_width()
+    }
+
+    fn min_cell_width(&self) -> f32 {
+        CANDLES.min_cell_width()
+    }
+
+    fn max_cell_height(&self) -> f32 {
+        CANDLES.max_cell_height()
+    }
+
+    fn min_cell_height(&self) -> f32 {
+        CANDLES.min_cell_height()
+    }
+
+    fn default_cell_width(&self) -> f32 {
+        CANDLES.default_cell_width()
+    }
+}
+
+/// Candles built from live trades, a new bar starts once price leaves the open by a fixed range.
+///
+/// Bars are laid out by index like tick charts, 0 being the latest one.
+pub struct RangeChart {
+    chart: ViewState,
+    series: RangeBarSeries,
+    raw_trades: Vec<Trade>,
+    config: range::Config,
+    last_tick: Instant,
+}
+
+impl RangeChart {
+    pub fn new(
+        layout: ViewConfig,
+        tick_size: f32,
+        ticker_info: TickerInfo,
+        config: Option<range::Config>,
+    ) -> Self {
+        let step = PriceStep::from_f32(tick_size);
+        let config = config.unwrap_or_default();
+
+        let mut chart = ViewState::new(
+            Basis::Tick(TickCount(1)),
+            step,
+            count_decimals(tick_size),
+            ticker_info,
+            ViewConfig {
+                splits: layout.splits,
+                autoscale: Some(Autoscale::FitToVisible),
+                auto_scroll: layout.auto_scroll,
+                fixed_cells: layout.fixed_cells,
+                price_axis: layout.price_axis,
+                price_rounding: layout.price_rounding,
+            },
+            CANDLES.default_cell_width(),
+            8.0,
+        );
+        chart.translation.x = latest_bar_x(&chart);
+
+        RangeChart {
+            chart,
+            series: RangeBarSeries::new(config.range_ticks, step, &[]),
+            raw_trades: vec![],
+            config,
+            last_tick: Instant::now(),
+        }
+    }
+
+    pub fn visual_config(&self) -> range::Config {
+        self.config
+    }
+
+    pub fn set_visual_config(&mut self, config: range::Config) {
+        if config.range_ticks != self.config.range_ticks {
+            self.series
+                .change_range(config.range_ticks, &self.raw_trades);
+            self.update_last_price();
+        }
+
+        self.config = config;
+        self.invalidate(None);
+    }
+
+    pub fn chart_layout(&self) -> ViewConfig {
+        self.chart.layout()
+    }
+
+    pub fn insert_trades_buffer(&mut self, trades_buffer: &[Trade]) {
+        self.raw_trades.extend_from_slice(trades_buffer);
+
+        let old_len = self.series.bars.len();
+        self.series.insert_trades(trades_buffer);
+
+        let new_bars = self.series.bars.len().saturating_sub(old_len);
+        if new_bars > 0 {
+            let shift_x = new_bars as f32 * self.chart.cell_width;
+            self.chart.hold_viewport(shift_x);
+        }
+
+        self.update_last_price();
+        self.invalidate(None);
+    }
+
+    fn update_last_price(&mut self) {
+        self.chart.last_price = self
+            .series
+            .latest_bar()
+            .map(|bar| PriceInfoLabel::new(bar.close, bar.open));
+    }
+
+    pub fn last_update(&self) -> Instant {
+        self.last_tick
+    }
+
+    pub fn invalidate(&mut self, now: Option<Instant>) -> Option<Action> {
+        let chart = &mut self.chart;
+
+        match chart.layout.autoscale {
+            Some(Autoscale::CenterLatest) => {
+                chart.translation.x = latest_bar_x(chart);
+
+                if let Some(bar) = self.series.latest_bar() {
+                    let y_low = chart.price_to_y(bar.low);
+                    let y_high = chart.price_to_y(bar.high);
+
+                    chart.translation.y = -(y_low + y_high) / 2.0;
+                }
+            }
+            Some(Autoscale::FitToVisible) => {
+                let visible_region = chart.visible_region(chart.bounds.size());
+                let (earliest, latest) = chart.interval_range(&visible_region);
+
+                let (highest, lowest) = self.series.price_scale_in_range(
+                    earliest as usize,
+                    latest as usize,
+                    data::chart::kline::Config::default().scale_padding,
+                    Price::from_f32(0.0),
+                );
+                let price_span = (highest - lowest).to_f32();
+                let tick_size = chart.tick_size.to_f32_lossy();
+
+                if price_span > 0.0 && chart.bounds.height > f32::EPSILON && tick_size > 0.0 {
+                    chart.cell_height = (chart.bounds.height * tick_size) / price_span;
+                    chart.base_price_y = highest;
+                    chart.translation.y = -chart.bounds.height / 2.0;
+                }
+            }
+            None => {}
+        }
+
+        chart.cache.clear_all();
+
+        if let Some(t) = now {
+            self.last_tick = t;
+        }
+        None
+    }
+}
+
+impl canvas::Program<Message> for RangeChart {
+    type State = Interaction;
+
+    fn update(
+        &self,
+        interaction: &mut Interaction,
+        event: &Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Option<canvas::Action<Message>> {
+        super::canvas_interaction(self, interaction, event, bounds, cursor)
+    }
+
+    fn draw(
+        &self,
+        interaction: &Interaction,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let chart = self.state();
+
+        if chart.bounds.width == 0.0 {
+            return vec![];
+        }
+
+        let bounds_size = bounds.size();
+        let palette = theme.extended_palette();
+
+        let bars = chart.cache.main.draw(renderer, bounds_size, |frame| {
+            let center = Vector::new(bounds.width / 2.0, bounds.height / 2.0);
+
+            frame.translate(center);
+            frame.scale(chart.scaling);
+            frame.translate(chart.translation);
+
+            let region = chart.visible_region(frame.size());
+            let (earliest, latest) = chart.interval_range(&region);
+
+            let price_to_y = |price| chart.price_to_y(price);
+            let candle_width = chart.cell_width * 0.8;
+
+            for (index, bar) in self
+                .series
+                .bars_in_range(earliest as usize, latest as usize)
+            {
+                let x_position = chart.interval_to_x(index as u64);
+
+                draw_candle_dp(
+                    frame,
+                    price_to_y,
+                    candle_width,
+                    palette,
+                    x_position,
+                    &bar.kline(),
+                    None,
+                    CandleStyle::Standard,
+                    0.25,
+                );
+            }
+
+            chart.draw_last_price_line(frame, palette, region);
+        });
+
+        let crosshair = chart.cache.crosshair.draw(renderer, bounds_size, |frame| {
+            chart.draw_measurement(frame, theme, bounds_size);
+            chart.draw_price_lines(
+                frame,
+                theme,
+                bounds_size,
+                interaction,
+                cursor.position_in(bounds),
+            );
+
+            if let Some(cursor_position) = cursor.position_in(bounds) {
+                let (_, index) =
+                    chart.draw_crosshair(frame, theme, bounds_size, cursor_position, interaction);
+
+                if let Some(bar) = self.series.bars.iter().rev().nth(index as usize) {
+                    draw_kline_tooltip(&bar.kline(), &chart.ticker_info, frame, palette);
+                }
+            } else {
+                chart.draw_synced_crosshair(frame, theme, bounds_size);
+            }
+        });
+
+        vec![bars, crosshair]
+    }
+
+    fn mouse_interaction(
+        &self,
+        interaction: &Interaction,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        match interaction {
+            Interaction::Panning { .. } => mouse::Interaction::Grabbing,
+            Interaction::Zoomin { .. } => mouse::Interaction::ZoomIn,
+            Interaction::MovingPriceLine { .. } => mouse::Interaction::ResizingVertically,
+            Interaction::None
+            | Interaction::Ruler { .. }
+            | Interaction::Clicked(_)
+            | Interaction::PressStart(..) => {
+                if cursor.is_over(bounds) {
+                    mouse::Interaction::Crosshair
+                } else {
+                    mouse::Interaction::default()
+                }
+            }
+        }
+    }
+}
+
+/// Horizontal translation that puts the latest bar near the right edge
+fn latest_bar_x(chart: &ViewState) -> f32 {
+    0.5 * (chart.bounds.width / chart.scaling) - (8.0 * chart.cell_width / chart.scaling)
+}
@@ -4,7 +4,10 @@ pub mod timeseries;
 use crate::{chart::TEXT_SIZE, style::AZERET_MONO};
 
 use super::{Basis, Interaction, Message};
-use data::{chart::Autoscale, util::round_to_tick};
+use data::{
+    chart::{Autoscale, PriceAxisMode, PriceAxisRounding, format_percent},
+    util::round_to_tick,
+};
 use iced::{
     Alignment, Color, Event, Point, Rectangle, Renderer, Size, Theme, mouse,
     theme::palette::Extended,
@@ -226,6 +229,8 @@ pub struct AxisLabelsX<'a> {
     pub timezone: data::UserTimezone,
     pub chart_bounds: Rectangle,
     pub interval_keys: Option<Vec<u64>>,
+    /// Timestamps of the first bar of each session, labeled ahead of the regular ones
+    pub session_boundaries: Vec<u64>,
     pub autoscaling: Option<Autoscale>,
 }
 
@@ -499,6 +504,22 @@ impl canvas::Program<Message> for AxisLabelsX<'_> {
                     );
 
                     labels.extend(generated_labels);
+
+                    // the separator sits on the left edge of the session's first bar
+                    let half_interval = timeframe.to_milliseconds() / 2;
+                    let span = x_max_region.saturating_sub(x_min_region) as f32;
+
+                    for &timestamp in &self.session_boundaries {
+                        let edge = timestamp.saturating_sub(half_interval);
+                        if span <= 0.0 || edge < x_min_region || edge > x_max_region {
+                            continue;
+                        }
+
+                        let snap_x = (edge - x_min_region) as f32 / span * bounds.width;
+                        let label_text = self.timezone.format_session_label(timestamp as i64);
+
+                        labels.push(AxisLabel::new_x(snap_x, label_text, bounds, false, palette));
+                    }
                 }
             }
 
@@ -541,6 +562,8 @@ pub struct AxisLabelsY<'a> {
     pub cell_height: f32,
     pub basis: Basis,
     pub chart_bounds: Rectangle,
+    pub price_axis: PriceAxisMode,
+    pub price_rounding: PriceAxisRounding,
 }
 
 impl AxisLabelsY<'_> {
@@ -559,6 +582,13 @@ impl AxisLabelsY<'_> {
     fn y_to_price(&self, y: f32) -> f32 {
         self.min - (y / self.cell_height) * self.tick_size
     }
+
+    fn format_price(&self, price: f32) -> String {
+        match self.price_axis.percent_change(price) {
+            Some(change) => format_percent(change),
+            None => format!("{:.*}", self.decimals, price),
+        }
+    }
 }
 
 impl canvas::Program<Message> for AxisLabelsY<'_> {
@@ -645,14 +675,27 @@ impl canvas::Program<Message> for AxisLabelsY<'_> {
 
             let range = highest - lowest;
 
-            let mut all_labels = linear::generate_labels(
-                bounds,
-                lowest,
-                highest,
-                text_size,
-                palette.background.base.text,
-                Some(self.decimals),
-            );
+            let mut all_labels = match (
+                self.price_axis.percent_change(lowest),
+                self.price_axis.percent_change(highest),
+            ) {
+                (Some(lowest_pct), Some(highest_pct)) => linear::generate_percent_labels(
+                    bounds,
+                    lowest_pct,
+                    highest_pct,
+                    text_size,
+                    palette.background.base.text,
+                ),
+                _ => linear::generate_price_labels(
+                    bounds,
+                    lowest,
+                    highest,
+                    text_size,
+                    palette.background.base.text,
+                    self.decimals,
+                    self.price_rounding,
+                ),
+            };
 
             // Last price (priority 2)
             if let Some(label) = self.last_price {
@@ -697,7 +740,7 @@ impl canvas::Program<Message> for AxisLabelsY<'_> {
                 let price = price.to_f32();
 
                 let price_label = LabelContent {
-                    content: format!("{:.*}", self.decimals, price),
+                    content: self.format_price(price),
                     background_color: Some(color),
                     text_color: {
                         if candle_close_label.is_some() {
@@ -732,16 +775,26 @@ impl canvas::Program<Message> for AxisLabelsY<'_> {
                 let y_position = bounds.height - ((rounded_price - lowest) / range * bounds.height);
 
                 let label = LabelContent {
-                    content: format!("{:.*}", self.decimals, rounded_price),
+                    content: self.format_price(rounded_price),
                     background_color: Some(palette.secondary.base.color),
                     text_color: palette.secondary.base.text,
                     text_size: 12.0,
                 };
 
+                // in percent mode the absolute price goes on a second line
+                let is_percent = self.price_axis.percent_change(rounded_price).is_some();
+                let absolute_label = is_percent.then(|| LabelContent {
+                    content: format!("{:.*}", self.decimals, rounded_price),
+                    background_color: Some(palette.secondary.base.color),
+                    text_color: palette.secondary.base.text,
+                    text_size: 11.0,
+                });
+                let content_amt = if absolute_label.is_some() { 2 } else { 1 };
+
                 all_labels.push(AxisLabel::Y {
-                    bounds: calc_label_rect(y_position, 1, text_size, bounds),
+                    bounds: calc_label_rect(y_position, content_amt, text_size, bounds),
                     value_label: label,
-                    timer_label: None,
+                    timer_label: absolute_label,
                 });
             }
 
@@ -1,5 +1,6 @@
 use super::{AxisLabel, LabelContent, calc_label_rect};
-use data::util::abbr_large_numbers;
+use data::chart::{PriceAxisRounding, format_percent};
+use data::util::{abbr_large_numbers, count_decimals};
 use exchange::util::Price;
 
 const MAX_ITERATIONS: usize = 1000;
@@ -23,6 +24,15 @@ fn calc_optimal_ticks(highest: f32, lowest: f32, labels_can_fit: i32) -> (f32, f
     (step, rounded_highest)
 }
 
+/// Multiples of `fixed`, widened to every nth multiple when they'd be denser than the labels
+/// that fit
+fn calc_fixed_ticks(fixed: f32, highest: f32, lowest: f32, labels_can_fit: i32) -> (f32, f32) {
+    let per_label = (highest - lowest).abs() / labels_can_fit.max(1) as f32;
+    let step = fixed * (per_label / fixed).ceil().max(1.0);
+
+    (step, (highest / step).ceil() * step)
+}
+
 pub fn generate_labels(
     bounds: iced::Rectangle,
     lowest: f32,
@@ -30,6 +40,102 @@ pub fn generate_labels(
     text_size: f32,
     text_color: iced::Color,
     decimals: Option<usize>,
+) -> Vec<AxisLabel> {
+    let format = |value: f32| {
+        if let Some(decimals) = decimals {
+            format!("{value:.decimals$}")
+        } else {
+            abbr_large_numbers(value)
+        }
+    };
+
+    labels_with(bounds, lowest, highest, text_size, text_color, format)
+}
+
+/// Price axis labels placed by `rounding`, a fixed step printed with no more decimals
+/// than it has
+pub fn generate_price_labels(
+    bounds: iced::Rectangle,
+    lowest: f32,
+    highest: f32,
+    text_size: f32,
+    text_color: iced::Color,
+    decimals: usize,
+    rounding: PriceAxisRounding,
+) -> Vec<AxisLabel> {
+    match rounding {
+        PriceAxisRounding::Fixed(fixed) if fixed > 0.0 => {
+            let decimals = decimals.min(count_decimals(fixed));
+
+            labels_at(
+                bounds,
+                lowest,
+                highest,
+                text_size,
+                text_color,
+                |value| format!("{value:.decimals$}"),
+                |highest, lowest, fit| calc_fixed_ticks(fixed, highest, lowest, fit),
+            )
+        }
+        _ => generate_labels(
+            bounds,
+            lowest,
+            highest,
+            text_size,
+            text_color,
+            Some(decimals),
+        ),
+    }
+}
+
+/// Labels for a price axis shown as percent change, `lowest` and `highest` already in percent
+pub fn generate_percent_labels(
+    bounds: iced::Rectangle,
+    lowest: f32,
+    highest: f32,
+    text_size: f32,
+    text_color: iced::Color,
+) -> Vec<AxisLabel> {
+    labels_with(
+        bounds,
+        lowest,
+        highest,
+        text_size,
+        text_color,
+        format_percent,
+    )
+}
+
+/// Labels spread over the axis, each value written by `format`
+pub fn labels_with(
+    bounds: iced::Rectangle,
+    lowest: f32,
+    highest: f32,
+    text_size: f32,
+    text_color: iced::Color,
+    format: impl Fn(f32) -> String,
+) -> Vec<AxisLabel> {
+    labels_at(
+        bounds,
+        lowest,
+        highest,
+        text_size,
+        text_color,
+        format,
+        calc_optimal_ticks,
+    )
+}
+
+/// Labels every step from the topmost one, `ticks` giving the step and topmost value
+/// for the range and the number of labels that fit
+fn labels_at(
+    bounds: iced::Rectangle,
+    lowest: f32,
+    highest: f32,
+    text_size: f32,
+    text_color: iced::Color,
+    format: impl Fn(f32) -> String,
+    ticks: impl Fn(f32, f32, i32) -> (f32, f32),
 ) -> Vec<AxisLabel> {
     if !lowest.is_finite() || !highest.is_finite() {
         return Vec::new();
@@ -43,11 +149,7 @@ pub fn generate_labels(
 
     if labels_can_fit <= 1 {
         let label = LabelContent {
-            content: if let Some(decimals) = decimals {
-                format!("{highest:.decimals$}")
-            } else {
-                abbr_large_numbers(highest)
-            },
+            content: format(highest),
             background_color: None,
             text_color,
             text_size,
@@ -60,7 +162,7 @@ pub fn generate_labels(
         }];
     }
 
-    let (step, max) = calc_optimal_ticks(highest, lowest, labels_can_fit);
+    let (step, max) = ticks(highest, lowest, labels_can_fit);
 
     let mut value = max;
     while value > highest {
@@ -72,14 +174,8 @@ pub fn generate_labels(
 
     while value >= lowest && safety_counter < MAX_ITERATIONS {
         if value <= highest + step * 0.5 && value >= lowest - step * 0.5 {
-            let content = if let Some(decimals) = decimals {
-                format!("{value:.decimals$}")
-            } else {
-                abbr_large_numbers(value)
-            };
-
             let label = LabelContent {
-                content,
+                content: format(value),
                 background_color: None,
                 text_color,
                 text_size,
@@ -2,7 +2,7 @@ use crate::chart::scale::{AxisLabel, TEXT_SIZE};
 
 use chrono::{DateTime, Datelike, Months, Offset};
 use data::{
-    UserTimezone,
+    TimestampFormat, UserTimezone,
     util::{reset_to_start_of_month_utc, reset_to_start_of_year_utc},
 };
 use iced::theme::palette::Extended;
@@ -280,7 +280,7 @@ fn daily_labels_gen(
         &calc_x_pos,
         &is_drawable,
         move |dt| dt.checked_add_signed(chrono::Duration::days(1)),
-        with_user_timezone(timezone, |dt| dt.format("%d").to_string()),
+        move |dt| format_label(dt, timezone, TimestampFormat::Day),
         with_user_timezone(timezone, |dt| dt.month() == 1 && dt.day() == 1),
         palette,
     );
@@ -313,7 +313,7 @@ fn monthly_labels_gen(
             dt.checked_add_months(Months::new(1))
                 .map(reset_to_start_of_month_utc)
         },
-        with_user_timezone(timezone, |dt| dt.format("%b").to_string()),
+        move |dt| format_label(dt, timezone, TimestampFormat::Month),
         with_user_timezone(timezone, |dt| dt.month() == 1),
         palette,
     );
@@ -345,7 +345,7 @@ fn yearly_labels_gen(
             dt.checked_add_months(Months::new(12))
                 .map(reset_to_start_of_year_utc)
         },
-        |dt| dt.format("%Y").to_string(),
+        |dt| format_label(dt, UserTimezone::Utc, TimestampFormat::Year),
         |_dt| false,
         palette,
     );
@@ -393,6 +393,19 @@ fn sub_daily_labels_gen(
     }
 }
 
+fn format_label<Tz: chrono::TimeZone>(
+    dt: &DateTime<Tz>,
+    timezone: UserTimezone,
+    format: TimestampFormat,
+) -> String {
+    data::format_timestamp_as(
+        dt.timestamp_millis() as u64,
+        timezone,
+        data::i18n::Language::from_code(data::i18n::current_language()),
+        format,
+    )
+}
+
 fn to_user_fixed_offset<Tz: chrono::TimeZone>(
     dt: &chrono::DateTime<Tz>,
     tz: UserTimezone,
@@ -406,6 +419,7 @@ fn to_user_fixed_offset<Tz: chrono::TimeZone>(
             let offset = chrono::FixedOffset::east_opt(0).unwrap();
             dt.with_timezone(&offset)
         }
+        UserTimezone::Fixed(_) => dt.with_timezone(&tz.offset_at(0)),
     }
 }
 
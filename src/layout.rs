@@ -12,6 +12,9 @@ use crate::i18n;
 pub struct Layout {
     pub id: LayoutId,
     pub dashboard: Dashboard,
+    /// Display settings used instead of the global ones while the layout is active
+    pub timezone: Option<UserTimezone>,
+    pub theme: Option<data::Theme>,
 }
 
 #[derive(Debug, Clone)]
@@ -31,6 +34,16 @@ pub struct SavedState {
     pub audio_cfg: data::AudioStream,
     pub volume_size_unit: exchange::SizeUnit,
     pub language: i18n::Language,
+    pub sync_crosshair: bool,
+    pub toast_cfg: data::config::ToastConfig,
+    pub keyboard_nav: data::config::KeyboardNav,
+    pub data_retention: data::config::DataRetention,
+    pub autosave: data::config::Autosave,
+    pub depth_throttle: data::config::DepthThrottle,
+    pub series_retention: data::config::SeriesRetention,
+    pub zoom_memory: data::config::ZoomMemory,
+    /// The saved state file was unreadable and the backup of the previous save was loaded
+    pub recovered_from_backup: bool,
 }
 
 impl SavedState {
@@ -59,11 +72,42 @@ impl Default for SavedState {
             custom_theme: None,
             audio_cfg: data::AudioStream::default(),
             volume_size_unit: exchange::SizeUnit::Base,
+            sync_crosshair: false,
+            toast_cfg: data::config::ToastConfig::default(),
+            keyboard_nav: data::config::KeyboardNav::default(),
+            data_retention: data::config::DataRetention::default(),
+            autosave: data::config::Autosave::default(),
+            depth_throttle: data::config::DepthThrottle::default(),
+            series_retention: data::config::SeriesRetention::default(),
+            zoom_memory: data::config::ZoomMemory::default(),
+            recovered_from_backup: false,
             language: i18n::Language::English,
         }
     }
 }
 
+impl From<&LayoutManager> for data::Layouts {
+    fn from(layout_manager: &LayoutManager) -> Self {
+        let layouts = layout_manager
+            .layouts
+            .iter()
+            .map(|layout| data::Layout {
+                name: layout.id.name.clone(),
+                dashboard: data::Dashboard::from(&layout.dashboard),
+                timezone: layout.timezone,
+                theme: layout.theme.clone(),
+            })
+            .collect();
+
+        data::Layouts {
+            layouts,
+            active_layout: layout_manager
+                .active_layout_id()
+                .map(|layout| layout.name.to_string()),
+        }
+    }
+}
+
 impl From<&Dashboard> for data::Dashboard {
     fn from(dashboard: &Dashboard) -> Self {
         use pane_grid::Node;
@@ -103,6 +147,8 @@ impl From<&Dashboard> for data::Dashboard {
                     .map(|(pane, window_spec)| (pane.clone(), *window_spec))
                     .collect()
             },
+            price_lines: dashboard.price_lines.clone(),
+            hybrid_weight: dashboard.hybrid_weight,
         }
     }
 }
@@ -145,6 +191,12 @@ impl From<&pane::State> for data::Pane {
                 indicators: indicators.clone(),
                 link_group: pane.link_group,
             },
+            pane::Content::Range { chart, layout } => data::Pane::RangeChart {
+                layout: chart.as_ref().map_or(layout.clone(), |c| c.chart_layout()),
+                stream_type: streams,
+                settings: pane.settings.clone(),
+                link_group: pane.link_group,
+            },
             pane::Content::TimeAndSales(_) => data::Pane::TimeAndSales {
                 stream_type: streams,
                 settings: pane.settings.clone(),
@@ -155,6 +207,21 @@ impl From<&pane::State> for data::Pane {
                 settings: pane.settings.clone(),
                 link_group: pane.link_group,
             },
+            pane::Content::Unloaded { config } => config.as_ref().clone(),
+            pane::Content::Statistics(_) => data::Pane::Statistics {
+                stream_type: streams,
+                settings: pane.settings.clone(),
+                link_group: pane.link_group,
+            },
+            pane::Content::Spread(_) => data::Pane::SpreadChart {
+                stream_type: streams,
+                settings: pane.settings.clone(),
+                link_group: pane.link_group,
+            },
+            pane::Content::MiniCharts { columns } => data::Pane::MiniCharts {
+                columns: *columns,
+                link_group: pane.link_group,
+            },
             pane::Content::Comparison(chart) => {
                 let settings = data::layout::pane::Settings {
                     visual_config: chart.as_ref().map(|c| {
@@ -169,6 +236,20 @@ impl From<&pane::State> for data::Pane {
                     link_group: pane.link_group,
                 }
             }
+            pane::Content::Correlation(matrix) => {
+                let settings = data::layout::pane::Settings {
+                    visual_config: matrix
+                        .as_ref()
+                        .map(|m| data::layout::pane::VisualConfig::Correlation(m.config)),
+                    ..pane.settings.clone()
+                };
+
+                data::Pane::CorrelationMatrix {
+                    stream_type: streams,
+                    settings,
+                    link_group: pane.link_group,
+                }
+            }
         }
     }
 }
@@ -234,6 +315,24 @@ pub fn configuration(pane: data::Pane) -> Configuration<pane::State> {
                 link_group,
             ))
         }
+        data::Pane::RangeChart {
+            layout,
+            stream_type,
+            settings,
+            link_group,
+        } => {
+            let content = pane::Content::Range {
+                chart: None,
+                layout,
+            };
+
+            Configuration::Pane(pane::State::from_config(
+                content,
+                stream_type,
+                settings,
+                link_group,
+            ))
+        }
         data::Pane::ComparisonChart {
             stream_type,
             settings,
@@ -248,6 +347,20 @@ pub fn configuration(pane: data::Pane) -> Configuration<pane::State> {
                 link_group,
             ))
         }
+        data::Pane::CorrelationMatrix {
+            stream_type,
+            settings,
+            link_group,
+        } => {
+            let content = pane::Content::Correlation(None);
+
+            Configuration::Pane(pane::State::from_config(
+                content,
+                stream_type,
+                settings,
+                link_group,
+            ))
+        }
         data::Pane::TimeAndSales {
             stream_type,
             settings,
@@ -276,42 +389,124 @@ pub fn configuration(pane: data::Pane) -> Configuration<pane::State> {
                 link_group,
             ))
         }
+        data::Pane::Statistics {
+            stream_type,
+            settings,
+            link_group,
+        } => {
+            let content = pane::Content::Statistics(None);
+
+            Configuration::Pane(pane::State::from_config(
+                content,
+                stream_type,
+                settings,
+                link_group,
+            ))
+        }
+        data::Pane::SpreadChart {
+            stream_type,
+            settings,
+            link_group,
+        } => {
+            let content = pane::Content::Spread(None);
+
+            Configuration::Pane(pane::State::from_config(
+                content,
+                stream_type,
+                settings,
+                link_group,
+            ))
+        }
+        data::Pane::MiniCharts {
+            columns,
+            link_group,
+        } => Configuration::Pane(pane::State::from_config(
+            pane::Content::MiniCharts { columns },
+            vec![],
+            data::layout::pane::Settings::default(),
+            link_group,
+        )),
+    }
+}
+
+/// State of a leaf pane, `None` for splits
+pub fn pane_state(pane: data::Pane) -> Option<pane::State> {
+    match configuration(pane) {
+        Configuration::Pane(state) => Some(state),
+        Configuration::Split { .. } => None,
+    }
+}
+
+/// Rebuilds a saved dashboard with its panes unloaded,
+/// they are rebuilt once their layout is shown
+fn restore_dashboard(saved: &data::Dashboard, layout_id: Uuid) -> Dashboard {
+    let mut popout_windows = Vec::new();
+
+    for (pane, window_spec) in &saved.popout {
+        let configuration = configuration(pane.clone());
+        popout_windows.push((configuration, *window_spec));
     }
+
+    let mut dashboard = Dashboard::from_config(
+        configuration(saved.pane.clone()),
+        popout_windows,
+        layout_id,
+        saved.price_lines.clone(),
+        saved.hybrid_weight,
+    );
+    dashboard.unload_panes();
+    dashboard
+}
+
+/// Adds the layouts of an imported state next to the existing ones,
+/// renaming those whose name is taken. Returns the names they were added under
+pub fn merge_layouts(manager: &mut LayoutManager, layouts: &[data::Layout]) -> Vec<String> {
+    layouts
+        .iter()
+        .map(|layout| {
+            let unique = Uuid::new_v4();
+            let id = LayoutId {
+                unique,
+                name: manager.ensure_unique_name(&layout.name, unique),
+            };
+            let name = id.name.clone();
+
+            manager.insert_layout(id, restore_dashboard(&layout.dashboard, unique));
+            if let Some(merged) = manager.get_mut(unique) {
+                merged.timezone = layout.timezone;
+                merged.theme = layout.theme.clone();
+            }
+            name
+        })
+        .collect()
 }
 
 pub fn load_saved_state() -> SavedState {
     match data::read_from_file(data::SAVED_STATE_PATH) {
-        Ok(state) => {
+        Ok((state, source)) => {
             let mut de_layouts = vec![];
 
             for layout in &state.layout_manager.layouts {
-                let mut popout_windows = Vec::new();
-
-                for (pane, window_spec) in &layout.dashboard.popout {
-                    let configuration = configuration(pane.clone());
-                    popout_windows.push((configuration, *window_spec));
-                }
-
                 let layout_id = Uuid::new_v4();
+                let dashboard = restore_dashboard(&layout.dashboard, layout_id);
 
-                let dashboard = Dashboard::from_config(
-                    configuration(layout.dashboard.pane.clone()),
-                    popout_windows,
-                    layout_id,
-                );
-
-                de_layouts.push((layout.name.clone(), layout_id, dashboard));
+                de_layouts.push((layout, layout_id, dashboard));
             }
 
             let layout_manager = {
                 let mut layouts = Vec::with_capacity(de_layouts.len());
 
-                for (name, layout_id, dashboard) in de_layouts {
+                for (layout, layout_id, dashboard) in de_layouts {
                     let id = LayoutId {
                         unique: layout_id,
-                        name,
+                        name: layout.name.clone(),
                     };
-                    layouts.push(Layout { id, dashboard });
+                    layouts.push(Layout {
+                        id,
+                        dashboard,
+                        timezone: layout.timezone,
+                        theme: layout.theme.clone(),
+                    });
                 }
 
                 let active_layout =
@@ -332,6 +527,14 @@ pub fn load_saved_state() -> SavedState {
             exchange::fetcher::toggle_trade_fetch(state.trade_fetch_enabled);
             exchange::set_preferred_currency(state.size_in_quote_ccy);
 
+            // files from before notifications could be moved show them beside the sidebar
+            let toast_cfg = state
+                .toast_cfg
+                .unwrap_or_else(|| data::config::ToastConfig {
+                    position: data::config::ToastPosition::beside(state.sidebar.position),
+                    ..Default::default()
+                });
+
             SavedState {
                 theme: state.selected_theme,
                 custom_theme: state.custom_theme,
@@ -342,7 +545,16 @@ pub fn load_saved_state() -> SavedState {
                 scale_factor: state.scale_factor,
                 audio_cfg: state.audio_cfg,
                 volume_size_unit: state.size_in_quote_ccy,
+                sync_crosshair: state.sync_crosshair,
+                toast_cfg,
+                keyboard_nav: state.keyboard_nav,
+                data_retention: state.data_retention,
+                autosave: state.autosave,
+                depth_throttle: state.depth_throttle,
+                series_retention: state.series_retention,
+                zoom_memory: state.zoom_memory,
                 language: state.language,
+                recovered_from_backup: source == data::StateSource::Backup,
             }
         }
         Err(e) => {
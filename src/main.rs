@@ -21,14 +21,20 @@ mod i18n;
 
 rust_i18n::i18n!("locales", fallback = "en-US");
 use rust_i18n::t;
+use data::aggr::throttle::{CoalescedDepth, DepthCoalescer};
 use data::config::theme::default_theme;
 use data::{layout::WindowSpec, sidebar};
+use exchange::adapter::StreamKind;
 use layout::{LayoutId, configuration};
-use modal::{LayoutManager, SettingWindow, ThemeEditor, audio::AudioStream};
+use modal::{
+    LayoutManager, SettingWindow, ThemeEditor, audio::AudioStream, connections::Connections,
+    replay::ReplayControls,
+};
 use modal::{dashboard_modal, main_dialog_modal, setting_window};
 use screen::dashboard::{self, Dashboard};
 use widget::{
-    confirm_dialog_container,
+    confirm_dialog_container, labeled_slider,
+    perf_overlay::PerfOverlay,
     toast::{self, Toast},
     tooltip,
 };
@@ -37,19 +43,17 @@ use widget::{
 use iced::{
     Alignment, Element, Subscription, Task, keyboard, padding,
     widget::{
-        button, column, container, pick_list, row, rule, scrollable, text,
+        button, column, container, mouse_area, pick_list, row, rule, scrollable, text,
         tooltip::Position as TooltipPosition,
     },
 };
+use std::time::Instant;
 use std::{collections::HashMap, vec};
 
 fn main() {
     // 初始化日志系统
     logger::setup(cfg!(debug_assertions)).expect("Failed to initialize logger");
 
-    // 在后台线程中清理旧的市场数据文件
-    std::thread::spawn(data::cleanup_old_market_data);
-
     let _ = iced::daemon(Flowsurface::new, Flowsurface::update, Flowsurface::view)
         .settings(iced::Settings {
             antialiasing: true,  // 开启抗锯齿，使图形更平滑
@@ -94,9 +98,66 @@ struct Flowsurface {
     /// 确认对话框，使用 Option 表示可能不存在
     /// Option<T> 是 Rust 的标准类型，避免空指针错误
     confirm_dialog: Option<screen::ConfirmDialog<Message>>,
+
+    /// 全局行情快速切换框（Ctrl+P），None 表示未打开
+    quick_switcher: Option<modal::QuickSwitcher>,
     
     /// 数量单位设置（基础货币 / 报价货币）
     volume_size_unit: exchange::SizeUnit,
+
+    /// 是否在同一链接组的面板间同步十字线
+    sync_crosshair: bool,
+
+    /// 主窗口角落的性能浮层，关闭时为 None，不做任何统计
+    perf_overlay: Option<PerfOverlay>,
+
+    /// 离线回放：把导出的K线文件按时间推送给聚焦面板的K线流
+    replay: ReplayControls,
+
+    /// 连接面板：各数据流的健康状态，选中的数据流显示最近的错误
+    connections: Connections,
+
+    /// 通知的位置、显示时长和最大数量
+    toast_cfg: data::config::ToastConfig,
+
+    /// 键盘平移、缩放聚焦面板的步长
+    keyboard_nav: data::config::KeyboardNav,
+
+    /// 下载的行情数据保留天数，超过后被清理
+    data_retention: data::config::DataRetention,
+
+    /// 数据文件夹的大小（字节），统计完成前为 None
+    data_folder_size: Option<u64>,
+
+    /// 手动清理是否正在进行，避免重复触发
+    data_cleanup_running: bool,
+
+    /// 后台定时保存布局状态的间隔
+    autosave: data::config::Autosave,
+
+    /// 深度更新的节流间隔，为 0 时每次更新都立即处理
+    depth_throttle: data::config::DepthThrottle,
+
+    /// 每个图表在内存中保留的K线上限
+    series_retention: data::config::SeriesRetention,
+
+    /// 切换交易对时是否恢复面板在该交易对上的缩放
+    zoom_memory: data::config::ZoomMemory,
+
+    /// 节流期间暂存的各数据流最新深度及其间的成交
+    depth_coalescer: DepthCoalescer<StreamKind>,
+
+    /// 最近一次已知的主窗口位置与大小，自动保存时无需再查询窗口
+    main_window_spec: Option<WindowSpec>,
+
+    /// 上次写入磁盘的状态的哈希值，内容未变化时跳过自动保存
+    saved_state_hash: Option<u64>,
+
+    /// 恢复默认设置时是否保留现有布局
+    reset_keep_layouts: bool,
+
+    /// 导入他人的完整配置时是否一并应用其中的设置
+    import_profile_settings: bool,
     
     /// UI 缩放系数（0.8 - 1.5）
     ui_scale_factor: data::ScaleFactor,
@@ -128,6 +189,7 @@ struct Flowsurface {
 /// - Debug trait 允许使用 {:?} 格式化输出
 /// - Clone trait 允许显式复制值（Rust 默认是移动语义）
 /// - enum 是标签联合（Tagged Union），编译器保证类型安全
+///
 /// ============================================================================
 #[derive(Debug, Clone)]
 enum Message {
@@ -173,9 +235,47 @@ enum Message {
     
     /// 返回上一级（ESC 键）
     GoBack,
+    /// 打开或关闭快速切换框（Ctrl+P）
+    ToggleQuickSwitcher,
+    QuickSwitcher(modal::quick_switcher::Message),
+    NavigateFocusedPane(chart::NavigationKey),
+    KeyboardNavChanged(data::config::KeyboardNav),
     
     /// 打开数据文件夹请求
     DataFolderRequested,
+    /// 修改行情数据保留天数
+    DataRetentionChanged(data::config::DataRetention),
+
+    /// 自动保存间隔变更
+    AutosaveChanged(data::config::Autosave),
+
+    /// 自动保存定时器触发
+    AutosaveTick,
+
+    /// 深度更新节流间隔变更
+    DepthThrottleChanged(data::config::DepthThrottle),
+
+    /// 图表K线保留上限变更
+    SeriesRetentionChanged(data::config::SeriesRetention),
+
+    /// 恢复默认设置时是否保留布局
+    ToggleResetKeepLayouts(bool),
+
+    /// 将保存的状态重置为默认值并重启，bool 表示是否保留布局
+    ResetSettings(bool),
+
+    /// 导入配置时是否一并应用其中的设置
+    ToggleImportProfileSettings(bool),
+    /// 打开文件选择器，导入他人导出的完整配置
+    ImportProfile,
+    /// 选中了要导入的配置文件，合并其中的布局
+    ProfilePicked(std::path::PathBuf),
+    /// 立即清理过期的行情数据
+    CleanupDataRequested,
+    /// 清理完成，附带删除的文件数和字节数
+    DataCleanupFinished(data::CleanupReport),
+    /// 数据文件夹大小统计完成
+    DataFolderSized(u64),
     
     /// 主题选择变更
     ThemeSelected(data::Theme),
@@ -185,10 +285,24 @@ enum Message {
     
     /// 时区设置变更
     SetTimezone(data::UserTimezone),
+
+    /// 当前布局是否使用独立的时区与主题
+    ToggleLayoutDisplay(bool),
     
     /// 切换历史交易数据获取（仅 Binance）
     /// bool 表示开启/关闭
     ToggleTradeFetch(bool),
+
+    /// 切换链接面板间的十字线同步
+    ToggleSyncCrosshair(bool),
+
+    /// 切换按交易对记住面板缩放
+    /// 关闭后切换交易对总是从默认缩放开始
+    ToggleZoomMemory(bool),
+
+    /// 切换主窗口的性能浮层（帧率、更新耗时、数据流与数据点数）
+    TogglePerfOverlay(bool),
+    ToastConfigChanged(data::config::ToastConfig),
     
     /// 应用数量单位设置（需要重启）
     ApplyVolumeSizeUnit(exchange::SizeUnit),
@@ -205,10 +319,21 @@ enum Message {
     
     /// 布局管理器消息
     Layouts(modal::layout_manager::Message),
+
+    /// 按序号切换布局（Ctrl+1 ~ Ctrl+9），序号从 0 开始
+    SelectLayoutByIndex(usize),
     
     /// 音频流消息
     AudioStream(modal::audio::Message),
 
+    /// 回放控制消息
+    Replay(modal::replay::Message),
+    /// 回放文件读取完成，K线将推送给该数据流
+    ReplayLoaded(StreamKind, Result<Vec<exchange::Kline>, String>),
+
+    /// 连接面板消息
+    Connections(modal::connections::Message),
+
     // 语言切换
     LanguageChanged(i18n::Language),
 }
@@ -229,6 +354,8 @@ impl Flowsurface {
         };
 
         let (sidebar, launch_sidebar) = dashboard::Sidebar::new(&saved_state);
+        data::config::timezone::set_session_timezone(saved_state.timezone);
+        data::config::set_series_retention(saved_state.series_retention);
 
         let mut state = Self {
             main_window: window::Window::new(main_window_id),
@@ -237,15 +364,40 @@ impl Flowsurface {
             audio_stream: AudioStream::new(saved_state.audio_cfg),
             sidebar,
             confirm_dialog: None,
+            quick_switcher: None,
             timezone: saved_state.timezone,
             ui_scale_factor: saved_state.scale_factor,
             volume_size_unit: saved_state.volume_size_unit,
+            sync_crosshair: saved_state.sync_crosshair,
+            perf_overlay: None,
+            replay: ReplayControls::default(),
+            connections: Connections::default(),
+            toast_cfg: saved_state.toast_cfg,
+            keyboard_nav: saved_state.keyboard_nav,
+            data_retention: saved_state.data_retention,
+            data_folder_size: None,
+            data_cleanup_running: false,
+            autosave: saved_state.autosave,
+            depth_throttle: saved_state.depth_throttle,
+            series_retention: saved_state.series_retention,
+            zoom_memory: saved_state.zoom_memory,
+            depth_coalescer: DepthCoalescer::default(),
+            main_window_spec: saved_state.main_window,
+            saved_state_hash: None,
+            reset_keep_layouts: false,
+            import_profile_settings: false,
             theme: saved_state.theme,
             notifications: vec![],
             setting_window: None,
             language: i18n::Language::English,
         };
 
+        if saved_state.recovered_from_backup {
+            state.notifications.push(Toast::warn(
+                "Saved state was unreadable, restored it from the last backup",
+            ));
+        }
+
         let active_layout_id = state.layout_manager.active_layout_id().unwrap_or(
             &state
                 .layout_manager
@@ -256,12 +408,23 @@ impl Flowsurface {
         );
         let load_layout = state.load_layout(active_layout_id.unique, main_window_id);
 
+        // 在后台静默清理过期的行情数据文件，完成后统计数据文件夹大小
+        let retention_days = state.data_retention.days;
+        let startup_cleanup = Task::perform(
+            async move {
+                data::cleanup_old_market_data(retention_days);
+                data::data_folder_size()
+            },
+            Message::DataFolderSized,
+        );
+
         (
             state,
             open_main_window
                 .discard()
                 .chain(load_layout)
-                .chain(launch_sidebar.map(Message::Sidebar)),
+                .chain(launch_sidebar.map(Message::Sidebar))
+                .chain(startup_cleanup),
         )
     }
 
@@ -269,8 +432,16 @@ impl Flowsurface {
         match message {
             Message::MarketWsEvent(event) => {
                 let main_window_id = self.main_window.id;
+                let is_replaying = self.replay.is_active();
                 let dashboard = self.active_dashboard_mut();
 
+                // 回放推送的K线不是来自交易所，不计入连接状态
+                if !is_replaying {
+                    dashboard
+                        .stream_health
+                        .record(&event, chrono::Utc::now().timestamp_millis() as u64);
+                }
+
                 match event {
                     exchange::Event::Connected(exchange) => {
                         log::info!("a stream connected to {exchange} WS");
@@ -284,56 +455,105 @@ impl Flowsurface {
                         depth,
                         trades_buffer,
                     ) => {
-                        let task = dashboard
-                            .update_depth_and_trades(
+                        // 节流时只保留最新的深度并累积其间的成交，到期后一并处理
+                        let update = match self.depth_throttle.interval() {
+                            Some(interval) => self.depth_coalescer.push(
                                 &stream,
                                 depth_update_t,
-                                &depth,
+                                depth,
                                 &trades_buffer,
-                                main_window_id,
-                            )
-                            .map(move |msg| Message::Dashboard {
-                                layout_id: None,
-                                event: msg,
-                            });
+                                interval,
+                                Instant::now(),
+                            ),
+                            None => Some(CoalescedDepth {
+                                depth_update_t,
+                                depth,
+                                trades: trades_buffer.into_vec(),
+                            }),
+                        };
 
-                        if let Err(err) = self.audio_stream.try_play_sound(&stream, &trades_buffer)
+                        return update.map_or_else(Task::none, |update| {
+                            self.apply_depth_update(&stream, &update)
+                        });
+                    }
+                    exchange::Event::KlineReceived(stream, kline) => {
+                        let (task, play_sound) =
+                            dashboard.update_latest_klines(&stream, &kline, main_window_id);
+
+                        if play_sound
+                            && let Err(err) = self.audio_stream.play_alert(
+                                data::audio::AlertKind::PriceAlert,
+                                audio::SoundType::HardBuy,
+                            )
                         {
-                            log::error!("Failed to play sound: {err}");
+                            log::error!("Failed to play price alert sound: {err}");
                         }
 
-                        return task;
+                        return task.map(move |msg| Message::Dashboard {
+                            layout_id: None,
+                            event: msg,
+                        });
                     }
-                    exchange::Event::KlineReceived(stream, kline) => {
-                        return dashboard
-                            .update_latest_klines(&stream, &kline, main_window_id)
-                            .map(move |msg| Message::Dashboard {
-                                layout_id: None,
-                                event: msg,
-                            });
+                    exchange::Event::LiquidationReceived(stream, liquidation) => {
+                        dashboard.insert_liquidation(&stream, &liquidation, main_window_id);
+                    }
+                    exchange::Event::FetchProgress(stream, progress) => {
+                        dashboard.update_fetch_progress(&stream, progress, main_window_id);
                     }
                 }
             }
             Message::Tick(now) => {
                 let main_window_id = self.main_window.id;
-
-                return self
+                let zoom_memory = self.zoom_memory;
+                let started = Instant::now();
+
+                // 数据流安静下来后，暂存的最后一次深度更新也要显示出来
+                let flushed = self
+                    .depth_throttle
+                    .interval()
+                    .map(|interval| self.depth_coalescer.flush_due(interval, now))
+                    .unwrap_or_default();
+                let depth_updates = flushed
+                    .into_iter()
+                    .map(|(stream, update)| self.apply_depth_update(&stream, &update))
+                    .collect::<Vec<_>>();
+
+                let tick = self
                     .active_dashboard_mut()
-                    .tick(now, main_window_id)
+                    .tick(now, main_window_id, zoom_memory)
                     .map(move |msg| Message::Dashboard {
                         layout_id: None,
                         event: msg,
                     });
+
+                if self.perf_overlay.is_some() {
+                    let dashboard = self.active_dashboard();
+                    let (streams, datapoints) = (
+                        dashboard.stream_count(),
+                        dashboard.datapoint_count(main_window_id),
+                    );
+
+                    if let Some(overlay) = &mut self.perf_overlay {
+                        overlay.record(now, started.elapsed(), streams, datapoints);
+                    }
+                }
+
+                let replayed = match self.replay.tick(now) {
+                    Some(action) => self.replay_action(action),
+                    None => Task::none(),
+                };
+
+                return Task::batch(depth_updates.into_iter().chain([tick, replayed]));
             }
             Message::WindowEvent(event) => match event {
                 window::Event::CloseRequested(window) => {
                     let main_window = self.main_window.id;
                     
                     // 检查是否是设置窗口的关闭请求
-                    if let Some((_, window_id)) = &self.setting_window {
-                        if *window_id == window {
-                            return Task::done(Message::SettingWindowClosed(window));
-                        }
+                    if let Some((_, window_id)) = &self.setting_window
+                        && *window_id == window
+                    {
+                        return Task::done(Message::SettingWindowClosed(window));
                     }
                     
                     let dashboard = self.active_dashboard_mut();
@@ -352,6 +572,25 @@ impl Flowsurface {
 
                     return window::collect_window_specs(active_windows, Message::ExitRequested);
                 }
+                // 记下主窗口当前的位置与大小，自动保存直接写入
+                window::Event::Opened(window, position, size) => {
+                    if window == self.main_window.id {
+                        if let Some(position) = position {
+                            self.set_main_window_position(position);
+                        }
+                        self.set_main_window_size(size);
+                    }
+                }
+                window::Event::Moved(window, position) => {
+                    if window == self.main_window.id {
+                        self.set_main_window_position(position);
+                    }
+                }
+                window::Event::Resized(window, size) => {
+                    if window == self.main_window.id {
+                        self.set_main_window_size(size);
+                    }
+                }
             },
             Message::ExitRequested(windows) => {
                 self.save_state_to_disk(&windows);
@@ -364,7 +603,17 @@ impl Flowsurface {
             Message::GoBack => {
                 let main_window = self.main_window.id;
 
-                if self.confirm_dialog.is_some() {
+                // 右键菜单最先关闭，其次是图表上的测量结果
+                if self.active_dashboard_mut().close_context_menus(main_window) {
+                    return Task::none();
+                }
+                if self.active_dashboard_mut().clear_measurements(main_window) {
+                    return Task::none();
+                }
+
+                if self.quick_switcher.is_some() {
+                    self.quick_switcher = None;
+                } else if self.confirm_dialog.is_some() {
                     self.confirm_dialog = None;
                 } else if self.sidebar.active_menu().is_some() {
                     self.sidebar.set_menu(None);
@@ -380,8 +629,45 @@ impl Flowsurface {
                     }
                 }
             }
+            Message::ToggleQuickSwitcher => {
+                if self.quick_switcher.take().is_none() {
+                    self.quick_switcher = Some(modal::QuickSwitcher::new());
+                    return iced::widget::operation::focus(modal::QuickSwitcher::input_id());
+                }
+            }
+            Message::QuickSwitcher(message) => {
+                let Some(switcher) = self.quick_switcher.as_mut() else {
+                    return Task::none();
+                };
+
+                if let Some(modal::quick_switcher::Action::Open(ticker_info)) =
+                    switcher.update(message, &self.sidebar.tickers_table)
+                {
+                    self.quick_switcher = None;
+                    let main_window_id = self.main_window.id;
+                    let zoom_memory = self.zoom_memory;
+
+                    return self
+                        .active_dashboard_mut()
+                        .open_ticker(main_window_id, ticker_info, zoom_memory)
+                        .map(move |msg| Message::Dashboard {
+                            layout_id: None,
+                            event: msg,
+                        });
+                }
+            }
+            Message::NavigateFocusedPane(key) => {
+                let main_window = self.main_window.id;
+                let steps = self.keyboard_nav;
+
+                self.active_dashboard_mut()
+                    .navigate_focused(main_window, key, steps);
+            }
+            Message::KeyboardNavChanged(steps) => {
+                self.keyboard_nav = steps;
+            }
             Message::ThemeSelected(theme) => {
-                self.theme = theme.clone();
+                self.set_theme(theme);
             }
             Message::Dashboard {
                 layout_id: id,
@@ -396,7 +682,13 @@ impl Flowsurface {
                 let layout_id = id.unwrap_or(active_layout.unique);
 
                 if let Some(dashboard) = self.layout_manager.mut_dashboard(layout_id) {
-                    let (main_task, event) = dashboard.update(msg, &main_window, &layout_id);
+                    let (main_task, event) = dashboard.update(
+                        msg,
+                        &main_window,
+                        &layout_id,
+                        self.sync_crosshair,
+                        self.zoom_memory,
+                    );
 
                     let additional_task = match event {
                         Some(dashboard::Event::DistributeFetchedData {
@@ -454,6 +746,12 @@ impl Flowsurface {
                                 }
                             }
                         }
+                        Some(dashboard::Event::ExportPng {
+                            pane_id,
+                            scale,
+                            size,
+                            path,
+                        }) => self.export_pane_png(layout_id, pane_id, scale, size, path),
                         None => Task::none(),
                     };
                       // 处理额外的 dashboard 事件
@@ -471,7 +769,24 @@ impl Flowsurface {
                 }
             }
             Message::SetTimezone(tz) => {
-                self.timezone = tz;
+                match self.layout_manager.active_layout_mut() {
+                    Some(layout) if layout.timezone.is_some() => layout.timezone = Some(tz),
+                    _ => self.timezone = tz,
+                }
+                data::config::timezone::set_session_timezone(tz);
+            }
+            Message::ToggleLayoutDisplay(enabled) => {
+                let (timezone, theme) = (self.timezone, self.theme.clone());
+
+                if let Some(layout) = self.layout_manager.active_layout_mut() {
+                    layout.timezone = enabled.then_some(timezone);
+                    layout.theme = enabled.then_some(theme);
+                }
+                data::config::timezone::set_session_timezone(self.active_timezone());
+
+                let main_window = self.main_window.id;
+                self.active_dashboard_mut()
+                    .invalidate_all_panes(main_window);
             }
             Message::ScaleFactorChanged(value) => {
                 self.ui_scale_factor = value;
@@ -487,6 +802,25 @@ impl Flowsurface {
                     self.confirm_dialog = None;
                 }
             }
+            Message::ToastConfigChanged(cfg) => {
+                self.toast_cfg = cfg;
+            }
+            Message::TogglePerfOverlay(checked) => {
+                self.perf_overlay = checked.then(PerfOverlay::default);
+            }
+            Message::ToggleZoomMemory(checked) => {
+                self.zoom_memory.per_ticker = checked;
+            }
+            Message::ToggleSyncCrosshair(checked) => {
+                self.sync_crosshair = checked;
+
+                if !checked {
+                    let main_window = self.main_window.id;
+                    self.layout_manager
+                        .iter_dashboards_mut()
+                        .for_each(|dashboard| dashboard.clear_synced_crosshair(main_window));
+                }
+            }
             Message::ToggleDialogModal(dialog) => {
                 self.confirm_dialog = dialog;
             }
@@ -495,37 +829,7 @@ impl Flowsurface {
 
                 match action {
                     Some(modal::layout_manager::Action::Select(layout)) => {
-                        let active_popout_keys = self
-                            .active_dashboard()
-                            .popout
-                            .keys()
-                            .copied()
-                            .collect::<Vec<_>>();
-
-                        let window_tasks = Task::batch(
-                            active_popout_keys
-                                .iter()
-                                .map(|&popout_id| window::close::<window::Id>(popout_id))
-                                .collect::<Vec<_>>(),
-                        )
-                        .discard();
-
-                        let old_layout_id = self
-                            .layout_manager
-                            .active_layout_id()
-                            .as_ref()
-                            .map(|layout| layout.unique);
-
-                        return window::collect_window_specs(
-                            active_popout_keys,
-                            dashboard::Message::SavePopoutSpecs,
-                        )
-                        .map(move |msg| Message::Dashboard {
-                            layout_id: old_layout_id,
-                            event: msg,
-                        })
-                        .chain(window_tasks)
-                        .chain(self.load_layout(layout, self.main_window.id));
+                        return self.switch_layout(layout);
                     }
                     Some(modal::layout_manager::Action::Clone(id)) => {
                         let manager = &mut self.layout_manager;
@@ -535,10 +839,12 @@ impl Flowsurface {
                                 layout.id.name.clone(),
                                 layout.id.unique,
                                 data::Dashboard::from(&layout.dashboard),
+                                (layout.timezone, layout.theme.clone()),
                             )
                         });
 
-                        if let Some((name, old_id, ser_dashboard)) = source_data {
+                        if let Some((name, old_id, ser_dashboard, (timezone, theme))) = source_data
+                        {
                             let new_uid = uuid::Uuid::new_v4();
                             let new_layout = LayoutId {
                                 unique: new_uid,
@@ -552,25 +858,209 @@ impl Flowsurface {
                                 popout_windows.push((configuration, *window_spec));
                             }
 
-                            let dashboard = Dashboard::from_config(
+                            let mut dashboard = Dashboard::from_config(
                                 configuration(ser_dashboard.pane.clone()),
                                 popout_windows,
                                 old_id,
+                                ser_dashboard.price_lines.clone(),
+                                ser_dashboard.hybrid_weight,
                             );
+                            dashboard.unload_panes();
 
                             manager.insert_layout(new_layout.clone(), dashboard);
+
+                            if let Some(layout) = manager.get_mut(new_uid) {
+                                layout.timezone = timezone;
+                                layout.theme = theme;
+                            }
                         }
                     }
                     None => {}
                 }
             }
-            Message::AudioStream(message) => self.audio_stream.update(message),
+            Message::SelectLayoutByIndex(index) => {
+                let active = self.layout_manager.active_layout_id().map(|l| l.unique);
+
+                if let Some(layout) = self.layout_manager.layout_at(index)
+                    && Some(layout) != active
+                {
+                    return self.switch_layout(layout);
+                }
+            }
+            Message::AudioStream(message) => {
+                if let Err(err) = self.audio_stream.update(message) {
+                    self.notifications.push(Toast::error(err.to_string()));
+                }
+            }
+            Message::Replay(message) => {
+                if let Some(action) = self.replay.update(message) {
+                    return self.replay_action(action);
+                }
+            }
+            Message::Connections(message) => self.connections.update(message),
+            Message::ReplayLoaded(stream, result) => {
+                let StreamKind::Kline { timeframe, .. } = stream else {
+                    return Task::none();
+                };
+
+                match result.map(|klines| data::replay::Replay::new(klines, timeframe)) {
+                    Ok(Some(replay)) => {
+                        let action = self.replay.start(stream, replay);
+                        return self.replay_action(action);
+                    }
+                    Ok(None) => self.notifications.push(Toast::error("No klines found")),
+                    Err(err) => self
+                        .notifications
+                        .push(Toast::error(format!("Replay failed: {err}"))),
+                }
+            }
             Message::DataFolderRequested => {
                 if let Err(err) = data::open_data_folder() {
                     self.notifications
                         .push(Toast::error(format!("Failed to open data folder: {err}")));
                 }
             }
+            Message::DataRetentionChanged(retention) => {
+                self.data_retention = retention;
+            }
+            Message::AutosaveChanged(autosave) => {
+                self.autosave = autosave;
+            }
+            Message::DepthThrottleChanged(throttle) => {
+                self.depth_throttle = throttle;
+
+                if throttle.interval().is_none() {
+                    let flushed = self.depth_coalescer.flush_all();
+                    return Task::batch(
+                        flushed
+                            .into_iter()
+                            .map(|(stream, update)| self.apply_depth_update(&stream, &update))
+                            .collect::<Vec<_>>(),
+                    );
+                }
+            }
+            Message::SeriesRetentionChanged(retention) => {
+                self.series_retention = retention;
+                data::config::set_series_retention(retention);
+            }
+            Message::ToggleResetKeepLayouts(keep) => {
+                self.reset_keep_layouts = keep;
+            }
+            Message::ResetSettings(keep_layouts) => {
+                self.confirm_dialog = None;
+
+                let layout_manager = if keep_layouts {
+                    data::Layouts::from(&self.layout_manager)
+                } else {
+                    data::Layouts::from(&LayoutManager::new())
+                };
+                let state = data::State {
+                    layout_manager,
+                    ..data::State::default()
+                };
+
+                let written = serde_json::to_string(&state)
+                    .map_err(|e| e.to_string())
+                    .and_then(|json| {
+                        data::write_json_to_file(&json, data::SAVED_STATE_PATH)
+                            .map_err(|e| e.to_string())
+                    });
+
+                if let Err(err) = written {
+                    self.notifications
+                        .push(Toast::error(format!("Failed to reset settings: {err}")));
+                    return Task::none();
+                }
+
+                // 重启会重新读取刚写入的默认状态，通知要在重启之后加入
+                let restart = self.restart();
+                self.notifications
+                    .push(Toast::info("Settings were reset to defaults"));
+                return restart;
+            }
+            Message::ToggleImportProfileSettings(checked) => {
+                self.import_profile_settings = checked;
+            }
+            Message::ImportProfile => {
+                let pick = async {
+                    rfd::AsyncFileDialog::new()
+                        .add_filter("JSON", &["json"])
+                        .pick_file()
+                        .await
+                        .map(|handle| handle.path().to_path_buf())
+                };
+
+                return Task::future(pick)
+                    .and_then(|path| Task::done(Message::ProfilePicked(path)));
+            }
+            Message::ProfilePicked(path) => {
+                let profile = match data::read_profile(&path) {
+                    Ok(profile) => profile,
+                    Err(err) => {
+                        self.notifications.push(Toast::error(format!(
+                            "Couldn't import {}: {err}",
+                            path.display()
+                        )));
+                        return Task::none();
+                    }
+                };
+
+                // 同名布局会被重命名，当前的布局、主题和界面缩放保持不变
+                let names = layout::merge_layouts(
+                    &mut self.layout_manager,
+                    &profile.layout_manager.layouts,
+                );
+                self.notifications.push(Toast::info(format!(
+                    "Imported layouts: {}",
+                    names.join(", ")
+                )));
+
+                if self.import_profile_settings {
+                    return self.apply_imported_settings(profile);
+                }
+            }
+            Message::AutosaveTick => {
+                // 拖动面板或侧边栏期间不写盘，等布局稳定后的下一次触发再保存
+                if !self.sidebar.is_resizing() && !self.active_dashboard().is_layout_being_edited()
+                {
+                    self.autosave_state();
+                }
+            }
+            Message::CleanupDataRequested => {
+                if self.data_cleanup_running {
+                    return Task::none();
+                }
+                self.data_cleanup_running = true;
+                self.notifications.push(Toast::info("Cleaning up old market data..."));
+
+                let retention_days = self.data_retention.days;
+                return Task::perform(
+                    async move { data::cleanup_old_market_data(retention_days) },
+                    Message::DataCleanupFinished,
+                );
+            }
+            Message::DataCleanupFinished(report) => {
+                self.data_cleanup_running = false;
+
+                let summary = if report.files == 0 {
+                    format!(
+                        "No market data older than {} days to remove",
+                        self.data_retention.days
+                    )
+                } else {
+                    format!(
+                        "Removed {} files, freed {}",
+                        report.files,
+                        data::util::format_bytes(report.bytes)
+                    )
+                };
+                self.notifications.push(Toast::info(summary));
+
+                return measure_data_folder();
+            }
+            Message::DataFolderSized(bytes) => {
+                self.data_folder_size = Some(bytes);
+            }
             // 打开新设置窗口
             Message::OpenNewSettingWindow => {
                 // 如果设置窗口已经打开，则不重复打开
@@ -595,67 +1085,84 @@ impl Flowsurface {
             }
             Message::SettingWindow(msg) => {
                 // 处理设置窗口的消息
-                if let Some((window, id)) = &mut self.setting_window {
-                    if let Some(action) = window.update(msg) {
-                        match action {
-                            setting_window::Action::Close => {
-                                return Task::done(Message::SettingWindowClosed(id.clone()));
-                            }
-                            setting_window::Action::ThemeChanged(theme) => {
-                                return Task::done(Message::ThemeSelected(data::Theme(theme.into()))); 
-                            }
-                            setting_window::Action::TimezoneChanged(timezone) => {
-                                return Task::done(Message::SetTimezone(timezone));
-                            }
-                            setting_window::Action::OpenThemeEditor => {
-                                // todo 主题编辑
-                                return Task::none();
-                            }
-                            setting_window::Action::ScaleFactorChanged(scale_factor) => {
-                                return Task::done(Message::ScaleFactorChanged(scale_factor));
-                            }
-                            setting_window::Action::LanguageChanged(language) => {
-                                return Task::done(Message::LanguageChanged(language));
-                            }
+                if let Some((window, id)) = &mut self.setting_window
+                    && let Some(action) = window.update(msg)
+                {
+                    match action {
+                        setting_window::Action::Close => {
+                            return Task::done(Message::SettingWindowClosed(*id));
+                        }
+                        setting_window::Action::ThemeChanged(theme) => {
+                            return Task::done(Message::ThemeSelected(data::Theme(theme.into()))); 
+                        }
+                        setting_window::Action::TimezoneChanged(timezone) => {
+                            return Task::done(Message::SetTimezone(timezone));
+                        }
+                        setting_window::Action::OpenThemeEditor => {
+                            // todo 主题编辑
+                            return Task::none();
+                        }
+                        setting_window::Action::ScaleFactorChanged(scale_factor) => {
+                            return Task::done(Message::ScaleFactorChanged(scale_factor));
+                        }
+                        setting_window::Action::LanguageChanged(language) => {
+                            return Task::done(Message::LanguageChanged(language));
+                        }
+                        setting_window::Action::ToastConfigChanged(cfg) => {
+                            return Task::done(Message::ToastConfigChanged(cfg));
+                        }
+                        setting_window::Action::KeyboardNavChanged(steps) => {
+                            return Task::done(Message::KeyboardNavChanged(steps));
+                        }
+                        setting_window::Action::PreviewToast => {
+                            self.notifications
+                                .push(Toast::info("This is how notifications will appear"));
                         }
                     }
                 }
             }
             Message::SettingWindowClosed(id) => {
                 // 清除设置窗口的状态
-                if let Some((_, window_id)) = &self.setting_window {
-                    if *window_id == id {
-                        self.setting_window = None;
-                    }
+                if let Some((_, window_id)) = &self.setting_window
+                    && *window_id == id
+                {
+                    self.setting_window = None;
                 }
                 
                 // 关闭窗口
                 return window::close(id);
             }
             Message::ThemeEditor(msg) => {
-                let action = self.theme_editor.update(msg, &self.theme.clone().into());
+                let (task, action) = self
+                    .theme_editor
+                    .update(msg, &self.active_theme().clone().into());
 
                 match action {
                     Some(modal::theme_editor::Action::Exit) => {
                         self.sidebar.set_menu(Some(sidebar::Menu::Settings));
                     }
                     Some(modal::theme_editor::Action::UpdateTheme(theme)) => {
-                        self.theme = data::Theme(theme);
-
-                        let main_window = self.main_window.id;
-
-                        self.active_dashboard_mut()
-                            .invalidate_all_panes(main_window);
+                        self.set_theme(data::Theme(theme));
+                    }
+                    Some(modal::theme_editor::Action::Notify(toast)) => {
+                        self.notifications.push(toast);
                     }
                     None => {}
                 }
+
+                return task.map(Message::ThemeEditor);
             }
             Message::Sidebar(message) => {
+                let opens_settings = matches!(
+                    message,
+                    dashboard::sidebar::Message::ToggleSidebarMenu(Some(sidebar::Menu::Settings))
+                );
                 let (task, action) = self.sidebar.update(message);
 
                 match action {
                     Some(dashboard::sidebar::Action::TickerSelected(ticker_info, content)) => {
                         let main_window_id = self.main_window.id;
+                        let zoom_memory = self.zoom_memory;
 
                         let task = {
                             if let Some(kind) = content {
@@ -663,10 +1170,14 @@ impl Flowsurface {
                                     main_window_id,
                                     ticker_info,
                                     kind,
+                                    zoom_memory,
                                 )
                             } else {
-                                self.active_dashboard_mut()
-                                    .switch_tickers_in_group(main_window_id, ticker_info)
+                                self.active_dashboard_mut().switch_tickers_in_group(
+                                    main_window_id,
+                                    ticker_info,
+                                    zoom_memory,
+                                )
                             }
                         };
 
@@ -681,6 +1192,9 @@ impl Flowsurface {
                     None => {}
                 }
 
+                if opens_settings {
+                    return Task::batch([task.map(Message::Sidebar), measure_data_folder()]);
+                }
                 return task.map(Message::Sidebar);
             }
             Message::ApplyVolumeSizeUnit(pref) => {
@@ -710,11 +1224,16 @@ impl Flowsurface {
         let content = if id == self.main_window.id {
             let sidebar_view = self
                 .sidebar
-                .view(self.audio_stream.volume())
+                .view(
+                    self.audio_stream.volume(),
+                    dashboard
+                        .stream_health
+                        .worst(chrono::Utc::now().timestamp_millis() as u64),
+                )
                 .map(Message::Sidebar);
 
             let dashboard_view = dashboard
-                .view(&self.main_window, tickers_table, self.timezone)
+                .view(&self.main_window, tickers_table, self.active_timezone())
                 .map(move |msg| Message::Dashboard {
                     layout_id: None,
                     event: msg,
@@ -742,30 +1261,75 @@ impl Flowsurface {
                 }
             };
 
-            let base = column![
-                header_title,
-                match sidebar_pos {
-                    sidebar::Position::Left => row![sidebar_view, dashboard_view,],
-                    sidebar::Position::Right => row![dashboard_view, sidebar_view],
-                }
-                .spacing(4)
-                .padding(8),
-            ];
+            // 侧边栏位置是相对阅读方向而言的，实际左右顺序交给当前语言的 LayoutDirection
+            let direction = self.language.direction();
+            let [first, second] = direction.arrange(match sidebar_pos {
+                sidebar::Position::Left => [sidebar_view, dashboard_view],
+                sidebar::Position::Right => [dashboard_view, sidebar_view],
+            });
 
-            if let Some(menu) = self.sidebar.active_menu() {
+            // 侧边栏在屏幕左侧时，向右拖动把手才是加宽
+            let [grows_rightward, _] = direction.arrange(match sidebar_pos {
+                sidebar::Position::Left => [true, false],
+                sidebar::Position::Right => [false, true],
+            });
+            let resize_handle = self
+                .sidebar
+                .resize_handle(grows_rightward)
+                .map(Message::Sidebar);
+
+            let panes = row![first, resize_handle, second].padding(8);
+            let panes: Element<'_, Message> = if self.sidebar.is_resizing() {
+                mouse_area(panes)
+                    .on_move(|point| Message::Sidebar(dashboard::sidebar::Message::Resize(point.x)))
+                    .on_release(Message::Sidebar(dashboard::sidebar::Message::EndResize))
+                    .interaction(iced::mouse::Interaction::ResizingHorizontally)
+                    .into()
+            } else {
+                panes.into()
+            };
+
+            let base = column![header_title, panes];
+
+            let base = if let Some(menu) = self.sidebar.active_menu() {
                 self.view_with_modal(base.into(), dashboard, menu)
             } else {
                 base.into()
+            };
+
+            let base = if let Some(overlay) = &self.perf_overlay {
+                iced::widget::stack![
+                    base,
+                    container(overlay.view())
+                        .align_right(iced::Length::Fill)
+                        .align_bottom(iced::Length::Fill)
+                        .padding(12)
+                ]
+                .into()
+            } else {
+                base
+            };
+
+            if let Some(switcher) = &self.quick_switcher {
+                main_dialog_modal(
+                    base,
+                    switcher.view(tickers_table).map(Message::QuickSwitcher),
+                    Message::ToggleQuickSwitcher,
+                )
+            } else {
+                base
             }
         } else if let Some((window, window_id)) = &self.setting_window {
             // 设置窗口的视图
             if *window_id == id {
                 return window.view(
-                    &self.theme,
+                    self.active_theme(),
                     &self.theme_editor,
-                    self.timezone,
+                    self.active_timezone(),
                     self.volume_size_unit,
                     self.ui_scale_factor,
+                    self.toast_cfg,
+                    self.keyboard_nav,
                     // self.sidebar.position(),
                 ).map(Message::SettingWindow);
             }
@@ -773,7 +1337,7 @@ impl Flowsurface {
             // 如果不是设置窗口，继续检查其他窗口
             container(
                 dashboard
-                    .view_window(id, &self.main_window, tickers_table, self.timezone)
+                    .view_window(id, &self.main_window, tickers_table, self.active_timezone())
                     .map(move |msg| Message::Dashboard {
                         layout_id: None,
                         event: msg,
@@ -784,7 +1348,7 @@ impl Flowsurface {
         } else {
             container(
                 dashboard
-                    .view_window(id, &self.main_window, tickers_table, self.timezone)
+                    .view_window(id, &self.main_window, tickers_table, self.active_timezone())
                     .map(move |msg| Message::Dashboard {
                         layout_id: None,
                         event: msg,
@@ -797,25 +1361,166 @@ impl Flowsurface {
         toast::Manager::new(
             content,
             &self.notifications,
-            match sidebar_pos {
-                sidebar::Position::Left => Alignment::Start,
-                sidebar::Position::Right => Alignment::End,
-            },
+            self.toast_cfg,
             Message::RemoveNotification,
         )
         .into()
     }
 
     fn theme(&self, _window: window::Id) -> iced_core::Theme {
-        self.theme.clone().into()
+        self.active_theme().clone().into()
+    }
+
+    /// 应用导入配置中的设置，主题、界面缩放、侧边栏与窗口位置保留本机的
+    fn apply_imported_settings(&mut self, profile: data::State) -> Task<Message> {
+        self.audio_stream = AudioStream::new(profile.audio_cfg);
+
+        Task::batch([
+            Task::done(Message::SetTimezone(profile.timezone)),
+            Task::done(Message::ToggleSyncCrosshair(profile.sync_crosshair)),
+            Task::done(Message::ToggleZoomMemory(profile.zoom_memory.per_ticker)),
+            Task::done(Message::ToastConfigChanged(
+                profile.toast_cfg.unwrap_or(self.toast_cfg),
+            )),
+            Task::done(Message::KeyboardNavChanged(profile.keyboard_nav)),
+            Task::done(Message::DataRetentionChanged(profile.data_retention)),
+            Task::done(Message::AutosaveChanged(profile.autosave)),
+            Task::done(Message::SeriesRetentionChanged(profile.series_retention)),
+            Task::done(Message::DepthThrottleChanged(profile.depth_throttle)),
+        ])
+    }
+
+    /// 把一次（可能合并过的）深度更新交给面板，并按其中的成交播放提醒音
+    fn apply_depth_update(
+        &mut self,
+        stream: &StreamKind,
+        update: &CoalescedDepth,
+    ) -> Task<Message> {
+        let main_window_id = self.main_window.id;
+
+        let task = self
+            .active_dashboard_mut()
+            .update_depth_and_trades(
+                stream,
+                update.depth_update_t,
+                &update.depth,
+                &update.trades,
+                main_window_id,
+            )
+            .map(move |msg| Message::Dashboard {
+                layout_id: None,
+                event: msg,
+            });
+
+        if let Err(err) = self.audio_stream.try_play_sound(stream, &update.trades) {
+            log::error!("Failed to play sound: {err}");
+        }
+
+        // 每批成交只对数量最大的一笔检查大单提醒，避免连续成交时声音叠加
+        if let Some(trade) = update.trades.iter().max_by(|a, b| a.qty.total_cmp(&b.qty))
+            && let Err(err) = self.audio_stream.try_play_large_trade_alert(stream, trade)
+        {
+            log::error!("Failed to play large trade alert: {err}");
+        }
+
+        task
+    }
+
+    /// 当前布局的时区，未单独设置时使用全局时区
+    fn active_timezone(&self) -> data::UserTimezone {
+        self.layout_manager
+            .active_layout()
+            .and_then(|layout| layout.timezone)
+            .unwrap_or(self.timezone)
+    }
+
+    /// 执行回放控件的动作：选择文件、推送K线，或在开始、回退和停止时重置目标图表
+    fn replay_action(&mut self, action: modal::replay::Action) -> Task<Message> {
+        let main_window = self.main_window.id;
+
+        match action {
+            modal::replay::Action::PickFile => {
+                let Some(stream) = self.active_dashboard().focused_kline_stream(main_window) else {
+                    self.notifications.push(Toast::warn(
+                        "Focus a pane with a time based kline chart to replay into",
+                    ));
+                    return Task::none();
+                };
+                load_replay(stream)
+            }
+            modal::replay::Action::Emit(stream, klines) => replay_klines(stream, klines),
+            modal::replay::Action::Restart(stream, klines) => {
+                self.active_dashboard_mut()
+                    .restart_replay(&stream, &klines, main_window);
+                Task::none()
+            }
+            modal::replay::Action::Stop(stream) => {
+                self.active_dashboard_mut().end_replay(&stream, main_window);
+                Task::none()
+            }
+        }
+    }
+
+    /// 离屏渲染面板的图表并写入 PNG，结果以通知显示
+    fn export_pane_png(
+        &self,
+        layout_id: uuid::Uuid,
+        pane_id: uuid::Uuid,
+        scale: widget::export::ExportScale,
+        size: iced::Size,
+        path: std::path::PathBuf,
+    ) -> Task<Message> {
+        let theme = self.theme(self.main_window.id);
+        let snapshot = self.layout_manager.get(layout_id).and_then(|layout| {
+            layout.dashboard.canvas_snapshot(
+                self.main_window.id,
+                pane_id,
+                size,
+                &theme,
+                self.active_timezone(),
+            )
+        });
+
+        let result = match snapshot {
+            Some(snapshot) => widget::export::capture_pane_as_png(snapshot, scale, path),
+            None => Task::done(Err(widget::export::ExportError::NoChart)),
+        };
+
+        result.map(move |result| Message::Dashboard {
+            layout_id: Some(layout_id),
+            event: dashboard::Message::Notification(match result {
+                Ok(path) => Toast::info(format!("Saved {}", path.display())),
+                Err(err) => Toast::error(err.to_string()),
+            }),
+        })
+    }
+
+    /// 当前布局的主题，未单独设置时使用全局主题
+    fn active_theme(&self) -> &data::Theme {
+        self.layout_manager
+            .active_layout()
+            .and_then(|layout| layout.theme.as_ref())
+            .unwrap_or(&self.theme)
+    }
+
+    /// 当前布局有独立主题时只修改该布局，否则修改全局主题
+    fn set_theme(&mut self, theme: data::Theme) {
+        match self.layout_manager.active_layout_mut() {
+            Some(layout) if layout.theme.is_some() => layout.theme = Some(theme),
+            _ => self.theme = theme,
+        }
+
+        let main_window = self.main_window.id;
+        self.active_dashboard_mut()
+            .invalidate_all_panes(main_window);
     }
 
     fn title(&self, window: window::Id) -> String {
         // 检查是否是设置窗口
-        if let Some((_, window_id)) = &self.setting_window {
-            if *window_id == window {
-                return t!("settings.title").to_string();
-            }
+        if let Some((_, window_id)) = &self.setting_window
+            && *window_id == window
+        {
+            return t!("settings.title").to_string();
         }
         
         if let Some(id) = self.layout_manager.active_layout_id() {
@@ -833,20 +1538,67 @@ impl Flowsurface {
         let window_events = window::events().map(Message::WindowEvent);
         let sidebar = self.sidebar.subscription().map(Message::Sidebar);
 
-        let exchange_streams = self
-            .active_dashboard()
-            .market_subscriptions()
-            .map(Message::MarketWsEvent);
+        // 回放期间不连实时行情，免得两边的K线混在一起
+        let exchange_streams = if self.replay.is_active() {
+            Subscription::none()
+        } else {
+            self.active_dashboard()
+                .market_subscriptions()
+                .map(Message::MarketWsEvent)
+        };
 
         let tick = iced::time::every(std::time::Duration::from_millis(100)).map(Message::Tick);
+        let autosave = iced::time::every(self.autosave.interval()).map(|_| Message::AutosaveTick);
 
+        // 仅收到未被控件捕获的按键，文本框获得焦点时不会触发导航
         let hotkeys = keyboard::listen().filter_map(|event| {
-            let keyboard::Event::KeyPressed { key, .. } = event else {
+            let keyboard::Event::KeyPressed {
+                key,
+                modified_key,
+                physical_key,
+                modifiers,
+                ..
+            } = event
+            else {
                 return None;
             };
+
+            // Ctrl+1 ~ Ctrl+9 按物理按键切换布局，不受键盘布局影响
+            if modifiers.command() && !modifiers.alt() && !modifiers.shift() {
+                if let keyboard::Key::Character("p") = key.as_ref() {
+                    return Some(Message::ToggleQuickSwitcher);
+                }
+                return layout_shortcut_index(physical_key).map(Message::SelectLayoutByIndex);
+            }
+
+            if modifiers.command() || modifiers.alt() {
+                return None;
+            }
+
             match key {
                 keyboard::Key::Named(keyboard::key::Named::Escape) => Some(Message::GoBack),
-                _ => None,
+                keyboard::Key::Named(keyboard::key::Named::ArrowLeft) => {
+                    Some(Message::NavigateFocusedPane(chart::NavigationKey::Left))
+                }
+                keyboard::Key::Named(keyboard::key::Named::ArrowRight) => {
+                    Some(Message::NavigateFocusedPane(chart::NavigationKey::Right))
+                }
+                // 仅在快速切换框打开时生效，用于移动高亮的搜索结果
+                keyboard::Key::Named(keyboard::key::Named::ArrowUp) => Some(
+                    Message::QuickSwitcher(modal::quick_switcher::Message::MoveSelection(-1)),
+                ),
+                keyboard::Key::Named(keyboard::key::Named::ArrowDown) => Some(
+                    Message::QuickSwitcher(modal::quick_switcher::Message::MoveSelection(1)),
+                ),
+                _ => match modified_key.as_ref() {
+                    keyboard::Key::Character("+" | "=") => {
+                        Some(Message::NavigateFocusedPane(chart::NavigationKey::ZoomIn))
+                    }
+                    keyboard::Key::Character("-" | "_") => {
+                        Some(Message::NavigateFocusedPane(chart::NavigationKey::ZoomOut))
+                    }
+                    _ => None,
+                },
             }
         });
 
@@ -855,6 +1607,7 @@ impl Flowsurface {
             sidebar,
             window_events,
             tick,
+            autosave,
             hotkeys,
         ])
     }
@@ -881,16 +1634,54 @@ impl Flowsurface {
             .expect("No active dashboard")
     }
 
+    /// 保存当前布局弹出窗口的位置后关闭它们，再加载目标布局
+    fn switch_layout(&mut self, layout: uuid::Uuid) -> Task<Message> {
+        let active_popout_keys = self
+            .active_dashboard()
+            .popout
+            .keys()
+            .copied()
+            .collect::<Vec<_>>();
+
+        let window_tasks = Task::batch(
+            active_popout_keys
+                .iter()
+                .map(|&popout_id| window::close::<window::Id>(popout_id))
+                .collect::<Vec<_>>(),
+        )
+        .discard();
+
+        let old_layout_id = self
+            .layout_manager
+            .active_layout_id()
+            .as_ref()
+            .map(|layout| layout.unique);
+
+        self.active_dashboard_mut().unload_panes();
+
+        window::collect_window_specs(active_popout_keys, dashboard::Message::SavePopoutSpecs)
+            .map(move |msg| Message::Dashboard {
+                layout_id: old_layout_id,
+                event: msg,
+            })
+            .chain(window_tasks)
+            .chain(self.load_layout(layout, self.main_window.id))
+    }
+
     fn load_layout(&mut self, layout_uid: uuid::Uuid, main_window: window::Id) -> Task<Message> {
         match self.layout_manager.set_active_layout(layout_uid) {
             Ok(layout) => {
-                layout
-                    .dashboard
-                    .load_layout(main_window)
-                    .map(move |msg| Message::Dashboard {
-                        layout_id: Some(layout_uid),
-                        event: msg,
-                    })
+                let task = layout.dashboard.load_layout(main_window);
+
+                // 布局可能有独立的时区与主题，切换后重绘所有面板
+                data::config::timezone::set_session_timezone(self.active_timezone());
+                self.active_dashboard_mut()
+                    .invalidate_all_panes(main_window);
+
+                task.map(move |msg| Message::Dashboard {
+                    layout_id: Some(layout_uid),
+                    event: msg,
+                })
             }
             Err(err) => {
                 log::error!("Failed to set active layout: {}", err);
@@ -920,7 +1711,7 @@ impl Flowsurface {
                             themes.push(custom_theme.clone());
                         }
 
-                        pick_list(themes, Some(self.theme.0.clone()), |theme| {
+                        pick_list(themes, Some(self.active_theme().0.clone()), |theme| {
                             Message::ThemeSelected(data::Theme(theme))
                         })
                     };
@@ -932,11 +1723,28 @@ impl Flowsurface {
                     );
 
                     let timezone_picklist = pick_list(
-                        [data::UserTimezone::Utc, data::UserTimezone::Local],
-                        Some(self.timezone),
+                        data::UserTimezone::ALL,
+                        Some(self.active_timezone()),
                         Message::SetTimezone,
                     );
 
+                    let layout_display_checkbox = {
+                        let is_separate =
+                            self.layout_manager.active_layout().is_some_and(|layout| {
+                                layout.timezone.is_some() || layout.theme.is_some()
+                            });
+
+                        let checkbox = iced::widget::checkbox(is_separate)
+                            .label("Own time zone and theme")
+                            .on_toggle(Message::ToggleLayoutDisplay);
+
+                        tooltip(
+                            checkbox,
+                            Some("Time zone and theme changes apply only to this layout"),
+                            TooltipPosition::Top,
+                        )
+                    };
+
                     let size_in_quote_currency_checkbox = {
                         let is_active = match self.volume_size_unit {
                             exchange::SizeUnit::Quote => true,
@@ -971,6 +1779,34 @@ impl Flowsurface {
                         )
                     };
 
+                    let zoom_memory_checkbox = {
+                        let checkbox = iced::widget::checkbox(self.zoom_memory.per_ticker)
+                            .label("Remember zoom per ticker")
+                            .on_toggle(Message::ToggleZoomMemory);
+
+                        tooltip(
+                            checkbox,
+                            Some(
+                                "Switching a pane back to a ticker restores the zoom it had there",
+                            ),
+                            TooltipPosition::Top,
+                        )
+                    };
+
+                    let sync_crosshair_checkbox = {
+                        let checkbox = iced::widget::checkbox(self.sync_crosshair)
+                            .label("Sync crosshair across linked panes")
+                            .on_toggle(Message::ToggleSyncCrosshair);
+
+                        tooltip(
+                            checkbox,
+                            Some(
+                                "Draw the cursor time of a pane on the other panes of its link group",
+                            ),
+                            TooltipPosition::Top,
+                        )
+                    };
+
                     let sidebar_pos = pick_list(
                         [sidebar::Position::Left, sidebar::Position::Right],
                         Some(sidebar_pos),
@@ -1011,10 +1847,12 @@ impl Flowsurface {
 
                     let trade_fetch_checkbox = {
                         let is_active = exchange::fetcher::is_trade_fetch_enabled();
+                        // 当前布局里没有支持回补成交的交易所时禁用
+                        let is_supported = self.active_dashboard().supports_trade_fetch();
 
                         let checkbox = iced::widget::checkbox(is_active)
-                            .label("Fetch trades (Binance)")
-                            .on_toggle(|checked| {
+                            .label("Fetch trades")
+                            .on_toggle_maybe(is_supported.then_some(|checked| {
                                 if checked {
                                     let confirm_dialog = screen::ConfirmDialog::new(
                                         "This might be unreliable and take some time to complete. Proceed?"
@@ -1025,11 +1863,25 @@ impl Flowsurface {
                                 } else {
                                     Message::ToggleTradeFetch(false)
                                 }
-                            });
+                            }));
+
+                        let tooltip_text = if is_supported {
+                            "Try to fetch trades for footprint charts"
+                        } else {
+                            "None of the current streams' exchanges support fetching trades"
+                        };
+
+                        tooltip(checkbox, Some(tooltip_text), TooltipPosition::Top)
+                    };
+
+                    let perf_overlay_checkbox = {
+                        let checkbox = iced::widget::checkbox(self.perf_overlay.is_some())
+                            .label("Performance overlay")
+                            .on_toggle(Message::TogglePerfOverlay);
 
                         tooltip(
                             checkbox,
-                            Some("Try to fetch trades for footprint charts"),
+                            Some("Show frame rate, update time and data counts in a corner"),
                             TooltipPosition::Top,
                         )
                     };
@@ -1045,6 +1897,144 @@ impl Flowsurface {
                         )
                     };
 
+                    let data_folder = {
+                        let size = self
+                            .data_folder_size
+                            .map_or_else(|| "...".to_string(), data::util::format_bytes);
+
+                        let retention = labeled_slider(
+                            "Keep",
+                            data::config::DataRetention::DAYS_RANGE,
+                            self.data_retention.days,
+                            |days| {
+                                Message::DataRetentionChanged(data::config::DataRetention { days })
+                            },
+                            |days| format!("{days} days"),
+                            Some(1),
+                        );
+
+                        let cleanup_button = {
+                            let button = button(text("Clean up now")).on_press_maybe(
+                                (!self.data_cleanup_running)
+                                    .then_some(Message::CleanupDataRequested),
+                            );
+
+                            tooltip(
+                                button,
+                                Some("Remove trade archives older than the kept days"),
+                                TooltipPosition::Top,
+                            )
+                        };
+
+                        column![
+                            text(format!("Size: {size}")),
+                            retention,
+                            row![open_data_folder, cleanup_button].spacing(8),
+                        ]
+                        .spacing(8)
+                    };
+
+                    let depth_throttle_slider = {
+                        let slider = labeled_slider(
+                            "Depth updates",
+                            data::config::DepthThrottle::INTERVAL_RANGE,
+                            self.depth_throttle.interval_ms,
+                            |interval_ms| {
+                                Message::DepthThrottleChanged(data::config::DepthThrottle {
+                                    interval_ms,
+                                })
+                            },
+                            |ms| {
+                                if *ms == 0 {
+                                    "Every update".to_string()
+                                } else {
+                                    format!("Every {ms}ms")
+                                }
+                            },
+                            Some(50),
+                        );
+
+                        tooltip(
+                            slider,
+                            Some("Coalesce order book updates to lower CPU use on busy pairs"),
+                            TooltipPosition::Top,
+                        )
+                    };
+
+                    let series_retention_slider = {
+                        let slider = labeled_slider(
+                            "Bars kept",
+                            data::config::SeriesRetention::MAX_BARS_RANGE,
+                            self.series_retention.max_bars,
+                            |max_bars| {
+                                Message::SeriesRetentionChanged(data::config::SeriesRetention {
+                                    max_bars,
+                                })
+                            },
+                            |bars| {
+                                let bars = data::util::format_with_commas(*bars as f32);
+                                format!("{bars} per chart")
+                            },
+                            Some(10_000),
+                        );
+
+                        tooltip(
+                            slider,
+                            Some("Older bars are dropped and fetched again when scrolled back to"),
+                            TooltipPosition::Top,
+                        )
+                    };
+
+                    let autosave_slider = labeled_slider(
+                        "Autosave every",
+                        data::config::Autosave::INTERVAL_RANGE,
+                        self.autosave.interval_secs,
+                        |interval_secs| {
+                            Message::AutosaveChanged(data::config::Autosave { interval_secs })
+                        },
+                        |secs| format!("{secs}s"),
+                        Some(15),
+                    );
+
+                    let reset_settings = {
+                        let keep_layouts_checkbox = iced::widget::checkbox(self.reset_keep_layouts)
+                            .label("Keep layouts")
+                            .on_toggle(Message::ToggleResetKeepLayouts);
+
+                        let reset_button = {
+                            let message = if self.reset_keep_layouts {
+                                "Reset settings to defaults and restart? Layouts are kept"
+                            } else {
+                                "Reset settings and layouts to defaults and restart?"
+                            };
+                            let confirm_dialog = screen::ConfirmDialog::new(
+                                message.to_string(),
+                                Box::new(Message::ResetSettings(self.reset_keep_layouts)),
+                            )
+                            .with_confirm_btn_text("Reset".to_string());
+
+                            button(text("Reset all settings"))
+                                .on_press(Message::ToggleDialogModal(Some(confirm_dialog)))
+                        };
+
+                        column![keep_layouts_checkbox, reset_button].spacing(8)
+                    };
+
+                    let import_profile = {
+                        let settings_checkbox =
+                            iced::widget::checkbox(self.import_profile_settings)
+                                .label("Include settings")
+                                .on_toggle(Message::ToggleImportProfileSettings);
+
+                        let import_button = tooltip(
+                            button(text("Import profile...")).on_press(Message::ImportProfile),
+                            Some("Add the layouts of another user's saved-state.json"),
+                            TooltipPosition::Top,
+                        );
+
+                        column![settings_checkbox, import_button].spacing(8)
+                    };
+
                     let open_new_window_test = {
                         let button = button(text("Open new window test")).on_press(Message::OpenNewSettingWindow);
                         tooltip(
@@ -1056,15 +2046,42 @@ impl Flowsurface {
 
                     let column_content = split_column![
                         column![open_new_window_test,].spacing(8),
-                        column![open_data_folder,].spacing(8),
+                        column![text("Data folder").size(14), data_folder,].spacing(12),
                         column![text("Sidebar position").size(14), sidebar_pos,].spacing(12),
                         column![text("Time zone").size(14), timezone_picklist,].spacing(12),
-                        column![text("Market data").size(14), size_in_quote_currency_checkbox,].spacing(12),
+                        column![
+                            text("Market data").size(14),
+                            column![size_in_quote_currency_checkbox, depth_throttle_slider]
+                                .spacing(8),
+                        ]
+                        .spacing(12),
+                        column![
+                            text("Charts").size(14),
+                            column![
+                                sync_crosshair_checkbox,
+                                zoom_memory_checkbox,
+                                series_retention_slider
+                            ]
+                            .spacing(8),
+                        ]
+                        .spacing(12),
                         column![text("Theme").size(14), theme_picklist,].spacing(12),
+                        column![
+                            text("Layout").size(14),
+                            column![layout_display_checkbox, autosave_slider].spacing(8),
+                        ]
+                        .spacing(12),
                         column![text("Interface scale").size(14), scale_factor,].spacing(12),
+                        column![text("Import").size(14), import_profile,].spacing(12),
+                        column![text("Reset").size(14), reset_settings,].spacing(12),
                         column![
                             text("Experimental").size(14),
-                            column![trade_fetch_checkbox, toggle_theme_editor,].spacing(8),
+                            column![
+                                trade_fetch_checkbox,
+                                perf_overlay_checkbox,
+                                toggle_theme_editor,
+                            ]
+                            .spacing(8),
                         ]
                         .spacing(12),
                         ; spacing = 16, align_x = Alignment::Start
@@ -1129,6 +2146,25 @@ impl Flowsurface {
 
                     let is_main_window = window_id == main_window;
 
+                    let repair_gaps_button = {
+                        let btn = button(text("Repair gaps").align_x(Alignment::Center))
+                            .width(iced::Length::Fill);
+                        let is_kline_chart = dashboard
+                            .get_pane(main_window, window_id, pane_id)
+                            .is_some_and(|state| {
+                                matches!(state.content, dashboard::pane::Content::Kline { .. })
+                            });
+
+                        if is_kline_chart {
+                            btn.on_press(Message::Dashboard {
+                                layout_id: None,
+                                event: dashboard::Message::RepairFocusedKlines,
+                            })
+                        } else {
+                            btn
+                        }
+                    };
+
                     let reset_pane_button = {
                         let btn = button(text("Reset").align_x(Alignment::Center))
                             .width(iced::Length::Fill);
@@ -1178,6 +2214,11 @@ impl Flowsurface {
                                 },
                                 TooltipPosition::Top,
                             ),
+                            tooltip(
+                                repair_gaps_button,
+                                Some("Refetch klines missing from the selected chart"),
+                                TooltipPosition::Top,
+                            ),
                             // tooltip(
                             //     split_pane_button,
                             //     if is_main_window {
@@ -1241,6 +2282,46 @@ impl Flowsurface {
                     align_x,
                 )
             }
+            sidebar::Menu::Connections => {
+                let (align_x, padding) = match sidebar_pos {
+                    sidebar::Position::Left => (Alignment::Start, padding::left(44).top(156)),
+                    sidebar::Position::Right => (Alignment::End, padding::right(44).top(156)),
+                };
+
+                dashboard_modal(
+                    base,
+                    self.connections
+                        .view(
+                            &dashboard.stream_health,
+                            chrono::Utc::now().timestamp_millis() as u64,
+                        )
+                        .map(Message::Connections),
+                    Message::Sidebar(dashboard::sidebar::Message::ToggleSidebarMenu(None)),
+                    padding,
+                    Alignment::Start,
+                    align_x,
+                )
+            }
+            sidebar::Menu::Replay => {
+                let (align_x, padding) = match sidebar_pos {
+                    sidebar::Position::Left => (Alignment::Start, padding::left(44).top(116)),
+                    sidebar::Position::Right => (Alignment::End, padding::right(44).top(116)),
+                };
+
+                dashboard_modal(
+                    base,
+                    self.replay
+                        .view(
+                            dashboard.focused_kline_stream(self.main_window.id),
+                            self.active_timezone(),
+                        )
+                        .map(Message::Replay),
+                    Message::Sidebar(dashboard::sidebar::Message::ToggleSidebarMenu(None)),
+                    padding,
+                    Alignment::Start,
+                    align_x,
+                )
+            }
             sidebar::Menu::ThemeEditor => {
                 let (align_x, padding) = match sidebar_pos {
                     sidebar::Position::Left => (Alignment::Start, padding::left(44).bottom(4)),
@@ -1250,7 +2331,7 @@ impl Flowsurface {
                 dashboard_modal(
                     base,
                     self.theme_editor
-                        .view(&self.theme.0)
+                        .view(&self.active_theme().0)
                         .map(Message::ThemeEditor),
                     Message::Sidebar(dashboard::sidebar::Message::ToggleSidebarMenu(None)),
                     padding,
@@ -1261,6 +2342,24 @@ impl Flowsurface {
         }
     }
 
+    fn set_main_window_position(&mut self, position: iced::Point) {
+        self.main_window.position = Some(position);
+
+        let spec = self
+            .main_window_spec
+            .get_or_insert_with(WindowSpec::default);
+        spec.pos_x = position.x;
+        spec.pos_y = position.y;
+    }
+
+    fn set_main_window_size(&mut self, size: iced::Size) {
+        let spec = self
+            .main_window_spec
+            .get_or_insert_with(WindowSpec::default);
+        spec.width = size.width;
+        spec.height = size.height;
+    }
+
     fn save_state_to_disk(&mut self, windows: &HashMap<window::Id, WindowSpec>) {
         self.active_dashboard_mut()
             .popout
@@ -1271,58 +2370,74 @@ impl Flowsurface {
                 }
             });
 
-        self.sidebar.sync_tickers_table_settings();
+        if let Some(spec) = windows.get(&self.main_window.id) {
+            self.main_window_spec = Some(*spec);
+        }
 
-        let mut ser_layouts = vec![];
-        for layout in &self.layout_manager.layouts {
-            if let Some(layout) = self.layout_manager.get(layout.id.unique) {
-                let serialized_dashboard = data::Dashboard::from(&layout.dashboard);
-                ser_layouts.push(data::Layout {
-                    name: layout.id.name.clone(),
-                    dashboard: serialized_dashboard,
-                });
+        if let Some(json) = self.serialize_state() {
+            let file_name = data::SAVED_STATE_PATH;
+            if let Err(e) = data::write_json_to_file(&json, file_name) {
+                log::error!("Failed to write layout state to file: {}", e);
+            } else {
+                log::info!("Persisted state to {file_name}");
             }
         }
+    }
 
-        let layouts = data::Layouts {
-            layouts: ser_layouts,
-            active_layout: self
-                .layout_manager
-                .active_layout_id()
-                .map(|layout| layout.name.to_string())
-                .clone(),
+    /// 定时保存，不查询窗口，沿用最近一次已知的窗口位置与大小
+    fn autosave_state(&mut self) {
+        let Some(json) = self.serialize_state() else {
+            return;
         };
 
-        let main_window_spec = windows
-            .iter()
-            .find(|(id, _)| **id == self.main_window.id)
-            .map(|(_, spec)| *spec);
+        let hash = {
+            use std::hash::{Hash, Hasher};
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            json.hash(&mut hasher);
+            hasher.finish()
+        };
+        if self.saved_state_hash == Some(hash) {
+            return;
+        }
 
+        match data::write_json_to_file(&json, data::SAVED_STATE_PATH) {
+            Ok(()) => {
+                self.saved_state_hash = Some(hash);
+                log::debug!("Autosaved state to {}", data::SAVED_STATE_PATH);
+            }
+            Err(e) => log::error!("Failed to autosave layout state: {}", e),
+        }
+    }
+
+    fn serialize_state(&mut self) -> Option<String> {
+        self.sidebar.sync_tickers_table_settings();
+
+        let layouts = data::Layouts::from(&self.layout_manager);
         let audio_cfg = data::AudioStream::from(&self.audio_stream);
 
         let state = data::State::from_parts(
             layouts,
             self.theme.clone(),
             self.theme_editor.custom_theme.clone().map(data::Theme),
-            main_window_spec,
+            self.main_window_spec,
             self.timezone,
             self.sidebar.state.clone(),
             self.ui_scale_factor,
             audio_cfg,
             self.volume_size_unit,
+            self.sync_crosshair,
+            self.toast_cfg,
+            self.keyboard_nav,
+            self.data_retention,
+            self.autosave,
+            self.depth_throttle,
+            self.series_retention,
+            self.zoom_memory,
         );
 
-        match serde_json::to_string(&state) {
-            Ok(layout_str) => {
-                let file_name = data::SAVED_STATE_PATH;
-                if let Err(e) = data::write_json_to_file(&layout_str, file_name) {
-                    log::error!("Failed to write layout state to file: {}", e);
-                } else {
-                    log::info!("Persisted state to {file_name}");
-                }
-            }
-            Err(e) => log::error!("Failed to serialize layout: {}", e),
-        }
+        serde_json::to_string(&state)
+            .inspect_err(|e| log::error!("Failed to serialize layout: {}", e))
+            .ok()
     }
 
     fn restart(&mut self) -> Task<Message> {
@@ -1343,3 +2458,72 @@ impl Flowsurface {
         close_windows.chain(init_task)
     }
 }
+
+/// Ctrl+数字键对应的布局序号（从 0 开始），数字键 1~9 之外返回 None
+fn layout_shortcut_index(physical_key: keyboard::key::Physical) -> Option<usize> {
+    use keyboard::key::Code;
+
+    const DIGITS: [Code; modal::layout_manager::SHORTCUT_LAYOUTS] = [
+        Code::Digit1,
+        Code::Digit2,
+        Code::Digit3,
+        Code::Digit4,
+        Code::Digit5,
+        Code::Digit6,
+        Code::Digit7,
+        Code::Digit8,
+        Code::Digit9,
+    ];
+
+    match physical_key {
+        keyboard::key::Physical::Code(code) => DIGITS.iter().position(|digit| *digit == code),
+        keyboard::key::Physical::Unidentified(_) => None,
+    }
+}
+
+/// 选择要回放的K线文件并读取，CSV 按该数据流的周期对齐
+fn load_replay(stream: StreamKind) -> Task<Message> {
+    let StreamKind::Kline { timeframe, .. } = stream else {
+        return Task::none();
+    };
+
+    let load = async move {
+        let handle = rfd::AsyncFileDialog::new()
+            .add_filter("Klines", &["csv", "json"])
+            .pick_file()
+            .await?;
+        let path = handle.path();
+
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+
+        Some(if is_json {
+            data::import::import_klines_json(path)
+        } else {
+            data::import::import_klines_csv(path, timeframe)
+        })
+    };
+
+    Task::future(load).then(move |result| match result {
+        Some(result) => Task::done(Message::ReplayLoaded(
+            stream,
+            result.map_err(|err| err.to_string()),
+        )),
+        None => Task::none(),
+    })
+}
+
+/// 回放的K线走与实时行情相同的 MarketWsEvent 路径
+fn replay_klines(stream: StreamKind, klines: Vec<exchange::Kline>) -> Task<Message> {
+    Task::batch(klines.into_iter().map(|kline| {
+        Task::done(Message::MarketWsEvent(exchange::Event::KlineReceived(
+            stream, kline,
+        )))
+    }))
+}
+
+/// 在后台统计数据文件夹大小，结果通过 DataFolderSized 返回
+fn measure_data_folder() -> Task<Message> {
+    Task::perform(async { data::data_folder_size() }, Message::DataFolderSized)
+}
@@ -1,6 +1,9 @@
 pub mod audio;
+pub mod connections;
 pub mod layout_manager;
 pub mod pane;
+pub mod quick_switcher;
+pub mod replay;
 pub mod setting_window;
 pub mod theme_editor;
 
@@ -9,6 +12,7 @@ use iced::{Alignment, Color, Element, Length, padding};
 pub use layout_manager::LayoutManager;
 pub use pane::indicators;
 pub use pane::stream::{self, ModifierKind};
+pub use quick_switcher::QuickSwitcher;
 pub use setting_window::SettingWindow;
 pub use theme_editor::ThemeEditor;
 
@@ -1,31 +1,56 @@
 use crate::TooltipPosition;
-use crate::audio::{SoundCache, SoundType};
+use crate::audio::{AudioError, SoundCache, SoundType};
 use crate::style::{self, icon_text};
 use crate::widget::{labeled_slider, tooltip};
-use data::audio::StreamCfg;
+use data::audio::{AlertKind, AlertSound, AlertSounds, StreamCfg};
 use exchange::adapter::{Exchange, StreamKind, StreamTicksize};
 
-use exchange::{PushFrequency, Trade};
+use exchange::{PushFrequency, Trade, volume_size_unit};
 use iced::widget::{button, column, container, row, text};
-use iced::widget::{checkbox, slider, space};
+use iced::widget::{checkbox, pick_list, slider, space};
 use iced::{Element, padding};
 use rustc_hash::FxHashMap;
 use std::collections::HashMap;
+use std::time::Instant;
 
 const HARD_THRESHOLD: usize = 4;
 
-#[derive(Debug, Clone, Copy)]
+/// Selectable notional steps for the large trade alert, the first one disables it
+const LARGE_TRADE_THRESHOLDS: [f32; 11] = [
+    0.0,
+    10_000.0,
+    25_000.0,
+    50_000.0,
+    100_000.0,
+    250_000.0,
+    500_000.0,
+    1_000_000.0,
+    2_500_000.0,
+    5_000_000.0,
+    10_000_000.0,
+];
+
+#[derive(Debug, Clone)]
 pub enum Message {
     SoundLevelChanged(f32),
+    SetAlertSound(AlertKind, AlertSound),
     ToggleStream(bool, (Exchange, exchange::Ticker)),
     ToggleCard(Exchange, exchange::Ticker),
     SetThreshold(Exchange, exchange::Ticker, data::audio::Threshold),
+    SetLargeTradeThreshold(Exchange, exchange::Ticker, f32),
+    SetCooldown(Exchange, exchange::Ticker, u64),
+    ToggleRampDown(Exchange, exchange::Ticker, bool),
 }
 
 pub struct AudioStream {
     cache: SoundCache,
     streams: HashMap<Exchange, HashMap<exchange::Ticker, StreamCfg>>,
     expanded_card: Option<(Exchange, exchange::Ticker)>,
+    alert_sounds: AlertSounds,
+    /// WAV files found in the sounds folder at startup
+    custom_sounds: Vec<String>,
+    /// When each stream last played a trade alert, and how far into a ramp down it is
+    last_played_at: HashMap<StreamKind, (Instant, usize)>,
 }
 
 impl AudioStream {
@@ -42,19 +67,33 @@ impl AudioStream {
                 .insert(ticker, stream_cfg);
         }
 
+        let custom_sounds = data::audio::list_custom_sounds().unwrap_or_else(|err| {
+            log::error!("Failed to scan custom sounds: {err}");
+            vec![]
+        });
+
         AudioStream {
             cache: SoundCache::with_default_sounds(cfg.volume)
                 .expect("Failed to create sound cache"),
             streams,
             expanded_card: None,
+            alert_sounds: cfg.alert_sounds,
+            custom_sounds,
+            last_played_at: HashMap::new(),
         }
     }
 
-    pub fn update(&mut self, message: Message) {
+    pub fn update(&mut self, message: Message) -> Result<(), AudioError> {
         match message {
             Message::SoundLevelChanged(value) => {
                 self.cache.set_volume(value);
             }
+            Message::SetAlertSound(kind, sound) => {
+                if let AlertSound::Custom(name) = &sound {
+                    self.cache.load_custom_sound(name)?;
+                }
+                self.alert_sounds.set(kind, sound);
+            }
             Message::ToggleStream(is_checked, (exchange, ticker)) => {
                 if is_checked {
                     if let Some(streams) = self.streams.get_mut(&exchange) {
@@ -93,7 +132,30 @@ impl AudioStream {
                     cfg.threshold = threshold;
                 }
             }
+            Message::SetLargeTradeThreshold(exchange, ticker, threshold) => {
+                if let Some(streams) = self.streams.get_mut(&exchange)
+                    && let Some(cfg) = streams.get_mut(&ticker)
+                {
+                    cfg.large_trade_threshold = threshold;
+                }
+            }
+            Message::SetCooldown(exchange, ticker, cooldown_ms) => {
+                if let Some(streams) = self.streams.get_mut(&exchange)
+                    && let Some(cfg) = streams.get_mut(&ticker)
+                {
+                    cfg.cooldown_ms = cooldown_ms;
+                }
+            }
+            Message::ToggleRampDown(exchange, ticker, ramp_down) => {
+                if let Some(streams) = self.streams.get_mut(&exchange)
+                    && let Some(cfg) = streams.get_mut(&ticker)
+                {
+                    cfg.ramp_down = ramp_down;
+                }
+            }
         }
+
+        Ok(())
     }
 
     pub fn view(
@@ -117,6 +179,36 @@ impl AudioStream {
             column![text("Sound").size(14), volume_slider,].spacing(8)
         };
 
+        let alert_sounds = {
+            let options: Vec<AlertSound> = std::iter::once(AlertSound::BuiltIn)
+                .chain(self.custom_sounds.iter().cloned().map(AlertSound::Custom))
+                .collect();
+
+            let mut rows = column![].spacing(4);
+            for kind in AlertKind::ALL {
+                let picker = pick_list(
+                    options.clone(),
+                    Some(self.alert_sounds.get(kind).clone()),
+                    move |sound| Message::SetAlertSound(kind, sound),
+                )
+                .text_size(12);
+
+                rows = rows.push(
+                    row![text(kind.to_string()).size(12), space::horizontal(), picker]
+                        .align_y(iced::Alignment::Center)
+                        .spacing(8),
+                );
+            }
+
+            let hint = if self.custom_sounds.is_empty() {
+                "Place WAV files in the sounds folder of the data folder to use them here"
+            } else {
+                "WAV files from the sounds folder of the data folder"
+            };
+
+            column![text("Alert sounds").size(14), rows, text(hint).size(11),].spacing(8)
+        };
+
         let audio_contents = {
             let mut available_streams = column![].spacing(4);
 
@@ -212,6 +304,59 @@ impl AudioStream {
                                 );
                             }
                         }
+
+                        let step = LARGE_TRADE_THRESHOLDS
+                            .iter()
+                            .position(|v| *v >= cfg.large_trade_threshold)
+                            .unwrap_or(LARGE_TRADE_THRESHOLDS.len() - 1);
+
+                        let large_trade_slider = slider(
+                            0.0..=(LARGE_TRADE_THRESHOLDS.len() - 1) as f32,
+                            step as f32,
+                            move |value| {
+                                Message::SetLargeTradeThreshold(
+                                    exchange,
+                                    ticker,
+                                    LARGE_TRADE_THRESHOLDS[value as usize],
+                                )
+                            },
+                        );
+
+                        let label = if cfg.large_trade_threshold > 0.0 {
+                            format!(
+                                "Large trade alert, notional > {}",
+                                data::util::abbr_large_numbers(cfg.large_trade_threshold)
+                            )
+                        } else {
+                            "Large trade alert: off".to_string()
+                        };
+
+                        column = column.push(
+                            column![text(label), large_trade_slider]
+                                .padding(8)
+                                .spacing(4),
+                        );
+
+                        let cooldown_slider = labeled_slider(
+                            "Cooldown",
+                            0.0..=2000.0,
+                            cfg.cooldown_ms as f32,
+                            move |value| Message::SetCooldown(exchange, ticker, value as u64),
+                            |value| format!("{value:.0}ms"),
+                            Some(50.0),
+                        );
+
+                        let ramp_down_checkbox = checkbox(cfg.ramp_down)
+                            .label("Fade out alerts within the cooldown")
+                            .on_toggle(move |is_checked| {
+                                Message::ToggleRampDown(exchange, ticker, is_checked)
+                            });
+
+                        column = column.push(
+                            column![cooldown_slider, ramp_down_checkbox]
+                                .padding(8)
+                                .spacing(4),
+                        );
                     }
 
                     available_streams =
@@ -222,7 +367,7 @@ impl AudioStream {
             column![text("Audio streams").size(14), available_streams,].spacing(8)
         };
 
-        container(column![volume_container, audio_contents,].spacing(20))
+        container(column![volume_container, alert_sounds, audio_contents,].spacing(20))
             .max_width(320)
             .padding(24)
             .style(style::dashboard_modal)
@@ -234,7 +379,27 @@ impl AudioStream {
     }
 
     pub fn play(&mut self, sound: SoundType) -> Result<(), String> {
-        self.cache.play(sound)
+        self.cache.play(sound, 1.0)
+    }
+
+    /// Plays the sound picked for `kind`, `built_in` unless a custom one was chosen
+    pub fn play_alert(&mut self, kind: AlertKind, built_in: SoundType) -> Result<(), String> {
+        self.play_alert_scaled(kind, built_in, 1.0)
+    }
+
+    fn play_alert_scaled(
+        &mut self,
+        kind: AlertKind,
+        built_in: SoundType,
+        gain: f32,
+    ) -> Result<(), String> {
+        match self.alert_sounds.get(kind) {
+            AlertSound::BuiltIn => self.cache.play(built_in, gain),
+            AlertSound::Custom(name) => {
+                let name = name.clone();
+                self.cache.play_custom(&name, gain)
+            }
+        }
     }
 
     pub fn is_stream_audio_enabled(&self, stream: &StreamKind) -> bool {
@@ -305,16 +470,27 @@ impl AudioStream {
                     }
                 };
 
+                let now = Instant::now();
+                let last = self.last_played_at.get(stream);
+                let Some((gain, burst)) = cfg.cooldown_gain(
+                    last.map(|(at, _)| now.duration_since(*at)),
+                    last.map_or(0, |(_, burst)| *burst),
+                ) else {
+                    return Ok(());
+                };
+                self.last_played_at.insert(*stream, (now, burst));
+
+                let trade_alert = AlertKind::Trade;
                 match buy_count.cmp(&sell_count) {
                     std::cmp::Ordering::Greater => {
-                        self.play(sound(buy_count, false))?;
+                        self.play_alert_scaled(trade_alert, sound(buy_count, false), gain)?;
                     }
                     std::cmp::Ordering::Less => {
-                        self.play(sound(sell_count, true))?;
+                        self.play_alert_scaled(trade_alert, sound(sell_count, true), gain)?;
                     }
                     std::cmp::Ordering::Equal => {
-                        self.play(sound(buy_count, false))?;
-                        self.play(sound(sell_count, true))?;
+                        self.play_alert_scaled(trade_alert, sound(buy_count, false), gain)?;
+                        self.play_alert_scaled(trade_alert, sound(sell_count, true), gain)?;
                     }
                 }
             }
@@ -325,6 +501,39 @@ impl AudioStream {
 
         Ok(())
     }
+
+    /// Plays the large buy/sell alert if the trade's notional value exceeds
+    /// the stream's large trade threshold
+    pub fn try_play_large_trade_alert(
+        &mut self,
+        stream: &StreamKind,
+        trade: &Trade,
+    ) -> Result<(), AudioError> {
+        let Some(cfg) = self.should_play_sound(stream) else {
+            return Ok(());
+        };
+
+        let StreamKind::DepthAndTrades { ticker_info, .. } = stream else {
+            return Ok(());
+        };
+
+        let market_type = ticker_info.market_type();
+        let size_in_quote_ccy = volume_size_unit() == exchange::SizeUnit::Quote;
+        let notional = market_type.qty_in_quote_value(trade.qty, trade.price, size_in_quote_ccy);
+
+        if !cfg.is_large_trade(notional) {
+            return Ok(());
+        }
+
+        let sound = if trade.is_sell {
+            SoundType::LargeSell
+        } else {
+            SoundType::LargeBuy
+        };
+
+        self.play_alert(AlertKind::LargeTrade, sound)
+            .map_err(AudioError::Playback)
+    }
 }
 
 impl From<&AudioStream> for data::AudioStream {
@@ -341,6 +550,7 @@ impl From<&AudioStream> for data::AudioStream {
         data::AudioStream {
             volume: audio_stream.cache.get_volume(),
             streams,
+            alert_sounds: audio_stream.alert_sounds.clone(),
         }
     }
 }
@@ -0,0 +1,144 @@
+//! Market data streams of the dashboard with how well each keeps up, and the last errors of
+//! the one picked
+
+use crate::style::{self, exchange_icon, icon_text};
+use data::util::format_duration_ms;
+use exchange::StreamHealth;
+use exchange::adapter::StreamKind;
+use exchange::health::HealthMonitor;
+
+use iced::widget::{button, column, container, row, scrollable, space, text};
+use iced::{Alignment, Element, Length};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Select(StreamKind),
+}
+
+#[derive(Default)]
+pub struct Connections {
+    selected: Option<StreamKind>,
+}
+
+impl Connections {
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::Select(stream) => {
+                self.selected = (self.selected != Some(stream)).then_some(stream);
+            }
+        }
+    }
+
+    /// `now` is the unix ms uptimes are counted to
+    pub fn view<'a>(&'a self, monitor: &'a HealthMonitor, now: u64) -> Element<'a, Message> {
+        let mut streams = monitor.health(now).collect::<Vec<_>>();
+        streams.sort_by_key(|health| {
+            let ticker_info = health.stream.ticker_info();
+            (
+                ticker_info.exchange().to_string(),
+                ticker_info.ticker.to_string(),
+                stream_label(&health.stream),
+            )
+        });
+
+        let content: Element<'_, Message> = if streams.is_empty() {
+            text("No streams are open").size(12).into()
+        } else {
+            let rows = streams.into_iter().map(|health| {
+                let is_selected = self.selected == Some(health.stream);
+                let row = self.stream_row(health, is_selected, now);
+
+                if is_selected {
+                    column![row, self.errors_view(monitor, &health.stream)]
+                        .spacing(4)
+                        .into()
+                } else {
+                    row
+                }
+            });
+
+            scrollable(column(rows).spacing(2)).into()
+        };
+
+        container(column![text("Connections").size(14), content].spacing(12))
+            .width(360)
+            .max_height(480)
+            .padding(24)
+            .style(style::dashboard_modal)
+            .into()
+    }
+
+    fn stream_row<'a>(
+        &self,
+        health: StreamHealth,
+        is_selected: bool,
+        now: u64,
+    ) -> Element<'a, Message> {
+        let status = health.status;
+        let ticker_info = health.stream.ticker_info();
+
+        let uptime = match health.connected_since {
+            Some(since) => format_duration_ms(now.saturating_sub(since)),
+            None => "-".to_string(),
+        };
+
+        let content = row![
+            text("●")
+                .size(10)
+                .style(move |theme| iced::widget::text::Style {
+                    color: Some(style::health_color(theme, status)),
+                }),
+            icon_text(exchange_icon(ticker_info.exchange()), 12),
+            text(format!(
+                "{} {}",
+                ticker_info.ticker,
+                stream_label(&health.stream)
+            ))
+            .size(12),
+            space::horizontal(),
+            text(format!("{:.1} msg/s", health.message_rate)).size(11),
+            text(uptime).size(11).width(56).align_x(Alignment::End),
+        ]
+        .spacing(6)
+        .align_y(Alignment::Center);
+
+        button(content)
+            .on_press(Message::Select(health.stream))
+            .width(Length::Fill)
+            .style(move |theme, status| style::button::transparent(theme, status, is_selected))
+            .into()
+    }
+
+    fn errors_view<'a>(
+        &self,
+        monitor: &'a HealthMonitor,
+        stream: &StreamKind,
+    ) -> Element<'a, Message> {
+        let errors = monitor
+            .errors(stream)
+            .rev()
+            .map(|(at, reason)| {
+                let at = chrono::DateTime::from_timestamp_millis(*at as i64)
+                    .map(|dt| dt.format("%H:%M:%S").to_string())
+                    .unwrap_or_default();
+
+                text(format!("{at}  {reason}")).size(11).into()
+            })
+            .collect::<Vec<Element<'_, Message>>>();
+
+        let content: Element<'_, Message> = if errors.is_empty() {
+            text("No errors since the stream opened").size(11).into()
+        } else {
+            column(errors).spacing(4).into()
+        };
+
+        container(content).padding([4, 12]).into()
+    }
+}
+
+fn stream_label(stream: &StreamKind) -> String {
+    match stream {
+        StreamKind::Kline { timeframe, .. } => timeframe.to_string(),
+        StreamKind::DepthAndTrades { .. } => "Depth & trades".to_string(),
+    }
+}
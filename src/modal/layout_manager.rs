@@ -13,6 +13,9 @@ use iced::{Element, Theme, padding};
 use std::vec;
 use uuid::Uuid;
 
+/// Layouts reachable with the `Ctrl+1` to `Ctrl+9` shortcuts
+pub const SHORTCUT_LAYOUTS: usize = 9;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Editing {
     ConfirmingDelete(Uuid),
@@ -55,6 +58,8 @@ impl LayoutManager {
             layouts: vec![Layout {
                 id: default_layout.clone(),
                 dashboard: Dashboard::default(),
+                timezone: None,
+                theme: None,
             }],
             active_layout_id: Some(default_layout.unique),
             edit_mode: Editing::None,
@@ -81,12 +86,30 @@ impl LayoutManager {
             .find(|layout| layout.id.unique == unique)
     }
 
+    /// Layout at `index` in the manager's order, `None` past the last one
+    pub fn layout_at(&self, index: usize) -> Option<Uuid> {
+        self.layouts.get(index).map(|layout| layout.id.unique)
+    }
+
     pub fn active_layout_id(&self) -> Option<&LayoutId> {
         self.get(self.active_layout_id?).map(|layout| &layout.id)
     }
 
+    pub fn active_layout(&self) -> Option<&Layout> {
+        self.get(self.active_layout_id?)
+    }
+
+    pub fn active_layout_mut(&mut self) -> Option<&mut Layout> {
+        self.get_mut(self.active_layout_id?)
+    }
+
     pub fn insert_layout(&mut self, id: LayoutId, dashboard: Dashboard) {
-        self.layouts.push(Layout { id, dashboard });
+        self.layouts.push(Layout {
+            id,
+            dashboard,
+            timezone: None,
+            theme: None,
+        });
     }
 
     pub fn generate_unique_layout_name(&self) -> String {
@@ -227,7 +250,7 @@ impl LayoutManager {
 
         let mut layout_widgets: Vec<Element<'_, Message>> = vec![];
 
-        for layout in &self.layouts {
+        for (index, layout) in self.layouts.iter().enumerate() {
             let layout_id = &layout.id;
 
             let mut layout_row = row![].height(iced::Length::Fixed(32.0)).padding(4);
@@ -243,7 +266,7 @@ impl LayoutManager {
                             .push(confirm_btn)
                             .push(cancel_btn);
                     } else {
-                        layout_row = layout_row.push(create_layout_button(layout_id, None));
+                        layout_row = layout_row.push(create_layout_button(layout_id, index, None));
                     }
                 }
                 Editing::Renaming(renaming_id, name) => {
@@ -258,12 +281,12 @@ impl LayoutManager {
                             .push(center(input_box).padding(padding::left(4)))
                             .push(cancel_btn);
                     } else {
-                        layout_row = layout_row.push(create_layout_button(layout_id, None));
+                        layout_row = layout_row.push(create_layout_button(layout_id, index, None));
                     }
                 }
                 Editing::Preview => {
                     layout_row = layout_row
-                        .push(create_layout_button(layout_id, None))
+                        .push(create_layout_button(layout_id, index, None))
                         .push(create_clone_button(layout_id))
                         .push(create_rename_button(layout_id));
 
@@ -274,6 +297,7 @@ impl LayoutManager {
                 Editing::None => {
                     layout_row = layout_row.push(create_layout_button(
                         layout_id,
+                        index,
                         if is_active {
                             None
                         } else {
@@ -396,8 +420,22 @@ fn create_confirm_delete_buttons<'a>(
     (confirm, cancel)
 }
 
-fn create_layout_button<'a>(layout: &LayoutId, on_press: Option<Message>) -> Element<'a, Message> {
-    let mut layout_btn = button(text(layout.name.clone()).align_y(iced::Alignment::Center))
+fn create_layout_button<'a>(
+    layout: &LayoutId,
+    index: usize,
+    on_press: Option<Message>,
+) -> Element<'a, Message> {
+    let mut label = row![text(layout.name.clone())].align_y(iced::Alignment::Center);
+
+    if index < SHORTCUT_LAYOUTS {
+        label = label.push(space::horizontal()).push(
+            container(text(index + 1).size(10))
+                .padding(padding::left(4).right(4))
+                .style(style::tooltip),
+        );
+    }
+
+    let mut layout_btn = button(label)
         .width(iced::Length::Fill)
         .style(style::button::layout_name);
 
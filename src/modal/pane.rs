@@ -1,8 +1,10 @@
+use crate::widget::export::ExportScale;
 use iced::{
     Alignment, Element, Length, padding,
     widget::{container, mouse_area, opaque},
 };
 
+pub mod alerts;
 pub mod indicators;
 pub mod mini_tickers_list;
 pub mod settings;
@@ -14,8 +16,19 @@ pub enum Modal {
     MiniTickersList(mini_tickers_list::MiniPanel),
     Settings,
     Indicators,
+    PriceAlerts,
+    Export(ExportScale),
+    /// Note editor, its draft is kept on the pane
+    Note,
+    /// Name typed for a new template and the templates found in the data folder when opened
+    Templates {
+        name: String,
+        saved: Vec<data::ChartTemplate>,
+    },
     LinkGroup,
     Controls,
+    /// Pane actions opened by a right click, anchored at the cursor within the pane body
+    ContextMenu(iced::Point),
 }
 
 pub fn stack_modal<'a, Message>(
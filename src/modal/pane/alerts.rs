@@ -0,0 +1,114 @@
+use crate::chart;
+use crate::screen::dashboard::pane::{self, Message};
+use crate::style::{self, Icon, icon_text};
+
+use data::chart::annotation::{AlertDirection, PriceAlert, PriceLine};
+use exchange::TickerInfo;
+use iced::{
+    Alignment, Element, Length,
+    widget::{button, checkbox, column, container, pane_grid, pick_list, row, space, text},
+};
+
+pub fn view<'a>(
+    pane: pane_grid::Pane,
+    lines: &'a [PriceLine],
+    ticker_info: Option<TickerInfo>,
+) -> Element<'a, Message> {
+    let content: Element<_> = match ticker_info {
+        Some(ticker_info) if !lines.is_empty() => iced::widget::Column::with_children(
+            lines
+                .iter()
+                .enumerate()
+                .map(|(index, line)| line_row(pane, index, line, ticker_info)),
+        )
+        .spacing(8)
+        .into(),
+        _ => text("Double-click the chart to add a price line")
+            .size(12)
+            .into(),
+    };
+
+    container(column![text("Price alerts").size(14), content].spacing(12))
+        .max_width(420)
+        .padding(16)
+        .style(style::chart_modal)
+        .into()
+}
+
+fn line_row<'a>(
+    pane: pane_grid::Pane,
+    index: usize,
+    line: &'a PriceLine,
+    ticker_info: TickerInfo,
+) -> Element<'a, Message> {
+    let interaction =
+        move |msg: chart::Message| Message::PaneEvent(pane, pane::Event::ChartInteraction(msg));
+
+    let price = line.price.to_string(ticker_info.min_ticksize);
+    let title = if line.label.is_empty() {
+        text(price)
+    } else {
+        text(format!("{} {}", line.label, price))
+    };
+
+    let direction = pick_list(
+        AlertDirection::ALL,
+        line.alert.map(|alert| alert.direction),
+        move |direction| {
+            let alert = match line.alert {
+                Some(alert) => PriceAlert { direction, ..alert },
+                None => PriceAlert::new(direction),
+            };
+            interaction(chart::Message::SetPriceAlert(index, Some(alert)))
+        },
+    )
+    .placeholder("No alert")
+    .text_size(12);
+
+    let mut controls = row![direction].spacing(4).align_y(Alignment::Center);
+
+    if let Some(alert) = line.alert {
+        controls = controls.push(
+            checkbox(alert.sound)
+                .label("Sound")
+                .on_toggle(move |sound| {
+                    interaction(chart::Message::SetPriceAlert(
+                        index,
+                        Some(PriceAlert { sound, ..alert }),
+                    ))
+                }),
+        );
+
+        if alert.triggered {
+            controls = controls.push(
+                button(text("Re-arm").size(12))
+                    .on_press(interaction(chart::Message::SetPriceAlert(
+                        index,
+                        Some(PriceAlert {
+                            triggered: false,
+                            ..alert
+                        }),
+                    )))
+                    .style(move |theme, status| style::button::transparent(theme, status, false)),
+            );
+        }
+
+        controls = controls.push(
+            button(icon_text(Icon::Close, 12))
+                .on_press(interaction(chart::Message::SetPriceAlert(index, None)))
+                .style(move |theme, status| style::button::transparent(theme, status, false)),
+        );
+    }
+
+    controls = controls.push(
+        button(icon_text(Icon::TrashBin, 12))
+            .on_press(interaction(chart::Message::RemovePriceLine(index)))
+            .style(move |theme, status| style::button::transparent(theme, status, false)),
+    );
+
+    row![title.size(12), space::horizontal(), controls]
+        .width(Length::Fill)
+        .spacing(8)
+        .align_y(Alignment::Center)
+        .into()
+}
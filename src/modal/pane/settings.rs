@@ -5,16 +5,22 @@ use crate::split_column;
 use crate::widget::{classic_slider_row, labeled_slider};
 use crate::{style, tooltip, widget::scrollable_content};
 use data::chart::heatmap::HeatmapStudy;
-use data::chart::kline::FootprintStudy;
+use data::chart::kline::{
+    ClusterScaling, DepthLayer, FootprintCellText, FootprintStudy, KlineStudy,
+    MAX_LOADED_TIMEFRAMES,
+};
 use data::chart::{
-    KlineChartKind,
+    KlineChartKind, PriceAxisRounding, correlation,
     heatmap::{self, CoalesceKind},
     kline::ClusterKind,
+    range::{self, RANGE_TICKS_OPTIONS},
+    spread, stats_panel,
 };
 use data::layout::pane::VisualConfig;
-use data::panel::ladder;
 use data::panel::timeandsales::{StackedBar, StackedBarRatio};
+use data::panel::{imbalance, ladder};
 use data::util::format_with_commas;
+use exchange::Timeframe;
 
 use iced::widget::{checkbox, space};
 use iced::{
@@ -25,7 +31,6 @@ use iced::{
     },
 };
 use std::time::Duration;
-use crate::screen::ConfirmDialog;
 
 fn cfg_view_container<'a, T>(max_width: u32, content: T) -> Element<'a, Message>
 where
@@ -460,42 +465,156 @@ pub fn kline_cfg_view<'a>(
     study_config: &'a study::Configurator<FootprintStudy>,
     cfg: data::chart::kline::Config,
     kind: &'a KlineChartKind,
+    kline_studies: &'a [KlineStudy],
+    timeframe_tabs: &'a [Timeframe],
     pane: pane_grid::Pane,
     basis: data::chart::Basis,
+    hybrid_weight: f32,
+    price_rounding: (PriceAxisRounding, Vec<PriceAxisRounding>),
+    timezone: data::UserTimezone,
 ) -> Element<'a, Message> {
+    let scale_padding = labeled_slider(
+        "Padding",
+        data::chart::kline::Config::SCALE_PADDING_RANGE,
+        cfg.scale_padding,
+        move |value| {
+            Message::VisualConfigChanged(
+                pane,
+                VisualConfig::Kline(data::chart::kline::Config {
+                    scale_padding: value,
+                    ..cfg
+                }),
+                false,
+            )
+        },
+        |value| format!("{value:.0}%"),
+        Some(1.0),
+    );
+
+    let anchored_vwap = anchored_vwap_list(cfg, kline_studies, pane, timezone);
+    let keltner_channel = keltner_channel_cfg(kline_studies, pane);
+    let depth_imbalance = depth_imbalance_cfg(kline_studies, pane);
+    let ichimoku = ichimoku_cfg(kline_studies, pane);
+    let supertrend = supertrend_cfg(kline_studies, pane);
+    let max_delta_bar = max_delta_bar_toggle(kline_studies, pane);
+    let stochastic = stochastic_cfg(kline_studies, pane);
+
+    let (rounding, rounding_choices) = price_rounding;
+    let price_rounding = column![
+        text("Label rounding"),
+        pick_list(rounding_choices, Some(rounding), move |rounding| {
+            Message::PaneEvent(
+                pane,
+                Event::ChartInteraction(crate::chart::Message::PriceRoundingChanged(rounding)),
+            )
+        }),
+    ]
+    .spacing(4);
+
+    let session_separator = pick_list(
+        data::chart::kline::SessionSeparator::ALL,
+        Some(cfg.session_separator),
+        move |session_separator| {
+            Message::VisualConfigChanged(
+                pane,
+                VisualConfig::Kline(data::chart::kline::Config {
+                    session_separator,
+                    ..cfg
+                }),
+                false,
+            )
+        },
+    );
+
     let content = match kind {
-        KlineChartKind::Candles => column![text(
-            "This chart type doesn't have any configurations, WIP..."
-        )],
+        KlineChartKind::Candles {
+            show_volume_profile,
+        } => split_column![
+            column![text("Candle coloring").size(14), candle_coloring(cfg, pane)].spacing(8),
+            column![
+                text("Candle style").size(14),
+                candle_style(cfg, pane),
+                candle_proportions(cfg, pane)
+            ]
+            .spacing(8),
+            column![text("Price scale").size(14), scale_padding, price_rounding].spacing(8),
+            column![text("Session separators").size(14), session_separator].spacing(8),
+            column![text("New bars").size(14), flash_new_bar_toggle(cfg, pane)].spacing(8),
+            column![text("Overview strip").size(14), minimap(cfg, pane)].spacing(8),
+            column![text("Link group").size(14), compare_previous_on_link(cfg, pane)].spacing(8),
+            column![
+                text("Timeframe tabs").size(14),
+                timeframe_tabs_cfg(timeframe_tabs, pane)
+            ]
+            .spacing(8),
+            column![text("Volume").size(14), volume_display(cfg, pane)].spacing(8),
+            column![
+                text("Studies").size(14),
+                checkbox(*show_volume_profile)
+                    .label("Volume profile panel")
+                    .on_toggle(move |value| {
+                        Message::PaneEvent(pane, Event::CandleVolumeProfileToggled(value))
+                    }),
+                anchored_vwap,
+                keltner_channel,
+                ichimoku,
+                supertrend,
+                max_delta_bar,
+                stochastic,
+                depth_imbalance
+            ]
+            .spacing(8),
+            row![
+                space::horizontal(),
+                sync_all_button(pane, VisualConfig::Kline(cfg))
+            ],
+            ; spacing = 12, align_x = Alignment::Start
+        ],
         KlineChartKind::Footprint {
             clusters,
             scaling,
             studies,
+            depth_layer,
+            cell_text,
         } => {
             let cluster_picklist =
                 pick_list(ClusterKind::ALL, Some(clusters), move |new_cluster_kind| {
                     Message::PaneEvent(pane, Event::ClusterKindSelected(new_cluster_kind))
                 });
 
+            let cell_text_picklist = pick_list(
+                FootprintCellText::ALL,
+                Some(cell_text),
+                move |new_cell_text| {
+                    Message::PaneEvent(pane, Event::FootprintCellTextSelected(new_cell_text))
+                },
+            );
+
             let scaling = {
                 let picklist = pick_list(
-                    data::chart::kline::ClusterScaling::ALL,
+                    ClusterScaling::options(hybrid_weight),
                     Some(scaling),
                     move |new_scaling| {
                         Message::PaneEvent(pane, Event::ClusterScalingSelected(new_scaling))
                     },
                 );
 
-                if let data::chart::kline::ClusterScaling::Hybrid { weight } = scaling {
-                    let hybrid_slider = slider(0.0..=1.0, *weight, move |new_weight| {
-                        Message::PaneEvent(
-                            pane,
-                            Event::ClusterScalingSelected(
-                                data::chart::kline::ClusterScaling::Hybrid { weight: new_weight },
-                            ),
-                        )
-                    })
-                    .step(0.05);
+                if let ClusterScaling::Hybrid { weight } = scaling {
+                    let hybrid_slider = labeled_slider(
+                        "Weight",
+                        ClusterScaling::HYBRID_WEIGHT_RANGE,
+                        weight.clamp(0.0, 1.0),
+                        move |new_weight| {
+                            Message::PaneEvent(
+                                pane,
+                                Event::ClusterScalingSelected(ClusterScaling::Hybrid {
+                                    weight: new_weight,
+                                }),
+                            )
+                        },
+                        |weight| format!("{weight:.2}"),
+                        Some(0.05),
+                    );
 
                     column![
                         picklist,
@@ -515,10 +634,43 @@ pub fn kline_cfg_view<'a>(
                 )
             });
 
+            let mut cluster_column = column![
+                text("Cluster type").size(14),
+                cluster_picklist,
+                text("Cell text").size(14),
+                cell_text_picklist,
+                text("Cell rendering").size(14),
+                cell_renderer(cfg, pane),
+            ]
+            .spacing(8);
+            if let Some(layer) = depth_layer {
+                cluster_column = cluster_column
+                    .push(text("Layer opacity").size(14))
+                    .push(depth_layer_opacity(*layer, pane));
+            }
+
             split_column![
-                column![text("Cluster type").size(14), cluster_picklist].spacing(8),
+                cluster_column,
                 column![text("Cluster scaling").size(14), scaling].spacing(8),
-                column![text("Studies").size(14), study_cfg].spacing(8),
+                column![text("Price scale").size(14), scale_padding, price_rounding].spacing(8),
+                column![text("Session separators").size(14), session_separator].spacing(8),
+                column![text("New bars").size(14), flash_new_bar_toggle(cfg, pane)].spacing(8),
+                column![text("Small panes").size(14), compact_below(cfg, pane)].spacing(8),
+                column![text("Volume").size(14), volume_display(cfg, pane)].spacing(8),
+                column![
+                    text("Studies").size(14),
+                    study_cfg,
+                    poc_history_toggle(cfg, pane),
+                    visible_profile_toggle(cfg, pane),
+                    anchored_vwap,
+                    keltner_channel,
+                    ichimoku,
+                    supertrend,
+                    max_delta_bar,
+                    stochastic,
+                    depth_imbalance
+                ]
+                .spacing(8),
                 row![
                     space::horizontal(),
                     sync_all_button(pane, VisualConfig::Kline(cfg))
@@ -531,160 +683,1197 @@ pub fn kline_cfg_view<'a>(
     cfg_view_container(360, content)
 }
 
-pub fn ladder_cfg_view<'a>(cfg: ladder::Config, pane: pane_grid::Pane) -> Element<'a, Message> {
-    let display_options = {
-        let spread = checkbox(cfg.show_spread)
-            .label("Show Spread")
-            .on_toggle(move |value| {
-                Message::VisualConfigChanged(
-                    pane,
-                    VisualConfig::Ladder(ladder::Config {
-                        show_spread: value,
-                        ..cfg
-                    }),
-                    false,
-                )
-            });
-
-        let chase_tracker = checkbox(cfg.show_chase_tracker)
-            .label("Show Chase Tracker")
-            .on_toggle(move |value| {
-                Message::VisualConfigChanged(
-                    pane,
-                    VisualConfig::Ladder(ladder::Config {
-                        show_chase_tracker: value,
-                        ..cfg
-                    }),
-                    false,
-                )
-            });
-
-        column![
-            text("Display Options").size(14),
-            column![
-                spread,
-                row![
-                    chase_tracker,
-                    tooltip(
-                        button("i").style(style::button::info),
-                        Some("Highlights consecutive best-price moves and fades when momentum stalls.\nCalculated using raw ungrouped data."),
-                        TooltipPosition::Top,
-                    )
-                ]
-                .align_y(Alignment::Center)
-                .spacing(4)
-            ]
-            .spacing(4)
-        ]
-        .spacing(8)
-    };
+fn depth_layer_opacity<'a>(layer: DepthLayer, pane: pane_grid::Pane) -> Element<'a, Message> {
+    let heatmap = labeled_slider(
+        "Heatmap opacity",
+        DepthLayer::OPACITY_RANGE,
+        layer.heatmap_opacity,
+        move |value| {
+            Message::PaneEvent(
+                pane,
+                Event::DepthLayerChanged(DepthLayer {
+                    heatmap_opacity: value,
+                    ..layer
+                }),
+            )
+        },
+        |value| format!("{value}%"),
+        Some(5),
+    );
+
+    let footprint = labeled_slider(
+        "Footprint opacity",
+        DepthLayer::OPACITY_RANGE,
+        layer.footprint_opacity,
+        move |value| {
+            Message::PaneEvent(
+                pane,
+                Event::DepthLayerChanged(DepthLayer {
+                    footprint_opacity: value,
+                    ..layer
+                }),
+            )
+        },
+        |value| format!("{value}%"),
+        Some(5),
+    );
 
-    let retention_slider = {
-        let retention_minutes = (cfg.trade_retention.as_secs_f32() / 60.0).max(1.0);
+    column![heatmap, footprint].spacing(8).into()
+}
 
-        let slider_ui = slider(1.0..=60.0, retention_minutes, move |new_minutes| {
-            let mins = new_minutes.round().max(1.0) as u64;
+fn candle_style<'a>(
+    cfg: data::chart::kline::Config,
+    pane: pane_grid::Pane,
+) -> Element<'a, Message> {
+    pick_list(
+        data::chart::kline::CandleStyle::ALL,
+        Some(cfg.candle_style),
+        move |candle_style| {
             Message::VisualConfigChanged(
                 pane,
-                VisualConfig::Ladder(ladder::Config {
-                    trade_retention: Duration::from_secs(mins * 60),
+                VisualConfig::Kline(data::chart::kline::Config {
+                    candle_style,
                     ..cfg
                 }),
                 false,
             )
-        })
-        .step(1.0);
+        },
+    )
+    .into()
+}
 
-        classic_slider_row(
-            text("Keep trades for"),
-            slider_ui.into(),
-            Some(text(format!("≈ {} min", retention_minutes.round() as u64)).size(13)),
-        )
-    };
+fn candle_proportions<'a>(
+    cfg: data::chart::kline::Config,
+    pane: pane_grid::Pane,
+) -> Element<'a, Message> {
+    let body_width = labeled_slider(
+        "Body width",
+        data::chart::kline::Config::CANDLE_BODY_WIDTH_RANGE,
+        cfg.candle_body_width,
+        move |value| {
+            Message::VisualConfigChanged(
+                pane,
+                VisualConfig::Kline(data::chart::kline::Config {
+                    candle_body_width: value,
+                    ..cfg
+                }),
+                false,
+            )
+        },
+        |value| format!("{:.0}%", value * 100.0),
+        Some(0.05),
+    );
+
+    let wick_width = labeled_slider(
+        "Wick width",
+        data::chart::kline::Config::CANDLE_WICK_WIDTH_RANGE,
+        cfg.candle_wick_width,
+        move |value| {
+            Message::VisualConfigChanged(
+                pane,
+                VisualConfig::Kline(data::chart::kline::Config {
+                    candle_wick_width: value,
+                    ..cfg
+                }),
+                false,
+            )
+        },
+        |value| format!("{:.0}%", value * 100.0),
+        Some(0.05),
+    );
 
-    let history_column = column![text("History").size(14), retention_slider].spacing(8);
+    column![body_width, wick_width].spacing(8).into()
+}
 
-    let content = split_column![
-        display_options,
-        history_column,
-        row![
-            space::horizontal(),
-            sync_all_button(pane, VisualConfig::Ladder(cfg))
-        ],
-        ; spacing = 12, align_x = Alignment::Start
-    ];
+fn candle_coloring<'a>(
+    cfg: data::chart::kline::Config,
+    pane: pane_grid::Pane,
+) -> Element<'a, Message> {
+    pick_list(
+        data::chart::kline::CandleColoring::ALL,
+        Some(cfg.candle_coloring),
+        move |candle_coloring| {
+            Message::VisualConfigChanged(
+                pane,
+                VisualConfig::Kline(data::chart::kline::Config {
+                    candle_coloring,
+                    ..cfg
+                }),
+                false,
+            )
+        },
+    )
+    .into()
+}
 
-    cfg_view_container(320, content)
+fn volume_display<'a>(
+    cfg: data::chart::kline::Config,
+    pane: pane_grid::Pane,
+) -> Element<'a, Message> {
+    pick_list(
+        data::chart::kline::VolumeDisplayMode::ALL,
+        Some(cfg.volume_display),
+        move |volume_display| {
+            Message::VisualConfigChanged(
+                pane,
+                VisualConfig::Kline(data::chart::kline::Config {
+                    volume_display,
+                    ..cfg
+                }),
+                false,
+            )
+        },
+    )
+    .into()
 }
 
-fn sync_all_button<'a>(pane: pane_grid::Pane, config: VisualConfig) -> Element<'a, Message> {
+fn cell_renderer<'a>(
+    cfg: data::chart::kline::Config,
+    pane: pane_grid::Pane,
+) -> Element<'a, Message> {
+    if !crate::chart::wgpu_active() {
+        return tooltip(
+            text("Canvas").size(13),
+            Some("GPU cell bars need the wgpu renderer, this window is drawn by tiny-skia"),
+            TooltipPosition::Top,
+        );
+    }
+
+    let picklist = pick_list(
+        data::chart::kline::CellRenderer::ALL,
+        Some(cfg.cell_renderer),
+        move |cell_renderer| {
+            Message::VisualConfigChanged(
+                pane,
+                VisualConfig::Kline(data::chart::kline::Config {
+                    cell_renderer,
+                    ..cfg
+                }),
+                false,
+            )
+        },
+    );
+
     tooltip(
-        button("Sync all").on_press(Message::VisualConfigChanged(pane, config, true)),
-        Some("Apply configuration to similar panes"),
+        picklist,
+        Some("GPU draws the cell bars in a shader, cheaper on dense footprints"),
         TooltipPosition::Top,
     )
 }
 
-pub mod study {
-    use crate::{
-        split_column,
-        style::{self, Icon, icon_text},
-    };
-    use data::chart::heatmap::{CLEANUP_THRESHOLD, HeatmapStudy, ProfileKind};
-    use data::chart::kline::FootprintStudy;
-    use iced::{
-        Element, padding,
-        widget::{button, checkbox, column, container, row, slider, space, text},
-    };
-
-    #[derive(Debug, Clone, Copy)]
-    pub enum StudyMessage {
-        Footprint(Message<FootprintStudy>),
-        Heatmap(Message<HeatmapStudy>),
-    }
+fn poc_history_toggle<'a>(
+    cfg: data::chart::kline::Config,
+    pane: pane_grid::Pane,
+) -> Element<'a, Message> {
+    checkbox(cfg.poc_history)
+        .label("POC history")
+        .on_toggle(move |value| {
+            Message::VisualConfigChanged(
+                pane,
+                VisualConfig::Kline(data::chart::kline::Config {
+                    poc_history: value,
+                    ..cfg
+                }),
+                false,
+            )
+        })
+        .into()
+}
 
-    pub trait Study: Sized + Copy + 'static {
-        fn is_same_type(&self, other: &Self) -> bool;
-        fn all() -> Vec<Self>;
-        fn view_config<'a>(
-            &self,
-            basis: data::chart::Basis,
-            on_change: impl Fn(Self) -> Message<Self> + Copy + 'a,
-        ) -> Element<'a, Message<Self>>;
-    }
+fn flash_new_bar_toggle<'a>(
+    cfg: data::chart::kline::Config,
+    pane: pane_grid::Pane,
+) -> Element<'a, Message> {
+    checkbox(cfg.flash_new_bar)
+        .label("Flash when a new bar opens")
+        .on_toggle(move |value| {
+            Message::VisualConfigChanged(
+                pane,
+                VisualConfig::Kline(data::chart::kline::Config {
+                    flash_new_bar: value,
+                    ..cfg
+                }),
+                false,
+            )
+        })
+        .into()
+}
 
-    impl Study for FootprintStudy {
-        fn is_same_type(&self, other: &Self) -> bool {
-            std::mem::discriminant(self) == std::mem::discriminant(other)
-        }
+fn timeframe_tabs_cfg<'a>(tabs: &[Timeframe], pane: pane_grid::Pane) -> Element<'a, Message> {
+    let toggles = Timeframe::KLINE
+        .iter()
+        .fold(row![].spacing(2), |toggles, timeframe| {
+            let is_tab = tabs.contains(timeframe);
 
-        fn all() -> Vec<Self> {
-            FootprintStudy::ALL.to_vec()
-        }
+            toggles.push(
+                button(text(timeframe.to_string()).size(11))
+                    .on_press(Message::PaneEvent(
+                        pane,
+                        Event::TimeframeTabToggled(*timeframe),
+                    ))
+                    .style(move |theme, status| style::button::transparent(theme, status, is_tab)),
+            )
+        });
 
-        fn view_config<'a>(
-            &self,
-            _basis: data::chart::Basis,
-            on_change: impl Fn(Self) -> Message<Self> + Copy + 'a,
-        ) -> Element<'a, Message<Self>> {
-            match *self {
-                FootprintStudy::NPoC { lookback } => {
-                    let slider_ui = slider(10.0..=400.0, lookback as f32, move |new_value| {
-                        on_change(FootprintStudy::NPoC {
-                            lookback: new_value as usize,
-                        })
-                    })
-                    .step(10.0);
+    column![
+        toggles,
+        text(format!(
+            "Switch from the header, the last {MAX_LOADED_TIMEFRAMES} viewed stay loaded and live"
+        ))
+        .size(11),
+    ]
+    .spacing(8)
+    .into()
+}
 
-                    column![text(format!("Lookback: {lookback} datapoints")), slider_ui]
-                        .padding(8)
-                        .spacing(4)
-                        .into()
-                }
-                FootprintStudy::Imbalance {
-                    threshold,
-                    color_scale,
-                    ignore_zeros,
+fn compare_previous_on_link<'a>(
+    cfg: data::chart::kline::Config,
+    pane: pane_grid::Pane,
+) -> Element<'a, Message> {
+    let toggle = checkbox(cfg.compare_previous_on_link)
+        .label("Compare against the previous ticker")
+        .on_toggle(move |value| {
+            Message::VisualConfigChanged(
+                pane,
+                VisualConfig::Kline(data::chart::kline::Config {
+                    compare_previous_on_link: value,
+                    ..cfg
+                }),
+                false,
+            )
+        });
+
+    tooltip(
+        toggle,
+        Some("When the link group switches this chart's ticker, the one it had is overlaid"),
+        TooltipPosition::Top,
+    )
+}
+
+fn minimap<'a>(cfg: data::chart::kline::Config, pane: pane_grid::Pane) -> Element<'a, Message> {
+    let toggle = checkbox(cfg.minimap)
+        .label("Show under the chart")
+        .on_toggle(move |value| {
+            Message::VisualConfigChanged(
+                pane,
+                VisualConfig::Kline(data::chart::kline::Config {
+                    minimap: value,
+                    ..cfg
+                }),
+                false,
+            )
+        });
+
+    let height = labeled_slider(
+        "Height",
+        data::chart::kline::Config::MINIMAP_HEIGHT_RANGE,
+        cfg.minimap_height,
+        move |value| {
+            Message::VisualConfigChanged(
+                pane,
+                VisualConfig::Kline(data::chart::kline::Config {
+                    minimap_height: value,
+                    ..cfg
+                }),
+                false,
+            )
+        },
+        |value| format!("{value:.0}px"),
+        Some(1.0),
+    );
+
+    column![toggle, height].spacing(8).into()
+}
+
+fn compact_below<'a>(
+    cfg: data::chart::kline::Config,
+    pane: pane_grid::Pane,
+) -> Element<'a, Message> {
+    let slider = labeled_slider(
+        "Plain candles below",
+        data::chart::kline::Config::COMPACT_BELOW_RANGE,
+        cfg.compact_below,
+        move |value| {
+            Message::VisualConfigChanged(
+                pane,
+                VisualConfig::Kline(data::chart::kline::Config {
+                    compact_below: value,
+                    ..cfg
+                }),
+                false,
+            )
+        },
+        |value| {
+            if *value > 0.0 {
+                format!("{value:.0}px")
+            } else {
+                "Off".to_string()
+            }
+        },
+        Some(20.0),
+    );
+
+    column![
+        slider,
+        text("Axis labels are hidden too, full detail comes back once enlarged").size(11),
+    ]
+    .spacing(8)
+    .into()
+}
+
+fn visible_profile_toggle<'a>(
+    cfg: data::chart::kline::Config,
+    pane: pane_grid::Pane,
+) -> Element<'a, Message> {
+    checkbox(cfg.visible_profile)
+        .label("Visible range profile")
+        .on_toggle(move |value| {
+            Message::VisualConfigChanged(
+                pane,
+                VisualConfig::Kline(data::chart::kline::Config {
+                    visible_profile: value,
+                    ..cfg
+                }),
+                false,
+            )
+        })
+        .into()
+}
+
+fn anchored_vwap_list<'a>(
+    cfg: data::chart::kline::Config,
+    kline_studies: &'a [KlineStudy],
+    pane: pane_grid::Pane,
+    timezone: data::UserTimezone,
+) -> Element<'a, Message> {
+    let toggle = checkbox(cfg.anchored_vwap)
+        .label("Anchored VWAP")
+        .on_toggle(move |value| {
+            Message::VisualConfigChanged(
+                pane,
+                VisualConfig::Kline(data::chart::kline::Config {
+                    anchored_vwap: value,
+                    ..cfg
+                }),
+                false,
+            )
+        });
+
+    let mut content = column![toggle].spacing(4);
+
+    if !cfg.anchored_vwap {
+        return content.into();
+    }
+
+    let is_anchored_vwap = |study: &KlineStudy| matches!(study, KlineStudy::AnchoredVwap { .. });
+
+    if !kline_studies.iter().any(is_anchored_vwap) {
+        content = content.push(text("Right-click a candle to anchor a VWAP").size(11));
+    }
+
+    for (index, study) in kline_studies.iter().enumerate() {
+        let KlineStudy::AnchoredVwap { anchor_time, color } = *study else {
+            continue;
+        };
+
+        let [r, g, b, a] = color;
+        let swatch = container(space::horizontal())
+            .width(10)
+            .height(10)
+            .style(move |_theme| container::Style {
+                background: Some(iced::Color::from_rgba8(r, g, b, f32::from(a) / 255.0).into()),
+                ..Default::default()
+            });
+
+        let anchored_at = data::format_timestamp(
+            anchor_time,
+            timezone,
+            data::i18n::Language::from_code(data::i18n::current_language()),
+        );
+
+        content = content.push(
+            row![
+                swatch,
+                text(anchored_at).size(11),
+                space::horizontal(),
+                button(text("Remove").size(11))
+                    .on_press(Message::PaneEvent(pane, Event::RemoveKlineStudy(index))),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center),
+        );
+    }
+
+    content.into()
+}
+
+fn keltner_channel_cfg<'a>(
+    kline_studies: &'a [KlineStudy],
+    pane: pane_grid::Pane,
+) -> Element<'a, Message> {
+    let existing = kline_studies
+        .iter()
+        .enumerate()
+        .find(|(_, study)| study.is_same_type(&KlineStudy::KELTNER_CHANNEL));
+
+    let toggle = checkbox(existing.is_some())
+        .label("Keltner Channel")
+        .on_toggle(move |value| match existing {
+            Some((index, _)) if !value => Message::PaneEvent(pane, Event::RemoveKlineStudy(index)),
+            _ => Message::PaneEvent(pane, Event::SetKlineStudy(KlineStudy::KELTNER_CHANNEL)),
+        });
+
+    let Some((
+        _,
+        &KlineStudy::KeltnerChannel {
+            ema_period,
+            atr_period,
+            multiplier,
+            highlight_squeeze,
+        },
+    )) = existing
+    else {
+        return toggle.into();
+    };
+
+    let set = move |ema_period, atr_period, multiplier, highlight_squeeze| {
+        Message::PaneEvent(
+            pane,
+            Event::SetKlineStudy(KlineStudy::KeltnerChannel {
+                ema_period,
+                atr_period,
+                multiplier,
+                highlight_squeeze,
+            }),
+        )
+    };
+
+    let ema_slider = labeled_slider(
+        "EMA",
+        5.0..=50.0,
+        ema_period as f32,
+        move |value| set(value as usize, atr_period, multiplier, highlight_squeeze),
+        |value| format!("{value:.0} bars"),
+        Some(1.0),
+    );
+
+    let atr_slider = labeled_slider(
+        "ATR",
+        5.0..=50.0,
+        atr_period as f32,
+        move |value| set(ema_period, value as usize, multiplier, highlight_squeeze),
+        |value| format!("{value:.0} bars"),
+        Some(1.0),
+    );
+
+    let multiplier_slider = labeled_slider(
+        "Width",
+        0.5..=4.0,
+        multiplier,
+        move |value| set(ema_period, atr_period, value, highlight_squeeze),
+        |value| format!("{value:.2} ATR"),
+        Some(0.25),
+    );
+
+    let squeeze = checkbox(highlight_squeeze)
+        .label("Highlight squeezes")
+        .on_toggle(move |value| set(ema_period, atr_period, multiplier, value));
+
+    column![toggle, ema_slider, atr_slider, multiplier_slider, squeeze]
+        .spacing(4)
+        .into()
+}
+
+fn ichimoku_cfg<'a>(
+    kline_studies: &'a [KlineStudy],
+    pane: pane_grid::Pane,
+) -> Element<'a, Message> {
+    let existing = kline_studies
+        .iter()
+        .enumerate()
+        .find(|(_, study)| study.is_same_type(&KlineStudy::ICHIMOKU));
+
+    let toggle = checkbox(existing.is_some())
+        .label("Ichimoku Cloud")
+        .on_toggle(move |value| match existing {
+            Some((index, _)) if !value => Message::PaneEvent(pane, Event::RemoveKlineStudy(index)),
+            _ => Message::PaneEvent(pane, Event::SetKlineStudy(KlineStudy::ICHIMOKU)),
+        });
+
+    let Some((
+        _,
+        &KlineStudy::Ichimoku {
+            tenkan,
+            kijun,
+            senkou_b,
+        },
+    )) = existing
+    else {
+        return toggle.into();
+    };
+
+    let set = move |tenkan, kijun, senkou_b| {
+        Message::PaneEvent(
+            pane,
+            Event::SetKlineStudy(KlineStudy::Ichimoku {
+                tenkan,
+                kijun,
+                senkou_b,
+            }),
+        )
+    };
+
+    let tenkan_slider = labeled_slider(
+        "Tenkan",
+        2.0..=50.0,
+        tenkan as f32,
+        move |value| set(value as usize, kijun, senkou_b),
+        |value| format!("{value:.0} bars"),
+        Some(1.0),
+    );
+
+    let kijun_slider = labeled_slider(
+        "Kijun",
+        2.0..=100.0,
+        kijun as f32,
+        move |value| set(tenkan, value as usize, senkou_b),
+        |value| format!("{value:.0} bars"),
+        Some(1.0),
+    );
+
+    let senkou_b_slider = labeled_slider(
+        "Senkou B",
+        2.0..=200.0,
+        senkou_b as f32,
+        move |value| set(tenkan, kijun, value as usize),
+        |value| format!("{value:.0} bars"),
+        Some(1.0),
+    );
+
+    column![toggle, tenkan_slider, kijun_slider, senkou_b_slider]
+        .spacing(4)
+        .into()
+}
+
+fn stochastic_cfg<'a>(
+    kline_studies: &'a [KlineStudy],
+    pane: pane_grid::Pane,
+) -> Element<'a, Message> {
+    let existing = kline_studies
+        .iter()
+        .enumerate()
+        .find(|(_, study)| study.is_same_type(&KlineStudy::STOCHASTIC));
+
+    let toggle = checkbox(existing.is_some())
+        .label("Stochastic panel")
+        .on_toggle(move |value| match existing {
+            Some((index, _)) if !value => Message::PaneEvent(pane, Event::RemoveKlineStudy(index)),
+            _ => Message::PaneEvent(pane, Event::SetKlineStudy(KlineStudy::STOCHASTIC)),
+        });
+
+    let Some((
+        _,
+        &KlineStudy::Stochastic {
+            k_period,
+            d_period,
+            smooth,
+        },
+    )) = existing
+    else {
+        return toggle.into();
+    };
+
+    let set = move |k_period, d_period, smooth| {
+        Message::PaneEvent(
+            pane,
+            Event::SetKlineStudy(KlineStudy::Stochastic {
+                k_period,
+                d_period,
+                smooth,
+            }),
+        )
+    };
+
+    let k_slider = labeled_slider(
+        "%K",
+        2.0..=50.0,
+        k_period as f32,
+        move |value| set(value as usize, d_period, smooth),
+        |value| format!("{value:.0} bars"),
+        Some(1.0),
+    );
+
+    let smooth_slider = labeled_slider(
+        "Smoothing",
+        1.0..=10.0,
+        smooth as f32,
+        move |value| set(k_period, d_period, value as usize),
+        |value| format!("{value:.0} bars"),
+        Some(1.0),
+    );
+
+    let d_slider = labeled_slider(
+        "%D",
+        1.0..=10.0,
+        d_period as f32,
+        move |value| set(k_period, value as usize, smooth),
+        |value| format!("{value:.0} bars"),
+        Some(1.0),
+    );
+
+    column![toggle, k_slider, smooth_slider, d_slider]
+        .spacing(4)
+        .into()
+}
+
+fn max_delta_bar_toggle<'a>(
+    kline_studies: &'a [KlineStudy],
+    pane: pane_grid::Pane,
+) -> Element<'a, Message> {
+    let existing = kline_studies
+        .iter()
+        .position(|study| study.is_same_type(&KlineStudy::MaxDeltaBar));
+
+    checkbox(existing.is_some())
+        .label("Max delta bar")
+        .on_toggle(move |value| match existing {
+            Some(index) if !value => Message::PaneEvent(pane, Event::RemoveKlineStudy(index)),
+            _ => Message::PaneEvent(pane, Event::SetKlineStudy(KlineStudy::MaxDeltaBar)),
+        })
+        .into()
+}
+
+fn supertrend_cfg<'a>(
+    kline_studies: &'a [KlineStudy],
+    pane: pane_grid::Pane,
+) -> Element<'a, Message> {
+    let existing = kline_studies
+        .iter()
+        .enumerate()
+        .find(|(_, study)| study.is_same_type(&KlineStudy::SUPERTREND));
+
+    let toggle = checkbox(existing.is_some())
+        .label("Supertrend")
+        .on_toggle(move |value| match existing {
+            Some((index, _)) if !value => Message::PaneEvent(pane, Event::RemoveKlineStudy(index)),
+            _ => Message::PaneEvent(pane, Event::SetKlineStudy(KlineStudy::SUPERTREND)),
+        });
+
+    let Some((_, &KlineStudy::Supertrend { period, multiplier })) = existing else {
+        return toggle.into();
+    };
+
+    let set = move |period, multiplier| {
+        Message::PaneEvent(
+            pane,
+            Event::SetKlineStudy(KlineStudy::Supertrend { period, multiplier }),
+        )
+    };
+
+    let period_slider = labeled_slider(
+        "ATR",
+        2.0..=50.0,
+        period as f32,
+        move |value| set(value as usize, multiplier),
+        |value| format!("{value:.0} bars"),
+        Some(1.0),
+    );
+
+    let multiplier_slider = labeled_slider(
+        "Multiplier",
+        0.5..=6.0,
+        multiplier,
+        move |value| set(period, value),
+        |value| format!("{value:.2} ATR"),
+        Some(0.25),
+    );
+
+    column![toggle, period_slider, multiplier_slider]
+        .spacing(4)
+        .into()
+}
+
+fn depth_imbalance_cfg<'a>(
+    kline_studies: &'a [KlineStudy],
+    pane: pane_grid::Pane,
+) -> Element<'a, Message> {
+    let existing = kline_studies
+        .iter()
+        .enumerate()
+        .find(|(_, study)| study.is_same_type(&KlineStudy::DEPTH_IMBALANCE));
+
+    let toggle = checkbox(existing.is_some())
+        .label("Depth Imbalance")
+        .on_toggle(move |value| match existing {
+            Some((index, _)) if !value => Message::PaneEvent(pane, Event::RemoveKlineStudy(index)),
+            _ => Message::PaneEvent(pane, Event::SetKlineStudy(KlineStudy::DEPTH_IMBALANCE)),
+        });
+
+    let Some((
+        _,
+        &KlineStudy::DepthImbalance {
+            window_size,
+            lookback_levels,
+        },
+    )) = existing
+    else {
+        return toggle.into();
+    };
+
+    let set = move |window_size, lookback_levels| {
+        Message::PaneEvent(
+            pane,
+            Event::SetKlineStudy(KlineStudy::DepthImbalance {
+                window_size,
+                lookback_levels,
+            }),
+        )
+    };
+
+    let window_slider = labeled_slider(
+        "Window",
+        1.0..=100.0,
+        window_size as f32,
+        move |value| set(value as usize, lookback_levels),
+        |value| format!("{value:.0} snapshots"),
+        Some(1.0),
+    );
+
+    let levels_slider = labeled_slider(
+        "Levels",
+        1.0..=50.0,
+        lookback_levels as f32,
+        move |value| set(window_size, value as usize),
+        |value| format!("{value:.0} per side"),
+        Some(1.0),
+    );
+
+    column![toggle, window_slider, levels_slider]
+        .spacing(4)
+        .into()
+}
+
+pub fn ladder_cfg_view<'a>(cfg: ladder::Config, pane: pane_grid::Pane) -> Element<'a, Message> {
+    let display_options = {
+        let spread = checkbox(cfg.show_spread)
+            .label("Show Spread")
+            .on_toggle(move |value| {
+                Message::VisualConfigChanged(
+                    pane,
+                    VisualConfig::Ladder(ladder::Config {
+                        show_spread: value,
+                        ..cfg
+                    }),
+                    false,
+                )
+            });
+
+        let chase_tracker = checkbox(cfg.show_chase_tracker)
+            .label("Show Chase Tracker")
+            .on_toggle(move |value| {
+                Message::VisualConfigChanged(
+                    pane,
+                    VisualConfig::Ladder(ladder::Config {
+                        show_chase_tracker: value,
+                        ..cfg
+                    }),
+                    false,
+                )
+            });
+
+        let cumulative = checkbox(cfg.show_cumulative)
+            .label("Show Cumulative Depth")
+            .on_toggle(move |value| {
+                Message::VisualConfigChanged(
+                    pane,
+                    VisualConfig::Ladder(ladder::Config {
+                        show_cumulative: value,
+                        ..cfg
+                    }),
+                    false,
+                )
+            });
+
+        let highlight_changes = checkbox(cfg.highlight_changes)
+            .label("Highlight Size Changes")
+            .on_toggle(move |value| {
+                Message::VisualConfigChanged(
+                    pane,
+                    VisualConfig::Ladder(ladder::Config {
+                        highlight_changes: value,
+                        ..cfg
+                    }),
+                    false,
+                )
+            });
+
+        column![
+            text("Display Options").size(14),
+            column![
+                spread,
+                cumulative,
+                highlight_changes,
+                row![
+                    chase_tracker,
+                    tooltip(
+                        button("i").style(style::button::info),
+                        Some("Highlights consecutive best-price moves and fades when momentum stalls.\nCalculated using raw ungrouped data."),
+                        TooltipPosition::Top,
+                    )
+                ]
+                .align_y(Alignment::Center)
+                .spacing(4)
+            ]
+            .spacing(4)
+        ]
+        .spacing(8)
+    };
+
+    let retention_slider = {
+        let retention_minutes = (cfg.trade_retention.as_secs_f32() / 60.0).max(1.0);
+
+        let slider_ui = slider(1.0..=60.0, retention_minutes, move |new_minutes| {
+            let mins = new_minutes.round().max(1.0) as u64;
+            Message::VisualConfigChanged(
+                pane,
+                VisualConfig::Ladder(ladder::Config {
+                    trade_retention: Duration::from_secs(mins * 60),
+                    ..cfg
+                }),
+                false,
+            )
+        })
+        .step(1.0);
+
+        classic_slider_row(
+            text("Keep trades for"),
+            slider_ui.into(),
+            Some(text(format!("≈ {} min", retention_minutes.round() as u64)).size(13)),
+        )
+    };
+
+    let history_column = column![text("History").size(14), retention_slider].spacing(8);
+
+    let imbalance_column = {
+        let imbalance_cfg = cfg.imbalance;
+
+        let enable_checkbox = checkbox(cfg.show_imbalance)
+            .label("Show Depth Imbalance")
+            .on_toggle(move |value| {
+                Message::VisualConfigChanged(
+                    pane,
+                    VisualConfig::Ladder(ladder::Config {
+                        show_imbalance: value,
+                        ..cfg
+                    }),
+                    false,
+                )
+            });
+
+        let mut col = column![text("Depth Imbalance").size(14), enable_checkbox].spacing(8);
+
+        if cfg.show_imbalance {
+            let levels_slider = slider(1.0..=100.0, imbalance_cfg.levels as f32, move |value| {
+                Message::VisualConfigChanged(
+                    pane,
+                    VisualConfig::Ladder(ladder::Config {
+                        imbalance: imbalance::Config {
+                            levels: value as usize,
+                            ..imbalance_cfg
+                        },
+                        ..cfg
+                    }),
+                    false,
+                )
+            })
+            .step(1.0);
+
+            let band_slider = slider(0.1..=5.0, imbalance_cfg.band_pct, move |value| {
+                Message::VisualConfigChanged(
+                    pane,
+                    VisualConfig::Ladder(ladder::Config {
+                        imbalance: imbalance::Config {
+                            band_pct: value,
+                            ..imbalance_cfg
+                        },
+                        ..cfg
+                    }),
+                    false,
+                )
+            })
+            .step(0.1);
+
+            col = col
+                .push(classic_slider_row(
+                    text("Levels"),
+                    levels_slider.into(),
+                    Some(text(imbalance_cfg.levels.to_string()).size(13)),
+                ))
+                .push(classic_slider_row(
+                    text("Band"),
+                    band_slider.into(),
+                    Some(text(format!("±{:.1}%", imbalance_cfg.band_pct)).size(13)),
+                ));
+        }
+
+        col
+    };
+
+    let content = split_column![
+        display_options,
+        imbalance_column,
+        history_column,
+        row![
+            space::horizontal(),
+            sync_all_button(pane, VisualConfig::Ladder(cfg))
+        ],
+        ; spacing = 12, align_x = Alignment::Start
+    ];
+
+    cfg_view_container(320, content)
+}
+
+pub fn correlation_cfg_view<'a>(
+    cfg: correlation::Config,
+    pane: pane_grid::Pane,
+) -> Element<'a, Message> {
+    let (min, max) = correlation::Config::WINDOW_BARS_RANGE.into_inner();
+
+    let window_slider = labeled_slider(
+        "Window",
+        min as f32..=max as f32,
+        cfg.window_bars as f32,
+        move |value| {
+            Message::VisualConfigChanged(
+                pane,
+                VisualConfig::Correlation(correlation::Config {
+                    window_bars: value as usize,
+                }),
+                false,
+            )
+        },
+        |value| format!("{value:.0} bars"),
+        Some(10.0),
+    );
+
+    let content = split_column![
+        column![text("Correlation").size(14), window_slider].spacing(8),
+        row![
+            space::horizontal(),
+            sync_all_button(pane, VisualConfig::Correlation(cfg))
+        ],
+        ; spacing = 12, align_x = Alignment::Start
+    ];
+
+    cfg_view_container(320, content)
+}
+
+pub fn statistics_cfg_view<'a>(
+    cfg: stats_panel::Config,
+    pane: pane_grid::Pane,
+) -> Element<'a, Message> {
+    let (min, max) = stats_panel::Config::WINDOW_SECS_RANGE.into_inner();
+
+    let window_slider = labeled_slider(
+        "Window",
+        min as f32..=max as f32,
+        cfg.window_secs as f32,
+        move |value| {
+            Message::VisualConfigChanged(
+                pane,
+                VisualConfig::Statistics(stats_panel::Config {
+                    window_secs: value as u64,
+                }),
+                false,
+            )
+        },
+        |value| format!("{value:.0}s"),
+        Some(10.0),
+    );
+
+    let content = split_column![
+        column![text("Rolling window").size(14), window_slider].spacing(8),
+        row![
+            space::horizontal(),
+            sync_all_button(pane, VisualConfig::Statistics(cfg))
+        ],
+        ; spacing = 12, align_x = Alignment::Start
+    ];
+
+    cfg_view_container(320, content)
+}
+
+pub fn spread_cfg_view<'a>(cfg: spread::Config, pane: pane_grid::Pane) -> Element<'a, Message> {
+    let (min, max) = spread::Config::AVG_PERIODS_RANGE.into_inner();
+
+    let avg_slider = labeled_slider(
+        "Periods",
+        min as f32..=max as f32,
+        cfg.avg_periods as f32,
+        move |value| {
+            Message::VisualConfigChanged(
+                pane,
+                VisualConfig::Spread(spread::Config {
+                    avg_periods: value as usize,
+                    ..cfg
+                }),
+                false,
+            )
+        },
+        |value| format!("{value:.0}"),
+        Some(5.0),
+    );
+
+    let unit_picklist = pick_list(spread::SpreadUnit::ALL, Some(cfg.unit), move |unit| {
+        Message::VisualConfigChanged(
+            pane,
+            VisualConfig::Spread(spread::Config { unit, ..cfg }),
+            false,
+        )
+    });
+
+    let content = split_column![
+        column![text("Rolling average").size(14), avg_slider].spacing(8),
+        column![text("Spread unit").size(14), unit_picklist].spacing(8),
+        row![
+            space::horizontal(),
+            sync_all_button(pane, VisualConfig::Spread(cfg))
+        ],
+        ; spacing = 12, align_x = Alignment::Start
+    ];
+
+    cfg_view_container(320, content)
+}
+
+pub fn range_cfg_view<'a>(cfg: range::Config, pane: pane_grid::Pane) -> Element<'a, Message> {
+    let range_slider = {
+        let selected = RANGE_TICKS_OPTIONS
+            .iter()
+            .position(|&ticks| ticks >= cfg.range_ticks)
+            .unwrap_or(RANGE_TICKS_OPTIONS.len() - 1);
+
+        let slider_ui = slider(
+            0.0..=(RANGE_TICKS_OPTIONS.len() - 1) as f32,
+            selected as f32,
+            move |value| {
+                Message::VisualConfigChanged(
+                    pane,
+                    VisualConfig::Range(range::Config {
+                        range_ticks: RANGE_TICKS_OPTIONS[value as usize],
+                    }),
+                    false,
+                )
+            },
+        )
+        .step(1.0);
+
+        classic_slider_row(
+            text("Bar range"),
+            slider_ui.into(),
+            Some(text(format!("{} ticks", cfg.range_ticks)).size(13)),
+        )
+    };
+
+    let content = split_column![
+        column![text("Range Bars").size(14), range_slider].spacing(8),
+        row![
+            space::horizontal(),
+            sync_all_button(pane, VisualConfig::Range(cfg))
+        ],
+        ; spacing = 12, align_x = Alignment::Start
+    ];
+
+    cfg_view_container(320, content)
+}
+
+fn sync_all_button<'a>(pane: pane_grid::Pane, config: VisualConfig) -> Element<'a, Message> {
+    tooltip(
+        button("Sync all").on_press(Message::VisualConfigChanged(pane, config, true)),
+        Some("Apply configuration to similar panes"),
+        TooltipPosition::Top,
+    )
+}
+
+pub mod study {
+    use crate::{
+        split_column,
+        style::{self, Icon, icon_text},
+    };
+    use data::chart::heatmap::{CLEANUP_THRESHOLD, HeatmapStudy, ProfileKind};
+    use data::chart::kline::{FootprintStudy, NpocLookback};
+    use iced::{
+        Element, padding,
+        widget::{button, checkbox, column, container, row, slider, space, text},
+    };
+
+    #[derive(Debug, Clone, Copy)]
+    pub enum StudyMessage {
+        Footprint(Message<FootprintStudy>),
+        Heatmap(Message<HeatmapStudy>),
+    }
+
+    pub trait Study: Sized + Copy + 'static {
+        fn is_same_type(&self, other: &Self) -> bool;
+        fn all() -> Vec<Self>;
+        fn view_config<'a>(
+            &self,
+            basis: data::chart::Basis,
+            on_change: impl Fn(Self) -> Message<Self> + Copy + 'a,
+        ) -> Element<'a, Message<Self>>;
+    }
+
+    impl Study for FootprintStudy {
+        fn is_same_type(&self, other: &Self) -> bool {
+            std::mem::discriminant(self) == std::mem::discriminant(other)
+        }
+
+        fn all() -> Vec<Self> {
+            FootprintStudy::ALL.to_vec()
+        }
+
+        fn view_config<'a>(
+            &self,
+            _basis: data::chart::Basis,
+            on_change: impl Fn(Self) -> Message<Self> + Copy + 'a,
+        ) -> Element<'a, Message<Self>> {
+            match *self {
+                FootprintStudy::NPoC { lookback } => {
+                    let visible_checkbox = checkbox(lookback == NpocLookback::VisibleRange)
+                        .label("Visible range only")
+                        .on_toggle(move |is_checked| {
+                            on_change(FootprintStudy::NPoC {
+                                lookback: if is_checked {
+                                    NpocLookback::VisibleRange
+                                } else {
+                                    NpocLookback::Fixed(80)
+                                },
+                            })
+                        });
+
+                    let NpocLookback::Fixed(count) = lookback else {
+                        return column![visible_checkbox].padding(8).into();
+                    };
+
+                    let slider_ui = slider(10.0..=400.0, count as f32, move |new_value| {
+                        on_change(FootprintStudy::NPoC {
+                            lookback: NpocLookback::Fixed(new_value as usize),
+                        })
+                    })
+                    .step(10.0);
+
+                    column![
+                        visible_checkbox,
+                        text(format!("Lookback: {count} datapoints")),
+                        slider_ui
+                    ]
+                    .padding(8)
+                    .spacing(4)
+                    .into()
+                }
+                FootprintStudy::Imbalance {
+                    threshold,
+                    color_scale,
+                    ignore_zeros,
                 } => {
                     let qty_threshold = {
                         let info_text = text(format!("Ask:Bid threshold: {threshold}%"));
@@ -722,7 +1911,7 @@ pub mod study {
 
                         if color_scale_enabled {
                             let scaling_slider = column![
-                                text(format!("Opaque color at: {color_scale_value}x")),
+                                text(format!("Full saturation at: {color_scale_value}%")),
                                 slider(50.0..=2000.0, color_scale_value as f32, move |new_value| {
                                     on_change(FootprintStudy::Imbalance {
                                         threshold,
@@ -760,6 +1949,68 @@ pub mod study {
                         .padding(4)
                         .into()
                 }
+                FootprintStudy::Divergence { lookback } => {
+                    let slider_ui = slider(2.0..=20.0, lookback as f32, move |new_value| {
+                        on_change(FootprintStudy::Divergence {
+                            lookback: new_value as usize,
+                        })
+                    })
+                    .step(1.0);
+
+                    column![text(format!("Swing lookback: {lookback} bars")), slider_ui]
+                        .padding(8)
+                        .spacing(4)
+                        .into()
+                }
+                FootprintStudy::StackedImbalance {
+                    threshold,
+                    min_stack,
+                } => {
+                    let qty_threshold = {
+                        let threshold_slider =
+                            slider(100.0..=800.0, threshold as f32, move |new_value| {
+                                on_change(FootprintStudy::StackedImbalance {
+                                    threshold: new_value as usize,
+                                    min_stack,
+                                })
+                            })
+                            .step(25.0);
+
+                        column![
+                            text(format!("Ask:Bid threshold: {threshold}%")),
+                            threshold_slider
+                        ]
+                        .padding(8)
+                        .spacing(4)
+                    };
+
+                    let stack_size = {
+                        let stack_slider = slider(2.0..=8.0, min_stack as f32, move |new_value| {
+                            on_change(FootprintStudy::StackedImbalance {
+                                threshold,
+                                min_stack: new_value as usize,
+                            })
+                        })
+                        .step(1.0);
+
+                        column![text(format!("Min stack: {min_stack} levels")), stack_slider]
+                            .padding(8)
+                            .spacing(4)
+                    };
+
+                    split_column![qty_threshold, stack_size].padding(4).into()
+                }
+                FootprintStudy::DeltaBar => {
+                    text("Buy minus sell volume of each bar, scaled to the visible bars")
+                        .size(12)
+                        .into()
+                }
+                FootprintStudy::LiquidationOverlay => text(
+                    "Liquidated size at each price, scaled to the visible cells. \
+                     Only Binance perpetuals stream liquidations",
+                )
+                .size(12)
+                .into(),
             }
         }
     }
@@ -778,31 +2029,30 @@ pub mod study {
             basis: data::chart::Basis,
             on_change: impl Fn(Self) -> Message<Self> + Copy + 'a,
         ) -> Element<'a, Message<Self>> {
-            let interval_ms = match basis {
-                data::chart::Basis::Time(interval) => interval.to_milliseconds(),
-                data::chart::Basis::Tick(_) => {
-                    return iced::widget::center(text(
-                        "Heatmap studies are not supported for tick-based charts",
-                    ))
-                    .into();
-                }
-            };
-
             match self {
                 HeatmapStudy::VolumeProfile(kind) => match kind {
                     ProfileKind::FixedWindow(datapoint_count) => {
-                        let duration_secs = (*datapoint_count as u64 * interval_ms) / 1000;
                         let min_range = CLEANUP_THRESHOLD / 20;
 
-                        let duration_text = if duration_secs < 60 {
-                            format!("{} seconds", duration_secs)
-                        } else {
-                            let minutes = duration_secs / 60;
-                            let seconds = duration_secs % 60;
-                            if seconds == 0 {
-                                format!("{} minutes", minutes)
-                            } else {
-                                format!("{}m {}s", minutes, seconds)
+                        let duration_text = match basis {
+                            data::chart::Basis::Time(interval) => {
+                                let duration_secs =
+                                    (*datapoint_count as u64 * interval.to_milliseconds()) / 1000;
+
+                                if duration_secs < 60 {
+                                    format!("{} seconds", duration_secs)
+                                } else {
+                                    let minutes = duration_secs / 60;
+                                    let seconds = duration_secs % 60;
+                                    if seconds == 0 {
+                                        format!("{} minutes", minutes)
+                                    } else {
+                                        format!("{}m {}s", minutes, seconds)
+                                    }
+                                }
+                            }
+                            data::chart::Basis::Tick(interval) => {
+                                format!("{} trades", *datapoint_count * usize::from(interval.0))
                             }
                         };
 
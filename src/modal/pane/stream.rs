@@ -127,6 +127,7 @@ pub enum SelectedTab {
 pub enum Action {
     BasisSelected(Basis),
     TicksizeSelected(TickMultiplier),
+    AutoTicksizeSelected,
     TabSelected(SelectedTab),
 }
 
@@ -136,6 +137,7 @@ pub enum Message {
     TabSelected(SelectedTab),
     TicksizeInputChanged(String),
     TicksizeSelected(TickMultiplier),
+    AutoTicksizeSelected,
     TickCountInputChanged(String),
 }
 
@@ -146,6 +148,8 @@ pub struct Modifier {
     kind: ModifierKind,
     base_ticksize: Option<f32>,
     exchange: Option<Exchange>,
+    /// Footprint tick size is picked from the price instead of a fixed multiplier
+    auto_ticksize: bool,
 }
 
 impl Modifier {
@@ -158,6 +162,7 @@ impl Modifier {
             view_mode: ViewMode::BasisSelection,
             base_ticksize: None,
             exchange: None,
+            auto_ticksize: false,
         }
     }
 
@@ -190,6 +195,23 @@ impl Modifier {
         self
     }
 
+    pub fn with_auto_ticksize(mut self, enabled: bool) -> Self {
+        self.auto_ticksize = enabled;
+
+        if enabled && let ViewMode::TicksizeSelection { .. } = self.view_mode {
+            self.view_mode = ViewMode::TicksizeSelection {
+                raw_input_buf: NumericInput::default(),
+                parsed_input: None,
+                is_input_valid: true,
+            };
+        }
+        self
+    }
+
+    pub fn set_auto_ticksize(&mut self, enabled: bool) {
+        self.auto_ticksize = enabled;
+    }
+
     pub fn update_kind_with_basis(&mut self, basis: Basis) {
         match self.kind {
             ModifierKind::Candlestick(_) => self.kind = ModifierKind::Candlestick(basis),
@@ -264,6 +286,19 @@ impl Modifier {
                 }
                 Some(Action::TicksizeSelected(new_ticksize))
             }
+            Message::AutoTicksizeSelected => {
+                if let ViewMode::TicksizeSelection {
+                    ref mut raw_input_buf,
+                    ref mut parsed_input,
+                    ref mut is_input_valid,
+                } = self.view_mode
+                {
+                    *raw_input_buf = NumericInput::default();
+                    *parsed_input = None;
+                    *is_input_valid = true;
+                }
+                Some(Action::AutoTicksizeSelected)
+            }
             Message::TicksizeInputChanged(value_str) => {
                 if let ViewMode::TicksizeSelection {
                     ref mut raw_input_buf,
@@ -355,11 +390,15 @@ impl Modifier {
                 let mut basis_selection_column =
                     column![].padding(4).spacing(8).align_x(Horizontal::Center);
 
+                let is_kline_kind = matches!(
+                    kind,
+                    ModifierKind::Candlestick(_) | ModifierKind::Footprint(_, _)
+                );
                 let allows_tick_basis = match kind {
-                    ModifierKind::Candlestick(_) | ModifierKind::Footprint(_, _) => true,
-                    ModifierKind::Heatmap(_, _)
-                    | ModifierKind::Orderbook(_, _)
-                    | ModifierKind::Comparison(_) => false,
+                    ModifierKind::Candlestick(_)
+                    | ModifierKind::Footprint(_, _)
+                    | ModifierKind::Heatmap(_, _) => true,
+                    ModifierKind::Orderbook(_, _) | ModifierKind::Comparison(_) => false,
                 };
 
                 if selected_basis.is_some() {
@@ -460,7 +499,17 @@ impl Modifier {
                             _ => None,
                         };
 
-                        if allows_tick_basis {
+                        if is_kline_kind {
+                            let second_timeframe_grid = modifiers_grid(
+                                &Timeframe::SECONDS,
+                                selected_tf,
+                                |tf| Message::BasisSelected(tf.into()),
+                                &create_button,
+                                4,
+                            );
+                            basis_selection_column =
+                                basis_selection_column.push(second_timeframe_grid);
+
                             let kline_timeframe_grid = modifiers_grid(
                                 &Timeframe::KLINE,
                                 selected_tf,
@@ -590,12 +639,22 @@ impl Modifier {
 
                     let tick_multiplier_grid = modifiers_grid(
                         &allowed_tm,
-                        Some(ticksize),
+                        (!self.auto_ticksize).then_some(ticksize),
                         Message::TicksizeSelected,
                         &create_button,
                         3,
                     );
 
+                    if matches!(kind, ModifierKind::Footprint(_, _)) {
+                        let auto_button = create_button(
+                            text(format!("Auto ({ticksize})")),
+                            (!self.auto_ticksize).then_some(Message::AutoTicksizeSelected),
+                            self.auto_ticksize,
+                        );
+
+                        ticksizes_column = ticksizes_column.push(auto_button);
+                    }
+
                     if allows_custom_tsizes {
                         let custom_input = {
                             let tick_multiplier_to_submit = parsed_input.filter(|tm| {
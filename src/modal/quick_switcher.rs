@@ -0,0 +1,138 @@
+use crate::screen::dashboard::tickers_table::TickersTable;
+use crate::style::{self, icon_text};
+
+use data::tickers_table::fuzzy_score;
+use exchange::TickerInfo;
+use iced::{
+    Alignment, Element, Length,
+    widget::{button, column, container, row, space, text, text_input},
+};
+
+/// Results listed below the search box
+const MAX_RESULTS: usize = 10;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    QueryChanged(String),
+    /// Moves the highlighted result up or down by the given rows
+    MoveSelection(isize),
+    Submit,
+    Pick(TickerInfo),
+}
+
+pub enum Action {
+    Open(TickerInfo),
+}
+
+/// Search box over every ticker the sidebar knows of, opened with Ctrl+P
+#[derive(Default)]
+pub struct QuickSwitcher {
+    query: String,
+    selected: usize,
+}
+
+impl QuickSwitcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn input_id() -> iced::widget::Id {
+        iced::widget::Id::new("quick-switcher-input")
+    }
+
+    pub fn update(&mut self, message: Message, tickers_table: &TickersTable) -> Option<Action> {
+        match message {
+            Message::QueryChanged(query) => {
+                self.query = query.to_uppercase();
+                self.selected = 0;
+            }
+            Message::MoveSelection(delta) => {
+                let count = self.results(tickers_table).len();
+                if count > 0 {
+                    self.selected = self.selected.saturating_add_signed(delta).min(count - 1);
+                }
+            }
+            Message::Submit => {
+                return self
+                    .results(tickers_table)
+                    .get(self.selected)
+                    .map(|ticker_info| Action::Open(*ticker_info));
+            }
+            Message::Pick(ticker_info) => return Some(Action::Open(ticker_info)),
+        }
+
+        None
+    }
+
+    /// Best matches of the query by symbol, ties broken alphabetically
+    fn results(&self, tickers_table: &TickersTable) -> Vec<TickerInfo> {
+        if self.query.is_empty() {
+            return vec![];
+        }
+
+        let mut scored: Vec<(usize, String, TickerInfo)> = tickers_table
+            .tickers_info
+            .values()
+            .flatten()
+            .filter_map(|ticker_info| {
+                let (symbol, _) = ticker_info.ticker.display_symbol_and_type();
+                fuzzy_score(&self.query, &symbol).map(|score| (score, symbol, *ticker_info))
+            })
+            .collect();
+
+        scored.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+
+        scored
+            .into_iter()
+            .take(MAX_RESULTS)
+            .map(|(_, _, ticker_info)| ticker_info)
+            .collect()
+    }
+
+    pub fn view<'a>(&'a self, tickers_table: &'a TickersTable) -> Element<'a, Message> {
+        let input = text_input("Jump to ticker...", &self.query)
+            .id(Self::input_id())
+            .on_input(Message::QueryChanged)
+            .on_submit(Message::Submit)
+            .padding(8);
+
+        let results = self.results(tickers_table);
+
+        let list: Element<'_, Message> = if results.is_empty() && !self.query.is_empty() {
+            text("No matching tickers").size(12).into()
+        } else {
+            column(results.into_iter().enumerate().map(|(i, ticker_info)| {
+                let ticker = ticker_info.ticker;
+                let (symbol, _) = ticker.display_symbol_and_type();
+                let price = tickers_table.price_display(&ticker).unwrap_or("-");
+                let is_selected = i == self.selected;
+
+                let content = row![
+                    icon_text(style::exchange_icon(ticker.exchange), 12),
+                    text(symbol).size(13),
+                    text(ticker.exchange.to_string()).size(11),
+                    space::horizontal(),
+                    text(price.to_string()).size(12).font(style::AZERET_MONO),
+                ]
+                .spacing(8)
+                .align_y(Alignment::Center);
+
+                button(content)
+                    .on_press(Message::Pick(ticker_info))
+                    .width(Length::Fill)
+                    .style(move |theme, status| {
+                        style::button::menu_body(theme, status, is_selected)
+                    })
+                    .into()
+            }))
+            .spacing(2)
+            .into()
+        };
+
+        container(column![input, list].spacing(8))
+            .width(420)
+            .padding(12)
+            .style(style::dashboard_modal)
+            .into()
+    }
+}
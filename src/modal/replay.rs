@@ -0,0 +1,183 @@
+//! Controls of the kline replay, which plays an exported file into the kline stream of the
+//! focused pane through the same path as live market events
+
+use crate::style;
+use data::replay::{Replay, ReplaySpeed, Seek};
+use exchange::Kline;
+use exchange::adapter::StreamKind;
+
+use iced::widget::{button, column, container, pick_list, row, slider, space, text};
+use iced::{Alignment, Element};
+use std::time::Instant;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Load,
+    SetSpeed(ReplaySpeed),
+    /// Share of the file's time span to jump to
+    Seek(f32),
+    Stop,
+}
+
+pub enum Action {
+    /// Pick a file to play into the focused pane's kline stream
+    PickFile,
+    /// Klines to hand over as if `stream` received them
+    Emit(StreamKind, Vec<Kline>),
+    /// Empties the charts of `stream` and fills them with the klines released so far
+    Restart(StreamKind, Vec<Kline>),
+    /// Playback ended, the charts of `stream` drop the replayed klines and go back to live
+    Stop(StreamKind),
+}
+
+#[derive(Default)]
+pub struct ReplayControls {
+    playback: Option<Playback>,
+}
+
+struct Playback {
+    stream: StreamKind,
+    replay: Replay,
+    last_advance: Instant,
+}
+
+impl ReplayControls {
+    /// The charts of `stream` start over empty, the replay fills them from its first kline
+    pub fn start(&mut self, stream: StreamKind, replay: Replay) -> Action {
+        self.playback = Some(Playback {
+            stream,
+            replay,
+            last_advance: Instant::now(),
+        });
+
+        Action::Restart(stream, vec![])
+    }
+
+    /// Whether a file is loaded, live market streams stay off meanwhile
+    pub fn is_active(&self) -> bool {
+        self.playback.is_some()
+    }
+
+    pub fn update(&mut self, message: Message) -> Option<Action> {
+        match message {
+            Message::Load => return Some(Action::PickFile),
+            Message::SetSpeed(speed) => {
+                if let Some(playback) = &mut self.playback {
+                    playback.replay.speed = speed;
+                    playback.last_advance = Instant::now();
+                }
+            }
+            Message::Seek(ratio) => {
+                if let Some(playback) = &mut self.playback {
+                    let (start, end) = playback.replay.span();
+                    let time = start + ((end - start) as f64 * f64::from(ratio)) as u64;
+
+                    return Some(match playback.replay.seek(time) {
+                        Seek::Ahead(klines) => Action::Emit(playback.stream, klines.to_vec()),
+                        Seek::Rewound(klines) => Action::Restart(playback.stream, klines.to_vec()),
+                    });
+                }
+            }
+            Message::Stop => {
+                return self
+                    .playback
+                    .take()
+                    .map(|playback| Action::Stop(playback.stream));
+            }
+        }
+        None
+    }
+
+    /// Advances the playback by the time since the last tick
+    pub fn tick(&mut self, now: Instant) -> Option<Action> {
+        let playback = self.playback.as_mut()?;
+        let elapsed = now.saturating_duration_since(playback.last_advance);
+        playback.last_advance = now;
+
+        let klines = playback.replay.advance(elapsed);
+        (!klines.is_empty()).then(|| Action::Emit(playback.stream, klines.to_vec()))
+    }
+
+    /// `target` is the kline stream a new file would be played into
+    pub fn view(
+        &self,
+        target: Option<StreamKind>,
+        timezone: data::UserTimezone,
+    ) -> Element<'_, Message> {
+        let content = match &self.playback {
+            None => {
+                let hint = match target {
+                    Some(stream) => format!(
+                        "Plays an exported CSV or JSON kline file into {} {}",
+                        stream.ticker_info().ticker,
+                        kline_timeframe(&stream),
+                    ),
+                    None => "Focus a pane with a time based kline chart to replay into".to_string(),
+                };
+
+                column![
+                    text(hint).size(12),
+                    button(text("Load file...")).on_press_maybe(target.map(|_| Message::Load)),
+                ]
+                .spacing(12)
+            }
+            Some(playback) => {
+                let replay = &playback.replay;
+                let (start, end) = replay.span();
+                let progress = if end > start {
+                    (replay.clock() - start) as f32 / (end - start) as f32
+                } else {
+                    1.0
+                };
+
+                let clock = data::format_timestamp(
+                    replay.clock(),
+                    timezone,
+                    data::i18n::Language::from_code(data::i18n::current_language()),
+                );
+                let status = if replay.is_finished() {
+                    "Finished"
+                } else {
+                    "Playing"
+                };
+
+                column![
+                    text(format!(
+                        "{} {}",
+                        playback.stream.ticker_info().ticker,
+                        kline_timeframe(&playback.stream),
+                    ))
+                    .size(12),
+                    row![
+                        text(clock).size(12),
+                        space::horizontal(),
+                        text(status).size(11),
+                    ]
+                    .align_y(Alignment::Center),
+                    slider(0.0..=1.0, progress, Message::Seek).step(0.001),
+                    row![
+                        pick_list(ReplaySpeed::ALL, Some(replay.speed), Message::SetSpeed),
+                        space::horizontal(),
+                        button(text("Stop")).on_press(Message::Stop),
+                    ]
+                    .align_y(Alignment::Center),
+                    text("Live market streams are paused while replaying").size(11),
+                ]
+                .spacing(8)
+            }
+        };
+
+        container(column![text("Replay").size(14), content].spacing(12))
+            .width(280)
+            .padding(24)
+            .style(style::dashboard_modal)
+            .into()
+    }
+}
+
+fn kline_timeframe(stream: &StreamKind) -> String {
+    match stream {
+        StreamKind::Kline { timeframe, .. } => timeframe.to_string(),
+        StreamKind::DepthAndTrades { .. } => String::new(),
+    }
+}
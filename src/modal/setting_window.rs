@@ -1,15 +1,11 @@
 use iced::{
     Alignment, Element,
-    widget::{button, column, container, text, pick_list, scrollable, row},
-};
-use crate::widget::{
-    confirm_dialog_container,
-    toast::{self, Toast},
-    tooltip,
+    widget::{button, column, container, text, pick_list, scrollable, row, slider},
 };
 use crate::split_column;
 use crate::modal::ThemeEditor;
 use data::config::theme::default_theme;
+use data::config::{KeyboardNav, ToastConfig, ToastPosition};
 use crate::i18n::{self, t};
 
 /// 设置窗口消息
@@ -22,8 +18,12 @@ pub enum Message {
     ScaleFactorChanged(data::ScaleFactor),
     // ToggleTradeFetch(bool),
     // OpenDataFolder,
+    #[allow(dead_code)]
     CloseRequested,
     LanguageChanged(i18n::Language),
+    ToastConfigChanged(ToastConfig),
+    PreviewToast,
+    KeyboardNavChanged(KeyboardNav),
 }
 
 /// 设置窗口返回给父组件的动作
@@ -38,6 +38,9 @@ pub enum Action {
     // DataFolderRequested,
     Close,
     LanguageChanged(i18n::Language),
+    ToastConfigChanged(ToastConfig),
+    PreviewToast,
+    KeyboardNavChanged(KeyboardNav),
 }
 
 /// 设置窗口状态
@@ -73,6 +76,15 @@ impl SettingWindow {
             Message::LanguageChanged(language) => {
                 Some(Action::LanguageChanged(language))
             }
+            Message::ToastConfigChanged(cfg) => {
+                Some(Action::ToastConfigChanged(cfg))
+            }
+            Message::PreviewToast => {
+                Some(Action::PreviewToast)
+            }
+            Message::KeyboardNavChanged(steps) => {
+                Some(Action::KeyboardNavChanged(steps))
+            }
         }
     }
 
@@ -82,8 +94,10 @@ impl SettingWindow {
         theme: &data::Theme,
         theme_editor: &ThemeEditor,
         timezone: data::UserTimezone,
-        volume_size_unit: exchange::SizeUnit,
+        _volume_size_unit: exchange::SizeUnit,
         ui_scale_factor: data::ScaleFactor,
+        toast_cfg: ToastConfig,
+        keyboard_nav: KeyboardNav,
     ) -> Element<'_, Message> {
         let settings_modal = {
             let theme_picklist = {
@@ -104,17 +118,14 @@ impl SettingWindow {
             let toggle_theme_editor = button(text("Theme editor")).on_press( Message::OpenThemeEditor);
 
             let timezone_picklist = pick_list(
-                [data::UserTimezone::Utc, data::UserTimezone::Local],
+                data::UserTimezone::ALL,
                 Some(timezone),
                 Message::SetTimezone,
             );
 
             let current_lang = i18n::Language::from_code(i18n::current_language());
             let language_picker = pick_list(
-                [
-                    i18n::Language::English,
-                    i18n::Language::SimplifiedChinese,
-                ],
+                i18n::Language::ALL,
                 Some(current_lang),
                 Message::LanguageChanged,
             );
@@ -183,6 +194,133 @@ impl SettingWindow {
                 .style(crate::style::modal_container)
             };
 
+            let toast_settings = {
+                let position_picker = pick_list(
+                    ToastPosition::ALL,
+                    Some(toast_cfg.position),
+                    move |position| {
+                        Message::ToastConfigChanged(ToastConfig {
+                            position,
+                            ..toast_cfg
+                        })
+                    },
+                );
+
+                let duration_secs = (toast_cfg.duration_ms / 1000) as u8;
+                let duration_slider = slider(
+                    (data::config::MIN_TOAST_DURATION_MS / 1000) as u8
+                        ..=(data::config::MAX_TOAST_DURATION_MS / 1000) as u8,
+                    duration_secs,
+                    move |secs| {
+                        Message::ToastConfigChanged(ToastConfig {
+                            duration_ms: u64::from(secs) * 1000,
+                            ..toast_cfg
+                        })
+                    },
+                );
+
+                let max_count_slider = slider(
+                    1..=data::config::MAX_TOAST_COUNT as u8,
+                    toast_cfg.max_count as u8,
+                    move |count| {
+                        Message::ToastConfigChanged(ToastConfig {
+                            max_count: usize::from(count),
+                            ..toast_cfg
+                        })
+                    },
+                );
+
+                // 缩小的窗口示意，通知小方块按所选位置对齐
+                let preview = {
+                    let mock_toast = container(text("Info").size(9))
+                        .padding(2)
+                        .width(40)
+                        .style(crate::style::chart_modal);
+
+                    let (align_x, align_y) = match toast_cfg.position {
+                        ToastPosition::TopLeft => (Alignment::Start, Alignment::Start),
+                        ToastPosition::TopCenter => (Alignment::Center, Alignment::Start),
+                        ToastPosition::TopRight => (Alignment::End, Alignment::Start),
+                        ToastPosition::BottomLeft => (Alignment::Start, Alignment::End),
+                        ToastPosition::BottomRight => (Alignment::End, Alignment::End),
+                    };
+
+                    container(mock_toast)
+                        .width(160)
+                        .height(90)
+                        .padding(6)
+                        .align_x(align_x)
+                        .align_y(align_y)
+                        .style(crate::style::modal_container)
+                };
+
+                column![
+                    row![text("Position"), position_picker]
+                        .spacing(8)
+                        .align_y(Alignment::Center),
+                    column![text(format!("Duration: {duration_secs}s")), duration_slider]
+                        .spacing(4),
+                    column![
+                        text(format!("Max shown: {}", toast_cfg.max_count)),
+                        max_count_slider
+                    ]
+                    .spacing(4),
+                    row![
+                        preview,
+                        button(text("Show test notification")).on_press(Message::PreviewToast),
+                    ]
+                    .spacing(12)
+                    .align_y(Alignment::Center),
+                ]
+                .spacing(8)
+                .max_width(360)
+            };
+
+            let keyboard_settings = {
+                let pan_slider = slider(
+                    KeyboardNav::STEP_RANGE,
+                    keyboard_nav.pan_step,
+                    move |pan_step| {
+                        Message::KeyboardNavChanged(KeyboardNav {
+                            pan_step,
+                            ..keyboard_nav
+                        })
+                    },
+                )
+                .step(0.05);
+
+                let zoom_slider = slider(
+                    KeyboardNav::STEP_RANGE,
+                    keyboard_nav.zoom_step,
+                    move |zoom_step| {
+                        Message::KeyboardNavChanged(KeyboardNav {
+                            zoom_step,
+                            ..keyboard_nav
+                        })
+                    },
+                )
+                .step(0.05);
+
+                column![
+                    text("Arrow keys pan and +/- zoom the focused chart").size(11),
+                    column![
+                        text(format!(
+                            "Pan step: {:.0}% of view",
+                            keyboard_nav.pan_step * 100.0
+                        )),
+                        pan_slider
+                    ]
+                    .spacing(4),
+                    column![
+                        text(format!("Zoom step: {:.0}%", keyboard_nav.zoom_step * 100.0)),
+                        zoom_slider
+                    ]
+                    .spacing(4),
+                ]
+                .spacing(8)
+                .max_width(360)
+            };
+
             // let trade_fetch_checkbox = {
             //     let is_active = exchange::fetcher::is_trade_fetch_enabled();
 
@@ -227,6 +365,8 @@ impl SettingWindow {
                 
                 column![text(t!("settings.theme")).size(14), theme_picklist,].spacing(12),
                 column![text(t!("settings.interface_scale")).size(14), scale_factor,].spacing(12),
+                column![text(t!("settings.notifications")).size(14), toast_settings,].spacing(12),
+                column![text(t!("settings.keyboard")).size(14), keyboard_settings,].spacing(12),
                 column![
                     text("Experimental").size(14),
                     // column![trade_fetch_checkbox, toggle_theme_editor,].spacing(8),
@@ -1,11 +1,11 @@
 use iced::{
-    Alignment, Element,
-    widget::{button, column, container, pick_list, row, space, text_input::default},
+    Alignment, Element, Task,
+    widget::{button, column, container, pick_list, row, space, text, text_input::default},
 };
 
 use crate::{
     style::{self, Icon, icon_text},
-    widget::color_picker::color_picker,
+    widget::{color_picker::color_picker, toast::Toast},
 };
 use palette::Hsva;
 
@@ -42,12 +42,17 @@ pub enum Message {
     CloseRequested,
     Color(Hsva),
     HexInput(String),
+    ExportTheme,
+    ImportTheme,
+    ExportPathPicked(std::path::PathBuf),
+    ImportPathPicked(std::path::PathBuf),
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub enum Action {
     UpdateTheme(iced_core::Theme),
     Exit,
+    Notify(Toast),
 }
 
 pub struct ThemeEditor {
@@ -79,8 +84,12 @@ impl ThemeEditor {
         }
     }
 
-    pub fn update(&mut self, message: Message, theme: &iced_core::Theme) -> Option<Action> {
-        match message {
+    pub fn update(
+        &mut self,
+        message: Message,
+        theme: &iced_core::Theme,
+    ) -> (Task<Message>, Option<Action>) {
+        let action = match message {
             Message::Color(hsva) => {
                 self.hex_input = None;
                 self.editing = Some(hsva);
@@ -134,8 +143,59 @@ impl ThemeEditor {
                 self.hex_input = Some(input);
                 action
             }
+            Message::ExportTheme => {
+                let pick = rfd::AsyncFileDialog::new()
+                    .add_filter("JSON", &["json"])
+                    .set_file_name("theme.json")
+                    .save_file();
+
+                return (
+                    Task::future(pick).and_then(|handle| {
+                        Task::done(Message::ExportPathPicked(handle.path().to_path_buf()))
+                    }),
+                    None,
+                );
+            }
+            Message::ImportTheme => {
+                let pick = rfd::AsyncFileDialog::new()
+                    .add_filter("JSON", &["json"])
+                    .pick_file();
+
+                return (
+                    Task::future(pick).and_then(|handle| {
+                        Task::done(Message::ImportPathPicked(handle.path().to_path_buf()))
+                    }),
+                    None,
+                );
+            }
+            Message::ExportPathPicked(path) => {
+                match data::config::theme::export_theme(theme, &path) {
+                    Ok(()) => Some(Action::Notify(Toast::info(format!(
+                        "Theme exported to {}",
+                        path.display()
+                    )))),
+                    Err(err) => Some(Action::Notify(Toast::error(format!(
+                        "Failed to export theme: {err}"
+                    )))),
+                }
+            }
+            Message::ImportPathPicked(path) => match data::config::theme::import_theme(&path) {
+                Ok(new_theme) => {
+                    self.hex_input = None;
+                    self.editing = None;
+                    self.custom_theme = Some(new_theme.clone());
+
+                    Some(Action::UpdateTheme(new_theme))
+                }
+                Err(err) => Some(Action::Notify(Toast::error(format!(
+                    "Failed to import theme from {}: {err}",
+                    path.display()
+                )))),
+            },
             Message::CloseRequested => Some(Action::Exit),
-        }
+        };
+
+        (Task::none(), action)
     }
 
     pub fn view(&self, theme: &iced_core::Theme) -> Element<'_, Message> {
@@ -193,6 +253,11 @@ impl ThemeEditor {
             .spacing(8)
             .align_y(Alignment::Center),
             color_picker(hsva_in, Message::Color),
+            row![
+                button(text("Import theme")).on_press(Message::ImportTheme),
+                button(text("Export theme")).on_press(Message::ExportTheme),
+            ]
+            .spacing(4),
         ]
         .spacing(10);
 
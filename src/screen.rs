@@ -15,6 +15,7 @@ pub struct ConfirmDialog<M> {
     pub message: String,
     pub on_confirm: Box<M>,
     pub on_confirm_btn_text: Option<String>,
+    pub on_cancel_btn_text: Option<String>,
 }
 
 impl<M> ConfirmDialog<M> {
@@ -23,6 +24,7 @@ impl<M> ConfirmDialog<M> {
             message,
             on_confirm,
             on_confirm_btn_text: None,
+            on_cancel_btn_text: None,
         }
     }
 
@@ -30,4 +32,9 @@ impl<M> ConfirmDialog<M> {
         self.on_confirm_btn_text = Some(on_confirm_btn_text);
         self
     }
+
+    pub fn with_cancel_btn_text(mut self, on_cancel_btn_text: String) -> Self {
+        self.on_cancel_btn_text = Some(on_cancel_btn_text);
+        self
+    }
 }
@@ -5,26 +5,42 @@ pub mod tickers_table;
 
 pub use sidebar::Sidebar;
 
-use super::DashboardError;
+use super::{ConfirmDialog, DashboardError};
 use crate::{
     chart,
-    screen::dashboard::tickers_table::TickersTable,
+    layout::configuration,
+    modal::{main_dialog_modal, pane::Modal},
+    screen::dashboard::{panel::mini_charts::MiniSeries, tickers_table::TickersTable},
     style,
-    widget::toast::Toast,
+    widget::{
+        confirm_dialog_container,
+        export::{self, CanvasSnapshot, ExportScale},
+        toast::Toast,
+    },
     window::{self, Window},
 };
 use data::{
     UserTimezone,
+    aggr::time::NpocSnapshot,
+    chart::{
+        Basis,
+        annotation::PriceLines,
+        kline::{ClusterScaling, NPoc},
+    },
+    config::ZoomMemory,
     layout::{WindowSpec, pane::ContentKind},
 };
 use exchange::{
-    Kline, PushFrequency, StreamPairKind, TickMultiplier, TickerInfo, Timeframe, Trade,
+    Kline, Liquidation, PushFrequency, StreamPairKind, TickMultiplier, TickerInfo, Timeframe,
+    Trade,
     adapter::{
         self, AdapterError, Exchange, PersistStreamKind, ResolvedStream, StreamConfig, StreamKind,
         StreamTicksize, UniqueStreams, binance, bybit, hyperliquid, okex,
     },
     depth::Depth,
-    fetcher::{FetchRange, FetchedData},
+    fetcher::{FetchProgress, FetchRange, FetchedData, TradeFetcher},
+    health::HealthMonitor,
+    util::Price,
 };
 
 use iced::{
@@ -35,9 +51,14 @@ use iced::{
         pane_grid::{self, Configuration},
     },
 };
-use iced_futures::futures::TryFutureExt;
+use iced_futures::futures::{TryFutureExt, channel::oneshot};
 use std::{collections::HashMap, path::PathBuf, time::Instant, vec};
 
+/// Ticks slower than a 60fps frame get logged in debug builds
+const FRAME_BUDGET: std::time::Duration = std::time::Duration::from_millis(16);
+/// Panes count as being rearranged until this long after the last drag or resize
+const LAYOUT_EDIT_SETTLE: std::time::Duration = std::time::Duration::from_secs(2);
+
 #[derive(Debug, Clone)]
 pub enum Message {
     Pane(window::Id, pane::Message),
@@ -52,6 +73,24 @@ pub enum Message {
         data: FetchedData,
     },
     ResolveStreams(uuid::Uuid, Vec<PersistStreamKind>),
+    RepairFocusedKlines,
+    FetchEvent(exchange::Event),
+    KlinesImported(uuid::Uuid, Vec<Kline>),
+    NpocComputed(uuid::Uuid, Vec<(u64, Price, NPoc)>),
+    /// Merges a cloned popout back, `keep_popout` moving its timeframe and zoom to the original
+    DockPopout {
+        window: window::Id,
+        pane: pane_grid::Pane,
+        keep_popout: bool,
+    },
+    CancelDock,
+    /// The pane's body is laid out at `size` and `path` was picked for its PNG
+    ExportPng {
+        pane_id: uuid::Uuid,
+        scale: ExportScale,
+        size: iced::Size,
+        path: PathBuf,
+    },
 }
 
 pub struct Dashboard {
@@ -59,7 +98,17 @@ pub struct Dashboard {
     pub focus: Option<(window::Id, pane_grid::Pane)>,
     pub popout: HashMap<window::Id, (pane_grid::State<pane::State>, WindowSpec)>,
     pub streams: UniqueStreams,
+    /// Health of the streams in `streams`, fed by the market events
+    pub stream_health: HealthMonitor,
+    pub price_lines: PriceLines,
+    /// Close of each kline stream's last update, what price alerts check the next one against
+    alert_closes: HashMap<StreamKind, Price>,
+    pub hybrid_weight: f32,
     layout_id: uuid::Uuid,
+    crosshair_source: Option<(window::Id, pane_grid::Pane)>,
+    last_layout_edit: Option<Instant>,
+    /// Cloned popout being merged back, waiting for the choice of whose view to keep
+    dock_prompt: Option<(window::Id, pane_grid::Pane)>,
 }
 
 impl Default for Dashboard {
@@ -68,8 +117,15 @@ impl Default for Dashboard {
             panes: pane_grid::State::with_configuration(Self::default_pane_config()),
             focus: None,
             streams: UniqueStreams::default(),
+            stream_health: HealthMonitor::default(),
             popout: HashMap::new(),
+            price_lines: PriceLines::default(),
+            alert_closes: HashMap::new(),
+            hybrid_weight: ClusterScaling::DEFAULT_HYBRID_WEIGHT,
             layout_id: uuid::Uuid::new_v4(),
+            crosshair_source: None,
+            last_layout_edit: None,
+            dock_prompt: None,
         }
     }
 }
@@ -87,6 +143,13 @@ pub enum Event {
         pane_id: uuid::Uuid,
         streams: Vec<PersistStreamKind>,
     },
+    /// Rendering needs the theme and timezone, so it's done outside of the dashboard
+    ExportPng {
+        pane_id: uuid::Uuid,
+        scale: ExportScale,
+        size: iced::Size,
+        path: PathBuf,
+    },
 }
 
 impl Dashboard {
@@ -118,6 +181,8 @@ impl Dashboard {
         panes: Configuration<pane::State>,
         popout_windows: Vec<(Configuration<pane::State>, WindowSpec)>,
         layout_id: uuid::Uuid,
+        price_lines: PriceLines,
+        hybrid_weight: f32,
     ) -> Self {
         let panes = pane_grid::State::with_configuration(panes);
 
@@ -134,12 +199,38 @@ impl Dashboard {
             panes,
             focus: None,
             streams: UniqueStreams::default(),
+            stream_health: HealthMonitor::default(),
             popout,
+            price_lines,
+            alert_closes: HashMap::new(),
+            hybrid_weight,
             layout_id,
+            crosshair_source: None,
+            last_layout_edit: None,
+            dock_prompt: None,
         }
     }
 
+    /// Whether panes are being dragged or resized right now
+    pub fn is_layout_being_edited(&self) -> bool {
+        self.last_layout_edit
+            .is_some_and(|at| at.elapsed() < LAYOUT_EDIT_SETTLE)
+    }
+
+    /// Drops the live state of every pane, keeping only its serialized config
+    pub fn unload_panes(&mut self) {
+        self.panes.iter_mut().for_each(|(_, state)| state.unload());
+        self.popout.values_mut().for_each(|(panes, _)| {
+            panes.iter_mut().for_each(|(_, state)| state.unload());
+        });
+
+        self.streams = UniqueStreams::default();
+    }
+
     pub fn load_layout(&mut self, main_window: window::Id) -> Task<Message> {
+        self.iter_all_panes_mut(main_window)
+            .for_each(|(_, _, state)| state.load());
+
         let mut open_popouts_tasks: Vec<Task<Message>> = vec![];
         let mut new_popout = Vec::new();
         let mut keys_to_remove = Vec::new();
@@ -177,8 +268,39 @@ impl Dashboard {
         message: Message,
         main_window: &Window,
         layout_id: &uuid::Uuid,
+        sync_crosshair: bool,
+        zoom_memory: ZoomMemory,
     ) -> (Task<Message>, Option<Event>) {
         match message {
+            Message::DockPopout {
+                window,
+                pane,
+                keep_popout,
+            } => {
+                return (
+                    self.dock_popout(window, pane, keep_popout, main_window),
+                    None,
+                );
+            }
+            Message::CancelDock => {
+                self.dock_prompt = None;
+            }
+            Message::ExportPng {
+                pane_id,
+                scale,
+                size,
+                path,
+            } => {
+                return (
+                    Task::none(),
+                    Some(Event::ExportPng {
+                        pane_id,
+                        scale,
+                        size,
+                        path,
+                    }),
+                );
+            }
             Message::SavePopoutSpecs(specs) => {
                 for (window_id, new_spec) in specs {
                     if let Some((_, spec)) = self.popout.get_mut(&window_id) {
@@ -191,6 +313,7 @@ impl Dashboard {
                     if let Some(state) = self.get_mut_pane_state_by_uuid(main_window.id, id) {
                         state.status = pane::Status::Ready;
                         state.notifications.push(Toast::error(err.to_string()));
+                        state.abort_gap_repair();
                     }
                 }
                 _ => {
@@ -206,8 +329,10 @@ impl Dashboard {
                 }
                 pane::Message::PaneResized(pane_grid::ResizeEvent { split, ratio }) => {
                     self.panes.resize(split, ratio);
+                    self.last_layout_edit = Some(Instant::now());
                 }
                 pane::Message::PaneDragged(event) => {
+                    self.last_layout_edit = Some(Instant::now());
                     if let pane_grid::DragEvent::Dropped { pane, target } = event {
                         self.panes.drop(pane, target);
                     }
@@ -276,11 +401,36 @@ impl Dashboard {
                                             ) | (
                                                 data::layout::pane::VisualConfig::Comparison(_),
                                                 pane::Content::Comparison(_)
+                                            ) | (
+                                                data::layout::pane::VisualConfig::Range(_),
+                                                pane::Content::Range { .. }
+                                            ) | (
+                                                data::layout::pane::VisualConfig::Correlation(_),
+                                                pane::Content::Correlation(_)
+                                            ) | (
+                                                data::layout::pane::VisualConfig::Statistics(_),
+                                                pane::Content::Statistics(_)
+                                            ) | (
+                                                data::layout::pane::VisualConfig::Spread(_),
+                                                pane::Content::Spread(_)
                                             )
                                         ),
                                     };
 
                                     if should_apply {
+                                        // compared ticker is specific to each pane
+                                        let cfg = match &cfg {
+                                            data::layout::pane::VisualConfig::Kline(kline_cfg) => {
+                                                data::layout::pane::VisualConfig::Kline(
+                                                    data::chart::kline::Config {
+                                                        compare: state.content.compare_overlays(),
+                                                        ..*kline_cfg
+                                                    },
+                                                )
+                                            }
+                                            _ => cfg.clone(),
+                                        };
+
                                         state.settings.visual_config = Some(cfg.clone());
                                         state.content.change_visual_config(cfg.clone());
 
@@ -332,8 +482,11 @@ impl Dashboard {
                             let pane_id = state.unique_id();
                             let content_kind = state.content.kind();
 
-                            let streams =
-                                state.set_content_and_streams(vec![ticker_info], content_kind);
+                            let streams = state.set_content_and_streams(
+                                vec![ticker_info],
+                                content_kind,
+                                zoom_memory,
+                            );
                             self.streams.extend(streams.iter());
 
                             for stream in &streams {
@@ -350,10 +503,31 @@ impl Dashboard {
                 pane::Message::Popout => {
                     return (self.popout_pane(main_window), None);
                 }
+                pane::Message::PopoutCopy => {
+                    return (self.popout_copy(main_window, zoom_memory), None);
+                }
                 pane::Message::Merge => {
                     return (self.merge_pane(main_window), None);
                 }
-                pane::Message::PaneEvent(pane, local) => {
+                pane::Message::PaneEvent(pane, mut local) => {
+                    if sync_crosshair
+                        && let pane::Event::ChartInteraction(chart::Message::CrosshairMoved(time)) =
+                            &local
+                    {
+                        self.broadcast_crosshair(main_window.id, window, pane, *time);
+                    }
+
+                    if let pane::Event::ChartInteraction(msg) = &local {
+                        self.update_price_lines(main_window.id, window, pane, msg);
+                    }
+
+                    if let pane::Event::ClusterScalingSelected(ClusterScaling::Hybrid { weight }) =
+                        &mut local
+                    {
+                        *weight = weight.clamp(0.0, 1.0);
+                        self.hybrid_weight = *weight;
+                    }
+
                     if let Some(state) = self.get_mut_pane(main_window.id, window, pane) {
                         let Some(effect) = state.update(local) else {
                             return (Task::none(), None);
@@ -367,12 +541,36 @@ impl Dashboard {
                                 reqs.into_iter().map(|r| (r.req_id, r.fetch, r.stream)),
                             )
                             .chain(self.refresh_streams(main_window.id)),
-                            pane::Effect::SwitchTickersInGroup(ticker_info) => {
-                                self.switch_tickers_in_group(main_window.id, ticker_info)
-                            }
+                            pane::Effect::SwitchTickersInGroup(ticker_info) => self
+                                .switch_tickers_in_group(main_window.id, ticker_info, zoom_memory),
                             pane::Effect::FocusWidget(id) => {
                                 return (iced::widget::operation::focus(id), None);
                             }
+                            pane::Effect::ExportPng(scale) => {
+                                export_pane_png(state.unique_id(), scale)
+                            }
+                            pane::Effect::CopyToClipboard(text) => iced::clipboard::write(text),
+                            pane::Effect::ExportCsv {
+                                file_name,
+                                contents,
+                            } => export_csv(file_name, contents),
+                            pane::Effect::ImportKlines(timeframe) => {
+                                import_klines(state.unique_id(), timeframe)
+                            }
+                            pane::Effect::LoadTemplate(template) => {
+                                let Some(ticker_info) = state.stream_pair() else {
+                                    return (Task::none(), None);
+                                };
+                                let content_kind = state.apply_template(&template, ticker_info);
+                                self.init_pane(
+                                    main_window.id,
+                                    window,
+                                    pane,
+                                    ticker_info,
+                                    content_kind,
+                                    zoom_memory,
+                                )
+                            }
                         };
                         return (task, None);
                     }
@@ -408,6 +606,46 @@ impl Dashboard {
             Message::Notification(toast) => {
                 return (Task::none(), Some(Event::Notification(toast)));
             }
+            Message::FetchEvent(event) => {
+                if let exchange::Event::FetchProgress(stream, progress) = event {
+                    self.update_fetch_progress(&stream, progress, main_window.id);
+                }
+            }
+            Message::KlinesImported(pane_id, klines) => {
+                let inserted = self
+                    .get_mut_pane_state_by_uuid(main_window.id, pane_id)
+                    .is_some_and(|state| state.insert_imported_klines(&klines));
+
+                let toast = if inserted {
+                    Toast::info(format!("Imported {} klines", klines.len()))
+                } else {
+                    Toast::warn("The pane no longer shows a time based kline chart")
+                };
+                return (Task::done(Message::Notification(toast)), None);
+            }
+            Message::NpocComputed(pane_id, statuses) => {
+                if let Some(state) = self.get_mut_pane_state_by_uuid(main_window.id, pane_id) {
+                    state.apply_npoc_statuses(&statuses);
+                }
+            }
+            Message::RepairFocusedKlines => {
+                let Some((window, pane)) = self.focus else {
+                    let toast = Toast::warn("Select a kline chart to repair its gaps");
+                    return (Task::done(Message::Notification(toast)), None);
+                };
+
+                if let Some(state) = self.get_mut_pane(main_window.id, window, pane)
+                    && let Some(pane::Action::Chart(chart::Action::RequestFetch(reqs))) =
+                        state.repair_kline_gaps()
+                {
+                    let task = request_fetch_many(
+                        state,
+                        *layout_id,
+                        reqs.into_iter().map(|r| (r.req_id, r.fetch, r.stream)),
+                    );
+                    return (task, None);
+                }
+            }
         }
 
         (Task::none(), None)
@@ -473,28 +711,83 @@ impl Dashboard {
         if let Some((_, id)) = self.focus.take()
             && let Some((pane, _)) = self.panes.close(id)
         {
-            let (window, task) = window::open(window::Settings {
-                position: main_window
-                    .position
-                    .map(|point| window::Position::Specific(point + Vector::new(20.0, 20.0)))
-                    .unwrap_or_default(),
-                exit_on_close_request: false,
-                min_size: Some(iced::Size::new(400.0, 300.0)),
-                ..window::settings()
-            });
+            return self.open_popout(pane, main_window);
+        }
 
-            let (state, id) = pane_grid::State::new(pane);
-            self.popout.insert(window, (state, WindowSpec::default()));
+        Task::none()
+    }
 
-            return task.then(move |window| {
-                Task::done(Message::Pane(window, pane::Message::PaneClicked(id)))
-            });
+    /// Opens a copy of the focused pane in a new window, with zoom and timeframe of its own
+    ///
+    /// The copy is rebuilt from the pane's saved config and shares the live streams of the
+    /// original. Time based candles start from the original's loaded series instead of
+    /// fetching it again, other charts fetch their own history.
+    fn popout_copy(&mut self, main_window: &Window, zoom_memory: ZoomMemory) -> Task<Message> {
+        if let Some((_, id)) = self.focus.take()
+            && let Some(origin) = self.panes.get(id)
+            && let Configuration::Pane(mut pane) = configuration(data::Pane::from(origin))
+        {
+            pane.popout_origin = Some(origin.unique_id());
+
+            if let pane::Content::Kline {
+                chart: Some(chart), ..
+            } = &origin.content
+            {
+                pane.set_content_and_streams(
+                    vec![chart.ticker_info()],
+                    origin.content.kind(),
+                    zoom_memory,
+                );
+
+                // the copy draws from the original's series, only its view of it is its own
+                if let pane::Content::Kline {
+                    chart: Some(copy), ..
+                } = &mut pane.content
+                {
+                    copy.share_series(chart);
+                }
+            }
+
+            return self.open_popout(pane, main_window);
         }
 
         Task::none()
     }
 
+    fn open_popout(&mut self, pane: pane::State, main_window: &Window) -> Task<Message> {
+        let (window, task) = window::open(window::Settings {
+            position: main_window
+                .position
+                .map(|point| window::Position::Specific(point + Vector::new(20.0, 20.0)))
+                .unwrap_or_default(),
+            exit_on_close_request: false,
+            min_size: Some(iced::Size::new(400.0, 300.0)),
+            ..window::settings()
+        });
+
+        let (state, id) = pane_grid::State::new(pane);
+        self.popout.insert(window, (state, WindowSpec::default()));
+
+        task.then(move |window| Task::done(Message::Pane(window, pane::Message::PaneClicked(id))))
+    }
+
     fn merge_pane(&mut self, main_window: &Window) -> Task<Message> {
+        // a copy whose original is still there asks which of the two settings to keep
+        if let Some((window, pane)) = self.focus
+            && let Some(origin) = self
+                .popout
+                .get(&window)
+                .and_then(|(panes, _)| panes.get(pane))
+                .and_then(|state| state.popout_origin)
+            && self
+                .panes
+                .iter()
+                .any(|(_, state)| state.unique_id() == origin)
+        {
+            self.dock_prompt = Some((window, pane));
+            return Task::none();
+        }
+
         if let Some((window, pane)) = self.focus.take()
             && let Some(pane_state) = self
                 .popout
@@ -509,6 +802,60 @@ impl Dashboard {
         Task::none()
     }
 
+    fn dock_popout(
+        &mut self,
+        window: window::Id,
+        pane: pane_grid::Pane,
+        keep_popout: bool,
+        main_window: &Window,
+    ) -> Task<Message> {
+        self.dock_prompt = None;
+
+        let Some(popout_state) = self
+            .popout
+            .remove(&window)
+            .and_then(|(mut panes, _)| panes.panes.remove(&pane))
+        else {
+            return Task::none();
+        };
+
+        // the copy shares the original's series, only its timeframe and zoom are its own
+        let mut fetch = Task::none();
+        if keep_popout
+            && let Some(origin) = popout_state.popout_origin
+            && let Some((_, state)) = self
+                .panes
+                .iter_mut()
+                .find(|(_, state)| state.unique_id() == origin)
+        {
+            if state.content.kind() == ContentKind::CandlestickChart
+                && let Some(Basis::Time(timeframe)) = popout_state.settings.selected_basis
+                && state.settings.selected_basis != Some(Basis::Time(timeframe))
+                && let Some(pane::Effect::RequestFetch(reqs)) =
+                    state.update(pane::Event::TimeframeTabSelected(timeframe))
+            {
+                fetch = request_fetch_many(
+                    state,
+                    self.layout_id,
+                    reqs.into_iter().map(|r| (r.req_id, r.fetch, r.stream)),
+                );
+            }
+            if let Some(zoom) = popout_state.content.zoom_state() {
+                state.content.restore_zoom(zoom);
+            }
+        }
+
+        if self.focus.is_some_and(|(focused, _)| focused == window) {
+            self.focus = None;
+        }
+
+        Task::batch([
+            window::close(window),
+            fetch,
+            self.refresh_streams(main_window.id),
+        ])
+    }
+
     pub fn get_pane(
         &self,
         main_window: window::Id,
@@ -539,6 +886,26 @@ impl Dashboard {
         }
     }
 
+    /// The pane's chart laid out at `size`, `None` when the pane is gone or shows no chart
+    pub fn canvas_snapshot<'a>(
+        &'a self,
+        main_window: window::Id,
+        pane_id: uuid::Uuid,
+        size: iced::Size,
+        theme: &'a iced::Theme,
+        timezone: UserTimezone,
+    ) -> Option<CanvasSnapshot<'a, chart::Message>> {
+        let (_, _, state) = self
+            .iter_all_panes(main_window)
+            .find(|(_, _, state)| state.unique_id() == pane_id)?;
+
+        Some(CanvasSnapshot {
+            content: state.chart_view(timezone)?,
+            size,
+            theme,
+        })
+    }
+
     fn get_mut_pane_state_by_uuid(
         &mut self,
         main_window: window::Id,
@@ -581,6 +948,8 @@ impl Dashboard {
         tickers_table: &'a TickersTable,
         timezone: UserTimezone,
     ) -> Element<'a, Message> {
+        let mini_series = mini_series(&self.panes);
+
         let pane_grid: Element<_> = PaneGrid::new(&self.panes, |id, pane, maximized| {
             let is_focused = self.focus == Some((main_window.id, id));
             pane.view(
@@ -592,6 +961,8 @@ impl Dashboard {
                 main_window,
                 timezone,
                 tickers_table,
+                self.hybrid_weight,
+                &mini_series,
             )
         })
         .min_size(240)
@@ -613,6 +984,8 @@ impl Dashboard {
         timezone: UserTimezone,
     ) -> Element<'a, Message> {
         if let Some((state, _)) = self.popout.get(&window) {
+            let mini_series = mini_series(state);
+
             let content = container(
                 PaneGrid::new(state, |id, pane, _maximized| {
                     let is_focused = self.focus == Some((window, id));
@@ -625,6 +998,8 @@ impl Dashboard {
                         main_window,
                         timezone,
                         tickers_table,
+                        self.hybrid_weight,
+                        &mini_series,
                     )
                 })
                 .on_click(pane::Message::PaneClicked),
@@ -633,7 +1008,30 @@ impl Dashboard {
             .height(Length::Fill)
             .padding(8);
 
-            Element::new(content).map(move |message| Message::Pane(window, message))
+            let content = Element::new(content).map(move |message| Message::Pane(window, message));
+
+            match self.dock_prompt {
+                Some((prompt_window, pane)) if prompt_window == window => {
+                    let dock = |keep_popout| Message::DockPopout {
+                        window,
+                        pane,
+                        keep_popout,
+                    };
+                    let dialog = ConfirmDialog::new(
+                        "Merge back into the main window. Keep whose view?".to_string(),
+                        Box::new(dock(true)),
+                    )
+                    .with_confirm_btn_text("Popout".to_string())
+                    .with_cancel_btn_text("Main window".to_string());
+
+                    main_dialog_modal(
+                        content,
+                        confirm_dialog_container(dialog, dock(false)),
+                        Message::CancelDock,
+                    )
+                }
+                _ => content,
+            }
         } else {
             Element::new(center("No pane found for window"))
                 .map(move |message| Message::Pane(window, message))
@@ -681,11 +1079,13 @@ impl Dashboard {
         selected_pane: pane_grid::Pane,
         ticker_info: TickerInfo,
         content_kind: ContentKind,
+        zoom_memory: ZoomMemory,
     ) -> Task<Message> {
         if let Some(state) = self.get_mut_pane(main_window, window, selected_pane) {
             let pane_id = state.unique_id();
 
-            let streams = state.set_content_and_streams(vec![ticker_info], content_kind);
+            let streams =
+                state.set_content_and_streams(vec![ticker_info], content_kind, zoom_memory);
             self.streams.extend(streams.iter());
 
             for stream in &streams {
@@ -703,6 +1103,7 @@ impl Dashboard {
         main_window: window::Id,
         ticker_info: TickerInfo,
         content_kind: ContentKind,
+        zoom_memory: ZoomMemory,
     ) -> Task<Message> {
         if self.focus.is_none()
             && self.panes.len() == 1
@@ -719,7 +1120,8 @@ impl Dashboard {
                 state.link_group = None;
             }
 
-            let streams = state.set_content_and_streams(vec![ticker_info], content_kind);
+            let streams =
+                state.set_content_and_streams(vec![ticker_info], content_kind, zoom_memory);
 
             let pane_id = state.unique_id();
             self.streams.extend(streams.iter());
@@ -737,10 +1139,180 @@ impl Dashboard {
         )))
     }
 
+    /// Mirrors the cursor time of a pane onto the other panes of its link group
+    fn broadcast_crosshair(
+        &mut self,
+        main_window: window::Id,
+        window: window::Id,
+        pane: pane_grid::Pane,
+        time: Option<u64>,
+    ) {
+        // every chart reports cursor moves, only the hovered one may clear the synced lines
+        if time.is_none() && self.crosshair_source != Some((window, pane)) {
+            return;
+        }
+
+        let Some(group) = self
+            .get_pane(main_window, window, pane)
+            .and_then(|state| state.link_group)
+        else {
+            return;
+        };
+
+        self.crosshair_source = time.map(|_| (window, pane));
+
+        self.iter_all_panes_mut(main_window)
+            .filter(|(_, _, state)| state.link_group == Some(group))
+            .for_each(|(w, p, state)| {
+                let is_source = w == window && p == pane;
+                state
+                    .content
+                    .set_synced_crosshair(if is_source { None } else { time });
+            });
+    }
+
+    pub fn clear_synced_crosshair(&mut self, main_window: window::Id) {
+        self.crosshair_source = None;
+
+        self.iter_all_panes_mut(main_window)
+            .for_each(|(_, _, state)| state.content.set_synced_crosshair(None));
+    }
+
+    /// Applies price line edits to the per-ticker store, then redraws every pane of that ticker
+    fn update_price_lines(
+        &mut self,
+        main_window: window::Id,
+        window: window::Id,
+        pane: pane_grid::Pane,
+        msg: &chart::Message,
+    ) {
+        let Some(ticker) = self
+            .get_pane(main_window, window, pane)
+            .and_then(pane::State::stream_pair)
+            .map(|ticker_info| ticker_info.ticker)
+        else {
+            return;
+        };
+
+        match msg {
+            chart::Message::AddPriceLine(price) => self.price_lines.add(ticker, *price),
+            chart::Message::MovePriceLine(index, price) => {
+                self.price_lines.move_line(&ticker, *index, *price);
+            }
+            chart::Message::RemovePriceLine(index) => self.price_lines.remove(&ticker, *index),
+            chart::Message::PriceLineLabelChanged(index, label) => {
+                self.price_lines.set_label(&ticker, *index, label.clone());
+            }
+            chart::Message::SetPriceAlert(index, alert) => {
+                self.price_lines.set_alert(&ticker, *index, *alert);
+            }
+            _ => return,
+        }
+
+        self.sync_price_lines(main_window);
+    }
+
+    fn sync_price_lines(&mut self, main_window: window::Id) {
+        let price_lines = std::mem::take(&mut self.price_lines);
+
+        self.iter_all_panes_mut(main_window)
+            .for_each(|(_, _, state)| state.content.sync_price_lines(&price_lines));
+
+        self.price_lines = price_lines;
+    }
+
+    /// Clears ruler measurements in every pane, returns whether any was active
+    /// Pans or zooms the focused pane's chart, returns whether there was one to navigate
+    pub fn navigate_focused(
+        &mut self,
+        main_window: window::Id,
+        key: chart::NavigationKey,
+        steps: data::config::KeyboardNav,
+    ) -> bool {
+        let Some((window, pane)) = self.focus else {
+            return false;
+        };
+
+        self.get_mut_pane(main_window, window, pane)
+            .is_some_and(|state| state.content.navigate(key, steps))
+    }
+
+    /// Closes pane right click menus, a right click opens one without focusing its pane
+    pub fn close_context_menus(&mut self, main_window: window::Id) -> bool {
+        self.iter_all_panes_mut(main_window)
+            .fold(false, |closed, (_, _, state)| {
+                if matches!(state.modal, Some(Modal::ContextMenu(_))) {
+                    state.modal = None;
+                    return true;
+                }
+                closed
+            })
+    }
+
+    pub fn clear_measurements(&mut self, main_window: window::Id) -> bool {
+        self.iter_all_panes_mut(main_window)
+            .fold(false, |cleared, (_, _, state)| {
+                state.content.clear_measurement() || cleared
+            })
+    }
+
+    /// Opens the ticker picked from the quick switcher: in the focused pane and its link group
+    /// keeping their content, or as a candlestick chart split off when no pane is focused
+    pub fn open_ticker(
+        &mut self,
+        main_window: window::Id,
+        ticker_info: TickerInfo,
+        zoom_memory: ZoomMemory,
+    ) -> Task<Message> {
+        if self.focus.is_none()
+            && self.panes.len() == 1
+            && let Some((pane_id, _)) = self.panes.iter().next()
+        {
+            self.focus = Some((main_window, *pane_id));
+        }
+
+        let focused_kind = self.focus.and_then(|(window, pane)| {
+            self.get_pane(main_window, window, pane)
+                .map(|state| state.content.kind())
+        });
+
+        match focused_kind {
+            Some(ContentKind::Starter) => self.init_focused_pane(
+                main_window,
+                ticker_info,
+                ContentKind::CandlestickChart,
+                zoom_memory,
+            ),
+            Some(_) => self.switch_tickers_in_group(main_window, ticker_info, zoom_memory),
+            None => {
+                let Some(anchor) = self.panes.iter().next().map(|(pane, _)| *pane) else {
+                    return Task::none();
+                };
+
+                match self
+                    .panes
+                    .split(pane_grid::Axis::Vertical, anchor, pane::State::new())
+                {
+                    Some((pane, _)) => {
+                        self.focus = Some((main_window, pane));
+                        self.init_focused_pane(
+                            main_window,
+                            ticker_info,
+                            ContentKind::CandlestickChart,
+                            zoom_memory,
+                        )
+                    }
+                    None => Task::none(),
+                }
+            }
+        }
+    }
+
     pub fn switch_tickers_in_group(
         &mut self,
         main_window: window::Id,
         ticker_info: TickerInfo,
+        zoom_memory: ZoomMemory,
     ) -> Task<Message> {
         if self.focus.is_none()
             && self.panes.len() == 1
@@ -769,7 +1341,17 @@ impl Dashboard {
             let tasks: Vec<Task<Message>> = pane_infos
                 .iter()
                 .map(|(window, pane, content_kind)| {
-                    self.init_pane(main_window, *window, *pane, ticker_info, *content_kind)
+                    if let Some(state) = self.get_mut_pane(main_window, *window, *pane) {
+                        state.compare_previous_on_link(ticker_info);
+                    }
+                    self.init_pane(
+                        main_window,
+                        *window,
+                        *pane,
+                        ticker_info,
+                        *content_kind,
+                        zoom_memory,
+                    )
                 })
                 .collect();
 
@@ -777,7 +1359,7 @@ impl Dashboard {
         } else if let Some((window, pane)) = self.focus {
             if let Some(state) = self.get_mut_pane(main_window, window, pane) {
                 let content_kind = state.content.kind();
-                self.init_focused_pane(main_window, ticker_info, content_kind)
+                self.init_focused_pane(main_window, ticker_info, content_kind, zoom_memory)
             } else {
                 Task::done(Message::Notification(Toast::warn(
                     "Couldn't get focused pane's content".to_string(),
@@ -880,6 +1462,7 @@ impl Dashboard {
                     "No matching pane state found for fetched trades".to_string(),
                 )
             })?;
+        pane_state.mark_dirty();
 
         match &mut pane_state.status {
             pane::Status::Loading(exchange::fetcher::InfoKind::FetchingTrades(count)) => {
@@ -913,36 +1496,116 @@ impl Dashboard {
         }
     }
 
+    /// Shows the progress of a batched kline fetch on the panes waiting for it
+    pub fn update_fetch_progress(
+        &mut self,
+        stream: &StreamKind,
+        progress: FetchProgress,
+        main_window: window::Id,
+    ) {
+        self.iter_all_panes_mut(main_window)
+            .for_each(|(_, _, pane_state)| {
+                let is_fetching_klines = matches!(
+                    pane_state.status,
+                    pane::Status::Loading(
+                        exchange::fetcher::InfoKind::FetchingKlines
+                            | exchange::fetcher::InfoKind::FetchingKlineHistory(_)
+                    )
+                );
+
+                if is_fetching_klines && pane_state.matches_stream(stream) {
+                    pane_state.status = pane::Status::Loading(
+                        exchange::fetcher::InfoKind::FetchingKlineHistory(progress),
+                    );
+                }
+            });
+    }
+
     pub fn update_latest_klines(
         &mut self,
         stream: &StreamKind,
         kline: &Kline,
         main_window: window::Id,
-    ) -> Task<Message> {
+    ) -> (Task<Message>, bool) {
         let mut found_match = false;
 
         self.iter_all_panes_mut(main_window)
             .for_each(|(_, _, pane_state)| {
                 if pane_state.matches_stream(stream) {
+                    pane_state.mark_dirty();
+
                     match &mut pane_state.content {
-                        pane::Content::Kline { chart: Some(c), .. } => {
-                            c.update_latest_kline(kline);
-                        }
+                        pane::Content::Kline { chart: Some(c), .. } => match stream {
+                            // a timeframe the pane keeps loaded but doesn't show
+                            StreamKind::Kline { timeframe, .. }
+                                if c.basis() != Basis::Time(*timeframe) =>
+                            {
+                                c.update_cached_kline(*timeframe, kline);
+                            }
+                            _ => c.update_latest_kline(&stream.ticker_info(), kline),
+                        },
                         pane::Content::Comparison(Some(c)) => {
                             c.update_latest_kline(&stream.ticker_info(), kline);
                         }
+                        pane::Content::Correlation(Some(m)) => {
+                            m.update_latest_kline(&stream.ticker_info(), kline);
+                        }
                         _ => {}
                     }
                     found_match = true;
                 }
             });
 
-        if found_match {
-            Task::none()
-        } else {
+        if !found_match {
             log::debug!("{stream:?} stream had no matching panes - dropping");
-            self.refresh_streams(main_window)
+            return (self.refresh_streams(main_window), false);
         }
+
+        let Some(previous_close) = self.alert_closes.insert(*stream, kline.close) else {
+            return (Task::none(), false);
+        };
+
+        self.check_price_alerts(&stream.ticker_info(), previous_close, kline, main_window)
+    }
+
+    /// Fires alerts on the ticker's price lines crossed by the kline,
+    /// returns the notifications and whether any of them asked for a sound
+    fn check_price_alerts(
+        &mut self,
+        ticker_info: &TickerInfo,
+        previous_close: Price,
+        kline: &Kline,
+        main_window: window::Id,
+    ) -> (Task<Message>, bool) {
+        let fired = self
+            .price_lines
+            .check_alerts(&ticker_info.ticker, previous_close, kline);
+        if fired.is_empty() {
+            return (Task::none(), false);
+        }
+
+        let symbol = ticker_info.ticker.display_symbol_and_type().0;
+        let play_sound = fired.iter().any(|line| line.alert.is_some_and(|a| a.sound));
+
+        let notifications = fired.into_iter().filter_map(|line| {
+            let alert = line.alert?;
+            let price = line.price.to_string(ticker_info.min_ticksize);
+
+            let body = if line.label.is_empty() {
+                format!("{symbol} crossed {} {price}", alert.direction)
+            } else {
+                format!(
+                    "{symbol} crossed {} {price} ({})",
+                    alert.direction, line.label
+                )
+            };
+
+            Some(Task::done(Message::Notification(Toast::info(body))))
+        });
+
+        self.sync_price_lines(main_window);
+
+        (Task::batch(notifications), play_sound)
     }
 
     pub fn update_depth_and_trades(
@@ -958,6 +1621,8 @@ impl Dashboard {
         self.iter_all_panes_mut(main_window)
             .for_each(|(_, _, pane_state)| {
                 if pane_state.matches_stream(stream) {
+                    pane_state.mark_dirty();
+
                     match &mut pane_state.content {
                         pane::Content::Heatmap { chart, .. } => {
                             if let Some(c) = chart {
@@ -965,6 +1630,12 @@ impl Dashboard {
                             }
                         }
                         pane::Content::Kline { chart, .. } => {
+                            if let Some(c) = chart {
+                                c.insert_trades_buffer(trades_buffer);
+                                c.insert_depth(depth_update_t, depth);
+                            }
+                        }
+                        pane::Content::Range { chart, .. } => {
                             if let Some(c) = chart {
                                 c.insert_trades_buffer(trades_buffer);
                             }
@@ -979,6 +1650,16 @@ impl Dashboard {
                                 panel.insert_buffers(depth_update_t, depth, trades_buffer);
                             }
                         }
+                        pane::Content::Statistics(panel) => {
+                            if let Some(panel) = panel {
+                                panel.insert_buffers(depth_update_t, depth, trades_buffer);
+                            }
+                        }
+                        pane::Content::Spread(chart) => {
+                            if let Some(chart) = chart {
+                                chart.insert_depth(depth_update_t, depth);
+                            }
+                        }
                         _ => {
                             log::error!("No chart found for the stream: {stream:?}");
                         }
@@ -995,6 +1676,23 @@ impl Dashboard {
         }
     }
 
+    pub fn insert_liquidation(
+        &mut self,
+        stream: &StreamKind,
+        liquidation: &Liquidation,
+        main_window: window::Id,
+    ) {
+        self.iter_all_panes_mut(main_window)
+            .for_each(|(_, _, pane_state)| {
+                if pane_state.matches_stream(stream)
+                    && let pane::Content::Kline { chart: Some(c), .. } = &mut pane_state.content
+                {
+                    c.insert_liquidation(liquidation);
+                    pane_state.mark_dirty();
+                }
+            });
+    }
+
     pub fn invalidate_all_panes(&mut self, main_window: window::Id) {
         self.iter_all_panes_mut(main_window)
             .for_each(|(_, _, state)| {
@@ -1002,44 +1700,80 @@ impl Dashboard {
             });
     }
 
-    pub fn tick(&mut self, now: Instant, main_window: window::Id) -> Task<Message> {
+    pub fn tick(
+        &mut self,
+        now: Instant,
+        main_window: window::Id,
+        zoom_memory: ZoomMemory,
+    ) -> Task<Message> {
         let mut tasks = vec![];
         let layout_id = self.layout_id;
+        let started = Instant::now();
+        let (mut pane_count, mut dirty_count) = (0, 0);
+
+        // charts created since the last tick pick up the lines of their ticker
+        self.sync_price_lines(main_window);
+        self.stream_health.sync(
+            self.streams.iter(),
+            chrono::Utc::now().timestamp_millis() as u64,
+        );
 
         self.iter_all_panes_mut(main_window)
-            .for_each(|(_window_id, _pane, state)| match state.tick(now) {
-                Some(pane::Action::Chart(action)) => match action {
-                    chart::Action::ErrorOccurred(err) => {
-                        state.status = pane::Status::Ready;
-                        state.notifications.push(Toast::error(err.to_string()));
-                    }
-                    chart::Action::RequestFetch(reqs) => {
-                        tasks.push(request_fetch_many(
-                            state,
-                            layout_id,
-                            reqs.into_iter().map(|r| (r.req_id, r.fetch, r.stream)),
-                        ));
-                    }
-                },
-                Some(pane::Action::Panel(_action)) => {}
-                Some(pane::Action::ResolveStreams(streams)) => {
-                    tasks.push(Task::done(Message::ResolveStreams(
-                        state.unique_id(),
-                        streams,
-                    )));
-                }
-                Some(pane::Action::ResolveContent) => match state.stream_pair_kind() {
-                    Some(StreamPairKind::MultiSource(tickers)) => {
-                        state.set_content_and_streams(tickers, state.content.kind());
-                    }
-                    Some(StreamPairKind::SingleSource(ticker)) => {
-                        state.set_content_and_streams(vec![ticker], state.content.kind());
+            .for_each(|(_window_id, _pane, state)| {
+                pane_count += 1;
+                dirty_count += usize::from(state.is_dirty());
+
+                match state.tick(now) {
+                    Some(pane::Action::Chart(action)) => match action {
+                        chart::Action::ErrorOccurred(err) => {
+                            state.status = pane::Status::Ready;
+                            state.notifications.push(Toast::error(err.to_string()));
+                        }
+                        chart::Action::RequestFetch(reqs) => {
+                            tasks.push(request_fetch_many(
+                                state,
+                                layout_id,
+                                reqs.into_iter().map(|r| (r.req_id, r.fetch, r.stream)),
+                            ));
+                        }
+                    },
+                    Some(pane::Action::Panel(_action)) => {}
+                    Some(pane::Action::ResolveStreams(streams)) => {
+                        tasks.push(Task::done(Message::ResolveStreams(
+                            state.unique_id(),
+                            streams,
+                        )));
                     }
+                    Some(pane::Action::ResolveContent) => match state.stream_pair_kind() {
+                        Some(StreamPairKind::MultiSource(tickers)) => {
+                            state.set_content_and_streams(
+                                tickers,
+                                state.content.kind(),
+                                zoom_memory,
+                            );
+                        }
+                        Some(StreamPairKind::SingleSource(ticker)) => {
+                            state.set_content_and_streams(
+                                vec![ticker],
+                                state.content.kind(),
+                                zoom_memory,
+                            );
+                        }
+                        None => {}
+                    },
                     None => {}
-                },
-                None => {}
+                }
+
+                if let Some(snapshot) = state.take_npoc_job() {
+                    tasks.push(compute_npoc(state.unique_id(), snapshot));
+                }
             });
 
+        let elapsed = started.elapsed();
+        if cfg!(debug_assertions) && elapsed > FRAME_BUDGET {
+            log::warn!("Tick took {elapsed:?} with {dirty_count} of {pane_count} panes to redraw");
+        }
+
         Task::batch(tasks)
     }
 
@@ -1055,6 +1789,89 @@ impl Dashboard {
         self.refresh_streams(main_window)
     }
 
+    /// Empties the charts showing the replayed `stream` and fills them with `klines`, those the
+    /// replay released so far. Live history isn't fetched into them until the replay ends
+    pub fn restart_replay(
+        &mut self,
+        stream: &StreamKind,
+        klines: &[Kline],
+        main_window: window::Id,
+    ) {
+        match klines.last() {
+            Some(kline) => self.alert_closes.insert(*stream, kline.close),
+            None => self.alert_closes.remove(stream),
+        };
+
+        self.for_each_replayed_chart(stream, main_window, |chart| {
+            chart.reset_for_replay(klines);
+        });
+    }
+
+    /// Drops the replayed klines, the charts fetch their live history again from the next tick
+    pub fn end_replay(&mut self, stream: &StreamKind, main_window: window::Id) {
+        self.alert_closes.remove(stream);
+
+        self.for_each_replayed_chart(stream, main_window, chart::kline::KlineChart::end_replay);
+    }
+
+    fn for_each_replayed_chart(
+        &mut self,
+        stream: &StreamKind,
+        main_window: window::Id,
+        mut f: impl FnMut(&mut chart::kline::KlineChart),
+    ) {
+        let StreamKind::Kline { timeframe, .. } = stream else {
+            return;
+        };
+
+        self.iter_all_panes_mut(main_window)
+            .for_each(|(_, _, pane_state)| {
+                if !pane_state.matches_stream(stream) {
+                    return;
+                }
+
+                if let pane::Content::Kline { chart: Some(c), .. } = &mut pane_state.content
+                    && c.basis() == Basis::Time(*timeframe)
+                {
+                    f(c);
+                    pane_state.mark_dirty();
+                }
+            });
+    }
+
+    /// Kline stream of the focused pane, the one a replay gets played into
+    pub fn focused_kline_stream(&self, main_window: window::Id) -> Option<StreamKind> {
+        let (window, pane) = self.focus?;
+
+        self.get_pane(main_window, window, pane)?
+            .streams
+            .find_ready_map(|stream| match stream {
+                StreamKind::Kline { .. } => Some(*stream),
+                StreamKind::DepthAndTrades { .. } => None,
+            })
+    }
+
+    /// Depth and kline subscriptions the dashboard keeps open
+    pub fn stream_count(&self) -> usize {
+        self.streams
+            .combined_used()
+            .map(|(_, specs)| specs.depth.len() + specs.kline.len())
+            .sum()
+    }
+
+    /// Whether any exchange streamed to this dashboard can backfill trades
+    pub fn supports_trade_fetch(&self) -> bool {
+        self.streams
+            .combined_used()
+            .any(|(exchange, _)| exchange.supports_trade_fetch())
+    }
+
+    pub fn datapoint_count(&self, main_window: window::Id) -> usize {
+        self.iter_all_panes(main_window)
+            .map(|(_, _, state)| state.datapoint_count())
+            .sum()
+    }
+
     pub fn market_subscriptions(&self) -> Subscription<exchange::Event> {
         let unique_streams = self
             .streams
@@ -1098,9 +1915,14 @@ impl Dashboard {
     }
 
     fn refresh_streams(&mut self, main_window: window::Id) -> Task<Message> {
+        let cached_streams = self
+            .iter_all_panes(main_window)
+            .flat_map(|(_, _, pane_state)| pane_state.cached_kline_streams())
+            .collect::<Vec<_>>();
         let all_pane_streams = self
             .iter_all_panes(main_window)
-            .flat_map(|(_, _, pane_state)| pane_state.streams.ready_iter().into_iter().flatten());
+            .flat_map(|(_, _, pane_state)| pane_state.streams.ready_iter().into_iter().flatten())
+            .chain(&cached_streams);
         self.streams = UniqueStreams::from(all_pane_streams);
 
         Task::none()
@@ -1163,7 +1985,9 @@ fn request_fetch(
         }
         FetchRange::Trades(from_time, to_time) => {
             let trade_info = state.streams.find_ready_map(|stream| {
-                if let StreamKind::DepthAndTrades { ticker_info, .. } = stream {
+                if let StreamKind::DepthAndTrades { ticker_info, .. } = stream
+                    && ticker_info.exchange().supports_trade_fetch()
+                {
                     Some((*ticker_info, pane_id, *stream))
                 } else {
                     None
@@ -1171,46 +1995,39 @@ fn request_fetch(
             });
 
             if let Some((ticker_info, pane_id, stream)) = trade_info {
-                let is_binance = matches!(
-                    ticker_info.exchange(),
-                    Exchange::BinanceSpot | Exchange::BinanceLinear | Exchange::BinanceInverse
-                );
-
-                if is_binance {
-                    let data_path = data::data_path(Some("market_data/binance/"));
-
-                    let (task, handle) = Task::sip(
-                        fetch_trades_batched(ticker_info, from_time, to_time, data_path),
-                        move |batch| {
-                            let data = FetchedData::Trades {
-                                batch,
-                                until_time: to_time,
-                            };
-                            Message::DistributeFetchedData {
-                                layout_id,
-                                pane_id,
-                                data,
-                                stream,
-                            }
-                        },
-                        move |result| match result {
-                            Ok(()) => Message::ChangePaneStatus(pane_id, pane::Status::Ready),
-                            Err(err) => Message::ErrorOccurred(
-                                Some(pane_id),
-                                DashboardError::Fetch(err.to_string()),
-                            ),
-                        },
-                    )
-                    .abortable();
-
-                    if let pane::Content::Kline { chart, .. } = &mut state.content
-                        && let Some(c) = chart
-                    {
-                        c.set_handle(handle.abort_on_drop());
-                    }
+                let data_path = data::data_path(Some("market_data/"));
+
+                let (task, handle) = Task::sip(
+                    fetch_trades_batched(ticker_info, from_time, to_time, data_path),
+                    move |batch| {
+                        let data = FetchedData::Trades {
+                            batch,
+                            until_time: to_time,
+                        };
+                        Message::DistributeFetchedData {
+                            layout_id,
+                            pane_id,
+                            data,
+                            stream,
+                        }
+                    },
+                    move |result| match result {
+                        Ok(()) => Message::ChangePaneStatus(pane_id, pane::Status::Ready),
+                        Err(err) => Message::ErrorOccurred(
+                            Some(pane_id),
+                            DashboardError::Fetch(err.to_string()),
+                        ),
+                    },
+                )
+                .abortable();
 
-                    return task;
+                if let pane::Content::Kline { chart, .. } = &mut state.content
+                    && let Some(c) = chart
+                {
+                    c.set_handle(handle.abort_on_drop());
                 }
+
+                return task;
             }
         }
     }
@@ -1218,6 +2035,98 @@ fn request_fetch(
     Task::none()
 }
 
+fn export_pane_png(pane_id: uuid::Uuid, scale: ExportScale) -> Task<Message> {
+    let file_name = format!(
+        "flowsurface-{}.png",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    );
+
+    Task::future(export::pick_png_path(file_name)).then(move |path| {
+        let Some(path) = path else {
+            return Task::none();
+        };
+
+        export::pane_size(pane_id).map(move |size| match size {
+            Some(size) => Message::ExportPng {
+                pane_id,
+                scale,
+                size,
+                path: path.clone(),
+            },
+            None => {
+                Message::Notification(Toast::error(export::ExportError::NotVisible.to_string()))
+            }
+        })
+    })
+}
+
+fn export_csv(file_name: String, contents: String) -> Task<Message> {
+    let save = async move {
+        let handle = rfd::AsyncFileDialog::new()
+            .add_filter("CSV", &["csv"])
+            .set_file_name(file_name)
+            .save_file()
+            .await?;
+        let path = handle.path().to_path_buf();
+
+        Some(std::fs::write(&path, contents).map(|()| path))
+    };
+
+    Task::future(save).then(|result| match result {
+        Some(Ok(path)) => Task::done(Message::Notification(Toast::info(format!(
+            "Saved {}",
+            path.display()
+        )))),
+        Some(Err(err)) => Task::done(Message::Notification(Toast::error(err.to_string()))),
+        None => Task::none(),
+    })
+}
+
+/// Works out the naked POCs of a large footprint on its own thread, the pane applies them
+/// once they're back
+fn compute_npoc(pane_id: uuid::Uuid, snapshot: Box<NpocSnapshot>) -> Task<Message> {
+    let compute = async move {
+        let (sender, receiver) = oneshot::channel();
+
+        std::thread::spawn(move || {
+            let _ = sender.send(snapshot.compute());
+        });
+
+        // an empty result still frees the pane for its next computation
+        receiver.await.unwrap_or_default()
+    };
+
+    Task::future(compute).map(move |statuses| Message::NpocComputed(pane_id, statuses))
+}
+
+fn import_klines(pane_id: uuid::Uuid, timeframe: Timeframe) -> Task<Message> {
+    let import = async move {
+        let handle = rfd::AsyncFileDialog::new()
+            .add_filter("Klines", &["csv", "json"])
+            .pick_file()
+            .await?;
+        let path = handle.path();
+
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+
+        Some(if is_json {
+            data::import::import_klines_json(path)
+        } else {
+            data::import::import_klines_csv(path, timeframe)
+        })
+    };
+
+    Task::future(import).then(move |result| match result {
+        Some(Ok(klines)) => Task::done(Message::KlinesImported(pane_id, klines)),
+        Some(Err(err)) => Task::done(Message::Notification(Toast::error(format!(
+            "Import failed: {err}"
+        )))),
+        None => Task::none(),
+    })
+}
+
 fn request_fetch_many(
     state: &mut pane::State,
     layout_id: uuid::Uuid,
@@ -1280,11 +2189,59 @@ fn kline_fetch_task(
         pane::Status::Loading(exchange::fetcher::InfoKind::FetchingKlines),
     ));
 
-    let fetch_task = match stream {
+    // ranges longer than a single Binance request are fetched in batches to report progress
+    let batched_range = match stream {
         StreamKind::Kline {
             ticker_info,
             timeframe,
-        } => Task::perform(
+        } if matches!(
+            ticker_info.exchange(),
+            Exchange::BinanceSpot | Exchange::BinanceLinear | Exchange::BinanceInverse
+        ) =>
+        {
+            range.filter(|(from, to)| {
+                to.saturating_sub(*from) / timeframe.to_milliseconds() > binance::KLINE_FETCH_LIMIT
+            })
+        }
+        _ => None,
+    };
+
+    let fetch_task = match (stream, batched_range) {
+        (
+            StreamKind::Kline {
+                ticker_info,
+                timeframe,
+            },
+            Some((from, to)),
+        ) => Task::sip(
+            fetch_klines_batched(stream, ticker_info, timeframe, from, to),
+            Message::FetchEvent,
+            move |result| match result {
+                Ok(klines) => {
+                    let data = FetchedData::Klines {
+                        data: klines,
+                        req_id,
+                    };
+                    Message::DistributeFetchedData {
+                        layout_id,
+                        pane_id,
+                        data,
+                        stream,
+                    }
+                }
+                Err(err) => Message::ErrorOccurred(
+                    Some(pane_id),
+                    DashboardError::Fetch(err.to_user_message().to_string()),
+                ),
+            },
+        ),
+        (
+            StreamKind::Kline {
+                ticker_info,
+                timeframe,
+            },
+            None,
+        ) => Task::perform(
             adapter::fetch_klines(ticker_info, timeframe, range)
                 .map_err(|err| err.to_user_message()),
             move |result| match result {
@@ -1311,6 +2268,49 @@ fn kline_fetch_task(
     update_status.chain(fetch_task)
 }
 
+/// Splits a kline range longer than one Binance request into consecutive requests,
+/// reporting each finished one as fetch progress
+pub fn fetch_klines_batched(
+    stream: StreamKind,
+    ticker_info: TickerInfo,
+    timeframe: Timeframe,
+    from_time: u64,
+    to_time: u64,
+) -> impl Straw<Vec<Kline>, exchange::Event, AdapterError> {
+    sipper(async move |mut progress| {
+        let batch_span = timeframe.to_milliseconds() * binance::KLINE_FETCH_LIMIT;
+        let total = to_time.saturating_sub(from_time).div_ceil(batch_span) as usize;
+
+        let mut klines: Vec<Kline> = vec![];
+        let mut batch_start = from_time;
+        let mut fetched = 0;
+
+        while batch_start < to_time {
+            let batch_end = (batch_start + batch_span).min(to_time);
+
+            let batch =
+                binance::fetch_klines(ticker_info, timeframe, Some((batch_start, batch_end)))
+                    .await?;
+            klines.extend(batch);
+
+            fetched += 1;
+            let () = progress
+                .send(exchange::Event::FetchProgress(
+                    stream,
+                    FetchProgress { fetched, total },
+                ))
+                .await;
+
+            batch_start = batch_end;
+        }
+
+        klines.sort_by_key(|kline| kline.time);
+        klines.dedup_by_key(|kline| kline.time);
+
+        Ok(klines)
+    })
+}
+
 pub fn fetch_trades_batched(
     ticker_info: TickerInfo,
     from_time: u64,
@@ -1318,10 +2318,14 @@ pub fn fetch_trades_batched(
     data_path: PathBuf,
 ) -> impl Straw<(), Vec<Trade>, AdapterError> {
     sipper(async move |mut progress| {
+        let exchange = ticker_info.exchange();
         let mut latest_trade_t = from_time;
 
         while latest_trade_t < to_time {
-            match binance::fetch_trades(ticker_info, latest_trade_t, data_path.clone()).await {
+            match exchange
+                .fetch_trades(ticker_info, latest_trade_t, to_time, data_path.clone())
+                .await
+            {
                 Ok(batch) => {
                     if batch.is_empty() {
                         break;
@@ -1407,3 +2411,36 @@ pub fn kline_subscription(
         }
     }
 }
+
+/// Recent candles of every ticker the window's kline panes stream, once per ticker,
+/// only gathered when a mini charts pane is there to show them
+fn mini_series(panes: &pane_grid::State<pane::State>) -> Vec<MiniSeries> {
+    let has_mini_charts = panes
+        .iter()
+        .any(|(_, state)| matches!(state.content, pane::Content::MiniCharts { .. }));
+    if !has_mini_charts {
+        return vec![];
+    }
+
+    let mut series: Vec<MiniSeries> = vec![];
+    for (pane, state) in panes.iter() {
+        let pane::Content::Kline {
+            chart: Some(chart), ..
+        } = &state.content
+        else {
+            continue;
+        };
+
+        let ticker_info = chart.ticker_info();
+        if series.iter().any(|s| s.ticker_info == ticker_info) {
+            continue;
+        }
+
+        series.push(MiniSeries {
+            pane: *pane,
+            ticker_info,
+            klines: chart.recent_klines(panel::mini_charts::CANDLES),
+        });
+    }
+    series
+}
@@ -1,41 +1,68 @@
 use crate::{
-    chart::{self, comparison::ComparisonChart, heatmap::HeatmapChart, kline::KlineChart},
+    chart::{
+        self, Chart,
+        comparison::ComparisonChart,
+        correlation::CorrelationMatrix,
+        heatmap::HeatmapChart,
+        kline::{GapRepairOutcome, KlineChart},
+        range::RangeChart,
+    },
     modal::{
         self, ModifierKind,
         pane::{
             Modal,
             mini_tickers_list::MiniPanel,
-            settings::{comparison_cfg_view, heatmap_cfg_view, kline_cfg_view},
+            settings::{
+                comparison_cfg_view, correlation_cfg_view, heatmap_cfg_view, kline_cfg_view,
+                range_cfg_view, spread_cfg_view, statistics_cfg_view,
+            },
             stack_modal,
         },
     },
     screen::dashboard::{
-        panel::{self, ladder::Ladder, timeandsales::TimeAndSales},
+        panel::{
+            self, ladder::Ladder, mini_charts::MiniSeries, spread::SpreadChart,
+            statistics::StatisticsPanel, timeandsales::TimeAndSales,
+        },
         tickers_table::TickersTable,
     },
     style::{self, Icon, icon_text},
-    widget::{self, button_with_tooltip, column_drag, link_group_button, toast::Toast},
+    widget::{
+        self, button_with_tooltip, column_drag, export::ExportScale, link_group_button,
+        toast::Toast,
+    },
     window::{self, Window},
 };
 use data::{
     UserTimezone,
+    aggr::time::NpocSnapshot,
     chart::{
         Basis, ViewConfig,
         indicator::{HeatmapIndicator, Indicator, KlineIndicator, UiIndicator},
+        kline::{KlineStudy, NPoc},
+    },
+    config::{ToastConfig, ToastPosition, ZoomMemory},
+    layout::pane::{
+        ContentKind, DEFAULT_MINI_COLUMNS, LinkGroup, MAX_NOTE_LEN, MINI_COLUMNS_RANGE, PaneSetup,
+        Settings, VisualConfig,
     },
-    layout::pane::{ContentKind, LinkGroup, PaneSetup, Settings, VisualConfig},
 };
 use exchange::{
-    Kline, OpenInterest, StreamPairKind, TickMultiplier, TickerInfo, Timeframe,
+    Kline, OpenInterest, StreamPairKind, TickMultiplier, Ticker, TickerInfo, Timeframe,
     adapter::{MarketKind, PersistStreamKind, ResolvedStream, StreamKind, StreamTicksize},
     fetcher::FetchRequests,
+    util::{Price, PriceStep},
 };
 use iced::{
     Alignment, Element, Length, Renderer, Theme,
     alignment::Vertical,
     padding,
-    widget::{button, center, column, container, pane_grid, pick_list, row, text, tooltip},
+    widget::{
+        button, center, column, container, pane_grid, pick_list, row, text, text_editor,
+        text_input, tooltip,
+    },
 };
+use rustc_hash::FxHashMap;
 use std::time::Instant;
 use rust_i18n::t;
 #[derive(Debug, Clone)]
@@ -44,6 +71,11 @@ pub enum Effect {
     RequestFetch(FetchRequests),
     SwitchTickersInGroup(TickerInfo),
     FocusWidget(iced::widget::Id),
+    ExportPng(ExportScale),
+    CopyToClipboard(String),
+    LoadTemplate(data::ChartTemplate),
+    ExportCsv { file_name: String, contents: String },
+    ImportKlines(Timeframe),
 }
 
 #[derive(Debug, Default, Clone, PartialEq)]
@@ -72,6 +104,7 @@ pub enum Message {
     Restore,
     ReplacePane(pane_grid::Pane),
     Popout,
+    PopoutCopy,
     Merge,
     SwitchLinkGroup(pane_grid::Pane, Option<LinkGroup>),
     VisualConfigChanged(pane_grid::Pane, VisualConfig, bool),
@@ -87,13 +120,35 @@ pub enum Event {
     PanelInteraction(super::panel::Message),
     ToggleIndicator(UiIndicator),
     DeleteNotification(usize),
+    NotificationAction(usize),
     ReorderIndicator(column_drag::DragEvent),
     ClusterKindSelected(data::chart::kline::ClusterKind),
     ClusterScalingSelected(data::chart::kline::ClusterScaling),
+    FootprintCellTextSelected(data::chart::kline::FootprintCellText),
+    DepthLayerChanged(data::chart::kline::DepthLayer),
+    CandleVolumeProfileToggled(bool),
+    /// Adds the timeframe to the pane's tabs or removes it
+    TimeframeTabToggled(Timeframe),
+    /// Shows the timeframe of a tab, from its cached series when it's still loaded
+    TimeframeTabSelected(Timeframe),
+    RemoveKlineStudy(usize),
+    /// Replaces the pane's study of the same type, or adds it if there is none
+    SetKlineStudy(KlineStudy),
     StudyConfigurator(modal::pane::settings::study::StudyMessage),
     StreamModifierChanged(modal::stream::Message),
     ComparisonChartInteraction(super::chart::comparison::Message),
     MiniTickersListInteraction(modal::pane::mini_tickers_list::Message),
+    ExportScaleSelected(ExportScale),
+    ExportPng(ExportScale),
+    NoteEdited(text_editor::Action),
+    NoteSubmitted,
+    OpenTemplates,
+    TemplateNameChanged(String),
+    SaveTemplate,
+    LoadTemplate(usize),
+    ExportCsv,
+    ImportKlines,
+    MiniChartColumnsChanged(usize),
 }
 
 pub struct State {
@@ -105,6 +160,18 @@ pub struct State {
     pub streams: ResolvedStream,
     pub status: Status,
     pub link_group: Option<LinkGroup>,
+    /// Unsaved text of the note editor, reset from `settings.note` each time it opens
+    note_draft: text_editor::Content,
+    /// Set when data or input arrived since the last redraw, idle panes skip the timed redraw
+    dirty: bool,
+    /// A footprint opened without a saved tick size offers one once its first klines arrive
+    suggest_tick_on_load: bool,
+    /// Tick multiplier the pane's "Apply" toast sets
+    suggested_tick: Option<TickMultiplier>,
+    /// Zoom the pane last had on each ticker and chart kind, restored when switching back
+    zoom_memory: FxHashMap<(Ticker, ContentKind), chart::ZoomState>,
+    /// Main window pane a popout was cloned from, which may take its settings when merged back
+    pub popout_origin: Option<uuid::Uuid>,
 }
 
 impl State {
@@ -127,6 +194,39 @@ impl State {
         }
     }
 
+    /// Drops the pane's data and streams, only its serialized form is kept until `load`
+    pub fn unload(&mut self) {
+        if matches!(self.content, Content::Unloaded { .. }) {
+            return;
+        }
+
+        let config = data::Pane::from(&*self);
+        *self = Self {
+            id: self.id,
+            content: Content::Unloaded {
+                config: Box::new(config),
+            },
+            link_group: self.link_group,
+            zoom_memory: std::mem::take(&mut self.zoom_memory),
+            ..Default::default()
+        };
+    }
+
+    /// Rebuilds an unloaded pane, its streams resolve and its data is fetched from the next tick
+    pub fn load(&mut self) {
+        let Content::Unloaded { config } = &self.content else {
+            return;
+        };
+
+        if let Some(state) = crate::layout::pane_state(config.as_ref().clone()) {
+            *self = Self {
+                id: self.id,
+                zoom_memory: std::mem::take(&mut self.zoom_memory),
+                ..state
+            };
+        }
+    }
+
     pub fn stream_pair(&self) -> Option<TickerInfo> {
         self.streams.find_ready_map(|stream| match stream {
             StreamKind::DepthAndTrades { ticker_info, .. }
@@ -156,15 +256,30 @@ impl State {
         &mut self,
         tickers: Vec<TickerInfo>,
         kind: ContentKind,
+        zoom_memory: ZoomMemory,
     ) -> Vec<StreamKind> {
+        self.dirty = true;
+
         if !(self.content.kind() == kind) {
             self.settings.selected_basis = None;
             self.settings.tick_multiply = None;
         }
 
+        self.suggest_tick_on_load = matches!(
+            kind,
+            ContentKind::FootprintChart | ContentKind::HeatmapFootprint
+        ) && self.settings.tick_multiply.is_none();
+
         let base_ticker = tickers[0];
         let prev_base_ticker = self.stream_pair();
 
+        if zoom_memory.per_ticker
+            && let Some(prev) = prev_base_ticker
+            && let Some(zoom) = self.content.zoom_state()
+        {
+            self.zoom_memory.insert((prev.ticker, self.content.kind()), zoom);
+        }
+
         let derived_plan = PaneSetup::new(
             kind,
             base_ticker,
@@ -200,7 +315,7 @@ impl State {
 
                     (content, streams)
                 }
-                ContentKind::FootprintChart => {
+                ContentKind::FootprintChart | ContentKind::HeatmapFootprint => {
                     let content = Content::new_kline(
                         kind,
                         &self.content,
@@ -209,14 +324,21 @@ impl State {
                         derived_plan.tick_size,
                     );
 
+                    let compare = content.compare_overlays();
+
                     let streams = by_basis_default(
                         derived_plan.basis,
                         Timeframe::M5,
                         |tf| {
-                            vec![
+                            if tf.is_sub_minute() {
+                                return vec![depth_stream(&derived_plan)];
+                            }
+                            let mut streams = vec![
                                 depth_stream(&derived_plan),
                                 kline_stream(derived_plan.ticker_info, tf),
-                            ]
+                            ];
+                            streams.extend(compare.tickers().map(|ti| kline_stream(ti, tf)));
+                            streams
                         },
                         || vec![depth_stream(&derived_plan)],
                     );
@@ -235,25 +357,61 @@ impl State {
                         )
                     };
 
+                    let compare = content.compare_overlays();
+
+                    // tick and second based candles are built from the trades of the depth stream
+                    let trades_only = || {
+                        let depth_aggr = derived_plan
+                            .ticker_info
+                            .exchange()
+                            .stream_ticksize(None, TickMultiplier(50));
+                        let temp = PaneSetup {
+                            depth_aggr,
+                            ..derived_plan
+                        };
+                        vec![depth_stream(&temp)]
+                    };
+
                     let streams = by_basis_default(
                         derived_plan.basis,
                         Timeframe::M15,
-                        |tf| vec![kline_stream(derived_plan.ticker_info, tf)],
-                        || {
-                            let depth_aggr = derived_plan
-                                .ticker_info
-                                .exchange()
-                                .stream_ticksize(None, TickMultiplier(50));
-                            let temp = PaneSetup {
-                                depth_aggr,
-                                ..derived_plan
-                            };
-                            vec![depth_stream(&temp)]
+                        |tf| {
+                            if tf.is_sub_minute() {
+                                return trades_only();
+                            }
+                            let mut streams = vec![kline_stream(derived_plan.ticker_info, tf)];
+                            streams.extend(compare.tickers().map(|ti| kline_stream(ti, tf)));
+                            streams
                         },
+                        trades_only,
                     );
 
                     (content, streams)
                 }
+                ContentKind::RangeChart => {
+                    let config = self
+                        .settings
+                        .visual_config
+                        .clone()
+                        .and_then(|cfg| cfg.range());
+                    let layout = match &self.content {
+                        Content::Range { chart, layout } => {
+                            chart.as_ref().map_or(layout.clone(), |c| c.chart_layout())
+                        }
+                        _ => ViewConfig::default(),
+                    };
+                    let content = Content::Range {
+                        chart: Some(RangeChart::new(
+                            layout.clone(),
+                            derived_plan.tick_size,
+                            derived_plan.ticker_info,
+                            config,
+                        )),
+                        layout,
+                    };
+
+                    (content, vec![depth_stream(&derived_plan)])
+                }
                 ContentKind::TimeAndSales => {
                     let config = self
                         .settings
@@ -286,6 +444,27 @@ impl State {
 
                     (content, vec![depth_stream(&derived_plan)])
                 }
+                ContentKind::Statistics => {
+                    let config = self
+                        .settings
+                        .visual_config
+                        .clone()
+                        .and_then(|cfg| cfg.statistics());
+                    let content = Content::Statistics(Some(StatisticsPanel::new(config)));
+
+                    (content, vec![depth_stream(&derived_plan)])
+                }
+                ContentKind::SpreadChart => {
+                    let config = self
+                        .settings
+                        .visual_config
+                        .clone()
+                        .and_then(|cfg| cfg.spread());
+                    let tick_size = derived_plan.ticker_info.min_ticksize.into();
+                    let content = Content::Spread(Some(SpreadChart::new(config, tick_size)));
+
+                    (content, vec![depth_stream(&derived_plan)])
+                }
                 ContentKind::ComparisonChart => {
                     let config = self
                         .settings
@@ -311,6 +490,40 @@ impl State {
 
                     (content, streams)
                 }
+                ContentKind::CorrelationMatrix => {
+                    let config = self
+                        .settings
+                        .visual_config
+                        .clone()
+                        .and_then(|cfg| cfg.correlation());
+                    // closes are paired by bar time, so a tick basis falls back to the default
+                    let timeframe = match derived_plan.basis {
+                        Some(Basis::Time(tf)) => tf,
+                        Some(Basis::Tick(_)) | None => Timeframe::M15,
+                    };
+                    self.settings.selected_basis = Some(Basis::Time(timeframe));
+                    let content = Content::Correlation(Some(CorrelationMatrix::new(
+                        timeframe,
+                        &tickers,
+                        config,
+                    )));
+
+                    let streams = tickers
+                        .iter()
+                        .copied()
+                        .map(|ti| kline_stream(ti, timeframe))
+                        .collect();
+
+                    (content, streams)
+                }
+                ContentKind::MiniCharts => {
+                    let columns = match self.content {
+                        Content::MiniCharts { columns } => columns,
+                        _ => DEFAULT_MINI_COLUMNS,
+                    };
+
+                    (Content::MiniCharts { columns }, vec![])
+                }
                 ContentKind::Starter => unreachable!(),
             }
         };
@@ -318,10 +531,75 @@ impl State {
         self.content = content;
         self.streams = ResolvedStream::Ready(streams.clone());
 
+        if zoom_memory.per_ticker
+            && let Some(zoom) = self.zoom_memory.get(&(base_ticker.ticker, kind))
+        {
+            self.content.restore_zoom(*zoom);
+        }
+
         streams
     }
 
+    /// Starts refetching the kline gaps of a time based kline chart
+    pub fn repair_kline_gaps(&mut self) -> Option<Action> {
+        let Content::Kline {
+            chart: Some(chart), ..
+        } = &mut self.content
+        else {
+            self.notifications
+                .push(Toast::warn("Gap repair needs a kline chart"));
+            return None;
+        };
+
+        match chart.basis() {
+            Basis::Tick(_) => {
+                self.notifications
+                    .push(Toast::warn("Gap repair needs a time based chart"));
+                return None;
+            }
+            Basis::Time(tf) if tf.is_sub_minute() => {
+                self.notifications.push(Toast::warn(
+                    "Second based charts are built from live trades, there is nothing to refetch",
+                ));
+                return None;
+            }
+            Basis::Time(_) => {}
+        }
+        if let Some((done, total)) = chart.gap_repair_progress() {
+            self.notifications.push(Toast::info(format!(
+                "Gap repair already running, {done}/{total} done"
+            )));
+            return None;
+        }
+
+        match chart.start_gap_repair() {
+            0 => {
+                self.notifications.push(Toast::info("No kline gaps found"));
+                None
+            }
+            queued => {
+                log::info!("Repairing kline gaps with {queued} requests");
+                chart.gap_repair_task().map(Action::Chart)
+            }
+        }
+    }
+
+    /// Stops a running gap repair after one of its requests failed
+    pub fn abort_gap_repair(&mut self) {
+        if let Content::Kline {
+            chart: Some(chart), ..
+        } = &mut self.content
+            && let Some((done, total)) = chart.abort_gap_repair()
+        {
+            self.notifications.push(Toast::warn(format!(
+                "Gap repair stopped, {done}/{total} requests done"
+            )));
+        }
+    }
+
     pub fn insert_hist_oi(&mut self, req_id: Option<uuid::Uuid>, oi: &[OpenInterest]) {
+        self.dirty = true;
+
         match &mut self.content {
             Content::Kline { chart, .. } => {
                 let Some(chart) = chart else {
@@ -335,6 +613,37 @@ impl State {
         }
     }
 
+    /// Fills a time based kline chart with klines read from a file,
+    /// `false` when the pane holds no such chart
+    pub fn insert_imported_klines(&mut self, klines: &[Kline]) -> bool {
+        self.dirty = true;
+
+        match &mut self.content {
+            Content::Kline {
+                chart: Some(chart), ..
+            } => chart.insert_imported_klines(klines),
+            _ => false,
+        }
+    }
+
+    pub fn take_npoc_job(&mut self) -> Option<Box<NpocSnapshot>> {
+        match &mut self.content {
+            Content::Kline {
+                chart: Some(chart), ..
+            } => chart.take_npoc_job(),
+            _ => None,
+        }
+    }
+
+    pub fn apply_npoc_statuses(&mut self, statuses: &[(u64, Price, NPoc)]) {
+        if let Content::Kline {
+            chart: Some(chart), ..
+        } = &mut self.content
+        {
+            chart.apply_npoc_statuses(statuses);
+        }
+    }
+
     pub fn insert_hist_klines(
         &mut self,
         req_id: Option<uuid::Uuid>,
@@ -342,6 +651,8 @@ impl State {
         ticker_info: TickerInfo,
         klines: &[Kline],
     ) {
+        self.dirty = true;
+
         match &mut self.content {
             Content::Kline {
                 chart, indicators, ..
@@ -350,6 +661,13 @@ impl State {
                     panic!("chart wasn't initialized when inserting klines");
                 };
 
+                if chart.compare_tickers().contains(&ticker_info) {
+                    if chart.basis() == Basis::Time(timeframe) {
+                        chart.insert_compare_klines(req_id, &ticker_info, klines);
+                    }
+                    return;
+                }
+
                 if let Some(id) = req_id {
                     if chart.basis() != Basis::Time(timeframe) {
                         log::warn!(
@@ -360,10 +678,64 @@ impl State {
                         return;
                     }
                     chart.insert_hist_klines(id, klines);
+
+                    if let Some(outcome) = chart.take_gap_repair_outcome() {
+                        self.notifications.push(gap_repair_toast(outcome));
+                    }
                 } else {
-                    let (raw_trades, tick_size) = (chart.raw_trades(), chart.tick_size());
+                    let (raw_trades, mut tick_size) = (chart.raw_trades(), chart.tick_size());
                     let layout = chart.chart_layout();
 
+                    let is_footprint =
+                        matches!(chart.kind, data::chart::KlineChartKind::Footprint { .. });
+                    if self.settings.auto_tick
+                        && is_footprint
+                        && let Some(latest) = klines.last()
+                    {
+                        let tm = data::chart::suggest_tick_multiplier(
+                            latest.close,
+                            PriceStep::from_f32(ticker_info.min_ticksize.into()),
+                            Basis::Time(timeframe),
+                        );
+                        self.settings.tick_multiply = Some(tm);
+                        tick_size = tm.multiply_with_min_tick_size(ticker_info);
+                    }
+
+                    if is_footprint
+                        && std::mem::take(&mut self.suggest_tick_on_load)
+                        && !self.settings.auto_tick
+                        && let Some(latest) = klines.last()
+                    {
+                        let min_tick = f32::from(ticker_info.min_ticksize);
+                        let suggested = data::chart::kline::suggest_tick_size(
+                            latest.close.to_f32(),
+                            min_tick,
+                            data::chart::kline::SUGGESTED_TICK_ROWS,
+                        );
+                        let tm = TickMultiplier(
+                            (suggested / min_tick).round().min(f32::from(u16::MAX)) as u16,
+                        );
+
+                        if self.settings.tick_multiply != Some(tm) {
+                            self.suggested_tick = Some(tm);
+                            self.notifications.push(
+                                Toast::info(format!(
+                                    "Tick size {} suits the current price of {}",
+                                    Price::from_f32(suggested).to_string(ticker_info.min_ticksize),
+                                    ticker_info.ticker.display_symbol_and_type().0,
+                                ))
+                                .with_action("Apply"),
+                            );
+                        }
+                    }
+                    let compare = chart.compare_overlays();
+                    let cfg = self
+                        .settings
+                        .visual_config
+                        .clone()
+                        .and_then(|cfg| cfg.kline())
+                        .unwrap_or_default();
+
                     *chart = KlineChart::new(
                         layout,
                         Basis::Time(timeframe),
@@ -374,6 +746,10 @@ impl State {
                         ticker_info,
                         chart.kind(),
                     );
+                    chart.set_compare_overlays(compare);
+                    chart.apply_config(&cfg);
+                    chart.set_timeframe_tabs(&self.settings.timeframe_tabs);
+                    chart.set_kline_studies(&self.settings.kline_studies);
                 }
             }
             Content::Comparison(chart) => {
@@ -399,12 +775,98 @@ impl State {
                     );
                 }
             }
+            Content::Correlation(matrix) => {
+                let Some(matrix) = matrix else {
+                    log::error!("Correlation matrix wasn't initialized when inserting klines");
+                    return;
+                };
+
+                if let Some(id) = req_id {
+                    if matrix.timeframe != timeframe {
+                        log::warn!(
+                            "Ignoring stale kline fetch for timeframe {:?}; matrix timeframe = {:?}",
+                            timeframe,
+                            matrix.timeframe
+                        );
+                        return;
+                    }
+                    matrix.insert_history(id, ticker_info, klines);
+                } else {
+                    *matrix = CorrelationMatrix::new(
+                        timeframe,
+                        &[ticker_info],
+                        Some(matrix.config),
+                    );
+                }
+            }
             _ => {
                 log::error!("pane content not candlestick or footprint");
             }
         }
     }
 
+    fn set_tick_multiplier(&mut self, tm: TickMultiplier) -> Option<Effect> {
+        self.settings.tick_multiply = Some(tm);
+
+        if let Some(ticker) = self.stream_pair() {
+            match &mut self.content {
+                Content::Kline { chart: Some(c), .. } => {
+                    c.change_tick_size(tm.multiply_with_min_tick_size(ticker));
+                    c.reset_request_handler();
+                }
+                Content::Heatmap { chart: Some(c), .. } => {
+                    c.change_tick_size(tm.multiply_with_min_tick_size(ticker));
+                }
+                Content::Ladder(Some(p)) => {
+                    p.set_tick_size(tm.multiply_with_min_tick_size(ticker));
+                }
+                _ => {}
+            }
+        }
+
+        let is_client = self
+            .stream_pair()
+            .map(|ti| ti.exchange().is_depth_client_aggr())
+            .unwrap_or(false);
+
+        if let Some(mut it) = self.streams.ready_iter_mut() {
+            for s in &mut it {
+                if let StreamKind::DepthAndTrades { depth_aggr, .. } = s {
+                    *depth_aggr = if is_client {
+                        StreamTicksize::Client
+                    } else {
+                        StreamTicksize::ServerSide(tm)
+                    };
+                }
+            }
+        }
+
+        (!is_client).then_some(Effect::RefreshStreams)
+    }
+
+    /// Footprint tick multiplier suggested for the latest price, `None` until data arrives
+    fn auto_tick_multiplier(&self, basis: Basis) -> Option<TickMultiplier> {
+        let Content::Kline {
+            chart: Some(chart), ..
+        } = &self.content
+        else {
+            return None;
+        };
+
+        if !matches!(chart.kind, data::chart::KlineChartKind::Footprint { .. }) {
+            return None;
+        }
+
+        let ticker = self.stream_pair()?;
+        let price = chart.latest_price()?;
+
+        Some(data::chart::suggest_tick_multiplier(
+            price,
+            PriceStep::from_f32(ticker.min_ticksize.into()),
+            basis,
+        ))
+    }
+
     fn has_stream(&self) -> bool {
         match &self.streams {
             ResolvedStream::Ready(streams) => !streams.is_empty(),
@@ -412,6 +874,35 @@ impl State {
         }
     }
 
+    fn has_chart_view(&self) -> bool {
+        matches!(
+            &self.content,
+            Content::Kline { chart: Some(_), .. }
+                | Content::Heatmap { chart: Some(_), .. }
+                | Content::Range { chart: Some(_), .. }
+        )
+    }
+
+    /// The chart alone, canvas with its axis labels and studies, without the pane's controls
+    pub fn chart_view(&self, timezone: UserTimezone) -> Option<Element<'_, chart::Message>> {
+        match &self.content {
+            Content::Kline {
+                chart: Some(chart),
+                indicators,
+                ..
+            } => Some(chart::view(chart, indicators, timezone)),
+            Content::Heatmap {
+                chart: Some(chart),
+                indicators,
+                ..
+            } => Some(chart::view(chart, indicators, timezone)),
+            Content::Range {
+                chart: Some(chart), ..
+            } => Some(chart::view(chart, &[], timezone)),
+            _ => None,
+        }
+    }
+
     pub fn view<'a>(
         &'a self,
         id: pane_grid::Pane,
@@ -422,6 +913,8 @@ impl State {
         main_window: &'a Window,
         timezone: UserTimezone,
         tickers_table: &'a TickersTable,
+        hybrid_weight: f32,
+        mini_series: &[MiniSeries],
     ) -> pane_grid::Content<'a, Message, Theme, Renderer> {
         let mut stream_info_element = if Content::Starter == self.content {
             row![]
@@ -468,7 +961,9 @@ impl State {
                 .padding([4, 10]);
 
             stream_info_element = stream_info_element.push(tickers_list_btn);
-        } else if !matches!(self.content, Content::Starter) && !self.has_stream() {
+        } else if !matches!(self.content, Content::Starter | Content::MiniCharts { .. })
+            && !self.has_stream()
+        {
             let content = row![text("Choose a ticker").size(13)]
                 .align_y(Alignment::Center)
                 .spacing(4);
@@ -490,6 +985,18 @@ impl State {
             stream_info_element = stream_info_element.push(tickers_list_btn);
         }
 
+        if let Some(note) = self.settings.note.as_deref() {
+            let note_btn = button(icon_text(Icon::Clone, 12))
+                .on_press(Message::PaneEvent(id, Event::ShowModal(Modal::Note)))
+                .style(|theme, status| style::button::transparent(theme, status, false));
+
+            stream_info_element = stream_info_element.push(widget::tooltip(
+                note_btn,
+                Some(note),
+                tooltip::Position::Bottom,
+            ));
+        }
+
         let modifier: Option<modal::stream::Modifier> = self.modal.clone().and_then(|m| {
             if let Modal::StreamModifier(modifier) = m {
                 Some(modifier)
@@ -540,7 +1047,7 @@ impl State {
                         .spacing(12),
                     ),
                     &self.notifications,
-                    Alignment::End,
+                    pane_toast_cfg(),
                     move |msg| Message::PaneEvent(id, Event::DeleteNotification(msg)),
                 )
                 .into();
@@ -596,26 +1103,32 @@ impl State {
                     )
                 }
             }
-            Content::TimeAndSales(panel) => {
-                if let Some(panel) = panel {
-                    let base = panel::view(panel, timezone).map(move |message| {
-                        Message::PaneEvent(id, Event::PanelInteraction(message))
-                    });
+            Content::Correlation(matrix) => {
+                if let Some(m) = matrix {
+                    let selected_basis = self
+                        .settings
+                        .selected_basis
+                        .unwrap_or(Timeframe::M15.into());
+                    let kind = ModifierKind::Comparison(selected_basis);
 
-                    let settings_modal =
-                        || modal::pane::settings::timesales_cfg_view(panel.config, id);
+                    let modifiers =
+                        row![basis_modifier(id, selected_basis, modifier, kind),].spacing(4);
+
+                    stream_info_element = stream_info_element.push(modifiers);
+
+                    let settings_modal = || correlation_cfg_view(m.config, id);
 
                     self.compose_stack_view(
-                        base,
+                        m.view(),
                         id,
                         None,
                         compact_controls,
                         settings_modal,
-                        None,
+                        Some(m.selected_tickers()),
                         tickers_table,
                     )
                 } else {
-                    let base = uninitialized_base(ContentKind::TimeAndSales);
+                    let base = uninitialized_base(ContentKind::CorrelationMatrix);
                     self.compose_stack_view(
                         base,
                         id,
@@ -627,36 +1140,17 @@ impl State {
                     )
                 }
             }
-            Content::Ladder(panel) => {
-                if let Some(panel) = panel {
-                    let basis = self
-                        .settings
-                        .selected_basis
-                        .unwrap_or(Basis::default_heatmap_time(self.stream_pair()));
-                    let tick_multiply = self.settings.tick_multiply.unwrap_or(TickMultiplier(1));
-
-                    let kind = ModifierKind::Orderbook(basis, tick_multiply);
-
-                    let base_ticksize = tick_multiply.base(panel.tick_size());
-                    let exchange = self.stream_pair().map(|ti| ti.ticker.exchange);
-
-                    let modifiers = ticksize_modifier(
-                        id,
-                        base_ticksize,
-                        tick_multiply,
-                        modifier,
-                        kind,
-                        exchange,
-                    );
-
-                    stream_info_element = stream_info_element.push(modifiers);
+            Content::Range { chart, .. } => {
+                if let Some(chart) = chart {
+                    if let Some(mode) = chart::auto_scroll(chart) {
+                        stream_info_element =
+                            stream_info_element.push(auto_scroll_picker(id, mode));
+                    }
 
-                    let base = panel::view(panel, timezone).map(move |message| {
-                        Message::PaneEvent(id, Event::PanelInteraction(message))
+                    let base = chart::view(chart, &[], timezone).map(move |message| {
+                        Message::PaneEvent(id, Event::ChartInteraction(message))
                     });
-
-                    let settings_modal =
-                        || modal::pane::settings::ladder_cfg_view(panel.config, id);
+                    let settings_modal = || range_cfg_view(chart.visual_config(), id);
 
                     self.compose_stack_view(
                         base,
@@ -668,7 +1162,7 @@ impl State {
                         tickers_table,
                     )
                 } else {
-                    let base = uninitialized_base(ContentKind::Ladder);
+                    let base = uninitialized_base(ContentKind::RangeChart);
                     self.compose_stack_view(
                         base,
                         id,
@@ -680,13 +1174,178 @@ impl State {
                     )
                 }
             }
-            Content::Heatmap {
-                chart, indicators, ..
-            } => {
-                if let Some(chart) = chart {
-                    let ticker_info = self.stream_pair();
-                    let exchange = ticker_info.as_ref().map(|info| info.ticker.exchange);
-
+            Content::TimeAndSales(panel) => {
+                if let Some(panel) = panel {
+                    let base = panel::view(panel, timezone).map(move |message| {
+                        Message::PaneEvent(id, Event::PanelInteraction(message))
+                    });
+
+                    let settings_modal =
+                        || modal::pane::settings::timesales_cfg_view(panel.config, id);
+
+                    self.compose_stack_view(
+                        base,
+                        id,
+                        None,
+                        compact_controls,
+                        settings_modal,
+                        None,
+                        tickers_table,
+                    )
+                } else {
+                    let base = uninitialized_base(ContentKind::TimeAndSales);
+                    self.compose_stack_view(
+                        base,
+                        id,
+                        None,
+                        compact_controls,
+                        || column![].into(),
+                        None,
+                        tickers_table,
+                    )
+                }
+            }
+            Content::Statistics(panel) => {
+                if let Some(panel) = panel {
+                    stream_info_element =
+                        stream_info_element.push(stats_window_slider(id, panel.config));
+
+                    let settings_modal = || statistics_cfg_view(panel.config, id);
+
+                    self.compose_stack_view(
+                        panel.view(),
+                        id,
+                        None,
+                        compact_controls,
+                        settings_modal,
+                        None,
+                        tickers_table,
+                    )
+                } else {
+                    let base = uninitialized_base(ContentKind::Statistics);
+                    self.compose_stack_view(
+                        base,
+                        id,
+                        None,
+                        compact_controls,
+                        || column![].into(),
+                        None,
+                        tickers_table,
+                    )
+                }
+            }
+            Content::Spread(chart) => {
+                if let Some(chart) = chart {
+                    let settings_modal = || spread_cfg_view(chart.config, id);
+
+                    self.compose_stack_view(
+                        chart.view(),
+                        id,
+                        None,
+                        compact_controls,
+                        settings_modal,
+                        None,
+                        tickers_table,
+                    )
+                } else {
+                    let base = uninitialized_base(ContentKind::SpreadChart);
+                    self.compose_stack_view(
+                        base,
+                        id,
+                        None,
+                        compact_controls,
+                        || column![].into(),
+                        None,
+                        tickers_table,
+                    )
+                }
+            }
+            Content::MiniCharts { columns } => {
+                let columns_picker = pick_list(
+                    MINI_COLUMNS_RANGE.collect::<Vec<_>>(),
+                    Some(*columns),
+                    move |columns| Message::PaneEvent(id, Event::MiniChartColumnsChanged(columns)),
+                )
+                .text_size(12);
+                stream_info_element = stream_info_element.push(columns_picker);
+
+                let base = panel::mini_charts::view(mini_series, *columns, Message::PaneClicked);
+
+                self.compose_stack_view(
+                    base,
+                    id,
+                    None,
+                    compact_controls,
+                    || column![].into(),
+                    None,
+                    tickers_table,
+                )
+            }
+            Content::Ladder(panel) => {
+                if let Some(panel) = panel {
+                    let basis = self
+                        .settings
+                        .selected_basis
+                        .unwrap_or(Basis::default_heatmap_time(self.stream_pair()));
+                    let tick_multiply = self.settings.tick_multiply.unwrap_or(TickMultiplier(1));
+
+                    let kind = ModifierKind::Orderbook(basis, tick_multiply);
+
+                    let base_ticksize = tick_multiply.base(panel.tick_size());
+                    let exchange = self.stream_pair().map(|ti| ti.ticker.exchange);
+
+                    let modifiers = ticksize_modifier(
+                        id,
+                        base_ticksize,
+                        tick_multiply,
+                        false,
+                        modifier,
+                        kind,
+                        exchange,
+                    );
+
+                    stream_info_element = stream_info_element.push(modifiers);
+
+                    if let Some(imbalance) = panel.imbalance() {
+                        stream_info_element = stream_info_element.push(imbalance_gauge(imbalance));
+                    }
+
+                    let base = panel::view(panel, timezone).map(move |message| {
+                        Message::PaneEvent(id, Event::PanelInteraction(message))
+                    });
+
+                    let settings_modal =
+                        || modal::pane::settings::ladder_cfg_view(panel.config, id);
+
+                    self.compose_stack_view(
+                        base,
+                        id,
+                        None,
+                        compact_controls,
+                        settings_modal,
+                        None,
+                        tickers_table,
+                    )
+                } else {
+                    let base = uninitialized_base(ContentKind::Ladder);
+                    self.compose_stack_view(
+                        base,
+                        id,
+                        None,
+                        compact_controls,
+                        || column![].into(),
+                        None,
+                        tickers_table,
+                    )
+                }
+            }
+            Content::Heatmap {
+                chart, indicators, ..
+            } => {
+                if let Some(chart) = chart {
+                    let ticker_info = self.stream_pair();
+                    let exchange = ticker_info.as_ref().map(|info| info.ticker.exchange);
+
                     let basis = self
                         .settings
                         .selected_basis
@@ -702,6 +1361,7 @@ impl State {
                             id,
                             base_ticksize,
                             tick_multiply,
+                            false,
                             modifier,
                             kind,
                             exchange
@@ -783,6 +1443,7 @@ impl State {
                                     id,
                                     base_ticksize,
                                     tick_multiply,
+                                    self.settings.auto_tick,
                                     modifier,
                                     kind,
                                     exchange
@@ -792,31 +1453,48 @@ impl State {
 
                             stream_info_element = stream_info_element.push(modifiers);
                         }
-                        data::chart::KlineChartKind::Candles => {
+                        data::chart::KlineChartKind::Candles { .. } => {
                             let selected_basis = self
                                 .settings
                                 .selected_basis
                                 .unwrap_or(Timeframe::M15.into());
                             let kind = ModifierKind::Candlestick(selected_basis);
 
-                            let modifiers =
+                            let mut modifiers =
                                 row![basis_modifier(id, selected_basis, modifier, kind),]
                                     .spacing(4);
+                            if self.settings.timeframe_tabs.len() > 1 {
+                                let tabs = &self.settings.timeframe_tabs;
+                                modifiers =
+                                    modifiers.push(timeframe_tab_strip(id, tabs, chart.basis()));
+                            }
 
-                            stream_info_element = stream_info_element.push(modifiers);
+                            stream_info_element = stream_info_element
+                                .push(modifiers)
+                                .push(minimap_toggle(id, self.kline_config()));
                         }
                     }
 
+                    if let Some(mode) = chart::auto_scroll(chart) {
+                        stream_info_element =
+                            stream_info_element.push(auto_scroll_picker(id, mode));
+                    }
+
                     let base = chart::view(chart, indicators, timezone).map(move |message| {
                         Message::PaneEvent(id, Event::ChartInteraction(message))
                     });
                     let settings_modal = || {
                         kline_cfg_view(
                             chart.study_configurator(),
-                            data::chart::kline::Config {},
+                            self.kline_config(),
                             chart_kind,
+                            &self.settings.kline_studies,
+                            &self.settings.timeframe_tabs,
                             id,
                             chart.basis(),
+                            hybrid_weight,
+                            (chart.price_rounding(), chart.price_rounding_choices()),
+                            timezone,
                         )
                     };
 
@@ -837,12 +1515,18 @@ impl State {
                         indicator_modal,
                         compact_controls,
                         settings_modal,
-                        None,
+                        Some(chart.compare_tickers()),
                         tickers_table,
                     )
                 } else {
                     let content_kind = match chart_kind {
-                        data::chart::KlineChartKind::Candles => ContentKind::CandlestickChart,
+                        data::chart::KlineChartKind::Candles { .. } => {
+                            ContentKind::CandlestickChart
+                        }
+                        data::chart::KlineChartKind::Footprint {
+                            depth_layer: Some(_),
+                            ..
+                        } => ContentKind::HeatmapFootprint,
                         data::chart::KlineChartKind::Footprint { .. } => {
                             ContentKind::FootprintChart
                         }
@@ -859,12 +1543,27 @@ impl State {
                     )
                 }
             }
+            Content::Unloaded { .. } => self.compose_stack_view(
+                center(text(t!("chart.loading")).size(16)).into(),
+                id,
+                None,
+                compact_controls,
+                || column![].into(),
+                None,
+                tickers_table,
+            ),
         };
 
         match &self.status {
             Status::Loading(exchange::fetcher::InfoKind::FetchingKlines) => {
                 stream_info_element = stream_info_element.push(text("Fetching Klines..."));
             }
+            Status::Loading(exchange::fetcher::InfoKind::FetchingKlineHistory(progress)) => {
+                stream_info_element = stream_info_element.push(text(format!(
+                    "Fetching Klines... {}/{}",
+                    progress.fetched, progress.total
+                )));
+            }
             Status::Loading(exchange::fetcher::InfoKind::FetchingTrades(count)) => {
                 stream_info_element =
                     stream_info_element.push(text(format!("Fetching Trades... {count} fetched")));
@@ -878,6 +1577,30 @@ impl State {
             Status::Ready => {}
         }
 
+        if let Content::Kline {
+            chart: Some(chart), ..
+        } = &self.content
+            && let Some((done, total)) = chart.gap_repair_progress()
+        {
+            stream_info_element =
+                stream_info_element.push(text(format!("Repairing gaps {done}/{total}")));
+        }
+
+        let body: Element<'_, Message> =
+            if let Status::Loading(exchange::fetcher::InfoKind::FetchingKlineHistory(progress)) =
+                &self.status
+            {
+                column![
+                    container(body).height(Length::Fill),
+                    iced::widget::progress_bar(0.0..=1.0, progress.ratio()).girth(2),
+                ]
+                .into()
+            } else {
+                body
+            };
+
+        let body = container(body).id(widget::export::pane_container_id(self.unique_id()));
+
         let content = pane_grid::Content::new(body)
             .style(move |theme| style::pane_background(theme, is_focused));
 
@@ -908,13 +1631,17 @@ impl State {
             }
         };
 
-        let title_bar = pane_grid::TitleBar::new(
+        let title_bar = pane_grid::TitleBar::new(widget::on_right_click(
             stream_info_element
                 .padding(padding::left(4).top(1))
                 .align_y(Vertical::Center)
                 .spacing(8)
                 .height(Length::Fixed(32.0)),
-        )
+            move |position| {
+                let position = iced::Point::new(position.x, 0.0);
+                Message::PaneEvent(id, Event::ShowModal(Modal::ContextMenu(position)))
+            },
+        ))
         .controls(controls)
         .style(style::pane_title_bar);
 
@@ -926,6 +1653,8 @@ impl State {
     }
 
     pub fn update(&mut self, msg: Event) -> Option<Effect> {
+        self.dirty = true;
+
         match msg {
             Event::ShowModal(requested_modal) => {
                 return self.show_modal_with_focus(requested_modal);
@@ -933,11 +1662,123 @@ impl State {
             Event::HideModal => {
                 self.modal = None;
             }
+            Event::ExportScaleSelected(scale) => {
+                self.modal = Some(Modal::Export(scale));
+            }
+            Event::ExportPng(scale) => {
+                self.modal = None;
+                return Some(Effect::ExportPng(scale));
+            }
+            Event::NoteEdited(action) => {
+                let grows = matches!(
+                    &action,
+                    text_editor::Action::Edit(edit)
+                        if !matches!(edit, text_editor::Edit::Backspace | text_editor::Edit::Delete)
+                );
+                if grows && self.note_draft.text().chars().count() >= MAX_NOTE_LEN {
+                    return None;
+                }
+
+                self.note_draft.perform(action);
+
+                // a paste can still run past the limit
+                let draft = self.note_draft.text();
+                if draft.chars().count() > MAX_NOTE_LEN {
+                    let truncated: String = draft.chars().take(MAX_NOTE_LEN).collect();
+                    self.note_draft = text_editor::Content::with_text(&truncated);
+                }
+            }
+            Event::NoteSubmitted => {
+                if matches!(self.modal, Some(Modal::Note)) {
+                    self.modal = None;
+                    self.save_note_draft();
+                }
+            }
+            Event::OpenTemplates => {
+                if matches!(self.modal, Some(Modal::Templates { .. })) {
+                    self.modal = None;
+                } else {
+                    match data::list_templates() {
+                        Ok(saved) => {
+                            self.modal = Some(Modal::Templates {
+                                name: String::new(),
+                                saved,
+                            });
+                        }
+                        Err(e) => self
+                            .notifications
+                            .push(Toast::error(format!("Failed to read templates: {e}"))),
+                    }
+                }
+            }
+            Event::TemplateNameChanged(draft) => {
+                if let Some(Modal::Templates { name, .. }) = &mut self.modal {
+                    *name = draft;
+                }
+            }
+            Event::SaveTemplate => {
+                let template = match &self.modal {
+                    Some(Modal::Templates { name, .. }) => self.template(name.trim()),
+                    _ => None,
+                };
+                if let Some(template) = template
+                    && let Some(Modal::Templates { name, saved }) = &mut self.modal
+                {
+                    match data::save_template(&template) {
+                        Ok(()) => {
+                            self.notifications
+                                .push(Toast::info(format!("Saved template \"{}\"", template.name)));
+                            saved.retain(|t| t.file_name() != template.file_name());
+                            saved.push(template);
+                            saved.sort_by(|a, b| a.name.cmp(&b.name));
+                            name.clear();
+                        }
+                        Err(e) => self
+                            .notifications
+                            .push(Toast::error(format!("Failed to save template: {e}"))),
+                    }
+                }
+            }
+            Event::LoadTemplate(index) => {
+                if let Some(Modal::Templates { saved, .. }) = &self.modal
+                    && let Some(template) = saved.get(index).cloned()
+                {
+                    self.modal = None;
+                    return Some(Effect::LoadTemplate(template));
+                }
+            }
+            Event::ExportCsv => {
+                self.modal = None;
+
+                if let Content::Kline { chart: Some(c), .. } = &self.content {
+                    let symbol = self.stream_pair().map_or(String::from("klines"), |ti| {
+                        ti.ticker.display_symbol_and_type().0
+                    });
+
+                    return Some(Effect::ExportCsv {
+                        file_name: format!("{symbol}-{}.csv", c.basis()),
+                        contents: c.klines_csv(),
+                    });
+                }
+            }
+            Event::ImportKlines => {
+                self.modal = None;
+
+                if let Content::Kline { chart: Some(c), .. } = &self.content
+                    && let Basis::Time(timeframe) = c.basis()
+                {
+                    return Some(Effect::ImportKlines(timeframe));
+                }
+            }
             Event::ContentSelected(kind) => {
                 self.content = Content::placeholder(kind);
 
-                if !matches!(kind, ContentKind::Starter) {
+                if kind != ContentKind::Starter {
                     self.streams = ResolvedStream::Waiting(vec![]);
+                }
+
+                // mini charts read the other panes' data, they need no ticker of their own
+                if !matches!(kind, ContentKind::Starter | ContentKind::MiniCharts) {
                     let modal = Modal::MiniTickersList(MiniPanel::new());
 
                     if let Some(effect) = self.show_modal_with_focus(modal) {
@@ -945,15 +1786,46 @@ impl State {
                     }
                 }
             }
-            Event::ChartInteraction(msg) => match &mut self.content {
-                Content::Heatmap { chart: Some(c), .. } => {
-                    super::chart::update(c, &msg);
+            Event::MiniChartColumnsChanged(new_columns) => {
+                if let Content::MiniCharts { columns } = &mut self.content {
+                    *columns = new_columns;
                 }
-                Content::Kline { chart: Some(c), .. } => {
-                    super::chart::update(c, &msg);
+            }
+            Event::ChartInteraction(msg) => {
+                let copied = match &mut self.content {
+                    Content::Heatmap { chart: Some(c), .. } => {
+                        super::chart::update(c, &msg);
+                        super::chart::clipboard_text(c, &msg)
+                    }
+                    Content::Range { chart: Some(c), .. } => {
+                        super::chart::update(c, &msg);
+                        super::chart::clipboard_text(c, &msg)
+                    }
+                    Content::Kline { chart: Some(c), .. } => {
+                        super::chart::update(c, &msg);
+
+                        if let super::chart::Message::AnchorVwap(anchor_time) = msg {
+                            let studies = &mut self.settings.kline_studies;
+                            let already_anchored = studies.iter().any(|study| {
+                                matches!(study, KlineStudy::AnchoredVwap { anchor_time: t, .. }
+                                    if *t == anchor_time)
+                            });
+                            if !already_anchored {
+                                studies.push(KlineStudy::anchored_vwap(anchor_time, studies.len()));
+                            }
+                            c.set_kline_studies(studies);
+                        }
+                        super::chart::clipboard_text(c, &msg)
+                    }
+                    _ => None,
+                };
+
+                if let Some(text) = copied {
+                    self.notifications
+                        .push(Toast::info(format!("Copied {}", text.replace('\t', " "))));
+                    return Some(Effect::CopyToClipboard(text));
                 }
-                _ => {}
-            },
+            }
             Event::PanelInteraction(msg) => match &mut self.content {
                 Content::Ladder(Some(p)) => super::panel::update(p, msg),
                 Content::TimeAndSales(Some(p)) => super::panel::update(p, msg),
@@ -963,9 +1835,19 @@ impl State {
                 self.content.toggle_indicator(ind);
             }
             Event::DeleteNotification(idx) => {
+                if idx < self.notifications.len() && self.notifications.remove(idx).has_action() {
+                    self.suggested_tick = None;
+                }
+            }
+            Event::NotificationAction(idx) => {
                 if idx < self.notifications.len() {
                     self.notifications.remove(idx);
                 }
+
+                if let Some(tm) = self.suggested_tick.take() {
+                    self.settings.auto_tick = false;
+                    return self.set_tick_multiplier(tm);
+                }
             }
             Event::ReorderIndicator(e) => {
                 self.content.reorder_indicators(&e);
@@ -980,6 +1862,24 @@ impl State {
                     *cur = c.kind.clone();
                 }
             }
+            Event::RemoveKlineStudy(index) => {
+                if index < self.settings.kline_studies.len() {
+                    self.settings.kline_studies.remove(index);
+                }
+                if let Content::Kline { chart: Some(c), .. } = &mut self.content {
+                    c.set_kline_studies(&self.settings.kline_studies);
+                }
+            }
+            Event::SetKlineStudy(study) => {
+                let studies = &mut self.settings.kline_studies;
+                match studies.iter_mut().find(|s| s.is_same_type(&study)) {
+                    Some(existing) => *existing = study,
+                    None => studies.push(study),
+                }
+                if let Content::Kline { chart: Some(c), .. } = &mut self.content {
+                    c.set_kline_studies(&self.settings.kline_studies);
+                }
+            }
             Event::ClusterScalingSelected(scaling) => {
                 if let Content::Kline { chart, kind, .. } = &mut self.content
                     && let Some(c) = chart
@@ -988,6 +1888,67 @@ impl State {
                     *kind = c.kind.clone();
                 }
             }
+            Event::FootprintCellTextSelected(cell_text) => {
+                if let Content::Kline { chart, kind, .. } = &mut self.content
+                    && let Some(c) = chart
+                {
+                    c.set_cell_text(cell_text);
+                    *kind = c.kind.clone();
+                }
+            }
+            Event::DepthLayerChanged(layer) => {
+                if let Content::Kline { chart, kind, .. } = &mut self.content
+                    && let Some(c) = chart
+                {
+                    c.set_depth_layer(layer);
+                    *kind = c.kind.clone();
+                }
+            }
+            Event::CandleVolumeProfileToggled(enabled) => {
+                if let Content::Kline { chart, kind, .. } = &mut self.content
+                    && let Some(c) = chart
+                {
+                    c.set_show_volume_profile(enabled);
+                    *kind = c.kind.clone();
+                }
+            }
+            Event::TimeframeTabToggled(timeframe) => {
+                let tabs = &mut self.settings.timeframe_tabs;
+                if let Some(index) = tabs.iter().position(|tf| *tf == timeframe) {
+                    tabs.remove(index);
+                } else {
+                    tabs.push(timeframe);
+                    tabs.sort();
+                }
+
+                if let Content::Kline { chart: Some(c), .. } = &mut self.content {
+                    c.set_timeframe_tabs(tabs);
+                    return Some(Effect::RefreshStreams);
+                }
+            }
+            Event::TimeframeTabSelected(timeframe) => {
+                let base_ticker = self.stream_pair();
+
+                if let Content::Kline { chart: Some(c), .. } = &mut self.content
+                    && let Some(base_ticker) = base_ticker
+                {
+                    self.settings.selected_basis = Some(Basis::Time(timeframe));
+                    self.streams = ResolvedStream::Ready(
+                        std::iter::once(base_ticker)
+                            .chain(c.compare_tickers().iter().copied())
+                            .map(|ticker_info| StreamKind::Kline {
+                                ticker_info,
+                                timeframe,
+                            })
+                            .collect(),
+                    );
+
+                    return Some(match c.switch_timeframe(timeframe) {
+                        Some(chart::Action::RequestFetch(fetch)) => Effect::RequestFetch(fetch),
+                        _ => Effect::RefreshStreams,
+                    });
+                }
+            }
             Event::StudyConfigurator(study_msg) => match study_msg {
                 modal::pane::settings::study::StudyMessage::Footprint(m) => {
                     if let Content::Kline { chart, kind, .. } = &mut self.content
@@ -1017,46 +1978,19 @@ impl State {
                             }
                             modal::stream::Action::TicksizeSelected(tm) => {
                                 modifier.update_kind_with_multiplier(tm);
-                                self.settings.tick_multiply = Some(tm);
-
-                                if let Some(ticker) = self.stream_pair() {
-                                    match &mut self.content {
-                                        Content::Kline { chart: Some(c), .. } => {
-                                            c.change_tick_size(
-                                                tm.multiply_with_min_tick_size(ticker),
-                                            );
-                                            c.reset_request_handler();
-                                        }
-                                        Content::Heatmap { chart: Some(c), .. } => {
-                                            c.change_tick_size(
-                                                tm.multiply_with_min_tick_size(ticker),
-                                            );
-                                        }
-                                        Content::Ladder(Some(p)) => {
-                                            p.set_tick_size(tm.multiply_with_min_tick_size(ticker));
-                                        }
-                                        _ => {}
-                                    }
-                                }
+                                modifier.set_auto_ticksize(false);
+                                self.settings.auto_tick = false;
 
-                                let is_client = self
-                                    .stream_pair()
-                                    .map(|ti| ti.exchange().is_depth_client_aggr())
-                                    .unwrap_or(false);
-
-                                if let Some(mut it) = self.streams.ready_iter_mut() {
-                                    for s in &mut it {
-                                        if let StreamKind::DepthAndTrades { depth_aggr, .. } = s {
-                                            *depth_aggr = if is_client {
-                                                StreamTicksize::Client
-                                            } else {
-                                                StreamTicksize::ServerSide(tm)
-                                            };
-                                        }
-                                    }
-                                }
-                                if !is_client {
-                                    effect = Some(Effect::RefreshStreams);
+                                effect = self.set_tick_multiplier(tm);
+                            }
+                            modal::stream::Action::AutoTicksizeSelected => {
+                                modifier.set_auto_ticksize(true);
+                                self.settings.auto_tick = true;
+
+                                let basis = self.settings.selected_basis;
+                                if let Some(tm) = basis.and_then(|b| self.auto_tick_multiplier(b)) {
+                                    modifier.update_kind_with_multiplier(tm);
+                                    effect = self.set_tick_multiplier(tm);
                                 }
                             }
                             modal::stream::Action::BasisSelected(new_basis) => {
@@ -1064,6 +1998,11 @@ impl State {
                                 self.settings.selected_basis = Some(new_basis);
 
                                 let base_ticker = self.stream_pair();
+                                let auto_tick = if self.settings.auto_tick {
+                                    self.auto_tick_multiplier(new_basis)
+                                } else {
+                                    None
+                                };
 
                                 match &mut self.content {
                                     Content::Heatmap { chart: Some(c), .. } => {
@@ -1097,6 +2036,14 @@ impl State {
                                     }
                                     Content::Kline { chart: Some(c), .. } => {
                                         if let Some(base_ticker) = base_ticker {
+                                            if let Some(tm) = auto_tick {
+                                                modifier.update_kind_with_multiplier(tm);
+                                                self.settings.tick_multiply = Some(tm);
+                                                c.change_tick_size(
+                                                    tm.multiply_with_min_tick_size(base_ticker),
+                                                );
+                                            }
+
                                             match new_basis {
                                                 Basis::Time(tf) => {
                                                     let kline_stream = StreamKind::Kline {
@@ -1105,6 +2052,13 @@ impl State {
                                                     };
                                                     let mut streams = vec![kline_stream];
 
+                                                    for ti in c.compare_tickers() {
+                                                        streams.push(StreamKind::Kline {
+                                                            ticker_info: *ti,
+                                                            timeframe: tf,
+                                                        });
+                                                    }
+
                                                     if matches!(
                                                         c.kind,
                                                         data::chart::KlineChartKind::Footprint { .. }
@@ -1186,6 +2140,27 @@ impl State {
                                             }
                                         }
                                     }
+                                    Content::Correlation(Some(m)) => {
+                                        if let Basis::Time(tf) = new_basis {
+                                            let streams: Vec<StreamKind> = m
+                                                .selected_tickers()
+                                                .iter()
+                                                .copied()
+                                                .map(|ti| StreamKind::Kline {
+                                                    ticker_info: ti,
+                                                    timeframe: tf,
+                                                })
+                                                .collect();
+
+                                            self.streams = ResolvedStream::Ready(streams);
+                                            let action = m.set_timeframe(tf);
+
+                                            if let Some(chart::Action::RequestFetch(fetch)) = action
+                                            {
+                                                effect = Some(Effect::RequestFetch(fetch));
+                                            }
+                                        }
+                                    }
                                     _ => {}
                                 }
                             }
@@ -1232,21 +2207,52 @@ impl State {
                     let crate::modal::pane::mini_tickers_list::Action::RowSelected(sel) = action;
                     match sel {
                         crate::modal::pane::mini_tickers_list::RowSelection::Add(ti) => {
-                            if let Content::Comparison(chart) = &mut self.content
-                                && let Some(c) = chart
-                            {
-                                let rebuilt = c.add_ticker(&ti);
-                                self.streams = ResolvedStream::Ready(rebuilt);
-                                return Some(Effect::RefreshStreams);
+                            match &mut self.content {
+                                Content::Comparison(Some(c)) => {
+                                    let rebuilt = c.add_ticker(&ti);
+                                    self.streams = ResolvedStream::Ready(rebuilt);
+                                    return Some(Effect::RefreshStreams);
+                                }
+                                Content::Correlation(Some(m)) => {
+                                    let rebuilt = m.add_ticker(&ti);
+                                    self.streams = ResolvedStream::Ready(rebuilt);
+                                    return Some(Effect::RefreshStreams);
+                                }
+                                Content::Kline { chart: Some(c), .. } => {
+                                    let mut overlays = c.compare_overlays();
+                                    if overlays.contains(&ti) {
+                                        return None;
+                                    }
+                                    if !overlays.add(ti) {
+                                        self.notifications.push(Toast::warn(format!(
+                                            "Up to {} tickers can be compared on one chart",
+                                            data::chart::kline::CompareOverlays::MAX
+                                        )));
+                                        return None;
+                                    }
+                                    return self.set_compare_overlays(overlays);
+                                }
+                                _ => {}
                             }
                         }
                         crate::modal::pane::mini_tickers_list::RowSelection::Remove(ti) => {
-                            if let Content::Comparison(chart) = &mut self.content
-                                && let Some(c) = chart
-                            {
-                                let rebuilt = c.remove_ticker(&ti);
-                                self.streams = ResolvedStream::Ready(rebuilt);
-                                return Some(Effect::RefreshStreams);
+                            match &mut self.content {
+                                Content::Comparison(Some(c)) => {
+                                    let rebuilt = c.remove_ticker(&ti);
+                                    self.streams = ResolvedStream::Ready(rebuilt);
+                                    return Some(Effect::RefreshStreams);
+                                }
+                                Content::Correlation(Some(m)) => {
+                                    let rebuilt = m.remove_ticker(&ti);
+                                    self.streams = ResolvedStream::Ready(rebuilt);
+                                    return Some(Effect::RefreshStreams);
+                                }
+                                Content::Kline { chart: Some(c), .. } => {
+                                    let mut overlays = c.compare_overlays();
+                                    overlays.remove(&ti);
+                                    return self.set_compare_overlays(overlays);
+                                }
+                                _ => {}
                             }
                         }
                         crate::modal::pane::mini_tickers_list::RowSelection::Switch(ti) => {
@@ -1317,6 +2323,62 @@ impl State {
                 tooltip_pos,
                 modal_btn_style(Modal::Indicators),
             ));
+            buttons = buttons.push(button_with_tooltip(
+                icon_text(Icon::Edit, 12),
+                show_modal(Modal::PriceAlerts),
+                Some("Price alerts"),
+                tooltip_pos,
+                modal_btn_style(Modal::PriceAlerts),
+            ));
+        }
+
+        if self.has_chart_view() {
+            buttons = buttons.push(button_with_tooltip(
+                icon_text(Icon::Folder, 12),
+                show_modal(Modal::Export(ExportScale::default())),
+                Some("Save as PNG"),
+                tooltip_pos,
+                control_btn_style(matches!(self.modal, Some(Modal::Export(_)))),
+            ));
+        }
+
+        if !treat_as_starter {
+            buttons = buttons.push(button_with_tooltip(
+                icon_text(Icon::Clone, 12),
+                show_modal(Modal::Note),
+                Some("Edit note"),
+                tooltip_pos,
+                control_btn_style(matches!(self.modal, Some(Modal::Note))),
+            ));
+        }
+        if let Content::Kline {
+            chart: Some(chart), ..
+        } = &self.content
+        {
+            let is_percent = matches!(
+                chart.price_axis_mode(),
+                data::chart::PriceAxisMode::Percent { .. }
+            );
+
+            buttons = buttons.push(button_with_tooltip(
+                text("%").size(12),
+                Message::PaneEvent(
+                    pane,
+                    Event::ChartInteraction(super::chart::Message::PriceAxisModeToggled),
+                ),
+                Some("Axis mode: percent change from the oldest visible bar"),
+                tooltip_pos,
+                control_btn_style(is_percent),
+            ));
+        }
+        if !treat_as_starter && matches!(&self.content, Content::Kline { .. }) {
+            buttons = buttons.push(button_with_tooltip(
+                icon_text(Icon::Layout, 12),
+                Message::PaneEvent(pane, Event::OpenTemplates),
+                Some("Templates"),
+                tooltip_pos,
+                control_btn_style(matches!(self.modal, Some(Modal::Templates { .. }))),
+            ));
         }
 
         if is_popout {
@@ -1327,11 +2389,20 @@ impl State {
                 tooltip_pos,
                 control_btn_style(is_popout),
             ));
-        } else if total_panes > 1 {
+        } else {
+            if total_panes > 1 {
+                buttons = buttons.push(button_with_tooltip(
+                    icon_text(Icon::Popout, 12),
+                    Message::Popout,
+                    Some("Pop out"),
+                    tooltip_pos,
+                    control_btn_style(is_popout),
+                ));
+            }
             buttons = buttons.push(button_with_tooltip(
-                icon_text(Icon::Popout, 12),
-                Message::Popout,
-                Some("Pop out"),
+                icon_text(Icon::ExternalLink, 12),
+                Message::PopoutCopy,
+                Some("Pop out a copy"),
                 tooltip_pos,
                 control_btn_style(is_popout),
             ));
@@ -1389,87 +2460,471 @@ impl State {
     where
         F: FnOnce() -> Element<'a, Message>,
     {
-        let base =
-            widget::toast::Manager::new(base, &self.notifications, Alignment::End, move |msg| {
-                Message::PaneEvent(pane, Event::DeleteNotification(msg))
+        let base: Element<'a, Message> = widget::toast::Manager::with_actions(
+            base,
+            &self.notifications,
+            pane_toast_cfg(),
+            move |idx| Message::PaneEvent(pane, Event::DeleteNotification(idx)),
+            move |idx| Message::PaneEvent(pane, Event::NotificationAction(idx)),
+        )
+        .into();
+
+        let base = widget::on_right_click(base, move |position| {
+            Message::PaneEvent(pane, Event::ShowModal(Modal::ContextMenu(position)))
+        });
+
+        let on_blur = Message::PaneEvent(pane, Event::HideModal);
+
+        match &self.modal {
+            Some(Modal::LinkGroup) => {
+                let content = link_group_modal(pane, self.link_group);
+
+                stack_modal(
+                    base,
+                    content,
+                    on_blur,
+                    padding::right(12).left(4),
+                    Alignment::Start,
+                )
+            }
+            Some(Modal::StreamModifier(modifier)) => stack_modal(
+                base,
+                modifier.view(self.stream_pair()).map(move |message| {
+                    Message::PaneEvent(pane, Event::StreamModifierChanged(message))
+                }),
+                Message::PaneEvent(pane, Event::HideModal),
+                padding::right(12).left(48),
+                Alignment::Start,
+            ),
+            Some(Modal::MiniTickersList(panel)) => {
+                let mini_list = panel
+                    .view(tickers_table, selected_tickers, self.stream_pair())
+                    .map(move |msg| {
+                        Message::PaneEvent(pane, Event::MiniTickersListInteraction(msg))
+                    });
+
+                let content: Element<_> = container(mini_list)
+                    .max_width(260)
+                    .padding(16)
+                    .style(style::chart_modal)
+                    .into();
+
+                stack_modal(
+                    base,
+                    content,
+                    Message::PaneEvent(pane, Event::HideModal),
+                    padding::left(12),
+                    Alignment::Start,
+                )
+            }
+            Some(Modal::Settings) => stack_modal(
+                base,
+                settings_modal(),
+                on_blur,
+                padding::right(12).left(12),
+                Alignment::End,
+            ),
+            Some(Modal::Indicators) => stack_modal(
+                base,
+                indicator_modal.unwrap_or_else(|| column![].into()),
+                on_blur,
+                padding::right(12).left(12),
+                Alignment::End,
+            ),
+            Some(Modal::PriceAlerts) => stack_modal(
+                base,
+                modal::pane::alerts::view(pane, self.content.price_lines(), self.stream_pair()),
+                on_blur,
+                padding::right(12).left(12),
+                Alignment::End,
+            ),
+            Some(Modal::Export(scale)) => stack_modal(
+                base,
+                export_modal(pane, *scale),
+                on_blur,
+                padding::right(12).left(12),
+                Alignment::End,
+            ),
+            Some(Modal::Note) => stack_modal(
+                base,
+                note_modal(pane, self.id, &self.note_draft),
+                Message::PaneEvent(pane, Event::NoteSubmitted),
+                padding::right(12).left(12),
+                Alignment::End,
+            ),
+            Some(Modal::Templates { name, saved }) => stack_modal(
+                base,
+                templates_modal(pane, name, saved),
+                on_blur,
+                padding::right(12).left(12),
+                Alignment::End,
+            ),
+            Some(Modal::ContextMenu(position)) => stack_modal(
+                base,
+                self.context_menu(pane),
+                on_blur,
+                padding::left(position.x).top(position.y),
+                Alignment::Start,
+            ),
+            Some(Modal::Controls) => stack_modal(
+                base,
+                if let Some(controls) = compact_controls {
+                    controls
+                } else {
+                    column![].into()
+                },
+                on_blur,
+                padding::left(12),
+                Alignment::End,
+            ),
+            None => base,
+        }
+    }
+
+    pub fn matches_stream(&self, stream: &StreamKind) -> bool {
+        self.streams.matches_stream(stream) || self.cached_kline_streams().contains(stream)
+    }
+
+    /// Kline streams of the timeframes a candle pane keeps loaded besides the shown one.
+    /// Left out of `streams`, which fetches and saved layouts take the shown timeframe from.
+    pub fn cached_kline_streams(&self) -> Vec<StreamKind> {
+        let (Content::Kline { chart: Some(c), .. }, Some(ticker_info)) =
+            (&self.content, self.stream_pair())
+        else {
+            return vec![];
+        };
+
+        c.cached_timeframes()
+            .into_iter()
+            .map(|timeframe| StreamKind::Kline {
+                ticker_info,
+                timeframe,
             })
-            .into();
+            .collect()
+    }
+
+    /// Flags the pane for the next timed redraw
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Datapoints held by the pane's chart, zero for panels
+    pub fn datapoint_count(&self) -> usize {
+        match &self.content {
+            Content::Heatmap {
+                chart: Some(chart), ..
+            } => chart.datapoint_count(),
+            Content::Kline {
+                chart: Some(chart), ..
+            } => chart.datapoint_count(),
+            Content::Range {
+                chart: Some(chart), ..
+            } => chart.datapoint_count(),
+            _ => 0,
+        }
+    }
+
+    fn kline_config(&self) -> data::chart::kline::Config {
+        self.settings
+            .visual_config
+            .clone()
+            .and_then(|cfg| cfg.kline())
+            .unwrap_or_default()
+    }
+
+    /// Before the link group switches a kline pane to `next`, adds its current ticker to the
+    /// overlays when the pane is set to keep comparing against it
+    pub fn compare_previous_on_link(&mut self, next: TickerInfo) {
+        let mut cfg = self.kline_config();
+        if !cfg.compare_previous_on_link || !matches!(self.content, Content::Kline { .. }) {
+            return;
+        }
+        let Some(previous) = self.stream_pair().filter(|previous| *previous != next) else {
+            return;
+        };
+
+        cfg.compare.retain(|ti| *ti != next);
+        if !cfg.compare.contains(&previous) && !cfg.compare.add(previous) {
+            self.notifications.push(Toast::warn(format!(
+                "Up to {} tickers can be compared on one chart",
+                data::chart::kline::CompareOverlays::MAX
+            )));
+            return;
+        }
+        self.settings.visual_config = Some(VisualConfig::Kline(cfg));
+    }
+
+    /// Swaps the tickers overlaid on a kline chart, keeping their kline streams in sync
+    fn set_compare_overlays(
+        &mut self,
+        mut compare: data::chart::kline::CompareOverlays,
+    ) -> Option<Effect> {
+        let base_ticker = self.stream_pair()?;
+        compare.retain(|ti| *ti != base_ticker);
+
+        let mut cfg = self.kline_config();
+        cfg.compare = compare;
+
+        let Content::Kline { chart: Some(c), .. } = &mut self.content else {
+            return None;
+        };
+        c.set_compare_overlays(compare);
+
+        self.settings.visual_config = Some(VisualConfig::Kline(cfg));
+
+        let mut streams: Vec<StreamKind> = self
+            .streams
+            .ready_iter()
+            .map(|it| {
+                it.filter(|s| match s {
+                    StreamKind::Kline { ticker_info, .. } => *ticker_info == base_ticker,
+                    StreamKind::DepthAndTrades { .. } => true,
+                })
+                .copied()
+                .collect()
+            })
+            .unwrap_or_default();
+
+        if let Basis::Time(timeframe) = c.basis() {
+            streams.extend(compare.tickers().map(|ticker_info| StreamKind::Kline {
+                ticker_info,
+                timeframe,
+            }));
+        }
+
+        self.streams = ResolvedStream::Ready(streams);
+        Some(Effect::RefreshStreams)
+    }
+
+    /// Basis picker of the pane's header, `None` for panes without one
+    fn basis_modifier_kind(&self) -> Option<ModifierKind> {
+        let basis = self.settings.selected_basis;
+        let tick_multiply = self.settings.tick_multiply;
+
+        match &self.content {
+            Content::Kline {
+                chart: Some(_),
+                kind,
+                ..
+            } => Some(match kind {
+                data::chart::KlineChartKind::Footprint { .. } => ModifierKind::Footprint(
+                    basis.unwrap_or(Timeframe::M5.into()),
+                    tick_multiply.unwrap_or(TickMultiplier(10)),
+                ),
+                data::chart::KlineChartKind::Candles { .. } => {
+                    ModifierKind::Candlestick(basis.unwrap_or(Timeframe::M15.into()))
+                }
+            }),
+            Content::Heatmap { chart: Some(_), .. } => Some(ModifierKind::Heatmap(
+                basis.unwrap_or(Basis::default_heatmap_time(self.stream_pair())),
+                tick_multiply.unwrap_or(TickMultiplier(5)),
+            )),
+            Content::Comparison(Some(_)) | Content::Correlation(Some(_)) => Some(
+                ModifierKind::Comparison(basis.unwrap_or(Timeframe::M15.into())),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Right click menu, entries the pane's content can't act on are left out
+    fn context_menu<'a>(&'a self, pane: pane_grid::Pane) -> Element<'a, Message> {
+        use modal::pane::settings::study::{Message as StudyEvent, Study, StudyMessage};
+
+        let menu_button = |label: &'static str, message: Option<Message>| {
+            button(text(label).size(12))
+                .width(Length::Fill)
+                .on_press_maybe(message)
+                .style(|theme: &Theme, status| style::button::transparent(theme, status, false))
+        };
+
+        let mut items = column![menu_button("Reset", Some(Message::ReplacePane(pane)))]
+            .spacing(2)
+            .width(Length::Fixed(200.0));
+
+        if let Some(kind) = self.basis_modifier_kind() {
+            let modifier = modal::stream::Modifier::new(kind)
+                .with_view_mode(modal::stream::ViewMode::BasisSelection);
+
+            items = items.push(menu_button(
+                "Change timeframe",
+                Some(Message::PaneEvent(
+                    pane,
+                    Event::ShowModal(Modal::StreamModifier(modifier)),
+                )),
+            ));
+        }
+
+        items = items.push(
+            row![
+                text("Chart kind").size(12),
+                pick_list(ContentKind::ALL, Some(self.content.kind()), move |kind| {
+                    Message::PaneEvent(pane, Event::ContentSelected(kind))
+                })
+                .text_size(12),
+            ]
+            .spacing(8)
+            .padding(padding::left(8))
+            .align_y(Alignment::Center),
+        );
+
+        let study_toggles: Vec<Element<'a, Message>> = match &self.content {
+            Content::Kline {
+                chart: Some(_),
+                kind: data::chart::KlineChartKind::Footprint { studies, .. },
+                ..
+            } => data::chart::kline::FootprintStudy::ALL
+                .iter()
+                .map(|study| {
+                    let enabled = studies.iter().any(|s| s.is_same_type(study));
+                    let study = *study;
+
+                    iced::widget::checkbox(enabled)
+                        .label(study.to_string())
+                        .text_size(12)
+                        .on_toggle(move |checked| {
+                            Message::PaneEvent(
+                                pane,
+                                Event::StudyConfigurator(StudyMessage::Footprint(
+                                    StudyEvent::StudyToggled(study, checked),
+                                )),
+                            )
+                        })
+                        .into()
+                })
+                .collect(),
+            Content::Heatmap {
+                chart: Some(_),
+                studies,
+                ..
+            } => data::chart::heatmap::HeatmapStudy::ALL
+                .iter()
+                .map(|study| {
+                    let enabled = studies.iter().any(|s| s.is_same_type(study));
+                    let study = *study;
+
+                    iced::widget::checkbox(enabled)
+                        .label(study.to_string())
+                        .text_size(12)
+                        .on_toggle(move |checked| {
+                            Message::PaneEvent(
+                                pane,
+                                Event::StudyConfigurator(StudyMessage::Heatmap(
+                                    StudyEvent::StudyToggled(study, checked),
+                                )),
+                            )
+                        })
+                        .into()
+                })
+                .collect(),
+            _ => vec![],
+        };
 
-        let on_blur = Message::PaneEvent(pane, Event::HideModal);
+        if !study_toggles.is_empty() {
+            items = items.push(text("Studies").size(11));
+            items = items.push(column(study_toggles).spacing(4).padding(padding::left(8)));
+        }
 
-        match &self.modal {
-            Some(Modal::LinkGroup) => {
-                let content = link_group_modal(pane, self.link_group);
+        if let Content::Kline { chart, .. } = &self.content {
+            items = items.push(menu_button(
+                "Export CSV",
+                chart
+                    .is_some()
+                    .then_some(Message::PaneEvent(pane, Event::ExportCsv)),
+            ));
+            items = items.push(menu_button(
+                "Import historical data",
+                chart
+                    .as_ref()
+                    .is_some_and(|c| c.basis().is_time())
+                    .then_some(Message::PaneEvent(pane, Event::ImportKlines)),
+            ));
+        }
 
-                stack_modal(
-                    base,
-                    content,
-                    on_blur,
-                    padding::right(12).left(4),
-                    Alignment::Start,
-                )
-            }
-            Some(Modal::StreamModifier(modifier)) => stack_modal(
-                base,
-                modifier.view(self.stream_pair()).map(move |message| {
-                    Message::PaneEvent(pane, Event::StreamModifierChanged(message))
-                }),
-                Message::PaneEvent(pane, Event::HideModal),
-                padding::right(12).left(48),
-                Alignment::Start,
-            ),
-            Some(Modal::MiniTickersList(panel)) => {
-                let mini_list = panel
-                    .view(tickers_table, selected_tickers, self.stream_pair())
-                    .map(move |msg| {
-                        Message::PaneEvent(pane, Event::MiniTickersListInteraction(msg))
-                    });
+        container(items).padding(4).style(style::chart_modal).into()
+    }
 
-                let content: Element<_> = container(mini_list)
-                    .max_width(260)
-                    .padding(16)
-                    .style(style::chart_modal)
-                    .into();
+    /// Current kline setup of the pane as a template named `name`
+    fn template(&self, name: &str) -> Option<data::ChartTemplate> {
+        let Content::Kline {
+            chart: Some(chart), ..
+        } = &self.content
+        else {
+            return None;
+        };
+        if name.is_empty() {
+            return None;
+        }
 
-                stack_modal(
-                    base,
-                    content,
-                    Message::PaneEvent(pane, Event::HideModal),
-                    padding::left(12),
-                    Alignment::Start,
-                )
-            }
-            Some(Modal::Settings) => stack_modal(
-                base,
-                settings_modal(),
-                on_blur,
-                padding::right(12).left(12),
-                Alignment::End,
-            ),
-            Some(Modal::Indicators) => stack_modal(
-                base,
-                indicator_modal.unwrap_or_else(|| column![].into()),
-                on_blur,
-                padding::right(12).left(12),
-                Alignment::End,
-            ),
-            Some(Modal::Controls) => stack_modal(
-                base,
-                if let Some(controls) = compact_controls {
-                    controls
-                } else {
-                    column![].into()
-                },
-                on_blur,
-                padding::left(12),
-                Alignment::End,
+        let default_tick_size = self
+            .settings
+            .tick_multiply
+            .zip(self.stream_pair())
+            .map(|(tm, ticker_info)| tm.multiply_with_min_tick_size(ticker_info));
+
+        Some(data::ChartTemplate {
+            name: name.to_string(),
+            chart_kind: chart.kind().clone(),
+            studies: self.settings.kline_studies.clone(),
+            default_tick_size,
+        })
+    }
+
+    /// Takes over the chart kind, studies and tick size of `template`, the chart itself is
+    /// rebuilt by `set_content_and_streams` with the returned content kind
+    pub fn apply_template(
+        &mut self,
+        template: &data::ChartTemplate,
+        ticker_info: TickerInfo,
+    ) -> ContentKind {
+        let content_kind = match template.chart_kind {
+            data::chart::KlineChartKind::Candles { .. } => ContentKind::CandlestickChart,
+            data::chart::KlineChartKind::Footprint {
+                depth_layer: Some(_),
+                ..
+            } => ContentKind::HeatmapFootprint,
+            data::chart::KlineChartKind::Footprint { .. } => ContentKind::FootprintChart,
+        };
+
+        if self.content.kind() != content_kind {
+            self.settings.selected_basis = None;
+        }
+
+        let (indicators, layout) = match &self.content {
+            Content::Kline {
+                chart,
+                indicators,
+                layout,
+                ..
+            } => (
+                indicators.clone(),
+                chart.as_ref().map_or(layout.clone(), |c| c.chart_layout()),
             ),
-            None => base,
+            _ => (vec![KlineIndicator::Volume], ViewConfig::default()),
+        };
+        self.content = Content::Kline {
+            chart: None,
+            indicators,
+            layout,
+            kind: template.chart_kind.clone(),
+        };
+
+        let min_tick = f32::from(ticker_info.min_ticksize);
+        if let Some(tick_size) = template.default_tick_size
+            && min_tick > 0.0
+        {
+            let multiplier = (tick_size / min_tick)
+                .round()
+                .clamp(1.0, f32::from(u16::MAX));
+            self.settings.tick_multiply = Some(TickMultiplier(multiplier as u16));
+            self.settings.auto_tick = false;
         }
-    }
+        self.settings.kline_studies = template.studies.clone();
 
-    pub fn matches_stream(&self, stream: &StreamKind) -> bool {
-        self.streams.matches_stream(stream)
+        content_kind
     }
 
     fn show_modal_with_focus(&mut self, requested_modal: Modal) -> Option<Effect> {
@@ -1482,12 +2937,20 @@ impl State {
         };
 
         if should_toggle_close {
-            self.modal = None;
+            if matches!(self.modal.take(), Some(Modal::Note)) {
+                self.save_note_draft();
+            }
             return None;
         }
 
+        if matches!(requested_modal, Modal::Note) {
+            let note = self.settings.note.as_deref().unwrap_or_default();
+            self.note_draft = text_editor::Content::with_text(note);
+        }
+
         let focus_widget_id = match &requested_modal {
             Modal::MiniTickersList(m) => Some(m.search_box_id.clone()),
+            Modal::Note => Some(note_input_id(self.id)),
             _ => None,
         };
 
@@ -1495,6 +2958,13 @@ impl State {
         focus_widget_id.map(Effect::FocusWidget)
     }
 
+    /// Stores the editor's text as the pane note, an empty draft removes it
+    fn save_note_draft(&mut self) {
+        let draft = self.note_draft.text();
+        let note = draft.trim();
+        self.settings.note = (!note.is_empty()).then(|| note.to_string());
+    }
+
     pub fn invalidate(&mut self, now: Instant) -> Option<Action> {
         match &mut self.content {
             Content::Heatmap { chart, .. } => chart
@@ -1503,31 +2973,51 @@ impl State {
             Content::Kline { chart, .. } => chart
                 .as_mut()
                 .and_then(|c| c.invalidate(Some(now)).map(Action::Chart)),
+            Content::Range { chart, .. } => chart
+                .as_mut()
+                .and_then(|c| c.invalidate(Some(now)).map(Action::Chart)),
             Content::TimeAndSales(panel) => panel
                 .as_mut()
                 .and_then(|p| p.invalidate(Some(now)).map(Action::Panel)),
             Content::Ladder(panel) => panel
                 .as_mut()
                 .and_then(|p| p.invalidate(Some(now)).map(Action::Panel)),
-            Content::Starter => None,
+            Content::Statistics(panel) => {
+                if let Some(p) = panel {
+                    p.invalidate(Some(now));
+                }
+                None
+            }
+            Content::Spread(chart) => {
+                if let Some(c) = chart {
+                    c.invalidate(Some(now));
+                }
+                None
+            }
+            Content::Starter | Content::MiniCharts { .. } | Content::Unloaded { .. } => None,
             Content::Comparison(chart) => chart
                 .as_mut()
                 .and_then(|c| c.invalidate(Some(now)).map(Action::Chart)),
+            Content::Correlation(matrix) => matrix
+                .as_mut()
+                .and_then(|m| m.invalidate(Some(now)).map(Action::Chart)),
         }
     }
 
     pub fn update_interval(&self) -> Option<u64> {
         match &self.content {
-            Content::Kline { .. } | Content::Comparison(_) => Some(1000),
-            Content::Heatmap { chart, .. } => {
-                if let Some(chart) = chart {
-                    chart.basis_interval()
-                } else {
-                    None
-                }
-            }
+            Content::Kline { .. }
+            | Content::Range { .. }
+            | Content::Comparison(_)
+            | Content::Correlation(_) => Some(1000),
+            // tick columns have no fixed width, redraw at the depth update cadence instead
+            Content::Heatmap { chart, .. } => chart
+                .as_ref()
+                .map(|chart| chart.basis_interval().unwrap_or(100)),
             Content::Ladder(_) | Content::TimeAndSales(_) => Some(100),
-            Content::Starter => None,
+            Content::Statistics(_) => Some(1000),
+            Content::Spread(_) => Some(250),
+            Content::Starter | Content::MiniCharts { .. } | Content::Unloaded { .. } => None,
         }
     }
 
@@ -1549,17 +3039,30 @@ impl State {
             return Some(Action::ResolveContent);
         }
 
+        if let Content::Kline {
+            chart: Some(chart), ..
+        } = &mut self.content
+        {
+            chart.fade_new_bar_flash(now);
+        }
+
+        if !self.dirty {
+            return None;
+        }
+
         match (invalidate_interval, last_tick) {
             (Some(interval_ms), Some(previous_tick_time)) => {
                 if interval_ms > 0 {
                     let interval_duration = std::time::Duration::from_millis(interval_ms);
                     if now.duration_since(previous_tick_time) >= interval_duration {
+                        self.dirty = false;
                         return self.invalidate(now);
                     }
                 }
             }
             (Some(interval_ms), None) => {
                 if interval_ms > 0 {
+                    self.dirty = false;
                     return self.invalidate(now);
                 }
             }
@@ -1585,11 +3088,18 @@ impl Default for State {
             notifications: vec![],
             status: Status::Ready,
             link_group: None,
+            note_draft: text_editor::Content::new(),
+            dirty: true,
+            suggest_tick_on_load: false,
+            suggested_tick: None,
+            zoom_memory: FxHashMap::default(),
+            popout_origin: None,
         }
     }
 }
 
 #[derive(Default)]
+#[allow(clippy::large_enum_variant)]
 pub enum Content {
     #[default]
     Starter,
@@ -1605,9 +3115,24 @@ pub enum Content {
         layout: data::chart::ViewConfig,
         kind: data::chart::KlineChartKind,
     },
+    Range {
+        chart: Option<RangeChart>,
+        layout: data::chart::ViewConfig,
+    },
     TimeAndSales(Option<TimeAndSales>),
     Ladder(Option<Ladder>),
     Comparison(Option<ComparisonChart>),
+    Correlation(Option<CorrelationMatrix>),
+    Statistics(Option<StatisticsPanel>),
+    Spread(Option<SpreadChart>),
+    /// Draws from the other kline panes of the window, so it holds no data of its own
+    MiniCharts {
+        columns: usize,
+    },
+    /// Pane of an inactive layout, kept serialized until the layout is shown again
+    Unloaded {
+        config: Box<data::Pane>,
+    },
 }
 
 impl Content {
@@ -1640,6 +3165,10 @@ impl Content {
                 ViewConfig {
                     splits: vec![],
                     autoscale: Some(data::chart::Autoscale::CenterLatest),
+                    auto_scroll: data::chart::AutoScrollMode::KeepLastVisible,
+                    fixed_cells: false,
+                    price_axis: data::chart::PriceAxisMode::Absolute,
+                    price_rounding: data::chart::PriceAxisRounding::Auto,
                 },
                 vec![],
             )
@@ -1692,17 +3221,40 @@ impl Content {
         };
 
         let (default_tf, determined_chart_kind) = match content_kind {
-            ContentKind::FootprintChart => (
-                Timeframe::M5,
+            ContentKind::FootprintChart | ContentKind::HeatmapFootprint => {
+                let depth_layer = (content_kind == ContentKind::HeatmapFootprint)
+                    .then(data::chart::kline::DepthLayer::default);
+
+                (
+                    Timeframe::M5,
+                    prev_kind_opt
+                        .filter(|k| matches!(k, data::chart::KlineChartKind::Footprint { .. }))
+                        .map(|mut k| {
+                            if let data::chart::KlineChartKind::Footprint {
+                                depth_layer: prev_layer,
+                                ..
+                            } = &mut k
+                                && prev_layer.is_some() != depth_layer.is_some()
+                            {
+                                *prev_layer = depth_layer;
+                            }
+                            k
+                        })
+                        .unwrap_or_else(|| data::chart::KlineChartKind::Footprint {
+                            clusters: data::chart::kline::ClusterKind::default(),
+                            scaling: data::chart::kline::ClusterScaling::default(),
+                            studies: vec![],
+                            depth_layer,
+                            cell_text: data::chart::kline::FootprintCellText::default(),
+                        }),
+                )
+            }
+            ContentKind::CandlestickChart => (
+                Timeframe::M15,
                 prev_kind_opt
-                    .filter(|k| matches!(k, data::chart::KlineChartKind::Footprint { .. }))
-                    .unwrap_or_else(|| data::chart::KlineChartKind::Footprint {
-                        clusters: data::chart::kline::ClusterKind::default(),
-                        scaling: data::chart::kline::ClusterScaling::default(),
-                        studies: vec![],
-                    }),
+                    .filter(|k| matches!(k, data::chart::KlineChartKind::Candles { .. }))
+                    .unwrap_or_default(),
             ),
-            ContentKind::CandlestickChart => (Timeframe::M15, data::chart::KlineChartKind::Candles),
             _ => unreachable!("invalid content kind for kline chart"),
         };
 
@@ -1748,9 +3300,13 @@ impl Content {
             .unwrap_or(ViewConfig {
                 splits,
                 autoscale: Some(data::chart::Autoscale::FitToVisible),
+                auto_scroll: data::chart::AutoScrollMode::KeepLastVisible,
+                fixed_cells: false,
+                price_axis: data::chart::PriceAxisMode::Absolute,
+                price_rounding: data::chart::PriceAxisRounding::Auto,
             });
 
-        let chart = KlineChart::new(
+        let mut chart = KlineChart::new(
             layout.clone(),
             basis,
             tick_size,
@@ -1761,6 +3317,18 @@ impl Content {
             &determined_chart_kind,
         );
 
+        let cfg = settings
+            .visual_config
+            .clone()
+            .and_then(|cfg| cfg.kline())
+            .unwrap_or_default();
+        let mut compare = cfg.compare;
+        compare.retain(|ti| *ti != ticker_info);
+        chart.set_compare_overlays(compare);
+        chart.apply_config(&cfg);
+        chart.set_timeframe_tabs(&settings.timeframe_tabs);
+        chart.set_kline_studies(&settings.kline_studies);
+
         Content::Kline {
             chart: Some(chart),
             indicators: enabled_indicators,
@@ -1775,23 +3343,34 @@ impl Content {
             ContentKind::CandlestickChart => Content::Kline {
                 chart: None,
                 indicators: vec![KlineIndicator::Volume],
-                kind: data::chart::KlineChartKind::Candles,
+                kind: data::chart::KlineChartKind::default(),
                 layout: ViewConfig {
                     splits: vec![],
                     autoscale: Some(data::chart::Autoscale::FitToVisible),
+                    auto_scroll: data::chart::AutoScrollMode::KeepLastVisible,
+                    fixed_cells: false,
+                    price_axis: data::chart::PriceAxisMode::Absolute,
+                    price_rounding: data::chart::PriceAxisRounding::Auto,
                 },
             },
-            ContentKind::FootprintChart => Content::Kline {
+            ContentKind::FootprintChart | ContentKind::HeatmapFootprint => Content::Kline {
                 chart: None,
                 indicators: vec![KlineIndicator::Volume],
                 kind: data::chart::KlineChartKind::Footprint {
                     clusters: data::chart::kline::ClusterKind::default(),
                     scaling: data::chart::kline::ClusterScaling::default(),
                     studies: vec![],
+                    depth_layer: (kind == ContentKind::HeatmapFootprint)
+                        .then(data::chart::kline::DepthLayer::default),
+                    cell_text: data::chart::kline::FootprintCellText::default(),
                 },
                 layout: ViewConfig {
                     splits: vec![],
                     autoscale: Some(data::chart::Autoscale::FitToVisible),
+                    auto_scroll: data::chart::AutoScrollMode::KeepLastVisible,
+                    fixed_cells: false,
+                    price_axis: data::chart::PriceAxisMode::Absolute,
+                    price_rounding: data::chart::PriceAxisRounding::Auto,
                 },
             },
             ContentKind::HeatmapChart => Content::Heatmap {
@@ -1801,11 +3380,25 @@ impl Content {
                 layout: ViewConfig {
                     splits: vec![],
                     autoscale: Some(data::chart::Autoscale::CenterLatest),
+                    auto_scroll: data::chart::AutoScrollMode::KeepLastVisible,
+                    fixed_cells: false,
+                    price_axis: data::chart::PriceAxisMode::Absolute,
+                    price_rounding: data::chart::PriceAxisRounding::Auto,
                 },
             },
+            ContentKind::RangeChart => Content::Range {
+                chart: None,
+                layout: ViewConfig::default(),
+            },
             ContentKind::ComparisonChart => Content::Comparison(None),
+            ContentKind::CorrelationMatrix => Content::Correlation(None),
             ContentKind::TimeAndSales => Content::TimeAndSales(None),
             ContentKind::Ladder => Content::Ladder(None),
+            ContentKind::Statistics => Content::Statistics(None),
+            ContentKind::SpreadChart => Content::Spread(None),
+            ContentKind::MiniCharts => Content::MiniCharts {
+                columns: DEFAULT_MINI_COLUMNS,
+            },
         }
     }
 
@@ -1813,10 +3406,91 @@ impl Content {
         match self {
             Content::Heatmap { chart, .. } => Some(chart.as_ref()?.last_update()),
             Content::Kline { chart, .. } => Some(chart.as_ref()?.last_update()),
+            Content::Range { chart, .. } => Some(chart.as_ref()?.last_update()),
             Content::TimeAndSales(panel) => Some(panel.as_ref()?.last_update()),
             Content::Ladder(panel) => Some(panel.as_ref()?.last_update()),
             Content::Comparison(chart) => Some(chart.as_ref()?.last_update()),
-            Content::Starter => None,
+            Content::Correlation(matrix) => Some(matrix.as_ref()?.last_update()),
+            Content::Statistics(panel) => Some(panel.as_ref()?.last_update()),
+            Content::Spread(chart) => Some(chart.as_ref()?.last_update()),
+            Content::Starter | Content::MiniCharts { .. } | Content::Unloaded { .. } => None,
+        }
+    }
+
+    pub fn set_synced_crosshair(&mut self, timestamp: Option<u64>) {
+        match self {
+            Content::Heatmap { chart: Some(c), .. } => chart::set_synced_crosshair(c, timestamp),
+            Content::Kline { chart: Some(c), .. } => chart::set_synced_crosshair(c, timestamp),
+            Content::Range { chart: Some(c), .. } => chart::set_synced_crosshair(c, timestamp),
+            _ => {}
+        }
+    }
+
+    pub fn sync_price_lines(&mut self, price_lines: &data::chart::annotation::PriceLines) {
+        match self {
+            Content::Heatmap { chart: Some(c), .. } => chart::sync_price_lines(c, price_lines),
+            Content::Kline { chart: Some(c), .. } => chart::sync_price_lines(c, price_lines),
+            Content::Range { chart: Some(c), .. } => chart::sync_price_lines(c, price_lines),
+            _ => {}
+        }
+    }
+
+    pub fn price_lines(&self) -> &[data::chart::annotation::PriceLine] {
+        match self {
+            Content::Heatmap { chart: Some(c), .. } => chart::price_lines(c),
+            Content::Kline { chart: Some(c), .. } => chart::price_lines(c),
+            Content::Range { chart: Some(c), .. } => chart::price_lines(c),
+            _ => &[],
+        }
+    }
+
+    /// Pans or zooms the chart, returns whether the content is a chart
+    pub fn navigate(
+        &mut self,
+        key: chart::NavigationKey,
+        steps: data::config::KeyboardNav,
+    ) -> bool {
+        match self {
+            Content::Heatmap { chart: Some(c), .. } => chart::navigate(c, key, steps),
+            Content::Kline { chart: Some(c), .. } => chart::navigate(c, key, steps),
+            Content::Range { chart: Some(c), .. } => chart::navigate(c, key, steps),
+            _ => return false,
+        }
+        true
+    }
+
+    pub fn zoom_state(&self) -> Option<chart::ZoomState> {
+        match self {
+            Content::Heatmap { chart: Some(c), .. } => Some(chart::zoom_state(c)),
+            Content::Kline { chart: Some(c), .. } => Some(chart::zoom_state(c)),
+            Content::Range { chart: Some(c), .. } => Some(chart::zoom_state(c)),
+            _ => None,
+        }
+    }
+
+    pub fn restore_zoom(&mut self, zoom: chart::ZoomState) {
+        match self {
+            Content::Heatmap { chart: Some(c), .. } => chart::restore_zoom(c, zoom),
+            Content::Kline { chart: Some(c), .. } => chart::restore_zoom(c, zoom),
+            Content::Range { chart: Some(c), .. } => chart::restore_zoom(c, zoom),
+            _ => {}
+        }
+    }
+
+    /// Clears the ruler measurement, returns whether there was one
+    pub fn clear_measurement(&mut self) -> bool {
+        match self {
+            Content::Heatmap { chart: Some(c), .. } => chart::clear_measurement(c),
+            Content::Kline { chart: Some(c), .. } => chart::clear_measurement(c),
+            Content::Range { chart: Some(c), .. } => chart::clear_measurement(c),
+            _ => false,
+        }
+    }
+
+    pub fn compare_overlays(&self) -> data::chart::kline::CompareOverlays {
+        match self {
+            Content::Kline { chart: Some(c), .. } => c.compare_overlays(),
+            _ => data::chart::kline::CompareOverlays::default(),
         }
     }
 
@@ -1871,10 +3545,16 @@ impl Content {
         match self {
             Content::Heatmap { indicators, .. } => column_drag::reorder_vec(indicators, event),
             Content::Kline { indicators, .. } => column_drag::reorder_vec(indicators, event),
-            Content::TimeAndSales(_)
+            Content::Range { .. }
+            | Content::TimeAndSales(_)
             | Content::Ladder(_)
             | Content::Starter
-            | Content::Comparison(_) => {
+            | Content::Comparison(_)
+            | Content::Correlation(_)
+            | Content::Statistics(_)
+            | Content::Spread(_)
+            | Content::MiniCharts { .. }
+            | Content::Unloaded { .. } => {
                 panic!("indicator reorder on {} pane", self)
             }
         }
@@ -1891,9 +3571,24 @@ impl Content {
             (Content::Ladder(Some(panel)), VisualConfig::Ladder(cfg)) => {
                 panel.config = cfg;
             }
+            (Content::Range { chart: Some(c), .. }, VisualConfig::Range(cfg)) => {
+                c.set_visual_config(cfg);
+            }
             (Content::Comparison(Some(chart)), VisualConfig::Comparison(cfg)) => {
                 chart.config = cfg;
             }
+            (Content::Correlation(Some(matrix)), VisualConfig::Correlation(cfg)) => {
+                matrix.set_window_bars(cfg.window_bars);
+            }
+            (Content::Statistics(Some(panel)), VisualConfig::Statistics(cfg)) => {
+                panel.set_config(cfg);
+            }
+            (Content::Spread(Some(chart)), VisualConfig::Spread(cfg)) => {
+                chart.set_config(cfg);
+            }
+            (Content::Kline { chart: Some(c), .. }, VisualConfig::Kline(cfg)) => {
+                c.apply_config(&cfg);
+            }
             _ => {}
         }
     }
@@ -1908,10 +3603,16 @@ impl Content {
                     None
                 }
             }
-            Content::TimeAndSales(_)
+            Content::Range { .. }
+            | Content::TimeAndSales(_)
             | Content::Ladder(_)
             | Content::Starter
-            | Content::Comparison(_) => None,
+            | Content::Comparison(_)
+            | Content::Correlation(_)
+            | Content::Statistics(_)
+            | Content::Spread(_)
+            | Content::MiniCharts { .. }
+            | Content::Unloaded { .. } => None,
         }
     }
 
@@ -1951,13 +3652,23 @@ impl Content {
         match self {
             Content::Heatmap { .. } => ContentKind::HeatmapChart,
             Content::Kline { kind, .. } => match kind {
+                data::chart::KlineChartKind::Footprint {
+                    depth_layer: Some(_),
+                    ..
+                } => ContentKind::HeatmapFootprint,
                 data::chart::KlineChartKind::Footprint { .. } => ContentKind::FootprintChart,
-                data::chart::KlineChartKind::Candles => ContentKind::CandlestickChart,
+                data::chart::KlineChartKind::Candles { .. } => ContentKind::CandlestickChart,
             },
+            Content::Range { .. } => ContentKind::RangeChart,
             Content::TimeAndSales(_) => ContentKind::TimeAndSales,
             Content::Ladder(_) => ContentKind::Ladder,
             Content::Comparison(_) => ContentKind::ComparisonChart,
+            Content::Correlation(_) => ContentKind::CorrelationMatrix,
+            Content::Statistics(_) => ContentKind::Statistics,
+            Content::Spread(_) => ContentKind::SpreadChart,
+            Content::MiniCharts { .. } => ContentKind::MiniCharts,
             Content::Starter => ContentKind::Starter,
+            Content::Unloaded { config } => config.content_kind(),
         }
     }
 
@@ -1965,10 +3676,15 @@ impl Content {
         match self {
             Content::Heatmap { chart, .. } => chart.is_some(),
             Content::Kline { chart, .. } => chart.is_some(),
+            Content::Range { chart, .. } => chart.is_some(),
             Content::TimeAndSales(panel) => panel.is_some(),
             Content::Ladder(panel) => panel.is_some(),
             Content::Comparison(chart) => chart.is_some(),
-            Content::Starter => true,
+            Content::Correlation(matrix) => matrix.is_some(),
+            Content::Statistics(panel) => panel.is_some(),
+            Content::Spread(chart) => chart.is_some(),
+            Content::Starter | Content::MiniCharts { .. } => true,
+            Content::Unloaded { .. } => false,
         }
     }
 }
@@ -1986,8 +3702,13 @@ impl PartialEq for Content {
             (Content::Starter, Content::Starter)
                 | (Content::Heatmap { .. }, Content::Heatmap { .. })
                 | (Content::Kline { .. }, Content::Kline { .. })
+                | (Content::Range { .. }, Content::Range { .. })
                 | (Content::TimeAndSales(_), Content::TimeAndSales(_))
                 | (Content::Ladder(_), Content::Ladder(_))
+                | (Content::Statistics(_), Content::Statistics(_))
+                | (Content::Spread(_), Content::Spread(_))
+                | (Content::MiniCharts { .. }, Content::MiniCharts { .. })
+                | (Content::Unloaded { .. }, Content::Unloaded { .. })
         )
     }
 }
@@ -2038,12 +3759,15 @@ fn ticksize_modifier<'a>(
     id: pane_grid::Pane,
     base_ticksize: f32,
     multiplier: TickMultiplier,
+    is_auto: bool,
     modifier: Option<modal::stream::Modifier>,
     kind: ModifierKind,
     exchange: Option<exchange::adapter::Exchange>,
 ) -> Element<'a, Message> {
     let modifier_modal = Modal::StreamModifier(
-        modal::stream::Modifier::new(kind).with_ticksize_view(base_ticksize, multiplier, exchange),
+        modal::stream::Modifier::new(kind)
+            .with_ticksize_view(base_ticksize, multiplier, exchange)
+            .with_auto_ticksize(is_auto),
     );
 
     let is_active = modifier.is_some_and(|m| {
@@ -2053,7 +3777,13 @@ fn ticksize_modifier<'a>(
         )
     });
 
-    button(text(multiplier.to_string()))
+    let label = if is_auto {
+        format!("Auto {multiplier}")
+    } else {
+        multiplier.to_string()
+    };
+
+    button(text(label))
         .style(move |theme, status| style::button::modifier(theme, status, !is_active))
         .on_press(Message::PaneEvent(id, Event::ShowModal(modifier_modal)))
         .into()
@@ -2078,6 +3808,269 @@ fn basis_modifier<'a>(
         .into()
 }
 
+/// Pane notifications stay in the pane's own corner, independent of the app-wide setting
+fn gap_repair_toast(outcome: GapRepairOutcome) -> Toast {
+    match outcome {
+        GapRepairOutcome::Repaired { requests } => {
+            Toast::info(format!("Kline gaps repaired with {requests} requests"))
+        }
+        GapRepairOutcome::GapsRemain { missing } => Toast::warn(format!(
+            "Gap repair finished, {missing} klines still missing"
+        )),
+    }
+}
+
+fn pane_toast_cfg() -> ToastConfig {
+    ToastConfig {
+        position: ToastPosition::TopRight,
+        ..ToastConfig::default()
+    }
+}
+
+fn export_modal<'a>(pane: pane_grid::Pane, scale: ExportScale) -> Element<'a, Message> {
+    let scale_picker = pick_list(ExportScale::ALL, Some(scale), move |scale| {
+        Message::PaneEvent(pane, Event::ExportScaleSelected(scale))
+    });
+
+    let content = column![
+        text("Save as PNG").size(14),
+        row![text("Resolution"), scale_picker]
+            .spacing(8)
+            .align_y(Alignment::Center),
+        button(text("Save...")).on_press(Message::PaneEvent(pane, Event::ExportPng(scale))),
+    ]
+    .spacing(12);
+
+    container(content)
+        .padding(16)
+        .style(style::chart_modal)
+        .into()
+}
+
+/// Saves the pane's kline setup under a name, or applies a saved one to the pane's ticker
+fn templates_modal<'a>(
+    pane: pane_grid::Pane,
+    name: &str,
+    saved: &'a [data::ChartTemplate],
+) -> Element<'a, Message> {
+    let can_save = !name.trim().is_empty();
+
+    let input = text_input("Template name", name)
+        .on_input(move |name| Message::PaneEvent(pane, Event::TemplateNameChanged(name)))
+        .on_submit_maybe(can_save.then_some(Message::PaneEvent(pane, Event::SaveTemplate)))
+        .width(Length::Fixed(180.0));
+    let save_btn = button(text("Save as template"))
+        .on_press_maybe(can_save.then_some(Message::PaneEvent(pane, Event::SaveTemplate)));
+
+    let mut list = column![].spacing(4);
+    if saved.is_empty() {
+        list = list.push(text("No saved templates").size(11));
+    }
+    for (index, template) in saved.iter().enumerate() {
+        list = list.push(
+            button(text(template.name.as_str()))
+                .width(Length::Fill)
+                .style(|theme, status| style::button::transparent(theme, status, false))
+                .on_press(Message::PaneEvent(pane, Event::LoadTemplate(index))),
+        );
+    }
+
+    let content = column![
+        text("Templates").size(14),
+        row![input, save_btn].spacing(8).align_y(Alignment::Center),
+        text("Load template").size(12),
+        iced::widget::scrollable(list).height(Length::Shrink),
+    ]
+    .spacing(12)
+    .max_width(320);
+
+    container(content)
+        .padding(16)
+        .style(style::chart_modal)
+        .into()
+}
+
+fn note_input_id(pane_id: uuid::Uuid) -> iced::widget::Id {
+    iced::widget::Id::from(format!("pane-note-{pane_id}"))
+}
+
+/// Multi-line editor that grows with the text, saving or a click outside keeps the draft
+fn note_modal<'a>(
+    pane: pane_grid::Pane,
+    pane_id: uuid::Uuid,
+    draft: &'a text_editor::Content,
+) -> Element<'a, Message> {
+    let editor = text_editor(draft)
+        .id(note_input_id(pane_id))
+        .placeholder("Trade thesis, levels to watch...")
+        .on_action(move |action| Message::PaneEvent(pane, Event::NoteEdited(action)))
+        .min_height(60.0)
+        .max_height(240.0)
+        .size(12);
+
+    let remaining = MAX_NOTE_LEN.saturating_sub(draft.text().chars().count());
+
+    let content = column![
+        text("Note").size(14),
+        editor,
+        row![
+            text(format!("{remaining} characters left")).size(11),
+            iced::widget::space::horizontal(),
+            button(text("Save").size(12)).on_press(Message::PaneEvent(pane, Event::NoteSubmitted)),
+        ]
+        .align_y(Vertical::Center),
+    ]
+    .spacing(8)
+    .width(Length::Fixed(260.0));
+
+    container(content)
+        .padding(16)
+        .style(style::chart_modal)
+        .into()
+}
+
+/// Bid/ask imbalance readout for the pane header, the bar fills from ask-heavy to bid-heavy
+fn imbalance_gauge<'a>(imbalance: f32) -> Element<'a, Message> {
+    let bar = iced::widget::progress_bar(-1.0..=1.0, imbalance)
+        .length(48)
+        .girth(4)
+        .style(move |theme: &Theme| {
+            if imbalance >= 0.0 {
+                iced::widget::progress_bar::success(theme)
+            } else {
+                iced::widget::progress_bar::danger(theme)
+            }
+        });
+
+    iced::widget::tooltip(
+        row![bar, text(format!("{imbalance:+.2}")).size(11)]
+            .spacing(4)
+            .align_y(Alignment::Center),
+        container(text("Depth imbalance, bids vs asks near mid").size(11))
+            .padding(8)
+            .style(style::tooltip),
+        tooltip::Position::Bottom,
+    )
+    .into()
+}
+
+/// Rolling window length of the statistics panel, adjustable from its header
+fn stats_window_slider<'a>(
+    pane: pane_grid::Pane,
+    cfg: data::chart::stats_panel::Config,
+) -> Element<'a, Message> {
+    let (min, max) = data::chart::stats_panel::Config::WINDOW_SECS_RANGE.into_inner();
+
+    let slider = iced::widget::slider(
+        min as f32..=max as f32,
+        cfg.window_secs as f32,
+        move |value| {
+            Message::VisualConfigChanged(
+                pane,
+                VisualConfig::Statistics(data::chart::stats_panel::Config {
+                    window_secs: value as u64,
+                }),
+                false,
+            )
+        },
+    )
+    .step(10.0)
+    .width(72);
+
+    iced::widget::tooltip(
+        row![slider, text(format!("{}s", cfg.window_secs)).size(11)]
+            .spacing(4)
+            .align_y(Alignment::Center),
+        container(text("Rolling window of the trade metrics").size(11))
+            .padding(8)
+            .style(style::tooltip),
+        tooltip::Position::Bottom,
+    )
+    .into()
+}
+
+/// How the chart scrolls when a new bar opens, picked from the pane header
+fn auto_scroll_picker<'a>(
+    pane: pane_grid::Pane,
+    mode: data::chart::AutoScrollMode,
+) -> Element<'a, Message> {
+    let picklist = pick_list(
+        data::chart::AutoScrollMode::CHOICES,
+        Some(mode),
+        move |mode| {
+            Message::PaneEvent(
+                pane,
+                Event::ChartInteraction(super::chart::Message::AutoScrollChanged(mode)),
+            )
+        },
+    )
+    .text_size(12);
+
+    iced::widget::tooltip(
+        picklist,
+        container(
+            text("Scrolling on new bars, paused while the latest bar is scrolled out of view")
+                .size(11),
+        )
+        .padding(8)
+        .style(style::tooltip),
+        tooltip::Position::Bottom,
+    )
+    .into()
+}
+
+/// Tabs of the timeframes a candle pane switches between, the shown one highlighted
+fn timeframe_tab_strip<'a>(
+    pane: pane_grid::Pane,
+    tabs: &[Timeframe],
+    shown: Basis,
+) -> Element<'a, Message> {
+    tabs.iter()
+        .fold(row![].spacing(2), |strip, timeframe| {
+            let is_shown = shown == Basis::Time(*timeframe);
+
+            strip.push(
+                button(text(timeframe.to_string()).size(11))
+                    .on_press(Message::PaneEvent(
+                        pane,
+                        Event::TimeframeTabSelected(*timeframe),
+                    ))
+                    .style(move |theme, status| {
+                        style::button::transparent(theme, status, is_shown)
+                    }),
+            )
+        })
+        .into()
+}
+
+/// Shows or hides the overview strip under a candle chart
+fn minimap_toggle<'a>(
+    pane: pane_grid::Pane,
+    cfg: data::chart::kline::Config,
+) -> Element<'a, Message> {
+    let is_shown = cfg.minimap;
+
+    let toggle = button(text("Map").size(11))
+        .on_press(Message::VisualConfigChanged(
+            pane,
+            VisualConfig::Kline(data::chart::kline::Config {
+                minimap: !is_shown,
+                ..cfg
+            }),
+            false,
+        ))
+        .style(move |theme, status| style::button::transparent(theme, status, is_shown));
+
+    iced::widget::tooltip(
+        toggle,
+        container(text("Overview of the whole history, click or drag it to scroll").size(11))
+            .padding(8)
+            .style(style::tooltip),
+        tooltip::Position::Bottom,
+    )
+    .into()
+}
+
 fn by_basis_default<T>(
     basis: Option<Basis>,
     default_tf: Timeframe,
@@ -1,4 +1,7 @@
 pub mod ladder;
+pub mod mini_charts;
+pub mod spread;
+pub mod statistics;
 pub mod timeandsales;
 
 use iced::{
@@ -1,5 +1,6 @@
 use super::Message;
 use crate::style;
+use data::panel::imbalance::ImbalanceGauge;
 use data::panel::ladder::{ChaseTracker, Config, GroupedDepth, Side, TradeStore};
 use exchange::Trade;
 use exchange::util::{Price, PriceStep};
@@ -65,6 +66,7 @@ pub struct Ladder {
     trades: TradeStore,
     pending_tick_size: Option<PriceStep>,
     raw_price_spread: Option<Price>,
+    imbalance: ImbalanceGauge,
 }
 
 impl Ladder {
@@ -81,6 +83,7 @@ impl Ladder {
             orderbook: [GroupedDepth::new(), GroupedDepth::new()],
             raw_price_spread: None,
             pending_tick_size: None,
+            imbalance: ImbalanceGauge::default(),
         }
     }
 
@@ -88,6 +91,7 @@ impl Ladder {
         if let Some(next) = self.pending_tick_size.take() {
             self.tick_size = next;
             self.trades.rebuild_grouped(self.tick_size);
+            self.orderbook.iter_mut().for_each(GroupedDepth::clear);
         }
 
         let raw_best_bid = depth.bids.last_key_value().map(|(p, _)| *p);
@@ -111,7 +115,11 @@ impl Ladder {
         let step = self.tick_size;
         self.trades.insert_trades(trades_buffer, step);
 
-        self.regroup_from_depth(depth);
+        self.regroup_from_depth(depth, update_t);
+
+        if self.config.show_imbalance {
+            self.imbalance.update(depth, self.config.imbalance);
+        }
 
         self.last_exchange_ts_ms = Some(update_t);
 
@@ -161,6 +169,15 @@ impl Ladder {
         self.invalidate(Some(Instant::now()));
     }
 
+    /// Smoothed bid/ask imbalance of the raw book, if enabled
+    pub fn imbalance(&self) -> Option<f32> {
+        if self.config.show_imbalance {
+            self.imbalance.value()
+        } else {
+            None
+        }
+    }
+
     pub fn set_show_chase_tracker(&mut self, enabled: bool) {
         if self.config.show_chase_tracker != enabled {
             self.config.show_chase_tracker = enabled;
@@ -173,11 +190,11 @@ impl Ladder {
         }
     }
 
-    fn regroup_from_depth(&mut self, depth: &Depth) {
+    fn regroup_from_depth(&mut self, depth: &Depth, update_t: u64) {
         let step = self.tick_size;
 
-        self.orderbook[Side::Ask.idx()].regroup_from_raw(&depth.asks, Side::Ask, step);
-        self.orderbook[Side::Bid.idx()].regroup_from_raw(&depth.bids, Side::Bid, step);
+        self.orderbook[Side::Ask.idx()].regroup_from_raw(&depth.asks, Side::Ask, step, update_t);
+        self.orderbook[Side::Bid.idx()].regroup_from_raw(&depth.bids, Side::Bid, step, update_t);
     }
 
     pub fn invalidate(&mut self, now: Option<Instant>) -> Option<super::Action> {
@@ -276,6 +293,14 @@ impl canvas::Program<Message> for Ladder {
 
                     match visible_row.row {
                         DomRow::Ask { price, qty } => {
+                            self.draw_depth_highlights(
+                                frame,
+                                visible_row,
+                                Side::Ask,
+                                &cols,
+                                &maxima,
+                                palette,
+                            );
                             self.draw_row(
                                 frame,
                                 visible_row.y,
@@ -294,6 +319,14 @@ impl canvas::Program<Message> for Ladder {
                             );
                         }
                         DomRow::Bid { price, qty } => {
+                            self.draw_depth_highlights(
+                                frame,
+                                visible_row,
+                                Side::Bid,
+                                &cols,
+                                &maxima,
+                                palette,
+                            );
                             self.draw_row(
                                 frame,
                                 visible_row.y,
@@ -440,6 +473,7 @@ impl canvas::Program<Message> for Ladder {
 struct Maxima {
     vis_max_order_qty: f32,
     vis_max_trade_qty: f32,
+    vis_max_cum_qty: f32,
 }
 
 struct VisibleRow {
@@ -447,6 +481,9 @@ struct VisibleRow {
     y: f32,
     buy_t: f32,
     sell_t: f32,
+    cum_qty: f32,
+    /// Whether the level grew and the remaining flash strength
+    change: Option<(bool, f32)>,
 }
 
 struct ColumnRanges {
@@ -662,6 +699,51 @@ impl Ladder {
         );
     }
 
+    /// Cumulative depth bar and size change flash behind the order quantity cell
+    fn draw_depth_highlights(
+        &self,
+        frame: &mut iced::widget::canvas::Frame,
+        row: &VisibleRow,
+        side: Side,
+        cols: &ColumnRanges,
+        maxima: &Maxima,
+        palette: &iced::theme::palette::Extended,
+    ) {
+        let (col, side_color) = match side {
+            Side::Bid => (cols.bid_order, palette.success.base.color),
+            Side::Ask => (cols.ask_order, palette.danger.base.color),
+        };
+
+        Self::fill_bar(
+            frame,
+            col,
+            row.y,
+            ROW_HEIGHT,
+            row.cum_qty,
+            maxima.vis_max_cum_qty,
+            side_color,
+            side.is_bid(),
+            0.08,
+        );
+
+        if let Some((increased, strength)) = row.change {
+            let color = if increased {
+                palette.success.strong.color
+            } else {
+                palette.danger.strong.color
+            };
+
+            frame.fill_rectangle(
+                Point::new(col.0, row.y),
+                Size::new(col.1 - col.0, ROW_HEIGHT),
+                iced::Color {
+                    a: 0.25 * strength,
+                    ..color
+                },
+            );
+        }
+    }
+
     fn fill_bar(
         frame: &mut iced::widget::canvas::Frame,
         (x_start, x_end): (f32, f32),
@@ -811,6 +893,8 @@ impl Ladder {
                         y: top_y_screen,
                         buy_t: 0.0,
                         sell_t: 0.0,
+                        cum_qty: 0.0,
+                        change: None,
                     });
                 }
                 continue;
@@ -821,6 +905,7 @@ impl Ladder {
             };
 
             let is_bid = idx > 0;
+            let side = if is_bid { Side::Bid } else { Side::Ask };
             let order_qty = if is_bid {
                 bids_grouped.get(&price).copied().unwrap_or(0.0)
             } else {
@@ -836,6 +921,21 @@ impl Ladder {
             let (buy_t, sell_t) = self.trade_qty_at(price);
             maxima.vis_max_trade_qty = maxima.vis_max_trade_qty.max(buy_t.max(sell_t));
 
+            let depth = &self.orderbook[side.idx()];
+            let cum_qty = if self.config.show_cumulative {
+                depth.cumulative_qty(side, price)
+            } else {
+                0.0
+            };
+            maxima.vis_max_cum_qty = maxima.vis_max_cum_qty.max(cum_qty);
+
+            let change = if self.config.highlight_changes {
+                self.last_exchange_ts_ms
+                    .and_then(|now_ms| depth.change_at(price, now_ms))
+            } else {
+                None
+            };
+
             let row = if is_bid {
                 DomRow::Bid {
                     price,
@@ -853,6 +953,8 @@ impl Ladder {
                 y: top_y_screen,
                 buy_t,
                 sell_t,
+                cum_qty,
+                change,
             });
         }
 
@@ -0,0 +1,152 @@
+use crate::style;
+use exchange::{Kline, TickerInfo};
+
+use iced::widget::canvas::{self, Canvas, Geometry, Path, Stroke};
+use iced::widget::{button, center, column, pane_grid, row, scrollable, text};
+use iced::{Element, Length, Point, Rectangle, Renderer, Size, Theme, mouse};
+
+/// Candles drawn per tile, the most recent ones
+pub const CANDLES: usize = 60;
+const TILE_WIDTH: f32 = 80.0;
+const TILE_HEIGHT: f32 = 50.0;
+/// Room left at the top of a tile for the ticker label
+const LABEL_HEIGHT: f32 = 12.0;
+
+/// Recent candles of a ticker, copied from the kline pane that streams it
+pub struct MiniSeries {
+    pub pane: pane_grid::Pane,
+    pub ticker_info: TickerInfo,
+    pub klines: Vec<Kline>,
+}
+
+/// Tiles every series in a grid of `columns`, pressing one selects its source pane
+pub fn view<'a, M: Clone + 'a>(
+    series: &[MiniSeries],
+    columns: usize,
+    on_select: impl Fn(pane_grid::Pane) -> M,
+) -> Element<'a, M> {
+    if series.is_empty() {
+        return center(text("Open candlestick or footprint charts to see them here").size(14))
+            .into();
+    }
+
+    let mut grid = column![].spacing(4);
+    for chunk in series.chunks(columns.max(1)) {
+        let mut tiles = row![].spacing(4);
+
+        for s in chunk {
+            let symbol = s.ticker_info.ticker.display_symbol_and_type().0;
+            let chart = Canvas::new(MiniChart {
+                klines: s.klines.clone(),
+                symbol,
+            })
+            .width(TILE_WIDTH)
+            .height(TILE_HEIGHT);
+
+            tiles = tiles.push(
+                button(chart)
+                    .padding(0)
+                    .on_press(on_select(s.pane))
+                    .style(|theme, status| style::button::transparent(theme, status, false)),
+            );
+        }
+
+        grid = grid.push(tiles);
+    }
+
+    scrollable(grid.padding(4))
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+}
+
+/// Net buy minus sell volume over the candles
+fn net_delta(klines: &[Kline]) -> f32 {
+    klines
+        .iter()
+        .map(|kline| kline.volume.0 - kline.volume.1)
+        .sum()
+}
+
+/// Sparkline candles of one ticker, tinted by the net delta of the shown range
+struct MiniChart {
+    klines: Vec<Kline>,
+    symbol: String,
+}
+
+impl<M> canvas::Program<M> for MiniChart {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let palette = theme.extended_palette();
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+
+        let delta = net_delta(&self.klines);
+        let tint = if delta >= 0.0 {
+            palette.success.base.color
+        } else {
+            palette.danger.base.color
+        };
+        frame.fill_rectangle(Point::ORIGIN, bounds.size(), tint.scale_alpha(0.12));
+
+        let (low, high) = self
+            .klines
+            .iter()
+            .fold((f32::MAX, f32::MIN), |(low, high), k| {
+                (low.min(k.low.to_f32()), high.max(k.high.to_f32()))
+            });
+
+        if !self.klines.is_empty() {
+            let plot_height = (bounds.height - LABEL_HEIGHT).max(1.0);
+            let range = (high - low).max(f32::EPSILON);
+            let to_y = |price: f32| LABEL_HEIGHT + (high - price) / range * plot_height;
+
+            let cell_width = bounds.width / CANDLES as f32;
+            let body_width = (cell_width * 0.7).max(1.0);
+            // the latest candle sits at the right edge, like on the full chart
+            let offset = CANDLES.saturating_sub(self.klines.len()) as f32 * cell_width;
+
+            for (i, kline) in self.klines.iter().enumerate() {
+                let color = if kline.close >= kline.open {
+                    palette.success.base.color
+                } else {
+                    palette.danger.base.color
+                };
+                let center_x = offset + (i as f32 + 0.5) * cell_width;
+
+                frame.stroke(
+                    &Path::line(
+                        Point::new(center_x, to_y(kline.high.to_f32())),
+                        Point::new(center_x, to_y(kline.low.to_f32())),
+                    ),
+                    Stroke::default().with_color(color).with_width(1.0),
+                );
+
+                let (open_y, close_y) = (to_y(kline.open.to_f32()), to_y(kline.close.to_f32()));
+                frame.fill_rectangle(
+                    Point::new(center_x - body_width / 2.0, open_y.min(close_y)),
+                    Size::new(body_width, (open_y - close_y).abs().max(1.0)),
+                    color,
+                );
+            }
+        }
+
+        frame.fill_text(canvas::Text {
+            content: self.symbol.clone(),
+            position: Point::new(2.0, 1.0),
+            color: palette.background.base.text,
+            size: iced::Pixels(10.0),
+            font: style::AZERET_MONO,
+            ..canvas::Text::default()
+        });
+
+        vec![frame.into_geometry()]
+    }
+}
@@ -0,0 +1,193 @@
+use crate::style;
+pub use data::chart::spread::Config;
+use data::chart::spread::{SpreadSeries, SpreadUnit, rolling_average, summary};
+use exchange::depth::Depth;
+
+use iced::widget::canvas::{self, Canvas, Geometry, Path, Stroke};
+use iced::widget::{center, column, container, stack, text};
+use iced::{Alignment, Element, Length, Point, Rectangle, Renderer, Theme, mouse};
+use std::time::Instant;
+
+/// Horizontal grid lines drawn over the spread range, labeled at the right edge
+const GRID_LINES: usize = 4;
+const LABELS_WIDTH: f32 = 48.0;
+
+/// Best bid/ask spread of a single ticker over time, with a rolling average line
+pub struct SpreadChart {
+    pub config: Config,
+    series: SpreadSeries,
+    tick_size: f32,
+    last_tick: Instant,
+}
+
+impl SpreadChart {
+    pub fn new(config: Option<Config>, tick_size: f32) -> Self {
+        Self {
+            config: config.unwrap_or_default(),
+            series: SpreadSeries::default(),
+            tick_size,
+            last_tick: Instant::now(),
+        }
+    }
+
+    pub fn insert_depth(&mut self, depth_update_t: u64, depth: &Depth) {
+        self.series.insert_depth(depth_update_t, depth);
+    }
+
+    pub fn set_config(&mut self, config: Config) {
+        self.config = config;
+    }
+
+    pub fn last_update(&self) -> Instant {
+        self.last_tick
+    }
+
+    pub fn invalidate(&mut self, now: Option<Instant>) {
+        if let Some(now) = now {
+            self.last_tick = now;
+        }
+    }
+
+    pub fn view<M: 'static>(&self) -> Element<'_, M> {
+        if self.series.is_empty() {
+            return center(text("Waiting for data...").size(16)).into();
+        }
+
+        let unit = self.config.unit;
+        let values = self.series.values(unit, self.tick_size);
+        let average = rolling_average(&values, self.config.avg_periods);
+
+        let readout = summary(&values).map(|summary| {
+            let line = |label: &str, value: f32| {
+                text(format!("{label:<4}{}", format_spread(value, unit)))
+                    .size(11)
+                    .font(style::AZERET_MONO)
+            };
+
+            container(
+                column![
+                    line("Cur", summary.current),
+                    line("Avg", summary.average),
+                    line("Max", summary.max),
+                ]
+                .spacing(2),
+            )
+            .padding(4)
+            .style(style::tooltip)
+        });
+
+        let plot = Canvas::new(SpreadPlot {
+            values,
+            average,
+            unit,
+        })
+        .width(Length::Fill)
+        .height(Length::Fill);
+
+        let mut content = stack![plot];
+        if let Some(readout) = readout {
+            content = content.push(container(readout).padding(8).align_x(Alignment::Start));
+        }
+
+        container(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+}
+
+fn format_spread(value: f32, unit: SpreadUnit) -> String {
+    match unit {
+        SpreadUnit::Ticks => format!("{value:.1}t"),
+        SpreadUnit::Bps => format!("{value:.2}bp"),
+    }
+}
+
+/// Spread line and its rolling average, from zero up to the largest spread shown
+struct SpreadPlot {
+    values: Vec<(u64, f32)>,
+    average: Vec<(u64, f32)>,
+    unit: SpreadUnit,
+}
+
+impl<M> canvas::Program<M> for SpreadPlot {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let palette = theme.extended_palette();
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+
+        let (Some((first, _)), Some((last, _))) = (self.values.first(), self.values.last()) else {
+            return vec![frame.into_geometry()];
+        };
+
+        let plot_width = (bounds.width - LABELS_WIDTH).max(1.0);
+        let max = self
+            .values
+            .iter()
+            .map(|(_, value)| *value)
+            .fold(f32::EPSILON, f32::max)
+            * 1.1;
+        let span_ms = last.saturating_sub(*first).max(1) as f32;
+
+        let to_x = |time: u64| time.saturating_sub(*first) as f32 / span_ms * plot_width;
+        let to_y = |value: f32| bounds.height - value / max * bounds.height;
+
+        for i in 0..GRID_LINES {
+            let value = max * i as f32 / GRID_LINES as f32;
+            let y = to_y(value);
+
+            frame.stroke(
+                &Path::line(Point::new(0.0, y), Point::new(plot_width, y)),
+                Stroke::default()
+                    .with_color(palette.background.strong.color.scale_alpha(0.4))
+                    .with_width(1.0),
+            );
+            frame.fill_text(canvas::Text {
+                content: format_spread(value, self.unit),
+                position: Point::new(bounds.width - 4.0, y),
+                color: palette.background.base.text.scale_alpha(0.6),
+                size: iced::Pixels(10.0),
+                font: style::AZERET_MONO,
+                align_x: iced::Alignment::End.into(),
+                align_y: iced::Alignment::End.into(),
+                ..canvas::Text::default()
+            });
+        }
+
+        let line = |points: &[(u64, f32)]| {
+            Path::new(|builder| {
+                for (i, (time, value)) in points.iter().enumerate() {
+                    let point = Point::new(to_x(*time), to_y(*value));
+                    if i == 0 {
+                        builder.move_to(point);
+                    } else {
+                        builder.line_to(point);
+                    }
+                }
+            })
+        };
+
+        frame.stroke(
+            &line(&self.values),
+            Stroke::default()
+                .with_color(palette.secondary.strong.color)
+                .with_width(1.0),
+        );
+        frame.stroke(
+            &line(&self.average),
+            Stroke::default()
+                .with_color(palette.primary.base.color)
+                .with_width(1.5),
+        );
+
+        vec![frame.into_geometry()]
+    }
+}
@@ -0,0 +1,175 @@
+use crate::style;
+pub use data::chart::stats_panel::Config;
+use data::chart::stats_panel::{Metric, RollingStats};
+use data::util::abbr_large_numbers;
+use exchange::Trade;
+use exchange::depth::Depth;
+
+use iced::widget::canvas::{self, Canvas, Geometry, Path, Stroke};
+use iced::widget::{center, column, container, row, text};
+use iced::{Alignment, Element, Length, Point, Rectangle, Renderer, Theme, mouse};
+use std::time::Instant;
+
+const ROW_HEIGHT: f32 = 28.0;
+const LABEL_WIDTH: f32 = 104.0;
+const VALUE_WIDTH: f32 = 72.0;
+
+/// Rolling trade and depth metrics of a single ticker, one table row per metric
+pub struct StatisticsPanel {
+    pub config: Config,
+    stats: RollingStats,
+    last_tick: Instant,
+}
+
+impl StatisticsPanel {
+    pub fn new(config: Option<Config>) -> Self {
+        let config = config.unwrap_or_default();
+
+        Self {
+            config,
+            stats: RollingStats::new(config),
+            last_tick: Instant::now(),
+        }
+    }
+
+    pub fn insert_buffers(&mut self, depth_update_t: u64, depth: &Depth, trades_buffer: &[Trade]) {
+        self.stats.insert_trades(trades_buffer);
+        self.stats.insert_depth(depth_update_t, depth);
+    }
+
+    pub fn set_config(&mut self, config: Config) {
+        if config.window_secs != self.config.window_secs {
+            self.stats.set_window(config.window_secs);
+        }
+        self.config = config;
+    }
+
+    pub fn last_update(&self) -> Instant {
+        self.last_tick
+    }
+
+    pub fn invalidate(&mut self, now: Option<Instant>) {
+        if let Some(now) = now {
+            self.last_tick = now;
+        }
+    }
+
+    pub fn view<M: 'static>(&self) -> Element<'_, M> {
+        if self.stats.is_empty() {
+            return center(text("Waiting for data...").size(16)).into();
+        }
+
+        let current = self.stats.current();
+
+        let rows = Metric::ALL.iter().map(|metric| {
+            let value = metric
+                .value(current)
+                .map_or_else(|| "-".to_string(), |v| format_metric(*metric, v));
+
+            row![
+                container(text(metric.to_string()).size(12)).width(LABEL_WIDTH),
+                container(text(value).size(12).font(style::AZERET_MONO))
+                    .width(VALUE_WIDTH)
+                    .align_x(Alignment::End),
+                Canvas::new(Sparkline {
+                    values: self.stats.history(*metric),
+                    signed: *metric == Metric::DeltaPerSec,
+                })
+                .width(Length::Fill)
+                .height(ROW_HEIGHT - 8.0),
+            ]
+            .spacing(8)
+            .height(ROW_HEIGHT)
+            .align_y(Alignment::Center)
+            .into()
+        });
+
+        container(column(rows).spacing(2))
+            .padding(8)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+}
+
+fn format_metric(metric: Metric, value: f32) -> String {
+    match metric {
+        Metric::BidAskRatio | Metric::LargeSmallRatio => format!("{value:.2}"),
+        Metric::DeltaPerSec if value > 0.0 => format!("+{}", abbr_large_numbers(value)),
+        Metric::DeltaPerSec | Metric::AvgTradeSize => abbr_large_numbers(value),
+        Metric::TradesPerMin => format!("{value:.0}"),
+    }
+}
+
+/// Line over a metric's recent samples, scaled to their own min and max
+struct Sparkline {
+    values: Vec<f32>,
+    /// Draws a zero line and colors the line by the sign of the latest value
+    signed: bool,
+}
+
+impl<M> canvas::Program<M> for Sparkline {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let palette = theme.extended_palette();
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+
+        if self.values.len() < 2 {
+            return vec![frame.into_geometry()];
+        }
+
+        let (mut min, mut max) = self
+            .values
+            .iter()
+            .fold((f32::MAX, f32::MIN), |(lo, hi), v| (lo.min(*v), hi.max(*v)));
+        if self.signed {
+            min = min.min(0.0);
+            max = max.max(0.0);
+        }
+        let span = (max - min).max(f32::EPSILON);
+
+        let step = bounds.width / (self.values.len() - 1) as f32;
+        let to_y = |v: f32| bounds.height - (v - min) / span * bounds.height;
+
+        if self.signed {
+            let zero = Path::line(
+                Point::new(0.0, to_y(0.0)),
+                Point::new(bounds.width, to_y(0.0)),
+            );
+            frame.stroke(
+                &zero,
+                Stroke::default()
+                    .with_color(palette.background.strong.color)
+                    .with_width(1.0),
+            );
+        }
+
+        let line = Path::new(|builder| {
+            for (i, value) in self.values.iter().enumerate() {
+                let point = Point::new(i as f32 * step, to_y(*value));
+                if i == 0 {
+                    builder.move_to(point);
+                } else {
+                    builder.line_to(point);
+                }
+            }
+        });
+
+        let color = match self.values.last() {
+            Some(v) if self.signed && *v < 0.0 => palette.danger.base.color,
+            Some(_) if self.signed => palette.success.base.color,
+            _ => palette.secondary.strong.color,
+        };
+        frame.stroke(&line, Stroke::default().with_color(color).with_width(1.0));
+
+        vec![frame.into_geometry()]
+    }
+}
@@ -2,28 +2,56 @@ use super::tickers_table::{self, TickersTable};
 use crate::{
     TooltipPosition,
     layout::SavedState,
-    style::{Icon, icon_text},
-    widget::button_with_tooltip,
+    style::{self, Icon, icon_text},
+    widget::{button_with_tooltip, column_drag, dragger_row},
 };
 use data::sidebar;
+use exchange::{HealthStatus, Ticker};
 
 use iced::{
-    Alignment, Element, Subscription, Task,
+    Alignment, Element, Length, Subscription, Task,
     widget::responsive,
-    widget::{column, row, space},
+    widget::{button, column, container, mouse_area, row, scrollable, space, text, text_input},
 };
 use rustc_hash::FxHashMap;
 
+const WATCHLIST_MAX_HEIGHT: f32 = 240.0;
+
 #[derive(Debug, Clone)]
 pub enum Message {
     ToggleSidebarMenu(Option<sidebar::Menu>),
     SetSidebarPosition(sidebar::Position),
     TickersTable(super::tickers_table::Message),
+    CreateGroup(String),
+    RenameGroup(usize, String),
+    DeleteGroup(usize),
+    MoveTickerToGroup(Ticker, usize),
+    RemoveTickerFromGroup(Ticker),
+    ReorderGroup(usize, usize),
+    ToggleGroupCollapsed(usize),
+    EditGroupName(Option<(usize, String)>),
+    NewGroupNameChanged(String),
+    DragTicker(Option<Ticker>),
+    /// Pressed the resize handle, `true` if dragging rightward widens the sidebar
+    StartResize(bool),
+    Resize(f32),
+    EndResize,
+}
+
+/// An ongoing drag of the resize handle
+struct Resize {
+    grows_rightward: bool,
+    /// Cursor x and sidebar width when the drag first moved
+    anchor: Option<(f32, f32)>,
 }
 
 pub struct Sidebar {
     pub state: data::Sidebar,
     pub tickers_table: TickersTable,
+    new_group_name: String,
+    renaming_group: Option<(usize, String)>,
+    dragging_ticker: Option<Ticker>,
+    resizing: Option<Resize>,
 }
 
 pub enum Action {
@@ -47,6 +75,10 @@ impl Sidebar {
             Self {
                 state: state.sidebar.clone(),
                 tickers_table,
+                new_group_name: String::new(),
+                renaming_group: None,
+                dragging_ticker: None,
+                resizing: None,
             },
             initial_fetch.map(Message::TickersTable),
         )
@@ -79,15 +111,92 @@ impl Sidebar {
                     Some(tickers_table::Action::FocusWidget(id)) => {
                         return (iced::widget::operation::focus(id), None);
                     }
+                    Some(tickers_table::Action::WatchlistDragStarted(ticker)) => {
+                        self.dragging_ticker = Some(ticker);
+                    }
                     None => {}
                 }
             }
+            Message::CreateGroup(name) => {
+                self.state.create_group(&name);
+                self.new_group_name.clear();
+            }
+            Message::RenameGroup(index, name) => {
+                self.state.rename_group(index, &name);
+                self.renaming_group = None;
+            }
+            Message::DeleteGroup(index) => {
+                self.state.delete_group(index);
+                self.renaming_group = None;
+            }
+            Message::MoveTickerToGroup(ticker, index) => {
+                self.state.move_ticker_to_group(ticker, index);
+                self.dragging_ticker = None;
+            }
+            Message::RemoveTickerFromGroup(ticker) => {
+                self.state.remove_ticker_from_groups(&ticker);
+            }
+            Message::ReorderGroup(from, to) => {
+                self.state.reorder_group(from, to);
+                self.renaming_group = None;
+            }
+            Message::ToggleGroupCollapsed(index) => {
+                if let Some(group) = self.state.watchlist.get_mut(index) {
+                    group.collapsed = !group.collapsed;
+                }
+            }
+            Message::EditGroupName(editing) => {
+                self.renaming_group = editing;
+            }
+            Message::NewGroupNameChanged(name) => {
+                self.new_group_name = name;
+            }
+            Message::DragTicker(ticker) => {
+                self.dragging_ticker = ticker;
+            }
+            Message::StartResize(grows_rightward) => {
+                self.resizing = Some(Resize {
+                    grows_rightward,
+                    anchor: None,
+                });
+            }
+            Message::Resize(x) => {
+                let is_table_open = self.tickers_table.is_shown;
+                let current_width = if is_table_open {
+                    self.state.width
+                } else {
+                    sidebar::ICON_COLUMN_WIDTH
+                };
+
+                let Some(resize) = self.resizing.as_mut() else {
+                    return (Task::none(), None);
+                };
+                let (start_x, start_width) = *resize.anchor.get_or_insert((x, current_width));
+
+                let delta = if resize.grows_rightward {
+                    x - start_x
+                } else {
+                    start_x - x
+                };
+
+                if self.state.drag_width(start_width + delta) != is_table_open {
+                    return self.update(Message::TickersTable(tickers_table::Message::ToggleTable));
+                }
+            }
+            Message::EndResize => {
+                self.resizing = None;
+            }
         }
 
         (Task::none(), None)
     }
 
-    pub fn view(&self, audio_volume: Option<f32>) -> Element<'_, Message> {
+    /// `stream_health` is the worst status among the open streams, tinting the connections button
+    pub fn view(
+        &self,
+        audio_volume: Option<f32>,
+        stream_health: Option<HealthStatus>,
+    ) -> Element<'_, Message> {
         let state = &self.state;
 
         let tooltip_position = if state.position == sidebar::Position::Left {
@@ -98,23 +207,177 @@ impl Sidebar {
 
         let is_table_open = self.tickers_table.is_shown;
 
-        let nav_buttons = self.nav_buttons(is_table_open, audio_volume, tooltip_position);
+        let nav_buttons =
+            self.nav_buttons(is_table_open, audio_volume, stream_health, tooltip_position);
 
         let tickers_table = if is_table_open {
-            column![responsive(move |size| self
-                .tickers_table
-                .view(size)
-                .map(Message::TickersTable))]
-            .width(200)
+            column![
+                self.watchlist_view(),
+                responsive(move |size| self.tickers_table.view(size).map(Message::TickersTable))
+            ]
+            .spacing(8)
+            .width(state.list_width())
         } else {
             column![]
         };
 
-        match state.position {
+        let content = match state.position {
             sidebar::Position::Left => row![nav_buttons, tickers_table],
             sidebar::Position::Right => row![tickers_table, nav_buttons],
         }
-        .spacing(if is_table_open { 8 } else { 4 })
+        .spacing(if is_table_open { 8 } else { 4 });
+
+        if self.dragging_ticker.is_some() {
+            mouse_area(content)
+                .on_release(Message::DragTicker(None))
+                .interaction(iced::mouse::Interaction::Grabbing)
+                .into()
+        } else {
+            content.into()
+        }
+    }
+
+    /// Draggable strip between the sidebar and the dashboard
+    pub fn resize_handle(&self, grows_rightward: bool) -> Element<'_, Message> {
+        mouse_area(container(space::horizontal()).width(4).height(Length::Fill))
+            .on_press(Message::StartResize(grows_rightward))
+            .interaction(iced::mouse::Interaction::ResizingHorizontally)
+            .into()
+    }
+
+    pub fn is_resizing(&self) -> bool {
+        self.resizing.is_some()
+    }
+
+    fn watchlist_view(&self) -> Element<'_, Message> {
+        let new_group_input = row![
+            text_input("New watchlist group", &self.new_group_name)
+                .on_input(Message::NewGroupNameChanged)
+                .on_submit(Message::CreateGroup(self.new_group_name.clone()))
+                .size(12),
+            button(text("+").size(12))
+                .on_press(Message::CreateGroup(self.new_group_name.clone()))
+                .style(|theme, status| style::button::transparent(theme, status, false)),
+        ]
+        .spacing(4)
+        .align_y(Alignment::Center);
+
+        let groups = self
+            .state
+            .watchlist
+            .iter()
+            .enumerate()
+            .map(|(index, group)| dragger_row(self.watchlist_group(index, group), true))
+            .collect::<Vec<_>>();
+
+        let groups_list: Element<'_, Message> = column_drag::Column::with_children(groups)
+            .on_drag(|event| match event {
+                column_drag::DragEvent::Dropped {
+                    index,
+                    target_index,
+                } => Message::ReorderGroup(index, target_index),
+                column_drag::DragEvent::Picked { index }
+                | column_drag::DragEvent::Canceled { index } => Message::ReorderGroup(index, index),
+            })
+            .spacing(4)
+            .into();
+
+        column![
+            new_group_input,
+            container(scrollable::Scrollable::with_direction(
+                groups_list,
+                scrollable::Direction::Vertical(
+                    scrollable::Scrollbar::new().width(4).scroller_width(4),
+                ),
+            ))
+            .max_height(WATCHLIST_MAX_HEIGHT),
+        ]
+        .spacing(8)
+        .into()
+    }
+
+    fn watchlist_group<'a>(
+        &'a self,
+        index: usize,
+        group: &'a sidebar::WatchlistGroup,
+    ) -> Element<'a, Message> {
+        let header: Element<'_, Message> = match &self.renaming_group {
+            Some((renaming_index, name)) if *renaming_index == index => row![
+                text_input("Group name", name)
+                    .on_input(move |new_name| Message::EditGroupName(Some((index, new_name))))
+                    .on_submit(Message::RenameGroup(index, name.clone()))
+                    .size(12),
+                button(icon_text(Icon::Return, 11))
+                    .on_press(Message::EditGroupName(None))
+                    .style(|theme, status| style::button::transparent(theme, status, false)),
+            ]
+            .spacing(2)
+            .align_y(Alignment::Center)
+            .into(),
+            _ => row![
+                button(text(format!("{} ({})", group.name, group.tickers.len())).size(12))
+                    .on_press(Message::ToggleGroupCollapsed(index))
+                    .width(Length::Fill)
+                    .style(move |theme, status| {
+                        style::button::transparent(theme, status, !group.collapsed)
+                    }),
+                button(icon_text(Icon::Edit, 11))
+                    .on_press(Message::EditGroupName(Some((index, group.name.clone()))))
+                    .style(|theme, status| style::button::transparent(theme, status, false)),
+                button(icon_text(Icon::TrashBin, 11))
+                    .on_press(Message::DeleteGroup(index))
+                    .style(|theme, status| style::button::transparent(theme, status, false)),
+            ]
+            .spacing(2)
+            .align_y(Alignment::Center)
+            .into(),
+        };
+
+        let header = match self.dragging_ticker {
+            Some(ticker) => mouse_area(header)
+                .on_release(Message::MoveTickerToGroup(ticker, index))
+                .into(),
+            None => header,
+        };
+
+        let mut content = column![header].spacing(2);
+
+        if !group.collapsed {
+            for ticker in &group.tickers {
+                content = content.push(self.watchlist_ticker(*ticker));
+            }
+        }
+
+        content.into()
+    }
+
+    fn watchlist_ticker(&self, ticker: Ticker) -> Element<'_, Message> {
+        let (symbol, _market) = ticker.display_symbol_and_type();
+
+        row![
+            mouse_area(container(icon_text(Icon::DragHandle, 10)).padding(2))
+                .on_press(Message::DragTicker(Some(ticker)))
+                .interaction(iced::mouse::Interaction::Grab),
+            button(
+                row![
+                    icon_text(style::exchange_icon(ticker.exchange), 11),
+                    text(symbol).size(12),
+                ]
+                .spacing(4)
+                .align_y(Alignment::Center),
+            )
+            .on_press(Message::TickersTable(
+                tickers_table::Message::TickerSelected(ticker, None),
+            ))
+            .width(Length::Fill)
+            .style(|theme, status| style::button::transparent(theme, status, false)),
+            button(icon_text(Icon::Close, 10))
+                .on_press(Message::RemoveTickerFromGroup(ticker))
+                .style(|theme, status| style::button::transparent(theme, status, false)),
+        ]
+        .spacing(2)
+        .padding(iced::padding::left(8))
+        .align_y(Alignment::Center)
         .into()
     }
 
@@ -126,6 +389,7 @@ impl Sidebar {
         &self,
         is_table_open: bool,
         audio_volume: Option<f32>,
+        stream_health: Option<HealthStatus>,
         tooltip_position: TooltipPosition,
     ) -> iced::widget::Column<'_, Message> {
         let settings_modal_button = {
@@ -189,10 +453,50 @@ impl Sidebar {
             )
         };
 
+        let replay_btn = {
+            let is_active = self.is_menu_active(sidebar::Menu::Replay);
+
+            button_with_tooltip(
+                icon_text(Icon::ChartOutline, 14)
+                    .width(24)
+                    .align_x(Alignment::Center),
+                Message::ToggleSidebarMenu(Some(sidebar::Menu::Replay)),
+                Some("Replay"),
+                tooltip_position,
+                move |theme, status| crate::style::button::transparent(theme, status, is_active),
+            )
+        };
+
+        let connections_btn = {
+            let is_active = self.is_menu_active(sidebar::Menu::Connections);
+            let tooltip = match stream_health {
+                Some(HealthStatus::Disconnected) => "Connections: a stream is disconnected",
+                Some(HealthStatus::Degraded) => "Connections: a stream is degraded",
+                Some(HealthStatus::Healthy) | None => "Connections",
+            };
+
+            button_with_tooltip(
+                icon_text(Icon::Link, 14)
+                    .width(24)
+                    .align_x(Alignment::Center)
+                    .style(move |theme| iced::widget::text::Style {
+                        color: stream_health
+                            .filter(|status| *status != HealthStatus::Healthy)
+                            .map(|status| style::health_color(theme, status)),
+                    }),
+                Message::ToggleSidebarMenu(Some(sidebar::Menu::Connections)),
+                Some(tooltip),
+                tooltip_position,
+                move |theme, status| crate::style::button::transparent(theme, status, is_active),
+            )
+        };
+
         column![
             ticker_search_button,
             layout_modal_button,
             audio_btn,
+            replay_btn,
+            connections_btn,
             space::vertical(),
             settings_modal_button,
         ]
@@ -1,13 +1,14 @@
 use crate::{
     modal::pane::mini_tickers_list::RowSelection,
     style::{self, Icon, icon_text},
+    tooltip,
 };
 use data::{
     InternalError,
     layout::pane::ContentKind,
     tickers_table::{
         PriceChangeDirection, Settings, SortOptions, TickerDisplayData, TickerRowData,
-        compute_display_data,
+        compute_display_data, push_recent,
     },
 };
 use exchange::{
@@ -19,9 +20,10 @@ use iced::{
     alignment::{self, Horizontal, Vertical},
     padding,
     widget::{
-        Button, Space, button, column, container, row, rule,
+        Button, Space, button, column, container, mouse_area, row, rule,
         scrollable::{self, AbsoluteOffset},
         space, text, text_input,
+        tooltip::Position as TooltipPosition,
     },
 };
 use rustc_hash::{FxHashMap, FxHashSet};
@@ -42,6 +44,9 @@ const SORT_AND_FILTER_HEIGHT: f32 = 200.0;
 
 const COMPACT_ROW_HEIGHT: f32 = 28.0;
 
+/// One row of recent or favorite chips, including the spacing below it
+const QUICK_ACCESS_ROW_HEIGHT: f32 = 32.0;
+
 const EXCHANGE_FILTERS: [(ExchangeInclusive, Exchange, &str); 4] = [
     (ExchangeInclusive::Bybit, Exchange::BybitLinear, "Bybit"),
     (
@@ -76,6 +81,7 @@ pub enum Action {
     ErrorOccurred(data::InternalError),
     Fetch(Task<Message>),
     FocusWidget(iced::widget::Id),
+    WatchlistDragStarted(Ticker),
 }
 
 #[derive(Debug, Clone)]
@@ -86,6 +92,7 @@ pub enum Message {
     TickerSelected(Ticker, Option<ContentKind>),
     ExpandTickerCard(Option<Ticker>),
     FavoriteTicker(Ticker),
+    WatchlistDragStarted(Ticker),
     Scrolled(scrollable::Viewport),
     ToggleMarketFilter(MarketKind),
     ToggleExchangeFilter(ExchangeInclusive),
@@ -100,6 +107,7 @@ pub enum Message {
 pub struct TickersTable {
     ticker_rows: Vec<TickerRowData>,
     pub favorited_tickers: FxHashSet<Ticker>,
+    recent_tickers: Vec<Ticker>,
     display_cache: FxHashMap<Ticker, TickerDisplayData>,
     search_query: String,
     show_sort_options: bool,
@@ -126,6 +134,7 @@ impl TickersTable {
                 ticker_rows: Vec::new(),
                 display_cache: FxHashMap::default(),
                 favorited_tickers: settings.favorited_tickers.iter().cloned().collect(),
+                recent_tickers: settings.recent_tickers.clone(),
                 search_query: String::new(),
                 show_sort_options: false,
                 selected_sort_option: settings.selected_sort_option,
@@ -146,6 +155,7 @@ impl TickersTable {
     pub fn settings(&self) -> Settings {
         Settings {
             favorited_tickers: self.favorited_tickers.iter().copied().collect(),
+            recent_tickers: self.recent_tickers.clone(),
             show_favorites: self.show_favorites,
             selected_sort_option: self.selected_sort_option,
             selected_exchanges: self.selected_exchanges.iter().cloned().collect(),
@@ -170,6 +180,9 @@ impl TickersTable {
             Message::FavoriteTicker(ticker) => {
                 self.favorite_ticker(ticker);
             }
+            Message::WatchlistDragStarted(ticker) => {
+                return Some(Action::WatchlistDragStarted(ticker));
+            }
             Message::Scrolled(viewport) => {
                 self.scroll_offset = viewport.absolute_offset();
             }
@@ -194,6 +207,7 @@ impl TickersTable {
                 let ticker_info = self.tickers_info.get(&ticker).cloned().flatten();
 
                 if let Some(ticker_info) = ticker_info {
+                    push_recent(&mut self.recent_tickers, ticker);
                     return Some(Action::TickerSelected(ticker_info, content));
                 } else {
                     log::warn!(
@@ -319,6 +333,12 @@ impl TickersTable {
             .padding(padding::right(8))
             .width(Length::Fill);
 
+        if let Some(recents) = self.chips_row(Icon::Return, &self.recent_tickers) {
+            content = content.push(recents);
+        }
+        if let Some(favorites) = self.chips_row(Icon::StarFilled, &self.sorted_favorites()) {
+            content = content.push(favorites);
+        }
         if self.show_sort_options {
             content = content.push(sort_and_filter);
         }
@@ -625,8 +645,64 @@ impl TickersTable {
         }
     }
 
+    /// Favorites in a stable order for the chips, by symbol
+    fn sorted_favorites(&self) -> Vec<Ticker> {
+        let mut favorites: Vec<Ticker> = self.favorited_tickers.iter().copied().collect();
+        favorites.sort_by_key(|ticker| ticker.display_symbol_and_type().0);
+        favorites
+    }
+
+    /// Quick-access chips that open a ticker in the focused pane, `None` when there are none
+    fn chips_row<'a>(&'a self, icon: Icon, tickers: &[Ticker]) -> Option<Element<'a, Message>> {
+        if tickers.is_empty() {
+            return None;
+        }
+
+        let chips = tickers.iter().map(|ticker| {
+            let (symbol, _) = ticker.display_symbol_and_type();
+
+            button(
+                row![
+                    icon_text(style::exchange_icon(ticker.exchange), 10),
+                    text(symbol).size(11),
+                ]
+                .spacing(4)
+                .align_y(Vertical::Center),
+            )
+            .padding([2, 6])
+            .on_press(Message::TickerSelected(*ticker, None))
+            .style(|theme, status| style::button::transparent(theme, status, false))
+            .into()
+        });
+
+        let chips = scrollable::Scrollable::with_direction(
+            row(chips).spacing(4).align_y(Vertical::Center),
+            scrollable::Direction::Horizontal(
+                scrollable::Scrollbar::new().width(2).scroller_width(2),
+            ),
+        )
+        .style(style::scroll_bar);
+
+        Some(
+            row![icon_text(icon, 11), chips]
+                .spacing(6)
+                .height(QUICK_ACCESS_ROW_HEIGHT - 8.0)
+                .align_y(Vertical::Center)
+                .into(),
+        )
+    }
+
     fn header_offset_main(&self) -> f32 {
+        let chip_rows = [
+            !self.recent_tickers.is_empty(),
+            !self.favorited_tickers.is_empty(),
+        ]
+        .into_iter()
+        .filter(|shown| *shown)
+        .count();
+
         TOP_BAR_HEIGHT
+            + chip_rows as f32 * QUICK_ACCESS_ROW_HEIGHT
             + if self.show_sort_options {
                 SORT_AND_FILTER_HEIGHT
             } else {
@@ -1013,6 +1089,13 @@ impl TickersTable {
         }
     }
 
+    /// Last price shown for the ticker, once its stats were fetched
+    pub fn price_display(&self, ticker: &Ticker) -> Option<&str> {
+        self.display_cache
+            .get(ticker)
+            .map(|data| data.mark_price_display.as_str())
+    }
+
     fn label_with_suffix(&self, ticker: Ticker) -> String {
         let mut s = self.label_for(ticker);
         s.push_str(market_suffix(ticker.market_type()));
@@ -1162,7 +1245,16 @@ fn expanded_ticker_card<'a>(
             })
             .on_press(Message::FavoriteTicker(*ticker))
             .style(move |theme, status| { style::button::transparent(theme, status, false) }),
+            space::horizontal(),
+            tooltip(
+                mouse_area(container(icon_text(Icon::DragHandle, 11)).padding(4))
+                    .on_press(Message::WatchlistDragStarted(*ticker))
+                    .interaction(iced::mouse::Interaction::Grab),
+                Some("Drag onto a watchlist group"),
+                TooltipPosition::Left,
+            ),
         ]
+        .align_y(Alignment::Center)
         .spacing(2),
         row![
             icon_text(exchange_icon, 12),
@@ -1207,10 +1299,15 @@ fn expanded_ticker_card<'a>(
         column![
             init_content_button(ContentKind::HeatmapChart, *ticker, 180.0),
             init_content_button(ContentKind::FootprintChart, *ticker, 180.0),
+            init_content_button(ContentKind::HeatmapFootprint, *ticker, 180.0),
             init_content_button(ContentKind::CandlestickChart, *ticker, 180.0),
+            init_content_button(ContentKind::RangeChart, *ticker, 180.0),
             init_content_button(ContentKind::ComparisonChart, *ticker, 180.0),
+            init_content_button(ContentKind::CorrelationMatrix, *ticker, 180.0),
             init_content_button(ContentKind::TimeAndSales, *ticker, 160.0),
             init_content_button(ContentKind::Ladder, *ticker, 160.0),
+            init_content_button(ContentKind::Statistics, *ticker, 160.0),
+            init_content_button(ContentKind::SpreadChart, *ticker, 160.0),
         ]
         .width(Length::Fill)
         .spacing(2)
@@ -1,3 +1,4 @@
+use exchange::HealthStatus;
 use exchange::adapter::Exchange;
 
 use iced::font::{Family, Stretch, Weight};
@@ -111,6 +112,16 @@ pub fn exchange_icon(exchange: Exchange) -> Icon {
     }
 }
 
+pub fn health_color(theme: &Theme, status: HealthStatus) -> Color {
+    let palette = theme.extended_palette();
+
+    match status {
+        HealthStatus::Healthy => palette.success.base.color,
+        HealthStatus::Degraded => palette.warning.base.color,
+        HealthStatus::Disconnected => palette.danger.base.color,
+    }
+}
+
 #[cfg(target_os = "macos")]
 pub fn title_text(theme: &Theme) -> iced::widget::text::Style {
     let palette = theme.extended_palette();
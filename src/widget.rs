@@ -15,7 +15,9 @@ pub mod chart;
 pub mod color_picker;
 pub mod column_drag;
 pub mod decorate;
+pub mod export;
 pub mod multi_split;
+pub mod perf_overlay;
 pub mod toast;
 
 #[allow(dead_code)]
@@ -64,12 +66,13 @@ pub fn confirm_dialog_container<'a, Message: 'a + Clone>(
     let dialog = confirm_dialog.message;
     let on_confirm = *confirm_dialog.on_confirm;
     let on_confirm_msg = confirm_dialog.on_confirm_btn_text;
+    let on_cancel_msg = confirm_dialog.on_cancel_btn_text;
 
     container(
         column![
             text(dialog).size(14),
             row![
-                button(text("Cancel"))
+                button(text(on_cancel_msg.unwrap_or("Cancel".to_string())))
                     .style(|theme, status| style::button::transparent(theme, status, false))
                     .on_press(on_cancel),
                 button(text(on_confirm_msg.unwrap_or("Confirm".to_string()))).on_press(on_confirm),
@@ -263,6 +266,47 @@ where
         .into()
 }
 
+/// Publishes `on_right_click` with the cursor position relative to `content` when a right click
+/// on it isn't captured by `content` itself, e.g. by a chart canvas with its own menu
+pub fn on_right_click<'a, Message: 'a>(
+    content: impl Into<Element<'a, Message>>,
+    on_right_click: impl Fn(iced::Point) -> Message + 'a,
+) -> Element<'a, Message> {
+    use iced::advanced;
+
+    decorate::decorate(content)
+        .update(
+            move |_state: &mut (),
+                  inner: &mut Element<'a, Message>,
+                  tree: &mut advanced::widget::Tree,
+                  event: &iced::Event,
+                  layout: advanced::Layout<'_>,
+                  cursor: advanced::mouse::Cursor,
+                  renderer: &iced::Renderer,
+                  clipboard: &mut dyn advanced::Clipboard,
+                  shell: &mut advanced::Shell<'_, Message>,
+                  viewport: &iced::Rectangle| {
+                inner.as_widget_mut().update(
+                    tree, event, layout, cursor, renderer, clipboard, shell, viewport,
+                );
+
+                if shell.is_event_captured() {
+                    return;
+                }
+
+                if let iced::Event::Mouse(iced::mouse::Event::ButtonPressed(
+                    iced::mouse::Button::Right,
+                )) = event
+                    && let Some(position) = cursor.position_in(layout.bounds())
+                {
+                    shell.publish(on_right_click(position));
+                    shell.capture_event();
+                }
+            },
+        )
+        .into()
+}
+
 #[macro_export]
 /// Creates a column with horizontal rules between each item.
 ///
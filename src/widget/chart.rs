@@ -1,6 +1,6 @@
 pub mod comparison;
 
-use chrono::{TimeZone, Utc};
+use data::{TimestampFormat, UserTimezone};
 use exchange::TickerInfo;
 
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
@@ -156,27 +156,31 @@ fn time_tick_candidates() -> &'static [u64] {
     ]
 }
 
+/// `ts_ms` is already shifted into the user's timezone
 fn format_time_label(ts_ms: u64, step_ms: u64) -> String {
-    let Some(dt) = Utc.timestamp_millis_opt(ts_ms as i64).single() else {
-        return String::new();
-    };
-
     const S: u64 = 1_000;
     const M: u64 = 60 * S;
     const H: u64 = 60 * M;
     const D: u64 = 24 * H;
 
-    if step_ms < M {
-        dt.format("%H:%M:%S").to_string()
+    let format = if step_ms < M {
+        TimestampFormat::HourMinuteSecond
     } else if step_ms < D {
-        dt.format("%H:%M").to_string()
+        TimestampFormat::HourMinute
     } else if step_ms < 7 * D {
-        dt.format("%b %d").to_string()
+        TimestampFormat::DayMonth
     } else if step_ms < 365 * D {
-        dt.format("%Y-%m").to_string()
+        TimestampFormat::YearMonth
     } else {
-        dt.format("%Y").to_string()
-    }
+        TimestampFormat::Year
+    };
+
+    data::format_timestamp_as(
+        ts_ms,
+        UserTimezone::Utc,
+        data::i18n::Language::from_code(data::i18n::current_language()),
+        format,
+    )
 }
 
 fn time_ticks(min_x: u64, max_x: u64, px_per_ms: f32, min_px: f32) -> (Vec<u64>, u64) {
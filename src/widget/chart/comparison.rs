@@ -673,23 +673,12 @@ where
     }
 
     fn format_crosshair_time(ts_ms: u64, tz: UserTimezone) -> String {
-        let ts_i64 = ts_ms as i64;
-        match tz {
-            UserTimezone::Utc => {
-                if let Some(dt) = chrono::Utc.timestamp_millis_opt(ts_i64).single() {
-                    dt.format("%a %b %-d %H:%M").to_string()
-                } else {
-                    ts_ms.to_string()
-                }
-            }
-            UserTimezone::Local => {
-                if let Some(dt) = chrono::Local.timestamp_millis_opt(ts_i64).single() {
-                    dt.format("%a %b %-d %H:%M").to_string()
-                } else {
-                    ts_ms.to_string()
-                }
-            }
-        }
+        data::format_timestamp_as(
+            ts_ms,
+            tz,
+            data::i18n::Language::from_code(data::i18n::current_language()),
+            data::TimestampFormat::Crosshair,
+        )
     }
 
     fn to_tz_ms(ts_ms: u64, tz: UserTimezone) -> u64 {
@@ -707,6 +696,14 @@ where
                     ts_ms
                 }
             }
+            UserTimezone::Fixed(_) => {
+                let off_ms = tz.offset_ms_at(ts_ms as i64);
+                if off_ms >= 0 {
+                    ts_ms.saturating_add(off_ms as u64)
+                } else {
+                    ts_ms.saturating_sub((-off_ms) as u64)
+                }
+            }
         }
     }
 }
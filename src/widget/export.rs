@@ -0,0 +1,209 @@
+//! Saves a pane's chart as a PNG image.
+//!
+//! The chart is laid out at the pane's logical size and drawn offscreen by a headless
+//! renderer, so the image includes the chart canvas, axis labels and study overlays but not
+//! whatever covers the pane on screen. Its resolution is picked with [`ExportScale`], the
+//! same layout drawn at 1, 2 or 4 physical pixels per logical one.
+use iced::advanced::renderer::{self, Headless};
+use iced::advanced::widget::{self, Operation, operation};
+use iced::{Element, Font, Pixels, Rectangle, Renderer, Size, Task, Theme, mouse};
+use iced_futures::futures::channel::oneshot;
+use iced_runtime::user_interface::{self, UserInterface};
+use std::{fmt, path::PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportScale {
+    X1,
+    #[default]
+    X2,
+    X4,
+}
+
+impl ExportScale {
+    pub const ALL: [ExportScale; 3] = [ExportScale::X1, ExportScale::X2, ExportScale::X4];
+
+    fn factor(self) -> f32 {
+        match self {
+            ExportScale::X1 => 1.0,
+            ExportScale::X2 => 2.0,
+            ExportScale::X4 => 4.0,
+        }
+    }
+}
+
+impl fmt::Display for ExportScale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x", self.factor())
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ExportError {
+    #[error("Pane is not visible")]
+    NotVisible,
+    #[error("Pane has no chart to save")]
+    NoChart,
+    #[error("No renderer available to draw the chart")]
+    NoRenderer,
+    #[error("Failed to encode PNG: {0}")]
+    Encode(#[from] png::EncodingError),
+    #[error("Failed to write file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Export was interrupted")]
+    Interrupted,
+}
+
+/// A pane's chart and what the offscreen render pass needs to draw it
+pub struct CanvasSnapshot<'a, Message> {
+    pub content: Element<'a, Message>,
+    /// Logical size the chart is laid out at, the pane's
+    pub size: Size,
+    pub theme: &'a Theme,
+}
+
+impl<Message> CanvasSnapshot<'_, Message> {
+    /// Lays the chart out and draws it offscreen at `scale`
+    fn render(self, scale: ExportScale) -> Result<Image, ExportError> {
+        // cached canvas geometry belongs to the window's backend, it has to be drawn by the same
+        let backend = if crate::chart::wgpu_active() {
+            "wgpu"
+        } else {
+            "tiny-skia"
+        };
+
+        let mut renderer = iced_futures::futures::executor::block_on(<Renderer as Headless>::new(
+            Font::default(),
+            Pixels(12.0),
+            Some(backend),
+        ))
+        .ok_or(ExportError::NoRenderer)?;
+
+        let palette = self.theme.extended_palette();
+        let style = renderer::Style {
+            text_color: palette.background.base.text,
+        };
+
+        let mut interface = UserInterface::build(
+            self.content,
+            self.size,
+            user_interface::Cache::default(),
+            &mut renderer,
+        );
+        interface.draw(
+            &mut renderer,
+            self.theme,
+            &style,
+            mouse::Cursor::Unavailable,
+        );
+
+        let size = Size::new(
+            (self.size.width * scale.factor()).round() as u32,
+            (self.size.height * scale.factor()).round() as u32,
+        );
+        if size.width == 0 || size.height == 0 {
+            return Err(ExportError::NotVisible);
+        }
+
+        let rgba = renderer.screenshot(size, scale.factor(), palette.background.weakest.color);
+
+        Ok(Image { rgba, size })
+    }
+}
+
+/// RGBA pixels of a rendered chart
+struct Image {
+    rgba: Vec<u8>,
+    /// Size in physical pixels
+    size: Size<u32>,
+}
+
+impl Image {
+    fn write_png(&self, path: &std::path::Path) -> Result<(), ExportError> {
+        let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        let mut encoder = png::Encoder::new(file, self.size.width, self.size.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.rgba)?;
+        writer.finish()?;
+
+        Ok(())
+    }
+}
+
+/// Id of the container wrapping a pane's body, used to find the size it is laid out at
+pub fn pane_container_id(pane_id: uuid::Uuid) -> widget::Id {
+    widget::Id::from(format!("pane-export-{pane_id}"))
+}
+
+/// Logical size of the pane's body, `None` when it isn't laid out in any window
+pub fn pane_size(pane_id: uuid::Uuid) -> Task<Option<Size>> {
+    widget::operate(find_bounds(pane_container_id(pane_id)))
+        .map(|bounds| bounds.map(|bounds| bounds.size()))
+}
+
+/// Asks for a destination through a save dialog, `None` if the user cancelled
+pub async fn pick_png_path(file_name: String) -> Option<PathBuf> {
+    rfd::AsyncFileDialog::new()
+        .add_filter("PNG image", &["png"])
+        .set_file_name(file_name)
+        .save_file()
+        .await
+        .map(|handle| handle.path().to_path_buf())
+}
+
+/// Renders the chart offscreen at `scale` and writes it as a PNG.
+/// Encoding runs on a background thread.
+pub fn capture_pane_as_png<Message>(
+    snapshot: CanvasSnapshot<'_, Message>,
+    scale: ExportScale,
+    output_path: PathBuf,
+) -> Task<Result<PathBuf, ExportError>> {
+    let image = match snapshot.render(scale) {
+        Ok(image) => image,
+        Err(err) => return Task::done(Err(err)),
+    };
+
+    Task::future(async move {
+        let (sender, receiver) = oneshot::channel();
+
+        std::thread::spawn(move || {
+            let result = image.write_png(&output_path).map(|()| output_path);
+            let _ = sender.send(result);
+        });
+
+        receiver.await.unwrap_or(Err(ExportError::Interrupted))
+    })
+}
+
+/// Finds the bounds of the container with the given id
+fn find_bounds(target: widget::Id) -> impl Operation<Option<Rectangle>> {
+    struct FindBounds {
+        target: widget::Id,
+        bounds: Option<Rectangle>,
+    }
+
+    impl Operation<Option<Rectangle>> for FindBounds {
+        fn traverse(&mut self, operate: &mut dyn FnMut(&mut dyn Operation<Option<Rectangle>>)) {
+            if self.bounds.is_none() {
+                operate(self);
+            }
+        }
+
+        fn container(&mut self, id: Option<&widget::Id>, bounds: Rectangle) {
+            if id == Some(&self.target) {
+                self.bounds = Some(bounds);
+            }
+        }
+
+        fn finish(&self) -> operation::Outcome<Option<Rectangle>> {
+            operation::Outcome::Some(self.bounds)
+        }
+    }
+
+    FindBounds {
+        target,
+        bounds: None,
+    }
+}
@@ -0,0 +1,79 @@
+//! Corner readout of how well the app keeps up, so slowdowns can be reported with numbers.
+//!
+//! Timings come from the 100ms tick: when the event loop stalls, ticks arrive late and the
+//! rate drops below 10 per second.
+
+use crate::style;
+use iced::widget::{column, container, text};
+use iced::{Element, Font};
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// How far back tick arrivals are counted for the rate
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Default)]
+pub struct PerfOverlay {
+    /// Arrivals of the ticks within the last [`RATE_WINDOW`]
+    ticks: VecDeque<Instant>,
+    last_interval: Duration,
+    last_update: Duration,
+    streams: usize,
+    datapoints: usize,
+}
+
+impl PerfOverlay {
+    /// Records a tick that arrived at `now` and took `update` to handle
+    pub fn record(&mut self, now: Instant, update: Duration, streams: usize, datapoints: usize) {
+        if let Some(prev) = self.ticks.back() {
+            self.last_interval = now.saturating_duration_since(*prev);
+        }
+
+        self.ticks.push_back(now);
+        while self
+            .ticks
+            .front()
+            .is_some_and(|tick| now.saturating_duration_since(*tick) > RATE_WINDOW)
+        {
+            self.ticks.pop_front();
+        }
+
+        self.last_update = update;
+        self.streams = streams;
+        self.datapoints = datapoints;
+    }
+
+    /// Ticks handled per second, 10 while nothing blocks the event loop
+    fn fps(&self) -> f32 {
+        match (self.ticks.front(), self.ticks.back()) {
+            (Some(first), Some(last)) if last > first => {
+                (self.ticks.len() - 1) as f32 / last.duration_since(*first).as_secs_f32()
+            }
+            _ => 0.0,
+        }
+    }
+
+    pub fn view<'a, Message: 'a>(&self) -> Element<'a, Message> {
+        let update_ms = self.last_update.as_secs_f64() * 1000.0;
+        let line = |label: &str, value: String| {
+            text(format!("{label:<11}{value}"))
+                .size(11)
+                .font(Font::MONOSPACE)
+        };
+
+        container(
+            column![
+                line("FPS", format!("{:.1}", self.fps())),
+                line("Tick", format!("{} ms", self.last_interval.as_millis())),
+                line("Update", format!("{update_ms:.2} ms")),
+                line("Streams", self.streams.to_string()),
+                line("Datapoints", self.datapoints.to_string()),
+            ]
+            .spacing(2),
+        )
+        .padding(6)
+        .style(style::tooltip)
+        .into()
+    }
+}
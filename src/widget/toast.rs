@@ -3,7 +3,7 @@ use iced::advanced::overlay;
 use iced::advanced::renderer;
 use iced::advanced::widget::{self, Operation, Tree};
 use iced::advanced::{Clipboard, Shell, Widget};
-use iced::time::{self, Duration, Instant};
+use iced::time::{Duration, Instant};
 use iced::widget::{button, column, container, row, space, text};
 use iced::{
     Alignment, Center, Element, Event, Fill, Length, Point, Rectangle, Renderer, Size, Theme,
@@ -12,8 +12,7 @@ use iced::{
 use iced::{Border, mouse, padding, theme, window};
 
 use crate::style;
-
-pub const DEFAULT_TIMEOUT: u64 = 8;
+use data::config::{ToastConfig, ToastPosition};
 
 #[allow(dead_code)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -38,6 +37,8 @@ pub struct Toast {
     title: String,
     body: String,
     status: Status,
+    /// Label of a button under the body, handled by the manager's `on_action`
+    action: Option<String>,
 }
 
 impl Toast {
@@ -47,16 +48,19 @@ impl Toast {
                 title: "Error".to_string(),
                 body,
                 status: Status::Danger,
+                action: None,
             },
             Notification::Info(body) => Self {
                 title: "Info".to_string(),
                 body,
                 status: Status::Primary,
+                action: None,
             },
             Notification::Warn(body) => Self {
                 title: "Warning".to_string(),
                 body,
                 status: Status::Warning,
+                action: None,
             },
         }
     }
@@ -66,6 +70,16 @@ impl Toast {
             title: "Error".to_string(),
             body: body.into(),
             status: Status::Danger,
+            action: None,
+        }
+    }
+
+    pub fn info(body: impl Into<String>) -> Self {
+        Self {
+            title: "Info".to_string(),
+            body: body.into(),
+            status: Status::Primary,
+            action: None,
         }
     }
 
@@ -74,16 +88,25 @@ impl Toast {
             title: "Warning".to_string(),
             body: body.into(),
             status: Status::Warning,
+            action: None,
         }
     }
+
+    pub fn with_action(mut self, label: impl Into<String>) -> Self {
+        self.action = Some(label.into());
+        self
+    }
+
+    pub fn has_action(&self) -> bool {
+        self.action.is_some()
+    }
 }
 
 pub struct Manager<'a, Message> {
     content: Element<'a, Message>,
     toasts: Vec<Element<'a, Message>>,
-    timeout_secs: u64,
+    config: ToastConfig,
     on_close: Box<dyn Fn(usize) -> Message + 'a>,
-    alignment: Alignment,
 }
 
 impl<'a, Message> Manager<'a, Message>
@@ -93,38 +116,76 @@ where
     pub fn new(
         content: impl Into<Element<'a, Message>>,
         toasts: &'a [Toast],
-        alignment: Alignment,
+        config: ToastConfig,
         on_close: impl Fn(usize) -> Message + 'a,
+    ) -> Self {
+        Self::build(content, toasts, config, on_close, None)
+    }
+
+    /// Like `new`, with the action button of a toast emitting `on_action` with its index
+    pub fn with_actions(
+        content: impl Into<Element<'a, Message>>,
+        toasts: &'a [Toast],
+        config: ToastConfig,
+        on_close: impl Fn(usize) -> Message + 'a,
+        on_action: impl Fn(usize) -> Message + 'a,
+    ) -> Self {
+        Self::build(content, toasts, config, on_close, Some(&on_action))
+    }
+
+    fn build(
+        content: impl Into<Element<'a, Message>>,
+        toasts: &'a [Toast],
+        config: ToastConfig,
+        on_close: impl Fn(usize) -> Message + 'a,
+        on_action: Option<&dyn Fn(usize) -> Message>,
     ) -> Self {
         let toasts = toasts
             .iter()
             .enumerate()
             .map(|(index, toast)| {
-                container(column![
-                    container(
-                        row![
-                            text(toast.title.as_str()),
-                            space::horizontal(),
-                            button("X")
-                                .on_press((on_close)(index))
-                                .style(move |theme, status| {
-                                    style::button::transparent(theme, status, true)
-                                })
-                                .padding(padding::right(6).left(6).top(2).bottom(2))
-                        ]
-                        .align_y(Center)
-                    )
-                    .style(match toast.status {
-                        Status::Primary => primary,
-                        Status::Secondary => secondary,
-                        Status::Success => success,
-                        Status::Danger => danger,
-                        Status::Warning => warning,
-                    })
-                    .width(Fill)
-                    .padding(4),
-                    container(text(toast.body.as_str())).width(Fill).padding(4)
-                ])
+                let action = toast
+                    .action
+                    .as_deref()
+                    .zip(on_action)
+                    .map(|(label, on_action)| {
+                        container(
+                            button(text(label).size(12))
+                                .on_press(on_action(index))
+                                .padding(padding::right(8).left(8).top(2).bottom(2)),
+                        )
+                        .align_right(Fill)
+                        .padding(padding::right(4).left(4).bottom(4))
+                    });
+
+                container(
+                    column![
+                        container(
+                            row![
+                                text(toast.title.as_str()),
+                                space::horizontal(),
+                                button("X")
+                                    .on_press((on_close)(index))
+                                    .style(move |theme, status| {
+                                        style::button::transparent(theme, status, true)
+                                    })
+                                    .padding(padding::right(6).left(6).top(2).bottom(2))
+                            ]
+                            .align_y(Center)
+                        )
+                        .style(match toast.status {
+                            Status::Primary => primary,
+                            Status::Secondary => secondary,
+                            Status::Success => success,
+                            Status::Danger => danger,
+                            Status::Warning => warning,
+                        })
+                        .width(Fill)
+                        .padding(4),
+                        container(text(toast.body.as_str())).width(Fill).padding(4)
+                    ]
+                    .push(action),
+                )
                 .style(style::chart_modal)
                 .padding(4)
                 .max_width(200)
@@ -134,19 +195,11 @@ where
 
         Self {
             content: content.into(),
-            alignment,
             toasts,
-            timeout_secs: DEFAULT_TIMEOUT,
+            config,
             on_close: Box::new(on_close),
         }
     }
-
-    pub fn timeout(self, seconds: u64) -> Self {
-        Self {
-            timeout_secs: seconds,
-            ..self
-        }
-    }
 }
 
 impl<Message> Widget<Message, Theme, Renderer> for Manager<'_, Message> {
@@ -305,12 +358,11 @@ impl<Message> Widget<Message, Theme, Renderer> for Manager<'_, Message> {
                 position: layout.bounds().position() + translation,
                 viewport: *viewport,
                 bounds: layout.bounds(),
-                alignment: self.alignment,
+                config: self.config,
                 toasts: &mut self.toasts,
                 state: toasts_state,
                 instants,
                 on_close: &self.on_close,
-                timeout_secs: self.timeout_secs,
             }))
         });
         let overlays = content.into_iter().chain(toasts).collect::<Vec<_>>();
@@ -323,31 +375,56 @@ struct Overlay<'a, 'b, Message> {
     position: Point,
     viewport: Rectangle,
     bounds: Rectangle,
-    alignment: Alignment,
+    config: ToastConfig,
     toasts: &'b mut [Element<'a, Message>],
     state: &'b mut [Tree],
     instants: &'b mut [Option<Instant>],
     on_close: &'b dyn Fn(usize) -> Message,
-    timeout_secs: u64,
 }
 
 impl<Message> overlay::Overlay<Message, Theme, Renderer> for Overlay<'_, '_, Message> {
     fn layout(&mut self, renderer: &Renderer, _bounds: Size) -> layout::Node {
+        const PADDING: f32 = 32.0;
+
         let limits = layout::Limits::new(Size::ZERO, self.bounds.size());
 
-        layout::flex::resolve(
+        let alignment = match self.config.position {
+            ToastPosition::TopLeft | ToastPosition::BottomLeft => Alignment::Start,
+            ToastPosition::TopRight | ToastPosition::BottomRight => Alignment::End,
+            ToastPosition::TopCenter => Alignment::Center,
+        };
+
+        let node = layout::flex::resolve(
             layout::flex::Axis::Vertical,
             renderer,
             &limits,
             Fill,
             Fill,
-            32.into(),
+            PADDING.into(),
             10.0,
-            self.alignment,
+            alignment,
             self.toasts,
             self.state,
-        )
-        .translate(Vector::new(self.position.x, self.position.y))
+        );
+
+        // flex always stacks from the top, push the whole stack down for bottom positions
+        let node = match node.children().last() {
+            Some(last) if self.config.position.is_bottom() => {
+                let stack_bottom = last.bounds().y + last.bounds().height + PADDING;
+                let offset = Vector::new(0.0, (node.size().height - stack_bottom).max(0.0));
+
+                layout::Node::with_children(
+                    node.size(),
+                    node.children()
+                        .iter()
+                        .map(|child| child.clone().translate(offset))
+                        .collect(),
+                )
+            }
+            _ => node,
+        };
+
+        node.translate(Vector::new(self.position.x, self.position.y))
     }
 
     fn update(
@@ -360,22 +437,45 @@ impl<Message> overlay::Overlay<Message, Theme, Renderer> for Overlay<'_, '_, Mes
         shell: &mut Shell<'_, Message>,
     ) {
         if let Event::Window(window::Event::RedrawRequested(now)) = &event {
-            self.instants
-                .iter_mut()
-                .enumerate()
-                .for_each(|(index, maybe_instant)| {
-                    if let Some(instant) = maybe_instant.as_mut() {
-                        let remaining =
-                            time::seconds(self.timeout_secs).saturating_sub(instant.elapsed());
-
-                        if remaining == Duration::ZERO {
-                            maybe_instant.take();
-                            shell.publish((self.on_close)(index));
-                        } else {
-                            shell.request_redraw_at(*now + remaining);
+            let overflow = self
+                .instants
+                .iter()
+                .flatten()
+                .count()
+                .saturating_sub(self.config.max_count.max(1));
+
+            if overflow > 0 {
+                // dismiss the oldest ones first, each close shifts the rest down to index 0.
+                // timeouts are checked on the next redraw, once indices are settled again
+                self.instants
+                    .iter_mut()
+                    .filter(|instant| instant.is_some())
+                    .take(overflow)
+                    .for_each(|maybe_instant| {
+                        maybe_instant.take();
+                        shell.publish((self.on_close)(0));
+                    });
+
+                shell.request_redraw();
+            } else {
+                let duration = Duration::from_millis(self.config.duration_ms);
+
+                self.instants
+                    .iter_mut()
+                    .enumerate()
+                    .for_each(|(index, maybe_instant)| {
+                        if let Some(instant) = maybe_instant.as_mut() {
+                            let remaining = duration.saturating_sub(instant.elapsed());
+
+                            if remaining == Duration::ZERO {
+                                maybe_instant.take();
+                                shell.publish((self.on_close)(index));
+                            } else {
+                                shell.request_redraw_at(*now + remaining);
+                            }
                         }
-                    }
-                });
+                    });
+            }
         }
 
         let viewport = layout.bounds();
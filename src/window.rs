@@ -25,6 +25,10 @@ pub fn default_size() -> Size {
 #[derive(Debug, Clone, Copy)]
 pub enum Event {
     CloseRequested(window::Id),
+    /// Outer position of the window, `None` where the platform doesn't report it
+    Opened(window::Id, Option<Point>, Size),
+    Moved(window::Id, Point),
+    Resized(window::Id, Size),
 }
 
 pub fn events() -> Subscription<Event> {
@@ -40,6 +44,15 @@ fn filtered_events(
         iced::Event::Window(iced::window::Event::CloseRequested) => {
             Some(Event::CloseRequested(window))
         }
+        iced::Event::Window(iced::window::Event::Opened { position, size }) => {
+            Some(Event::Opened(window, *position, *size))
+        }
+        iced::Event::Window(iced::window::Event::Moved(position)) => {
+            Some(Event::Moved(window, *position))
+        }
+        iced::Event::Window(iced::window::Event::Resized(size)) => {
+            Some(Event::Resized(window, *size))
+        }
         _ => None,
     }
 }